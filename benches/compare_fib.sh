@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+# Compares benches/fib.rs between an older commit and the working tree.
+#
+#     benches/compare_fib.sh [REV]
+#
+# REV defaults to 67ecd16~1, the commit before function calls stopped
+# copying the caller's scope. The old tree is checked out in a temporary
+# git worktree and gets this bench, saved by criterion as the "before"
+# baseline; the working tree is then measured against it. RUST_BACKTRACE
+# is unset so neither side pays for backtraces.
+set -euo pipefail
+
+rev="${1:-67ecd16~1}"
+root="$(git rev-parse --show-toplevel)"
+old="$(mktemp -d)"
+export CARGO_TARGET_DIR="${CARGO_TARGET_DIR:-$root/target}"
+unset RUST_BACKTRACE
+
+cleanup() { git -C "$root" worktree remove --force "$old"; }
+git -C "$root" worktree add --detach "$old" "$rev" >/dev/null
+trap cleanup EXIT
+
+mkdir -p "$old/benches"
+cp "$root/benches/fib.rs" "$old/benches/fib.rs"
+if ! grep -q 'name = "fib"' "$old/Cargo.toml"; then
+    printf '\n[[bench]]\nname = "fib"\nharness = false\n' >>"$old/Cargo.toml"
+fi
+
+(cd "$old" && cargo bench --bench fib -- --save-baseline before)
+(cd "$root" && cargo bench --bench fib -- --baseline before)
@@ -0,0 +1,25 @@
+//! Recursive Fibonacci, which spends nearly all its time calling and
+//! returning from user functions. Run with `cargo bench --bench fib`, or
+//! with `benches/compare_fib.sh` to measure it against an older commit.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+const FIB: &str =
+    "دالة فيب(ن) {\n    اذا ن < ٢ {\n        ارجع ن\n    }\n    ارجع فيب(ن - ١) + فيب(ن - ٢)\n}\n";
+
+fn fib(c: &mut Criterion) {
+    let mut runtime = Runtime::new();
+    runtime.execute(FIB).unwrap();
+    c.bench_function("fib(22)", |b| {
+        b.iter(|| assert_eq!(runtime.execute("فيب(٢٢)").unwrap(), Value::Int(17711)))
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = fib
+}
+criterion_main!(benches);
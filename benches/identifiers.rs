@@ -0,0 +1,39 @@
+//! A generated 5,000-line function that does nothing but read and assign
+//! fifty variables, so nearly all its time goes to looking up names. Run
+//! with `cargo bench --bench identifiers`.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+/// `عمل(ن)`: fifty variables, then 4,950 lines each combining two of them.
+fn script() -> String {
+    let mut lines = vec!["دالة عمل(ن) {".to_string()];
+    lines.extend((0..50).map(|i| format!("    متغير_{} = {}", i, i)));
+    lines.extend((50..5000).map(|i| {
+        format!(
+            "    متغير_{} = (متغير_{} + متغير_{} * 2) % 1000",
+            i % 50,
+            (i * 7 + 3) % 50,
+            (i * 13 + 5) % 50
+        )
+    }));
+    lines.push("    ارجع متغير_0 + ن".to_string());
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+fn identifiers(c: &mut Criterion) {
+    let mut runtime = Runtime::new();
+    runtime.execute(&script()).unwrap();
+    c.bench_function("5k-line variable shuffle", |b| {
+        b.iter(|| assert!(matches!(runtime.execute("عمل(1)").unwrap(), Value::Int(_))))
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = identifiers
+}
+criterion_main!(benches);
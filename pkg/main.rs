@@ -3,214 +3,380 @@
 
 use std::fs;
 use std::env;
-use std::process;
-use std::path::Path;
-use std::collections::HashMap;
-use reqwest::blocking::Client;
+use std::process::{self, ExitCode};
+use std::path::{Path, PathBuf};
+use iqra::cli::{RunOptions, run_file};
+use iqra::i18n::{ErrorLanguage, List};
+use iqra::paths::{self, INSTALL_MANIFEST, LOCAL_PACKAGES};
+use iqra::pkg::{
+    DEFAULT_JOBS, FsRegistry, PackageArchive, PackageManifest, PackageMetadata, REGISTRY_TOKEN_VAR, RegistryClient, SearchOptions,
+    YankOptions, check_publish_version, confirmed, download_packages, read_install_manifest, registry_for, registry_url, render_download_summary,
+    render_search_results, resolve_entry, write_install_manifest,
+};
 // ...existing code...
-use std::io::Write;
+use iqra::tr;
+use std::io::{IsTerminal, Write};
 
 fn print_usage() {
-    println!("\nأوامر مدير الحزم العبقري (iqra-pkg):");
-    println!("  iqra-pkg install <package>     # تثبيت مكتبة");
-    println!("  iqra-pkg update                # تحديث جميع المكتبات");
-    println!("  iqra-pkg publish <package>     # نشر مكتبتك");
-    println!("  iqra-pkg remove <package>      # إزالة مكتبة");
-    println!("  iqra-pkg search <query>        # البحث عن مكتبة");
-    println!("  iqra-pkg help                  # عرض المساعدة");
+    let jobs = tr!("pkg.usage.jobs", jobs = DEFAULT_JOBS);
+    let token = tr!("pkg.usage.needs_token", var = REGISTRY_TOKEN_VAR);
+    let commands = [
+        ("iqra-pkg install <package>...", tr!("pkg.usage.install")),
+        ("iqra-pkg update", tr!("pkg.usage.update")),
+        ("    [--jobs N]", jobs),
+        ("iqra-pkg list", tr!("pkg.usage.list")),
+        ("iqra-pkg publish <package>", tr!("pkg.usage.publish")),
+        ("    [--dry-run] [--force]", String::new()),
+        ("iqra-pkg remove <package>", tr!("pkg.usage.remove")),
+        ("iqra-pkg yank <package>", tr!("pkg.usage.yank")),
+        ("    --version X.Y.Z [--yes]", token),
+        ("iqra-pkg run <package> [args]", tr!("pkg.usage.run")),
+        ("    [--script <name>]", String::new()),
+        ("iqra-pkg search <query>", tr!("pkg.usage.search")),
+        ("    [--limit N] [--page M] [--exact]", String::new()),
+        ("iqra-pkg help", tr!("pkg.usage.help")),
+    ];
+    println!("\n{}", tr!("pkg.usage.title"));
+    for (command, summary) in commands {
+        if summary.is_empty() {
+            println!("  {}", command);
+        } else {
+            println!("  {:<31}# {}", command, summary);
+        }
+    }
+    println!("\n  --global, -g          {}", tr!("pkg.usage.global"));
+    println!("  --registry-dir <dir>  {}", tr!("pkg.usage.registry_dir"));
+    println!("  --lang <ar|en|both>   {}", tr!("pkg.usage.lang"));
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        print_usage();
-        process::exit(1);
+/// Splits install/update arguments into package names and the `--jobs N`
+/// download limit, exiting on a bad count.
+fn packages_and_jobs(args: &[String]) -> (Vec<String>, usize) {
+    let mut packages = Vec::new();
+    let mut jobs = DEFAULT_JOBS;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--jobs" || arg == "-j" {
+            match args.next().and_then(|count| count.parse().ok()).filter(|count| *count > 0) {
+                Some(count) => jobs = count,
+                None => {
+                    println!("{}", tr!("pkg.jobs_not_positive"));
+                    process::exit(1);
+                }
+            }
+        } else {
+            packages.push(arg.clone());
+        }
     }
-    let pkg_dir = "iqra_packages";
-    let manifest_path = "iqra-pkg.json";
-    // Read registry URL from environment variable for testing/mocking
-    let registry_url = env::var("MOCKITO_SERVER_URL")
-        .or_else(|_| env::var("IQRA_REGISTRY_URL"))
-        .unwrap_or_else(|_| "https://iqra-registry.example.com".to_string());
-    let client = Client::new();
+    (packages, jobs)
+}
 
-    // Helper: load manifest
-    fn load_manifest(path: &str) -> HashMap<String, String> {
-        if Path::new(path).exists() {
-            let data = fs::read_to_string(path).unwrap_or_default();
-            serde_json::from_str(&data).unwrap_or_default()
-        } else {
-            HashMap::new()
+fn main() -> ExitCode {
+    let mut args: Vec<String> = env::args().collect();
+    // --lang, or IQRA_LANG, picks the language of messages as it does for iqra.
+    let lang = match args.iter().position(|arg| arg == "--lang") {
+        Some(index) if index + 1 < args.len() => args.drain(index..index + 2).nth(1),
+        Some(_) => {
+            println!("{}", tr!("pkg.flag_needs_value", flag = "--lang"));
+            process::exit(1);
+        }
+        None => env::var("IQRA_LANG").ok().filter(|lang| !lang.is_empty()),
+    };
+    if let Some(lang) = lang {
+        match lang.parse::<ErrorLanguage>() {
+            Ok(language) => ErrorLanguage::set_current(language),
+            Err(message) => {
+                println!("{}", message);
+                process::exit(1);
+            }
         }
     }
-    // Helper: save manifest
-    fn save_manifest(path: &str, manifest: &HashMap<String, String>) {
-        let data = serde_json::to_string_pretty(manifest).unwrap();
-        fs::write(path, data).unwrap();
+    if args.len() < 2 {
+        print_usage();
+        process::exit(1);
     }
+    // --global/-g switches these commands from ./iqra_packages to IQRA_HOME/packages.
+    let global = matches!(args[1].as_str(), "install" | "ثبت" | "update" | "حدث" | "remove" | "احذف" | "list" | "اعرض")
+        && args.iter().any(|arg| arg == "--global" || arg == "-g");
+    let (pkg_dir, manifest_path) = if global {
+        args.retain(|arg| arg != "--global" && arg != "-g");
+        match paths::iqra_home() {
+            Some(home) => (home.join("packages"), home.join(INSTALL_MANIFEST)),
+            None => {
+                println!("{}", tr!("pkg.no_home"));
+                process::exit(1);
+            }
+        }
+    } else {
+        (PathBuf::from(LOCAL_PACKAGES), PathBuf::from(INSTALL_MANIFEST))
+    };
+    // --registry-dir, or a file:// URL, reads packages from a directory instead of the network.
+    let registry_dir = match args.iter().position(|arg| arg == "--registry-dir") {
+        Some(index) if index + 1 < args.len() => Some(args.drain(index..index + 2).nth(1).unwrap()),
+        Some(_) => {
+            println!("{}", tr!("pkg.flag_needs_path", flag = "--registry-dir"));
+            process::exit(1);
+        }
+        None => None,
+    };
+    let registry_url = registry_url();
+    let registry: Box<dyn RegistryClient> = match registry_dir {
+        Some(dir) => Box::new(FsRegistry::new(dir)),
+        None => registry_for(&registry_url),
+    };
 
     match args[1].as_str() {
         "install" | "ثبت" => {
-            if args.len() < 3 {
-                println!("يرجى تحديد اسم المكتبة | Please specify a package name");
+            let (packages, jobs) = packages_and_jobs(&args[2..]);
+            if packages.is_empty() {
+                println!("{}", tr!("pkg.specify_package"));
                 process::exit(1);
             }
-            let pkg = &args[2];
-            println!("تثبيت المكتبة '{}' من السجل ... | Installing package '{}' from registry ...", pkg, pkg);
-            fs::create_dir_all(pkg_dir).ok();
-            let pkg_path = format!("{}/{}", pkg_dir, pkg);
-            if Path::new(&pkg_path).exists() {
-                println!("المكتبة مثبتة بالفعل | Package already installed");
+            fs::create_dir_all(&pkg_dir).ok();
+            let (installed, packages): (Vec<String>, Vec<String>) =
+                packages.into_iter().partition(|pkg| pkg_dir.join(pkg).exists());
+            for pkg in &installed {
+                println!("{}", tr!("pkg.already_installed", package = pkg));
+            }
+            if packages.is_empty() {
                 process::exit(0);
             }
-            // Download from registry
-            let url = format!("{}/packages/{}/latest/download", registry_url, pkg);
-            match client.get(&url).send() {
-                Ok(resp) => {
-                    if resp.status().is_success() {
-                        let mut file = fs::File::create(&pkg_path).unwrap();
-                        let bytes = resp.bytes().unwrap();
-                        file.write_all(&bytes).unwrap();
-                        let mut manifest = load_manifest(manifest_path);
-                        manifest.insert(pkg.clone(), "latest".to_string());
-                        save_manifest(manifest_path, &manifest);
-                        println!("تم التثبيت بنجاح | Installed successfully");
-                    } else {
-                        println!("فشل التحميل من السجل | Failed to download from registry");
-                    }
-                }
-                Err(_) => println!("خطأ في الاتصال بالسجل | Registry connection error"),
+            println!("{}", tr!("pkg.installing", packages = List(&packages)));
+            let outcomes = download_packages(registry.as_ref(), &packages, &pkg_dir, jobs);
+            let mut manifest = read_install_manifest(&manifest_path);
+            for outcome in &outcomes {
+                if let Ok(version) = &outcome.result {
+                    manifest.insert(outcome.package.clone(), version.clone());
+                }
+            }
+            write_install_manifest(&manifest_path, &manifest).unwrap();
+            print!("{}", render_download_summary(&outcomes));
+            if outcomes.iter().any(|outcome| outcome.result.is_err()) {
+                println!("{}", tr!("pkg.install_failed"));
+                return ExitCode::FAILURE;
             }
+            println!("{}", tr!("pkg.installed"));
         }
         "update" | "حدث" => {
-                println!("تحديث جميع المكتبات من السجل ... | Updating all packages from registry ...");
-                let manifest = load_manifest(manifest_path);
+                println!("{}", tr!("pkg.updating"));
+                let mut manifest = read_install_manifest(&manifest_path);
                 if manifest.is_empty() {
-                    println!("لا توجد مكتبات مثبتة | No packages installed");
+                    println!("{}", tr!("pkg.none_installed"));
                     process::exit(0);
                 }
-                for (pkg, _ver) in manifest.iter() {
-                    println!("تحديث '{}' ... | Updating '{}' ...", pkg, pkg);
-                    let url = format!("{}/packages/{}/latest/download", registry_url, pkg);
-                    match client.get(&url).send() {
-                        Ok(resp) => {
-                            if resp.status().is_success() {
-                                let pkg_path = format!("{}/{}", pkg_dir, pkg);
-                                let mut file = fs::File::create(&pkg_path).unwrap();
-                                let bytes = resp.bytes().unwrap();
-                                file.write_all(&bytes).unwrap();
-                            } else {
-                                println!("فشل تحديث '{}' | Failed to update '{}'", pkg, pkg);
-                            }
-                        }
-                        Err(_) => println!("خطأ في الاتصال بالسجل | Registry connection error"),
-                    }
-                }
-                println!("تم التحديث بنجاح | All packages updated successfully");
+                let (_, jobs) = packages_and_jobs(&args[2..]);
+                let mut packages: Vec<String> = manifest.keys().cloned().collect();
+                packages.sort();
+                fs::create_dir_all(&pkg_dir).ok();
+                let outcomes = download_packages(registry.as_ref(), &packages, &pkg_dir, jobs);
+                for outcome in &outcomes {
+                    if let Ok(version) = &outcome.result {
+                        manifest.insert(outcome.package.clone(), version.clone());
+                    }
+                }
+                write_install_manifest(&manifest_path, &manifest).unwrap();
+                print!("{}", render_download_summary(&outcomes));
+                if outcomes.iter().any(|outcome| outcome.result.is_err()) {
+                    println!("{}", tr!("pkg.update_failed"));
+                    return ExitCode::FAILURE;
+                }
+                println!("{}", tr!("pkg.updated"));
         }
         "publish" | "انشر" => {
-                if args.len() < 3 {
-                    println!("يرجى تحديد اسم المكتبة للنشر | Please specify a package to publish");
+                let flags = ["--dry-run", "--force"];
+                let Some(pkg) = args[2..].iter().find(|arg| !flags.contains(&arg.as_str())) else {
+                    println!("{}", tr!("pkg.specify_publish"));
                     process::exit(1);
-                }
-                let pkg = &args[2];
-                let pkg_path = format!("{}/{}", pkg_dir, pkg);
-                if !Path::new(&pkg_path).exists() {
-                    println!("المكتبة غير موجودة محلياً | Package not found locally");
+                };
+                let dry_run = args.iter().any(|arg| arg == "--dry-run");
+                let force = args.iter().any(|arg| arg == "--force");
+                let pkg_path = pkg_dir.join(pkg);
+                if !pkg_path.exists() {
+                    println!("{}", tr!("pkg.not_found_locally"));
                     process::exit(1);
                 }
-                println!("نشر المكتبة '{}' إلى السجل ... | Publishing package '{}' to registry ...", pkg, pkg);
+                let prepared = PackageManifest::load(&pkg_path)
+                    .and_then(|manifest| manifest.metadata(pkg))
+                    .and_then(|metadata| {
+                        let archive = PackageArchive::build(&pkg_path)?;
+                        Ok((PackageMetadata { checksum: Some(archive.checksum()), ..metadata }, archive))
+                    });
+                let (metadata, archive) = match prepared {
+                    Ok(prepared) => prepared,
+                    Err(err) => {
+                        println!("{}", err);
+                        process::exit(1);
+                    }
+                };
+                let metadata_json = serde_json::to_string_pretty(&metadata).unwrap();
+                if dry_run {
+                    // Nothing is sent, so the registry isn't asked about versions either.
+                    println!("{}", tr!("pkg.dry_run", package = pkg, version = metadata.version));
+                    println!("{}", tr!("pkg.files"));
+                    for file in &archive.files {
+                        println!("  {}", file);
+                    }
+                    println!("{}\n{}", tr!("pkg.metadata"), metadata_json);
+                    println!("{}", tr!("pkg.nothing_uploaded"));
+                    process::exit(0);
+                }
                 // TODO: Add authentication (token)
-                let url = format!("{}/packages", registry_url);
-                let file_bytes = fs::read(&pkg_path).unwrap();
-                let metadata = serde_json::json!({
-                    "name": pkg,
-                    "version": "1.0.0", // TODO: Read from manifest
-                    "author": "TODO", // TODO: Get author
-                    "description": "TODO", // TODO: Get description
-                    "lang": "ar"
-                });
-                let form = reqwest::blocking::multipart::Form::new()
-                    .text("metadata", metadata.to_string())
-                    .part("archive", reqwest::blocking::multipart::Part::bytes(file_bytes).file_name(pkg.clone()));
-                match client.post(&url).multipart(form).send() {
-                    Ok(resp) => {
-                        if resp.status().is_success() {
-                            println!("تم النشر بنجاح | Published successfully");
-                        } else {
-                            println!("فشل النشر | Publish failed: {}", resp.status());
-                        }
-                    }
-                    Err(_) => println!("خطأ في الاتصال بالسجل | Registry connection error"),
+                let existing = match registry.package(pkg) {
+                    Ok(existing) => existing,
+                    Err(err) => {
+                        println!("{}", err);
+                        process::exit(1);
+                    }
+                };
+                match check_publish_version(&metadata.version, &existing, force) {
+                    Ok(Some(warning)) => println!("{}", warning),
+                    Ok(None) => {}
+                    Err(err) => {
+                        println!("{}", err);
+                        process::exit(1);
+                    }
+                }
+                println!("{}", tr!("pkg.publishing", package = pkg));
+                match registry.publish(&metadata, archive) {
+                    Ok(()) => println!("{}", tr!("pkg.published")),
+                    Err(err) => {
+                        eprintln!("{}", err);
+                        return ExitCode::FAILURE;
+                    }
                 }
         }
         "remove" | "احذف" => {
                 if args.len() < 3 {
-                    println!("يرجى تحديد اسم المكتبة للإزالة | Please specify a package to remove");
+                    println!("{}", tr!("pkg.specify_remove"));
                     process::exit(1);
                 }
+                // Only the local copy goes; the registry is left alone, see `yank`.
                 let pkg = &args[2];
-                let pkg_path = format!("{}/{}", pkg_dir, pkg);
-                if !Path::new(&pkg_path).exists() {
-                    println!("المكتبة غير مثبتة | Package not installed");
+                let pkg_path = pkg_dir.join(pkg);
+                if !pkg_path.exists() {
+                    println!("{}", tr!("pkg.not_installed"));
+                    process::exit(1);
+                }
+                let removed = if pkg_path.is_dir() { fs::remove_dir_all(&pkg_path) } else { fs::remove_file(&pkg_path) };
+                if let Err(err) = removed {
+                    println!("{}", tr!("pkg.remove_failed", package = pkg, error = err));
                     process::exit(1);
                 }
-                // Remove locally
-                fs::remove_file(&pkg_path).unwrap();
-                let mut manifest = load_manifest(manifest_path);
+                let mut manifest = read_install_manifest(&manifest_path);
                 manifest.remove(pkg);
-                save_manifest(manifest_path, &manifest);
-                println!("تمت الإزالة محلياً | Removed locally");
-                // Remove from registry (if owner)
-                // TODO: Add authentication (token)
-                let url = format!("{}/packages/{}/1.0.0", registry_url, pkg); // TODO: Use real version
-                match client.delete(&url).send() {
-                    Ok(resp) => {
-                        if resp.status().is_success() {
-                            println!("تمت الإزالة من السجل | Removed from registry");
-                        } else {
-                            println!("فشل الإزالة من السجل | Failed to remove from registry: {}", resp.status());
-                        }
+                write_install_manifest(&manifest_path, &manifest).unwrap();
+                println!("{}", tr!("pkg.removed"));
+        }
+        "yank" | "اسحب" => {
+                let options = match YankOptions::from_args(&args[2..]) {
+                    Ok(options) => options,
+                    Err(message) => {
+                        println!("{}", message);
+                        process::exit(1);
+                    }
+                };
+                let Some(token) = env::var(REGISTRY_TOKEN_VAR).ok().filter(|token| !token.is_empty()) else {
+                    println!("{}", tr!("pkg.yank_needs_token", var = REGISTRY_TOKEN_VAR));
+                    process::exit(1);
+                };
+                let (pkg, version) = (&options.package, &options.version);
+                if !options.yes {
+                    print!("{} ", tr!("pkg.yank_confirm", package = pkg, version = version));
+                    std::io::stdout().flush().ok();
+                    let mut answer = String::new();
+                    std::io::stdin().read_line(&mut answer).ok();
+                    if !confirmed(&answer) {
+                        println!("{}", tr!("pkg.yank_cancelled"));
+                        process::exit(1);
+                    }
+                }
+                match registry.yank(pkg, version, &token) {
+                    Ok(()) => {
+                        println!("{}", tr!("pkg.yanked", package = pkg, version = version));
+                    }
+                    Err(err) => {
+                        println!("{}", err);
+                        return ExitCode::FAILURE;
                     }
-                    Err(_) => println!("خطأ في الاتصال بالسجل | Registry connection error"),
                 }
         }
         "search" | "ابحث" => {
+                let options = match SearchOptions::from_args(&args[2..]) {
+                    Ok(options) => options,
+                    Err(message) => {
+                        println!("{}", message);
+                        process::exit(1);
+                    }
+                };
+                let query = &options.query;
+                println!("{}", tr!("pkg.searching", query = query));
+                match registry.search(&options) {
+                    Ok(results) => {
+                        println!("{}", tr!("pkg.search_results"));
+                        let color = std::io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+                        print!("{}", render_search_results(&results, &options, color));
+                    }
+                    Err(err) => println!("{}", err),
+                }
+        }
+        "run" | "شغل" => {
                 if args.len() < 3 {
-                    println!("يرجى تحديد كلمة البحث | Please specify a search query");
+                    println!("{}", tr!("pkg.specify_run"));
                     process::exit(1);
                 }
-                let query = &args[2];
-                println!("البحث في السجل عن '{}' ... | Searching registry for '{}' ...", query, query);
-                let url = format!("{}/packages?q={}", registry_url, query);
-                match client.get(&url).send() {
-                    Ok(resp) => {
-                        if resp.status().is_success() {
-                            let results: serde_json::Value = resp.json().unwrap_or_default();
-                            println!("نتائج البحث | Search results:");
-                            if let Some(arr) = results.as_array() {
-                                for r in arr {
-                                    let name = r.get("name").and_then(|v| v.as_str()).unwrap_or("");
-                                    let desc = r.get("description").and_then(|v| v.as_str()).unwrap_or("");
-                                    println!("  - {}: {}", name, desc);
-                                }
-                            } else {
-                                println!("لا توجد نتائج | No results found");
-                            }
-                        } else {
-                            println!("فشل البحث في السجل | Registry search failed: {}", resp.status());
-                        }
-                    }
-                    Err(_) => println!("خطأ في الاتصال بالسجل | Registry connection error"),
+                let pkg = &args[2];
+                let mut rest = &args[3..];
+                let script = match rest {
+                    [flag, name, ..] if flag == "--script" || flag == "--سكربت" => {
+                        rest = &rest[2..];
+                        Some(name.as_str())
+                    }
+                    _ => None,
+                };
+                // Project-local packages shadow global ones.
+                let pkg_path = paths::find_package(Path::new("."), pkg).unwrap_or_else(|| pkg_dir.join(pkg));
+                let entry = match resolve_entry(&pkg_path, script) {
+                    Ok(entry) => entry,
+                    Err(err) => {
+                        println!("{}", err);
+                        process::exit(1);
+                    }
+                };
+                let options = RunOptions { args: rest.to_vec(), ..RunOptions::default() };
+                return match run_file(&entry.to_string_lossy(), &options) {
+                    Ok(code) => code,
+                    Err(err) => {
+                        println!("{}", tr!("pkg.run_failed", package = pkg, error = err));
+                        ExitCode::FAILURE
+                    }
+                };
+        }
+        "list" | "اعرض" => {
+                let mut installed: Vec<String> = fs::read_dir(&pkg_dir)
+                    .map(|entries| entries.flatten().map(|entry| entry.file_name().to_string_lossy().into_owned()).collect())
+                    .unwrap_or_default();
+                if installed.is_empty() {
+                    println!("{}", tr!("pkg.none_installed_in", dir = pkg_dir.display()));
+                    process::exit(0);
+                }
+                installed.sort();
+                let manifest = read_install_manifest(&manifest_path);
+                println!("{}", tr!("pkg.installed_in", dir = pkg_dir.display()));
+                for pkg in installed {
+                    match manifest.get(&pkg) {
+                        Some(version) => println!("  - {} ({})", pkg, version),
+                        None => println!("  - {}", pkg),
+                    }
                 }
         }
         "help" | "مساعدة" => {
             print_usage();
         }
         _ => {
-            println!("أمر غير معروف | Unknown command");
+            println!("{}", tr!("pkg.unknown_command"));
             print_usage();
             process::exit(1);
         }
     }
+    ExitCode::SUCCESS
 }
@@ -0,0 +1,170 @@
+//! وسائط سطر الأوامر | Command-line arguments of the `iqra` binary.
+
+use crate::cli::doc::DocFormat;
+use crate::cli::trace::DEFAULT_TRACE_LIMIT;
+use crate::lang::runtime::ErrorLanguage;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Version, commit, target and features, as printed by `iqra version`.
+pub const LONG_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    "\ncommit: ",
+    env!("IQRA_GIT_COMMIT"),
+    "\ntarget: ",
+    env!("IQRA_TARGET"),
+    "\nfeatures: ",
+    env!("IQRA_FEATURES"),
+);
+
+#[derive(Parser)]
+#[command(name = "iqra")]
+#[command(about = "Iqra - Arabic-first scripting language", long_about = None)]
+#[command(version = LONG_VERSION)]
+pub struct Cli {
+    /// Language of messages and errors: ar, en or both
+    #[arg(long, global = true, env = "IQRA_LANG", default_value = "both")]
+    pub lang: ErrorLanguage,
+
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Start interactive REPL
+    Repl {
+        /// Script to load into the session before the first prompt (repeatable)
+        #[arg(long)]
+        preload: Vec<String>,
+        /// Prompt text, or `ascii`/`arabic` for a built-in set (default: $IQRA_PROMPT, else by terminal)
+        #[arg(long)]
+        prompt: Option<String>,
+    },
+    /// Run a script file
+    Run {
+        /// Path to the script file
+        file: String,
+        /// Do not print analyzer warnings
+        #[arg(long)]
+        no_warnings: bool,
+        /// Refuse commands, file writes and environment changes
+        #[arg(long)]
+        sandbox: bool,
+        /// Re-run the script whenever the file changes
+        #[arg(long)]
+        watch: bool,
+        /// Print each statement before it runs and the variables it changed
+        #[arg(long)]
+        trace: bool,
+        /// Stop tracing after this many statements
+        #[arg(long, default_value_t = DEFAULT_TRACE_LIMIT)]
+        trace_limit: usize,
+        /// Print call counts and time per function on exit
+        #[arg(long)]
+        profile: bool,
+        /// Run the script as written, without folding constants or
+        /// removing dead branches
+        #[arg(long)]
+        no_optimize: bool,
+        /// Write the final variables, functions, output and error as JSON
+        #[arg(long, value_name = "PATH")]
+        emit_state: Option<PathBuf>,
+        /// Stop after this many statements
+        #[arg(long)]
+        max_statements: Option<u64>,
+        /// Stop after this many milliseconds
+        #[arg(long)]
+        timeout_ms: Option<u64>,
+        /// Print the value of the last statement when it is not nil
+        #[arg(long)]
+        print_result: bool,
+        /// Install the dependencies in the project's iqra.json that are
+        /// missing before running
+        #[arg(long)]
+        auto_install: bool,
+    },
+    /// Check a script file for likely mistakes without running it
+    Check {
+        /// Path to the script file
+        file: String,
+    },
+    /// Extract `///` doc comments of functions as Markdown or JSON
+    Doc {
+        /// Script file, or a directory of .iqra files
+        path: String,
+        /// Output format: md or json
+        #[arg(long, default_value = "md")]
+        format: DocFormat,
+    },
+    /// Time a script file or a code snippet over several runs
+    Bench {
+        /// Script file, or code to run when no such file exists
+        file_or_code: String,
+        /// Number of timed runs
+        #[arg(long, default_value_t = 10)]
+        iterations: usize,
+        /// Untimed runs before timing starts
+        #[arg(long, default_value_t = 2)]
+        warmup: usize,
+        /// Print the results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Evaluate programs sent as JSON requests
+    Serve {
+        /// Read newline-delimited JSON requests from stdin and answer on stdout
+        #[arg(long)]
+        stdin_rpc: bool,
+        /// Maximum statements per request
+        #[arg(long, default_value_t = 1_000_000)]
+        max_statements: u64,
+        /// Maximum milliseconds per request
+        #[arg(long, default_value_t = 5000)]
+        timeout_ms: u64,
+    },
+    /// Start a language server on stdio for editor integration
+    Lsp,
+    /// Print a shell completion script
+    Completions {
+        /// Shell to generate completions for
+        shell: Shell,
+    },
+    /// Print version and build information
+    Version,
+    /// Run code directly from command line
+    #[command(after_help = "Examples:\n  iqra code -c 'س = ١; اطبع(س)'\n  iqra code -c 'س = ١' -c 'اطبع(س + ١)'\n  iqra code --escape 'x = 2\\nprint(x * 3)'")]
+    Code {
+        /// Code to execute; separate statements on one line with ; or ؛.
+        /// Repeat -c to add lines
+        #[arg(short, long, required_unless_present = "source")]
+        code: Vec<String>,
+        /// Code to execute, instead of -c
+        #[arg(conflicts_with = "code")]
+        source: Option<String>,
+        /// Turn each literal \n in the code into a newline
+        #[arg(long)]
+        escape: bool,
+        /// Refuse commands, file writes and environment changes
+        #[arg(long)]
+        sandbox: bool,
+        /// Print each statement before it runs and the variables it changed
+        #[arg(long)]
+        trace: bool,
+        /// Stop tracing after this many statements
+        #[arg(long, default_value_t = DEFAULT_TRACE_LIMIT)]
+        trace_limit: usize,
+    },
+}
+
+/// The text printed by `iqra version` and `iqra --version`.
+pub fn version_info() -> String {
+    format!("iqra {}\n", LONG_VERSION)
+}
+
+/// Writes the completion script for `shell` to `out`.
+pub fn write_completions(shell: Shell, out: &mut dyn Write) {
+    clap_complete::generate(shell, &mut Cli::command(), "iqra", out);
+}
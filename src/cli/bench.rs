@@ -0,0 +1,116 @@
+//! قياس الأداء | `iqra bench`: time a script or expression over many runs.
+
+use crate::lang::output::CapturedOutput;
+use crate::lang::program::Program;
+use crate::lang::runtime::Runtime;
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+/// خيارات القياس | How many runs to time and how to report them.
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    pub iterations: usize,
+    pub warmup: usize,
+    pub json: bool,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions { iterations: 10, warmup: 2, json: false }
+    }
+}
+
+/// Summary of the timed runs, in milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchStats {
+    pub iterations: usize,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Computes min/median/mean/max of `durations`. The median of an even number
+/// of runs is the mean of the two middle runs. Returns `None` for no runs.
+pub fn summarize(durations: &[Duration]) -> Option<BenchStats> {
+    if durations.is_empty() {
+        return None;
+    }
+    let mut millis: Vec<f64> = durations.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+    millis.sort_by(f64::total_cmp);
+    let n = millis.len();
+    let median = if n.is_multiple_of(2) { (millis[n / 2 - 1] + millis[n / 2]) / 2.0 } else { millis[n / 2] };
+    Some(BenchStats {
+        iterations: n,
+        min_ms: millis[0],
+        median_ms: median,
+        mean_ms: millis.iter().sum::<f64>() / n as f64,
+        max_ms: millis[n - 1],
+    })
+}
+
+/// Renders the stats as the human-readable report.
+pub fn render_stats(label: &str, stats: &BenchStats) -> String {
+    format!(
+        "{}\n  تكرارات | iterations: {}\n  أقل | min:       {:.3} ms\n  وسيط | median:   {:.3} ms\n  متوسط | mean:    {:.3} ms\n  أكثر | max:      {:.3} ms\n",
+        label, stats.iterations, stats.min_ms, stats.median_ms, stats.mean_ms, stats.max_ms
+    )
+}
+
+/// Benchmarks a script file, or a code snippet when `file_or_code` is not an
+/// existing path. The source is compiled once; each run gets a fresh runtime
+/// and its printed output is discarded.
+pub fn run_bench(file_or_code: &str, options: &BenchOptions) -> Result<ExitCode> {
+    let source = if Path::new(file_or_code).is_file() {
+        fs::read_to_string(file_or_code)?
+    } else {
+        file_or_code.to_string()
+    };
+    let program = match Program::compile(&source) {
+        Ok(program) => program,
+        Err(err) => {
+            eprintln!("خطأ - Error: {}", err);
+            return Ok(ExitCode::from(2));
+        }
+    };
+
+    let output = CapturedOutput::new();
+    let run_once = || -> Result<Duration> {
+        let mut runtime = Runtime::new();
+        runtime.set_output(Box::new(output.clone()));
+        let start = Instant::now();
+        let result = runtime.run_program(&program);
+        let elapsed = start.elapsed();
+        output.take();
+        result.map(|_| elapsed)
+    };
+
+    let mut durations = Vec::with_capacity(options.iterations);
+    for i in 0..options.warmup + options.iterations {
+        match run_once() {
+            Ok(elapsed) if i >= options.warmup => durations.push(elapsed),
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("خطأ - Error: {}", err);
+                return Ok(ExitCode::from(1));
+            }
+        }
+    }
+
+    let Some(stats) = summarize(&durations) else {
+        eprintln!(
+            "خطأ - Error: عدد التكرارات يجب أن يكون ١ على الأقل | iterations must be at least 1"
+        );
+        return Ok(ExitCode::from(2));
+    };
+    if options.json {
+        println!("{}", serde_json::to_string(&stats)?);
+    } else {
+        print!("{}", render_stats(file_or_code, &stats));
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,202 @@
+//! المصحح التفاعلي | Interactive debugger with breakpoints, step-in and step-over.
+//!
+//! The debugger drives a [`Runtime`] through its `on_statement` hook: before each statement
+//! it decides whether to pause, and when paused asks a prompt callback for the
+//! next command. The REPL supplies a prompt that reads from the terminal; tests
+//! supply a scripted one.
+
+use crate::i18n::List;
+use crate::lang::parser::Stmt;
+use crate::lang::runtime::{DebugAction, Runtime};
+use crate::tr;
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+
+/// أوامر المصحح | Commands understood by the debugger prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DebugCommand {
+    /// `تابع` / `continue`: run until the next breakpoint.
+    Continue,
+    /// `ادخل` / `step-in`: pause at the very next statement, entering function calls.
+    StepIn,
+    /// `تخط` / `step-over`: pause at the next statement at the same call depth or shallower.
+    StepOver,
+    /// `نقطة_توقف ١٢` / `break 12`
+    SetBreakpoint(usize),
+    /// `امسح ١٢` / `clear 12`, or `امسح` / `clear` for all breakpoints.
+    ClearBreakpoint(Option<usize>),
+    /// `نقاط` / `breakpoints`
+    ListBreakpoints,
+    /// `إنهاء` / `quit`: abort the program being debugged.
+    Quit,
+}
+
+impl DebugCommand {
+    /// Parses a command line typed at the debugger prompt.
+    pub fn parse(input: &str) -> Option<DebugCommand> {
+        let mut parts = input.split_whitespace();
+        let command = parts.next()?;
+        let line = parts.next().map(parse_count);
+        match (command, line) {
+            ("تابع" | "continue" | "c", None) => Some(DebugCommand::Continue),
+            ("ادخل" | "step-in" | "s", None) => Some(DebugCommand::StepIn),
+            ("تخط" | "step-over" | "n", None) => Some(DebugCommand::StepOver),
+            ("نقطة_توقف" | "break" | "b", Some(Some(line))) => {
+                Some(DebugCommand::SetBreakpoint(line))
+            }
+            ("امسح" | "clear", None) => Some(DebugCommand::ClearBreakpoint(None)),
+            ("امسح" | "clear", Some(Some(line))) => {
+                Some(DebugCommand::ClearBreakpoint(Some(line)))
+            }
+            ("نقاط" | "breakpoints", None) => Some(DebugCommand::ListBreakpoints),
+            ("إنهاء" | "quit" | "q", None) => Some(DebugCommand::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a whole number typed with ASCII or Arabic-Indic digits.
+pub(crate) fn parse_count(text: &str) -> Option<usize> {
+    let ascii: String = text
+        .chars()
+        .map(|ch| match ch {
+            '٠'..='٩' => char::from(b'0' + (ch as u32 - '٠' as u32) as u8),
+            _ => ch,
+        })
+        .collect();
+    ascii.parse().ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Running,
+    StepIn,
+    StepOver(usize),
+}
+
+#[derive(Debug)]
+struct DebuggerState {
+    breakpoints: BTreeSet<usize>,
+    mode: Mode,
+}
+
+/// Cloning a `Debugger` shares its breakpoints, so a prompt callback can hold a
+/// clone and manage breakpoints while the program is paused.
+#[derive(Debug, Clone)]
+pub struct Debugger {
+    state: Arc<Mutex<DebuggerState>>,
+}
+
+impl Default for Debugger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debugger {
+    /// Creates a debugger that pauses before the first statement.
+    pub fn new() -> Self {
+        Debugger {
+            state: Arc::new(Mutex::new(DebuggerState {
+                breakpoints: BTreeSet::new(),
+                mode: Mode::StepIn,
+            })),
+        }
+    }
+
+    pub fn add_breakpoint(&self, line: usize) {
+        self.state.lock().unwrap().breakpoints.insert(line);
+    }
+
+    /// Removes a breakpoint, returning whether it existed.
+    pub fn remove_breakpoint(&self, line: usize) -> bool {
+        self.state.lock().unwrap().breakpoints.remove(&line)
+    }
+
+    pub fn clear_breakpoints(&self) {
+        self.state.lock().unwrap().breakpoints.clear();
+    }
+
+    pub fn breakpoints(&self) -> Vec<usize> {
+        self.state.lock().unwrap().breakpoints.iter().copied().collect()
+    }
+
+    /// Runs without pausing until a breakpoint is hit.
+    pub fn continue_running(&self) {
+        self.state.lock().unwrap().mode = Mode::Running;
+    }
+
+    /// Applies a breakpoint-management command, returning a message for the user.
+    /// Flow commands are handled by the step hook and return `None`.
+    pub fn apply(&self, command: &DebugCommand) -> Option<String> {
+        match command {
+            DebugCommand::SetBreakpoint(line) => {
+                self.add_breakpoint(*line);
+                Some(tr!("debug.breakpoint_set", line = line))
+            }
+            DebugCommand::ClearBreakpoint(Some(line)) => Some(if self.remove_breakpoint(*line) {
+                tr!("debug.breakpoint_cleared", line = line)
+            } else {
+                tr!("debug.no_breakpoint_at", line = line)
+            }),
+            DebugCommand::ClearBreakpoint(None) => {
+                self.clear_breakpoints();
+                Some(tr!("debug.breakpoints_cleared"))
+            }
+            DebugCommand::ListBreakpoints => {
+                let lines = self.breakpoints();
+                Some(if lines.is_empty() {
+                    tr!("debug.no_breakpoints")
+                } else {
+                    tr!("debug.breakpoints", lines = List(&lines))
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Installs the debugger on `runtime`. Whenever execution pauses, `prompt`
+    /// is called with the statement about to run until it returns a flow
+    /// command (continue, step-in, step-over or quit). Breakpoint commands are
+    /// applied in between, and the next call receives their message to show;
+    /// the first call of each pause receives `None`.
+    pub fn attach<F>(&self, runtime: &mut Runtime, mut prompt: F)
+    where
+        F: FnMut(&Stmt, &Runtime, Option<String>) -> DebugCommand + Send + 'static,
+    {
+        let debugger = self.clone();
+        runtime.hooks_mut().on_statement = Some(Box::new(move |stmt, runtime| {
+            if !debugger.should_pause(stmt, runtime) {
+                return DebugAction::Continue;
+            }
+            let mut message = None;
+            loop {
+                let command = prompt(stmt, runtime, message.take());
+                let mode = match command {
+                    DebugCommand::Continue => Mode::Running,
+                    DebugCommand::StepIn => Mode::StepIn,
+                    DebugCommand::StepOver => Mode::StepOver(runtime.call_depth()),
+                    DebugCommand::Quit => return DebugAction::Abort,
+                    other => {
+                        message = debugger.apply(&other);
+                        continue;
+                    }
+                };
+                debugger.state.lock().unwrap().mode = mode;
+                return DebugAction::Continue;
+            }
+        }));
+    }
+
+    fn should_pause(&self, stmt: &Stmt, runtime: &Runtime) -> bool {
+        let state = self.state.lock().unwrap();
+        if state.breakpoints.contains(&stmt.span.start_line) {
+            return true;
+        }
+        match state.mode {
+            Mode::Running => false,
+            Mode::StepIn => true,
+            Mode::StepOver(depth) => runtime.call_depth() <= depth,
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! توثيق الدوال | `iqra doc`: extracts `///` comments on functions.
+//!
+//! A doc comment is one or more `///` (or `//!`) lines immediately above
+//! `دالة`:
+//!
+//! ```text
+//! /// يجمع عددين
+//! /// Adds two numbers.
+//! دالة جمع_عددين(أ، ب) { ارجع أ + ب }
+//! ```
+
+use crate::lang::lexer::Lexer;
+use crate::lang::parser::{Parser, StmtKind};
+use crate::tr;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// صيغة التوثيق | Output format of `iqra doc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DocFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+impl std::str::FromStr for DocFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "md" | "markdown" => Ok(DocFormat::Markdown),
+            "json" => Ok(DocFormat::Json),
+            other => Err(tr!("doc.unknown_format", format = other)),
+        }
+    }
+}
+
+/// One documented function. `params` lists the rest parameter last, as `...name`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FunctionDoc {
+    pub name: String,
+    pub params: Vec<String>,
+    pub doc: Option<String>,
+    pub line: usize,
+}
+
+/// The functions of one script file, in source order.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileDoc {
+    pub file: String,
+    pub functions: Vec<FunctionDoc>,
+}
+
+/// Parses `source` and returns its top-level functions.
+pub fn extract_docs(source: &str) -> Result<Vec<FunctionDoc>> {
+    let statements = Parser::new(Lexer::new(source)).parse()?;
+    Ok(statements
+        .iter()
+        .filter_map(|stmt| match &stmt.kind {
+            StmtKind::FunctionDef { name, params, rest, doc, .. } => Some(FunctionDoc {
+                name: name.to_string(),
+                params: params
+                    .iter()
+                    .map(|(param, _)| param.to_string())
+                    .chain(rest.iter().map(|rest| format!("...{}", rest)))
+                    .collect(),
+                doc: doc.clone(),
+                line: stmt.span.start_line,
+            }),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Renders `files` as Markdown. Each file's section is wrapped in a
+/// `dir="rtl"` block so Arabic text reads right to left when rendered.
+pub fn render_markdown(files: &[FileDoc]) -> String {
+    let mut out = String::new();
+    for file in files {
+        out.push_str(&format!("# {}\n\n<div dir=\"rtl\">\n\n", file.file));
+        if file.functions.is_empty() {
+            out.push_str("لا توجد دوال.\n\n");
+        }
+        for function in &file.functions {
+            out.push_str(&format!("## `{}({})`\n\n", function.name, function.params.join("، ")));
+            match &function.doc {
+                Some(doc) => out.push_str(&format!("{}\n\n", doc)),
+                None => out.push_str("_بدون توثيق_\n\n"),
+            }
+        }
+        out.push_str("</div>\n\n");
+    }
+    out
+}
+
+/// Collects the `.iqra` files to document: `path` itself, or the files in
+/// the directory sorted by name.
+fn script_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(path)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|file| file.extension().is_some_and(|ext| ext == "iqra"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Documents a script file or a directory of scripts in `format`.
+pub fn document(path: &str, format: DocFormat) -> Result<String> {
+    let mut files = Vec::new();
+    for file in script_files(Path::new(path))? {
+        let source = fs::read_to_string(&file).with_context(|| format!("{}", file.display()))?;
+        let functions = extract_docs(&source).with_context(|| format!("{}", file.display()))?;
+        files.push(FileDoc { file: file.display().to_string(), functions });
+    }
+    Ok(match format {
+        DocFormat::Markdown => render_markdown(&files),
+        DocFormat::Json => format!("{}\n", serde_json::to_string_pretty(&files)?),
+    })
+}
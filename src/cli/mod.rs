@@ -1,27 +1,82 @@
-use crate::lang::runtime::Runtime;
+pub mod args;
+pub mod bench;
+pub mod debugger;
+pub mod doc;
+pub mod profile;
+pub mod report;
+pub mod results;
+pub mod serve;
+pub mod terminal;
+pub mod trace;
+
+use crate::cli::debugger::{DebugCommand, Debugger};
+use crate::cli::profile::render_profile;
+use crate::cli::report::{parse_error_report, state_report, write_report};
+use crate::cli::results::{ResultHistory, result_label};
+use crate::cli::terminal::{Prompts, choose_prompts, isolate};
+use crate::cli::trace::Tracer;
+use crate::i18n::List;
+use crate::lang::analyzer::{Warning, analyze_with_comments};
+use crate::lang::builtins;
+use crate::lang::lexer::{Lexer, Span};
+use crate::lang::optimizer::optimize;
+use crate::lang::output::{CapturedOutput, StdoutSink, TeeOutput};
+use crate::lang::parser::Parser;
+use crate::lang::runtime::{ErrorLanguage, ExitSignal, IqraError, Limits, Runtime};
+use crate::lang::stepper::{Stepper, VariableChange};
+use crate::lang::value::{PrettyLimits, Value, set_interactive_display};
+use crate::paths;
+use crate::pkg::{
+    DEFAULT_JOBS, PROJECT_MANIFEST, PackageManifest, download_packages, read_install_manifest,
+    registry_for, registry_url, render_download_summary, write_install_manifest,
+};
+use crate::tr;
 use anyhow::Result;
+use owo_colors::{OwoColorize, Style};
 use rustyline::Editor;
 use rustyline::error::ReadlineError;
 use rustyline::history::DefaultHistory;
+use notify::{RecursiveMode, Watcher};
+use std::collections::HashMap;
 use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
 
-pub fn run_repl() -> Result<()> {
-    println!("مرحباً بك في اقرأ - Welcome to Iqra");
-    println!("اكتب 'خروج' أو 'exit' للخروج - Type 'خروج' or 'exit' to quit");
-    println!("اكتب 'خطوة' أو 'step' لتفعيل التنفيذ التفاعلي - Type 'خطوة' or 'step' for interactive step-by-step mode");
+/// Starts the REPL, first loading `init.iqra` from `IQRA_HOME` (default
+/// `~/.iqra`) when it exists and then each of `preload` in order. `prompt`
+/// overrides `IQRA_PROMPT`; see [`choose_prompts`].
+pub fn run_repl(preload: &[String], prompt: Option<&str>) -> Result<()> {
+    set_interactive_display(true);
+    println!("{}", tr!("repl.welcome"));
+    println!("{}", tr!("repl.exit_hint"));
+    println!("{}", tr!("repl.step_hint"));
+    println!("{}", tr!("repl.debug_hint"));
 
+    let prompts = choose_prompts(prompt, |name| std::env::var(name).ok());
     let mut rl = Editor::<(), DefaultHistory>::new()?;
     let mut runtime = Runtime::new();
+    // At the prompt Ctrl-C reaches the line editor; while a line runs it
+    // cancels that line and the REPL carries on.
+    let cancellation = runtime.cancellation_token();
+    let interrupt = cancellation.clone();
+    let _ = ctrlc::set_handler(move || interrupt.cancel());
+    let init_file = paths::init_file().filter(|path| path.is_file());
+    for path in init_file.iter().map(|path| path.to_string_lossy().into_owned()).chain(preload.iter().cloned()) {
+        load_and_report(&path, &mut runtime);
+    }
     let mut step_mode = false;
-    let mut step_lines: Vec<String> = Vec::new();
-    let mut step_index = 0;
+    let mut stepper: Option<Stepper> = None;
+    let mut step_source = String::new();
+    let mut timing = false;
+    let mut limits = PrettyLimits::default();
+    let mut last_result: Option<Value> = None;
+    let mut results = ResultHistory::new();
 
     loop {
-        let prompt = if step_mode {
-            "اقرأ (خطوة)> "
-        } else {
-            "اقرأ> "
-        };
+        let prompt = if step_mode { &prompts.step } else { &prompts.main };
         let readline = rl.readline(prompt);
         match readline {
             Ok(line) => {
@@ -31,92 +86,179 @@ pub fn run_repl() -> Result<()> {
                 }
 
                 // Handle exit commands in both Arabic and English
-                if line == "خروج" || line == "exit" || line == "quit" || line == "إنهاء" {
-                    println!("وداعاً! - Goodbye!");
+                if !step_mode && (line == "خروج" || line == "exit" || line == "quit" || line == "إنهاء") {
+                    println!("{}", tr!("repl.goodbye"));
                     break;
                 }
 
+                if !step_mode && (line == ":توقيت" || line == ":time") {
+                    timing = !timing;
+                    println!("{}", if timing { tr!("repl.timing_on") } else { tr!("repl.timing_off") });
+                    continue;
+                }
+
+                if !step_mode && (line == ":صفّر" || line == ":صفر" || line == ":reset") {
+                    results.reset(&mut runtime);
+                    println!("{}", tr!("repl.results_cleared"));
+                    continue;
+                }
+
+                if !step_mode && (line == ":كامل" || line == ":full") {
+                    match &last_result {
+                        Some(value) => println!("{}", value.pretty_with(0, &PrettyLimits::UNLIMITED)),
+                        None => println!("{}", tr!("repl.no_previous_result")),
+                    }
+                    continue;
+                }
+
+                if !step_mode && let Some(setting) = meta_argument(line, &[":اضبط", ":set"]) {
+                    match set_display_limit(&mut limits, setting) {
+                        Ok(()) => println!("{}", tr!("repl.set", setting = setting)),
+                        Err(e) => eprintln!("{}: {}", tr!("label.error"), e),
+                    }
+                    continue;
+                }
+
+                if !step_mode && let Some(path) = meta_argument(line, &[":حمل", ":load"]) {
+                    load_and_report(path, &mut runtime);
+                    continue;
+                }
+
+                if !step_mode && let Some(path) = meta_argument(line, &[":احفظ_الجلسة", ":save-session"]) {
+                    save_session(path, &runtime);
+                    continue;
+                }
+
+                if !step_mode && let Some(path) = meta_argument(line, &[":استرجع_الجلسة", ":restore-session"]) {
+                    restore_session(path, &mut runtime);
+                    continue;
+                }
+
+                if !step_mode && let Some(expr) = meta_argument(line, &[":نوع", ":type"]) {
+                    match runtime.execute_named(expr, "<repl>") {
+                        Ok(value) => println!("{} : {} | {}", value.pretty_with(0, &limits), value.type_name_ar(), value.type_name()),
+                        Err(e) => eprintln!("{}: {}", tr!("label.error"), e),
+                    }
+                    continue;
+                }
+
                 // Step mode activation
                 if !step_mode && (line == "خطوة" || line == "step") {
-                    println!("تم تفعيل وضع التنفيذ التفاعلي خطوة بخطوة!\nأدخل البرنامج كاملاً، ثم استخدم 'التالي' أو 'next' للتنفيذ خطوة خطوة.");
-                    println!("اكتب 'إعادة' أو 'restart' لإعادة التنفيذ، 'إنهاء' أو 'exit' للخروج من الوضع.");
-                    let program = rl.readline("أدخل البرنامج:")?;
-                    step_lines = program
-                        .lines()
-                        .map(|l| l.trim().to_string())
-                        .filter(|l| !l.is_empty())
-                        .collect();
-                    step_index = 0;
-                    step_mode = true;
+                    println!("{}", tr!("repl.step_enabled"));
+                    println!("{}", tr!("repl.step_enter"));
+                    println!("{}", tr!("repl.step_controls"));
+                    let program = read_program(&mut rl, &prompts)?;
+                    match Stepper::parse(&program) {
+                        Ok(parsed) => {
+                            stepper = Some(parsed);
+                            step_source = program;
+                            step_mode = true;
+                        }
+                        Err(e) => eprintln!("{}: {}", tr!("label.error"), e),
+                    }
+                    continue;
+                }
+
+                // Debugger with breakpoints
+                if !step_mode && (line == "تصحيح" || line == "debug") {
+                    println!("{}", tr!("repl.debugger_intro"));
+                    let program = read_program(&mut rl, &prompts)?;
+                    debug_program(&mut runtime, &program, &prompts);
                     continue;
                 }
 
                 if step_mode {
+                    let Some(current) = stepper.as_mut() else {
+                        step_mode = false;
+                        continue;
+                    };
                     if line == "التالي" || line == "next" {
-                        if step_index < step_lines.len() {
-                            let current_line = &step_lines[step_index];
-                            println!("السطر الحالي [{}]: {}", step_index + 1, current_line);
-                            match runtime.execute(current_line) {
-                                Ok(result) => {
-                                    println!("الناتج: {}", result);
-                                }
-                                Err(e) => {
-                                    eprintln!("خطأ - Error: {}", e);
+                        if let Some(stmt) = current.peek() {
+                            println!("{}", describe_span(&step_source, stmt.span));
+                        }
+                        match current.next_step(&mut runtime) {
+                            Some(Ok(step)) => {
+                                println!("{}: {}", tr!("label.result"), isolate(&format!("{}", step.result)));
+                                if !step.changes.is_empty() {
+                                    println!("{}", tr!("repl.changed_variables"));
+                                    for change in &step.changes {
+                                        println!("  {}", isolate(&describe_change(change)));
+                                    }
                                 }
                             }
-                            println!("المتغيرات الحالية:");
-                            for (k, v) in runtime.get_variables() {
-                                println!("{} = {}", k, v);
-                            }
-                            step_index += 1;
-                            if step_index == step_lines.len() {
-                                println!("تم تنفيذ جميع الأسطر!\nAll lines executed!");
-                                step_mode = false;
-                            }
-                        } else {
-                            println!("لا توجد أسطر متبقية - No more lines.");
+                            Some(Err(e)) => eprintln!("{}: {}", tr!("label.error"), e),
+                            None => {}
+                        }
+                        if current.is_finished() {
+                            println!("{}", tr!("repl.step_done"));
                             step_mode = false;
                         }
                         continue;
                     }
+                    if line == "متغيرات" || line == "vars" {
+                        for line in describe_scopes(&runtime.all_scopes()) {
+                            println!("{}", line);
+                        }
+                        continue;
+                    }
                     if line == "إعادة" || line == "restart" {
                         runtime = Runtime::new();
-                        step_index = 0;
-                        println!("تمت إعادة التنفيذ - Execution restarted.");
+                        runtime.set_cancellation_token(cancellation.clone());
+                        results = ResultHistory::new();
+                        current.restart();
+                        println!("{}", tr!("repl.step_restarted"));
                         continue;
                     }
                     if line == "إنهاء" || line == "exit" {
                         step_mode = false;
-                        println!("تم الخروج من وضع التنفيذ التفاعلي - Exited step mode.");
+                        println!("{}", tr!("repl.step_exited"));
                         continue;
                     }
-                    println!("استخدم 'التالي' أو 'next' للتنفيذ، 'إعادة' أو 'restart' لإعادة التنفيذ، 'إنهاء' أو 'exit' للخروج.");
+                    println!("{}", tr!("repl.step_usage"));
                     continue;
                 }
 
                 let _ = rl.add_history_entry(line);
 
-                match runtime.execute(line) {
+                let started = Instant::now();
+                let outcome = runtime.execute_named(line, "<repl>");
+                if timing {
+                    println!("⏱ {:.3} ms", started.elapsed().as_secs_f64() * 1000.0);
+                }
+                match outcome {
                     Ok(result) => {
-                        if !result.is_nil() {
-                            println!("{}", result);
+                        if let Some(help) = builtins::render_help(&result) {
+                            println!("{}", help);
+                        } else {
+                            match results.record(&mut runtime, &result) {
+                                Ok(Some(number)) => {
+                                    // ASCII prompts get ASCII result numbers.
+                                    let label = result_label(number, prompts.main.is_ascii());
+                                    println!("{} = {}", label, result.pretty_with(0, &limits));
+                                    last_result = Some(result);
+                                }
+                                Ok(None) => {}
+                                Err(e) => eprintln!("{}: {}", tr!("label.error"), e),
+                            }
                         }
                     }
+                    // The REPL outlives scripts that call exit.
+                    Err(e) if e.is::<ExitSignal>() => println!("{}", e),
                     Err(e) => {
-                        eprintln!("خطأ - Error: {}", e);
+                        eprintln!("{}: {}", tr!("label.error"), e);
                     }
                 }
             }
             Err(ReadlineError::Interrupted) => {
-                println!("تم المقاطعة - Interrupted");
+                println!("{}", tr!("repl.interrupted"));
                 break;
             }
             Err(ReadlineError::Eof) => {
-                println!("وداعاً! - Goodbye!");
+                println!("{}", tr!("repl.goodbye"));
                 break;
             }
             Err(err) => {
-                eprintln!("خطأ في القراءة - Read error: {}", err);
+                eprintln!("{}: {}", tr!("label.read_error"), err);
                 break;
             }
         }
@@ -125,14 +267,642 @@ pub fn run_repl() -> Result<()> {
     Ok(())
 }
 
-pub fn run_file(path: &str) -> Result<()> {
-    let content = fs::read_to_string(path)?;
+/// Runs the script at `path` in `runtime`, keeping the functions and
+/// variables it defines.
+pub fn load_into_runtime(path: &str, runtime: &mut Runtime) -> Result<()> {
+    let source = fs::read_to_string(path)?;
+    runtime.execute_named(&source, path)?;
+    Ok(())
+}
+
+/// Loads a file into the REPL session, printing a notice on success and the
+/// error, annotated with the file name, on failure.
+fn load_and_report(path: &str, runtime: &mut Runtime) {
+    match load_into_runtime(path, runtime) {
+        Ok(()) => println!("{}", tr!("repl.loaded", path = path)),
+        Err(err) if err.is::<ExitSignal>() => println!("{}: {}", path, err),
+        Err(err) => report_error(&fs::read_to_string(path).unwrap_or_default(), path, &err),
+    }
+}
+
+/// Writes the REPL session to `path`, naming any variables that were left out.
+fn save_session(path: &str, runtime: &Runtime) {
+    let json = match runtime.save_state() {
+        Ok(json) => json,
+        Err(e) => return eprintln!("{}: {}", tr!("label.error"), e),
+    };
+    if let Err(e) = fs::write(path, &json) {
+        return eprintln!("{}: {}", tr!("label.error"), e);
+    }
+    println!("{}", tr!("repl.session_saved", path = path));
+    let state: serde_json::Value = serde_json::from_str(&json).unwrap_or_default();
+    let skipped: Vec<&str> = state["skipped"].as_array().into_iter().flatten().filter_map(|name| name.as_str()).collect();
+    if !skipped.is_empty() {
+        eprintln!("{}", tr!("repl.variables_not_saved", names = List(&skipped)));
+    }
+}
+
+fn restore_session(path: &str, runtime: &mut Runtime) {
+    match fs::read_to_string(path).map_err(anyhow::Error::from).and_then(|json| runtime.load_state(&json)) {
+        Ok(_) => println!("{}", tr!("repl.session_restored", path = path)),
+        Err(e) => eprintln!("{}: {}", tr!("label.error"), e),
+    }
+}
+
+/// Applies a REPL `:set` line such as `max-items 200` to `limits`. The
+/// settings are `max-items`, `max-chars` and `max-depth`, or in Arabic
+/// `أقصى_عناصر`, `أقصى_حروف` and `أقصى_عمق`.
+pub fn set_display_limit(limits: &mut PrettyLimits, setting: &str) -> Result<()> {
+    let mut words = setting.split_whitespace();
+    let (name, value) = (words.next().unwrap_or(""), words.next());
+    let field = match name {
+        "max-items" | "أقصى_عناصر" => &mut limits.max_items,
+        "max-chars" | "أقصى_حروف" => &mut limits.max_chars,
+        "max-depth" | "أقصى_عمق" => &mut limits.max_depth,
+        _ => {
+            return Err(anyhow::anyhow!(IqraError {
+                kind: "إعداد غير معروف | Unknown Setting".to_string(),
+                message_ar: format!("لا يوجد إعداد باسم '{}'", name),
+                message_en: format!("There is no setting named '{}'", name),
+                suggestion: Some(tr!("suggest.display_settings")),
+                line: None,
+                column: None,
+                source_name: None,
+            }));
+        }
+    };
+    match value.and_then(debugger::parse_count) {
+        Some(n) if words.next().is_none() => {
+            *field = n;
+            Ok(())
+        }
+        _ => Err(anyhow::anyhow!(IqraError {
+            kind: "قيمة غير صالحة | Invalid Value".to_string(),
+            message_ar: format!("قيمة '{}' يجب أن تكون عدداً صحيحاً موجباً", name),
+            message_en: format!("The value of '{}' must be a non-negative whole number", name),
+            suggestion: Some(format!(":set {} 200", name)),
+            line: None,
+            column: None,
+            source_name: None,
+        })),
+    }
+}
+
+/// Returns the rest of a REPL meta-command line such as `:type x + 1` when it
+/// starts with one of `names`.
+fn meta_argument<'a>(line: &'a str, names: &[&str]) -> Option<&'a str> {
+    names.iter().find_map(|name| {
+        let rest = line.strip_prefix(name)?;
+        rest.starts_with(char::is_whitespace).then(|| rest.trim())
+    })
+}
+
+/// خيارات تشغيل ملف | Options for running a script file.
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    /// Print analyzer warnings to stderr before running.
+    pub warnings: bool,
+    /// Refuse builtins that change the host; see `Runtime::set_sandboxed`.
+    pub sandbox: bool,
+    /// Trace at most this many statements; `None` disables tracing.
+    pub trace: Option<usize>,
+    /// Print a table of function calls and their time to stderr on exit.
+    pub profile: bool,
+    /// Arguments the script reads with `وسائط()`.
+    pub args: Vec<String>,
+    /// Fold constants and drop dead branches before running; ignored when
+    /// tracing, so the trace follows the source as written.
+    pub optimize: bool,
+    /// Write a JSON report of the final state here once the script ends.
+    pub emit_state: Option<PathBuf>,
+    /// Statement and time limits; `--sandbox` adds its collection caps.
+    pub limits: Limits,
+    /// Print the value of the last statement when it is not nil. Off by
+    /// default, so a script's stdout holds only what it prints itself.
+    pub print_result: bool,
+    /// Install the project's missing dependencies from the registry before
+    /// running, instead of stopping with a list of them.
+    pub auto_install: bool,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        RunOptions {
+            warnings: true,
+            sandbox: false,
+            trace: None,
+            profile: false,
+            args: Vec::new(),
+            optimize: true,
+            emit_state: None,
+            limits: Limits::default(),
+            print_result: false,
+            auto_install: false,
+        }
+    }
+}
+
+/// Runs a script file, printing any error annotated with its source line.
+///
+/// A script inside a project, a directory with an `iqra.json`, runs only
+/// once the packages the project depends on are installed.
+///
+/// Returns exit code 2 when the file fails to parse, 1 when it can't be read,
+/// its project lacks dependencies or it fails while running, and the
+/// script's own code when it calls `اخرج`/`exit`.
+pub fn run_file(path: &str, options: &RunOptions) -> Result<ExitCode> {
+    if let Some(code) = check_dependencies(Path::new(path), options.auto_install) {
+        return Ok(code);
+    }
+    match read_script(path) {
+        Ok(content) => run_source(&content, path, options),
+        Err(err) => {
+            eprintln!("{}: {}", tr!("label.error"), err);
+            Ok(ExitCode::from(1))
+        }
+    }
+}
+
+/// Checks the dependencies in the `iqra.json` of the project `script` is in,
+/// first installing the missing ones into its `iqra_packages` with
+/// `auto_install`. Returns the exit code to stop with when the manifest is
+/// invalid or packages are still missing.
+fn check_dependencies(script: &Path, auto_install: bool) -> Option<ExitCode> {
+    let dir = script.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    // Canonical, so that a relative path can still walk up past `.`.
+    let project = PackageManifest::find_project(&fs::canonicalize(dir).ok()?)?;
+    let manifest_path = project.join(PROJECT_MANIFEST);
+    let manifest = match PackageManifest::read(&manifest_path) {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            eprintln!("{}: {}", tr!("label.error"), err);
+            return Some(ExitCode::from(1));
+        }
+    };
+    let mut missing = manifest.missing_dependencies(&project);
+    if !missing.is_empty() && auto_install {
+        install_dependencies(&project, &missing);
+        missing = manifest.missing_dependencies(&project);
+    }
+    if missing.is_empty() {
+        return None;
+    }
+    eprintln!(
+        "{}: {}",
+        tr!("label.error"),
+        tr!("run.missing_dependencies", manifest = manifest_path.display(), packages = List(&missing))
+    );
+    let command = format!("iqra-pkg install {}", missing.join(" "));
+    eprintln!("{}", tr!("run.install_dependencies", command = command, dir = project.display()));
+    Some(ExitCode::from(1))
+}
+
+/// Downloads `packages` into `project`'s `iqra_packages` and records them in
+/// its `iqra-pkg.json`, as `iqra-pkg install` run in `project` would.
+/// Progress goes to stderr, leaving stdout to the script.
+fn install_dependencies(project: &Path, packages: &[String]) {
+    let dir = project.join(paths::LOCAL_PACKAGES);
+    if let Err(err) = fs::create_dir_all(&dir) {
+        eprintln!("{}: {}", tr!("label.error"), err);
+        return;
+    }
+    eprintln!("{}", tr!("pkg.installing", packages = List(packages)));
+    let registry = registry_for(&registry_url());
+    let outcomes = download_packages(registry.as_ref(), packages, &dir, DEFAULT_JOBS);
+    let manifest_path = project.join(paths::INSTALL_MANIFEST);
+    let mut installed = read_install_manifest(&manifest_path);
+    for outcome in &outcomes {
+        if let Ok(version) = &outcome.result {
+            installed.insert(outcome.package.clone(), version.clone());
+        }
+    }
+    if let Err(err) = write_install_manifest(&manifest_path, &installed) {
+        eprintln!("{}: {}", tr!("label.error"), err);
+    }
+    eprint!("{}", render_download_summary(&outcomes));
+}
+
+/// Runs code given on the command line as `<cmdline>`, with the same error
+/// reports and exit codes as [`run_file`].
+pub fn run_code(code: &str, options: &RunOptions) -> Result<ExitCode> {
+    run_source(code, "<cmdline>", options)
+}
+
+/// Joins the pieces of `iqra code -c ... -c ...` with newlines. With
+/// `escape`, a literal `\n` also becomes a newline, for shells that make
+/// real newlines awkward to type.
+pub fn join_code(pieces: &[String], escape: bool) -> String {
+    let code = pieces.join("\n");
+    if escape { code.replace("\\n", "\n") } else { code }
+}
+
+fn run_source(content: &str, path: &str, options: &RunOptions) -> Result<ExitCode> {
+    let mut parser = Parser::new(Lexer::new(content));
+    parser.set_source_name(path);
+    let statements = match parser.parse() {
+        Ok(statements) => statements,
+        Err(err) => {
+            report_error(content, path, &err);
+            if let Some(state_path) = &options.emit_state {
+                emit_state(state_path, &parse_error_report(&err));
+            }
+            return Ok(ExitCode::from(2));
+        }
+    };
+
+    if options.warnings {
+        report_warnings(path, &analyze_with_comments(&statements, parser.comments()));
+    }
+    let statements = if options.optimize && options.trace.is_none() { optimize(statements) } else { statements };
+
     let mut runtime = Runtime::new();
-    let result = runtime.execute(&content)?;
+    runtime.set_source_name(Some(path.to_string()));
+    runtime.set_sandboxed(options.sandbox);
+    runtime.set_limits(if options.sandbox { options.limits.with_sandbox_caps() } else { options.limits });
+    runtime.set_profiling(options.profile);
+    runtime.set_script_args(options.args.clone());
+    let stdout = CapturedOutput::new();
+    if options.emit_state.is_some() {
+        runtime.set_output(Box::new(TeeOutput::new(Box::new(StdoutSink), stdout.clone())));
+    }
+    let tracer = options.trace.map(|limit| {
+        let tracer = Tracer::new(content, limit, Box::new(StdoutSink));
+        tracer.attach(&mut runtime);
+        tracer
+    });
+    let outcome = statements.iter().try_fold(Value::Nil, |_, stmt| runtime.execute_parsed_statement(stmt));
+    if let Some(tracer) = &tracer {
+        tracer.finish(&runtime);
+    }
+    if options.profile {
+        eprint!("{}", render_profile(runtime.profile()));
+    }
+    if let Some(state_path) = &options.emit_state {
+        emit_state(state_path, &state_report(&runtime, &outcome, &stdout.contents()));
+    }
+
+    match outcome {
+        Ok(result) => {
+            if options.print_result && !result.is_nil() {
+                println!("{}", result);
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(err) if err.is::<ExitSignal>() => Ok(exit_code(&err)),
+        Err(err) => {
+            report_error(content, path, &err);
+            Ok(ExitCode::from(1))
+        }
+    }
+}
+
+/// Writes the `--emit-state` report. Failing to write it is reported but
+/// leaves the script's own exit code alone.
+fn emit_state(path: &Path, report: &serde_json::Value) {
+    if let Err(err) = write_report(path, report) {
+        eprintln!("{}: {}", tr!("label.error"), err);
+    }
+}
+
+/// Reads a script, naming the path and the working directory it was
+/// resolved against when that fails.
+pub fn read_script(path: &str) -> Result<String> {
+    fs::read_to_string(path).map_err(|err| {
+        let cwd = std::env::current_dir()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_else(|_| "?".to_string());
+        anyhow::anyhow!(IqraError {
+            kind: "تعذرت قراءة الملف | Cannot Read File".to_string(),
+            message_ar: format!("تعذرت قراءة الملف '{}': {}", path, err),
+            message_en: format!("Could not read '{}': {}", path, err),
+            suggestion: Some(tr!("suggest.relative_paths", cwd = cwd)),
+            line: None,
+            column: None,
+            source_name: None,
+        })
+    })
+}
 
-    if !result.is_nil() {
-        println!("{}", result);
+/// Converts an `ExitSignal` into a process exit code. Codes outside 0..=255
+/// are truncated the way the shell reports them.
+pub fn exit_code(err: &anyhow::Error) -> ExitCode {
+    match err.downcast_ref::<ExitSignal>() {
+        Some(ExitSignal(code)) => ExitCode::from(*code as u8),
+        None => ExitCode::FAILURE,
     }
+}
+
+/// Checks a script file without running it.
+///
+/// Returns exit code 2 when the file can't be read or fails to parse, 1 when
+/// it has warnings, and 0 when it is clean.
+pub fn check_file(path: &str) -> Result<ExitCode> {
+    let content = match read_script(path) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("{}: {}", tr!("label.error"), err);
+            return Ok(ExitCode::from(2));
+        }
+    };
+    let mut parser = Parser::new(Lexer::new(&content));
+    parser.set_source_name(path);
+    let (statements, errors) = parser.parse_with_recovery();
+    if !errors.is_empty() {
+        for err in &errors {
+            report_iqra_error(&content, path, err);
+        }
+        return Ok(ExitCode::from(2));
+    }
+
+    let warnings = analyze_with_comments(&statements, parser.comments());
+    report_warnings(path, &warnings);
+    Ok(if warnings.is_empty() { ExitCode::SUCCESS } else { ExitCode::from(1) })
+}
+
+/// حدث مراقبة | Something that happened while watching a script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// The watched file was written, created, renamed or removed.
+    Changed,
+    /// Ctrl-C was pressed.
+    Stop,
+}
 
+/// How long to wait for more changes before re-running; editors often write a
+/// file in several steps.
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Runs `rerun` once, then again whenever `path` changes, until Ctrl-C.
+///
+/// The file's directory is watched rather than the file itself so editors that
+/// save by replacing the file are still noticed.
+pub fn watch_file(path: &str, mut rerun: impl FnMut()) -> Result<()> {
+    let target = fs::canonicalize(path)?;
+    let dir = target.parent().map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("."));
+
+    let (sender, events) = mpsc::channel();
+    let changes = sender.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event
+            && !event.kind.is_access()
+            && event.paths.iter().any(|p| p.file_name() == target.file_name())
+        {
+            let _ = changes.send(WatchEvent::Changed);
+        }
+    })?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    ctrlc::set_handler(move || {
+        let _ = sender.send(WatchEvent::Stop);
+    })?;
+
+    rerun();
+    watch_events(&events, WATCH_DEBOUNCE, rerun);
+    println!("{}", tr!("repl.goodbye"));
     Ok(())
 }
+
+/// Calls `rerun` for each burst of `Changed` events, waiting `debounce` after
+/// the last event of a burst. Returns the number of re-runs once a `Stop`
+/// arrives or the sender goes away.
+pub fn watch_events(events: &Receiver<WatchEvent>, debounce: Duration, mut rerun: impl FnMut()) -> usize {
+    let mut runs = 0;
+    while let Ok(WatchEvent::Changed) = events.recv() {
+        loop {
+            match events.recv_timeout(debounce) {
+                Ok(WatchEvent::Changed) => continue,
+                Ok(WatchEvent::Stop) | Err(RecvTimeoutError::Disconnected) => return runs,
+                Err(RecvTimeoutError::Timeout) => break,
+            }
+        }
+        rerun();
+        runs += 1;
+    }
+    runs
+}
+
+fn report_warnings(path: &str, warnings: &[Warning]) {
+    let (language, color) = (ErrorLanguage::current(), stderr_color_enabled());
+    for warning in warnings {
+        eprint!("{}", render_warning(path, warning, language, color));
+    }
+}
+
+/// Renders an analyzer warning with its location and suggestion.
+pub fn render_warning(path: &str, warning: &Warning, language: ErrorLanguage, color: bool) -> String {
+    let (warning_style, gutter_style, hint_style) = if color {
+        (Style::new().yellow().bold(), Style::new().blue().bold(), Style::new().green().bold())
+    } else {
+        (Style::new(), Style::new(), Style::new())
+    };
+    let mut out = format!(
+        "{}: {}\n {} {}:{}\n",
+        language.pick("تحذير", "warning").style(warning_style),
+        warning.message_in(language),
+        "-->".style(gutter_style),
+        path,
+        warning.line
+    );
+    if let Some(suggestion) = &warning.suggestion {
+        let label = language.pick("اقتراح", "suggestion");
+        out.push_str(&format!("  = {}: {}\n", label.style(hint_style), suggestion));
+    }
+    out
+}
+
+fn report_error(source: &str, path: &str, err: &anyhow::Error) {
+    match err.downcast_ref::<IqraError>() {
+        Some(iqra_err) => report_iqra_error(source, path, iqra_err),
+        None => eprintln!("{}: {}", path, err),
+    }
+}
+
+fn report_iqra_error(source: &str, path: &str, err: &IqraError) {
+    let language = ErrorLanguage::current();
+    eprint!("{}", render_error_with(source, path, err, language, stderr_color_enabled()))
+}
+
+/// Colors are used only on a terminal, and never when `NO_COLOR` is set.
+fn stderr_color_enabled() -> bool {
+    std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Renders an error with the file name, the offending source line underlined,
+/// the message and the suggestion, in the current error language and without colors.
+pub fn render_error(source: &str, path: &str, err: &IqraError) -> String {
+    render_error_with(source, path, err, ErrorLanguage::current(), false)
+}
+
+/// Same as [`render_error`], in `language` and with ANSI colors when `color` is true.
+pub fn render_error_with(
+    source: &str,
+    path: &str,
+    err: &IqraError,
+    language: ErrorLanguage,
+    color: bool,
+) -> String {
+    let (error_style, gutter_style, hint_style) = if color {
+        (Style::new().red().bold(), Style::new().blue().bold(), Style::new().green().bold())
+    } else {
+        (Style::new(), Style::new(), Style::new())
+    };
+
+    let mut out = format!(
+        "{}: {}\n",
+        format!("{}[{}]", language.pick("خطأ", "error"), err.kind).style(error_style),
+        err.message_in(language)
+    );
+
+    let path = err.source_name.as_deref().unwrap_or(path);
+    let source = source.strip_prefix('\u{FEFF}').unwrap_or(source);
+    let source_line = err
+        .line
+        .and_then(|line| source.lines().nth(line.checked_sub(1)?).map(|text| (line, text)));
+    match source_line {
+        Some((line, text)) => {
+            let gutter = " ".repeat(line.to_string().len());
+            let bar = format!("{} |", gutter);
+            let (excerpt, offset, width) = error_excerpt(text, err.column);
+            out.push_str(&format!("{}{} {}:{}\n", gutter, "-->".style(gutter_style), path, line));
+            out.push_str(&format!("{}\n", bar.style(gutter_style)));
+            out.push_str(&format!("{} {}\n", format!("{} |", line).style(gutter_style), excerpt));
+            out.push_str(&format!(
+                "{} {}{}\n",
+                bar.style(gutter_style),
+                " ".repeat(offset),
+                "^".repeat(width).style(error_style)
+            ));
+        }
+        None => out.push_str(&format!(" {} {}\n", "-->".style(gutter_style), path)),
+    }
+
+    if let Some(suggestion) = &err.suggestion {
+        let label = language.pick("اقتراح", "suggestion");
+        out.push_str(&format!("  = {}: {}\n", label.style(hint_style), suggestion));
+    }
+    out
+}
+
+/// Source lines longer than this many characters are cut down to a window
+/// around the error when rendered.
+const LONG_LINE: usize = 200;
+/// How many characters of a long line the window shows, and how many of
+/// them come before the error column.
+const WINDOW_WIDTH: usize = 120;
+const WINDOW_BEFORE: usize = 40;
+
+/// The source line as shown under an error, with how many characters come
+/// before the carets and how many carets there are. A short line is shown
+/// whole, with one caret at `column`, or underlined whole when the error
+/// has no column. A long one shows a window around `column`,
+/// with `…` where text was cut, and underlines from `column` to the end of
+/// the statement there.
+fn error_excerpt(text: &str, column: Option<usize>) -> (String, usize, usize) {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= LONG_LINE {
+        let indent = chars.iter().take_while(|c| c.is_whitespace()).count();
+        let excerpt = format!("{}{}", " ".repeat(indent), isolate(text.trim()));
+        if let Some(column) = column {
+            let end = indent + text.trim().chars().count();
+            return (excerpt, (column.max(1) - 1).min(end), 1);
+        }
+        return (excerpt, indent, text.trim().chars().count().max(1));
+    }
+    let column = column.unwrap_or(1).clamp(1, chars.len()) - 1;
+    let start = column.saturating_sub(WINDOW_BEFORE);
+    let end = (start + WINDOW_WIDTH).min(chars.len());
+    let statement: String =
+        chars[column..end].iter().take_while(|&&c| c != ';' && c != '؛').collect();
+    let width = statement.trim_end().chars().count().max(1);
+    let visible: String = chars[start..end].iter().collect();
+    let before = if start > 0 { "…" } else { "" };
+    let after = if end < chars.len() { "…" } else { "" };
+    let offset = before.chars().count() + column - start;
+    (format!("{}{}{}", before, isolate(&visible), after), offset, width)
+}
+
+/// Reads program lines until an empty line is entered.
+fn read_program(rl: &mut Editor<(), DefaultHistory>, prompts: &Prompts) -> Result<String> {
+    let mut program = String::new();
+    loop {
+        let program_line = rl.readline(&prompts.program)?;
+        if program_line.trim().is_empty() {
+            break;
+        }
+        program.push_str(&program_line);
+        program.push('\n');
+    }
+    Ok(program)
+}
+
+/// Runs `program` under the debugger, prompting on the terminal whenever it pauses.
+fn debug_program(runtime: &mut Runtime, program: &str, prompts: &Prompts) {
+    let debugger = Debugger::new();
+    let prompt = prompts.debug.clone();
+    let source = program.to_string();
+    let mut editor = match Editor::<(), DefaultHistory>::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("{}: {}", tr!("label.read_error"), e);
+            return;
+        }
+    };
+    debugger.attach(runtime, move |stmt, runtime, message| {
+        match message {
+            Some(message) => println!("{}", message),
+            None => println!("{} ({} {})", describe_span(&source, stmt.span), tr!("label.depth"), runtime.call_depth()),
+        }
+        loop {
+            let input = match editor.readline(&prompt) {
+                Ok(input) => input,
+                Err(_) => return DebugCommand::Quit,
+            };
+            match DebugCommand::parse(input.trim()) {
+                Some(command) => return command,
+                None => println!("{}", tr!("debug.unknown_command")),
+            }
+        }
+    });
+    match runtime.execute(program) {
+        Ok(result) => println!("{}: {}", tr!("label.result"), result),
+        Err(e) => eprintln!("{}: {}", tr!("label.error"), e),
+    }
+    runtime.hooks_mut().on_statement = None;
+}
+
+/// Formats the location and first source line of the statement about to run.
+fn describe_span(source: &str, span: Span) -> String {
+    let text = isolate(source.lines().nth(span.start_line.saturating_sub(1)).unwrap_or("").trim());
+    if span.end_line > span.start_line {
+        format!("{} [{}-{}]: {}", tr!("label.current_statement"), span.start_line, span.end_line, text)
+    } else {
+        format!("{} [{}]: {}", tr!("label.current_statement"), span.start_line, text)
+    }
+}
+
+/// The innermost call's variables, when a call is running, then the
+/// globals, each sorted by name. `scopes` is as `Runtime::all_scopes` gives it.
+pub fn describe_scopes(scopes: &[HashMap<String, Value>]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut section = |title: String, scope: &HashMap<String, Value>| {
+        lines.push(title);
+        let mut names: Vec<&String> = scope.keys().collect();
+        names.sort();
+        lines.extend(names.into_iter().map(|name| format!("  {}", isolate(&format!("{} = {}", name, scope[name])))));
+    };
+    if let [_, .., innermost] = scopes {
+        section(tr!("repl.current_frame"), innermost);
+    }
+    if let Some(globals) = scopes.first() {
+        section(tr!("repl.globals"), globals);
+    }
+    lines
+}
+
+pub(crate) fn describe_change(change: &VariableChange) -> String {
+    match (&change.old, &change.new) {
+        (None, Some(new)) => format!("+ {} = {}", change.name, new),
+        (Some(old), Some(new)) => format!("{}: {} -> {}", change.name, old, new),
+        (Some(old), None) => format!("- {} ({} {})", change.name, tr!("label.was"), old),
+        (None, None) => change.name.clone(),
+    }
+}
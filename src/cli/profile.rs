@@ -0,0 +1,27 @@
+//! تقرير الأداء | The table printed by `iqra run --profile`.
+
+use crate::lang::runtime::ProfileEntry;
+use std::collections::HashMap;
+
+/// Renders profiling entries as a table, slowest function first. Ties are
+/// ordered by name so the output is stable.
+pub fn render_profile(profile: &HashMap<String, ProfileEntry>) -> String {
+    let mut rows: Vec<(&String, &ProfileEntry)> = profile.iter().collect();
+    rows.sort_by(|a, b| b.1.total.cmp(&a.1.total).then_with(|| a.0.cmp(b.0)));
+    let width = rows.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0).max(8);
+    let mut out =
+        format!("{:<width$}  {:>8}  {:>12}  {:>10}\n", "function", "calls", "total ms", "avg ms");
+    for (name, entry) in rows {
+        let total_ms = entry.total.as_secs_f64() * 1000.0;
+        let padding = width - name.chars().count();
+        out.push_str(&format!(
+            "{}{}  {:>8}  {:>12.3}  {:>10.3}\n",
+            name,
+            " ".repeat(padding),
+            entry.calls,
+            total_ms,
+            total_ms / entry.calls as f64
+        ));
+    }
+    out
+}
@@ -0,0 +1,113 @@
+//! تقرير الحالة | The JSON report `iqra run --emit-state` writes once a
+//! script has finished, whether it succeeded or not: the global variables,
+//! the functions it defined, its last value, what it printed and the error
+//! that stopped it, if any, what the runtime's caches hold, and under a
+//! statement or time limit the statements that ran most. Graders and CI
+//! read it instead of parsing the terminal output.
+
+use crate::lang::runtime::{ExitSignal, IqraError, REPORTED_HOTSPOTS, Runtime, is_saveable};
+use crate::lang::value::Value;
+use anyhow::Result;
+use serde_json::{Map, Value as Json, json};
+use std::fs;
+use std::path::Path;
+
+/// Bumped whenever a field changes meaning or goes away.
+pub const STATE_REPORT_VERSION: u32 = 1;
+
+/// Builds the report of a run that ended with `outcome`, and printed
+/// `stdout`, in `runtime`. A script that called `اخرج`/`exit` reports its
+/// code and no error.
+pub fn state_report(runtime: &Runtime, outcome: &Result<Value>, stdout: &str) -> Json {
+    let (last_value, exit_code, error) = match outcome {
+        Ok(value) => (value_json(value), 0, Json::Null),
+        Err(err) => match err.downcast_ref::<ExitSignal>() {
+            Some(ExitSignal(code)) => (Json::Null, *code, Json::Null),
+            None => (Json::Null, 1, error_json(err)),
+        },
+    };
+    let variables: Map<String, Json> = runtime
+        .global_variables()
+        .iter()
+        .map(|(name, value)| (name.clone(), value_json(value)))
+        .collect();
+    let hotspots: Vec<Json> = runtime
+        .hotspots(REPORTED_HOTSPOTS)
+        .iter()
+        .map(|spot| json!({ "line": spot.line, "column": spot.column, "count": spot.count }))
+        .collect();
+    let caches = runtime.cache_status();
+    json!({
+        "version": STATE_REPORT_VERSION,
+        "success": exit_code == 0,
+        "exit_code": exit_code,
+        "variables": variables,
+        "functions": runtime.function_names(),
+        "last_value": last_value,
+        "stdout": stdout,
+        "error": error,
+        "hotspots": hotspots,
+        "caches": {
+            "system_info": caches.system_info,
+            "today": caches.today.map(|day| day.to_string()),
+            "regex_patterns": caches.regex_patterns,
+        },
+    })
+}
+
+/// The report of a script that failed to parse, so nothing ran.
+pub fn parse_error_report(err: &anyhow::Error) -> Json {
+    let mut report = state_report(&Runtime::new(), &Ok(Value::Nil), "");
+    report["success"] = json!(false);
+    report["exit_code"] = json!(2);
+    report["error"] = error_json(err);
+    report["last_value"] = Json::Null;
+    report
+}
+
+/// Writes `report` to `path` as pretty-printed JSON.
+pub fn write_report(path: &Path, report: &Json) -> Result<()> {
+    let json = serde_json::to_string_pretty(report).expect("reports always serialize");
+    fs::write(path, json + "\n").map_err(|err| {
+        anyhow::anyhow!(IqraError {
+            kind: "فشل كتابة الملف".to_string(),
+            message_ar: format!("تعذرت كتابة تقرير الحالة '{}': {}", path.display(), err),
+            message_en: format!("Could not write the state report '{}': {}", path.display(), err),
+            suggestion: None,
+            line: None,
+            column: None,
+            source_name: None,
+        })
+    })
+}
+
+/// `value` as it is saved in sessions, e.g. `{"Int": 1}`. Values JSON
+/// can't hold, such as NaN, become their type and how they print.
+pub fn value_json(value: &Value) -> Json {
+    if is_saveable(value) {
+        serde_json::to_value(value).expect("saveable values serialize")
+    } else {
+        json!({ "type": value.type_name(), "repr": format!("{}", value) })
+    }
+}
+
+fn error_json(err: &anyhow::Error) -> Json {
+    match err.downcast_ref::<IqraError>() {
+        Some(e) => json!({
+            "kind": e.kind,
+            "message_ar": e.message_ar,
+            "message_en": e.message_en,
+            "suggestion": e.suggestion,
+            "line": e.line,
+            "column": e.column,
+        }),
+        None => json!({
+            "kind": "internal",
+            "message_ar": err.to_string(),
+            "message_en": err.to_string(),
+            "suggestion": null,
+            "line": null,
+            "column": null,
+        }),
+    }
+}
@@ -0,0 +1,64 @@
+//! نتائج الجلسة | Numbered REPL results. Each value the REPL shows is bound
+//! to `_` and `النتيجة`, and to `_1`, `_2`, ... by its number, so later
+//! lines can use it without typing it again.
+
+use crate::lang::builtins::arabic::{DigitScript, normalize_digits};
+use crate::lang::runtime::Runtime;
+use crate::lang::value::Value;
+use anyhow::Result;
+
+/// The variables that always hold the latest result.
+pub const LAST_RESULT_NAMES: &[&str] = &["_", "النتيجة"];
+
+/// How many results the session has numbered.
+#[derive(Debug, Default)]
+pub struct ResultHistory {
+    count: usize,
+}
+
+impl ResultHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds `value` as the next result and returns its number. `nil` is
+    /// not a result: it is left unbound and takes no number.
+    pub fn record(&mut self, runtime: &mut Runtime, value: &Value) -> Result<Option<usize>> {
+        if value.is_nil() {
+            return Ok(None);
+        }
+        let number = self.count + 1;
+        runtime.set_variable(&format!("_{}", number), value.clone())?;
+        for name in LAST_RESULT_NAMES {
+            runtime.set_variable(name, value.clone())?;
+        }
+        self.count = number;
+        Ok(Some(number))
+    }
+
+    /// The number of the latest result, 0 before the first.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Unbinds every result from `runtime` and starts numbering at 1 again.
+    pub fn reset(&mut self, runtime: &mut Runtime) {
+        for number in 1..=self.count {
+            runtime.remove_variable(&format!("_{}", number));
+        }
+        for name in LAST_RESULT_NAMES {
+            runtime.remove_variable(name);
+        }
+        self.count = 0;
+    }
+}
+
+/// `[٣]`, or `[3]` with `ascii`, shown before result number 3.
+pub fn result_label(number: usize, ascii: bool) -> String {
+    let digits = number.to_string();
+    if ascii {
+        format!("[{}]", digits)
+    } else {
+        format!("[{}]", normalize_digits(&digits, DigitScript::Arabic))
+    }
+}
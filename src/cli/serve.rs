@@ -0,0 +1,175 @@
+//! خادم التقييم | `iqra serve --stdin-rpc`: evaluate programs sent as
+//! newline-delimited JSON requests, answering each with one JSON line.
+//!
+//! Request:  `{"id":1,"method":"eval","params":{"code":"١+٢","reset":true}}`
+//! Response: `{"id":1,"result":{"value":"3","type":"number","stdout":""}}`
+//! or        `{"id":1,"error":{"kind":...,"message_ar":...,"message_en":...}}`
+
+use crate::lang::output::CapturedOutput;
+use crate::lang::runtime::{
+    CANCELLED_ERROR_KIND, ExitSignal, IqraError, Limits, Runtime, timeout_error,
+};
+use crate::lang::value::Value;
+use anyhow::Result;
+use serde_json::{Value as Json, json};
+use std::io::{BufRead, Write};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+
+/// One client session: a runtime that keeps its variables between requests
+/// until a request asks for `reset`.
+pub struct Server {
+    runtime: Runtime,
+    output: CapturedOutput,
+    limits: Limits,
+}
+
+impl Server {
+    pub fn new(limits: Limits) -> Self {
+        let output = CapturedOutput::new();
+        let runtime = Self::fresh_runtime(&output, limits);
+        Server { runtime, output, limits }
+    }
+
+    /// Scripts run sandboxed: they may not run commands, write files or
+    /// change the server's environment, or build collections past the
+    /// sandbox caps. The timeout is left to `execute`.
+    fn fresh_runtime(output: &CapturedOutput, limits: Limits) -> Runtime {
+        let mut runtime = Runtime::new();
+        runtime.set_output(Box::new(output.clone()));
+        runtime.set_sandboxed(true);
+        runtime.set_limits(Limits { timeout: None, ..limits.with_sandbox_caps() });
+        runtime
+    }
+
+    /// Runs `code`, cancelling it from a watchdog thread once the timeout
+    /// passes. A cancelled run is reported as a timeout.
+    fn execute(&mut self, code: &str) -> Result<Value> {
+        let Some(timeout) = self.limits.timeout else {
+            return self.runtime.execute(code);
+        };
+        let cancellation = self.runtime.cancellation_token();
+        let (finished, done) = mpsc::channel::<()>();
+        let watchdog = {
+            let cancellation = cancellation.clone();
+            thread::spawn(move || {
+                let expired = done.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout);
+                if expired {
+                    cancellation.cancel();
+                }
+                expired
+            })
+        };
+        let outcome = self.runtime.execute(code);
+        let _ = finished.send(());
+        if !watchdog.join().unwrap_or(false) {
+            return outcome;
+        }
+        // The run may have finished just before the watchdog fired; the
+        // next request must not inherit the cancellation.
+        cancellation.reset();
+        match outcome {
+            Err(err)
+                if err.downcast_ref::<IqraError>().is_some_and(|e| e.kind == CANCELLED_ERROR_KIND) =>
+            {
+                Err(timeout_error(timeout))
+            }
+            outcome => outcome,
+        }
+    }
+
+    /// Answers one request line.
+    pub fn handle_line(&mut self, line: &str) -> Json {
+        let request: Json = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => {
+                return error_response(
+                    Json::Null,
+                    rpc_error("invalid_request", &format!("Invalid JSON: {}", e)),
+                );
+            }
+        };
+        let id = request.get("id").cloned().unwrap_or(Json::Null);
+        match request.get("method").and_then(Json::as_str) {
+            Some("eval") => {}
+            Some(other) => {
+                return error_response(
+                    id,
+                    rpc_error("unknown_method", &format!("Unknown method '{}'", other)),
+                );
+            }
+            None => return error_response(id, rpc_error("invalid_request", "Missing method")),
+        }
+        let params = request.get("params").cloned().unwrap_or(Json::Null);
+        let Some(code) = params.get("code").and_then(Json::as_str) else {
+            return error_response(id, rpc_error("invalid_params", "params.code must be a string"));
+        };
+        if params.get("reset").and_then(Json::as_bool).unwrap_or(false) {
+            self.runtime = Self::fresh_runtime(&self.output, self.limits);
+        }
+
+        let outcome = self.execute(code);
+        let stdout = self.output.take();
+        let stderr = self.output.take_errors();
+        let response = match outcome {
+            Ok(value) => json!({
+                "id": id,
+                "result": { "value": format!("{}", value), "type": value.type_name(), "stdout": stdout },
+            }),
+            Err(err) => match err.downcast_ref::<ExitSignal>() {
+                Some(ExitSignal(code)) => json!({
+                    "id": id,
+                    "result": { "value": "فارغ", "type": "nil", "stdout": stdout, "exit_code": code },
+                }),
+                None => {
+                    let mut error = match err.downcast_ref::<IqraError>() {
+                        Some(e) => json!({
+                            "kind": e.kind,
+                            "message_ar": e.message_ar,
+                            "message_en": e.message_en,
+                            "suggestion": e.suggestion,
+                            "line": e.line,
+                        }),
+                        None => rpc_error("internal", &err.to_string()),
+                    };
+                    error["stdout"] = json!(stdout);
+                    error_response(id, error)
+                }
+            },
+        };
+        with_stderr(response, stderr)
+    }
+}
+
+/// Adds what the script wrote to standard error next to its `stdout`, when
+/// it wrote anything.
+fn with_stderr(mut response: Json, stderr: String) -> Json {
+    if !stderr.is_empty() {
+        let body = if response.get("result").is_some() { "result" } else { "error" };
+        response[body]["stderr"] = json!(stderr);
+    }
+    response
+}
+
+fn rpc_error(kind: &str, message: &str) -> Json {
+    json!({ "kind": kind, "message_ar": message, "message_en": message, "suggestion": null, "line": null })
+}
+
+fn error_response(id: Json, error: Json) -> Json {
+    json!({ "id": id, "error": error })
+}
+
+/// Reads requests from `input` until it ends, writing one response per
+/// non-empty line to `output`.
+pub fn serve(input: impl BufRead, mut output: impl Write, limits: Limits) -> Result<()> {
+    let mut server = Server::new(limits);
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        writeln!(output, "{}", server.handle_line(&line))?;
+        output.flush()?;
+    }
+    Ok(())
+}
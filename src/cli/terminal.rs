@@ -0,0 +1,105 @@
+//! الطرفية | What the REPL shows around the user's text: the prompts, and the
+//! isolates that keep right-to-left text from reordering the line numbers,
+//! labels and prompts next to it. Everything here is pure; the environment
+//! is passed in as a lookup function.
+
+/// U+2066, which starts a left-to-right isolate.
+pub const LEFT_TO_RIGHT_ISOLATE: char = '\u{2066}';
+/// U+2069, which ends the innermost isolate.
+pub const POP_DIRECTIONAL_ISOLATE: char = '\u{2069}';
+
+/// Whether `text` contains a right-to-left letter: Arabic, Hebrew, Syriac,
+/// Thaana, N'Ko or one of their presentation forms.
+pub fn has_rtl(text: &str) -> bool {
+    text.chars().any(|ch| {
+        matches!(ch, '\u{0590}'..='\u{08FF}' | '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}')
+    })
+}
+
+/// Wraps `text` in U+2066 and U+2069 when it has right-to-left letters, so
+/// that a terminal lays it out as a single run and its direction doesn't
+/// leak into what is printed around it. Other text is returned unchanged.
+pub fn isolate(text: &str) -> String {
+    if has_rtl(text) {
+        format!("{}{}{}", LEFT_TO_RIGHT_ISOLATE, text, POP_DIRECTIONAL_ISOLATE)
+    } else {
+        text.to_string()
+    }
+}
+
+/// The prompts the REPL shows, one per mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prompts {
+    /// Before each line of code.
+    pub main: String,
+    /// In step-by-step mode.
+    pub step: String,
+    /// While the debugger is paused.
+    pub debug: String,
+    /// For each line of a program typed in for `خطوة` or `تصحيح`.
+    pub program: String,
+}
+
+impl Prompts {
+    pub fn arabic() -> Self {
+        Prompts {
+            main: "اقرأ> ".to_string(),
+            step: "اقرأ (خطوة)> ".to_string(),
+            debug: "اقرأ (تصحيح)> ".to_string(),
+            program: "أدخل البرنامج:".to_string(),
+        }
+    }
+
+    /// Prompts with no Arabic in them, for terminals that can't shape it.
+    pub fn ascii() -> Self {
+        Prompts {
+            main: "iqra> ".to_string(),
+            step: "iqra (step)> ".to_string(),
+            debug: "iqra (debug)> ".to_string(),
+            program: "program> ".to_string(),
+        }
+    }
+
+    /// `prompt` as given for code, with the mode in front of it for step and
+    /// debug mode. The mode names are in Arabic unless `ascii`.
+    pub fn custom(prompt: &str, ascii: bool) -> Self {
+        let base = if ascii { Prompts::ascii() } else { Prompts::arabic() };
+        let (step, debug) = if ascii { ("step", "debug") } else { ("خطوة", "تصحيح") };
+        Prompts {
+            main: prompt.to_string(),
+            step: format!("({}) {}", step, prompt),
+            debug: format!("({}) {}", debug, prompt),
+            program: base.program,
+        }
+    }
+}
+
+/// Picks the REPL prompts from `--prompt`, else `IQRA_PROMPT`, else from
+/// whether the terminal looks able to shape Arabic. Either setting may be
+/// `ascii` or `arabic` to choose a built-in set instead of giving the text.
+pub fn choose_prompts(flag: Option<&str>, env: impl Fn(&str) -> Option<String>) -> Prompts {
+    let shapes = shapes_arabic(&env);
+    let setting = flag.map(str::to_string).or_else(|| env("IQRA_PROMPT").filter(|p| !p.is_empty()));
+    match setting.as_deref() {
+        Some("ascii") => Prompts::ascii(),
+        Some("arabic") => Prompts::arabic(),
+        Some(prompt) => Prompts::custom(prompt, !shapes),
+        None if shapes => Prompts::arabic(),
+        None => Prompts::ascii(),
+    }
+}
+
+/// A guess at whether the terminal can show joined Arabic letters: the
+/// locale (`LC_ALL`, `LC_CTYPE` or `LANG`, whichever is set first) must be
+/// UTF-8, and `TERM` must not be the Linux console or a dumb terminal.
+pub fn shapes_arabic(env: impl Fn(&str) -> Option<String>) -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| env(name).filter(|value| !value.is_empty()));
+    let utf8 = locale.is_some_and(|locale| {
+        let locale = locale.to_ascii_lowercase();
+        locale.contains("utf-8") || locale.contains("utf8")
+    });
+    let term = env("TERM").unwrap_or_default();
+    utf8 && !matches!(term.as_str(), "linux" | "dumb" | "vt100" | "vt220")
+}
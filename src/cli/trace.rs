@@ -0,0 +1,124 @@
+//! وضع التتبع | `--trace`: prints each statement as it runs, then the
+//! variables it changed.
+//!
+//! The tracer is installed as the runtime's `on_statement` hook, which runs
+//! before a statement, so the changes made by one statement are printed when
+//! the next one is about to run, or by [`Tracer::finish`] after the last.
+
+use crate::cli::describe_change;
+use crate::lang::lexer::Span;
+use crate::lang::output::OutputSink;
+use crate::lang::parser::Stmt;
+use crate::lang::runtime::{DebugAction, Runtime};
+use crate::lang::stepper::{VariableChange, diff_variables};
+use crate::lang::value::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Default for `--trace-limit`.
+pub const DEFAULT_TRACE_LIMIT: usize = 1000;
+
+/// Formats the trace line for a statement: its line number and the first
+/// line of its source text, indented by call depth.
+pub fn format_statement(source: &str, span: Span, depth: usize) -> String {
+    let text = source.lines().nth(span.start_line.saturating_sub(1)).unwrap_or("").trim();
+    format!("{}[{}] {}\n", "  ".repeat(depth), span.start_line, text)
+}
+
+/// Formats the variables changed by a statement, one per line, below it.
+pub fn format_changes(changes: &[VariableChange], depth: usize) -> String {
+    changes
+        .iter()
+        .map(|change| format!("{}    {}\n", "  ".repeat(depth), describe_change(change)))
+        .collect()
+}
+
+struct TracerState {
+    source: String,
+    limit: usize,
+    traced: usize,
+    /// Variables as they were before the last traced statement, one entry per
+    /// call depth, so changes are only compared within the same function call.
+    snapshots: Vec<(usize, HashMap<String, Value>)>,
+    output: Box<dyn OutputSink>,
+}
+
+impl TracerState {
+    /// Prints what changed since the snapshot taken at `depth`, then records
+    /// the current variables as the new snapshot.
+    fn flush(&mut self, depth: usize, variables: &HashMap<String, Value>) {
+        while self.snapshots.last().is_some_and(|(d, _)| *d > depth) {
+            self.snapshots.pop();
+        }
+        match self.snapshots.last_mut() {
+            Some((d, snapshot)) if *d == depth => {
+                let changes = diff_variables(snapshot, variables);
+                if !changes.is_empty() {
+                    self.output.write(&format_changes(&changes, depth));
+                }
+                *snapshot = variables.clone();
+            }
+            _ => self.snapshots.push((depth, variables.clone())),
+        }
+    }
+}
+
+/// Cloning a `Tracer` shares its state, so the caller can keep a clone to call
+/// [`Tracer::finish`] after the program ends.
+#[derive(Clone)]
+pub struct Tracer {
+    state: Arc<Mutex<TracerState>>,
+}
+
+impl Tracer {
+    /// Creates a tracer for `source` that stops tracing after `limit` statements.
+    pub fn new(source: &str, limit: usize, output: Box<dyn OutputSink>) -> Self {
+        Tracer {
+            state: Arc::new(Mutex::new(TracerState {
+                source: source.to_string(),
+                limit,
+                traced: 0,
+                snapshots: Vec::new(),
+                output,
+            })),
+        }
+    }
+
+    /// Installs the tracer as `runtime`'s `on_statement` hook.
+    pub fn attach(&self, runtime: &mut Runtime) {
+        let tracer = self.clone();
+        runtime.hooks_mut().on_statement = Some(Box::new(move |stmt, runtime| {
+            tracer.trace(stmt, runtime);
+            DebugAction::Continue
+        }));
+    }
+
+    fn trace(&self, stmt: &Stmt, runtime: &Runtime) {
+        let mut state = self.state.lock().unwrap();
+        if state.traced > state.limit {
+            return;
+        }
+        let depth = runtime.call_depth();
+        state.flush(depth, &runtime.get_variables());
+        if state.traced == state.limit {
+            let limit = state.limit;
+            state.output.write(&format!(
+                "… توقف التتبع بعد {} جملة | trace limit of {} statements reached\n",
+                limit, limit
+            ));
+            state.traced += 1;
+            return;
+        }
+        state.traced += 1;
+        let line = format_statement(&state.source, stmt.span, depth);
+        state.output.write(&line);
+    }
+
+    /// Prints the changes made by the last traced statement.
+    pub fn finish(&self, runtime: &Runtime) {
+        let mut state = self.state.lock().unwrap();
+        if state.traced <= state.limit {
+            state.flush(runtime.call_depth(), &runtime.get_variables());
+        }
+    }
+}
@@ -0,0 +1,948 @@
+//! الرسائل | The catalog of messages the two binaries print.
+//!
+//! Each message has a key and an Arabic and an English template. Templates
+//! name their arguments in braces, such as `{path}`, and [`tr`] fills them in
+//! and picks the language set with `--lang`: one of the two, or both joined
+//! with `|`. The catalog is a plain static, so a missing translation is a test
+//! failure rather than something found at run time.
+
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// لغة الرسائل | Which language messages and errors are shown in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorLanguage {
+    Arabic,
+    English,
+    #[default]
+    Both,
+}
+
+static ERROR_LANGUAGE: AtomicU8 = AtomicU8::new(ErrorLanguage::Both as u8);
+
+impl ErrorLanguage {
+    /// The process-wide language, used by [`tr`] and when an `IqraError`
+    /// is displayed.
+    pub fn current() -> ErrorLanguage {
+        match ERROR_LANGUAGE.load(Ordering::Relaxed) {
+            0 => ErrorLanguage::Arabic,
+            1 => ErrorLanguage::English,
+            _ => ErrorLanguage::Both,
+        }
+    }
+
+    pub fn set_current(language: ErrorLanguage) {
+        ERROR_LANGUAGE.store(language as u8, Ordering::Relaxed);
+    }
+
+    /// Picks the Arabic text, the English text, or both joined with `|`.
+    pub fn pick(self, ar: &str, en: &str) -> String {
+        match self {
+            ErrorLanguage::Arabic => ar.to_string(),
+            ErrorLanguage::English => en.to_string(),
+            ErrorLanguage::Both => format!("{} | {}", ar, en),
+        }
+    }
+}
+
+impl std::str::FromStr for ErrorLanguage {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ar" | "arabic" | "عربي" => Ok(ErrorLanguage::Arabic),
+            "en" | "english" | "انجليزي" => Ok(ErrorLanguage::English),
+            "both" | "كلاهما" => Ok(ErrorLanguage::Both),
+            other => Err(format!(
+                "لغة غير معروفة: {} | Unknown language: {} (ar, en, both)",
+                other, other
+            )),
+        }
+    }
+}
+
+/// A value filled into a template. Anything `Display` is shown the same in
+/// both languages; a [`List`] is joined with the language's comma.
+pub trait Arg {
+    fn render(&self, language: ErrorLanguage) -> String;
+}
+
+impl<T: Display + ?Sized> Arg for T {
+    fn render(&self, _language: ErrorLanguage) -> String {
+        self.to_string()
+    }
+}
+
+/// Items joined with `، ` in Arabic and `, ` in English.
+pub struct List<'a, T>(pub &'a [T]);
+
+impl<T: Display> Arg for List<'_, T> {
+    fn render(&self, language: ErrorLanguage) -> String {
+        let separator = if language == ErrorLanguage::Arabic { "، " } else { ", " };
+        self.0.iter().map(|item| item.to_string()).collect::<Vec<_>>().join(separator)
+    }
+}
+
+/// A word with an Arabic and an English form, such as a keyword.
+pub struct Word<'a>(pub &'a str, pub &'a str);
+
+impl Arg for Word<'_> {
+    fn render(&self, language: ErrorLanguage) -> String {
+        let word = if language == ErrorLanguage::Arabic { self.0 } else { self.1 };
+        word.to_string()
+    }
+}
+
+/// One entry of [`MESSAGES`].
+#[derive(Debug)]
+pub struct Message {
+    pub key: &'static str,
+    pub ar: &'static str,
+    pub en: &'static str,
+}
+
+const fn m(key: &'static str, ar: &'static str, en: &'static str) -> Message {
+    Message { key, ar, en }
+}
+
+/// The message `key`, if the catalog has it.
+pub fn message(key: &str) -> Option<&'static Message> {
+    MESSAGES.iter().find(|message| message.key == key)
+}
+
+/// The names of the `{name}` placeholders in `template`.
+pub fn placeholders(template: &str) -> BTreeSet<&str> {
+    let mut names = BTreeSet::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('}') else { break };
+        let name = &rest[..end];
+        if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            names.insert(name);
+        }
+        rest = &rest[end + 1..];
+    }
+    names
+}
+
+fn fill(template: &str, args: &[(&str, &dyn Arg)], language: ErrorLanguage) -> String {
+    let mut out = template.to_string();
+    for (name, value) in args {
+        out = out.replace(&format!("{{{}}}", name), &value.render(language));
+    }
+    out
+}
+
+/// The message `key` in `language` with `args` filled in. An unknown key is
+/// returned as it is.
+pub fn tr_in(language: ErrorLanguage, key: &str, args: &[(&str, &dyn Arg)]) -> String {
+    let Some(message) = message(key) else {
+        debug_assert!(false, "unknown message key: {}", key);
+        return key.to_string();
+    };
+    match language {
+        ErrorLanguage::Arabic => fill(message.ar, args, ErrorLanguage::Arabic),
+        ErrorLanguage::English => fill(message.en, args, ErrorLanguage::English),
+        ErrorLanguage::Both => format!(
+            "{} | {}",
+            fill(message.ar, args, ErrorLanguage::Arabic),
+            fill(message.en, args, ErrorLanguage::English)
+        ),
+    }
+}
+
+/// The message `key` in the process-wide language.
+pub fn tr(key: &str, args: &[(&str, &dyn Arg)]) -> String {
+    tr_in(ErrorLanguage::current(), key, args)
+}
+
+/// `tr!("pkg.removed")` or `tr!("repl.loaded", path = path)`.
+#[macro_export]
+macro_rules! tr {
+    ($key:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::i18n::tr($key, &[$((stringify!($name), &$value as &dyn $crate::i18n::Arg)),*])
+    };
+}
+
+pub static MESSAGES: &[Message] = &[
+    // Labels, followed by `: ` and a value.
+    m("label.error", "خطأ", "Error"),
+    m("label.read_error", "خطأ في القراءة", "Read error"),
+    m("label.result", "الناتج", "Result"),
+    m("label.depth", "العمق", "depth"),
+    m("label.params", "المعاملات", "Parameters"),
+    m("label.was", "كان", "was"),
+    m("label.failed", "فشل", "failed"),
+    m("label.current_statement", "الجملة الحالية", "Current statement"),
+    // The REPL.
+    m("repl.welcome", "مرحباً بك في اقرأ", "Welcome to Iqra"),
+    m("repl.exit_hint", "اكتب 'خروج' أو 'exit' للخروج", "Type 'خروج' or 'exit' to quit"),
+    m(
+        "repl.step_hint",
+        "اكتب 'خطوة' أو 'step' لتفعيل التنفيذ التفاعلي",
+        "Type 'خطوة' or 'step' for interactive step-by-step mode",
+    ),
+    m(
+        "repl.debug_hint",
+        "اكتب 'تصحيح' أو 'debug' لتشغيل برنامج في المصحح",
+        "Type 'تصحيح' or 'debug' to run a program under the debugger",
+    ),
+    m("repl.goodbye", "وداعاً!", "Goodbye!"),
+    m("repl.exited", "انتهى البرنامج بالرمز {code}", "Program exited with code {code}"),
+    m("repl.text_length", "الطول {length}", "length {length}"),
+    m("repl.more_items", "… و {count} أخرى", "… and {count} more"),
+    m("repl.timing_on", "التوقيت مفعل", "Timing on"),
+    m("repl.timing_off", "التوقيت معطل", "Timing off"),
+    m("repl.results_cleared", "مُسحت النتائج المرقمة", "Numbered results cleared"),
+    m("repl.no_previous_result", "لا توجد نتيجة سابقة", "No previous result"),
+    m("repl.set", "تم الضبط: {setting}", "Set: {setting}"),
+    m("repl.interrupted", "تم المقاطعة", "Interrupted"),
+    m("repl.loaded", "تم تحميل: {path}", "Loaded: {path}"),
+    m("repl.session_saved", "حُفظت الجلسة في {path}", "Session saved to {path}"),
+    m("repl.session_restored", "استُرجعت الجلسة من {path}", "Session restored from {path}"),
+    m(
+        "repl.variables_not_saved",
+        "تحذير: لم تُحفظ المتغيرات: {names}",
+        "Warning: variables not saved: {names}",
+    ),
+    m("repl.step_enabled", "تم تفعيل وضع التنفيذ التفاعلي خطوة بخطوة!", "Step-by-step mode is on!"),
+    m(
+        "repl.step_enter",
+        "أدخل البرنامج كاملاً ثم سطراً فارغاً، ثم استخدم 'التالي' أو 'next' للتنفيذ خطوة خطوة.",
+        "Enter the whole program, then an empty line, then use 'التالي' or 'next' to run it a statement at a time.",
+    ),
+    m(
+        "repl.step_controls",
+        "اكتب 'متغيرات' أو 'vars' لعرض المتغيرات، 'إعادة' أو 'restart' لإعادة التنفيذ، 'إنهاء' أو 'exit' للخروج من الوضع.",
+        "Type 'متغيرات' or 'vars' to show the variables, 'إعادة' or 'restart' to start over, 'إنهاء' or 'exit' to leave step mode.",
+    ),
+    m(
+        "repl.step_usage",
+        "استخدم 'التالي' أو 'next' للتنفيذ، 'متغيرات' أو 'vars' للمتغيرات، 'إعادة' أو 'restart' لإعادة التنفيذ، 'إنهاء' أو 'exit' للخروج.",
+        "Use 'التالي' or 'next' to run, 'متغيرات' or 'vars' for the variables, 'إعادة' or 'restart' to start over, 'إنهاء' or 'exit' to leave.",
+    ),
+    m("repl.current_frame", "متغيرات الدالة الحالية:", "Current function's variables:"),
+    m("repl.globals", "المتغيرات العامة:", "Global variables:"),
+    m("repl.changed_variables", "المتغيرات المتغيرة:", "Changed variables:"),
+    m("repl.step_done", "تم تنفيذ جميع الجمل!", "All statements executed!"),
+    m("repl.step_restarted", "تمت إعادة التنفيذ.", "Execution restarted."),
+    m("repl.step_exited", "تم الخروج من وضع التنفيذ التفاعلي.", "Exited step mode."),
+    m(
+        "repl.debugger_intro",
+        "المصحح: أدخل البرنامج ثم سطراً فارغاً. الأوامر: تابع، ادخل، تخط، نقطة_توقف N، امسح [N]، نقاط، إنهاء",
+        "Debugger: enter the program, then an empty line. Commands: continue, step-in, step-over, break N, clear [N], breakpoints, quit",
+    ),
+    m("debug.unknown_command", "أمر غير معروف", "Unknown debugger command"),
+    m("debug.breakpoint_set", "نقطة توقف عند السطر {line}", "Breakpoint set at line {line}"),
+    m("debug.breakpoint_cleared", "أزيلت نقطة التوقف {line}", "Breakpoint {line} cleared"),
+    m("debug.no_breakpoint_at", "لا توجد نقطة توقف عند {line}", "No breakpoint at line {line}"),
+    m("debug.breakpoints_cleared", "أزيلت جميع نقاط التوقف", "All breakpoints cleared"),
+    m("debug.no_breakpoints", "لا توجد نقاط توقف", "No breakpoints"),
+    m("debug.breakpoints", "نقاط التوقف: {lines}", "Breakpoints: {lines}"),
+    // iqra doc.
+    m(
+        "doc.unknown_format",
+        "صيغة غير معروفة: {format} (md, json)",
+        "Unknown format: {format} (md, json)",
+    ),
+    // iqra-pkg usage.
+    m("pkg.usage.title", "أوامر مدير الحزم (iqra-pkg):", "Package manager commands (iqra-pkg):"),
+    m("pkg.usage.install", "تثبيت مكتبة أو أكثر", "install one or more packages"),
+    m("pkg.usage.update", "تحديث جميع المكتبات", "update every package"),
+    m(
+        "pkg.usage.jobs",
+        "عدد التنزيلات المتزامنة (الافتراضي {jobs})",
+        "downloads at once (default {jobs})",
+    ),
+    m("pkg.usage.list", "عرض المكتبات المثبتة", "list installed packages"),
+    m("pkg.usage.publish", "نشر مكتبتك", "publish your package"),
+    m(
+        "pkg.usage.remove",
+        "إزالة مكتبة مثبتة من هذا الجهاز فقط",
+        "remove an installed package from this machine only",
+    ),
+    m("pkg.usage.yank", "سحب إصدار منشور من السجل", "yank a published version from the registry"),
+    m("pkg.usage.needs_token", "يتطلب {var}", "needs {var}"),
+    m("pkg.usage.run", "تشغيل مكتبة", "run a package"),
+    m("pkg.usage.search", "البحث عن مكتبة", "search for a package"),
+    m("pkg.usage.help", "عرض المساعدة", "show this help"),
+    m(
+        "pkg.usage.global",
+        "مع install و update و remove و list: مكتبات IQRA_HOME/packages",
+        "with install, update, remove and list: the packages in IQRA_HOME/packages",
+    ),
+    m(
+        "pkg.usage.registry_dir",
+        "سجل في مجلد بدلاً من الشبكة، مثل IQRA_REGISTRY_URL=file://<dir>",
+        "a registry in a directory instead of the network, like IQRA_REGISTRY_URL=file://<dir>",
+    ),
+    m(
+        "pkg.usage.lang",
+        "لغة الرسائل: ar أو en أو both، مثل IQRA_LANG",
+        "language of messages: ar, en or both, like IQRA_LANG",
+    ),
+    // iqra-pkg output.
+    m("pkg.unknown_command", "أمر غير معروف", "Unknown command"),
+    m(
+        "pkg.no_home",
+        "تعذر تحديد مجلد المنزل، عيّن IQRA_HOME",
+        "Could not find the home directory; set IQRA_HOME",
+    ),
+    m("pkg.flag_needs_path", "{flag} يتطلب مساراً", "{flag} needs a path"),
+    m("pkg.flag_needs_value", "{flag} يتطلب قيمة", "{flag} needs a value"),
+    m("pkg.jobs_not_positive", "--jobs يتطلب عدداً موجباً", "--jobs needs a positive number"),
+    m("pkg.specify_package", "يرجى تحديد اسم المكتبة", "Please specify a package name"),
+    m("pkg.specify_publish", "يرجى تحديد اسم المكتبة للنشر", "Please specify a package to publish"),
+    m("pkg.specify_remove", "يرجى تحديد اسم المكتبة للإزالة", "Please specify a package to remove"),
+    m("pkg.specify_run", "يرجى تحديد اسم المكتبة للتشغيل", "Please specify a package to run"),
+    m(
+        "pkg.already_installed",
+        "المكتبة '{package}' مثبتة بالفعل",
+        "Package '{package}' already installed",
+    ),
+    m("pkg.installing", "تثبيت {packages} من السجل ...", "Installing {packages} from registry ..."),
+    m("pkg.install_failed", "فشل التحميل من السجل", "Failed to download from registry"),
+    m("pkg.installed", "تم التثبيت بنجاح", "Installed successfully"),
+    m(
+        "pkg.updating",
+        "تحديث جميع المكتبات من السجل ...",
+        "Updating all packages from registry ...",
+    ),
+    m("pkg.none_installed", "لا توجد مكتبات مثبتة", "No packages installed"),
+    m("pkg.update_failed", "فشل تحديث بعض المكتبات", "Some packages failed to update"),
+    m("pkg.updated", "تم التحديث بنجاح", "All packages updated successfully"),
+    m("pkg.not_found_locally", "المكتبة غير موجودة محلياً", "Package not found locally"),
+    m(
+        "pkg.dry_run",
+        "تجربة نشر '{package}' {version}",
+        "Dry run of publishing '{package}' {version}",
+    ),
+    m("pkg.files", "الملفات:", "Files:"),
+    m("pkg.metadata", "البيانات:", "Metadata:"),
+    m("pkg.nothing_uploaded", "لم يُرفع شيء", "Nothing was uploaded"),
+    m(
+        "pkg.publishing",
+        "نشر المكتبة '{package}' إلى السجل ...",
+        "Publishing package '{package}' to registry ...",
+    ),
+    m("pkg.published", "تم النشر بنجاح", "Published successfully"),
+    m("pkg.not_installed", "المكتبة غير مثبتة", "Package not installed"),
+    m(
+        "pkg.remove_failed",
+        "تعذرت إزالة '{package}': {error}",
+        "Could not remove '{package}': {error}",
+    ),
+    m("pkg.removed", "تمت الإزالة محلياً", "Removed locally"),
+    m(
+        "pkg.yank_needs_token",
+        "السحب يتطلب رمز السجل في {var}",
+        "Yanking needs a registry token in {var}",
+    ),
+    m(
+        "pkg.yank_confirm",
+        "سيُسحب '{package}' {version} من السجل للجميع. متابعة؟ [y/N]",
+        "This yanks '{package}' {version} from the registry for everyone. Continue? [y/N]",
+    ),
+    m("pkg.yank_cancelled", "أُلغي السحب", "Yank cancelled"),
+    m(
+        "pkg.yanked",
+        "سُحب '{package}' {version} من السجل",
+        "Yanked '{package}' {version} from the registry",
+    ),
+    m("pkg.searching", "البحث في السجل عن '{query}' ...", "Searching registry for '{query}' ..."),
+    m("pkg.search_results", "نتائج البحث:", "Search results:"),
+    m("pkg.run_failed", "فشل تشغيل '{package}': {error}", "Failed to run '{package}': {error}"),
+    m("pkg.none_installed_in", "لا توجد مكتبات مثبتة في {dir}", "No packages installed in {dir}"),
+    m("pkg.installed_in", "المكتبات المثبتة في {dir}:", "Packages installed in {dir}:"),
+    m("pkg.unknown_option", "خيار غير معروف: {option}", "Unknown option: {option}"),
+    m("pkg.unexpected_argument", "وسيط زائد: {arg}", "Unexpected argument: {arg}"),
+    m("pkg.flag_needs_count", "{flag} يتطلب عدداً موجباً", "{flag} needs a positive number"),
+    m(
+        "pkg.flag_needs_version",
+        "{flag} يتطلب إصداراً مثل 1.2.0",
+        "{flag} needs a version such as 1.2.0",
+    ),
+    m("pkg.specify_query", "يرجى تحديد كلمة البحث", "Please specify a search query"),
+    m("pkg.specify_yank", "يرجى تحديد اسم المكتبة للسحب", "Please specify a package to yank"),
+    m(
+        "pkg.specify_yank_version",
+        "حدد الإصدار المراد سحبه: yank {package} --version X.Y.Z",
+        "Name the version to yank: yank {package} --version X.Y.Z",
+    ),
+    m("pkg.invalid_response", "رد السجل غير صالح: {error}", "Invalid registry response: {error}"),
+    m("pkg.no_results", "لا توجد نتائج", "No results found"),
+    // Column headings of the search results and the download summary.
+    m("pkg.column.name", "الاسم", "Name"),
+    m("pkg.column.version", "الإصدار", "Version"),
+    m("pkg.column.author", "المؤلف", "Author"),
+    m("pkg.column.downloads", "التنزيلات", "Downloads"),
+    m("pkg.column.description", "الوصف", "Description"),
+    m("pkg.column.package", "الحزمة", "Package"),
+    m("pkg.column.result", "النتيجة", "Result"),
+    m("pkg.download_ok", "تم", "ok"),
+    m(
+        "pkg.download_counts",
+        "نجح {succeeded}، فشل {failed}",
+        "{succeeded} succeeded, {failed} failed",
+    ),
+    m(
+        "pkg.invalid_manifest",
+        "بيان الحزمة غير صالح {path}: {error}",
+        "Invalid package manifest {path}: {error}",
+    ),
+    m(
+        "pkg.manifest_needs_version",
+        "أضف \"version\" إلى {manifest} قبل النشر",
+        "Add a \"version\" to {manifest} before publishing",
+    ),
+    m(
+        "pkg.invalid_version",
+        "الإصدار '{version}' ليس بصيغة X.Y.Z",
+        "Version '{version}' is not of the form X.Y.Z",
+    ),
+    m("pkg.manifest_declares", "ما يعلنه بيان الحزمة:", "The package manifest declares:"),
+    m("pkg.not_installed_at", "الحزمة غير مثبتة: {path}", "Package not installed: {path}"),
+    m("pkg.no_script", "لا يوجد سكربت باسم '{name}'", "No script named '{name}'"),
+    m("pkg.entry_not_found", "ملف الدخول غير موجود: {path}", "Entry file not found: {path}"),
+    m(
+        "pkg.already_published",
+        "الإصدار {version} منشور بالفعل (آخر إصدار {latest})، ارفع رقم الإصدار في {manifest} أو استخدم --force",
+        "Version {version} is already published (latest is {latest}); bump the version in {manifest} or use --force",
+    ),
+    m(
+        "pkg.version_replaced",
+        "تحذير: سيُستبدل الإصدار {version}",
+        "Warning: version {version} will be replaced",
+    ),
+    m(
+        "pkg.version_older",
+        "تحذير: الإصدار {version} أقدم من آخر إصدار {latest}",
+        "Warning: version {version} is older than the latest, {latest}",
+    ),
+    m("pkg.path_too_long", "المسار أطول من ١٠٠ بايت: {path}", "Path longer than 100 bytes: {path}"),
+    // Package registries.
+    m("registry.connection_error", "خطأ في الاتصال بالسجل", "Registry connection error"),
+    m(
+        "registry.incomplete_download",
+        "التنزيل غير مكتمل: وصل {received} من {expected} بايت",
+        "Incomplete download: got {received} of {expected} bytes",
+    ),
+    m("registry.download_interrupted", "انقطع التنزيل", "The download was interrupted"),
+    m(
+        "registry.checksum_mismatch",
+        "بصمة SHA-256 لا تطابق: المتوقعة sha256:{expected} والفعلية sha256:{actual}، لم تُثبَّت الحزمة",
+        "SHA-256 mismatch: expected sha256:{expected}, got sha256:{actual}; the package was not installed",
+    ),
+    m("registry.search_failed", "فشل البحث في السجل: {status}", "Registry search failed: {status}"),
+    m("registry.publish_failed", "فشل النشر: {status}", "Publish failed: {status}"),
+    m(
+        "registry.yank_failed",
+        "فشل السحب من السجل: {status}",
+        "Failed to yank from registry: {status}",
+    ),
+    m("registry.invalid_index", "فهرس غير صالح {path}: {error}", "Invalid index {path}: {error}"),
+    m(
+        "registry.read_only",
+        "السجل في المجلد {dir} للقراءة فقط",
+        "The registry in {dir} is read-only",
+    ),
+    m(
+        "registry.not_found",
+        "المكتبة غير موجودة في السجل: {package}",
+        "Package not found in the registry: {package}",
+    ),
+    m("registry.read_failed", "تعذرت قراءة {path}: {error}", "Could not read {path}: {error}"),
+    m(
+        "registry.read_registry_failed",
+        "تعذرت قراءة السجل {dir}: {error}",
+        "Could not read the registry {dir}: {error}",
+    ),
+    m("registry.invalid_gzip", "أرشيف gzip غير صالح", "Invalid gzip archive"),
+    m("registry.invalid_tar", "أرشيف tar غير صالح", "Invalid tar archive"),
+    m("registry.unsafe_path", "مسار غير آمن في الأرشيف: {path}", "Unsafe path in archive: {path}"),
+    // Running a script inside a project.
+    m(
+        "run.missing_dependencies",
+        "مكتبات يحتاجها {manifest} غير مثبتة: {packages}",
+        "Packages {manifest} depends on are not installed: {packages}",
+    ),
+    m(
+        "run.install_dependencies",
+        "نفّذ: {command} (في {dir})، أو شغّل مع --auto-install",
+        "Run: {command} (in {dir}), or run with --auto-install",
+    ),
+    // Deprecated builtin names, keyed by `BuiltinAlias::deprecation`.
+    m(
+        "warn.deprecated_list_len",
+        "تحذير: استخدم {target} بدلاً من {name} ({name} تعدّ القوائم فقط، و{target} تعدّ النصوص والقواميس أيضاً)",
+        "Warning: use {target} instead of {name} ({name} only counts lists; {target} also counts strings and maps)",
+    ),
+    // Suggestions attached to runtime errors.
+    m("suggest.did_you_mean", "هل قصدت: {names}؟", "Did you mean: {names}?"),
+    m(
+        "suggest.define_variable",
+        "تأكد من تعريف المتغير قبل استخدامه",
+        "Make sure the variable is defined before it is used",
+    ),
+    m(
+        "suggest.check_function_name",
+        "تأكد من كتابة اسم الدالة بشكل صحيح",
+        "Check the spelling of the function name",
+    ),
+    m("suggest.numbers_only", "استخدم أرقام فقط", "Use numbers only"),
+    m(
+        "suggest.chained_comparison",
+        "استخدم: {a} {first} {b} و {b} {second} {c}",
+        "Use: {a} {first} {b} and {b} {second} {c}",
+    ),
+    m(
+        "suggest.chained_comparison_between",
+        "استخدم: {a} {first} {b} و {b} {second} {c}، أو بين؟({b}، {from}، {to})",
+        "Use: {a} {first} {b} and {b} {second} {c}, or between({b}, {from}, {to})",
+    ),
+    m(
+        "suggest.compare_pairs",
+        "قارن كل طرفين على حدة واربط المقارنتين بـ و، أو استخدم بين؟",
+        "Compare each pair on its own and join the two with and, or use between",
+    ),
+    m("suggest.all_numbers", "تأكد أن جميع العناصر أرقام", "Make sure every element is a number"),
+    m("suggest.text_only", "استخدم نصاً فقط", "Use a string only"),
+    m("suggest.texts_only", "استخدم نصوصاً فقط", "Use strings only"),
+    m("suggest.two_texts", "استخدم نصين فقط", "Use two strings only"),
+    m("suggest.list_only", "استخدم قائمة فقط", "Use a list only"),
+    m("suggest.one_list", "استخدم قائمة واحدة فقط", "Pass a single list only"),
+    m("suggest.one_value", "استخدم قيمة واحدة فقط", "Pass a single value only"),
+    m("suggest.text_or_list", "استخدم نصاً أو قائمة فقط", "Use a string or a list only"),
+    m(
+        "suggest.one_text_or_list",
+        "استخدم نصاً أو قائمة واحدة فقط",
+        "Pass a single string or list only",
+    ),
+    m("suggest.non_empty_list", "استخدم قائمة فيها عناصر", "Use a list that has elements"),
+    m(
+        "suggest.first_is_list",
+        "تأكد أن الوسيط الأول هو قائمة",
+        "Make sure the first argument is a list",
+    ),
+    m("suggest.dict_and_key", "استخدم قاموس ومفتاح", "Use a dictionary and a key"),
+    m(
+        "suggest.dict_and_text",
+        "تأكد أن الوسيط الأول قاموس والثاني نص",
+        "Make sure the first argument is a dictionary and the second a string",
+    ),
+    m("suggest.suitable_type", "استخدم نوعًا مناسبًا", "Use a suitable type"),
+    m(
+        "suggest.nonzero_divisor",
+        "تأكد أن المقسوم عليه ليس صفراً",
+        "Make sure the divisor is not zero",
+    ),
+    m("suggest.path_text", "استخدم نصاً يمثل المسار", "Use a string holding the path"),
+    m(
+        "suggest.read_access",
+        "تأكد من صحة المسار وصلاحيات القراءة",
+        "Check the path and the read permissions",
+    ),
+    m(
+        "suggest.write_access",
+        "تأكد من صحة المسار وصلاحيات الكتابة",
+        "Check the path and the write permissions",
+    ),
+    m(
+        "suggest.command_access",
+        "تأكد من صحة الأمر والمدخل وصلاحيات التنفيذ",
+        "Check the command, its input and the execute permissions",
+    ),
+    m("suggest.did_you_mean_name", "هل تقصد '{name}'؟", "Did you mean '{name}'?"),
+    m("suggest.single_equals", "هل قصدت =؟", "Did you mean =?"),
+    m(
+        "suggest.builtin_escape",
+        "استدعِ الدالة المدمجة بـ مدمج.{name}(...)",
+        "Call the builtin as builtin.{name}(...)",
+    ),
+    m(
+        "suggest.write_comparison",
+        "اكتب مقارنة بدلاً من قيمة ثابتة",
+        "Write a comparison instead of a fixed value",
+    ),
+    m(
+        "suggest.compare_text",
+        "قارن صراحة مثل {name} != \"\" أو استخدم إلى_منطقي({name})",
+        "Compare explicitly, as in {name} != \"\", or use to_bool({name})",
+    ),
+    m(
+        "suggest.compare_list",
+        "قارن صراحة مثل طول({name}) > ٠ أو استخدم إلى_منطقي({name})",
+        "Compare explicitly, as in len({name}) > 0, or use to_bool({name})",
+    ),
+    m(
+        "suggest.help_lists_builtins",
+        "مساعدة() تعرض كل الدوال المدمجة",
+        "help() lists every builtin",
+    ),
+    m("suggest.example_pad", "مثال: {name}(\"اسم\"، ١٠)", "Example: {name}(\"name\", 10)"),
+    m(
+        "suggest.inside_loop",
+        "ضع {keyword} داخل بينما أو افعل",
+        "Put {keyword} inside a while or do loop",
+    ),
+    m("suggest.one_of", "استخدم أحد: {names}", "Use one of: {names}"),
+    m(
+        "suggest.options",
+        "الخيارات: {names}، وقيمها صحيح أو خطأ",
+        "Options: {names}, each true or false",
+    ),
+    m("suggest.encodings", "الترميزات المدعومة: {names}", "Supported encodings: {names}"),
+    m(
+        "suggest.relative_paths",
+        "المسارات النسبية تُقرأ من مجلد العمل الحالي {cwd}",
+        "Relative paths are resolved from the working directory {cwd}",
+    ),
+    m("suggest.endless_loop", "تحقق من وجود حلقة لا تنتهي", "Check for a loop that never ends"),
+    m(
+        "suggest.decimal_point",
+        "أضف فاصلة عشرية لاستخدام رقم عشري تقريبي",
+        "Add a decimal point to use an approximate decimal number",
+    ),
+    m("suggest.check_number", "تأكد من صحة الرقم المدخل", "Check the input number"),
+    m("suggest.close_string", "تأكد من إغلاق السلسلة بعلامة اقتباس", "Close the string with a quote"),
+    m(
+        "suggest.unicode_escape",
+        "اكتب الرمز بالست عشري بين قوسين، مثل \\u{0623}",
+        "Write the code point in hex inside braces, like \\u{0623}",
+    ),
+    m("suggest.check_code", "تأكد من صحة الكود", "Check your code"),
+    m(
+        "suggest.index_in_bounds",
+        "تأكد من أن الفهرس ضمن حدود القائمة",
+        "Make sure the index is within the list",
+    ),
+    m(
+        "suggest.whole_index",
+        "استخدم عدداً صحيحاً من الصفر فما فوق",
+        "Use a whole number from zero upward",
+    ),
+    m("suggest.key_exists", "تأكد من وجود المفتاح في القاموس", "Make sure the key is in the map"),
+    m(
+        "suggest.byte_index",
+        "تأكد من أن الفهرس أصغر من عدد البايتات",
+        "Make sure the index is less than the number of bytes",
+    ),
+    m(
+        "suggest.indexable",
+        "استخدم قائمة أو قاموس مع فهرس مناسب",
+        "Use a list or a map with a suitable index",
+    ),
+    m("suggest.smaller_pieces", "قسّم العمل إلى أجزاء أصغر", "Split the work into smaller pieces"),
+    m(
+        "suggest.fill_character",
+        "استخدم حرفاً واحداً مثل \".\" أو \"-\"",
+        "Use one character such as \".\" or \"-\"",
+    ),
+    m("suggest.make_set", "أنشئ المجموعة بـ مجموعة(...)", "Create one with set(...)"),
+    m(
+        "suggest.session_file",
+        "استخدم ملفاً حفظته :احفظ_الجلسة أو احفظ_الحالة",
+        "Use a file written by :save-session or save_state",
+    ),
+    m(
+        "suggest.use_decimal",
+        "استخدم رقماً عشرياً (مثل ١٫٠) للحسابات الكبيرة التقريبية",
+        "Use a decimal number (such as 1.0) for large approximate calculations",
+    ),
+    m(
+        "suggest.example_normalize",
+        "مثال: طبع_نص(نص، قاموس(\"تاء_مربوطة\"، خطأ))",
+        "Example: normalize_arabic(text, map(\"teh_marbuta\", false))",
+    ),
+    m(
+        "suggest.hijri_date",
+        "اكتب التاريخ بالصيغة YYYY-MM-DD، بعد ١ محرم ١ هـ (١٩ يوليو ٦٢٢م)",
+        "Write the date as YYYY-MM-DD, after 1 Muharram 1 AH (19 July 622)",
+    ),
+    m("suggest.use_utf8", "استخدم ترميز utf-8", "Use utf-8"),
+    m(
+        "suggest.guess_encoding",
+        "جرّب خمّن_الترميز، أو ترميز windows-1256 للملفات العربية القديمة",
+        "Try detect_encoding, or windows-1256 for older Arabic files",
+    ),
+    m(
+        "suggest.sample_variance",
+        "تباين العينة يحتاج قيمتين على الأقل؛ أو احسب تباين المجتمع",
+        "A sample variance needs at least two values; or compute the population variance",
+    ),
+    m(
+        "suggest.format_template",
+        "استخدم {} أو {0} أو {:.2}، واكتب {{ و}} للأقواس نفسها",
+        "Use {}, {0} or {:.2}, and write {{ and }} for the braces themselves",
+    ),
+    m(
+        "suggest.argument_count",
+        "تأكد من عدد الوسائط المدخلة",
+        "Check the number of arguments passed",
+    ),
+    m(
+        "suggest.check_function_body",
+        "راجع الكود داخل الدالة",
+        "Check the code inside the function",
+    ),
+    m(
+        "suggest.leave_sandbox",
+        "شغّل البرنامج دون --sandbox إذا كنت تثق به",
+        "Run the program without --sandbox if you trust it",
+    ),
+    m("suggest.set_iqra_home", "عيّن المتغير IQRA_HOME", "Set the IQRA_HOME variable"),
+    m("suggest.read_permission", "تأكد من صلاحيات القراءة", "Check the read permissions"),
+    m("suggest.pattern_syntax", "راجع صياغة النمط", "Check the pattern syntax"),
+    m("suggest.return_in_function", "ضع ارجع داخل جسم دالة", "Put return inside a function body"),
+    m(
+        "suggest.match_value_count",
+        "اجعل عدد القيم على اليمين مساوياً لعدد المتغيرات على اليسار",
+        "Give as many values on the right as there are variables on the left",
+    ),
+    m(
+        "suggest.constant_name",
+        "استخدم اسماً آخر، أو عرّفه متغيراً عادياً بدون ثابت",
+        "Use another name, or define it as a plain variable without const",
+    ),
+    m(
+        "suggest.numbers_or_texts",
+        "تأكد أن الطرفين أرقام أو نصوص",
+        "Make sure both sides are numbers or strings",
+    ),
+    m("suggest.number_only", "استخدم رقماً فقط", "Use a number only"),
+    m(
+        "suggest.example_parallel_map",
+        "مثال: خريطة_متوازية([1، 2، 3]، \"مربع\")",
+        "Example: parallel_map([1, 2, 3], \"square\")",
+    ),
+    m("suggest.list_and_index", "استخدم قائمة وفهرس", "Use a list and an index"),
+    m(
+        "suggest.list_and_search_value",
+        "استخدم قائمة وقيمة للبحث",
+        "Use a list and a value to look for",
+    ),
+    m(
+        "suggest.example_print_sep",
+        "مثال: اطبع_مفصول(\"، \"، ١، ٢)",
+        "Example: print_sep(\", \", 1, 2)",
+    ),
+    m("suggest.list_and_new_value", "استخدم قائمة وقيمة للإضافة", "Use a list and a value to add"),
+    m("suggest.list_and_old_value", "استخدم قائمة وقيمة للحذف", "Use a list and a value to remove"),
+    m("suggest.key_value_pairs", "استخدم أزواج مفتاح/قيمة", "Use key/value pairs"),
+    m("suggest.text_keys", "تأكد أن جميع المفاتيح نصوص", "Make sure every key is a string"),
+    m("suggest.map_key_value", "استخدم قاموس، مفتاح، وقيمة", "Use a map, a key and a value"),
+    m(
+        "suggest.invert_duplicates",
+        "مرر صحيح لمعرفة المفاتيح المتكررة",
+        "Pass true to find the repeated keys",
+    ),
+    m("suggest.example_enumerate", "مثال: رقّم(قائمة، ١)", "Example: enumerate(list, 1)"),
+    m(
+        "suggest.example_group_by",
+        "مثال: جمّع_حسب(طلاب، \"صف\")",
+        "Example: group_by(students, \"class\")",
+    ),
+    m(
+        "suggest.field_in_each",
+        "تأكد أن كل عنصر قاموس يحتوي الحقل",
+        "Make sure every element is a map with the field",
+    ),
+    m("suggest.example_tally_top", "مثال: افرز_قيم(كلمات، ٣)", "Example: tally_top(words, 3)"),
+    m("suggest.file_exists", "تأكد من وجود الملف", "Make sure the file exists"),
+    m(
+        "suggest.example_cache_set",
+        "مثال: خزّن(\"مفتاح\"، قيمة)",
+        "Example: cache_set(\"key\", value)",
+    ),
+    m(
+        "suggest.example_cache_ttl",
+        "مثال: خزّن(\"مفتاح\"، قيمة، ٦٠)",
+        "Example: cache_set(\"key\", value, 60)",
+    ),
+    m(
+        "suggest.example_strict_number",
+        "مثال: إلى_رقم(\"١٢\"، صحيح)",
+        "Example: to_number(\"12\", true)",
+    ),
+    m(
+        "suggest.valid_number_text",
+        "تأكد أن النص يمثل رقماً صحيحاً",
+        "Make sure the string is a valid number",
+    ),
+    m("suggest.text_or_number", "استخدم نصاً أو رقماً فقط", "Use a string or a number only"),
+    m(
+        "suggest.sample_or_population",
+        "مرر صحيح لعينة، أو خطأ للمجتمع",
+        "Pass true for a sample, or false for a population",
+    ),
+    m("suggest.example_percentile", "مثال: مئين(درجات، ٩٠)", "Example: percentile(grades, 90)"),
+    m(
+        "suggest.example_reshape",
+        "مثال: أعد_تشكيل([١، ٢، ٣، ٤]، ٢، ٢)",
+        "Example: reshape([1, 2, 3, 4], 2, 2)",
+    ),
+    m("suggest.one_text", "استخدم نصاً واحداً فقط", "Pass a single string only"),
+    m(
+        "suggest.example_format_number",
+        "مثال: نسق_رقم(١٢٣٤٫٥، ٢، \"،\")",
+        "Example: format_number(1234.5, 2, \",\")",
+    ),
+    m(
+        "suggest.separator_text",
+        "استخدم نصاً مثل \"،\" أو \",\"",
+        "Use a string such as \"،\" or \",\"",
+    ),
+    m(
+        "suggest.example_format",
+        "مثال: نسق(\"{} من {}\"، ٣، ٥)",
+        "Example: format(\"{} of {}\", 3, 5)",
+    ),
+    m("suggest.example_repeat", "مثال: كرر_نص(\"-\"، ١٠)", "Example: repeat(\"-\", 10)"),
+    m(
+        "suggest.digit_sets",
+        "استخدم \"عربي\"/\"arabic\" أو \"لاتيني\"/\"ascii\"",
+        "Use \"عربي\"/\"arabic\" or \"لاتيني\"/\"ascii\"",
+    ),
+    m(
+        "suggest.code_point",
+        "استخدم عدداً صحيحاً بين ٠ و ١١١٤١١١ خارج نطاق D800–DFFF",
+        "Use a whole number from 0 to 1114111 outside D800–DFFF",
+    ),
+    m(
+        "suggest.base64_text",
+        "تأكد أن النص ناتج عن ترميز٦٤",
+        "Make sure the string came from base64_encode",
+    ),
+    m("suggest.make_bytes", "استخدم نص_إلى_بايتات أو اقرأ_بايتات", "Use to_bytes or read_bytes"),
+    m("suggest.example_hijri_month", "مثال: اسم_الشهر_الهجري(٩)", "Example: hijri_month_name(9)"),
+    m("suggest.command_text", "استخدم نصاً يمثل الأمر", "Use a string holding the command"),
+    m(
+        "suggest.command_permission",
+        "تأكد من صحة الأمر وصلاحيات التنفيذ",
+        "Check the command and the execute permissions",
+    ),
+    m(
+        "suggest.command_and_input",
+        "استخدم نصين: الأمر والمدخل",
+        "Use two strings: the command and its input",
+    ),
+    m(
+        "suggest.path_and_content",
+        "استخدم نصين: المسار والمحتوى",
+        "Use two strings: the path and the content",
+    ),
+    m(
+        "suggest.variable_name_text",
+        "استخدم نصاً يمثل اسم المتغير",
+        "Use a string holding the variable name",
+    ),
+    m(
+        "suggest.env_name",
+        "استخدم اسماً بلا '=' وقيمة نصية",
+        "Use a name without '=' and a string value",
+    ),
+    m(
+        "suggest.directory_access",
+        "تأكد من وجود المجلد وصلاحيات الوصول",
+        "Make sure the directory exists and can be accessed",
+    ),
+    m("suggest.exit_code_range", "استخدم رقماً من ٠ إلى ٢٥٥", "Use a number from 0 to 255"),
+    m("suggest.example_exit", "مثال: اخرج(١)", "Example: exit(1)"),
+    m(
+        "suggest.example_choose",
+        "مثال: اختر(\"اللون؟\"، [\"أحمر\"، \"أزرق\"])",
+        "Example: choose(\"Colour?\", [\"red\", \"blue\"])",
+    ),
+    m(
+        "suggest.refresh_flag",
+        "استخدم صحيح لتجاهل النسخة المحفوظة",
+        "Pass true to skip the cached copy",
+    ),
+    m("suggest.system_permission", "تأكد من صلاحيات النظام", "Check the system permissions"),
+    m(
+        "suggest.valid_number_string",
+        "تأكد من أن السلسلة تمثل رقمًا صالحًا",
+        "Ensure the string is a valid number",
+    ),
+    m(
+        "suggest.set_members",
+        "عناصر المجموعة نصوص أو أرقام أو قيم منطقية",
+        "Set members are strings, numbers or booleans",
+    ),
+    m("suggest.example_path", "مثال على مسار: a.b[2].c", "Example path: a.b[2].c"),
+    m(
+        "suggest.add_break",
+        "أضف توقف حيث يجب أن تنتهي الحلقة",
+        "Add a break where the loop should end",
+    ),
+    m(
+        "suggest.split_expression",
+        "قسّم التعبير إلى متغيرات أو دوال أصغر",
+        "Split the expression into smaller variables or functions",
+    ),
+    m("suggest.check_brackets", "راجع بناء الجملة أو الأقواس", "Check the syntax or the brackets"),
+    m("suggest.brace_after_try", "استخدم قوس الفتح بعد جرب/try", "Put an opening brace after try"),
+    m("suggest.catch_after_try", "استخدم امسك/catch بعد جرب/try", "Put catch after try"),
+    m(
+        "suggest.example_catch_kind",
+        "مثال: امسك أخطاء \"قسمة على صفر\" { ... }",
+        "Example: catch errors \"قسمة على صفر\" { ... }",
+    ),
+    m("suggest.error_variable", "اكتب اسم متغير بعد (", "Write a variable name after ("),
+    m(
+        "suggest.close_error_variable",
+        "استخدم قوس الإغلاق بعد اسم المتغير",
+        "Put a closing parenthesis after the variable name",
+    ),
+    m(
+        "suggest.open_block",
+        "استخدم قوس الفتح لبدء الكتلة",
+        "Use an opening brace to start the block",
+    ),
+    m(
+        "suggest.function_name",
+        "اكتب اسم الدالة مباشرة بعد الكلمة المفتاحية",
+        "Write the function name right after the keyword",
+    ),
+    m(
+        "suggest.rest_last",
+        "ضع ...الاسم في نهاية قائمة المعاملات",
+        "Put ...name at the end of the parameter list",
+    ),
+    m("suggest.example_rest", "اكتب مثلاً: ...قيم", "For example: ...values"),
+    m(
+        "suggest.defaults_last",
+        "ضع المعاملات ذات القيم الافتراضية في نهاية القائمة",
+        "Put the parameters with defaults at the end of the list",
+    ),
+    m("suggest.parameter_names", "استخدم أسماء معاملات صحيحة", "Use valid parameter names"),
+    m("suggest.example_multi_assign", "اكتب: أ، ب = ١، ٢", "Write: a, b = 1, 2"),
+    m("suggest.unmatched_paren", "احذف ) أو أضف ( قبله", "Remove the ) or add its ("),
+    m("suggest.finish_condition", "أكمل الشرط قبل {", "Finish the condition before {"),
+    m("suggest.double_equals", "هل قصدت ==؟", "Did you mean ==?"),
+    m("suggest.example_const", "اكتب: ثابت الاسم = القيمة", "Write: const name = value"),
+    m(
+        "suggest.cases_then_default",
+        "اكتب الحالات أولاً ثم 'افتراضي' مرة واحدة في النهاية",
+        "Write the cases first, then 'default' once at the end",
+    ),
+    m(
+        "suggest.call_by_name",
+        "احفظ النتيجة في متغير أو استدع الدالة باسمها",
+        "Store the result in a variable, or call the function by its name",
+    ),
+    m("suggest.example_builtin_escape", "اكتب: مدمج.اطبع(س)", "Write: builtin.print(x)"),
+    m(
+        "suggest.check_syntax",
+        "راجع بناء الجملة أو الرموز المستخدمة",
+        "Check the syntax or the symbols used",
+    ),
+    m("suggest.missing_paren", "أضف ) الناقص", "Add the missing )"),
+    m(
+        "suggest.display_settings",
+        "الإعدادات: أقصى_عناصر، أقصى_حروف، أقصى_عمق",
+        "Settings: max-items, max-chars, max-depth",
+    ),
+];
@@ -0,0 +1,561 @@
+//! التحليل الثابت | Post-parse checks that warn about likely mistakes.
+//!
+//! The analyzer walks the parsed program without running it and reports
+//! variables that are never read, variables read before any assignment,
+//! functions that shadow builtins, loops that can never end, strings and
+//! lists used directly as conditions, and names that are never defined (with
+//! a "did you mean" suggestion when a known name is close enough).
+//!
+//! A `// تجاهل: <code>` or `// allow: <code>` comment on a warning's line or
+//! the line above silences it, with the codes of [`WarningKind::code`].
+
+use crate::lang::lexer::Comment;
+use crate::lang::parser::{BinaryOp, Expr, ExprKind, Stmt, StmtKind, UnaryOp};
+use crate::lang::runtime::{BUILTIN_FUNCTIONS, ErrorLanguage, canonical_builtin};
+use crate::lang::symbol::Symbol;
+use crate::tr;
+use crate::lang::value::Value;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WarningKind {
+    UnusedVariable,
+    UseBeforeAssignment,
+    UndefinedVariable,
+    UndefinedFunction,
+    ShadowedBuiltin,
+    UnusedComparison,
+    EndlessLoop,
+    /// A string or list used as an `اذا`/`بينما` condition, which is true
+    /// whenever it isn't empty.
+    TruthyCondition,
+}
+
+impl WarningKind {
+    /// The name a `// تجاهل:` comment uses for the warning.
+    pub fn code(self) -> &'static str {
+        match self {
+            WarningKind::UnusedVariable => "unused-variable",
+            WarningKind::UseBeforeAssignment => "use-before-assignment",
+            WarningKind::UndefinedVariable => "undefined-variable",
+            WarningKind::UndefinedFunction => "undefined-function",
+            WarningKind::ShadowedBuiltin => "shadowed-builtin",
+            WarningKind::UnusedComparison => "unused-comparison",
+            WarningKind::EndlessLoop => "endless-loop",
+            WarningKind::TruthyCondition => "truthy-condition",
+        }
+    }
+}
+
+/// تحذير | A warning about a likely mistake. Warnings never stop execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub name: String,
+    pub line: usize,
+    pub suggestion: Option<String>,
+}
+
+impl Warning {
+    pub fn message_ar(&self) -> String {
+        match self.kind {
+            WarningKind::UnusedVariable => format!("المتغير '{}' عُيّن ولم يُستخدم", self.name),
+            WarningKind::UseBeforeAssignment => {
+                format!("المتغير '{}' مستخدم قبل تعيينه", self.name)
+            }
+            WarningKind::UndefinedVariable => format!("المتغير '{}' غير معرف", self.name),
+            WarningKind::UndefinedFunction => format!("الدالة '{}' غير معرفة", self.name),
+            WarningKind::ShadowedBuiltin => {
+                format!("الدالة '{}' تخفي الدالة المدمجة بنفس الاسم", self.name)
+            }
+            WarningKind::UnusedComparison => {
+                format!("نتيجة المقارنة مع '{}' غير مستخدمة", self.name)
+            }
+            WarningKind::EndlessLoop => {
+                format!("الحلقة '{}' لا تحتوي على توقف أو ارجع فلن تنتهي", self.name)
+            }
+            WarningKind::TruthyCondition => {
+                format!("'{}' نص أو قائمة في موضع الشرط، فيكون صحيحاً ما لم يكن فارغاً", self.name)
+            }
+        }
+    }
+
+    pub fn message_en(&self) -> String {
+        match self.kind {
+            WarningKind::UnusedVariable => {
+                format!("Variable '{}' is assigned but never used", self.name)
+            }
+            WarningKind::UseBeforeAssignment => {
+                format!("Variable '{}' is used before it is assigned", self.name)
+            }
+            WarningKind::UndefinedVariable => format!("Variable '{}' is never defined", self.name),
+            WarningKind::UndefinedFunction => format!("Function '{}' is not defined", self.name),
+            WarningKind::ShadowedBuiltin => {
+                format!("Function '{}' shadows the builtin of the same name", self.name)
+            }
+            WarningKind::UnusedComparison => {
+                format!("The result of comparing '{}' with == is never used", self.name)
+            }
+            WarningKind::EndlessLoop => {
+                let keyword = match self.name.as_str() {
+                    "بينما" => "while",
+                    "كرر" => "loop",
+                    other => other,
+                };
+                format!("The '{}' loop has no break or return, so it never ends", keyword)
+            }
+            WarningKind::TruthyCondition => {
+                format!("'{}' is a string or list used as a condition, so it is true unless empty", self.name)
+            }
+        }
+    }
+
+    pub fn message_in(&self, language: ErrorLanguage) -> String {
+        language.pick(&self.message_ar(), &self.message_en())
+    }
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let language = ErrorLanguage::current();
+        write!(f, "{}: {}", language.pick("السطر", "line"), self.line)?;
+        write!(f, ": {}", self.message_in(language))?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " ({})", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Analyzes a parsed program and returns its warnings ordered by line,
+/// leaving out those `comments` silence.
+pub fn analyze_with_comments(program: &[Stmt], comments: &[Comment]) -> Vec<Warning> {
+    let mut warnings = analyze(program);
+    warnings.retain(|warning| !is_silenced(warning, comments));
+    warnings
+}
+
+/// Whether a `// تجاهل: code` or `// allow: code` comment on the warning's
+/// line or the line above names its kind. One comment may list several
+/// codes separated by commas.
+fn is_silenced(warning: &Warning, comments: &[Comment]) -> bool {
+    comments.iter().filter(|comment| comment.line == warning.line || comment.line + 1 == warning.line).any(|comment| {
+        let text = comment.text.trim();
+        let codes = text.strip_prefix("تجاهل:").or_else(|| text.strip_prefix("allow:"));
+        codes.is_some_and(|codes| codes.split([',', '،']).any(|code| code.trim() == warning.kind.code()))
+    })
+}
+
+/// Analyzes a parsed program and returns its warnings ordered by line.
+pub fn analyze(program: &[Stmt]) -> Vec<Warning> {
+    let mut analyzer = Analyzer::default();
+    analyzer.collect(program, true);
+    let globals = analyzer.globals.clone();
+    analyzer.visit_block(program, &mut HashSet::new(), &globals);
+
+    let unused: Vec<Warning> = analyzer
+        .assignments
+        .iter()
+        .filter(|(name, _)| !analyzer.reads.contains(name) && !name.starts_with('_'))
+        .map(|(name, line)| Warning {
+            kind: WarningKind::UnusedVariable,
+            name: name.to_string(),
+            line: *line,
+            suggestion: None,
+        })
+        .collect();
+    let mut warnings = analyzer.warnings;
+    warnings.extend(unused);
+    warnings.sort_by(|a, b| (a.line, a.kind, &a.name).cmp(&(b.line, b.kind, &b.name)));
+    warnings
+}
+
+#[derive(Default)]
+struct Analyzer {
+    /// User functions defined anywhere in the program.
+    functions: HashSet<Symbol>,
+    /// Names assigned outside any function, visible to every function body.
+    globals: HashSet<Symbol>,
+    /// Names assigned anywhere, including function locals.
+    assigned: HashSet<Symbol>,
+    /// First assignment of every name, in program order.
+    assignments: Vec<(Symbol, usize)>,
+    reads: HashSet<Symbol>,
+    /// Variables whose last assignment so far was a string or list literal.
+    collections: HashMap<Symbol, Shape>,
+    warnings: Vec<Warning>,
+    reported: HashSet<(WarningKind, String)>,
+}
+
+impl Analyzer {
+    fn collect(&mut self, statements: &[Stmt], top_level: bool) {
+        for stmt in statements {
+            match &stmt.kind {
+                StmtKind::Assignment { name, .. } | StmtKind::ConstAssignment { name, .. } => {
+                    self.record_assignment(name.clone(), stmt.span.start_line, top_level)
+                }
+                StmtKind::If { then_branch, else_branch, .. } => {
+                    self.collect(then_branch, top_level);
+                    if let Some(else_branch) = else_branch {
+                        self.collect(else_branch, top_level);
+                    }
+                }
+                StmtKind::While { body, .. }
+                | StmtKind::DoWhile { body, .. }
+                | StmtKind::Loop(body)
+                | StmtKind::Block(body) => self.collect(body, top_level),
+                StmtKind::FunctionDef { name, body, .. } => {
+                    self.functions.insert(name.clone());
+                    self.collect(body, false);
+                }
+                StmtKind::TryCatch { try_block, catches } => {
+                    self.collect(try_block, top_level);
+                    for arm in catches {
+                        self.collect(&arm.body, top_level);
+                    }
+                }
+                StmtKind::Match { arms, default, .. } => {
+                    for arm in arms {
+                        self.collect(&arm.body, top_level);
+                    }
+                    if let Some(default) = default {
+                        self.collect(default, top_level);
+                    }
+                }
+                StmtKind::MultiAssignment { names, .. } => {
+                    for name in names {
+                        self.record_assignment(name.clone(), stmt.span.start_line, top_level);
+                    }
+                }
+                StmtKind::Expression(_) | StmtKind::Return(_) | StmtKind::Break | StmtKind::Continue => {}
+            }
+        }
+    }
+
+    fn record_assignment(&mut self, name: Symbol, line: usize, top_level: bool) {
+        if top_level {
+            self.globals.insert(name.clone());
+        }
+        if self.assigned.insert(name.clone()) {
+            self.assignments.push((name, line));
+        }
+    }
+
+    /// Visits statements in order; `defined` holds the names assigned so far.
+    fn visit_block(
+        &mut self,
+        statements: &[Stmt],
+        defined: &mut HashSet<Symbol>,
+        globals: &HashSet<Symbol>,
+    ) {
+        for (index, stmt) in statements.iter().enumerate() {
+            let line = stmt.span.start_line;
+            match &stmt.kind {
+                // Only the last statement of a block produces a value, so an
+                // earlier `س == ١` was most likely meant as `س = ١`.
+                StmtKind::Expression(
+                    expr @ Expr { kind: ExprKind::Binary { left, operator: BinaryOp::Equal, .. }, .. },
+                ) if index + 1 < statements.len() => {
+                    let name = match &left.kind {
+                        ExprKind::Identifier(name) => name.as_str(),
+                        _ => "==",
+                    };
+                    let suggestion = Some(tr!("suggest.single_equals"));
+                    self.report(WarningKind::UnusedComparison, name, line, suggestion);
+                    self.visit_expr(expr, line, defined)
+                }
+                StmtKind::Expression(expr) | StmtKind::Return(expr) => {
+                    self.visit_expr(expr, line, defined)
+                }
+                StmtKind::Assignment { name, value }
+                | StmtKind::ConstAssignment { name, value } => {
+                    self.visit_expr(value, line, defined);
+                    defined.insert(name.clone());
+                    match Shape::of(value) {
+                        Some(shape) => self.collections.insert(name.clone(), shape),
+                        None => self.collections.remove(name),
+                    };
+                }
+                StmtKind::MultiAssignment { names, values } => {
+                    for value in values {
+                        self.visit_expr(value, line, defined);
+                    }
+                    defined.extend(names.iter().cloned());
+                    for name in names {
+                        self.collections.remove(name);
+                    }
+                }
+                StmtKind::If { condition, then_branch, else_branch } => {
+                    self.check_condition(condition, line);
+                    self.visit_expr(condition, line, defined);
+                    self.visit_block(then_branch, defined, globals);
+                    if let Some(else_branch) = else_branch {
+                        self.visit_block(else_branch, defined, globals);
+                    }
+                }
+                StmtKind::While { condition, body } => {
+                    if matches!(&condition.kind, ExprKind::Literal(value) if value.is_truthy()) {
+                        self.check_loop_ends("بينما", body, line);
+                    }
+                    self.check_condition(condition, line);
+                    self.visit_expr(condition, line, defined);
+                    self.visit_block(body, defined, globals);
+                }
+                StmtKind::Loop(body) => {
+                    self.check_loop_ends("كرر", body, line);
+                    self.visit_block(body, defined, globals);
+                }
+                StmtKind::DoWhile { body, condition } => {
+                    self.visit_block(body, defined, globals);
+                    self.check_condition(condition, line);
+                    self.visit_expr(condition, line, defined);
+                }
+                StmtKind::Break | StmtKind::Continue => {}
+                StmtKind::Block(body) => self.visit_block(body, defined, globals),
+                StmtKind::FunctionDef { name, params, rest, body, .. } => {
+                    if canonical_builtin(name).is_some() {
+                        let suggestion = tr!("suggest.builtin_escape", name = name);
+                        self.report(WarningKind::ShadowedBuiltin, name, line, Some(suggestion));
+                    }
+                    // Functions run after the whole top level has had a chance
+                    // to assign globals, so every global counts as defined.
+                    let mut locals: HashSet<Symbol> = globals.clone();
+                    for (param, default) in params {
+                        if let Some(default) = default {
+                            self.visit_expr(default, line, &locals);
+                        }
+                        locals.insert(param.clone());
+                    }
+                    locals.extend(rest.iter().cloned());
+                    // What the body's names hold doesn't depend on the code around it.
+                    let outer = std::mem::take(&mut self.collections);
+                    self.visit_block(body, &mut locals, globals);
+                    self.collections = outer;
+                }
+                StmtKind::Match { subject, arms, default } => {
+                    self.visit_expr(subject, line, defined);
+                    for arm in arms {
+                        for value in &arm.values {
+                            self.visit_expr(value, line, defined);
+                        }
+                        self.visit_block(&arm.body, defined, globals);
+                    }
+                    if let Some(default) = default {
+                        self.visit_block(default, defined, globals);
+                    }
+                }
+                StmtKind::TryCatch { try_block, catches } => {
+                    self.visit_block(try_block, defined, globals);
+                    for arm in catches {
+                        if let Some(error_var) = &arm.error_var {
+                            defined.insert(error_var.clone());
+                        }
+                        self.visit_block(&arm.body, defined, globals);
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, line: usize, defined: &HashSet<Symbol>) {
+        match &expr.kind {
+            ExprKind::Literal(_) => {}
+            ExprKind::Identifier(name) => {
+                self.reads.insert(name.clone());
+                if defined.contains(name) {
+                    return;
+                }
+                if self.assigned.contains(name) {
+                    self.report(WarningKind::UseBeforeAssignment, name, line, None);
+                } else {
+                    let suggestion = closest_name(name, defined.iter().chain(&self.assigned).map(Symbol::as_str))
+                        .map(|n| did_you_mean(&n));
+                    self.report(WarningKind::UndefinedVariable, name, line, suggestion);
+                }
+            }
+            ExprKind::Binary { left, right, .. } => {
+                self.visit_expr(left, line, defined);
+                self.visit_expr(right, line, defined);
+            }
+            ExprKind::Unary { operand, .. } | ExprKind::SafeEval(operand) => {
+                self.visit_expr(operand, line, defined)
+            }
+            ExprKind::Call { name, args } => {
+                for arg in args {
+                    self.visit_expr(arg, line, defined);
+                }
+                if !self.functions.contains(name) && canonical_builtin(name).is_none() {
+                    let candidates = self.functions.iter().map(Symbol::as_str);
+                    let suggestion =
+                        closest_name(name, candidates.chain(BUILTIN_FUNCTIONS.iter().copied()))
+                            .map(|n| did_you_mean(&n));
+                    self.report(WarningKind::UndefinedFunction, name, line, suggestion);
+                }
+            }
+            ExprKind::BuiltinCall { name, args } => {
+                for arg in args {
+                    self.visit_expr(arg, line, defined);
+                }
+                if canonical_builtin(name).is_none() {
+                    let suggestion =
+                        closest_name(name, BUILTIN_FUNCTIONS.iter().copied()).map(|n| did_you_mean(&n));
+                    self.report(WarningKind::UndefinedFunction, name, line, suggestion);
+                }
+            }
+            ExprKind::List(elements) => {
+                for element in elements {
+                    self.visit_expr(element, line, defined);
+                }
+            }
+            ExprKind::Index { object, index } => {
+                self.visit_expr(object, line, defined);
+                self.visit_expr(index, line, defined);
+            }
+        }
+    }
+
+    /// Warns about a string or list used as `condition`, or as an operand of
+    /// its `و`, `أو` and `ليس`.
+    fn check_condition(&mut self, condition: &Expr, line: usize) {
+        match &condition.kind {
+            ExprKind::Binary { left, operator: BinaryOp::And | BinaryOp::Or, right } => {
+                self.check_condition(left, line);
+                self.check_condition(right, line);
+            }
+            ExprKind::Unary { operator: UnaryOp::Not, operand } => self.check_condition(operand, line),
+            ExprKind::Identifier(name) => {
+                if let Some(shape) = self.collections.get(name).copied() {
+                    self.report(WarningKind::TruthyCondition, name, line, Some(shape.suggestion(name)));
+                }
+            }
+            ExprKind::Literal(Value::String(_)) | ExprKind::List(_) => {
+                let name = match &condition.kind {
+                    ExprKind::Literal(Value::String(text)) => format!("\"{}\"", text),
+                    _ => "[...]".to_string(),
+                };
+                let suggestion = tr!("suggest.write_comparison");
+                self.report(WarningKind::TruthyCondition, &name, line, Some(suggestion));
+            }
+            _ => {}
+        }
+    }
+
+    /// Warns about a loop whose condition never fails when its body has
+    /// no way out.
+    fn check_loop_ends(&mut self, keyword: &str, body: &[Stmt], line: usize) {
+        if !can_leave_loop(body, false) {
+            // Every such loop is its own mistake, so none is deduplicated.
+            self.warnings.push(Warning {
+                kind: WarningKind::EndlessLoop,
+                name: keyword.to_string(),
+                line,
+                suggestion: Some(tr!("suggest.add_break")),
+            });
+        }
+    }
+
+    /// Records a warning once per kind and name.
+    fn report(&mut self, kind: WarningKind, name: &str, line: usize, suggestion: Option<String>) {
+        if self.reported.insert((kind, name.to_string())) {
+            self.warnings.push(Warning { kind, name: name.to_string(), line, suggestion });
+        }
+    }
+}
+
+/// What the analyzer knows a value to be when it is written as a literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Text,
+    List,
+}
+
+impl Shape {
+    fn of(expr: &Expr) -> Option<Shape> {
+        match &expr.kind {
+            ExprKind::Literal(Value::String(_)) => Some(Shape::Text),
+            ExprKind::List(_) => Some(Shape::List),
+            _ => None,
+        }
+    }
+
+    fn suggestion(self, name: &str) -> String {
+        match self {
+            Shape::Text => tr!("suggest.compare_text", name = name),
+            Shape::List => tr!("suggest.compare_list", name = name),
+        }
+    }
+}
+
+/// Whether `statements`, a loop body, hold an `ارجع` or a `توقف` that
+/// leaves the loop. Inside an inner loop (`nested`), a `توقف` only leaves
+/// that loop; the body of a function defined in the loop doesn't count.
+fn can_leave_loop(statements: &[Stmt], nested: bool) -> bool {
+    statements.iter().any(|stmt| match &stmt.kind {
+        StmtKind::Break => !nested,
+        StmtKind::Return(_) => true,
+        StmtKind::If { then_branch, else_branch, .. } => {
+            can_leave_loop(then_branch, nested)
+                || else_branch.as_ref().is_some_and(|branch| can_leave_loop(branch, nested))
+        }
+        StmtKind::Block(body) => can_leave_loop(body, nested),
+        StmtKind::While { body, .. } | StmtKind::DoWhile { body, .. } | StmtKind::Loop(body) => {
+            can_leave_loop(body, true)
+        }
+        StmtKind::TryCatch { try_block, catches } => {
+            can_leave_loop(try_block, nested) || catches.iter().any(|arm| can_leave_loop(&arm.body, nested))
+        }
+        StmtKind::Match { arms, default, .. } => {
+            arms.iter().any(|arm| can_leave_loop(&arm.body, nested))
+                || default.as_ref().is_some_and(|body| can_leave_loop(body, nested))
+        }
+        _ => false,
+    })
+}
+
+/// Levenshtein edit distance between two strings, counted in characters.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Returns the candidate closest to `name`, if it is within two edits and
+/// shorter than a full rewrite. Ties go to the alphabetically first name.
+pub fn closest_name<I>(name: &str, candidates: I) -> Option<String>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    closest_names(name, candidates, 1).pop()
+}
+
+/// Like [`closest_name`], but up to `limit` candidates, closest first.
+pub fn closest_names<I>(name: &str, candidates: I, limit: usize) -> Vec<String>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let length = name.chars().count();
+    let mut close: Vec<(usize, String)> = candidates
+        .into_iter()
+        .filter(|candidate| candidate.as_ref() != name)
+        .map(|candidate| (levenshtein(name, candidate.as_ref()), candidate.as_ref().to_string()))
+        .filter(|(distance, _)| *distance <= 2 && *distance < length)
+        .collect();
+    close.sort();
+    close.dedup();
+    close.into_iter().take(limit).map(|(_, candidate)| candidate).collect()
+}
+
+/// The suggestion text used for near-miss names.
+pub fn did_you_mean(name: &str) -> String {
+    tr!("suggest.did_you_mean_name", name = name)
+}
@@ -0,0 +1,128 @@
+//! معالجة النص العربي | Arabic text normalization behind `أزل_التشكيل`,
+//! `طبع_نص` and `وحد_الأرقام`. Every function here is pure.
+
+/// Whether `ch` is an Arabic combining mark: harakat, tanween, shadda,
+/// sukun, the superscript alef and the Quranic annotation marks.
+pub fn is_diacritic(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{0610}'..='\u{061A}'
+            | '\u{064B}'..='\u{065F}'
+            | '\u{0670}'
+            | '\u{06D6}'..='\u{06DC}'
+            | '\u{06DF}'..='\u{06E4}'
+            | '\u{06E7}'..='\u{06E8}'
+            | '\u{06EA}'..='\u{06ED}'
+            | '\u{08D3}'..='\u{08E1}'
+            | '\u{08E3}'..='\u{08FF}'
+    )
+}
+
+/// The text without its diacritics.
+pub fn strip_diacritics(text: &str) -> String {
+    text.chars().filter(|ch| !is_diacritic(*ch)).collect()
+}
+
+/// Which transforms `normalize_arabic` applies. All are on by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NormalizeOptions {
+    /// أ إ آ ٱ → ا
+    pub alef: bool,
+    /// ى → ي
+    pub yeh: bool,
+    /// ة → ه
+    pub teh_marbuta: bool,
+    /// Removes the tatweel (ـ) used to stretch words.
+    pub tatweel: bool,
+    /// Runs of whitespace become one space, and the ends are trimmed.
+    pub whitespace: bool,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        NormalizeOptions {
+            alef: true,
+            yeh: true,
+            teh_marbuta: true,
+            tatweel: true,
+            whitespace: true,
+        }
+    }
+}
+
+impl NormalizeOptions {
+    /// The option names accepted in the options map, Arabic then English.
+    pub const KEYS: &'static [(&'static str, &'static str)] = &[
+        ("ألف", "alef"),
+        ("ياء", "yeh"),
+        ("تاء_مربوطة", "teh_marbuta"),
+        ("تطويل", "tatweel"),
+        ("مسافات", "whitespace"),
+    ];
+
+    /// Sets the option named `key` in either language. Returns `false` for
+    /// an unknown name.
+    pub fn set(&mut self, key: &str, on: bool) -> bool {
+        let field = match key {
+            "ألف" | "alef" => &mut self.alef,
+            "ياء" | "yeh" => &mut self.yeh,
+            "تاء_مربوطة" | "teh_marbuta" => &mut self.teh_marbuta,
+            "تطويل" | "tatweel" => &mut self.tatweel,
+            "مسافات" | "whitespace" => &mut self.whitespace,
+            _ => return false,
+        };
+        *field = on;
+        true
+    }
+}
+
+/// Smooths over orthographic variation so two spellings of a word compare
+/// equal. Diacritics are left alone; see [`strip_diacritics`].
+pub fn normalize_arabic(text: &str, options: &NormalizeOptions) -> String {
+    let letters = text.chars().filter_map(|ch| match ch {
+        'أ' | 'إ' | 'آ' | 'ٱ' if options.alef => Some('ا'),
+        'ى' if options.yeh => Some('ي'),
+        'ة' if options.teh_marbuta => Some('ه'),
+        'ـ' if options.tatweel => None,
+        _ => Some(ch),
+    });
+    if options.whitespace {
+        letters.collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ")
+    } else {
+        letters.collect()
+    }
+}
+
+/// The digit set `normalize_digits` converts to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigitScript {
+    /// ٠١٢٣٤٥٦٧٨٩
+    Arabic,
+    /// 0123456789
+    Ascii,
+}
+
+/// Converts every digit in the text to `script`. Extended (Persian) digits
+/// count as Arabic-Indic digits.
+pub fn normalize_digits(text: &str, script: DigitScript) -> String {
+    text.chars()
+        .map(|ch| {
+            let value = match ch {
+                '0'..='9' => ch as u32 - '0' as u32,
+                '٠'..='٩' => ch as u32 - '٠' as u32,
+                '۰'..='۹' => ch as u32 - '۰' as u32,
+                _ => return ch,
+            };
+            let zero = match script {
+                DigitScript::Arabic => '٠',
+                DigitScript::Ascii => '0',
+            };
+            char::from_u32(zero as u32 + value).unwrap_or(ch)
+        })
+        .collect()
+}
+
+/// Shorthand for converting to ASCII digits before parsing numbers.
+pub fn to_ascii_digits(text: &str) -> String {
+    normalize_digits(text, DigitScript::Ascii)
+}
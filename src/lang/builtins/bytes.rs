@@ -0,0 +1,84 @@
+//! الترميزات | Text encodings behind `نص_إلى_بايتات`, `بايتات_إلى_نص`
+//! and the `بترميز` file builtins. UTF-8 is the default; windows-1256 and
+//! iso-8859-6 are still common in older Arabic files, such as CSV exports
+//! from Windows spreadsheets.
+
+use encoding_rs::{Encoding, ISO_8859_6, UTF_8, WINDOWS_1256};
+
+/// The encoding names the builtins accept, as shown in errors.
+pub const ENCODINGS: &[&str] = &["utf-8", "windows-1256", "iso-8859-6"];
+
+/// The encoding called `label`, ignoring case: `utf-8`/`utf8`,
+/// `windows-1256`/`cp1256` or `iso-8859-6`/`arabic`.
+pub fn encoding_for(label: &str) -> Option<&'static Encoding> {
+    match label.trim().to_lowercase().as_str() {
+        "utf-8" | "utf8" => Some(UTF_8),
+        "windows-1256" | "cp1256" => Some(WINDOWS_1256),
+        "iso-8859-6" | "arabic" => Some(ISO_8859_6),
+        _ => None,
+    }
+}
+
+/// The name of `encoding` as [`ENCODINGS`] spells it.
+pub fn label(encoding: &'static Encoding) -> &'static str {
+    if encoding == WINDOWS_1256 {
+        "windows-1256"
+    } else if encoding == ISO_8859_6 {
+        "iso-8859-6"
+    } else {
+        "utf-8"
+    }
+}
+
+/// `text` in `encoding`, or the first character the encoding has no byte
+/// for.
+pub fn encode(text: &str, encoding: &'static Encoding) -> Result<Vec<u8>, char> {
+    let (bytes, _, had_errors) = encoding.encode(text);
+    if !had_errors {
+        return Ok(bytes.into_owned());
+    }
+    let unmappable = text.chars().find(|ch| encoding.encode(ch.encode_utf8(&mut [0; 4])).2);
+    Err(unmappable.unwrap_or(char::REPLACEMENT_CHARACTER))
+}
+
+/// The text `bytes` hold in `encoding`, or `None` when they aren't valid
+/// in it. A byte order mark is kept as part of the text.
+pub fn decode(bytes: &[u8], encoding: &'static Encoding) -> Option<String> {
+    encoding
+        .decode_without_bom_handling_and_without_replacement(bytes)
+        .map(|text| text.into_owned())
+}
+
+/// A guess at the encoding of some bytes, and how sure it is, from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Guess {
+    pub encoding: &'static Encoding,
+    pub confidence: f64,
+}
+
+/// Guesses which of [`ENCODINGS`] `bytes` are in. Valid UTF-8 is taken as
+/// UTF-8, though only half surely when it is plain ASCII, which reads the
+/// same in all three. Otherwise the legacy encoding whose reading has more
+/// `ال`, the commonest pair of letters in Arabic, wins.
+pub fn detect(bytes: &[u8]) -> Guess {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        let confidence = if text.is_ascii() { 0.5 } else { 1.0 };
+        return Guess { encoding: UTF_8, confidence };
+    }
+    // windows-1256 has a character for every byte; iso-8859-6 leaves many
+    // undefined, and bytes using them rule it out.
+    let Some(iso) = decode(bytes, ISO_8859_6) else {
+        return Guess { encoding: WINDOWS_1256, confidence: 1.0 };
+    };
+    let windows = decode(bytes, WINDOWS_1256).unwrap_or_default();
+    let (windows_score, iso_score) = (windows.matches("ال").count(), iso.matches("ال").count());
+    if windows_score + iso_score == 0 {
+        return Guess { encoding: WINDOWS_1256, confidence: 0.5 };
+    }
+    let total = (windows_score + iso_score) as f64;
+    if iso_score > windows_score {
+        Guess { encoding: ISO_8859_6, confidence: iso_score as f64 / total }
+    } else {
+        Guess { encoding: WINDOWS_1256, confidence: windows_score as f64 / total }
+    }
+}
@@ -0,0 +1,59 @@
+//! الذاكرة المؤقتة | The file behind `خزّن`, `تذكر` and `امسح_الذاكرة`: a
+//! JSON map from each key to its value and the time it expires, if any.
+//! Every run that uses the same file sees the same entries.
+
+use crate::lang::value::Value;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The entries of a cache file. Times are Unix seconds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScriptCache {
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CacheEntry {
+    value: Value,
+    expires_at: Option<i64>,
+}
+
+impl ScriptCache {
+    /// Reads a cache file. Text that isn't one, such as a file cut short by
+    /// a crash, gives an empty cache: the entries are only a shortcut.
+    pub fn parse(json: &str) -> Self {
+        serde_json::from_str(json).unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("values always serialize")
+    }
+
+    /// The value under `key`, unless there is none or it expired by `now`.
+    pub fn get(&self, key: &str, now: i64) -> Option<&Value> {
+        let entry = self.entries.get(key)?;
+        match entry.expires_at {
+            Some(expires_at) if expires_at <= now => None,
+            _ => Some(&entry.value),
+        }
+    }
+
+    /// Stores `value` under `key`, replacing what was there, until
+    /// `expires_at` or, without one, until cleared.
+    pub fn set(&mut self, key: &str, value: Value, expires_at: Option<i64>) {
+        self.entries.insert(key.to_string(), CacheEntry { value, expires_at });
+    }
+
+    /// Drops the entries that expired by `now`.
+    pub fn remove_expired(&mut self, now: i64) {
+        self.entries.retain(|_, entry| entry.expires_at.is_none_or(|expires_at| expires_at > now));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -0,0 +1,104 @@
+//! القوائم والقواميس | List and map helpers behind `عناصر`, `اقلب`,
+//! `عد_التكرار`, `جمّع_حسب`, `افرز_قيم`, `رقّم` and `نطاق_فهارس`. Maps are unordered, so every
+//! function here fixes its order: sorted keys, or first appearance in a list.
+
+use std::collections::HashMap;
+
+use crate::lang::value::Value;
+
+/// The keys of `map` in the order iteration uses: sorted.
+pub fn sorted_keys(map: &HashMap<String, Value>) -> Vec<&String> {
+    let mut keys: Vec<_> = map.keys().collect();
+    keys.sort();
+    keys
+}
+
+/// `[key, value]` pairs of `map`, sorted by key.
+pub fn items(map: &HashMap<String, Value>) -> Vec<Value> {
+    sorted_keys(map)
+        .into_iter()
+        .map(|key| Value::List(vec![Value::String(key.clone()), map[key].clone()]))
+        .collect()
+}
+
+/// `map` with keys and values swapped. Values become keys as they display,
+/// and when several share a key the one whose old key sorts last wins. Also
+/// returns the new keys that had more than one candidate, sorted.
+pub fn invert(map: &HashMap<String, Value>) -> (HashMap<String, Value>, Vec<String>) {
+    let mut inverted = HashMap::new();
+    let mut collisions = Vec::new();
+    for key in sorted_keys(map) {
+        let new_key = format!("{}", map[key]);
+        if inverted.insert(new_key.clone(), Value::String(key.clone())).is_some()
+            && !collisions.contains(&new_key)
+        {
+            collisions.push(new_key);
+        }
+    }
+    collisions.sort();
+    (inverted, collisions)
+}
+
+/// How many times each element of `list` appears, keyed by its displayed
+/// form.
+pub fn count_by(list: &[Value]) -> HashMap<String, Value> {
+    tally(list)
+        .into_iter()
+        .map(|(value, count)| (format!("{}", value), Value::Int(count)))
+        .collect()
+}
+
+/// The `n` most frequent elements of `list` as `[value, count]` pairs, most
+/// frequent first. Ties keep the order the elements first appear in.
+pub fn tally_top(list: &[Value], n: usize) -> Vec<Value> {
+    let mut counts = tally(list);
+    counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    counts
+        .into_iter()
+        .take(n)
+        .map(|(value, count)| Value::List(vec![value.clone(), Value::Int(count)]))
+        .collect()
+}
+
+/// Each distinct element of `list`, in order of first appearance, with its
+/// count. Elements that display the same count as one.
+fn tally(list: &[Value]) -> Vec<(&Value, i64)> {
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut counts: Vec<(&Value, i64)> = Vec::new();
+    for value in list {
+        let key = format!("{}", value);
+        match positions.get(&key) {
+            Some(&position) => counts[position].1 += 1,
+            None => {
+                positions.insert(key, counts.len());
+                counts.push((value, 1));
+            }
+        }
+    }
+    counts
+}
+
+/// The maps in `list` grouped by the displayed value of their `field`, each
+/// group in list order. On failure, returns the index of the first element
+/// that is not a map or lacks the field.
+pub fn group_by(list: &[Value], field: &str) -> Result<HashMap<String, Value>, usize> {
+    let mut groups: HashMap<String, Vec<Value>> = HashMap::new();
+    for (index, item) in list.iter().enumerate() {
+        let key = item.as_map().and_then(|map| map.get(field)).ok_or(index)?;
+        groups.entry(format!("{}", key)).or_default().push(item.clone());
+    }
+    Ok(groups.into_iter().map(|(key, items)| (key, Value::List(items))).collect())
+}
+
+/// `[index, item]` pairs of `list`, counting from `start`.
+pub fn enumerate(list: &[Value], start: i64) -> Vec<Value> {
+    (start..)
+        .zip(list)
+        .map(|(index, item)| Value::List(vec![Value::Int(index), item.clone()]))
+        .collect()
+}
+
+/// The valid indexes of `list`: `0` up to its length.
+pub fn indices(list: &[Value]) -> Vec<Value> {
+    (0..list.len() as i64).map(Value::Int).collect()
+}
@@ -0,0 +1,92 @@
+//! التنسيق | The template language of `نسق`. `{}` takes the next argument,
+//! `{0}` a given one, `{:.2}` or `{1:.2}` a number with fixed decimals, and
+//! `{{` and `}}` stand for literal braces. Positions and decimals may be
+//! written in Arabic-Indic digits.
+
+use crate::lang::builtins::arabic::to_ascii_digits;
+use crate::lang::value::{Value, format_number};
+
+/// Why a template can't be filled in. Positions count from zero, as they
+/// are written in the template; `offset` is a byte offset into it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    /// Placeholder `position` has no argument; only `given` were passed.
+    MissingArgument { position: usize, given: usize },
+    /// A `{` at `offset` is never closed.
+    Unclosed { offset: usize },
+    /// A `}` at `offset` closes nothing; a literal one is written `}}`.
+    UnmatchedClose { offset: usize },
+    /// The text between the braces is not a position or a `:.N` spec.
+    BadPlaceholder { placeholder: String },
+    /// `{:.N}` applied to argument `position`, which is not a number.
+    NotANumber { position: usize },
+}
+
+/// Fills `template` in with `args`. Values appear as `اطبع` shows them.
+/// Arguments left over after the last placeholder are ignored.
+pub fn render(template: &str, args: &[Value]) -> Result<String, FormatError> {
+    let mut out = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut chars = template.char_indices().peekable();
+    while let Some((offset, ch)) = chars.next() {
+        match ch {
+            '{' if chars.next_if(|&(_, ch)| ch == '{').is_some() => out.push('{'),
+            '}' if chars.next_if(|&(_, ch)| ch == '}').is_some() => out.push('}'),
+            '}' => return Err(FormatError::UnmatchedClose { offset }),
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '}')) => break,
+                        Some((_, ch)) => placeholder.push(ch),
+                        None => return Err(FormatError::Unclosed { offset }),
+                    }
+                }
+                let (position, decimals) = parse_placeholder(&placeholder)
+                    .ok_or(FormatError::BadPlaceholder { placeholder })?;
+                let position = position.unwrap_or_else(|| {
+                    next += 1;
+                    next - 1
+                });
+                let value = args
+                    .get(position)
+                    .ok_or(FormatError::MissingArgument { position, given: args.len() })?;
+                match decimals {
+                    Some(decimals) => out.push_str(
+                        &format_number(value, decimals, None)
+                            .ok_or(FormatError::NotANumber { position })?,
+                    ),
+                    None => out.push_str(&format!("{}", value)),
+                }
+            }
+            ch => out.push(ch),
+        }
+    }
+    Ok(out)
+}
+
+/// Splits `0:.2` into its position and decimals, either of which may be
+/// missing. `None` when the text is neither.
+fn parse_placeholder(placeholder: &str) -> Option<(Option<usize>, Option<usize>)> {
+    let placeholder = to_ascii_digits(placeholder.trim());
+    let (position, spec) = match placeholder.split_once(':') {
+        Some((position, spec)) => (position.trim(), Some(spec.trim())),
+        None => (placeholder.as_str(), None),
+    };
+    let position = match position {
+        "" => None,
+        digits => Some(parse_digits(digits)?),
+    };
+    let decimals = match spec {
+        None => None,
+        Some(spec) => Some(parse_digits(spec.strip_prefix(['.', '٫'])?)?),
+    };
+    Some((position, decimals))
+}
+
+fn parse_digits(text: &str) -> Option<usize> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
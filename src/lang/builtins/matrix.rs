@@ -0,0 +1,101 @@
+//! المصفوفات | Lists of lists as matrices, behind `انقل_مصفوفة`,
+//! `أعد_تشكيل`, `اجمع_مصفوفات` and `اضرب_مصفوفات`. A `Matrix` borrows its
+//! cells, so checking shapes copies nothing.
+
+use crate::lang::value::Value;
+
+/// Why a value can't be used as a matrix, or two matrices can't be combined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatrixError {
+    /// The argument is not a list.
+    NotAList,
+    /// Row `row` is not a list.
+    RowNotAList { row: usize },
+    /// Row `row` has `found` cells where the first row has `expected`.
+    Ragged { row: usize, expected: usize, found: usize },
+    /// The cell at `row`, `col` is not a number.
+    NotANumber { row: usize, col: usize },
+    /// `len` items can't fill `rows` × `cols`.
+    Reshape { len: usize, rows: usize, cols: usize },
+    /// Element-wise operands of different shapes.
+    ShapeMismatch { left: (usize, usize), right: (usize, usize) },
+    /// A product whose left columns don't match its right rows.
+    InnerMismatch { left: (usize, usize), right: (usize, usize) },
+}
+
+#[derive(Debug)]
+pub struct Matrix<'a> {
+    pub rows: usize,
+    pub cols: usize,
+    cells: Vec<&'a Value>,
+}
+
+impl<'a> Matrix<'a> {
+    /// Reads a list of equally long lists.
+    pub fn parse(value: &'a Value) -> Result<Self, MatrixError> {
+        let Value::List(rows) = value else { return Err(MatrixError::NotAList) };
+        let mut cols = None;
+        let mut cells = Vec::new();
+        for (row, item) in rows.iter().enumerate() {
+            let Value::List(items) = item else { return Err(MatrixError::RowNotAList { row }) };
+            let expected = *cols.get_or_insert(items.len());
+            if items.len() != expected {
+                return Err(MatrixError::Ragged { row, expected, found: items.len() });
+            }
+            cells.extend(items);
+        }
+        Ok(Matrix { rows: rows.len(), cols: cols.unwrap_or(0), cells })
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> &'a Value {
+        self.cells[row * self.cols + col]
+    }
+
+    /// Checks that every cell is a number.
+    pub fn numeric(self) -> Result<Self, MatrixError> {
+        match self.cells.iter().position(|cell| cell.as_number().is_none()) {
+            Some(index) => {
+                Err(MatrixError::NotANumber { row: index / self.cols, col: index % self.cols })
+            }
+            None => Ok(self),
+        }
+    }
+
+    pub fn transpose(&self) -> Value {
+        Value::List(
+            (0..self.cols)
+                .map(|col| {
+                    Value::List((0..self.rows).map(|row| self.get(row, col).clone()).collect())
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Builds a `rows` × `cols` matrix from `items`, filling row by row. A
+/// matrix is read row by row first, so reshaping works between shapes.
+pub fn reshape(items: &Value, rows: usize, cols: usize) -> Result<Value, MatrixError> {
+    let Value::List(list) = items else { return Err(MatrixError::NotAList) };
+    let flat: Vec<&Value> =
+        if !list.is_empty() && list.iter().all(|item| matches!(item, Value::List(_))) {
+            Matrix::parse(items)?.cells
+        } else {
+            list.iter().collect()
+        };
+    if rows.checked_mul(cols) != Some(flat.len()) {
+        return Err(MatrixError::Reshape { len: flat.len(), rows, cols });
+    }
+    Ok(Value::List(
+        (0..rows)
+            .map(|row| {
+                Value::List(
+                    flat[row * cols..(row + 1) * cols].iter().map(|cell| (*cell).clone()).collect(),
+                )
+            })
+            .collect(),
+    ))
+}
@@ -0,0 +1,602 @@
+//! توثيق الدوال المدمجة | Documentation for every builtin, as returned by
+//! `مساعدة`/`help` and rendered by the REPL.
+
+pub mod arabic;
+pub mod bytes;
+pub mod cache;
+pub mod collections;
+pub mod format;
+pub mod matrix;
+pub mod path;
+pub mod stats;
+
+use crate::lang::runtime::canonical_builtin;
+use crate::lang::value::Value;
+use std::collections::HashMap;
+
+/// The categories `مساعدة()` groups builtins under, in display order.
+pub const CATEGORIES: &[&str] = &[
+    "إخراج | Output",
+    "قوائم | Lists",
+    "قواميس | Maps",
+    "مجموعات | Sets",
+    "أنواع وتحويل | Types",
+    "أرقام | Numbers",
+    "نصوص | Text",
+    "ترميز | Encoding",
+    "ملفات ونظام | Files & System",
+    "تفاعل | Input",
+    "الجلسة | Session",
+];
+
+/// One builtin's help entry. Parameters are written `عربي | english`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuiltinDoc {
+    pub arabic: &'static str,
+    pub english: &'static str,
+    pub category: &'static str,
+    pub params: &'static [&'static str],
+    pub description_ar: &'static str,
+    pub description_en: &'static str,
+    pub examples: &'static [&'static str],
+}
+
+macro_rules! doc {
+    ($ar:literal, $en:literal, $category:literal, [$($param:literal),*], $desc_ar:literal, $desc_en:literal, [$($example:literal),*]) => {
+        BuiltinDoc {
+            arabic: $ar,
+            english: $en,
+            category: $category,
+            params: &[$($param),*],
+            description_ar: $desc_ar,
+            description_en: $desc_en,
+            examples: &[$($example),*],
+        }
+    };
+}
+
+/// Help for each pair in [`BUILTIN_FUNCTIONS`](crate::lang::runtime::BUILTIN_FUNCTIONS).
+#[rustfmt::skip]
+pub const BUILTIN_DOCS: &[BuiltinDoc] = &[
+    doc!("اطبع", "print", "إخراج | Output", ["...قيم | ...values"],
+        "يطبع القيم في سطر واحد تفصل بينها مسافات",
+        "Prints the values on one line, separated by spaces",
+        ["اطبع(\"مرحبا\"، ٤٢)"]),
+    doc!("اطبع_ملون", "print_color", "إخراج | Output", ["قيمة | value", "لون | color", "نمط | style?"],
+        "يطبع قيمة بلون ونمط اختياري (عريض، مائل، تحته_خط) عندما يكون الإخراج طرفية",
+        "Prints a value in a color and optional style (bold, italic, underline) when output is a terminal",
+        ["اطبع_ملون(\"تم\"، \"أخضر\"، \"عريض\")"]),
+    doc!("اطبع_بدون_سطر", "print_inline", "إخراج | Output", ["...قيم | ...values"],
+        "يطبع القيم مثل اطبع لكن دون سطر جديد في آخرها",
+        "Prints the values like print, without a newline at the end",
+        ["اطبع_بدون_سطر(\"جارٍ التحميل...\")"]),
+    doc!("اطبع_مفصول", "print_sep", "إخراج | Output", ["فاصل | separator", "...قيم | ...values"],
+        "يطبع القيم في سطر واحد يفصل بينها الفاصل المعطى",
+        "Prints the values on one line, separated by the given separator",
+        ["اطبع_مفصول(\"، \"، ١، ٢، ٣)"]),
+    doc!("اطبع_خطأ", "print_error", "إخراج | Output", ["...قيم | ...values"],
+        "يطبع القيم مثل اطبع في مجرى الأخطاء بدل المخرجات",
+        "Prints the values like print, to standard error instead of standard output",
+        ["اطبع_خطأ(\"الملف غير موجود\")"]),
+    doc!("قائمة", "list", "قوائم | Lists", ["...عناصر | ...items"],
+        "ينشئ قائمة من الوسائط",
+        "Builds a list from its arguments",
+        ["قائمة(١، ٢، ٣)"]),
+    doc!("عنصر", "get", "قوائم | Lists", ["مجموعة_قيم | collection", "فهرس | index"],
+        "العنصر عند فهرس في قائمة أو مفتاح في قاموس",
+        "The item at an index of a list or a key of a map",
+        ["عنصر([\"أ\"، \"ب\"]، ١)"]),
+    doc!("أضف", "append", "قوائم | Lists", ["قائمة | list", "عنصر | item"],
+        "قائمة جديدة بإضافة عنصر في آخرها",
+        "A new list with the item added at the end",
+        ["أضف([١، ٢]، ٣)"]),
+    doc!("احذف", "remove", "قوائم | Lists", ["قائمة | list", "عنصر | item"],
+        "قائمة جديدة دون كل نسخ العنصر",
+        "A new list without any copies of the item",
+        ["احذف([١، ٢، ١]، ١)"]),
+    doc!("يحتوي", "contains", "قوائم | Lists", ["قائمة | list", "عنصر | item"],
+        "هل يوجد العنصر في القائمة",
+        "Whether the item is in the list",
+        ["يحتوي([١، ٢]، ٢)"]),
+    doc!("إلى_قائمة", "to_list", "قوائم | Lists", ["قيمة | value"],
+        "يحول مجموعة إلى قائمة",
+        "Converts a set to a list",
+        ["إلى_قائمة(مجموعة(١، ٢))"]),
+    doc!("خريطة_متوازية", "parallel_map", "قوائم | Lists", ["قائمة | list", "اسم_دالة | function_name"],
+        "يطبق دالة على كل عنصر على عدة خيوط ويعيد النتائج بالترتيب",
+        "Applies a function to every item on several threads and returns the results in order",
+        ["خريطة_متوازية([١، ٢، ٣]، \"مربع\")"]),
+    doc!("عكس", "reverse", "قوائم | Lists", ["قيمة | value"],
+        "يعكس ترتيب قائمة أو حروف نص",
+        "Reverses a list or the characters of a string",
+        ["عكس(\"سلام\")"]),
+    doc!("قاموس", "map", "قواميس | Maps", ["...مفتاح، قيمة | ...key, value"],
+        "ينشئ قاموساً من أزواج مفتاح وقيمة",
+        "Builds a map from key/value pairs",
+        ["قاموس(\"اسم\"، \"سارة\"، \"عمر\"، ٢٠)"]),
+    doc!("جلب_عنصر", "map_get", "قواميس | Maps", ["قاموس | map", "مفتاح | key"],
+        "قيمة المفتاح في القاموس",
+        "The value stored under a key",
+        ["جلب_عنصر(قاموس(\"أ\"، ١)، \"أ\")"]),
+    doc!("تعيين_عنصر", "map_set", "قواميس | Maps", ["قاموس | map", "مفتاح | key", "قيمة | value"],
+        "قاموس جديد يحمل القيمة تحت المفتاح",
+        "A new map with the value stored under the key",
+        ["تعيين_عنصر(قاموس()، \"أ\"، ١)"]),
+    doc!("حذف_عنصر", "map_remove", "قواميس | Maps", ["قاموس | map", "مفتاح | key"],
+        "قاموس جديد دون المفتاح",
+        "A new map without the key",
+        ["حذف_عنصر(قاموس(\"أ\"، ١)، \"أ\")"]),
+    doc!("رقّم", "enumerate", "قوائم | Lists", ["قائمة | list", "بداية؟ | start?"],
+        "أزواج [فهرس، عنصر] تبدأ من ٠ أو من البداية المعطاة",
+        "[index, item] pairs, counting from 0 or the given start",
+        ["رقّم([\"أ\"، \"ب\"])", "رقّم([\"أ\"، \"ب\"]، ١)"]),
+    doc!("نطاق_فهارس", "indices", "قوائم | Lists", ["قائمة | list"],
+        "فهارس القائمة من ٠ إلى ما قبل طولها",
+        "The list's indexes, from 0 up to its length",
+        ["نطاق_فهارس([\"أ\"، \"ب\"، \"ج\"])"]),
+    doc!("عد_التكرار", "count_by", "قوائم | Lists", ["قائمة | list"],
+        "قاموس بعدد مرات ظهور كل عنصر",
+        "A map from each distinct element to how often it appears",
+        ["عد_التكرار([\"قلم\"، \"كتاب\"، \"قلم\"])"]),
+    doc!("جمّع_حسب", "group_by", "قوائم | Lists", ["قائمة | list", "حقل | field"],
+        "يجمع قائمة قواميس في قاموس قوائم حسب قيمة الحقل",
+        "Groups a list of maps into a map of lists by the field's value",
+        ["جمّع_حسب([قاموس(\"صف\"، \"أ\")]، \"صف\")"]),
+    doc!("افرز_قيم", "tally_top", "قوائم | Lists", ["قائمة | list", "عدد | n"],
+        "أكثر n عناصر تكراراً كأزواج [قيمة، عدد] تنازلياً",
+        "The n most frequent elements as [value, count] pairs, most frequent first",
+        ["افرز_قيم([\"قلم\"، \"كتاب\"، \"قلم\"]، ١)"]),
+    doc!("عناصر", "items", "قواميس | Maps", ["قاموس | map"],
+        "أزواج [مفتاح، قيمة] مرتبة حسب المفتاح",
+        "[key, value] pairs sorted by key",
+        ["عناصر(قاموس(\"ب\"، ٢، \"أ\"، ١))"]),
+    doc!("اقلب", "invert", "قواميس | Maps", ["قاموس | map", "المتكرر؟ | collisions?"],
+        "يبدل المفاتيح والقيم؛ مع صحيح يعيد أيضاً المفاتيح المتكررة",
+        "Swaps keys and values; with true also returns the colliding keys",
+        ["اقلب(قاموس(\"أ\"، \"١\"))", "ق، متكرر = اقلب(قاموس(\"أ\"، ١، \"ب\"، ١)، صحيح)"]),
+    doc!("مجموعة", "set", "مجموعات | Sets", ["...عناصر | ...items"],
+        "ينشئ مجموعة من الوسائط دون تكرار",
+        "Builds a set of the arguments, without duplicates",
+        ["مجموعة(١، ٢، ٢)"]),
+    doc!("أضف_لمجموعة", "set_add", "مجموعات | Sets", ["مجموعة | set", "عنصر | item"],
+        "مجموعة جديدة تضم العنصر",
+        "A new set that includes the item",
+        ["أضف_لمجموعة(مجموعة(١)، ٢)"]),
+    doc!("في_مجموعة؟", "set_contains", "مجموعات | Sets", ["مجموعة | set", "عنصر | item"],
+        "هل العنصر في المجموعة",
+        "Whether the item is in the set",
+        ["في_مجموعة؟(مجموعة(١، ٢)، ٢)"]),
+    doc!("اتحاد", "union", "مجموعات | Sets", ["أ | a", "ب | b"],
+        "العناصر الموجودة في أي من المجموعتين",
+        "The items in either set",
+        ["اتحاد(مجموعة(١)، مجموعة(٢))"]),
+    doc!("تقاطع", "intersection", "مجموعات | Sets", ["أ | a", "ب | b"],
+        "العناصر الموجودة في المجموعتين معاً",
+        "The items in both sets",
+        ["تقاطع(مجموعة(١، ٢)، مجموعة(٢، ٣))"]),
+    doc!("فرق", "difference", "مجموعات | Sets", ["أ | a", "ب | b"],
+        "عناصر المجموعة الأولى غير الموجودة في الثانية",
+        "The items of the first set that are not in the second",
+        ["فرق(مجموعة(١، ٢)، مجموعة(٢))"]),
+    doc!("نوع", "type", "أنواع وتحويل | Types", ["قيمة | value"],
+        "اسم نوع القيمة",
+        "The name of the value's type",
+        ["نوع([١، ٢])"]),
+    doc!("إلى_رقم", "to_number", "أنواع وتحويل | Types", ["قيمة | value", "صارم | strict?"],
+        "يحول نصاً إلى رقم، ويرفض الحروف الزائدة في الوضع الصارم",
+        "Converts text to a number; strict mode rejects trailing characters",
+        ["إلى_رقم(\"١٢\")", "إلى_رقم(\"١٢أ\"، صحيح)"]),
+    doc!("إلى_نص", "to_string", "أنواع وتحويل | Types", ["قيمة | value"],
+        "يحول أي قيمة إلى نص",
+        "Converts any value to text",
+        ["إلى_نص(٤٢)"]),
+    doc!("رقم؟", "is_number", "أنواع وتحويل | Types", ["قيمة | value"],
+        "هل القيمة رقم",
+        "Whether the value is a number",
+        ["رقم؟(٣)"]),
+    doc!("نص؟", "is_string", "أنواع وتحويل | Types", ["قيمة | value"],
+        "هل القيمة نص",
+        "Whether the value is a string",
+        ["نص؟(\"أ\")"]),
+    doc!("منطقي؟", "is_bool", "أنواع وتحويل | Types", ["قيمة | value"],
+        "هل القيمة صحيح أو خطأ",
+        "Whether the value is true or false",
+        ["منطقي؟(صحيح)"]),
+    doc!("إلى_منطقي", "to_bool", "أنواع وتحويل | Types", ["قيمة | value"],
+        "قيمة الشرط: خطأ للعدم والصفر والنص والقائمة والقاموس والمجموعة الفارغة، وصحيح لغيرها",
+        "The value as a condition: false for nil, zero and an empty string, list, map, set or bytes; true otherwise",
+        ["إلى_منطقي(\"\")", "إلى_منطقي([٠])"]),
+    doc!("طول", "len", "أنواع وتحويل | Types", ["قيمة | value"],
+        "عدد حروف النص أو عناصر القائمة أو المجموعة أو مفاتيح القاموس",
+        "The number of characters in a string, items in a list or set, or keys in a map",
+        ["طول(\"مرحبا\")"]),
+    doc!("جمع", "sum", "أرقام | Numbers", ["قائمة | list"],
+        "مجموع أرقام القائمة",
+        "The sum of a list of numbers",
+        ["جمع([١، ٢، ٣])"]),
+    doc!("متوسط", "average", "أرقام | Numbers", ["قائمة | list"],
+        "متوسط أرقام القائمة",
+        "The mean of a list of numbers",
+        ["متوسط([٢، ٤])"]),
+    doc!("وسيط", "median", "أرقام | Numbers", ["قائمة | list"],
+        "القيمة الوسطى، أو متوسط القيمتين الوسطيين؛ القائمة الفارغة خطأ",
+        "The middle value, or the mean of the two middle values; an empty list is an error",
+        ["وسيط([٣، ١، ٢])"]),
+    doc!("منوال", "mode", "أرقام | Numbers", ["قائمة | list"],
+        "القيمة الأكثر تكراراً، أو قائمة بها عند التعادل؛ القائمة الفارغة تعطي []",
+        "The most frequent value, or a list of them on a tie; an empty list gives []",
+        ["منوال([١، ٢، ٢])"]),
+    doc!("تباين", "variance", "أرقام | Numbers", ["قائمة | list", "عينة؟ | sample?"],
+        "تباين المجتمع، أو تباين العينة مع صحيح",
+        "The population variance, or the sample variance with true",
+        ["تباين([٢، ٤، ٤، ٤، ٥، ٥، ٧، ٩])"]),
+    doc!("انحراف_معياري", "stddev", "أرقام | Numbers", ["قائمة | list", "عينة؟ | sample?"],
+        "الانحراف المعياري للمجتمع، أو للعينة مع صحيح",
+        "The population standard deviation, or the sample one with true",
+        ["انحراف_معياري([٢، ٤، ٤، ٤، ٥، ٥، ٧، ٩])"]),
+    doc!("مئين", "percentile", "أرقام | Numbers", ["قائمة | list", "نسبة | p"],
+        "القيمة التي تقع تحتها p بالمئة من القيم، باستيفاء خطي",
+        "The value below which p percent of the values fall, interpolated linearly",
+        ["مئين([١، ٢، ٣، ٤]، ٥٠)"]),
+    doc!("انقل_مصفوفة", "transpose", "أرقام | Numbers", ["مصفوفة | matrix"],
+        "منقول قائمة قوائم: الصفوف تصبح أعمدة",
+        "The transpose of a list of lists: rows become columns",
+        ["انقل_مصفوفة([[١، ٢]، [٣، ٤]])"]),
+    doc!("أعد_تشكيل", "reshape", "أرقام | Numbers", ["قائمة | list", "صفوف | rows", "أعمدة | cols"],
+        "يرتب عناصر قائمة أو مصفوفة في صفوف وأعمدة",
+        "Arranges the items of a list or matrix into rows and columns",
+        ["أعد_تشكيل([١، ٢، ٣، ٤، ٥، ٦]، ٢، ٣)"]),
+    doc!("اجمع_مصفوفات", "matrix_add", "أرقام | Numbers", ["أ | a", "ب | b"],
+        "مجموع مصفوفتين متساويتي الأبعاد عنصراً بعنصر",
+        "The element-wise sum of two matrices of the same shape",
+        ["اجمع_مصفوفات([[١، ٢]]، [[٣، ٤]])"]),
+    doc!("اضرب_مصفوفات", "matrix_multiply", "أرقام | Numbers", ["أ | a", "ب | b"],
+        "حاصل ضرب مصفوفتين؛ أعمدة الأولى تساوي صفوف الثانية",
+        "The matrix product; the first's columns must equal the second's rows",
+        ["اضرب_مصفوفات([[١، ٢]]، [[٣]، [٤]])"]),
+    doc!("أكبر", "max", "أرقام | Numbers", ["قائمة | list"],
+        "أكبر رقم في القائمة",
+        "The largest number in a list",
+        ["أكبر([٣، ٩، ١])"]),
+    doc!("أصغر", "min", "أرقام | Numbers", ["قائمة | list"],
+        "أصغر رقم في القائمة",
+        "The smallest number in a list",
+        ["أصغر([٣، ٩، ١])"]),
+    doc!("بين؟", "between", "أرقام | Numbers", ["قيمة | value", "من | low", "إلى | high"],
+        "صحيح إذا كانت القيمة بين الحدين أو تساوي أحدهما؛ بدلاً من ١ <= س <= ١٠",
+        "True when the value lies between the two bounds or equals one; instead of 1 <= x <= 10",
+        ["بين؟(٥، ١، ١٠)", "between(0, 1, 10)"]),
+    doc!("نسق_رقم", "format_number", "أرقام | Numbers", ["رقم | number", "منازل | decimals", "فاصل | separator?"],
+        "ينسق رقماً بعدد منازل عشرية وفاصل آلاف اختياري",
+        "Formats a number with a number of decimals and an optional thousands separator",
+        ["نسق_رقم(١٢٣٤٫٥، ٢، \"،\")"]),
+    doc!("نسق", "format", "نصوص | Text", ["قالب | template", "...قيم | ...values"],
+        "يملأ القالب بالقيم: {} للتالية، {0} لقيمة بعينها، {:.2} لمنازل عشرية، و{{ و}} للأقواس",
+        "Fills in the template: {} takes the next value, {0} a given one, {:.2} fixed decimals, {{ and }} braces",
+        ["نسق(\"النتيجة: {} من {}\"، ٨، ١٠)", "نسق(\"{0:.1}٪\"، ٨٧٫٢٦)"]),
+    doc!("عدد_الكلمات", "word_count", "نصوص | Text", ["نص | text"],
+        "عدد الكلمات في النص",
+        "The number of words in a string",
+        ["عدد_الكلمات(\"السلام عليكم\")"]),
+    doc!("كرر_نص", "repeat", "نصوص | Text", ["نص | text", "عدد | count"],
+        "النص مكرراً عدداً من المرات",
+        "The text repeated a number of times",
+        ["كرر_نص(\"-\"، ١٠)"]),
+    doc!("احشِ_يمين", "pad_start", "نصوص | Text", ["نص | text", "عرض | width", "حشو | fill?"],
+        "يحشو بداية النص حتى يبلغ العرض",
+        "Pads the start of the text up to the width",
+        ["احشِ_يمين(\"٧\"، ٣، \"٠\")"]),
+    doc!("احشِ_يسار", "pad_end", "نصوص | Text", ["نص | text", "عرض | width", "حشو | fill?"],
+        "يحشو نهاية النص حتى يبلغ العرض",
+        "Pads the end of the text up to the width",
+        ["احشِ_يسار(\"اسم\"، ١٠، \".\")"]),
+    doc!("وسّط", "center", "نصوص | Text", ["نص | text", "عرض | width", "حشو | fill?"],
+        "يضع النص في وسط العرض",
+        "Centers the text within the width",
+        ["وسّط(\"عنوان\"، ٢٠، \"*\")"]),
+    doc!("أزل_التشكيل", "strip_diacritics", "نصوص | Text", ["نص | text"],
+        "يحذف الحركات والتنوين والشدة وعلامات التلاوة من النص",
+        "Removes harakat, tanween, shadda and Quranic marks from the text",
+        ["أزل_التشكيل(\"مُحَمَّد\")"]),
+    doc!("طبع_نص", "normalize_arabic", "نصوص | Text", ["نص | text", "خيارات | options?"],
+        "يوحد أشكال الألف والياء والتاء المربوطة ويحذف التطويل ويضم المسافات؛ يعطل قاموس الخيارات أي تحويل منها",
+        "Unifies alef forms, ى and ة, drops tatweel and collapses whitespace; the options map turns any of these off",
+        ["طبع_نص(\"إسلامـــية\")", "طبع_نص(\"مدرسة\"، قاموس(\"تاء_مربوطة\"، خطأ))"]),
+    doc!("وحد_الأرقام", "normalize_digits", "نصوص | Text", ["نص | text", "أرقام | digits"],
+        "يحول كل الأرقام في النص إلى أرقام عربية أو لاتينية",
+        "Converts every digit in the text to Arabic-Indic or ASCII digits",
+        ["وحد_الأرقام(\"الصفحة 12\"، \"عربي\")", "وحد_الأرقام(\"١٤٤٥هـ\"، \"ascii\")"]),
+    doc!("رمز_حرف", "char_code", "نصوص | Text", ["حرف | character"],
+        "رمز يونيكود للحرف",
+        "The Unicode code point of a character",
+        ["رمز_حرف(\"أ\")"]),
+    doc!("حرف_من_رمز", "char_from_code", "نصوص | Text", ["رمز | code"],
+        "الحرف الذي يمثله رمز يونيكود",
+        "The character for a Unicode code point",
+        ["حرف_من_رمز(١٥٧٥)"]),
+    doc!("تطابق؟", "regex_match", "نصوص | Text", ["نص | text", "نمط | pattern"],
+        "هل يطابق النص التعبير النمطي",
+        "Whether the text matches a regular expression",
+        ["تطابق؟(\"abc123\"، \"[0-9]+\")"]),
+    doc!("ابحث_نمط", "regex_find_all", "نصوص | Text", ["نص | text", "نمط | pattern"],
+        "قائمة بكل المطابقات للتعبير النمطي",
+        "A list of every match of a regular expression",
+        ["ابحث_نمط(\"a1b22\"، \"[0-9]+\")"]),
+    doc!("استبدل_نمط", "regex_replace", "نصوص | Text", ["نص | text", "نمط | pattern", "بديل | replacement"],
+        "يستبدل كل مطابقات التعبير النمطي",
+        "Replaces every match of a regular expression",
+        ["استبدل_نمط(\"a1b2\"، \"[0-9]\"، \"#\")"]),
+    doc!("ترميز٦٤", "base64_encode", "ترميز | Encoding", ["نص | text"],
+        "يرمز النص بصيغة base64",
+        "Encodes the text as base64",
+        ["ترميز٦٤(\"مرحبا\")"]),
+    doc!("فك٦٤", "base64_decode", "ترميز | Encoding", ["نص | text"],
+        "يفك ترميز base64 إلى نص",
+        "Decodes base64 back to text",
+        ["فك٦٤(\"2YXYsdit2KjYpw==\")"]),
+    doc!("نص_إلى_بايتات", "to_bytes", "ترميز | Encoding", ["نص | text", "ترميز | encoding?"],
+        "بايتات النص بترميز utf-8، أو windows-1256 للملفات العربية القديمة",
+        "The bytes of the text in utf-8, or windows-1256 for older Arabic files",
+        ["نص_إلى_بايتات(\"سلام\"، \"windows-1256\")"]),
+    doc!("بايتات_إلى_نص", "from_bytes", "ترميز | Encoding", ["بايتات | bytes", "ترميز | encoding?"],
+        "النص الذي تمثله البايتات بترميز utf-8 أو windows-1256",
+        "The text the bytes hold in utf-8 or windows-1256",
+        ["بايتات_إلى_نص(اقرأ_بايتات(\"قديم.csv\")، \"windows-1256\")"]),
+    doc!("تجزئة", "sha256", "ترميز | Encoding", ["نص | text"],
+        "بصمة SHA-256 للنص بالنظام الست عشري",
+        "The hex SHA-256 digest of the text",
+        ["تجزئة(\"abc\")"]),
+    doc!("تجزئة_ملف", "sha256_file", "ترميز | Encoding", ["مسار | path"],
+        "بصمة SHA-256 لمحتوى ملف",
+        "The hex SHA-256 digest of a file's contents",
+        ["تجزئة_ملف(\"data.txt\")"]),
+    doc!("معرف_فريد", "uuid", "ترميز | Encoding", [],
+        "معرف UUID عشوائي جديد",
+        "A new random UUID",
+        ["معرف_فريد()"]),
+    doc!("تاريخ_اليوم", "today", "ملفات ونظام | Files & System", [],
+        "تاريخ اليوم بصيغة YYYY-MM-DD",
+        "Today's date as YYYY-MM-DD",
+        ["تاريخ_اليوم()"]),
+    doc!("تاريخ_هجري", "hijri_today", "ملفات ونظام | Files & System", [],
+        "تاريخ اليوم الهجري بصيغة YYYY-MM-DD حسب التقويم الهجري الحسابي",
+        "Today's Hijri date as YYYY-MM-DD, by the tabular Hijri calendar",
+        ["تاريخ_هجري()"]),
+    doc!("إلى_هجري", "to_hijri", "ملفات ونظام | Files & System", ["تاريخ | date"],
+        "يحول تاريخاً ميلادياً YYYY-MM-DD إلى هجري",
+        "Converts a Gregorian YYYY-MM-DD date to Hijri",
+        ["إلى_هجري(\"2024-03-11\")"]),
+    doc!("من_هجري", "from_hijri", "ملفات ونظام | Files & System", ["تاريخ | date"],
+        "يحول تاريخاً هجرياً YYYY-MM-DD إلى ميلادي",
+        "Converts a Hijri YYYY-MM-DD date to Gregorian",
+        ["من_هجري(\"١٤٤٥-٠٩-٠١\")"]),
+    doc!("اسم_الشهر_الهجري", "hijri_month_name", "ملفات ونظام | Files & System", ["رقم | number"],
+        "اسم الشهر الهجري من ١ (محرم) إلى ١٢ (ذو الحجة)",
+        "The Arabic name of Hijri month 1 (Muharram) to 12 (Dhu al-Hijjah)",
+        ["اسم_الشهر_الهجري(٩)"]),
+    doc!("نفذ_أمر", "system", "ملفات ونظام | Files & System", ["أمر | command"],
+        "ينفذ أمراً في الصدفة ويعيد مخرجه",
+        "Runs a shell command and returns its output",
+        ["نفذ_أمر(\"echo hi\")"]),
+    doc!("نفذ_أمر_بمدخل", "system_with_io", "ملفات ونظام | Files & System", ["أمر | command", "مدخل | input"],
+        "ينفذ أمراً ويمرر له نصاً على مدخله القياسي",
+        "Runs a command with the text on its standard input",
+        ["نفذ_أمر_بمدخل(\"sort\"، \"ب\\nأ\")"]),
+    doc!("نفذ_أمر_بمدخل_كامل", "system_with_io_full", "ملفات ونظام | Files & System", ["أمر | command", "مدخل | input"],
+        "مثل نفذ_أمر_بمدخل، ويعيد قاموساً فيه مخرج وأخطاء ورمز_الخروج ونجح",
+        "Like system_with_io, returning a map of stdout (مخرج), stderr (أخطاء), exit code (رمز_الخروج) and success (نجح)",
+        ["نفذ_أمر_بمدخل_كامل(\"sort\"، \"ب\\nأ\")[\"مخرج\"]"]),
+    doc!("اقرأ_ملف", "read_file", "ملفات ونظام | Files & System", ["مسار | path"],
+        "محتوى الملف نصاً",
+        "The contents of a file as text",
+        ["اقرأ_ملف(\"notes.txt\")"]),
+    doc!("اقرأ_بايتات", "read_bytes", "ملفات ونظام | Files & System", ["مسار | path"],
+        "محتوى الملف بايتات كما هو، لملفات ليست نصاً",
+        "The contents of a file as raw bytes, for files that aren't text",
+        ["اقرأ_بايتات(\"صورة.png\")"]),
+    doc!("اقرأ_ملف_بترميز", "read_file_encoding", "ملفات ونظام | Files & System", ["مسار | path", "ترميز | encoding"],
+        "محتوى الملف نصاً بترميز utf-8 أو windows-1256 أو iso-8859-6",
+        "The contents of a file as text in utf-8, windows-1256 or iso-8859-6",
+        ["اقرأ_ملف_بترميز(\"قديم.txt\"، \"windows-1256\")"]),
+    doc!("اكتب_ملف_بترميز", "write_file_encoding", "ملفات ونظام | Files & System", ["مسار | path", "نص | text", "ترميز | encoding"],
+        "يكتب النص في الملف بالترميز المعطى ويستبدل محتواه",
+        "Writes the text to a file in the given encoding, replacing its contents",
+        ["اكتب_ملف_بترميز(\"للبرنامج_القديم.txt\"، \"مرحبا\"، \"windows-1256\")"]),
+    doc!("خمّن_الترميز", "detect_encoding", "ملفات ونظام | Files & System", ["مسار | path"],
+        "قاموس فيه ترميز الملف المرجح (ترميز) ومدى الثقة فيه من ٠ إلى ١ (ثقة)",
+        "A map of the file's likely encoding (ترميز) and the confidence in it from 0 to 1 (ثقة)",
+        ["خمّن_الترميز(\"قديم.txt\")[\"ترميز\"]"]),
+    doc!("اكتب_ملف", "write_file", "ملفات ونظام | Files & System", ["مسار | path", "نص | text"],
+        "يكتب النص في الملف ويستبدل محتواه",
+        "Writes the text to a file, replacing its contents",
+        ["اكتب_ملف(\"notes.txt\"، \"مرحبا\")"]),
+    doc!("قائمة_ملفات", "list_files", "ملفات ونظام | Files & System", ["مجلد | directory"],
+        "أسماء الملفات في المجلد",
+        "The names of the files in a directory",
+        ["قائمة_ملفات(\".\")"]),
+    doc!("وصل_مسار", "join_path", "ملفات ونظام | Files & System", ["...أجزاء | ...parts"],
+        "يصل أجزاء مسار بفاصل واحد؛ الجزء المطلق يبدأ المسار من جديد",
+        "Joins path parts with one separator; an absolute part starts over",
+        ["وصل_مسار(\"بيانات\"، \"طلاب.csv\")"]),
+    doc!("وسع_مسار", "expand_path", "ملفات ونظام | Files & System", ["مسار | path"],
+        "يستبدل ~ في أول المسار بمجلد المنزل، و$VAR أو ${VAR} بقيمة متغير البيئة",
+        "Replaces a leading ~ with the home directory and $VAR or ${VAR} with the environment variable",
+        ["وسع_مسار(\"~/بيانات.txt\")"]),
+    doc!("اسم_الملف", "basename", "ملفات ونظام | Files & System", ["مسار | path"],
+        "آخر جزء في المسار، بفاصل / أو \\",
+        "The last component of a path, separated by / or \\",
+        ["اسم_الملف(\"بيانات/طلاب.csv\")"]),
+    doc!("مجلد_الملف", "dirname", "ملفات ونظام | Files & System", ["مسار | path"],
+        "المسار دون آخر جزء فيه",
+        "The path without its last component",
+        ["مجلد_الملف(\"بيانات/طلاب.csv\")"]),
+    doc!("متغير_بيئة", "env_var", "ملفات ونظام | Files & System", ["اسم | name"],
+        "قيمة متغير البيئة، أو لا شيء إن لم يكن موجوداً",
+        "The value of an environment variable, or nil when it is unset",
+        ["متغير_بيئة(\"HOME\")"]),
+    doc!("عين_متغير_بيئة", "set_env", "ملفات ونظام | Files & System", ["اسم | name", "قيمة | value"],
+        "يعين متغير بيئة للعملية الحالية",
+        "Sets an environment variable for the current process",
+        ["عين_متغير_بيئة(\"LANG\"، \"ar\")"]),
+    doc!("مجلد_حالي", "cwd", "ملفات ونظام | Files & System", [],
+        "مسار مجلد العمل الحالي",
+        "The current working directory",
+        ["مجلد_حالي()"]),
+    doc!("غير_مجلد", "change_dir", "ملفات ونظام | Files & System", ["مسار | path"],
+        "يغير مجلد العمل الحالي",
+        "Changes the current working directory",
+        ["غير_مجلد(\"..\")"]),
+    doc!("معرف_عملية", "pid", "ملفات ونظام | Files & System", [],
+        "رقم العملية الحالية",
+        "The current process id",
+        ["معرف_عملية()"]),
+    doc!("وسائط", "args", "ملفات ونظام | Files & System", [],
+        "الوسائط الممررة للبرنامج بعد اسم الملف",
+        "The arguments passed to the script after its file name",
+        ["وسائط()[٠]"]),
+    doc!("اخرج", "exit", "ملفات ونظام | Files & System", ["رمز | code?"],
+        "ينهي البرنامج برمز خروج اختياري",
+        "Ends the program with an optional exit code",
+        ["اخرج(١)"]),
+    doc!("معلومات_النظام", "system_info", "ملفات ونظام | Files & System", ["تحديث | refresh?"],
+        "قاموس بمعلومات نظام التشغيل والمعمارية، يُحفظ بعد أول استدعاء ما لم يكن تحديث صحيحاً",
+        "A map describing the operating system and architecture, saved after the first call unless refresh is true",
+        ["معلومات_النظام()[\"os\"]", "معلومات_النظام(صحيح)"]),
+    doc!("أكد؟", "confirm", "تفاعل | Input", ["سؤال | prompt"],
+        "يسأل المستخدم سؤال نعم/لا ويعيد الجواب",
+        "Asks the user a yes/no question and returns the answer",
+        ["أكد؟(\"متابعة؟\")"]),
+    doc!("اختر", "choose", "تفاعل | Input", ["سؤال | prompt", "خيارات | options"],
+        "يعرض قائمة مرقمة ويعيد الخيار الذي اختاره المستخدم",
+        "Shows a numbered menu and returns the option the user picks",
+        ["اختر(\"اللون؟\"، [\"أحمر\"، \"أزرق\"])"]),
+    doc!("كلمة_سر", "password", "تفاعل | Input", ["سؤال | prompt"],
+        "يقرأ نصاً دون إظهاره على الشاشة",
+        "Reads text without echoing it",
+        ["كلمة_سر(\"كلمة المرور: \")"]),
+    doc!("مساعدة", "help", "الجلسة | Session", ["اسم | name?"],
+        "توثيق دالة مدمجة أو دالة معرفة، أو قائمة الدوال المدمجة بلا وسائط",
+        "Documentation for a builtin or a defined function, or every builtin when called without arguments",
+        ["مساعدة(\"اطبع\")", "مساعدة()"]),
+    doc!("قائمة_الدوال", "list_builtins", "الجلسة | Session", [],
+        "أسماء الدوال المدمجة بالعربية، أو بالإنجليزية عند الاستدعاء بـ list_builtins",
+        "The names of the builtins in English, or in Arabic when called as قائمة_الدوال",
+        ["قائمة_الدوال()"]),
+    doc!("المتغيرات_العامة", "globals", "الجلسة | Session", [],
+        "قاموس بنسخ المتغيرات العامة، ولو استُدعيت داخل دالة",
+        "A map of copies of the global variables, even when called inside a function",
+        ["المتغيرات_العامة()"]),
+    doc!("المتغيرات_المحلية", "locals", "الجلسة | Session", [],
+        "قاموس بنسخ معاملات الدالة الحالية ومتغيراتها، أو المتغيرات العامة خارج الدوال",
+        "A map of copies of the current function's parameters and variables, or the globals outside any function",
+        ["دالة مربع(س) { ارجع المتغيرات_المحلية() }"]),
+    doc!("تذكر", "cache_get", "الجلسة | Session", ["مفتاح | key"],
+        "القيمة المخزنة بـ خزّن تحت المفتاح، أو لا_شيء إن لم توجد أو انتهت صلاحيتها",
+        "The value stored with cache_set under the key, or nil when there is none or it expired",
+        ["تذكر(\"الطقس\")"]),
+    doc!("خزّن", "cache_set", "الجلسة | Session", ["مفتاح | key", "قيمة | value", "ثوانٍ | ttl_seconds?"],
+        "يخزن قيمة تبقى بين التشغيلات في IQRA_HOME/cache/script_cache.json، لمدة الثواني المعطاة إن وُجدت",
+        "Stores a value that lasts across runs in IQRA_HOME/cache/script_cache.json, for the given seconds if any",
+        ["خزّن(\"الطقس\"، نفذ_أمر(\"curl -s wttr.in\")، ٣٦٠٠)"]),
+    doc!("امسح_الذاكرة", "cache_clear", "الجلسة | Session", [],
+        "يمسح كل القيم المخزنة بـ خزّن",
+        "Removes every value stored with cache_set",
+        ["امسح_الذاكرة()"]),
+    doc!("احذف_دالة", "undefine", "الجلسة | Session", ["اسم | name"],
+        "يحذف دالة معرفة في الجلسة ويعيد هل كانت موجودة",
+        "Removes a function defined in the session and returns whether it existed",
+        ["احذف_دالة(\"طول\")"]),
+    doc!("ملف_الأداء", "profile_report", "الجلسة | Session", [],
+        "عدد استدعاءات كل دالة ووقتها عند تفعيل قياس الأداء",
+        "Call counts and timings per function when profiling is on",
+        ["ملف_الأداء()"]),
+    doc!("احفظ_الحالة", "save_state", "الجلسة | Session", ["مسار | path"],
+        "يحفظ متغيرات الجلسة ودوالها في ملف",
+        "Saves the session's variables and functions to a file",
+        ["احفظ_الحالة(\"session.json\")"]),
+    doc!("استرجع_الحالة", "restore_state", "الجلسة | Session", ["مسار | path"],
+        "يستعيد جلسة محفوظة بـ احفظ_الحالة",
+        "Restores a session saved with save_state",
+        ["استرجع_الحالة(\"session.json\")"]),
+];
+
+/// Looks a builtin up by either of its names or an alias.
+pub fn builtin_doc(name: &str) -> Option<&'static BuiltinDoc> {
+    let name = canonical_builtin(name)?;
+    BUILTIN_DOCS.iter().find(|doc| doc.arabic == name)
+}
+
+impl BuiltinDoc {
+    /// The map `مساعدة("name")` returns.
+    pub fn to_value(&self) -> Value {
+        let list = |items: &[&str]| {
+            Value::List(items.iter().map(|item| Value::String(item.to_string())).collect())
+        };
+        Value::Map(HashMap::from([
+            ("اسم".to_string(), Value::String(self.arabic.to_string())),
+            ("name".to_string(), Value::String(self.english.to_string())),
+            ("وصف".to_string(), Value::String(self.description_ar.to_string())),
+            ("description".to_string(), Value::String(self.description_en.to_string())),
+            ("معاملات".to_string(), list(self.params)),
+            ("params".to_string(), list(self.params)),
+            ("أمثلة".to_string(), list(self.examples)),
+            ("examples".to_string(), list(self.examples)),
+        ]))
+    }
+}
+
+/// The map `مساعدة()` returns: each category to its builtins, written
+/// `عربي | english`.
+pub fn help_index() -> Value {
+    let mut index: HashMap<String, Value> =
+        CATEGORIES.iter().map(|category| (category.to_string(), Value::List(Vec::new()))).collect();
+    for doc in BUILTIN_DOCS {
+        if let Some(Value::List(names)) = index.get_mut(doc.category) {
+            names.push(Value::String(format!("{} | {}", doc.arabic, doc.english)));
+        }
+    }
+    Value::Map(index)
+}
+
+/// Renders a value returned by `مساعدة` for the REPL, one fact per line.
+/// Returns `None` for any other value.
+pub fn render_help(value: &Value) -> Option<String> {
+    let Value::Map(map) = value else { return None };
+    let text = |key: &str| match map.get(key) {
+        Some(Value::String(text)) => Some(text.as_str()),
+        _ => None,
+    };
+    let items = |key: &str| match map.get(key) {
+        Some(Value::List(items)) => items.iter().map(|item| format!("{}", item)).collect(),
+        _ => Vec::new(),
+    };
+
+    if let (Some(arabic), Some(english), Some(description_ar), Some(description_en)) =
+        (text("اسم"), text("name"), text("وصف"), text("description"))
+    {
+        let mut out =
+            format!("{} | {}\n  {}\n  {}\n", arabic, english, description_ar, description_en);
+        let params = items("معاملات");
+        out.push_str("المعاملات | Params:");
+        if params.is_empty() {
+            out.push_str(" -\n");
+        } else {
+            out.push('\n');
+            params.iter().for_each(|param| out.push_str(&format!("  {}\n", param)));
+        }
+        out.push_str("أمثلة | Examples:\n");
+        items("أمثلة").iter().for_each(|example| out.push_str(&format!("  {}\n", example)));
+        return Some(out.trim_end().to_string());
+    }
+
+    if map.len() == CATEGORIES.len()
+        && CATEGORIES.iter().all(|category| map.contains_key(*category))
+    {
+        let sections: Vec<String> = CATEGORIES
+            .iter()
+            .map(|category| format!("{}:\n  {}", category, items(category).join("\n  ")))
+            .collect();
+        return Some(sections.join("\n"));
+    }
+    None
+}
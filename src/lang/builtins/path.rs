@@ -0,0 +1,130 @@
+//! المسارات النصية | Path strings behind `وصل_مسار`, `اسم_الملف` and
+//! `مجلد_الملف`. `std::path` only splits on the host's separator, so a
+//! script written on Windows would behave differently on Linux; these
+//! functions accept both `/` and `\` everywhere and never touch the disk.
+//! `expand` is the `~` and `$VAR` step behind `وسع_مسار` and the file
+//! builtins.
+
+fn is_separator(ch: char) -> bool {
+    ch == '/' || ch == '\\'
+}
+
+/// Whether `path` starts at a root: a separator or a drive like `C:`.
+pub fn is_absolute(path: &str) -> bool {
+    let mut chars = path.chars();
+    match (chars.next(), chars.next()) {
+        (Some(first), _) if is_separator(first) => true,
+        (Some(drive), Some(':')) => drive.is_ascii_alphabetic(),
+        _ => false,
+    }
+}
+
+/// The separator to add after `path`: a backslash when it only uses
+/// backslashes, otherwise `/`.
+fn separator_for(path: &str) -> char {
+    if path.contains('\\') && !path.contains('/') { '\\' } else { '/' }
+}
+
+/// Joins `parts` like `Path::join`: an absolute part replaces everything
+/// before it.
+pub fn join(parts: &[&str]) -> String {
+    let mut joined = String::new();
+    for part in parts {
+        if joined.is_empty() || is_absolute(part) {
+            joined = part.to_string();
+        } else if !part.is_empty() {
+            if !joined.ends_with(is_separator) {
+                joined.push(separator_for(&joined));
+            }
+            joined.push_str(part);
+        }
+    }
+    joined
+}
+
+/// `path` without trailing separators, keeping a lone root.
+fn trim_end(path: &str) -> &str {
+    let trimmed = path.trim_end_matches(is_separator);
+    if trimmed.is_empty() && !path.is_empty() { &path[..1] } else { trimmed }
+}
+
+/// The last component of `path`, or `""` for a root.
+pub fn basename(path: &str) -> &str {
+    let path = trim_end(path);
+    if path.len() == 1 && path.starts_with(is_separator) {
+        return "";
+    }
+    match path.rfind(is_separator) {
+        Some(index) => &path[index + 1..],
+        None => path.strip_prefix(drive(path)).unwrap_or(path),
+    }
+}
+
+/// Everything before the last component of `path`: `""` when there is no
+/// directory part, and the root itself for a file at the root.
+pub fn dirname(path: &str) -> &str {
+    let path = trim_end(path);
+    match path.rfind(is_separator) {
+        // Keep the separator of a root, as in `/` or `C:\`.
+        Some(index) if path[..index].trim_end_matches(is_separator) == drive(path) => {
+            &path[..index + 1]
+        }
+        Some(index) => path[..index].trim_end_matches(is_separator),
+        None => drive(path),
+    }
+}
+
+/// The `C:` drive prefix of `path`, or `""`.
+fn drive(path: &str) -> &str {
+    if is_absolute(path) && !path.starts_with(is_separator) { &path[..2] } else { "" }
+}
+
+/// Whether `ch` can be part of a variable name after `$`.
+fn is_name_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_'
+}
+
+/// `path` with a leading `~` replaced by `home` and each `$VAR` or
+/// `${VAR}` by what `env` gives for it. A variable `env` doesn't know is
+/// passed to `unset` and expands to nothing. A `~` with no home, `~user`
+/// and a `$` not followed by a name are kept as written.
+pub fn expand(
+    path: &str,
+    home: Option<&str>,
+    env: &dyn Fn(&str) -> Option<String>,
+    unset: &mut dyn FnMut(&str),
+) -> String {
+    let mut expanded = String::with_capacity(path.len());
+    let mut rest = path;
+    if let (Some(after), Some(home)) = (path.strip_prefix('~'), home)
+        && (after.is_empty() || after.starts_with(is_separator))
+    {
+        expanded.push_str(home);
+        rest = after;
+    }
+    while let Some(dollar) = rest.find('$') {
+        expanded.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+        let (name, remainder) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) if braced[..end].chars().all(is_name_char) => {
+                    (&braced[..end], &braced[end + 1..])
+                }
+                _ => ("", after),
+            },
+            None => after.split_at(after.find(|ch| !is_name_char(ch)).unwrap_or(after.len())),
+        };
+        if name.is_empty() {
+            expanded.push('$');
+            rest = after;
+            continue;
+        }
+        match env(name) {
+            Some(value) => expanded.push_str(&value),
+            None => unset(name),
+        }
+        rest = remainder;
+    }
+    expanded.push_str(rest);
+    expanded
+}
@@ -0,0 +1,56 @@
+//! الإحصاء | Statistics behind `وسيط`, `منوال`, `تباين`, `انحراف_معياري`
+//! and `مئين`. The callers check that every element is a number; these
+//! functions return `None` when there are too few values.
+
+use std::collections::HashMap;
+
+use crate::lang::value::Value;
+
+/// The middle value, or the mean of the two middle values.
+pub fn median(values: &[f64]) -> Option<f64> {
+    percentile(values, 50.0)
+}
+
+/// The value below which `p` percent of `values` fall, interpolating
+/// linearly between the two nearest ranks. `p` is between 0 and 100.
+pub fn percentile(values: &[f64], p: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+    Some(sorted[below] + (sorted[above] - sorted[below]) * (rank - below as f64))
+}
+
+/// The population variance, or with `sample` the sample variance, which
+/// divides by one less than the count and needs at least two values.
+pub fn variance(values: &[f64], sample: bool) -> Option<f64> {
+    let divisor = values.len().checked_sub(usize::from(sample)).filter(|n| *n > 0)?;
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    Some(values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / divisor as f64)
+}
+
+pub fn stddev(values: &[f64], sample: bool) -> Option<f64> {
+    variance(values, sample).map(f64::sqrt)
+}
+
+/// The most frequent numbers in `list`, in order of first appearance. More
+/// than one means a tie; none means the list was empty. `1` and `1.0` count
+/// as the same number.
+pub fn modes(list: &[Value]) -> Vec<&Value> {
+    let mut counts: HashMap<u64, (usize, usize)> = HashMap::new();
+    for (index, value) in list.iter().enumerate() {
+        // Adding 0.0 turns -0.0 into 0.0 so both share a key.
+        let key = (value.as_number().unwrap_or(f64::NAN) + 0.0).to_bits();
+        counts.entry(key).or_insert((index, 0)).1 += 1;
+    }
+    let Some(most) = counts.values().map(|(_, count)| *count).max() else {
+        return Vec::new();
+    };
+    let mut firsts: Vec<usize> =
+        counts.values().filter(|(_, count)| *count == most).map(|(first, _)| *first).collect();
+    firsts.sort();
+    firsts.into_iter().map(|index| &list[index]).collect()
+}
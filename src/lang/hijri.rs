@@ -0,0 +1,147 @@
+//! التقويم الهجري | The Hijri calendar behind `تاريخ_هجري`, `إلى_هجري`
+//! and `من_هجري`.
+//!
+//! Dates are converted with the tabular (arithmetic) Islamic calendar: a
+//! 30-year cycle of 354- and 355-day years counted from the civil epoch of
+//! 16 July 622 (Julian). It agrees with Umm al-Qura on most days and never
+//! needs the network, but may differ from a sighted month by a day.
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, Utc};
+use std::fmt;
+
+/// Month names, Muharram first.
+pub const MONTH_NAMES: [&str; 12] = [
+    "محرم",
+    "صفر",
+    "ربيع الأول",
+    "ربيع الآخر",
+    "جمادى الأولى",
+    "جمادى الآخرة",
+    "رجب",
+    "شعبان",
+    "رمضان",
+    "شوال",
+    "ذو القعدة",
+    "ذو الحجة",
+];
+
+/// Julian day number of 1 Muharram 1 AH.
+const EPOCH: i64 = 1_948_440;
+
+/// Julian day number of 1 January 1 CE in the proleptic Gregorian calendar,
+/// less one, so that adding chrono's `num_days_from_ce` gives the day number.
+const CE_OFFSET: i64 = 1_721_425;
+
+/// Source of today's date and the current time, replaceable so tests can
+/// pin the day.
+pub trait Clock: Send + Sync {
+    fn today(&self) -> NaiveDate;
+
+    /// The current instant. Clocks that only know the day return its start.
+    fn now(&self) -> DateTime<Utc> {
+        self.today().and_time(NaiveTime::MIN).and_utc()
+    }
+}
+
+/// The local date of the machine.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> NaiveDate {
+        Local::now().date_naive()
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Always the same day, and the start of it.
+pub struct FixedClock(pub NaiveDate);
+
+impl Clock for FixedClock {
+    fn today(&self) -> NaiveDate {
+        self.0
+    }
+}
+
+/// A day of the Hijri calendar. Displays as `YYYY-MM-DD`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HijriDate {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+/// Whether `year` has 355 days, with an extra day in Dhu al-Hijjah.
+pub fn is_leap_year(year: i64) -> bool {
+    (14 + 11 * year).rem_euclid(30) < 11
+}
+
+/// 30 days in odd months, 29 in even ones, and 30 in Dhu al-Hijjah of a
+/// leap year.
+pub fn days_in_month(year: i64, month: u32) -> u32 {
+    if month % 2 == 1 || (month == 12 && is_leap_year(year)) { 30 } else { 29 }
+}
+
+impl HijriDate {
+    /// A date, or `None` if the month or day doesn't exist. Years before
+    /// 1 AH are rejected.
+    pub fn new(year: i64, month: u32, day: u32) -> Option<Self> {
+        let valid = year >= 1
+            && (1..=12).contains(&month)
+            && (1..=days_in_month(year, month)).contains(&day);
+        valid.then_some(HijriDate { year, month, day })
+    }
+
+    /// Parses `YYYY-MM-DD` written with ASCII or Arabic-Indic digits.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = crate::lang::builtins::arabic::to_ascii_digits(text.trim());
+        let mut parts = text.split('-').map(|part| part.parse::<i64>().ok());
+        let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+        if parts.next().is_some() {
+            return None;
+        }
+        HijriDate::new(year, u32::try_from(month).ok()?, u32::try_from(day).ok()?)
+    }
+
+    fn julian_day(&self) -> i64 {
+        let month_days = (59 * (i64::from(self.month) - 1) + 1) / 2;
+        let year_days = (self.year - 1) * 354 + (3 + 11 * self.year).div_euclid(30);
+        i64::from(self.day) + month_days + year_days + EPOCH - 1
+    }
+
+    fn from_julian_day(day: i64) -> Option<Self> {
+        if day < EPOCH {
+            return None;
+        }
+        let year = (30 * (day - EPOCH) + 10646).div_euclid(30 * 354 + 11);
+        let first_of_year = HijriDate { year, month: 1, day: 1 }.julian_day();
+        // Months alternate 30 and 29 days, 59 days every two months.
+        let month = (2 * (day - first_of_year) / 59 + 1).min(12) as u32;
+        let first_of_month = HijriDate { year, month, day: 1 }.julian_day();
+        Some(HijriDate { year, month, day: (day - first_of_month + 1) as u32 })
+    }
+
+    /// The Hijri date of a Gregorian day, or `None` before 1 AH.
+    pub fn from_gregorian(date: NaiveDate) -> Option<Self> {
+        HijriDate::from_julian_day(i64::from(date.num_days_from_ce()) + CE_OFFSET)
+    }
+
+    /// The Gregorian day this date falls on.
+    pub fn to_gregorian(&self) -> Option<NaiveDate> {
+        let days = i32::try_from(self.julian_day() - CE_OFFSET).ok()?;
+        NaiveDate::from_num_days_from_ce_opt(days)
+    }
+
+    /// The month's Arabic name.
+    pub fn month_name(&self) -> &'static str {
+        MONTH_NAMES[self.month as usize - 1]
+    }
+}
+
+impl fmt::Display for HijriDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
@@ -1,8 +1,21 @@
 use std::fmt;
 use crate::lang::runtime::IqraError;
+use crate::lang::symbol::Symbol;
+use crate::tr;
 use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
 
 // الدوال المساعدة يجب أن تكون هنا ليتمكن الـ impl من استخدامها
+/// الكلمات المحجوزة | Keyword spellings recognized by `next_token`, for
+/// editor completion. Keep in sync with the keyword match there.
+pub const KEYWORDS: &[&str] = &[
+    "اذا", "إذا", "وإلا", "والا", "وإلاّ", "وإلّا", "بينما", "طالما", "صحيح", "خطأ", "و", "أو", "ليس",
+    "دالة", "ارجع", "جرب", "امسك", "عدا", "أخطاء", "طابق", "حالة", "افتراضي", "ثابت", "افعل", "توقف",
+    "استمر", "كرر", "if", "else", "elif", "while", "true", "false", "and", "or", "not", "function",
+    "return", "try", "catch", "errors", "match", "case", "default", "const", "do", "break",
+    "continue", "loop",
+];
+
 fn is_arabic_digit(ch: char) -> bool {
     matches!(ch, '٠'..='٩')
 }
@@ -24,24 +37,33 @@ fn arabic_to_ascii_digit(ch: char) -> char {
 }
 
 fn is_arabic_letter(ch: char) -> bool {
-    matches!(ch, '\u{0600}'..='\u{06FF}' | '\u{0750}'..='\u{077F}' | '\u{08A0}'..='\u{08FF}')
+    // The Arabic comma and semicolon are punctuation like ',' and ';', not
+    // part of identifiers.
+    ch != '،' && ch != '؛'
+        && matches!(ch, '\u{0600}'..='\u{06FF}' | '\u{0750}'..='\u{077F}' | '\u{08A0}'..='\u{08FF}')
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     // Error handling keywords (Arabic and English)
     Try,      // جرب / try
-    Catch,    // امسك / catch
-    Errors,   // أخطاء / errors
+    Catch,    // امسك / عدا / catch
+    Errors,   // أخطاء / errors, as in `امسك أخطاء "قسمة على صفر"`
     // Literals
     Number(f64),
+    Int(i64),
     String(String),
-    Identifier(String),
+    Identifier(Symbol),
 
     // Keywords (Arabic and English)
     If,    // اذا / إذا / if
-    Else,  // وإلا / والا / وإلاّ / else
-    While, // بينما / while
+    Else,  // وإلا / والا / وإلاّ / وإلّا / else
+    Elif,  // elif, the same as `وإلا اذا`
+    While, // بينما / طالما / while
+    Do,       // افعل / do
+    Break,    // توقف / break
+    Continue, // استمر / continue
+    Loop,     // كرر / loop
     True,  // صحيح / true
     False, // خطأ / false
     And,   // و / && / and
@@ -49,6 +71,12 @@ pub enum Token {
     Not,   // ليس / ! / not
     Function, // دالة / function
     Return,   // ارجع / return
+    Match,    // طابق / match
+    Case,     // حالة / case
+    Default,  // افتراضي / default
+    Const,    // ثابت / const
+    Safe,     // آمن / try?
+    Coalesce, // وإلا_قيمة / ??
 
     // Operators
     Plus,
@@ -73,6 +101,8 @@ pub enum Token {
     RightBracket,
     Comma,
     Semicolon,
+    Ellipsis, // ...
+    Dot,      // .
 
     // Special
     Newline,
@@ -84,11 +114,17 @@ impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Token::Number(n) => write!(f, "{}", n),
+            Token::Int(n) => write!(f, "{}", n),
             Token::String(s) => write!(f, "\"{}\"", s),
             Token::Identifier(id) => write!(f, "{}", id),
             Token::If => write!(f, "if"),
             Token::Else => write!(f, "else"),
+            Token::Elif => write!(f, "elif"),
             Token::While => write!(f, "while"),
+            Token::Do => write!(f, "do"),
+            Token::Break => write!(f, "break"),
+            Token::Continue => write!(f, "continue"),
+            Token::Loop => write!(f, "loop"),
             Token::True => write!(f, "true"),
             Token::False => write!(f, "false"),
             Token::And => write!(f, "and"),
@@ -96,6 +132,12 @@ impl fmt::Display for Token {
             Token::Not => write!(f, "not"),
             Token::Function => write!(f, "function"),
             Token::Return => write!(f, "return"),
+            Token::Match => write!(f, "match"),
+            Token::Case => write!(f, "case"),
+            Token::Default => write!(f, "default"),
+            Token::Safe => write!(f, "try?"),
+            Token::Coalesce => write!(f, "??"),
+            Token::Const => write!(f, "const"),
             Token::Try => write!(f, "try"),
             Token::Catch => write!(f, "catch"),
             Token::Errors => write!(f, "errors"),
@@ -119,6 +161,8 @@ impl fmt::Display for Token {
             Token::RightBracket => write!(f, "]"),
             Token::Comma => write!(f, ","),
             Token::Semicolon => write!(f, ";"),
+            Token::Ellipsis => write!(f, "..."),
+            Token::Dot => write!(f, "."),
             Token::Newline => write!(f, "\\n"),
             Token::Eof => write!(f, "EOF"),
         }
@@ -126,27 +170,126 @@ impl fmt::Display for Token {
 }
 
 
+/// موضع في النص المصدري | Position of a token or statement in the source.
+/// Lines and columns are 1-based; the end position is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Span {
+    pub start_line: usize,
+    pub start_col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
+}
+
+impl Span {
+    /// Returns a span covering from the start of `self` to the end of `end`.
+    pub fn to(self, end: Span) -> Span {
+        Span {
+            start_line: self.start_line,
+            start_col: self.start_col,
+            end_line: end.end_line,
+            end_col: end.end_col,
+        }
+    }
+}
+
+/// خريطة المصدر | Maps spans back to the source text they came from.
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    source: String,
+    /// Byte offset where each line starts.
+    line_starts: Vec<usize>,
+}
+
+impl SourceMap {
+    pub fn new(source: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        SourceMap { source: source.to_string(), line_starts }
+    }
+
+    /// The source text covered by `span`, or `None` if the span lies
+    /// outside the source.
+    pub fn text(&self, span: Span) -> Option<&str> {
+        let start = self.offset(span.start_line, span.start_col)?;
+        let end = self.offset(span.end_line, span.end_col)?;
+        self.source.get(start..end)
+    }
+
+    /// The whole line `line` (1-based), without its newline.
+    pub fn line(&self, line: usize) -> Option<&str> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let rest = &self.source[start..];
+        Some(rest.split('\n').next().unwrap_or(rest))
+    }
+
+    /// Byte offset of a 1-based line and character column. The column may be
+    /// one past the end of the line, which is where an exclusive end points.
+    fn offset(&self, line: usize, col: usize) -> Option<usize> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let rest = &self.source[start..];
+        let index = col.checked_sub(1)?;
+        match rest.char_indices().nth(index) {
+            Some((i, _)) => Some(start + i),
+            None if rest.chars().count() == index => Some(self.source.len()),
+            None => None,
+        }
+    }
+}
+
+/// تعليق في النص المصدري | A `//` comment kept by the lexer.
+/// `doc` is set for `///` and `//!` comments; `text` excludes the marker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    pub line: usize,
+    pub text: String,
+    pub doc: bool,
+}
+
 #[derive(Debug)]
 pub struct Lexer {
     input: Vec<char>,
     position: usize,
     current_char: Option<char>,
     line: usize,
+    column: usize,
+    token_start: (usize, usize),
+    comments: Vec<Comment>,
 }
 
 
 impl Lexer {
+    /// A byte order mark at the start of `input`, as Windows editors write,
+    /// is skipped.
     pub fn new(input: &str) -> Self {
+        let input = input.strip_prefix('\u{FEFF}').unwrap_or(input);
         let chars: Vec<char> = input.chars().collect();
         let current_char = chars.first().copied();
-        Self { input: chars, position: 0, current_char, line: 1 }
+        Self { input: chars, position: 0, current_char, line: 1, column: 1, token_start: (1, 1), comments: Vec::new() }
+    }
+
+    /// Comments seen so far, in source order.
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
     }
 
+    /// Returns the span of the token most recently returned by `next_token`.
+    pub fn token_span(&self) -> Span {
+        Span {
+            start_line: self.token_start.0,
+            start_col: self.token_start.1,
+            end_line: self.line,
+            end_col: self.column,
+        }
+    }
 
     fn advance(&mut self) {
         self.position += 1;
         if self.current_char == Some('\n') {
             self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
         }
         self.current_char = self.input.get(self.position).copied();
     }
@@ -166,22 +309,36 @@ impl Lexer {
     }
 
     fn skip_comment(&mut self) {
+        let line = self.line;
+        let mut raw = String::new();
         // Skip until end of line
         while let Some(ch) = self.current_char {
             if ch == '\n' {
                 break;
             }
+            raw.push(ch);
             self.advance();
         }
+        let (doc, text) = match raw.strip_prefix("///").or_else(|| raw.strip_prefix("//!")) {
+            Some(rest) => (true, rest),
+            None => (false, &raw[2..]),
+        };
+        let text = text.strip_prefix(' ').unwrap_or(text).trim_end().to_string();
+        self.comments.push(Comment { line, text, doc });
     }
 
-    fn read_number(&mut self) -> Result<f64> {
+    /// Reads a numeric literal: an `Int` without a decimal point, otherwise a `Number`.
+    fn read_number(&mut self) -> Result<Token> {
         let mut num_str = String::new();
-        let start_line = self.line;
+        let (start_line, start_col) = (self.line, self.column);
         while let Some(ch) = self.current_char {
             if ch.is_ascii_digit() || ch == '.' {
                 num_str.push(ch);
                 self.advance();
+            } else if ch == '٫' {
+                // Arabic decimal separator, as in ٣٫١٤
+                num_str.push('.');
+                self.advance();
             } else if is_arabic_digit(ch) {
                 num_str.push(arabic_to_ascii_digit(ch));
                 self.advance();
@@ -189,26 +346,45 @@ impl Lexer {
                 break;
             }
         }
+        if !num_str.contains('.') {
+            return num_str.parse().map(Token::Int).map_err(|_| anyhow!(IqraError {
+                kind: "خطأ في الرقم | Number Error".to_string(),
+                message_ar: format!("العدد الصحيح '{}' أكبر من المدى المسموح", num_str),
+                message_en: format!("Integer literal '{}' is out of range", num_str),
+                suggestion: Some(tr!("suggest.decimal_point")),
+                line: Some(start_line),
+                column: Some(start_col),
+                source_name: None,
+            }));
+        }
         match num_str.parse() {
-            Ok(n) => Ok(n),
+            Ok(n) => Ok(Token::Number(n)),
             Err(_) => Err(anyhow!(IqraError {
                 kind: "خطأ في الرقم | Number Error".to_string(),
                 message_ar: format!("تعذر تحويل '{}' إلى رقم.", num_str),
                 message_en: format!("Failed to parse '{}' as a number.", num_str),
-                suggestion: Some("تأكد من صحة الرقم المدخل | Check the input number".to_string()),
+                suggestion: Some(tr!("suggest.check_number")),
                 line: Some(start_line),
+                column: Some(start_col),
+                source_name: None,
             }))
         }
     }
 
     fn read_string(&mut self) -> Result<String> {
         let mut string = String::new();
-        let start_line = self.line;
+        let (start_line, start_col) = (self.line, self.column);
+        // A bad escape is reported once the whole string is read, so lexing
+        // resumes after the closing quote rather than inside the string.
+        let mut escape_error = None;
         self.advance(); // Skip opening quote
         while let Some(ch) = self.current_char {
             if ch == '"' {
                 self.advance(); // Skip closing quote
-                return Ok(string);
+                return match escape_error {
+                    Some(err) => Err(err),
+                    None => Ok(string),
+                };
             } else if ch == '\\' {
                 self.advance();
                 if let Some(escaped) = self.current_char {
@@ -216,8 +392,16 @@ impl Lexer {
                         'n' => string.push('\n'),
                         't' => string.push('\t'),
                         'r' => string.push('\r'),
+                        '0' => string.push('\0'),
                         '\\' => string.push('\\'),
                         '"' => string.push('"'),
+                        'u' => match self.read_unicode_escape() {
+                            Ok(ch) => string.push(ch),
+                            Err(err) => {
+                                escape_error.get_or_insert(err);
+                                continue;
+                            }
+                        },
                         _ => {
                             string.push('\\');
                             string.push(escaped);
@@ -234,11 +418,62 @@ impl Lexer {
             kind: "خطأ في السلسلة | String Error".to_string(),
             message_ar: "سلسلة غير منتهية بعلامة اقتباس.".to_string(),
             message_en: "Unterminated string literal.".to_string(),
-            suggestion: Some("تأكد من إغلاق السلسلة بعلامة اقتباس | Close the string with a quote".to_string()),
+            suggestion: Some(tr!("suggest.close_string")),
             line: Some(start_line),
+            column: Some(start_col),
+            source_name: None,
         }))
     }
 
+    /// Reads the `{XXXX}` of a `\u{XXXX}` escape, with `current_char` on the
+    /// `u`, and leaves `current_char` on the closing brace.
+    fn read_unicode_escape(&mut self) -> Result<char> {
+        let (line, column) = (self.line, self.column);
+        let invalid = |message_ar: String, message_en: String| {
+            anyhow!(IqraError {
+                kind: "خطأ في السلسلة | String Error".to_string(),
+                message_ar,
+                message_en,
+                suggestion: Some(tr!("suggest.unicode_escape")),
+                line: Some(line),
+                column: Some(column),
+                source_name: None,
+            })
+        };
+        self.advance();
+        if self.current_char != Some('{') {
+            return Err(invalid("متوقع { بعد \\u".to_string(), "Expected '{' after \\u".to_string()));
+        }
+        self.advance();
+        let mut hex = String::new();
+        while let Some(ch) = self.current_char {
+            if ch == '}' || ch == '"' || ch == '\n' {
+                break;
+            }
+            hex.push(ch);
+            self.advance();
+        }
+        if self.current_char != Some('}') {
+            return Err(invalid("متوقع } لإغلاق \\u{".to_string(), "Expected '}' to close \\u{".to_string()));
+        }
+        let code = (1..=6)
+            .contains(&hex.len())
+            .then(|| u32::from_str_radix(&hex, 16).ok())
+            .flatten()
+            .ok_or_else(|| {
+                invalid(
+                    format!("'{}' ليس رمزاً ست عشرياً صالحاً", hex),
+                    format!("'{}' is not a valid hexadecimal code point", hex),
+                )
+            })?;
+        char::from_u32(code).ok_or_else(|| {
+            invalid(
+                format!("U+{:X} ليس حرف يونيكود صالحاً", code),
+                format!("U+{:X} is not a valid Unicode character", code),
+            )
+        })
+    }
+
     fn read_identifier(&mut self) -> String {
         let mut identifier = String::new();
 
@@ -254,8 +489,17 @@ impl Lexer {
         identifier
     }
 
+    /// Splits `input` into tokens with their spans, without the final `Eof`.
+    /// Fails with the first error the lexer finds.
+    pub fn tokenize(input: &str) -> Result<Vec<(Token, Span)>> {
+        Lexer::new(input).collect()
+    }
+
+    /// Returns the next token, or an error for input the lexer can't read.
+    /// The offending input is consumed, so calling it again carries on.
     pub fn next_token(&mut self) -> Result<Token> {
         loop {
+            self.token_start = (self.line, self.column);
             match self.current_char {
                 None => return Ok(Token::Eof),
                 Some(ch) if ch.is_whitespace() && ch != '\n' => {
@@ -267,11 +511,7 @@ impl Lexer {
                     return Ok(Token::Newline);
                 }
                 Some(ch) if ch.is_ascii_digit() || is_arabic_digit(ch) => {
-                    let n = self.read_number();
-                    match n {
-                        Ok(val) => return Ok(Token::Number(val)),
-                        Err(e) => return Err(e),
-                    }
+                    return self.read_number();
                 }
                 Some('"') => {
                     let s = self.read_string();
@@ -285,8 +525,12 @@ impl Lexer {
                     let t = match identifier.as_str() {
                         // Arabic keywords
                         "اذا" | "إذا" => Token::If,
-                        "وإلا" | "والا" | "وإلاّ" => Token::Else,
-                        "بينما" => Token::While,
+                        "وإلا" | "والا" | "وإلاّ" | "وإلّا" => Token::Else,
+                        "بينما" | "طالما" => Token::While,
+                        "افعل" => Token::Do,
+                        "توقف" => Token::Break,
+                        "استمر" => Token::Continue,
+                        "كرر" => Token::Loop,
                         "صحيح" => Token::True,
                         "خطأ" => Token::False,
                         "و" => Token::And,
@@ -295,12 +539,24 @@ impl Lexer {
                         "دالة" => Token::Function,
                         "ارجع" => Token::Return,
                         "جرب" => Token::Try,
-                        "امسك" => Token::Catch,
+                        "امسك" | "عدا" => Token::Catch,
+                        "أخطاء" => Token::Errors,
+                        "طابق" => Token::Match,
+                        "حالة" => Token::Case,
+                        "افتراضي" => Token::Default,
+                        "ثابت" => Token::Const,
+                        "آمن" => Token::Safe,
+                        "وإلا_قيمة" => Token::Coalesce,
 
                         // English keywords
                         "if" => Token::If,
                         "else" => Token::Else,
+                        "elif" => Token::Elif,
                         "while" => Token::While,
+                        "do" => Token::Do,
+                        "break" => Token::Break,
+                        "continue" => Token::Continue,
+                        "loop" => Token::Loop,
                         "true" => Token::True,
                         "false" => Token::False,
                         "and" => Token::And,
@@ -308,10 +564,19 @@ impl Lexer {
                         "not" => Token::Not,
                         "function" => Token::Function,
                         "return" => Token::Return,
+                        "try" if self.current_char == Some('?') => {
+                            self.advance();
+                            Token::Safe
+                        }
                         "try" => Token::Try,
                         "catch" => Token::Catch,
+                        "errors" => Token::Errors,
+                        "match" => Token::Match,
+                        "case" => Token::Case,
+                        "default" => Token::Default,
+                        "const" => Token::Const,
 
-                        _ => Token::Identifier(identifier),
+                        _ => Token::Identifier(Symbol::from(identifier)),
                     };
                     return Ok(t);
                 }
@@ -378,7 +643,15 @@ impl Lexer {
                         self.advance();
                         return Ok(Token::And);
                     }
-                    return Ok(Token::Identifier("&".to_string()));
+                    return Ok(Token::Identifier(Symbol::intern("&")));
+                }
+                Some('?') => {
+                    self.advance();
+                    if self.current_char == Some('?') {
+                        self.advance();
+                        return Ok(Token::Coalesce);
+                    }
+                    return Ok(Token::Identifier(Symbol::intern("?")));
                 }
                 Some('|') => {
                     self.advance();
@@ -386,7 +659,7 @@ impl Lexer {
                         self.advance();
                         return Ok(Token::Or);
                     }
-                    return Ok(Token::Identifier("|".to_string()));
+                    return Ok(Token::Identifier(Symbol::intern("|")));
                 }
                 Some('(') => {
                     self.advance();
@@ -412,21 +685,33 @@ impl Lexer {
                     self.advance();
                     return Ok(Token::RightBracket);
                 }
-                Some(',') => {
+                Some(',' | '،') => {
                     self.advance();
                     return Ok(Token::Comma);
                 }
-                Some(';') => {
+                Some(';' | '؛') => {
                     self.advance();
                     return Ok(Token::Semicolon);
                 }
+                Some('.') if self.peek() == Some('.') && self.input.get(self.position + 2) == Some(&'.') => {
+                    self.advance();
+                    self.advance();
+                    self.advance();
+                    return Ok(Token::Ellipsis);
+                }
+                Some('.') => {
+                    self.advance();
+                    return Ok(Token::Dot);
+                }
                 Some(ch) => {
                     let err = IqraError {
                         kind: "رمز غير معروف | Unknown Character".to_string(),
                         message_ar: format!("رمز غير معروف: '{}'", ch),
                         message_en: format!("Unknown character: '{}'", ch),
-                        suggestion: Some("تأكد من صحة الكود | Check your code".to_string()),
+                        suggestion: Some(tr!("suggest.check_code")),
                         line: Some(self.line),
+                        column: Some(self.column),
+                        source_name: None,
                     };
                     self.advance();
                     return Err(anyhow!(err));
@@ -435,3 +720,17 @@ impl Lexer {
         }
     }
 }
+
+/// Yields each token with its span until `Eof`. An error doesn't end the
+/// iteration: the next item resumes after the input that caused it.
+impl Iterator for Lexer {
+    type Item = Result<(Token, Span)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_token() {
+            Ok(Token::Eof) => None,
+            Ok(token) => Some(Ok((token, self.token_span()))),
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
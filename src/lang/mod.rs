@@ -1,6 +1,14 @@
+pub mod analyzer;
+pub mod builtins;
+pub mod hijri;
 pub mod lexer;
+pub mod optimizer;
+pub mod output;
 pub mod parser;
+pub mod program;
 pub mod runtime;
+pub mod stepper;
+pub mod symbol;
 pub mod value;
 
 pub use value::Value;
@@ -0,0 +1,149 @@
+//! التحسين | An optional pass over parsed statements that does at parse time
+//! what would otherwise be redone on every run of a loop: it folds operators
+//! whose operands are literals, turns lists of literals into one literal, and
+//! drops the branch an `اذا` with a literal condition can never take.
+//!
+//! Folding uses the runtime's own operators, so a folded value is exactly
+//! what evaluation would produce. An operation that would fail, such as
+//! `١ / ٠` or an overflow, is left as it is so the error still happens at
+//! run time, on its line.
+
+use crate::lang::parser::{CatchArm, Expr, ExprKind, MatchArm, Stmt, StmtKind};
+use crate::lang::runtime::Runtime;
+use crate::lang::value::Value;
+
+/// Optimizes `statements`, including function bodies and nested blocks.
+pub fn optimize(statements: Vec<Stmt>) -> Vec<Stmt> {
+    statements.into_iter().map(optimize_stmt).collect()
+}
+
+fn optimize_stmt(stmt: Stmt) -> Stmt {
+    let kind = match stmt.kind {
+        StmtKind::Expression(expr) => StmtKind::Expression(optimize_expr(expr)),
+        StmtKind::Assignment { name, value } => {
+            StmtKind::Assignment { name, value: optimize_expr(value) }
+        }
+        StmtKind::ConstAssignment { name, value } => {
+            StmtKind::ConstAssignment { name, value: optimize_expr(value) }
+        }
+        StmtKind::MultiAssignment { names, values } => StmtKind::MultiAssignment {
+            names,
+            values: values.into_iter().map(optimize_expr).collect(),
+        },
+        StmtKind::If { condition, then_branch, else_branch } => {
+            let condition = optimize_expr(condition);
+            match &condition.kind {
+                // A block runs its statements in the same scope as an `اذا`
+                // branch and is worth nil when empty, like an `اذا` whose
+                // condition is false and that has no `وإلا`.
+                ExprKind::Literal(value) if value.is_truthy() => {
+                    StmtKind::Block(optimize(then_branch))
+                }
+                ExprKind::Literal(_) => {
+                    StmtKind::Block(else_branch.map(optimize).unwrap_or_default())
+                }
+                _ => StmtKind::If {
+                    condition,
+                    then_branch: optimize(then_branch),
+                    else_branch: else_branch.map(optimize),
+                },
+            }
+        }
+        StmtKind::While { condition, body } => {
+            StmtKind::While { condition: optimize_expr(condition), body: optimize(body) }
+        }
+        StmtKind::DoWhile { body, condition } => {
+            StmtKind::DoWhile { body: optimize(body), condition: optimize_expr(condition) }
+        }
+        StmtKind::Loop(body) => StmtKind::Loop(optimize(body)),
+        StmtKind::Break => StmtKind::Break,
+        StmtKind::Continue => StmtKind::Continue,
+        StmtKind::Block(statements) => StmtKind::Block(optimize(statements)),
+        StmtKind::FunctionDef { name, params, rest, body, doc } => StmtKind::FunctionDef {
+            name,
+            params: params
+                .into_iter()
+                .map(|(param, default)| (param, default.map(optimize_expr)))
+                .collect(),
+            rest,
+            body: optimize(body),
+            doc,
+        },
+        StmtKind::Return(expr) => StmtKind::Return(optimize_expr(expr)),
+        StmtKind::TryCatch { try_block, catches } => StmtKind::TryCatch {
+            try_block: optimize(try_block),
+            catches: catches
+                .into_iter()
+                .map(|arm| CatchArm { body: optimize(arm.body), ..arm })
+                .collect(),
+        },
+        StmtKind::Match { subject, arms, default } => StmtKind::Match {
+            subject: optimize_expr(subject),
+            arms: arms
+                .into_iter()
+                .map(|arm| MatchArm {
+                    values: arm.values.into_iter().map(optimize_expr).collect(),
+                    body: optimize(arm.body),
+                })
+                .collect(),
+            default: default.map(optimize),
+        },
+    };
+    Stmt { kind, span: stmt.span }
+}
+
+fn optimize_expr(expr: Expr) -> Expr {
+    let kind = match expr.kind {
+        ExprKind::Binary { left, operator, right } => {
+            let (left, right) = (optimize_expr(*left), optimize_expr(*right));
+            let folded = match (&left.kind, &right.kind) {
+                (ExprKind::Literal(l), ExprKind::Literal(r)) => {
+                    Runtime::evaluate_binary_op(l, &operator, r).ok()
+                }
+                _ => None,
+            };
+            match folded {
+                Some(value) => ExprKind::Literal(value),
+                None => ExprKind::Binary { left: Box::new(left), operator, right: Box::new(right) },
+            }
+        }
+        ExprKind::Unary { operator, operand } => {
+            let operand = optimize_expr(*operand);
+            let folded = match &operand.kind {
+                ExprKind::Literal(value) => Runtime::evaluate_unary_op(&operator, value).ok(),
+                _ => None,
+            };
+            match folded {
+                Some(value) => ExprKind::Literal(value),
+                None => ExprKind::Unary { operator, operand: Box::new(operand) },
+            }
+        }
+        ExprKind::List(elements) => {
+            let elements: Vec<Expr> = elements.into_iter().map(optimize_expr).collect();
+            let literals: Option<Vec<Value>> = elements
+                .iter()
+                .map(|element| match &element.kind {
+                    ExprKind::Literal(value) => Some(value.clone()),
+                    _ => None,
+                })
+                .collect();
+            match literals {
+                Some(values) => ExprKind::Literal(Value::List(values)),
+                None => ExprKind::List(elements),
+            }
+        }
+        ExprKind::Call { name, args } => {
+            ExprKind::Call { name, args: args.into_iter().map(optimize_expr).collect() }
+        }
+        ExprKind::BuiltinCall { name, args } => {
+            ExprKind::BuiltinCall { name, args: args.into_iter().map(optimize_expr).collect() }
+        }
+        ExprKind::Index { object, index } => ExprKind::Index {
+            object: Box::new(optimize_expr(*object)),
+            index: Box::new(optimize_expr(*index)),
+        },
+        ExprKind::SafeEval(inner) => ExprKind::SafeEval(Box::new(optimize_expr(*inner))),
+        kind @ (ExprKind::Literal(_) | ExprKind::Identifier(_)) => kind,
+    };
+    Expr { kind, span: expr.span }
+}
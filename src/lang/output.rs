@@ -0,0 +1,118 @@
+//! مخرجات البرنامج | Where `اطبع` and friends write their output, and
+//! `اطبع_خطأ` its errors.
+
+use std::io::{IsTerminal, Write};
+use std::sync::{Arc, Mutex};
+
+/// Destination for everything a script prints. `Send` so a `Runtime` can
+/// move to another thread.
+pub trait OutputSink: Send {
+    fn write(&mut self, text: &str);
+    /// Writes to the error channel, kept apart from `write`'s.
+    fn write_error(&mut self, text: &str);
+    /// Whether the output is shown on a terminal, so ANSI styling makes sense.
+    fn is_tty(&self) -> bool;
+}
+
+/// Writes to the process's standard output, and errors to standard error.
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn write(&mut self, text: &str) {
+        let mut stdout = std::io::stdout().lock();
+        let _ = stdout.write_all(text.as_bytes());
+        let _ = stdout.flush();
+    }
+
+    fn write_error(&mut self, text: &str) {
+        let mut stderr = std::io::stderr().lock();
+        let _ = stderr.write_all(text.as_bytes());
+        let _ = stderr.flush();
+    }
+
+    fn is_tty(&self) -> bool {
+        std::io::stdout().is_terminal()
+    }
+}
+
+/// Collects output in memory. Clones share the same buffers, so one clone can
+/// be handed to the runtime while another reads what was printed.
+#[derive(Debug, Clone, Default)]
+pub struct CapturedOutput {
+    buffer: Arc<Mutex<String>>,
+    errors: Arc<Mutex<String>>,
+    tty: bool,
+}
+
+impl CapturedOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A capture that reports itself as a terminal.
+    pub fn tty() -> Self {
+        CapturedOutput { tty: true, ..Self::default() }
+    }
+
+    pub fn contents(&self) -> String {
+        self.buffer.lock().unwrap().clone()
+    }
+
+    /// Returns the output collected so far and empties the buffer.
+    pub fn take(&self) -> String {
+        std::mem::take(&mut *self.buffer.lock().unwrap())
+    }
+
+    /// What was written to the error channel.
+    pub fn errors(&self) -> String {
+        self.errors.lock().unwrap().clone()
+    }
+
+    /// Returns the errors collected so far and empties their buffer.
+    pub fn take_errors(&self) -> String {
+        std::mem::take(&mut *self.errors.lock().unwrap())
+    }
+}
+
+impl OutputSink for CapturedOutput {
+    fn write(&mut self, text: &str) {
+        self.buffer.lock().unwrap().push_str(text);
+    }
+
+    fn write_error(&mut self, text: &str) {
+        self.errors.lock().unwrap().push_str(text);
+    }
+
+    fn is_tty(&self) -> bool {
+        self.tty
+    }
+}
+
+/// Passes everything on to another sink and keeps a copy of the output,
+/// though not of the errors.
+pub struct TeeOutput {
+    inner: Box<dyn OutputSink>,
+    copy: CapturedOutput,
+}
+
+impl TeeOutput {
+    /// Writes to `inner`, copying the output into `copy`.
+    pub fn new(inner: Box<dyn OutputSink>, copy: CapturedOutput) -> Self {
+        TeeOutput { inner, copy }
+    }
+}
+
+impl OutputSink for TeeOutput {
+    fn write(&mut self, text: &str) {
+        self.inner.write(text);
+        self.copy.write(text);
+    }
+
+    fn write_error(&mut self, text: &str) {
+        self.inner.write_error(text);
+    }
+
+    fn is_tty(&self) -> bool {
+        self.inner.is_tty()
+    }
+}
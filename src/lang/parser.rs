@@ -1,12 +1,30 @@
-use crate::lang::lexer::{Lexer, Token};
+use crate::lang::lexer::{Comment, Lexer, Span, Token};
+use crate::lang::symbol::Symbol;
 use crate::lang::value::Value;
-use crate::lang::runtime::IqraError;
+use crate::lang::runtime::{IqraError, LoopExit};
+use crate::tr;
 use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
-pub enum Expr {
+/// تعبير مع موضعه في المصدر | An expression together with its source span.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+impl Expr {
+    /// A binary expression spanning from `left` to `right`.
+    pub fn binary(left: Expr, operator: BinaryOp, right: Expr) -> Expr {
+        let span = left.span.to(right.span);
+        Expr { kind: ExprKind::Binary { left: Box::new(left), operator, right: Box::new(right) }, span }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ExprKind {
     Literal(Value),
-    Identifier(String),
+    Identifier(Symbol),
     Binary {
         left: Box<Expr>,
         operator: BinaryOp,
@@ -17,17 +35,25 @@ pub enum Expr {
         operand: Box<Expr>,
     },
     Call {
-        name: String,
+        name: Symbol,
+        args: Vec<Expr>,
+    },
+    /// `مدمج.اسم(...)` / `builtin.name(...)`: calls the builtin even when a
+    /// user function of the same name hides it.
+    BuiltinCall {
+        name: Symbol,
         args: Vec<Expr>,
     },
     List(Vec<Expr>),
     Index {
         object: Box<Expr>,
         index: Box<Expr>,
-    }
+    },
+    /// `آمن تعبير` / `try? expr`: evaluates to a result map instead of failing.
+    SafeEval(Box<Expr>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BinaryOp {
     Add,
     Subtract,
@@ -41,61 +67,357 @@ pub enum BinaryOp {
     Greater,
     GreaterEqual,
     And,
-    Or
+    Or,
+    /// `??`: the right side when the left is nil or a missing key or index.
+    Coalesce,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum UnaryOp {
     Not,
     Minus
 }
 
-#[derive(Debug, Clone)]
-pub enum Stmt {
+/// معامل دالة | A function parameter: its name and optional default value.
+pub type Param = (Symbol, Option<Expr>);
+
+/// جملة مع موضعها في المصدر | A statement together with its source span.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum StmtKind {
     Expression(Expr),
-    Assignment { name: String, value: Expr },
+    Assignment { name: Symbol, value: Expr },
+    ConstAssignment { name: Symbol, value: Expr },
+    /// `أ، ب = ١، ٢`, or `أ، ب = قائمة` to destructure a list.
+    MultiAssignment { names: Vec<Symbol>, values: Vec<Expr> },
     If { condition: Expr, then_branch: Vec<Stmt>, else_branch: Option<Vec<Stmt>> },
     While { condition: Expr, body: Vec<Stmt> },
+    /// `افعل { } بينما شرط`: runs `body`, then again while `condition` holds.
+    DoWhile { body: Vec<Stmt>, condition: Expr },
+    /// `كرر { }`: runs `body` again and again until a `توقف` or `ارجع`.
+    Loop(Vec<Stmt>),
+    /// `توقف`: leaves the innermost loop.
+    Break,
+    /// `استمر`: skips to the next pass of the innermost loop.
+    Continue,
     Block(Vec<Stmt>),
-    FunctionDef { name: String, params: Vec<String>, body: Vec<Stmt> },
+    /// Parameters with defaults come after required ones; `rest` (`...قيم`)
+    /// collects any remaining arguments into a list. `doc` holds the `///`
+    /// comment lines directly above `دالة`.
+    FunctionDef { name: Symbol, params: Vec<Param>, rest: Option<Symbol>, body: Vec<Stmt>, doc: Option<String> },
     Return(Expr),
-    TryCatch {
-        try_block: Vec<Stmt>,
-        catch_block: Vec<Stmt>,
-        error_var: Option<String>,
+    /// `جرب { } امسك "قسمة على صفر" (خ) { } امسك (خ) { }`. The first arm
+    /// that matches the error runs; if none does, the error propagates.
+    TryCatch { try_block: Vec<Stmt>, catches: Vec<CatchArm> },
+    Match { subject: Expr, arms: Vec<MatchArm>, default: Option<Vec<Stmt>> },
+}
+
+/// Converts a parse error into an `IqraError`, filling in `line` and
+/// `column` if it has no line.
+fn into_iqra_error(err: anyhow::Error, line: usize, column: Option<usize>) -> IqraError {
+    match err.downcast::<IqraError>() {
+        Ok(mut err) => {
+            if err.line.is_none() {
+                err.line = Some(line);
+                err.column = column;
+            }
+            err
+        }
+        Err(err) => IqraError {
+            kind: "خطأ في التحليل".to_string(),
+            message_ar: err.to_string(),
+            message_en: err.to_string(),
+            suggestion: None,
+            line: Some(line),
+            column,
+            source_name: None,
+        },
     }
 }
 
+/// حالة في جملة طابق | One `حالة` arm: runs `body` when the subject equals any of `values`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchArm {
+    pub values: Vec<Expr>,
+    pub body: Vec<Stmt>,
+}
+
+/// فرع امسك | One `امسك` arm. An arm with a `kind` only catches errors of
+/// that kind, compared by [`IqraError::kind_matches`]; one without catches
+/// any error. `error_var` is bound to the error's text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatchArm {
+    pub kind: Option<String>,
+    pub error_var: Option<Symbol>,
+    pub body: Vec<Stmt>,
+}
+
+/// Names that qualify a call as a builtin call, as in `مدمج.اطبع(س)`.
+pub const BUILTIN_QUALIFIERS: &[&str] = &["مدمج", "builtin"];
+
+/// Default for [`Parser::set_max_errors`].
+pub const DEFAULT_MAX_ERRORS: usize = 20;
+
+/// Default for [`Parser::set_max_nesting`], also the limit on expression
+/// nesting when evaluating.
+pub const DEFAULT_MAX_NESTING: usize = 256;
+
+/// Kind of the error [`nesting_error`] raises.
+pub const NESTING_ERROR_KIND: &str = "تجاوز عمق التداخل | Nesting too deep";
+
+/// The error for input nested deeper than `max` levels.
+pub fn nesting_error(max: usize) -> anyhow::Error {
+    anyhow!(IqraError {
+        kind: NESTING_ERROR_KIND.to_string(),
+        message_ar: format!("التداخل أعمق من الحد المسموح ({} مستوى)", max),
+        message_en: format!("Nesting is deeper than the limit of {} levels", max),
+        suggestion: Some(tr!("suggest.split_expression")),
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+/// Levels from `expr` down to its deepest operand, `expr` itself included.
+fn expr_height(expr: &Expr) -> usize {
+    let below = match &expr.kind {
+        ExprKind::Literal(_) | ExprKind::Identifier(_) => 0,
+        ExprKind::Binary { left, right, .. } => expr_height(left).max(expr_height(right)),
+        ExprKind::Index { object, index } => expr_height(object).max(expr_height(index)),
+        ExprKind::Unary { operand, .. } | ExprKind::SafeEval(operand) => expr_height(operand),
+        ExprKind::Call { args, .. } | ExprKind::BuiltinCall { args, .. } | ExprKind::List(args) => {
+            args.iter().map(expr_height).max().unwrap_or(0)
+        }
+    };
+    below + 1
+}
+
 #[derive(Debug)]
 pub struct Parser {
     lexer: Lexer,
     current_token: Token,
+    current_span: Span,
+    previous_span: Span,
+    /// Errors from the lexer not yet reported. The offending token is
+    /// skipped so parsing can go on.
+    lexer_errors: Vec<anyhow::Error>,
+    /// The token after `current_token` and its span, once `peek_token` has read it.
+    peeked: Option<(Token, Span)>,
+    /// `{` consumed minus `}` consumed, used to resynchronize after an error.
+    open_braces: usize,
+    max_errors: usize,
+    source_name: Option<String>,
+    /// Statements and expressions currently being parsed inside each other.
+    depth: usize,
+    max_nesting: usize,
+    /// Whether the statements being parsed are in a loop body, where
+    /// `توقف` and `استمر` are allowed. A function body starts outside.
+    in_loop: bool,
 }
 
 impl Parser {
     /// Parses the input and returns a vector of statements.
     pub fn parse(&mut self) -> Result<Vec<Stmt>> {
+        let result = self.parse_statements();
+        match &self.source_name {
+            Some(name) => result.map_err(|err| IqraError::with_source_name(err, name)),
+            None => result,
+        }
+    }
+
+    fn parse_statements(&mut self) -> Result<Vec<Stmt>> {
         let mut statements = Vec::new();
         self.skip_newlines();
+        if let Some(err) = self.take_lexer_error() {
+            return Err(err);
+        }
         while self.current_token != Token::Eof {
-            statements.push(self.statement()?);
+            let stmt = self.statement();
+            if let Some(err) = self.take_lexer_error() {
+                return Err(err);
+            }
+            statements.push(stmt.map_err(|err| {
+                IqraError::with_position(err, self.current_span.start_line, self.current_span.start_col)
+            })?);
             self.skip_newlines();
         }
         Ok(statements)
     }
+
+    /// Parses the whole input, collecting errors instead of stopping at the
+    /// first one. After an error the parser skips to the next statement
+    /// boundary (a newline outside braces, or past the `}` closing the
+    /// failed statement) and carries on, until `max_errors` errors are found.
+    /// Used by `iqra check` and the language server.
+    pub fn parse_with_recovery(&mut self) -> (Vec<Stmt>, Vec<IqraError>) {
+        let mut statements = Vec::new();
+        let mut errors = Vec::new();
+        self.skip_newlines();
+        while self.current_token != Token::Eof && errors.len() < self.max_errors {
+            let braces_before = self.open_braces;
+            let stmt = self.statement();
+            errors.extend(self.lexer_errors.drain(..).map(|err| into_iqra_error(err, 1, None)));
+            match stmt {
+                Ok(stmt) => statements.push(stmt),
+                Err(err) => {
+                    let span = self.current_span;
+                    errors.push(into_iqra_error(err, span.start_line, Some(span.start_col)));
+                    self.synchronize(self.open_braces.saturating_sub(braces_before));
+                }
+            }
+            self.skip_newlines();
+        }
+        errors.extend(self.lexer_errors.drain(..).map(|err| into_iqra_error(err, 1, None)));
+        errors.truncate(self.max_errors);
+        for err in &mut errors {
+            err.source_name = err.source_name.take().or_else(|| self.source_name.clone());
+        }
+        (statements, errors)
+    }
+
+    /// The comments read so far, all of them once parsing is done.
+    pub fn comments(&self) -> &[Comment] {
+        self.lexer.comments()
+    }
+
+    /// Names the source being parsed, such as its file path, in the errors
+    /// the parser reports.
+    pub fn set_source_name(&mut self, name: &str) {
+        self.source_name = Some(name.to_string());
+    }
+
+    /// Sets how deeply statements and expressions may nest before parsing
+    /// fails, so deeply nested input can't overflow the stack.
+    pub fn set_max_nesting(&mut self, max_nesting: usize) {
+        self.max_nesting = max_nesting;
+    }
+
+    /// Runs `parse` one nesting level deeper, failing past `max_nesting`.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        if self.depth >= self.max_nesting {
+            return Err(nesting_error(self.max_nesting));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Checks the depth of a chain of operators or indexes before `left` and
+    /// `right` are joined under one more node. A chain is built in a loop, not
+    /// by recursion, so `nested` never sees it, yet every pass that walks the
+    /// tree recurses through it. `height` is the chain's depth so far.
+    fn check_chain(&self, height: &mut Option<usize>, left: &Expr, right: &Expr) -> Result<()> {
+        let below = height.unwrap_or_else(|| expr_height(left)).max(expr_height(right));
+        if self.depth + below >= self.max_nesting {
+            return Err(nesting_error(self.max_nesting));
+        }
+        *height = Some(below + 1);
+        Ok(())
+    }
+
+    /// Runs `parse` with `in_loop` set as given, restoring it afterwards.
+    fn with_in_loop<T>(&mut self, in_loop: bool, parse: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let outer = std::mem::replace(&mut self.in_loop, in_loop);
+        let result = parse(self);
+        self.in_loop = outer;
+        result
+    }
+
+    /// Sets how many errors `parse_with_recovery` collects before giving up.
+    pub fn set_max_errors(&mut self, max_errors: usize) {
+        self.max_errors = max_errors.max(1);
+    }
+
+    /// Skips tokens up to the end of the failed statement. `depth` is the
+    /// number of braces the statement opened that are still unclosed.
+    fn synchronize(&mut self, mut depth: usize) {
+        loop {
+            match self.current_token {
+                Token::Eof => return,
+                Token::Newline | Token::Semicolon if depth == 0 => return,
+                Token::LeftBrace => depth += 1,
+                Token::RightBrace if depth == 0 => {
+                    self.advance();
+                    return;
+                }
+                Token::RightBrace => depth -= 1,
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
+    fn take_lexer_error(&mut self) -> Option<anyhow::Error> {
+        (!self.lexer_errors.is_empty()).then(|| self.lexer_errors.remove(0))
+    }
+
     /// Creates a new Parser from a Lexer.
-    pub fn new(mut lexer: Lexer) -> Self {
-    let current_token = lexer.next_token().expect("Lexer error during parser initialization");
-    Parser { lexer, current_token }
+    pub fn new(lexer: Lexer) -> Self {
+        let mut parser = Parser {
+            lexer,
+            current_token: Token::Eof,
+            current_span: Span::default(),
+            previous_span: Span::default(),
+            lexer_errors: Vec::new(),
+            peeked: None,
+            open_braces: 0,
+            max_errors: DEFAULT_MAX_ERRORS,
+            source_name: None,
+            depth: 0,
+            max_nesting: DEFAULT_MAX_NESTING,
+            in_loop: false,
+        };
+        parser.current_token = parser.next_token();
+        parser.current_span = parser.lexer.token_span();
+        parser.previous_span = parser.current_span;
+        parser
     }
+
+    /// Reads the next token, recording and skipping any the lexer rejects.
+    fn next_token(&mut self) -> Token {
+        loop {
+            match self.lexer.next_token() {
+                Ok(token) => return token,
+                Err(err) => self.lexer_errors.push(err),
+            }
+        }
+    }
+
     /// Advances to the next token using the lexer.
     fn advance(&mut self) {
-    self.current_token = self.lexer.next_token().expect("Lexer error during token advance");
+        match self.current_token {
+            Token::LeftBrace => self.open_braces += 1,
+            Token::RightBrace => self.open_braces = self.open_braces.saturating_sub(1),
+            _ => {}
+        }
+        let (token, span) = match self.peeked.take() {
+            Some(peeked) => peeked,
+            None => (self.next_token(), self.lexer.token_span()),
+        };
+        self.current_token = token;
+        self.previous_span = self.current_span;
+        self.current_span = span;
+    }
+
+    /// Returns the token after the current one without consuming anything.
+    fn peek_token(&mut self) -> &Token {
+        if self.peeked.is_none() {
+            let token = self.next_token();
+            self.peeked = Some((token, self.lexer.token_span()));
+        }
+        &self.peeked.as_ref().unwrap().0
     }
 
-    /// Skips newlines in the token stream.
+    /// Skips newlines in the token stream. `;` and `؛` separate statements on
+    /// one line, so they are skipped the same way.
     fn skip_newlines(&mut self) {
-        while self.current_token == Token::Newline {
+        while matches!(self.current_token, Token::Newline | Token::Semicolon) {
             self.advance();
         }
     }
@@ -110,48 +432,63 @@ impl Parser {
                     kind: "خطأ في التحليل".to_string(),
                     message_ar: format!("متوقع {:?}", expected),
                     message_en: format!("Expected {:?}", expected),
-                    suggestion: Some("راجع بناء الجملة أو الأقواس".to_string()),
+                    suggestion: Some(tr!("suggest.check_brackets")),
                     line: None,
+                    column: None,
+                    source_name: None,
                 }))
         }
     }
+    /// Parses one statement and records the span from its first to its last token.
     fn statement(&mut self) -> Result<Stmt> {
+        let start = self.current_span;
+        let kind = self.nested(Self::statement_kind)?;
+        Ok(Stmt { kind, span: start.to(self.previous_span) })
+    }
+
+    fn statement_kind(&mut self) -> Result<StmtKind> {
+        // `اسم = ...` and `أ، ب = ...` need a second token to tell them apart
+        // from an expression that starts with an identifier.
+        if let Token::Identifier(name) = &self.current_token {
+            let name = name.clone();
+            match self.peek_token() {
+                Token::Assign => {
+                    self.advance();
+                    self.advance();
+                    let value = self.expression()?;
+                    return Ok(StmtKind::Assignment { name, value });
+                }
+                Token::Comma => {
+                    self.advance();
+                    return self.multi_assignment(name);
+                }
+                _ => {}
+            }
+        }
         match &self.current_token {
             Token::Try => self.try_catch_statement(),
             Token::Function => self.function_def(),
             Token::If => self.if_statement(),
             Token::While => self.while_statement(),
+            Token::Do => self.do_while_statement(),
+            Token::Loop => self.loop_statement(),
+            Token::Break | Token::Continue => self.loop_exit_statement(),
+            Token::Match => self.match_statement(),
+            Token::Const => self.const_statement(),
             Token::LeftBrace => self.block_statement(),
-            Token::Identifier(name) => {
-                let name = name.clone();
-                self.advance();
-
-                if self.current_token == Token::Assign {
-                    self.advance();
-                    let value = self.expression()?;
-                    Ok(Stmt::Assignment { name, value })
-                } else {
-                    // Put the identifier back and parse as expression
-                    let expr = Expr::Identifier(name);
-                    // Parse the rest of the expression if there's more
-                    let full_expr = self.parse_expression_continuation(expr)?;
-                    Ok(Stmt::Expression(full_expr))
-                }
-            }
             Token::Return => {
                 self.advance();
                 let expr = self.expression()?;
-                Ok(Stmt::Return(expr))
+                Ok(StmtKind::Return(expr))
             }
             _ => {
                 let expr = self.expression()?;
-                Ok(Stmt::Expression(expr))
+                Ok(StmtKind::Expression(expr))
             }
         }
     }
 
-    fn try_catch_statement(&mut self) -> Result<Stmt> {
-    println!("[DEBUG] Token after try/catch: {:?}", self.current_token);
+    fn try_catch_statement(&mut self) -> Result<StmtKind> {
         // Advance past 'جرب' or 'try'
         self.advance();
         self.skip_newlines();
@@ -160,63 +497,109 @@ impl Parser {
              kind: "خطأ في بناء جرب".to_string(),
              message_ar: "متوقع '{' بعد جرب/try".to_string(),
              message_en: "Expected '{' after try".to_string(),
-             suggestion: Some("استخدم قوس الفتح بعد جرب/try".to_string()),
+             suggestion: Some(tr!("suggest.brace_after_try")),
              line: None,
+             column: None,
+             source_name: None,
           }));
         }
         let try_block = self.block_statement_vec()?;
         self.skip_newlines();
         // Expect 'امسك' or 'catch'
-        match &self.current_token {
-            Token::Catch => {
+        if self.current_token != Token::Catch {
+            return Err(anyhow!(IqraError {
+                kind: "خطأ في بناء جرب".to_string(),
+                message_ar: "متوقع 'امسك' أو 'catch' بعد 'جرب'".to_string(),
+                message_en: "Expected 'catch' after 'try'".to_string(),
+                suggestion: Some(tr!("suggest.catch_after_try")),
+                line: None,
+                column: None,
+                source_name: None,
+            }));
+        }
+        let mut catches = Vec::new();
+        loop {
+            catches.push(self.catch_arm()?);
+            // A further arm may start on the line after the `}`.
+            if self.current_token == Token::Newline && *self.peek_token() == Token::Catch {
+                self.advance();
+            }
+            if self.current_token != Token::Catch {
+                break;
+            }
+        }
+        Ok(StmtKind::TryCatch { try_block, catches })
+    }
+
+    /// Parses one `امسك [أخطاء] ["نوع"] [(متغير)] { ... }` arm.
+    fn catch_arm(&mut self) -> Result<CatchArm> {
+        self.expect(Token::Catch)?;
+        self.skip_newlines();
+        let named = self.current_token == Token::Errors;
+        if named {
+            self.advance();
+        }
+        let kind = match &self.current_token {
+            Token::String(kind) => {
+                let kind = kind.clone();
                 self.advance();
                 self.skip_newlines();
-                let mut error_var = None;
-                if self.current_token == Token::LeftParen {
+                Some(kind)
+            }
+            _ if named => {
+                return Err(anyhow!(IqraError {
+                    kind: "خطأ في بناء جرب".to_string(),
+                    message_ar: "متوقع نوع الخطأ نصاً بعد 'أخطاء'".to_string(),
+                    message_en: "Expected an error kind string after 'errors'".to_string(),
+                    suggestion: Some(tr!("suggest.example_catch_kind")),
+                    line: None,
+                    column: None,
+                    source_name: None,
+                }));
+            }
+            _ => None,
+        };
+        let mut error_var = None;
+        if self.current_token == Token::LeftParen {
+            self.advance();
+            match &self.current_token {
+                Token::Identifier(var) => {
+                    error_var = Some(var.clone());
+                    self.advance();
+                }
+                Token::False => {
+                    error_var = Some(Symbol::intern("خطأ"));
                     self.advance();
-                    match &self.current_token {
-                        Token::Identifier(var) => {
-                            error_var = Some(var.clone());
-                            self.advance();
-                        },
-                        Token::False => {
-                            error_var = Some("خطأ".to_string());
-                            self.advance();
-                        },
-                        _ => {
-                            return Err(anyhow!(IqraError {
-                                kind: "خطأ في متغير الخطأ".to_string(),
-                                message_ar: "متوقع اسم متغير الخطأ بعد (".to_string(),
-                                message_en: "Expected error variable name after (".to_string(),
-                                suggestion: Some("اكتب اسم متغير بعد (".to_string()),
-                                line: None,
-                            }));
-                        }
-                    }
-                    if self.current_token == Token::RightParen {
-                        self.advance();
-                    } else {
-                        return Err(anyhow!(IqraError {
-                            kind: "خطأ في متغير الخطأ".to_string(),
-                            message_ar: "متوقع ')' بعد اسم متغير الخطأ".to_string(),
-                            message_en: "Expected ')' after error variable name".to_string(),
-                            suggestion: Some("استخدم قوس الإغلاق بعد اسم المتغير".to_string()),
-                            line: None,
-                        }));
-                    }
-                    self.skip_newlines();
                 }
-                let catch_block = self.block_statement_vec()?;
-                Ok(Stmt::TryCatch { try_block, catch_block, error_var })
+                _ => {
+                    return Err(anyhow!(IqraError {
+                        kind: "خطأ في متغير الخطأ".to_string(),
+                        message_ar: "متوقع اسم متغير الخطأ بعد (".to_string(),
+                        message_en: "Expected error variable name after (".to_string(),
+                        suggestion: Some(tr!("suggest.error_variable")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                }
             }
-            _ => Err(anyhow!(IqraError {
-                kind: "خطأ في بناء جرب".to_string(),
-                message_ar: "متوقع 'امسك' أو 'catch' بعد 'جرب'".to_string(),
-                message_en: "Expected 'catch' after 'try'".to_string(),
-                suggestion: Some("استخدم امسك/catch بعد جرب/try".to_string()),
-                line: None,
-            })),
+            if self.current_token == Token::RightParen {
+                self.advance();
+            } else {
+                return Err(anyhow!(IqraError {
+                    kind: "خطأ في متغير الخطأ".to_string(),
+                    message_ar: "متوقع ')' بعد اسم متغير الخطأ".to_string(),
+                    message_en: "Expected ')' after error variable name".to_string(),
+                    suggestion: Some(tr!("suggest.close_error_variable")),
+                    line: None,
+                    column: None,
+                    source_name: None,
+                }));
+            }
+            self.skip_newlines();
         }
+        let body = self.block_statement_vec()?;
+        Ok(CatchArm { kind, error_var, body })
     }
 
     fn block_statement_vec(&mut self) -> Result<Vec<Stmt>> {
@@ -236,13 +619,37 @@ impl Parser {
                 kind: "خطأ في بناء الكتلة".to_string(),
                 message_ar: "متوقع '{' لبدء كتلة".to_string(),
                 message_en: "Expected '{' to start block".to_string(),
-                suggestion: Some("استخدم قوس الفتح لبدء الكتلة".to_string()),
+                suggestion: Some(tr!("suggest.open_block")),
                 line: None,
+                column: None,
+                source_name: None,
             }))
         }
     }
 
-    fn function_def(&mut self) -> Result<Stmt> {
+    /// Joins the doc comments on the lines immediately above `line`.
+    fn doc_comment_before(&self, line: usize) -> Option<String> {
+        let mut lines = Vec::new();
+        let mut expected = line;
+        for comment in self.lexer.comments().iter().rev() {
+            if comment.line >= line {
+                continue;
+            }
+            if !comment.doc || comment.line + 1 != expected {
+                break;
+            }
+            lines.push(comment.text.as_str());
+            expected = comment.line;
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        lines.reverse();
+        Some(lines.join("\n"))
+    }
+
+    fn function_def(&mut self) -> Result<StmtKind> {
+        let doc = self.doc_comment_before(self.current_span.start_line);
         self.expect(Token::Function)?;
         let name = match &self.current_token {
             Token::Identifier(n) => n.clone(),
@@ -250,26 +657,75 @@ impl Parser {
                 kind: "خطأ في اسم الدالة".to_string(),
                 message_ar: "متوقع اسم دالة بعد الكلمة المفتاحية".to_string(),
                 message_en: "Expected function name after keyword".to_string(),
-                suggestion: Some("اكتب اسم الدالة مباشرة بعد الكلمة المفتاحية".to_string()),
+                suggestion: Some(tr!("suggest.function_name")),
                 line: None,
+                column: None,
+                source_name: None,
             })),
         };
         self.advance();
         self.expect(Token::LeftParen)?;
-        let mut params = Vec::new();
+        let mut params: Vec<Param> = Vec::new();
+        let mut rest = None;
         if self.current_token != Token::RightParen {
             loop {
+                if rest.is_some() {
+                    return Err(anyhow!(IqraError {
+                        kind: "خطأ في المعاملات".to_string(),
+                        message_ar: "المعامل المتبقي (...) يجب أن يكون آخر معامل، ومسموح بواحد فقط".to_string(),
+                        message_en: "The rest parameter (...) must be the last parameter, and only one is allowed".to_string(),
+                        suggestion: Some(tr!("suggest.rest_last")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                }
                 match &self.current_token {
+                    Token::Ellipsis | Token::Multiply => {
+                        self.advance();
+                        match &self.current_token {
+                            Token::Identifier(p) => rest = Some(p.clone()),
+                            _ => return Err(anyhow!(IqraError {
+                                kind: "خطأ في اسم المعامل".to_string(),
+                                message_ar: "متوقع اسم معامل بعد ...".to_string(),
+                                message_en: "Expected a parameter name after ...".to_string(),
+                                suggestion: Some(tr!("suggest.example_rest")),
+                                line: None,
+                                column: None,
+                                source_name: None,
+                            })),
+                        }
+                        self.advance();
+                    }
                     Token::Identifier(p) => {
-                        params.push(p.clone());
+                        let param = p.clone();
                         self.advance();
+                        let default = if self.current_token == Token::Assign {
+                            self.advance();
+                            Some(self.expression()?)
+                        } else if params.iter().any(|(_, default)| default.is_some()) {
+                            return Err(anyhow!(IqraError {
+                                kind: "خطأ في المعاملات".to_string(),
+                                message_ar: format!("المعامل '{}' بدون قيمة افتراضية يأتي بعد معامل له قيمة افتراضية", param),
+                                message_en: format!("Parameter '{}' without a default follows a parameter with a default", param),
+                                suggestion: Some(tr!("suggest.defaults_last")),
+                                line: None,
+                                column: None,
+                                source_name: None,
+                            }));
+                        } else {
+                            None
+                        };
+                        params.push((param, default));
                     }
                     _ => return Err(anyhow!(IqraError {
                         kind: "خطأ في اسم المعامل".to_string(),
                         message_ar: "متوقع اسم معامل صحيح".to_string(),
                         message_en: "Expected valid parameter name".to_string(),
-                        suggestion: Some("استخدم أسماء معاملات صحيحة".to_string()),
+                        suggestion: Some(tr!("suggest.parameter_names")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
                 if self.current_token == Token::Comma {
@@ -281,19 +737,35 @@ impl Parser {
         }
         self.expect(Token::RightParen)?;
         self.expect(Token::LeftBrace)?;
-        let body = self.block_body()?;
+        let body = self.with_in_loop(false, Self::block_body)?;
         self.expect(Token::RightBrace)?;
-        Ok(Stmt::FunctionDef { name, params, body })
+        Ok(StmtKind::FunctionDef { name, params, rest, body, doc })
     }
 
-    fn if_statement(&mut self) -> Result<Stmt> {
+    fn if_statement(&mut self) -> Result<StmtKind> {
         self.expect(Token::If)?;
-        let condition = self.expression()?;
+        self.if_rest()
+    }
+
+    /// Parses an `اذا` after its keyword. `وإلا اذا` and `elif` continue the
+    /// chain as an `اذا` nested in the else branch.
+    fn if_rest(&mut self) -> Result<StmtKind> {
+        let condition = self.condition()?;
         self.expect(Token::LeftBrace)?;
         let then_branch = self.block_body()?;
         self.expect(Token::RightBrace)?;
 
-        let else_branch = if self.current_token == Token::Else {
+        let else_branch = if self.current_token == Token::Elif
+            || (self.current_token == Token::Else && *self.peek_token() == Token::If)
+        {
+            let start = self.current_span;
+            if self.current_token == Token::Else {
+                self.advance();
+            }
+            self.advance();
+            let kind = self.nested(Self::if_rest)?;
+            Some(vec![Stmt { kind, span: start.to(self.previous_span) }])
+        } else if self.current_token == Token::Else {
             self.advance();
             self.expect(Token::LeftBrace)?;
             let else_body = self.block_body()?;
@@ -303,25 +775,206 @@ impl Parser {
             None
         };
 
-        Ok(Stmt::If { condition, then_branch, else_branch })
+        Ok(StmtKind::If { condition, then_branch, else_branch })
     }
 
-    fn while_statement(&mut self) -> Result<Stmt> {
+    fn while_statement(&mut self) -> Result<StmtKind> {
         self.expect(Token::While)?;
-        let condition = self.expression()?;
+        let condition = self.condition()?;
         self.expect(Token::LeftBrace)?;
-        let body = self.block_body()?;
+        let body = self.with_in_loop(true, Self::block_body)?;
+        self.expect(Token::RightBrace)?;
+
+        Ok(StmtKind::While { condition, body })
+    }
+
+    /// `افعل { ... } بينما شرط`. The `بينما` may start the next line; it
+    /// belongs to the `افعل`, so a `بينما` after the condition starts a new
+    /// loop.
+    fn do_while_statement(&mut self) -> Result<StmtKind> {
+        self.expect(Token::Do)?;
+        self.expect(Token::LeftBrace)?;
+        let body = self.with_in_loop(true, Self::block_body)?;
+        self.expect(Token::RightBrace)?;
+        self.skip_newlines();
+        self.expect(Token::While)?;
+        let condition = self.condition()?;
+
+        Ok(StmtKind::DoWhile { body, condition })
+    }
+
+    fn loop_statement(&mut self) -> Result<StmtKind> {
+        self.expect(Token::Loop)?;
+        self.expect(Token::LeftBrace)?;
+        let body = self.with_in_loop(true, Self::block_body)?;
+        self.expect(Token::RightBrace)?;
+
+        Ok(StmtKind::Loop(body))
+    }
+
+    fn loop_exit_statement(&mut self) -> Result<StmtKind> {
+        let (kind, loop_exit) = match self.current_token {
+            Token::Break => (StmtKind::Break, LoopExit::Break),
+            _ => (StmtKind::Continue, LoopExit::Continue),
+        };
+        if !self.in_loop {
+            let span = self.current_span;
+            return Err(loop_exit.outside_loop_error(Some(span.start_line), Some(span.start_col)));
+        }
+        self.advance();
+        Ok(kind)
+    }
+
+    /// Parses `أ، ب = ...` after the first target name has been consumed.
+    fn multi_assignment(&mut self, first: Symbol) -> Result<StmtKind> {
+        let mut names = vec![first];
+        while self.current_token == Token::Comma {
+            self.advance();
+            match &self.current_token {
+                Token::Identifier(name) => names.push(name.clone()),
+                other => {
+                    return Err(anyhow!(IqraError {
+                        kind: "خطأ في الإسناد المتعدد".to_string(),
+                        message_ar: format!("متوقع اسم متغير بعد الفاصلة، وجد {}", other),
+                        message_en: format!("Expected a variable name after the comma, found {}", other),
+                        suggestion: Some(tr!("suggest.example_multi_assign")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                }
+            }
+            self.advance();
+        }
+        self.expect(Token::Assign)?;
+        let values = self.expression_list()?;
+        Ok(StmtKind::MultiAssignment { names, values })
+    }
+
+    /// Parses the condition of `اذا`/`بينما`. Parsing stops at a `=`
+    /// wherever it appears in the condition, as in `بينما عدد = عدد + ١ < ١٠`
+    /// or `اذا (س = ٥)`, so report that `=` instead of what the parser
+    /// expected in its place.
+    ///
+    /// A `)` with no `(` and a `{` reached before the condition is complete
+    /// are reported where they are, rather than as whatever the body's
+    /// parsing trips over next.
+    fn condition(&mut self) -> Result<Expr> {
+        let condition = self.expression();
+        let span = self.current_span;
+        if condition.is_ok() && self.current_token == Token::RightParen {
+            return Err(anyhow!(IqraError {
+                kind: "قوس غير مفتوح".to_string(),
+                message_ar: format!("قوس ) زائد في الشرط (العمود {})", span.start_col),
+                message_en: format!("Unmatched ) in the condition (column {})", span.start_col),
+                suggestion: Some(tr!("suggest.unmatched_paren")),
+                line: Some(span.start_line),
+                column: Some(span.start_col),
+                source_name: None,
+            }));
+        }
+        if let Err(err) = &condition
+            && self.current_token == Token::LeftBrace
+            && err.downcast_ref::<IqraError>().is_some_and(|err| err.kind == "رمز غير متوقع")
+        {
+            return Err(anyhow!(IqraError {
+                kind: "شرط ناقص".to_string(),
+                message_ar: format!("الشرط ناقص قبل {{ (العمود {})", span.start_col),
+                message_en: format!("The condition is incomplete before {{ (column {})", span.start_col),
+                suggestion: Some(tr!("suggest.finish_condition")),
+                line: Some(span.start_line),
+                column: Some(span.start_col),
+                source_name: None,
+            }));
+        }
+        if self.current_token == Token::Assign {
+            return Err(anyhow!(IqraError {
+                kind: "إسناد داخل شرط".to_string(),
+                message_ar: format!("التعيين غير مسموح داخل الشرط (العمود {})", span.start_col),
+                message_en: format!(
+                    "Assignment is not allowed inside a condition (column {})",
+                    span.start_col
+                ),
+                suggestion: Some(tr!("suggest.double_equals")),
+                line: Some(span.start_line),
+                column: Some(span.start_col),
+                source_name: None,
+            }));
+        }
+        condition
+    }
+
+    fn const_statement(&mut self) -> Result<StmtKind> {
+        self.expect(Token::Const)?;
+        let name = match &self.current_token {
+            Token::Identifier(name) => name.clone(),
+            other => {
+                return Err(anyhow!(IqraError {
+                    kind: "خطأ في اسم الثابت".to_string(),
+                    message_ar: format!("متوقع اسم بعد ثابت، وجد {}", other),
+                    message_en: format!("Expected a name after const, found {}", other),
+                    suggestion: Some(tr!("suggest.example_const")),
+                    line: None,
+                    column: None,
+                    source_name: None,
+                }));
+            }
+        };
+        self.advance();
+        self.expect(Token::Assign)?;
+        let value = self.expression()?;
+        Ok(StmtKind::ConstAssignment { name, value })
+    }
+
+    fn match_statement(&mut self) -> Result<StmtKind> {
+        self.expect(Token::Match)?;
+        let subject = self.expression()?;
+        self.expect(Token::LeftBrace)?;
+        self.skip_newlines();
+
+        let mut arms = Vec::new();
+        let mut default = None;
+        while self.current_token != Token::RightBrace {
+            match self.current_token {
+                Token::Case if default.is_none() => {
+                    self.advance();
+                    let values = self.expression_list()?;
+                    self.expect(Token::LeftBrace)?;
+                    let body = self.block_body()?;
+                    self.expect(Token::RightBrace)?;
+                    arms.push(MatchArm { values, body });
+                }
+                Token::Default if default.is_none() => {
+                    self.advance();
+                    self.expect(Token::LeftBrace)?;
+                    default = Some(self.block_body()?);
+                    self.expect(Token::RightBrace)?;
+                }
+                _ => {
+                    return Err(anyhow!(IqraError {
+                        kind: "خطأ في بناء طابق".to_string(),
+                        message_ar: format!("متوقع 'حالة' أو 'افتراضي' داخل طابق، وجد {}", self.current_token),
+                        message_en: format!("Expected 'case' or 'default' inside match, found {}", self.current_token),
+                        suggestion: Some(tr!("suggest.cases_then_default")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                }
+            }
+            self.skip_newlines();
+        }
         self.expect(Token::RightBrace)?;
 
-        Ok(Stmt::While { condition, body })
+        Ok(StmtKind::Match { subject, arms, default })
     }
 
-    fn block_statement(&mut self) -> Result<Stmt> {
+    fn block_statement(&mut self) -> Result<StmtKind> {
         self.expect(Token::LeftBrace)?;
         let body = self.block_body()?;
         self.expect(Token::RightBrace)?;
 
-        Ok(Stmt::Block(body))
+        Ok(StmtKind::Block(body))
     }
 
     fn block_body(&mut self) -> Result<Vec<Stmt>> {
@@ -337,45 +990,33 @@ impl Parser {
     }
 
     fn expression(&mut self) -> Result<Expr> {
-        self.or_expression()
+        self.nested(Self::coalesce_expression)
     }
 
-    fn parse_expression_continuation(&mut self, left: Expr) -> Result<Expr> {
-        // Handle function calls and other operators
-        match &self.current_token {
-            Token::LeftParen => {
-                if let Expr::Identifier(name) = left {
-                    self.advance(); // consume '('
-                    let args = self.argument_list()?;
-                    self.expect(Token::RightParen)?;
-                    Ok(Expr::Call { name, args })
-                } else {
-                    Err(anyhow!(IqraError {
-                        kind: "خطأ في استدعاء الدالة".to_string(),
-                        message_ar: "استدعاء دالة غير صالح".to_string(),
-                        message_en: "Invalid function call".to_string(),
-                        suggestion: Some("استخدم اسم دالة صحيح قبل الأقواس".to_string()),
-                        line: None,
-                    }))
-                }
-            }
-            _ => {
-                self.parse_binary_with_left(left, 0)
-            }
+    /// `أ ?? ب`, below `أو` so `أ أو ب ?? ج` defaults the whole condition.
+    fn coalesce_expression(&mut self) -> Result<Expr> {
+        let mut expr = self.or_expression()?;
+        let mut height = None;
+
+        while self.current_token == Token::Coalesce {
+            self.advance();
+            let right = self.or_expression()?;
+            self.check_chain(&mut height, &expr, &right)?;
+            expr = Expr::binary(expr, BinaryOp::Coalesce, right);
         }
+
+        Ok(expr)
     }
 
     fn or_expression(&mut self) -> Result<Expr> {
         let mut expr = self.and_expression()?;
+        let mut height = None;
 
         while self.current_token == Token::Or {
             self.advance();
             let right = self.and_expression()?;
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                operator: BinaryOp::Or,
-                right: Box::new(right),
-            };
+            self.check_chain(&mut height, &expr, &right)?;
+            expr = Expr::binary(expr, BinaryOp::Or, right);
         }
 
         Ok(expr)
@@ -383,15 +1024,13 @@ impl Parser {
 
     fn and_expression(&mut self) -> Result<Expr> {
         let mut expr = self.equality_expression()?;
+        let mut height = None;
 
         while self.current_token == Token::And {
             self.advance();
             let right = self.equality_expression()?;
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                operator: BinaryOp::And,
-                right: Box::new(right),
-            };
+            self.check_chain(&mut height, &expr, &right)?;
+            expr = Expr::binary(expr, BinaryOp::And, right);
         }
 
         Ok(expr)
@@ -399,6 +1038,7 @@ impl Parser {
 
     fn equality_expression(&mut self) -> Result<Expr> {
         let mut expr = self.comparison_expression()?;
+        let mut height = None;
 
         while matches!(self.current_token, Token::Equal | Token::NotEqual) {
             let op = match self.current_token {
@@ -408,7 +1048,8 @@ impl Parser {
             };
             self.advance();
             let right = self.comparison_expression()?;
-            expr = Expr::Binary { left: Box::new(expr), operator: op, right: Box::new(right) };
+            self.check_chain(&mut height, &expr, &right)?;
+            expr = Expr::binary(expr, op, right);
         }
 
         Ok(expr)
@@ -416,6 +1057,7 @@ impl Parser {
 
     fn comparison_expression(&mut self) -> Result<Expr> {
         let mut expr = self.term_expression()?;
+        let mut height = None;
 
         while matches!(
             self.current_token,
@@ -430,7 +1072,8 @@ impl Parser {
             };
             self.advance();
             let right = self.term_expression()?;
-            expr = Expr::Binary { left: Box::new(expr), operator: op, right: Box::new(right) };
+            self.check_chain(&mut height, &expr, &right)?;
+            expr = Expr::binary(expr, op, right);
         }
 
         Ok(expr)
@@ -438,6 +1081,7 @@ impl Parser {
 
     fn term_expression(&mut self) -> Result<Expr> {
         let mut expr = self.factor_expression()?;
+        let mut height = None;
 
         while matches!(self.current_token, Token::Plus | Token::Minus) {
             let op = match self.current_token {
@@ -447,7 +1091,8 @@ impl Parser {
             };
             self.advance();
             let right = self.factor_expression()?;
-            expr = Expr::Binary { left: Box::new(expr), operator: op, right: Box::new(right) };
+            self.check_chain(&mut height, &expr, &right)?;
+            expr = Expr::binary(expr, op, right);
         }
 
         Ok(expr)
@@ -455,6 +1100,7 @@ impl Parser {
 
     fn factor_expression(&mut self) -> Result<Expr> {
         let mut expr = self.unary_expression()?;
+        let mut height = None;
 
         while matches!(self.current_token, Token::Multiply | Token::Divide | Token::Modulo) {
             let op = match self.current_token {
@@ -465,66 +1111,144 @@ impl Parser {
             };
             self.advance();
             let right = self.unary_expression()?;
-            expr = Expr::Binary { left: Box::new(expr), operator: op, right: Box::new(right) };
+            self.check_chain(&mut height, &expr, &right)?;
+            expr = Expr::binary(expr, op, right);
         }
 
         Ok(expr)
     }
 
+    /// Wraps `kind` with a span from `start` to the last token consumed.
+    fn spanned(&self, kind: ExprKind, start: Span) -> Expr {
+        Expr { kind, span: start.to(self.previous_span) }
+    }
+
     fn unary_expression(&mut self) -> Result<Expr> {
+        let start = self.current_span;
         match &self.current_token {
             Token::Not => {
                 self.advance();
-                let operand = self.unary_expression()?;
-                Ok(Expr::Unary { operator: UnaryOp::Not, operand: Box::new(operand) })
+                let operand = self.nested(Self::unary_expression)?;
+                Ok(self.spanned(ExprKind::Unary { operator: UnaryOp::Not, operand: Box::new(operand) }, start))
             }
             Token::Minus => {
                 self.advance();
-                let operand = self.unary_expression()?;
-                Ok(Expr::Unary { operator: UnaryOp::Minus, operand: Box::new(operand) })
+                let operand = self.nested(Self::unary_expression)?;
+                Ok(self.spanned(ExprKind::Unary { operator: UnaryOp::Minus, operand: Box::new(operand) }, start))
+            }
+            // Covers the whole expression to its right, so `آمن ١ / ٠` catches
+            // the division; use parentheses to make it part of a larger one.
+            Token::Safe => {
+                self.advance();
+                let operand = self.expression()?;
+                Ok(self.spanned(ExprKind::SafeEval(Box::new(operand)), start))
+            }
+            _ => self.postfix_expression(),
+        }
+    }
+
+    /// Applies any `[فهرس]` that follows a primary expression, so indexing
+    /// works after identifiers, calls, lists, parentheses and other indexes.
+    fn postfix_expression(&mut self) -> Result<Expr> {
+        let mut expr = self.primary_expression()?;
+        let mut height = None;
+        loop {
+            match self.current_token {
+                Token::LeftBracket => {
+                    self.advance();
+                    let index = self.expression()?;
+                    self.expect(Token::RightBracket)?;
+                    self.check_chain(&mut height, &expr, &index)?;
+                    let start = expr.span;
+                    expr = self.spanned(ExprKind::Index { object: Box::new(expr), index: Box::new(index) }, start);
+                }
+                // Only named functions can be called; a call on any other
+                // expression needs function values.
+                Token::LeftParen => {
+                    return Err(anyhow!(IqraError {
+                        kind: "خطأ في استدعاء الدالة".to_string(),
+                        message_ar: "لا يمكن استدعاء نتيجة تعبير؛ يمكن استدعاء الدوال بأسمائها فقط".to_string(),
+                        message_en: "Cannot call the result of an expression; only named functions can be called".to_string(),
+                        suggestion: Some(tr!("suggest.call_by_name")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                }
+                _ => return Ok(expr),
             }
-            _ => self.primary_expression(),
         }
     }
 
     fn primary_expression(&mut self) -> Result<Expr> {
+        let start = self.current_span;
         match &self.current_token.clone() {
             Token::Number(n) => {
                 let value = *n;
                 self.advance();
-                Ok(Expr::Literal(Value::Number(value)))
+                Ok(self.spanned(ExprKind::Literal(Value::Number(value)), start))
+            }
+            Token::Int(n) => {
+                let value = *n;
+                self.advance();
+                Ok(self.spanned(ExprKind::Literal(Value::Int(value)), start))
             }
             Token::String(s) => {
                 let value = s.clone();
                 self.advance();
-                Ok(Expr::Literal(Value::String(value)))
+                Ok(self.spanned(ExprKind::Literal(Value::String(value)), start))
             }
             Token::True => {
                 self.advance();
-                Ok(Expr::Literal(Value::Bool(true)))
+                Ok(self.spanned(ExprKind::Literal(Value::Bool(true)), start))
             }
             Token::False => {
                 self.advance();
-                Ok(Expr::Literal(Value::Bool(false)))
+                Ok(self.spanned(ExprKind::Literal(Value::Bool(false)), start))
             }
             Token::Identifier(name) => {
                 let name = name.clone();
                 self.advance();
 
-                if self.current_token == Token::LeftParen {
+                if BUILTIN_QUALIFIERS.contains(&name.as_str()) && self.current_token == Token::Dot {
+                    self.advance();
+                    let name = match &self.current_token {
+                        Token::Identifier(name) => name.clone(),
+                        other => {
+                            return Err(anyhow!(IqraError {
+                                kind: "خطأ في استدعاء مدمج".to_string(),
+                                message_ar: format!("متوقع اسم دالة مدمجة بعد النقطة، وجد {}", other),
+                                message_en: format!("Expected a builtin name after the dot, found {}", other),
+                                suggestion: Some(tr!("suggest.example_builtin_escape")),
+                                line: None,
+                                column: None,
+                                source_name: None,
+                            }));
+                        }
+                    };
+                    self.advance();
+                    let open = self.current_span;
+                    self.expect(Token::LeftParen)?;
+                    let args = self.argument_list()?;
+                    self.close_paren(open)?;
+                    Ok(self.spanned(ExprKind::BuiltinCall { name, args }, start))
+                } else if self.current_token == Token::LeftParen {
                     // Function call
+                    let open = self.current_span;
                     self.advance();
                     let args = self.argument_list()?;
-                    self.expect(Token::RightParen)?;
-                    Ok(Expr::Call { name, args })
+                    self.close_paren(open)?;
+                    Ok(self.spanned(ExprKind::Call { name, args }, start))
                 } else {
-                    Ok(Expr::Identifier(name))
+                    Ok(self.spanned(ExprKind::Identifier(name), start))
                 }
             }
             Token::LeftParen => {
                 self.advance();
-                let expr = self.expression()?;
-                self.expect(Token::RightParen)?;
+                let mut expr = self.expression()?;
+                self.close_paren(start)?;
+                // The parentheses belong to the expression's source text.
+                expr.span = start.to(self.previous_span);
                 Ok(expr)
             }
             Token::LeftBracket => {
@@ -535,72 +1259,44 @@ impl Parser {
                     self.expression_list()?
                 };
                 self.expect(Token::RightBracket)?;
-                Ok(Expr::List(elements))
+                Ok(self.spanned(ExprKind::List(elements), start))
             }
             _ => Err(anyhow!(IqraError {
                 kind: "رمز غير متوقع".to_string(),
                 message_ar: format!("رمز غير متوقع: {:?}", self.current_token),
                 message_en: format!("Unexpected token: {:?}", self.current_token),
-                suggestion: Some("راجع بناء الجملة أو الرموز المستخدمة".to_string()),
+                suggestion: Some(tr!("suggest.check_syntax")),
                 line: None,
+                column: None,
+                source_name: None,
             })),
         }
     }
 
-    fn parse_binary_with_left(&mut self, left: Expr, min_precedence: i32) -> Result<Expr> {
-        let mut left = left;
-
-        while let Some(op) = self.binary_operator() {
-            let precedence = self.operator_precedence(&op);
-            if precedence < min_precedence {
-                break;
-            }
-
+    /// Consumes the `)` closing the `(` at `open`. Anything else in its
+    /// place is reported at that token, as the `{` of the body in
+    /// `اذا (س > ١ {`, naming where the `(` was.
+    fn close_paren(&mut self, open: Span) -> Result<()> {
+        if self.current_token == Token::RightParen {
             self.advance();
-            let mut right = self.unary_expression()?;
-
-            while let Some(next_op) = self.binary_operator() {
-                let next_precedence = self.operator_precedence(&next_op);
-                if next_precedence <= precedence {
-                    break;
-                }
-                right = self.parse_binary_with_left(right, next_precedence)?;
-            }
-
-            left = Expr::Binary { left: Box::new(left), operator: op, right: Box::new(right) };
-        }
-
-        Ok(left)
-    }
-
-    fn binary_operator(&self) -> Option<BinaryOp> {
-        match self.current_token {
-            Token::Plus => Some(BinaryOp::Add),
-            Token::Minus => Some(BinaryOp::Subtract),
-            Token::Multiply => Some(BinaryOp::Multiply),
-            Token::Divide => Some(BinaryOp::Divide),
-            Token::Modulo => Some(BinaryOp::Modulo),
-            Token::Equal => Some(BinaryOp::Equal),
-            Token::NotEqual => Some(BinaryOp::NotEqual),
-            Token::Less => Some(BinaryOp::Less),
-            Token::LessEqual => Some(BinaryOp::LessEqual),
-            Token::Greater => Some(BinaryOp::Greater),
-            Token::GreaterEqual => Some(BinaryOp::GreaterEqual),
-            Token::And => Some(BinaryOp::And),
-            Token::Or => Some(BinaryOp::Or),
-            _ => None,
-        }
-    }
-
-    fn operator_precedence(&self, op: &BinaryOp) -> i32 {
-        match op {
-            BinaryOp::Or => 1,
-            BinaryOp::And => 2,
-            BinaryOp::Equal | BinaryOp::NotEqual => 3,
-            BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual => 4,
-            BinaryOp::Add | BinaryOp::Subtract => 5,
-            BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Modulo => 6,
+            return Ok(());
         }
+        let found = &self.current_token;
+        Err(anyhow!(IqraError {
+            kind: "قوس غير مغلق".to_string(),
+            message_ar: format!(
+                "القوس ( في السطر {} العمود {} لم يُغلق، وجد {} مكان )",
+                open.start_line, open.start_col, found
+            ),
+            message_en: format!(
+                "The ( at line {} column {} is not closed; found {} where ) was expected",
+                open.start_line, open.start_col, found
+            ),
+            suggestion: Some(tr!("suggest.missing_paren")),
+            line: Some(self.current_span.start_line),
+            column: Some(self.current_span.start_col),
+            source_name: None,
+        }))
     }
 
     fn argument_list(&mut self) -> Result<Vec<Expr>> {
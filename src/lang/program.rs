@@ -0,0 +1,37 @@
+//! برنامج مترجم | Source parsed once and run as many times as needed.
+
+use crate::lang::lexer::Lexer;
+use crate::lang::optimizer::optimize;
+use crate::lang::parser::{Parser, Stmt};
+use anyhow::Result;
+
+/// A parsed program. Compiling once and running it on several runtimes avoids
+/// paying for lexing and parsing on every run.
+#[derive(Debug, Clone)]
+pub struct Program {
+    statements: Vec<Stmt>,
+}
+
+impl Program {
+    /// Parses `source`, reporting syntax errors with their line.
+    pub fn compile(source: &str) -> Result<Program> {
+        let statements = Parser::new(Lexer::new(source)).parse()?;
+        Ok(Program { statements })
+    }
+
+    /// Like [`Program::compile`], naming `name` as the source in errors.
+    pub fn compile_named(source: &str, name: &str) -> Result<Program> {
+        let mut parser = Parser::new(Lexer::new(source));
+        parser.set_source_name(name);
+        Ok(Program { statements: parser.parse()? })
+    }
+
+    /// The program after the optimizer's pass; see `optimizer::optimize`.
+    pub fn optimized(self) -> Program {
+        Program { statements: optimize(self.statements) }
+    }
+
+    pub fn statements(&self) -> &[Stmt] {
+        &self.statements
+    }
+}
@@ -5,36 +5,214 @@ pub struct IqraError {
     pub message_en: String,
     pub suggestion: Option<String>,
     pub line: Option<usize>,
+    /// The 1-based character column on `line` where the error was found,
+    /// when known: the token for parse errors, the statement for runtime ones.
+    pub column: Option<usize>,
+    /// The file or other source the error came from, such as `<repl>`.
+    pub source_name: Option<String>,
 }
 
+pub use crate::i18n::ErrorLanguage;
+
 impl std::fmt::Display for IqraError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}] {} | {}", self.kind, self.message_ar, self.message_en)?;
-        if let Some(suggestion) = &self.suggestion {
-            write!(f, "\nاقتراح: {} | Suggestion: {}", suggestion, suggestion)?;
+        write!(f, "{}", self.localized(ErrorLanguage::current()))
+    }
+}
+
+/// An `IqraError` displayed in a fixed language, see [`IqraError::localized`].
+pub struct LocalizedError<'a> {
+    error: &'a IqraError,
+    language: ErrorLanguage,
+}
+
+impl std::fmt::Display for LocalizedError<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (error, language) = (self.error, self.language);
+        write!(f, "[{}] {}", error.kind, error.message_in(language))?;
+        if let Some(suggestion) = &error.suggestion {
+            match language {
+                ErrorLanguage::Arabic => write!(f, "\nاقتراح: {}", suggestion)?,
+                ErrorLanguage::English => write!(f, "\nSuggestion: {}", suggestion)?,
+                // Suggestions are written once, usually already in both languages.
+                ErrorLanguage::Both => write!(f, "\nاقتراح | Suggestion: {}", suggestion)?,
+            }
         }
-        if let Some(line) = self.line {
-            write!(f, "\nالسطر: {} | Line: {}", line, line)?;
+        if let Some(line) = error.line {
+            match language {
+                ErrorLanguage::Arabic => write!(f, "\nالسطر: {}", line)?,
+                ErrorLanguage::English => write!(f, "\nLine: {}", line)?,
+                ErrorLanguage::Both => write!(f, "\nالسطر: {} | Line: {}", line, line)?,
+            }
+        }
+        if let Some(name) = &error.source_name {
+            match language {
+                ErrorLanguage::Arabic => write!(f, "\nالملف: {}", name)?,
+                ErrorLanguage::English => write!(f, "\nFile: {}", name)?,
+                ErrorLanguage::Both => write!(f, "\nالملف: {} | File: {}", name, name)?,
+            }
         }
         Ok(())
     }
 }
-use crate::lang::lexer::Lexer;
-use crate::lang::parser::{BinaryOp, Expr, Parser, Stmt, UnaryOp};
-use crate::lang::value::Value;
+
+impl IqraError {
+    /// Displays the error in `language` instead of the process-wide setting.
+    pub fn localized(&self, language: ErrorLanguage) -> LocalizedError<'_> {
+        LocalizedError { error: self, language }
+    }
+
+    /// The message alone, in `language`.
+    pub fn message_in(&self, language: ErrorLanguage) -> String {
+        language.pick(&self.message_ar, &self.message_en)
+    }
+
+    /// Whether `err` is of kind `pattern`, as a filtered `امسك` arm compares them.
+    /// Either half of a bilingual kind such as `انتهت المهلة | Timeout`
+    /// matches, English ignoring case. Errors that aren't an `IqraError`
+    /// have kind `خطأ`, as in [`error_map`].
+    pub fn kind_matches(err: &anyhow::Error, pattern: &str) -> bool {
+        let pattern = pattern.trim();
+        let kind = err.downcast_ref::<IqraError>().map_or("خطأ", |e| e.kind.as_str());
+        kind.trim() == pattern
+            || kind.split('|').any(|part| part.trim().to_lowercase() == pattern.to_lowercase())
+    }
+
+    /// Fills in `line` on an `IqraError` that does not know its line yet.
+    /// Errors that already carry a line, and non-Iqra errors, are left as-is.
+    pub fn with_line(mut err: anyhow::Error, line: usize) -> anyhow::Error {
+        if let Some(iqra_err) = err.downcast_mut::<IqraError>()
+            && iqra_err.line.is_none()
+        {
+            iqra_err.line = Some(line);
+        }
+        err
+    }
+
+    /// Like [`IqraError::with_line`], also filling in `column` when the line
+    /// was missing.
+    pub fn with_position(mut err: anyhow::Error, line: usize, column: usize) -> anyhow::Error {
+        if let Some(iqra_err) = err.downcast_mut::<IqraError>()
+            && iqra_err.line.is_none()
+        {
+            iqra_err.line = Some(line);
+            iqra_err.column = Some(column);
+        }
+        err
+    }
+
+    /// Fills in `source_name` on an `IqraError` that does not have one yet, column: None,
+    /// like [`IqraError::with_line`].
+    pub fn with_source_name(mut err: anyhow::Error, name: &str) -> anyhow::Error {
+        if let Some(iqra_err) = err.downcast_mut::<IqraError>()
+            && iqra_err.source_name.is_none()
+        {
+            iqra_err.source_name = Some(name.to_string());
+        }
+        err
+    }
+}
+use crate::lang::analyzer;
+use crate::lang::builtins;
+use crate::lang::hijri::{self, Clock, HijriDate, SystemClock};
+use chrono::NaiveDate;
+use crate::lang::builtins::arabic::{self, DigitScript, NormalizeOptions, to_ascii_digits};
+use crate::lang::builtins::cache::ScriptCache;
+use crate::lang::builtins::format::{self, FormatError};
+use crate::lang::builtins::matrix::{self, Matrix, MatrixError};
+use crate::lang::builtins::{bytes, collections, path, stats};
+use crate::lang::lexer::Span;
+use crate::lang::parser::{
+    BinaryOp, DEFAULT_MAX_NESTING, Expr, ExprKind, MatchArm, NESTING_ERROR_KIND, Param, Stmt, StmtKind, UnaryOp,
+    nesting_error,
+};
+use crate::lang::program::Program;
+use crate::lang::symbol::{Symbol, SymbolMap, SymbolSet};
+use crate::paths;
+use crate::i18n::{Arg, List, Word};
+use crate::tr;
+use crate::lang::value::{Value, ValueSet, display_width, format_number};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use crate::lang::output::{CapturedOutput, OutputSink, StdoutSink};
+use owo_colors::{OwoColorize, Style};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use anyhow::{Result, anyhow};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
+use std::ffi::OsString;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Arc, OnceLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// ناتج أمر | Everything a finished command produced.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// The exit code, or `None` when a signal ended the process.
+    pub code: Option<i32>,
+}
 
-pub trait SystemExecutor {
+impl ExecOutput {
+    /// The map `نفذ_أمر_بمدخل_كامل` returns.
+    pub fn to_value(&self) -> Value {
+        Value::Map(HashMap::from([
+            ("مخرج".to_string(), Value::String(self.stdout.clone())),
+            ("أخطاء".to_string(), Value::String(self.stderr.clone())),
+            ("رمز_الخروج".to_string(), self.code.map_or(Value::Nil, |code| Value::Int(code.into()))),
+            ("نجح".to_string(), Value::Bool(self.code == Some(0))),
+        ]))
+    }
+}
+
+/// `Send` so a `Runtime` can move to another thread.
+pub trait SystemExecutor: Send {
     fn exec(&self, cmd: &str) -> std::io::Result<String>;
     fn exec_with_io(&self, cmd: &str, input: &str) -> std::io::Result<String>;
+    /// Like `exec_with_io`, but also returns stderr and the exit code. The
+    /// default reports only stdout, as a successful run.
+    fn exec_with_io_full(&self, cmd: &str, input: &str) -> std::io::Result<ExecOutput> {
+        Ok(ExecOutput { stdout: self.exec_with_io(cmd, input)?, stderr: String::new(), code: Some(0) })
+    }
     fn read_file(&self, path: &str) -> std::io::Result<String>;
+    /// The contents of the file at `path` as they are. The default reads
+    /// them as text.
+    fn read_bytes(&self, path: &str) -> std::io::Result<Vec<u8>> {
+        self.read_file(path).map(String::into_bytes)
+    }
+    /// The size of the file at `path`, if known without reading it.
+    fn file_size(&self, _path: &str) -> Option<u64> {
+        None
+    }
     fn write_file(&self, path: &str, content: &str) -> std::io::Result<bool>;
+    /// Writes `content` to the file at `path` as it is. The default writes
+    /// it as text, replacing bytes that aren't UTF-8.
+    fn write_bytes(&self, path: &str, content: &[u8]) -> std::io::Result<bool> {
+        self.write_file(path, &String::from_utf8_lossy(content))
+    }
+    /// Creates the directory `path` and any missing parents.
+    fn create_dir_all(&self, _path: &str) -> std::io::Result<()> {
+        Ok(())
+    }
     fn list_files(&self, path: &str) -> std::io::Result<Vec<String>>;
     fn get_env_var(&self, name: &str) -> Option<String>;
+    fn set_env_var(&self, name: &str, value: &str) -> std::io::Result<()>;
+    fn current_dir(&self) -> std::io::Result<String>;
+    fn change_dir(&self, path: &str) -> std::io::Result<()>;
+    fn process_id(&self) -> u32;
+    /// Shows `prompt` and reads one line of input, without the line ending.
+    fn read_line(&self, prompt: &str) -> std::io::Result<String>;
+    /// Like `read_line`, but the typed characters are not echoed.
+    fn read_password(&self, prompt: &str) -> std::io::Result<String>;
     fn system_info(&self) -> std::io::Result<HashMap<String, String>>;
 }
 
@@ -66,6 +244,10 @@ impl SystemExecutor for DefaultSystemExecutor {
     }
 
     fn exec_with_io(&self, cmd: &str, input: &str) -> std::io::Result<String> {
+        Ok(self.exec_with_io_full(cmd, input)?.stdout)
+    }
+
+    fn exec_with_io_full(&self, cmd: &str, input: &str) -> std::io::Result<ExecOutput> {
         let allow_shell_fallback = env::var("IQRA_ALLOW_SHELL_FALLBACK").is_ok();
 
         let mut command = if allow_shell_fallback {
@@ -98,33 +280,61 @@ impl SystemExecutor for DefaultSystemExecutor {
 
         let mut child = command.spawn()?;
 
-        if let Some(stdin) = child.stdin.take() {
-            let mut stdin = stdin;
-            stdin.write_all(input.as_bytes())?;
-        }
-
+        // Writing all the input before reading would deadlock once the child
+        // fills its stdout pipe, so the input goes in from another thread
+        // while `wait_with_output` drains stdout and stderr.
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let input = input.to_string();
+        let writer = std::thread::spawn(move || match stdin.write_all(input.as_bytes()) {
+            // A child that exits without reading all its input is not an error.
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+            result => result,
+        });
         let output = child.wait_with_output()?;
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        writer.join().expect("stdin writer panicked")?;
+
+        Ok(ExecOutput {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            code: output.status.code(),
+        })
     }
 
     fn read_file(&self, path: &str) -> std::io::Result<String> {
         fs::read_to_string(path)
     }
 
+    fn read_bytes(&self, path: &str) -> std::io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+
+    fn file_size(&self, path: &str) -> Option<u64> {
+        fs::metadata(path).ok().map(|metadata| metadata.len())
+    }
+
     fn write_file(&self, path: &str, content: &str) -> std::io::Result<bool> {
         fs::write(path, content)?;
         Ok(true)
     }
 
+    fn write_bytes(&self, path: &str, content: &[u8]) -> std::io::Result<bool> {
+        fs::write(path, content)?;
+        Ok(true)
+    }
+
+    fn create_dir_all(&self, path: &str) -> std::io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
     fn list_files(&self, path: &str) -> std::io::Result<Vec<String>> {
         let entries = fs::read_dir(path)?;
         let mut files = Vec::new();
 
         for entry in entries {
             let entry = entry?;
-            if let Some(name) = entry.file_name().to_str() {
-                files.push(name.to_string());
-            }
+            // Names that aren't valid UTF-8 are listed with U+FFFD in place
+            // of the bad bytes rather than dropped.
+            files.push(entry.file_name().to_string_lossy().into_owned());
         }
 
         files.sort();
@@ -135,6 +345,41 @@ impl SystemExecutor for DefaultSystemExecutor {
         env::var(name).ok()
     }
 
+    fn set_env_var(&self, name: &str, value: &str) -> std::io::Result<()> {
+        if name.is_empty() || name.contains(['=', '\0']) || value.contains('\0') {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid environment variable"));
+        }
+        // SAFETY: the interpreter runs scripts on a single thread, so nothing
+        // reads the environment concurrently with this write.
+        unsafe { env::set_var(name, value) };
+        Ok(())
+    }
+
+    fn current_dir(&self) -> std::io::Result<String> {
+        Ok(env::current_dir()?.to_string_lossy().to_string())
+    }
+
+    fn change_dir(&self, path: &str) -> std::io::Result<()> {
+        env::set_current_dir(path)
+    }
+
+    fn process_id(&self) -> u32 {
+        std::process::id()
+    }
+
+    fn read_line(&self, prompt: &str) -> std::io::Result<String> {
+        use std::io::Write;
+        print!("{}", prompt);
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    }
+
+    fn read_password(&self, prompt: &str) -> std::io::Result<String> {
+        rpassword::prompt_password(prompt)
+    }
+
     fn system_info(&self) -> std::io::Result<HashMap<String, String>> {
         let mut info = HashMap::new();
 
@@ -172,478 +417,3070 @@ impl SystemExecutor for DefaultSystemExecutor {
     }
 }
 
-pub struct Runtime {
-    variable_stack: Vec<HashMap<String, Value>>,
-    functions: HashMap<String, (Vec<String>, Vec<Stmt>)>,
-    system_executor: Box<dyn SystemExecutor>,
-    today_cache: Option<String>,
-    system_info_cache: Option<HashMap<String, String>>,
+/// An evaluated operand: a literal or variable element is borrowed where it
+/// lives rather than copied.
+enum Operand<'e> {
+    Literal(&'e Value),
+    Place(Symbol, Vec<Value>),
+    Value(Value),
 }
 
-impl Default for Runtime {
-    fn default() -> Self {
-        Self::new()
+/// The element of a list or map at `index`, borrowed from `object`.
+fn index_ref<'v>(object: &'v Value, index: &Value) -> Result<&'v Value> {
+    match (object, index) {
+        (Value::List(list), Value::Number(_) | Value::Int(_)) => {
+            let idx = index_position(index)?;
+            list.get(idx).ok_or_else(|| anyhow!(IqraError {
+                kind: "فهرسة خارج النطاق".to_string(),
+                message_ar: format!("الفهرس خارج النطاق: {}", idx),
+                message_en: format!("Index out of bounds: {}", idx),
+                suggestion: Some(tr!("suggest.index_in_bounds")),
+                line: None,
+                column: None,
+                source_name: None,
+            }))
+        }
+        (Value::Map(map), Value::String(key)) => {
+            map.get(key).ok_or_else(|| anyhow!(IqraError {
+                kind: "مفتاح غير موجود".to_string(),
+                message_ar: format!("المفتاح غير موجود: {}", key),
+                message_en: format!("Key not found: {}", key),
+                suggestion: Some(tr!("suggest.key_exists")),
+                line: None,
+                column: None,
+                source_name: None,
+            }))
+        }
+        _ => Err(invalid_index_error()),
     }
 }
 
-impl Runtime {
-    /// Returns a reference to the current variables (for REPL step mode)
-    pub fn get_variables(&self) -> &HashMap<String, Value> {
-        self.variable_stack.last().unwrap()
-    }
-    fn call_user_function(&mut self, name: &str, args: &[Value]) -> Result<Value> {
-        // Lazy evaluation: defer block execution, avoid unnecessary evaluation
-        let (params, body) = self.functions.get(name).ok_or_else(|| anyhow!(IqraError {
-            kind: "دالة غير معرفة".to_string(),
-            message_ar: format!("الدالة غير معرفة: {}", name),
-            message_en: format!("Undefined function: {}", name),
-            suggestion: Some("تأكد من كتابة اسم الدالة بشكل صحيح".to_string()),
+/// The byte of `bytes` at `index`, as a number.
+fn byte_at(bytes: &[u8], index: &Value) -> Result<Value> {
+    let idx = match index {
+        Value::Int(_) | Value::Number(_) => index_position(index)?,
+        _ => return Err(invalid_index_error()),
+    };
+    bytes.get(idx).map(|byte| Value::Int(i64::from(*byte))).ok_or_else(|| anyhow!(IqraError {
+        kind: "فهرسة خارج النطاق".to_string(),
+        message_ar: format!("الفهرس خارج النطاق: {}", idx),
+        message_en: format!("Index out of bounds: {}", idx),
+        suggestion: Some(tr!("suggest.byte_index")),
+        line: None,
+        column: None,
+        source_name: None,
+    }))
+}
+
+/// A numeric `index` as a position, or an error showing the index as written
+/// when it is negative or not a whole number.
+fn index_position(index: &Value) -> Result<usize> {
+    let position = match index {
+        Value::Int(n) => usize::try_from(*n).ok(),
+        Value::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Some(*n as usize),
+        _ => None,
+    };
+    position.ok_or_else(|| anyhow!(IqraError {
+        kind: "فهرس غير صالح".to_string(),
+        message_ar: format!("الفهرس يجب أن يكون عدداً صحيحاً غير سالب: {}", index),
+        message_en: format!("Index must be a non-negative whole number: {}", index),
+        suggestion: Some(tr!("suggest.whole_index")),
+        line: None,
+        column: None,
+        source_name: None,
+    }))
+}
+
+fn invalid_index_error() -> anyhow::Error {
+    anyhow!(IqraError {
+        kind: "عملية فهرسة غير صالحة".to_string(),
+        message_ar: "عملية فهرسة غير صالحة".to_string(),
+        message_en: "Invalid indexing operation".to_string(),
+        suggestion: Some(tr!("suggest.indexable")),
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+/// Builtins that only read their arguments, so a call can pass variables by
+/// reference instead of copying them.
+const READ_ONLY_BUILTINS: &[&str] = &["طول", "عنصر", "جلب_عنصر", "يحتوي", "نوع"];
+
+/// Builtins whose first argument is a file path, expanded by
+/// `Runtime::expand_path_arg` before they run.
+const PATH_BUILTINS: &[&str] = &[
+    "اقرأ_ملف", "اقرأ_بايتات", "اقرأ_ملف_بترميز", "اكتب_ملف_بترميز", "خمّن_الترميز", "اكتب_ملف",
+    "قائمة_ملفات", "تجزئة_ملف", "غير_مجلد", "احفظ_الحالة", "استرجع_الحالة",
+];
+
+/// أسماء الدوال المدمجة | Names (Arabic and English) accepted by `call_builtin`.
+pub const BUILTIN_FUNCTIONS: &[&str] = &[
+    "اطبع", "print",
+    "اطبع_ملون", "print_color",
+    "اطبع_بدون_سطر", "print_inline",
+    "اطبع_مفصول", "print_sep",
+    "اطبع_خطأ", "print_error",
+    "قائمة", "list",
+    "عنصر", "get",
+    "أضف", "append",
+    "احذف", "remove",
+    "يحتوي", "contains",
+    "قاموس", "map",
+    "جلب_عنصر", "map_get",
+    "تعيين_عنصر", "map_set",
+    "حذف_عنصر", "map_remove",
+    "عناصر", "items",
+    "اقلب", "invert",
+    "عد_التكرار", "count_by",
+    "جمّع_حسب", "group_by",
+    "افرز_قيم", "tally_top",
+    "رقّم", "enumerate",
+    "نطاق_فهارس", "indices",
+    "مجموعة", "set",
+    "أضف_لمجموعة", "set_add",
+    "في_مجموعة؟", "set_contains",
+    "اتحاد", "union",
+    "تقاطع", "intersection",
+    "فرق", "difference",
+    "إلى_قائمة", "to_list",
+    "خريطة_متوازية", "parallel_map",
+    "احذف_دالة", "undefine",
+    "نوع", "type",
+    "إلى_رقم", "to_number",
+    "إلى_نص", "to_string",
+    "رقم؟", "is_number",
+    "نص؟", "is_string",
+    "منطقي؟", "is_bool",
+    "إلى_منطقي", "to_bool",
+    "طول", "len",
+    "جمع", "sum",
+    "متوسط", "average",
+    "وسيط", "median",
+    "منوال", "mode",
+    "تباين", "variance",
+    "انحراف_معياري", "stddev",
+    "مئين", "percentile",
+    "انقل_مصفوفة", "transpose",
+    "أعد_تشكيل", "reshape",
+    "اجمع_مصفوفات", "matrix_add",
+    "اضرب_مصفوفات", "matrix_multiply",
+    "أكبر", "max",
+    "أصغر", "min",
+    "بين؟", "between",
+    "عدد_الكلمات", "word_count",
+    "نسق_رقم", "format_number",
+    "نسق", "format",
+    "عكس", "reverse",
+    "كرر_نص", "repeat",
+    "احشِ_يمين", "pad_start",
+    "احشِ_يسار", "pad_end",
+    "وسّط", "center",
+    "أزل_التشكيل", "strip_diacritics",
+    "طبع_نص", "normalize_arabic",
+    "وحد_الأرقام", "normalize_digits",
+    "رمز_حرف", "char_code",
+    "حرف_من_رمز", "char_from_code",
+    "تطابق؟", "regex_match",
+    "ابحث_نمط", "regex_find_all",
+    "استبدل_نمط", "regex_replace",
+    "ترميز٦٤", "base64_encode",
+    "فك٦٤", "base64_decode",
+    "نص_إلى_بايتات", "to_bytes",
+    "بايتات_إلى_نص", "from_bytes",
+    "تجزئة", "sha256",
+    "تجزئة_ملف", "sha256_file",
+    "معرف_فريد", "uuid",
+    "تاريخ_اليوم", "today",
+    "تاريخ_هجري", "hijri_today",
+    "إلى_هجري", "to_hijri",
+    "من_هجري", "from_hijri",
+    "اسم_الشهر_الهجري", "hijri_month_name",
+    "نفذ_أمر", "system",
+    "نفذ_أمر_بمدخل", "system_with_io",
+    "نفذ_أمر_بمدخل_كامل", "system_with_io_full",
+    "اقرأ_ملف", "read_file",
+    "اقرأ_بايتات", "read_bytes",
+    "اقرأ_ملف_بترميز", "read_file_encoding",
+    "اكتب_ملف_بترميز", "write_file_encoding",
+    "خمّن_الترميز", "detect_encoding",
+    "اكتب_ملف", "write_file",
+    "قائمة_ملفات", "list_files",
+    "وصل_مسار", "join_path",
+    "وسع_مسار", "expand_path",
+    "اسم_الملف", "basename",
+    "مجلد_الملف", "dirname",
+    "متغير_بيئة", "env_var",
+    "عين_متغير_بيئة", "set_env",
+    "مجلد_حالي", "cwd",
+    "غير_مجلد", "change_dir",
+    "معرف_عملية", "pid",
+    "وسائط", "args",
+    "اخرج", "exit",
+    "أكد؟", "confirm",
+    "اختر", "choose",
+    "كلمة_سر", "password",
+    "معلومات_النظام", "system_info",
+    "مساعدة", "help",
+    "قائمة_الدوال", "list_builtins",
+    "المتغيرات_العامة", "globals",
+    "المتغيرات_المحلية", "locals",
+    "تذكر", "cache_get",
+    "خزّن", "cache_set",
+    "امسح_الذاكرة", "cache_clear",
+    "ملف_الأداء", "profile_report",
+    "احفظ_الحالة", "save_state",
+    "استرجع_الحالة", "restore_state",
+];
+
+/// اسم بديل | Another name a builtin can be called by. Each English name in
+/// [`BUILTIN_FUNCTIONS`] already stands for the Arabic one next to it; the
+/// names here are older ones kept so that existing scripts still run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuiltinAlias {
+    pub name: &'static str,
+    /// The builtin it calls, by a name in the same language.
+    pub target: &'static str,
+    /// For a name that is on its way out, the catalog key of its warning,
+    /// shown once per runtime with `name` and `target` filled in.
+    pub deprecation: Option<&'static str>,
+}
+
+pub const BUILTIN_ALIASES: &[BuiltinAlias] = &[
+    BuiltinAlias {
+        name: "طول_القائمة",
+        target: "طول",
+        deprecation: Some("warn.deprecated_list_len"),
+    },
+    BuiltinAlias {
+        name: "list_len",
+        target: "len",
+        deprecation: Some("warn.deprecated_list_len"),
+    },
+];
+
+/// The Arabic name of the builtin `name` calls, whether `name` is its
+/// Arabic name, its English one or an alias; `None` if it isn't a builtin.
+pub fn canonical_builtin(name: &str) -> Option<&'static str> {
+    static NAMES: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    let names = NAMES.get_or_init(|| {
+        let mut names: HashMap<_, _> = BUILTIN_FUNCTIONS
+            .chunks(2)
+            .flat_map(|pair| [(pair[0], pair[0]), (pair[1], pair[0])])
+            .collect();
+        for alias in BUILTIN_ALIASES {
+            let target = names[alias.target];
+            names.insert(alias.name, target);
+        }
+        names
+    });
+    names.get(name).copied()
+}
+
+/// Kinds of the errors raised for a missing map key or an out-of-range
+/// list index, which `??` replaces with its default.
+const MISSING_VALUE_KINDS: &[&str] = &["مفتاح غير موجود", "فهرسة خارج النطاق"];
+
+fn is_missing_value(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<IqraError>().is_some_and(|e| MISSING_VALUE_KINDS.contains(&e.kind.as_str()))
+}
+
+/// The kind of the error a cancelled run fails with.
+pub const CANCELLED_ERROR_KIND: &str = "أُلغي التنفيذ | Execution cancelled";
+
+/// The error for a value of `attempted` items or bytes, more than `max`.
+fn memory_limit_error(attempted: usize, max: usize, unit_ar: &str, unit_en: &str) -> anyhow::Error {
+    anyhow!(IqraError {
+        kind: "تجاوز حد الذاكرة | Memory limit exceeded".to_string(),
+        message_ar: format!("القيمة المطلوبة من {} {} والحد {}", attempted, unit_ar, max),
+        message_en: format!("The value would have {} {}; the limit is {}", attempted, unit_en, max),
+        suggestion: Some(tr!("suggest.smaller_pieces")),
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+/// The error for a run that took longer than `timeout`.
+pub fn timeout_error(timeout: Duration) -> anyhow::Error {
+    anyhow!(IqraError {
+        kind: "انتهت المهلة | Timeout".to_string(),
+        message_ar: format!("تجاوز البرنامج المهلة المسموحة ({} مللي ثانية)", timeout.as_millis()),
+        message_en: format!("The program exceeded the time limit of {} ms", timeout.as_millis()),
+        suggestion: Some(tr!("suggest.endless_loop")),
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+/// Nesting errors pass through `حاول` and `آمن` like limit errors, so a
+/// script can't hide one in an error value and carry on.
+fn is_nesting_error(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<IqraError>().is_some_and(|e| e.kind == NESTING_ERROR_KIND)
+}
+
+/// خريطة الخطأ | An error as a map script code can inspect, with keys
+/// `نوع`, `رسالة`, `message`, `اقتراح` and `سطر`. Errors that aren't an
+/// `IqraError` have kind `خطأ` and their text as both messages.
+pub fn error_map(err: &anyhow::Error) -> Value {
+    let (kind, message_ar, message_en, suggestion, line) = match err.downcast_ref::<IqraError>() {
+        Some(e) => (e.kind.clone(), e.message_ar.clone(), e.message_en.clone(), e.suggestion.clone(), e.line),
+        None => ("خطأ".to_string(), err.to_string(), err.to_string(), None, None),
+    };
+    Value::Map(HashMap::from([
+        ("نوع".to_string(), Value::String(kind)),
+        ("رسالة".to_string(), Value::String(message_ar)),
+        ("message".to_string(), Value::String(message_en)),
+        ("اقتراح".to_string(), suggestion.map_or(Value::Nil, Value::String)),
+        ("سطر".to_string(), line.map_or(Value::Nil, |line| Value::Int(line as i64))),
+    ]))
+}
+
+/// Adds the index of the failing element to an error from `parallel_map`.
+/// Exit requests pass through unchanged.
+fn element_error(index: usize, err: anyhow::Error) -> anyhow::Error {
+    if err.is::<ExitSignal>() {
+        return err;
+    }
+    match err.downcast_ref::<IqraError>() {
+        Some(inner) => anyhow!(IqraError {
+            message_ar: format!("عند العنصر {}: {}", index, inner.message_ar),
+            message_en: format!("At element {}: {}", index, inner.message_en),
+            ..inner.clone()
+        }),
+        None => anyhow!(IqraError {
+            kind: "خطأ في خريطة_متوازية".to_string(),
+            message_ar: format!("عند العنصر {}: {}", index, err),
+            message_en: format!("At element {}: {}", index, err),
+            suggestion: None,
             line: None,
-        }))?.clone();
-        if args.len() != params.len() {
+            column: None,
+            source_name: None,
+        }),
+    }
+}
+
+/// Pads `text` to `width` display columns for `pad_start`, `pad_end` and
+/// `center`. A wide fill that doesn't divide the gap is topped up with spaces.
+fn pad_text(name: &str, args: &[Value]) -> Result<Value> {
+    if !(2..=3).contains(&args.len()) {
+        return Err(arity_error(name, 2, Some(3), args.len()));
+    }
+    let (text, width) = match (&args[0], &args[1]) {
+        (Value::String(text), Value::Int(width)) if *width >= 0 => (text, *width as usize),
+        _ => {
             return Err(anyhow!(IqraError {
-                kind: "عدد وسائط غير صحيح".to_string(),
-                message_ar: "عدد الوسائط لا يطابق عدد المعاملات".to_string(),
-                message_en: "Argument count mismatch".to_string(),
-                suggestion: Some("تأكد من عدد الوسائط المدخلة".to_string()),
+                kind: "نوع وسيط غير صحيح".to_string(),
+                message_ar: format!("{} تتوقع نصاً وعرضاً صحيحاً غير سالب", name),
+                message_en: format!("{} expects a string and a non-negative whole width", name),
+                suggestion: Some(tr!("suggest.example_pad", name = name)),
                 line: None,
+                column: None,
+                source_name: None,
             }));
         }
-        // Save current variables (future: use stack frames for true lazy scope)
-        let old_vars = self.variable_stack.last().unwrap().clone();
-        for (p, v) in params.iter().zip(args.iter()) {
-            self.variable_stack.last_mut().unwrap().insert(p.clone(), v.clone());
-        }
-        // Execute body lazily: only evaluate statements as needed
-        let mut ret = Value::Nil;
-        for stmt in &body {
-            match self.execute_statement(stmt) {
-                Ok(v) => ret = v,
-                Err(e) => {
-                    let msg = format!("{}", e);
-                    if msg.starts_with("__RETURN__:") {
-                        let val_str = msg.trim_start_matches("__RETURN__:");
-                        if val_str.contains("String(") {
-                            let s = val_str.split("String(").nth(1).unwrap().split(")").next().unwrap();
-                            ret = Value::String(s.to_string());
-                        } else if val_str.contains("Number(") {
-                            let n = val_str.split("Number(").nth(1).unwrap().split(")").next().unwrap();
-                            ret = Value::Number(n.parse().unwrap_or(0.0));
-                        } else {
-                            ret = Value::Nil;
-                        }
-                        break;
-                    } else {
-                        *self.variable_stack.last_mut().unwrap() = old_vars.clone();
-                        // Wrap error in IqraError if not already
-                        if msg.contains("IqraError") {
-                            return Err(anyhow!(msg));
-                        } else {
-                            return Err(anyhow!(IqraError {
-                                kind: "خطأ في تنفيذ الدالة".to_string(),
-                                message_ar: format!("خطأ أثناء تنفيذ الدالة: {}", msg),
-                                message_en: format!("Error during function execution: {}", msg),
-                                suggestion: Some("راجع الكود داخل الدالة".to_string()),
-                                line: None,
-                            }));
-                        }
-                    }
-                }
-            }
+    };
+    let fill = match args.get(2) {
+        None => " ",
+        Some(Value::String(fill)) if fill.graphemes(true).count() == 1 && display_width(fill) > 0 => fill,
+        Some(other) => {
+            return Err(anyhow!(IqraError {
+                kind: "حشو غير صالح | Invalid Fill".to_string(),
+                message_ar: format!("حشو {} يجب أن يكون حرفاً واحداً ظاهراً، وصلها: {}", name, other),
+                message_en: format!("{} expects the fill to be one visible character, got: {}", name, other),
+                suggestion: Some(tr!("suggest.fill_character")),
+                line: None,
+                column: None,
+                source_name: None,
+            }));
         }
-    *self.variable_stack.last_mut().unwrap() = old_vars;
-        Ok(ret)
+    };
+    let gap = width.saturating_sub(display_width(text));
+    let padding = |columns: usize| {
+        let fill_width = display_width(fill);
+        format!("{}{}", fill.repeat(columns / fill_width), " ".repeat(columns % fill_width))
+    };
+    Ok(Value::String(match name {
+        "pad_start" | "احشِ_يمين" => format!("{}{}", padding(gap), text),
+        "pad_end" | "احشِ_يسار" => format!("{}{}", text, padding(gap)),
+        _ => format!("{}{}{}", padding(gap / 2), text, padding(gap - gap / 2)),
+    }))
+}
+
+/// Returns `value` as a set, or a type error naming builtin `name`.
+fn set_arg<'a>(name: &str, value: &'a Value) -> Result<&'a ValueSet> {
+    match value {
+        Value::Set(set) => Ok(set),
+        other => Err(anyhow!(IqraError {
+            kind: "نوع وسيط غير صحيح".to_string(),
+            message_ar: format!("{} تتوقع مجموعة، لكن وصلها {}", name, other.type_name_ar()),
+            message_en: format!("{} expects a set, got {}", name, other.type_name()),
+            suggestion: Some(tr!("suggest.make_set")),
+            line: None,
+            column: None,
+            source_name: None,
+        })),
     }
-    pub fn new() -> Self {
-            Runtime {
-                variable_stack: vec![HashMap::new()],
-                functions: HashMap::new(),
-                system_executor: Box::new(DefaultSystemExecutor),
-                today_cache: None,
-                system_info_cache: None,
-            }
+}
+
+/// دالة معرفة من المستخدم | A function defined with `دالة`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UserFunction {
+    params: Vec<Param>,
+    rest: Option<Symbol>,
+    body: Vec<Stmt>,
+    doc: Option<String>,
+}
+
+/// A variable bound by a function call: one of its parameters, or a name
+/// it assigned.
+struct Local {
+    name: Symbol,
+    value: Value,
+    constant: bool,
+}
+
+/// An active function call: where its locals start, and `locals_mask` as it
+/// was before the call.
+struct CallFrame {
+    first_local: usize,
+    mask: u64,
+}
+
+/// The bit standing for `name` in `Runtime::locals_mask`.
+fn local_bit(name: &Symbol) -> u64 {
+    let address = name.as_ptr() as u64;
+    1 << (address.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 58)
+}
+
+/// Format version written by `Runtime::save_state`.
+const SESSION_VERSION: u32 = 1;
+
+/// حالة الجلسة | Global variables, constants and functions as saved by
+/// `Runtime::save_state`. `skipped` lists variables that could not be saved.
+#[derive(Serialize, Deserialize)]
+struct SessionState {
+    version: u32,
+    variables: BTreeMap<Symbol, Value>,
+    constants: Vec<Symbol>,
+    functions: BTreeMap<Symbol, UserFunction>,
+    #[serde(default)]
+    skipped: Vec<Symbol>,
+}
+
+/// Whether `value` survives a JSON round trip; JSON has no NaN or infinity.
+pub(crate) fn is_saveable(value: &Value) -> bool {
+    match value {
+        Value::Number(n) => n.is_finite(),
+        Value::List(items) => items.iter().all(is_saveable),
+        Value::Map(map) => map.values().all(is_saveable),
+        Value::Set(set) => set.iter().all(is_saveable),
+        _ => true,
     }
+}
 
-    pub fn new_with_executor(executor: Box<dyn SystemExecutor>) -> Self {
-            Runtime {
-                variable_stack: vec![HashMap::new()],
-                functions: HashMap::new(),
-                system_executor: executor,
-                today_cache: None,
-                system_info_cache: None,
-            }
+fn session_error(message_ar: String, message_en: String) -> anyhow::Error {
+    anyhow!(IqraError {
+        kind: "خطأ في ملف الجلسة | Session Error".to_string(),
+        message_ar,
+        message_en,
+        suggestion: Some(tr!("suggest.session_file")),
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+/// Raised by `اخرج`/`exit` to stop the program with an exit code. It unwinds
+/// through function calls and `حاول` blocks, leaving it to the caller (the CLI
+/// or the REPL) to decide what exiting means.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitSignal(pub i32);
+
+impl std::fmt::Display for ExitSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&tr!("repl.exited", code = self.0))
     }
+}
 
-    pub fn execute(&mut self, input: &str) -> Result<Value> {
-        let lexer = Lexer::new(input);
-        let mut parser = Parser::new(lexer);
-        let statements = parser.parse()?;
+impl std::error::Error for ExitSignal {}
 
-        let mut last_value = Value::Nil;
-        for stmt in statements {
-            last_value = self.execute_statement(&stmt)?;
-        }
+/// A `توقف`/`break` or `استمر`/`continue` that has run but not yet reached
+/// its loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LoopExit {
+    Break,
+    Continue,
+}
 
-        Ok(last_value)
+impl LoopExit {
+    /// The error for `توقف` or `استمر` used outside any loop.
+    pub(crate) fn outside_loop_error(self, line: Option<usize>, column: Option<usize>) -> anyhow::Error {
+        let (arabic, english) = match self {
+            LoopExit::Break => ("توقف", "break"),
+            LoopExit::Continue => ("استمر", "continue"),
+        };
+        anyhow!(IqraError {
+            kind: "خارج حلقة".to_string(),
+            message_ar: format!("لا يمكن استخدام {} خارج حلقة", arabic),
+            message_en: format!("{} can only be used inside a loop", english),
+            suggestion: Some(tr!("suggest.inside_loop", keyword = Word(arabic, english))),
+            line,
+            column,
+            source_name: None,
+        })
     }
+}
 
-    fn execute_statement(&mut self, stmt: &Stmt) -> Result<Value> {
-            match stmt {
-                Stmt::Expression(expr) => self.evaluate_expression(expr),
-                Stmt::Assignment { name, value } => {
-                    let val = self.evaluate_expression(value)?;
-                    self.variable_stack.last_mut().unwrap().insert(name.clone(), val.clone());
-                    Ok(val)
-                }
-            Stmt::If { condition, then_branch, else_branch } => {
-                let condition_value = self.evaluate_expression(condition)?;
-                if condition_value.is_truthy() {
-                    self.execute_block(then_branch)
-                } else if let Some(else_branch) = else_branch {
-                    self.execute_block(else_branch)
-                } else {
-                    Ok(Value::Nil)
-                }
-            }
-            Stmt::While { condition, body } => {
-                let mut last_value = Value::Nil;
-                while self.evaluate_expression(condition)?.is_truthy() {
-                    last_value = self.execute_block(body)?;
-                }
-                Ok(last_value)
-            }
-            Stmt::Block(statements) => self.execute_block(statements),
-            Stmt::FunctionDef { name, params, body } => {
-                self.functions.insert(name.clone(), (params.clone(), body.clone()));
-                Ok(Value::Nil)
-            }
-            Stmt::Return(expr) => {
-                // Special handling: propagate return value up
-                let val = self.evaluate_expression(expr)?;
-                Err(anyhow!("__RETURN__:{:?}", val))
-            }
-            Stmt::TryCatch { try_block, catch_block, error_var } => {
-                // Execute try block
-                match self.execute_block(try_block) {
-                    Ok(val) => Ok(val),
-                    Err(e) => {
-                        // Optionally bind error to variable
-                        if let Some(var) = error_var {
-                            self.variable_stack.last_mut().unwrap().insert(var.clone(), Value::String(format!("{}", e)));
-                        }
-                        // Execute catch block
-                        self.execute_block(catch_block)
-                    }
-                }
-            },
-        }
+/// Applies an arithmetic operator with checked `i64` math when both operands
+/// are `Int`, and `f64` math once either is a `Number`. Returns `None` when an
+/// operand is not numeric.
+fn numeric_op(
+    left: &Value,
+    right: &Value,
+    int_op: fn(i64, i64) -> Option<i64>,
+    float_op: fn(f64, f64) -> f64,
+) -> Option<Result<Value>> {
+    match (left, right) {
+        (Value::Int(a), Value::Int(b)) => Some(int_op(*a, *b).map(Value::Int).ok_or_else(overflow_error)),
+        _ => Some(Ok(Value::Number(float_op(left.as_number()?, right.as_number()?)))),
     }
+}
 
-    fn execute_block(&mut self, statements: &[Stmt]) -> Result<Value> {
-        // Lazy evaluation: only evaluate statements as needed (e.g., for early return)
-        let mut last_value = Value::Nil;
-        for stmt in statements {
-            match self.execute_statement(stmt) {
-                Ok(v) => last_value = v,
-                Err(e) => {
-                    let msg = format!("{}", e);
-                    if msg.starts_with("__RETURN__:") {
-                        last_value = Value::Nil; // Value will be handled by caller
-                        break;
-                    } else {
-                        return Err(anyhow!(msg));
-                    }
-                }
-            }
-        }
-        Ok(last_value)
+fn overflow_error() -> anyhow::Error {
+    anyhow!(IqraError {
+        kind: "تجاوز سعة العدد الصحيح | Integer Overflow".to_string(),
+        message_ar: "نتيجة العملية أكبر من مدى العدد الصحيح".to_string(),
+        message_en: "The result is outside the integer range".to_string(),
+        suggestion: Some(tr!("suggest.use_decimal")),
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+/// The suggestion of an error about the undefined `name`: up to three
+/// `candidates` within two edits of it, or `fallback` when none is.
+fn name_suggestion<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>, fallback: String) -> String {
+    let close = analyzer::closest_names(name, candidates, 3);
+    if close.is_empty() {
+        return fallback;
     }
+    tr!("suggest.did_you_mean", names = List(&close))
+}
 
-    fn evaluate_expression(&mut self, expr: &Expr) -> Result<Value> {
-        match expr {
-            Expr::Literal(value) => Ok(value.clone()),
-            Expr::Identifier(name) => self
-                .variable_stack.last().unwrap()
-                .get(name)
-                .cloned()
-                .ok_or_else(|| anyhow!(IqraError {
-                    kind: "متغير غير معرف".to_string(),
-                    message_ar: format!("المتغير غير معرف: {}", name),
-                    message_en: format!("Undefined variable: {}", name),
-                    suggestion: Some("تأكد من تعريف المتغير قبل استخدامه".to_string()),
-                    line: None,
-                })),
-            Expr::Binary { left, operator, right } => {
-                let left_val = self.evaluate_expression(left)?;
-                let right_val = self.evaluate_expression(right)?;
-                self.evaluate_binary_op(&left_val, operator, &right_val)
-            }
-            Expr::Unary { operator, operand } => {
-                let operand_val = self.evaluate_expression(operand)?;
-                self.evaluate_unary_op(operator, &operand_val)
-            }
-            Expr::Call { name, args } => {
-                let arg_values: Result<Vec<Value>> =
-                    args.iter().map(|arg| self.evaluate_expression(arg)).collect();
-                let arg_values = arg_values?;
-                if self.functions.contains_key(name) {
-                    return self.call_user_function(name, &arg_values);
-                } else {
-                    return self.call_builtin(name, &arg_values);
-                }
-            }
-            Expr::List(elements) => {
-                let values: Result<Vec<Value>> =
-                    elements.iter().map(|elem| self.evaluate_expression(elem)).collect();
-                Ok(Value::List(values?))
+/// `س == ٥` on its own line with `س` undefined was almost certainly meant as
+/// an assignment, so the undefined-variable error suggests `=` instead.
+fn comparison_statement_hint(expr: &Expr, mut err: anyhow::Error) -> anyhow::Error {
+    if let ExprKind::Binary { left, operator: BinaryOp::Equal, .. } = &expr.kind
+        && let ExprKind::Identifier(name) = &left.kind
+        && let Some(iqra_err) = err.downcast_mut::<IqraError>()
+        && iqra_err.message_en == format!("Undefined variable: {}", name)
+    {
+        iqra_err.suggestion = Some(tr!("suggest.single_equals"));
+    }
+    err
+}
+
+/// `١ < س < ١٠` parses as `(١ < س) < ١٠`, so the second comparison gets a
+/// boolean. Any ordering of a boolean against a number is taken for such a
+/// chain, including one whose first comparison the optimizer has already
+/// folded, as in `١ < ٢ < ٣`. The error then says so and suggests two
+/// comparisons joined with `و`, or `بين؟` when both ends are inclusive.
+fn chained_comparison_hint(expr: &Expr, left: &Value, right: &Value, mut err: anyhow::Error) -> anyhow::Error {
+    let is_bool = |value: &Value| matches!(value, Value::Bool(_));
+    if let ExprKind::Binary { left: first, operator: outer, right: high } = &expr.kind
+        && let Some(outer_symbol) = ordering_symbol(outer)
+        && (is_bool(left) && right.as_number().is_some() || left.as_number().is_some() && is_bool(right))
+        && let Some(iqra_err) = err.downcast_mut::<IqraError>()
+    {
+        let chain = match &first.kind {
+            ExprKind::Binary { left: low, operator: inner, right: middle } if is_bool(left) => {
+                ordering_symbol(inner).map(|inner_symbol| (low, inner, inner_symbol, middle))
             }
-            Expr::Index { object, index } => {
-                let obj_val = self.evaluate_expression(object)?;
-                let idx_val = self.evaluate_expression(index)?;
-                self.evaluate_index(&obj_val, &idx_val)
+            _ => None,
+        };
+        let Some((low, inner, inner_symbol, middle)) = chain else {
+            iqra_err.message_ar = "لا تُسلسل المقارنات: لا تُقارن قيمة منطقية برقم".to_string();
+            iqra_err.message_en = "Comparisons do not chain: a boolean can't be compared with a number".to_string();
+            iqra_err.suggestion = Some(tr!("suggest.compare_pairs"));
+            return err;
+        };
+        iqra_err.message_ar = "لا تُسلسل المقارنات: نتيجة المقارنة الأولى قيمة منطقية وليست رقماً".to_string();
+        iqra_err.message_en = "Comparisons do not chain: the first comparison gives a boolean, not a number".to_string();
+        let simple = [low, middle, high].iter().all(|side| operand_text(side, ErrorLanguage::English).is_some());
+        let (low, middle, high) = (OperandText(low), OperandText(middle), OperandText(high));
+        iqra_err.suggestion = Some(match (simple, inner, outer) {
+            (false, _, _) => tr!("suggest.compare_pairs"),
+            (true, BinaryOp::LessEqual, BinaryOp::LessEqual) | (true, BinaryOp::GreaterEqual, BinaryOp::GreaterEqual) => {
+                let (from, to) = if matches!(inner, BinaryOp::LessEqual) { (low, high) } else { (high, low) };
+                tr!(
+                    "suggest.chained_comparison_between",
+                    a = low, first = inner_symbol, b = middle, second = outer_symbol, c = high, from = from, to = to
+                )
             }
-        }
+            (true, _, _) => tr!("suggest.chained_comparison", a = low, first = inner_symbol, b = middle, second = outer_symbol, c = high),
+        });
     }
+    err
+}
 
-    fn evaluate_binary_op(&self, left: &Value, op: &BinaryOp, right: &Value) -> Result<Value> {
-        match op {
-            BinaryOp::Add => match (left, right) {
-                (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + b)),
-                (Value::String(a), Value::String(b)) => Ok(Value::String(format!("{}{}", a, b))),
-                _ => Err(anyhow!(IqraError {
+fn ordering_symbol(op: &BinaryOp) -> Option<&'static str> {
+    match op {
+        BinaryOp::Less => Some("<"),
+        BinaryOp::LessEqual => Some("<="),
+        BinaryOp::Greater => Some(">"),
+        BinaryOp::GreaterEqual => Some(">="),
+        _ => None,
+    }
+}
+
+/// A name or a number literal as it would be typed, with the number in the
+/// digits of the language it is shown in; `None` for anything else.
+fn operand_text(expr: &Expr, language: ErrorLanguage) -> Option<String> {
+    match &expr.kind {
+        ExprKind::Identifier(name) => Some(name.as_str().to_string()),
+        ExprKind::Literal(value @ (Value::Int(_) | Value::Number(_))) => {
+            let text = format!("{}", value);
+            Some(match language {
+                ErrorLanguage::Arabic => arabic::normalize_digits(&text, DigitScript::Arabic).replace('.', "٫"),
+                _ => text,
+            })
+        }
+        ExprKind::Unary { operator: UnaryOp::Minus, operand } => {
+            operand_text(operand, language).map(|text| format!("-{}", text))
+        }
+        _ => None,
+    }
+}
+
+/// [`operand_text`] as a message argument.
+#[derive(Clone, Copy)]
+struct OperandText<'a>(&'a Expr);
+
+impl Arg for OperandText<'_> {
+    fn render(&self, language: ErrorLanguage) -> String {
+        operand_text(self.0, language).unwrap_or_default()
+    }
+}
+
+/// Colors accepted by `print_color`, by Arabic and English name.
+const COLORS: &[(&str, &str)] = &[
+    ("أحمر", "red"),
+    ("أخضر", "green"),
+    ("أصفر", "yellow"),
+    ("أزرق", "blue"),
+    ("بنفسجي", "magenta"),
+    ("سماوي", "cyan"),
+    ("أبيض", "white"),
+    ("أسود", "black"),
+];
+
+const STYLES: &[(&str, &str)] = &[("عريض", "bold"), ("مائل", "italic"), ("تحته_خط", "underline")];
+
+fn color_style(name: &str) -> Option<Style> {
+    let (_, english) = COLORS.iter().find(|(ar, en)| *ar == name || *en == name)?;
+    let style = Style::new();
+    Some(match *english {
+        "red" => style.red(),
+        "green" => style.green(),
+        "yellow" => style.yellow(),
+        "blue" => style.blue(),
+        "magenta" => style.magenta(),
+        "cyan" => style.cyan(),
+        "white" => style.white(),
+        _ => style.black(),
+    })
+}
+
+/// Reports an unknown color or style, listing the accepted names.
+fn color_error(what: &str, got: &Value) -> anyhow::Error {
+    let (kind_ar, options) = if what == "color" { ("لون", COLORS) } else { ("نمط", STYLES) };
+    let choices: Vec<String> = options.iter().map(|(ar, en)| format!("{}/{}", ar, en)).collect();
+    let names = choices.join("، ");
+    anyhow!(IqraError {
+        kind: format!("{} غير معروف | Unknown {}", kind_ar, what),
+        message_ar: format!("{} غير معروف: {}. الخيارات: {}", kind_ar, got, names),
+        message_en: format!("Unknown {} '{}'. Valid options: {}", what, got, names),
+        suggestion: Some(tr!("suggest.one_of", names = List(&choices))),
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+/// How many times `confirm` and `choose` ask before giving up with nil.
+const PROMPT_ATTEMPTS: usize = 3;
+
+fn input_error(e: std::io::Error) -> anyhow::Error {
+    anyhow!(IqraError {
+        kind: "فشل قراءة المدخلات".to_string(),
+        message_ar: format!("فشل قراءة المدخلات: {}", e),
+        message_en: format!("Failed to read input: {}", e),
+        suggestion: None,
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+/// Parses the text given to `to_number`. Strict parsing accepts only digits
+/// (Arabic or ASCII), `٫` as the decimal point and a leading `+` or `-`.
+/// Otherwise the text may also have surrounding whitespace and direction
+/// marks, a `−` sign, `,`/`٬` thousands separators and a `%`/`٪` sign at
+/// either end, which divides by 100. On failure returns the first character
+/// that can't be part of a number, if there is one.
+fn parse_number_text(text: &str, strict: bool) -> std::result::Result<Value, Option<char>> {
+    let parse = |digits: &str| match digits.parse::<i64>() {
+        Ok(n) => Some(Value::Int(n)),
+        Err(_) => digits.parse::<f64>().ok().map(Value::Number),
+    };
+    let unexpected = |text: &str| {
+        text.chars().find(|ch| !(ch.is_ascii_digit() || ('٠'..='٩').contains(ch) || "+-−.٫,٬%٪eE".contains(*ch)))
+    };
+    if strict {
+        return parse(&to_ascii_digits(text).replace('٫', ".")).ok_or_else(|| unexpected(text));
+    }
+    let trimmed = text.trim_matches(|ch: char| ch.is_whitespace() || matches!(ch, '\u{200E}' | '\u{200F}' | '\u{061C}'));
+    let (body, percent) = match trimmed.strip_suffix(['%', '٪']).or_else(|| trimmed.strip_prefix(['%', '٪'])) {
+        Some(body) => (body.trim(), true),
+        None => (trimmed, false),
+    };
+    let (negative, body) = match body.strip_prefix(['-', '−']) {
+        Some(rest) => (true, rest),
+        None => (false, body.strip_prefix('+').unwrap_or(body)),
+    };
+    let digits: String = to_ascii_digits(body).chars().filter(|ch| !matches!(ch, ',' | '٬')).collect();
+    let digits = digits.replace('٫', ".");
+    if digits.starts_with(['+', '-']) {
+        return Err(None);
+    }
+    let value = parse(&if negative { format!("-{}", digits) } else { digits }).ok_or_else(|| unexpected(trimmed))?;
+    Ok(match value {
+        _ if !percent => value,
+        Value::Int(n) if n % 100 == 0 => Value::Int(n / 100),
+        other => Value::Number(other.as_number().unwrap_or(f64::NAN) / 100.0),
+    })
+}
+
+/// Reads the optional options map of `normalize_arabic`: option names in
+/// either language, each set to true or false.
+fn normalize_options(name: &str, value: Option<&Value>) -> Result<NormalizeOptions> {
+    let mut options = NormalizeOptions::default();
+    let Some(value) = value else { return Ok(options) };
+    let names: Vec<String> = NormalizeOptions::KEYS.iter().map(|(ar, en)| format!("{}/{}", ar, en)).collect();
+    let Value::Map(map) = value else {
+        return Err(anyhow!(IqraError {
+            kind: "نوع وسيط غير صحيح".to_string(),
+            message_ar: format!("الوسيط الثاني لـ {} يجب أن يكون قاموس خيارات، لكن وصلها {}", name, value.type_name_ar()),
+            message_en: format!("The second argument of {} must be an options map, got {}", name, value.type_name()),
+            suggestion: Some(tr!("suggest.example_normalize")),
+            line: None,
+            column: None,
+            source_name: None,
+        }));
+    };
+    for (key, on) in map {
+        let known = match on {
+            Value::Bool(on) => options.set(key, *on),
+            _ => false,
+        };
+        if !known {
+            return Err(anyhow!(IqraError {
+                kind: "خيار غير صالح | Invalid Option".to_string(),
+                message_ar: format!("خيار غير صالح لـ {}: {} = {}", name, key, on),
+                message_en: format!("Invalid option for {}: {} = {}", name, key, on),
+                suggestion: Some(tr!("suggest.options", names = List(&names))),
+                line: None,
+                column: None,
+                source_name: None,
+            }));
+        }
+    }
+    Ok(options)
+}
+
+fn date_error(name: &str, date: &str) -> anyhow::Error {
+    anyhow!(IqraError {
+        kind: "تاريخ غير صالح | Invalid Date".to_string(),
+        message_ar: format!("تاريخ غير صالح لـ {}: {}", name, date),
+        message_en: format!("Invalid date for {}: {}", name, date),
+        suggestion: Some(tr!("suggest.hijri_date")),
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Checks that a builtin received exactly `N` string arguments.
+fn string_args<'a, const N: usize>(name: &str, args: &'a [Value]) -> Result<[&'a str; N]> {
+    if args.len() != N {
+        return Err(arity_error(name, N, Some(N), args.len()));
+    }
+    let mut strings = [""; N];
+    for (slot, arg) in strings.iter_mut().zip(args) {
+        *slot = arg.as_string().ok_or_else(|| anyhow!(IqraError {
+            kind: "نوع وسيط غير صحيح".to_string(),
+            message_ar: format!("{} تتوقع نصوصاً فقط", name),
+            message_en: format!("{} expects string arguments", name),
+            suggestion: Some(tr!("suggest.texts_only")),
+            line: None,
+            column: None,
+            source_name: None,
+        }))?;
+    }
+    Ok(strings)
+}
+
+/// The optional encoding argument of `نص_إلى_بايتات` and `بايتات_إلى_نص`,
+/// utf-8 when it is missing.
+fn encoding_arg(name: &str, arg: Option<&Value>) -> Result<&'static encoding_rs::Encoding> {
+    let Some(arg) = arg else {
+        return Ok(encoding_rs::UTF_8);
+    };
+    let label = string_args::<1>(name, std::slice::from_ref(arg))?[0];
+    bytes::encoding_for(label).ok_or_else(|| anyhow!(IqraError {
+        kind: "ترميز غير معروف".to_string(),
+        message_ar: format!("الترميز '{}' غير مدعوم", label),
+        message_en: format!("The encoding '{}' is not supported", label),
+        suggestion: Some(tr!("suggest.encodings", names = List(bytes::ENCODINGS))),
+        line: None,
+        column: None,
+        source_name: None,
+    }))
+}
+
+/// `text` in `encoding`, failing on the first character it can't hold.
+fn encode_text(text: &str, encoding: &'static encoding_rs::Encoding) -> Result<Vec<u8>> {
+    bytes::encode(text, encoding).map_err(|ch| anyhow!(IqraError {
+        kind: "ترميز غير صالح".to_string(),
+        message_ar: format!("لا يمكن تمثيل الحرف '{}' بترميز {}", ch, encoding.name()),
+        message_en: format!("'{}' cannot be represented in {}", ch, encoding.name()),
+        suggestion: Some(tr!("suggest.use_utf8")),
+        line: None,
+        column: None,
+        source_name: None,
+    }))
+}
+
+/// The text `data` holds in `encoding`.
+fn decode_text(data: &[u8], encoding: &'static encoding_rs::Encoding) -> Result<String> {
+    bytes::decode(data, encoding).ok_or_else(|| anyhow!(IqraError {
+        kind: "ترميز غير صالح".to_string(),
+        message_ar: format!("البايتات ليست نصاً صالحاً بترميز {}", encoding.name()),
+        message_en: format!("The bytes are not valid {} text", encoding.name()),
+        suggestion: Some(tr!("suggest.guess_encoding")),
+        line: None,
+        column: None,
+        source_name: None,
+    }))
+}
+
+/// The map argument of a builtin that takes a map.
+fn map_arg<'a>(name: &str, arg: &'a Value) -> Result<&'a HashMap<String, Value>> {
+    arg.as_map().ok_or_else(|| anyhow!(IqraError {
+        kind: "نوع وسيط غير صحيح".to_string(),
+        message_ar: format!("{} تتوقع قاموساً، لكن وصلها {}", name, arg.type_name_ar()),
+        message_en: format!("{} expects a map, got {}", name, arg.type_name()),
+        suggestion: None,
+        line: None,
+        column: None,
+        source_name: None,
+    }))
+}
+
+/// The list argument of a builtin that takes a list.
+fn list_arg<'a>(name: &str, arg: &'a Value) -> Result<&'a [Value]> {
+    arg.as_list().map(Vec::as_slice).ok_or_else(|| anyhow!(IqraError {
+        kind: "نوع وسيط غير صحيح".to_string(),
+        message_ar: format!("{} تتوقع قائمة، لكن وصلها {}", name, arg.type_name_ar()),
+        message_en: format!("{} expects a list, got {}", name, arg.type_name()),
+        suggestion: None,
+        line: None,
+        column: None,
+        source_name: None,
+    }))
+}
+
+/// The elements of a statistics builtin's list argument, which must all be
+/// numbers.
+fn numbers_arg(name: &str, arg: &Value) -> Result<Vec<f64>> {
+    let Value::List(list) = arg else {
+        return Err(anyhow!(IqraError {
+            kind: "نوع وسيط غير صحيح".to_string(),
+            message_ar: format!("{} يتوقع قائمة", name),
+            message_en: format!("{} expects a list", name),
+            suggestion: Some(tr!("suggest.list_only")),
+            line: None,
+            column: None,
+            source_name: None,
+        }));
+    };
+    list.iter()
+        .map(|item| item.as_number().ok_or_else(|| anyhow!(IqraError {
+            kind: "نوع عنصر غير صحيح".to_string(),
+            message_ar: format!("{} يتوقع قائمة أرقام فقط", name),
+            message_en: format!("{} expects a list of numbers", name),
+            suggestion: Some(tr!("suggest.all_numbers")),
+            line: None,
+            column: None,
+            source_name: None,
+        })))
+        .collect()
+}
+
+fn too_few_values_error(name: &str, min: usize) -> anyhow::Error {
+    if min == 1 {
+        return anyhow!(IqraError {
+            kind: "قائمة فارغة".to_string(),
+            message_ar: format!("{} تتوقع قائمة غير فارغة", name),
+            message_en: format!("{} expects a non-empty list", name),
+            suggestion: Some(tr!("suggest.non_empty_list")),
+            line: None,
+            column: None,
+            source_name: None,
+        });
+    }
+    anyhow!(IqraError {
+        kind: "قائمة قصيرة".to_string(),
+        message_ar: format!("{} تحتاج {} قيم على الأقل", name, min),
+        message_en: format!("{} needs at least {} values", name, min),
+        suggestion: Some(tr!("suggest.sample_variance")),
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+fn format_error(name: &str, err: FormatError) -> anyhow::Error {
+    let (message_ar, message_en) = match err {
+        FormatError::MissingArgument { position, given } => (
+            format!("{}: لا توجد قيمة للموضع {{{}}}؛ أُعطيت {} فقط", name, position, given),
+            format!("{}: placeholder {{{}}} has no argument; only {} given", name, position, given),
+        ),
+        FormatError::Unclosed { offset } => (
+            format!("{}: القوس '{{' عند الموضع {} غير مغلق", name, offset),
+            format!("{}: the '{{' at offset {} is never closed", name, offset),
+        ),
+        FormatError::UnmatchedClose { offset } => (
+            format!("{}: القوس '}}' عند الموضع {} لا يغلق شيئاً", name, offset),
+            format!("{}: the '}}' at offset {} closes nothing", name, offset),
+        ),
+        FormatError::BadPlaceholder { placeholder } => (
+            format!("{}: خانة غير مفهومة: {{{}}}", name, placeholder),
+            format!("{}: cannot read placeholder {{{}}}", name, placeholder),
+        ),
+        FormatError::NotANumber { position } => (
+            format!("{}: الخانات العشرية تحتاج رقماً في الموضع {{{}}}", name, position),
+            format!("{}: decimals need a number at placeholder {{{}}}", name, position),
+        ),
+    };
+    anyhow!(IqraError {
+        kind: "تنسيق غير صالح | Invalid Format".to_string(),
+        message_ar,
+        message_en,
+        suggestion: Some(tr!("suggest.format_template")),
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+fn matrix_error(name: &str, err: MatrixError) -> anyhow::Error {
+    let shape = |(rows, cols): (usize, usize)| format!("{}x{}", rows, cols);
+    let (message_ar, message_en) = match err {
+        MatrixError::NotAList => {
+            (format!("{} تتوقع قائمة قوائم", name), format!("{} expects a list of lists", name))
+        }
+        MatrixError::RowNotAList { row } => (
+            format!("{}: الصف {} ليس قائمة", name, row),
+            format!("{}: row {} is not a list", name, row),
+        ),
+        MatrixError::Ragged { row, expected, found } => (
+            format!("{}: الصف {} فيه {} عناصر والصف الأول فيه {}", name, row, found, expected),
+            format!("{}: row {} has {} items but the first row has {}", name, row, found, expected),
+        ),
+        MatrixError::NotANumber { row, col } => (
+            format!("{}: العنصر في الصف {} والعمود {} ليس رقماً", name, row, col),
+            format!("{}: the item at row {}, column {} is not a number", name, row, col),
+        ),
+        MatrixError::Reshape { len, rows, cols } => (
+            format!("{}: لا يمكن ترتيب {} عناصر في {} صفوف و{} أعمدة", name, len, rows, cols),
+            format!("{}: cannot arrange {} items into {} rows of {}", name, len, rows, cols),
+        ),
+        MatrixError::ShapeMismatch { left, right } => (
+            format!("{}: أبعاد المصفوفتين مختلفة، {} و{}", name, shape(left), shape(right)),
+            format!("{}: the matrices are {} and {}; their dimensions must match", name, shape(left), shape(right)),
+        ),
+        MatrixError::InnerMismatch { left, right } => (
+            format!(
+                "{}: لا يمكن ضرب {} في {}؛ أعمدة الأولى ({}) لا تساوي صفوف الثانية ({})",
+                name, shape(left), shape(right), left.1, right.0
+            ),
+            format!(
+                "{}: cannot multiply {} by {}; the first has {} columns but the second has {} rows",
+                name, shape(left), shape(right), left.1, right.0
+            ),
+        ),
+    };
+    anyhow!(IqraError {
+        kind: "مصفوفة غير صالحة | Invalid Matrix".to_string(),
+        message_ar,
+        message_en,
+        suggestion: None,
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+/// A matrix argument whose cells must all be numbers.
+fn numeric_matrix<'a>(name: &str, arg: &'a Value) -> Result<Matrix<'a>> {
+    Matrix::parse(arg).and_then(Matrix::numeric).map_err(|err| matrix_error(name, err))
+}
+
+/// The values as `اطبع` shows them, separated by `separator`.
+fn joined(values: &[Value], separator: &str) -> String {
+    values.iter().map(|value| format!("{}", value)).collect::<Vec<_>>().join(separator)
+}
+
+/// Error for a call whose argument count is outside `min..=max` (`None` means no maximum).
+fn arity_error(name: &str, min: usize, max: Option<usize>, received: usize) -> anyhow::Error {
+    let (expected_ar, expected_en) = match max {
+        Some(max) if max == min => (min.to_string(), min.to_string()),
+        Some(max) => (format!("من {} إلى {}", min, max), format!("{} to {}", min, max)),
+        None => (format!("{} على الأقل", min), format!("at least {}", min)),
+    };
+    anyhow!(IqraError {
+        kind: "عدد وسائط غير صحيح".to_string(),
+        message_ar: format!("الدالة '{}' تتوقع {} من الوسائط لكنها استلمت {}", name, expected_ar, received),
+        message_en: format!("Function '{}' expects {} argument(s) but received {}", name, expected_en, received),
+        suggestion: Some(tr!("suggest.argument_count")),
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+/// ما يفعله المنفذ بعد استدعاء خطاف الخطوة | What the runtime does after a step hook returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugAction {
+    /// Execute the statement and carry on.
+    Continue,
+    /// Stop the whole execution with an error.
+    Abort,
+}
+
+/// Called before every statement is executed, including statements inside function bodies.
+pub type StepHook = Box<dyn FnMut(&Stmt, &Runtime) -> DebugAction + Send>;
+
+/// Called with a variable's name and new value whenever the program assigns it.
+pub type AssignHook = Box<dyn FnMut(&str, &Value) + Send>;
+
+/// Called with the name and arguments of every user function and builtin call.
+pub type CallHook = Box<dyn FnMut(&str, &[Value]) + Send>;
+
+/// Called once for each error a statement raises, where it is raised, even if
+/// `جرب` later catches it.
+pub type ErrorHook = Box<dyn FnMut(&IqraError) + Send>;
+
+/// خطافات التنفيذ | Callbacks that let tools watch a program run without
+/// changing the interpreter. The debugger and `--trace` are built on
+/// `on_statement`. A hook that is `None` costs nothing.
+#[derive(Default)]
+pub struct RuntimeHooks {
+    pub on_statement: Option<StepHook>,
+    pub on_assign: Option<AssignHook>,
+    pub on_call: Option<CallHook>,
+    pub on_error: Option<ErrorHook>,
+}
+
+pub struct Runtime {
+    /// Variables bound outside any function call.
+    globals: SymbolMap<Value>,
+    /// Names in `globals` declared with `ثابت`.
+    constants: SymbolSet,
+    functions: SymbolMap<Arc<UserFunction>>,
+    /// The value of an `ارجع` that has run but not yet reached its function
+    /// call. While it is set, blocks and loops stop executing.
+    returning: Option<Value>,
+    /// A `توقف`/`استمر` that has run but not yet reached its loop. While it
+    /// is set, blocks stop executing.
+    loop_exit: Option<LoopExit>,
+    /// One frame per executing user function call.
+    call_frames: Vec<CallFrame>,
+    /// The locals of the calls in `call_frames`, oldest first. A call sees
+    /// its callers' locals too, so a name is looked up from the end.
+    locals: Vec<Local>,
+    /// The `local_bit` of every name in `locals`, so a name no call has bound
+    /// goes straight to `globals`.
+    locals_mask: u64,
+    /// Arguments evaluated for user function calls not yet made. Nested
+    /// calls push theirs above and take them off again.
+    arguments: Vec<Value>,
+    system_executor: Box<dyn SystemExecutor>,
+    output: Box<dyn OutputSink>,
+    /// Today's date for `تاريخ_اليوم` and `تاريخ_هجري`.
+    clock: Arc<dyn Clock>,
+    /// The answer of the first `معلومات_النظام`, until `تحديث` or `clear_caches`.
+    system_info_cache: Option<HashMap<String, String>>,
+    /// Today's Hijri date, keyed by the day the clock gave, so it is worked
+    /// out again once the clock passes midnight.
+    today_cache: Option<(NaiveDate, HijriDate)>,
+    /// Patterns compiled by the regex builtins, keyed by pattern string.
+    regex_cache: HashMap<String, Regex>,
+    /// Random source used when a seed is set, so scripts can be replayed.
+    rng: Option<StdRng>,
+    hooks: RuntimeHooks,
+    /// Whether `on_error` has seen the error currently leaving statements.
+    /// Cleared when the next statement starts.
+    error_reported: bool,
+    call_depth: usize,
+    /// When set, builtins that change the host (commands, file writes, the
+    /// environment, the working directory) are refused.
+    sandboxed: bool,
+    limits: Limits,
+    /// Statements executed since the current run started.
+    statements_executed: u64,
+    /// How often each statement ran this run, keyed by where it starts.
+    /// Only kept while a statement or time limit is set.
+    hotspots: HashMap<(usize, usize), u64>,
+    /// When the current run must stop, if `limits.timeout` is set.
+    deadline: Option<Instant>,
+    /// Stops the run when cancelled from another thread.
+    cancellation: CancellationToken,
+    /// Whether the current run has seen `cancellation` set.
+    cancelled: bool,
+    /// Deprecated builtin names already warned about.
+    warned_aliases: HashSet<&'static str>,
+    /// When set, every function call is counted and timed in `profile`.
+    profiling: bool,
+    profile: HashMap<String, ProfileEntry>,
+    /// Named in the errors of statements run from here on.
+    source_name: Option<String>,
+    /// Expressions being evaluated inside each other in the current call.
+    expression_depth: usize,
+    /// Arguments passed to the script, returned by `وسائط()`.
+    script_args: Vec<String>,
+    /// The file behind `خزّن` and `تذكر`; see `set_cache_file`.
+    cache_file: Option<PathBuf>,
+    /// Whether `compile` runs the optimizer; see `set_optimize`.
+    optimize: bool,
+    /// Whether file builtins expand `~` and `$VAR`; see `set_expand_paths`.
+    expand_paths: bool,
+}
+
+/// حالة الذاكرة المؤقتة | What the runtime's in-memory caches hold, as
+/// `--emit-state` reports it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStatus {
+    /// Whether `معلومات_النظام` has an answer saved.
+    pub system_info: bool,
+    /// The day today's Hijri date was worked out for, if it has been.
+    pub today: Option<NaiveDate>,
+    /// How many patterns the regex builtins have compiled.
+    pub regex_patterns: usize,
+}
+
+/// إحصاءات دالة | Calls to one function while profiling. `total` includes the
+/// time spent in functions it called.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProfileEntry {
+    pub calls: u64,
+    pub total: Duration,
+}
+
+/// حدود التنفيذ | Resource limits for running untrusted scripts. `None` means
+/// unlimited, which is the default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum number of statements (and loop iterations) per run.
+    pub max_statements: Option<u64>,
+    /// Maximum wall-clock time per run.
+    pub timeout: Option<Duration>,
+    /// Maximum number of items in a list or map a builtin or list literal
+    /// builds.
+    pub max_collection_len: Option<usize>,
+    /// Maximum size in bytes of a string a builtin or `+` builds.
+    pub max_collection_bytes: Option<usize>,
+}
+
+impl Limits {
+    /// Keeps any collection caps already set and fills in the others with
+    /// ones that stop a script from exhausting the host's memory, for
+    /// sandboxed runs and `iqra serve`.
+    pub fn with_sandbox_caps(self) -> Limits {
+        Limits {
+            max_collection_len: self.max_collection_len.or(Some(1_000_000)),
+            max_collection_bytes: self.max_collection_bytes.or(Some(16 * 1024 * 1024)),
+            ..self
+        }
+    }
+}
+
+/// A statement that ran often, from `Runtime::hotspots`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hotspot {
+    pub line: usize,
+    pub column: usize,
+    pub count: u64,
+}
+
+/// How many hotspots a limit error and the state report name.
+pub const REPORTED_HOTSPOTS: usize = 3;
+
+/// إلغاء التنفيذ | Stops a running script from another thread. Clones share
+/// one flag; the runtime checks it before each statement and loop iteration,
+/// and the run fails with an error `جرب` can't catch. A cancellation that
+/// arrives between runs stops the next one.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Withdraws a cancellation no run has seen yet.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Runtime {
+    /// Returns a copy of the current variables (for REPL step mode)
+    pub fn get_variables(&self) -> HashMap<String, Value> {
+        self.visible_variables().into_iter().map(|(name, value)| (name.to_string(), value)).collect()
+    }
+
+    /// The globals overlaid with the locals of every active call, each
+    /// binding the innermost one.
+    fn visible_variables(&self) -> SymbolMap<Value> {
+        let mut variables = self.globals.clone();
+        variables.extend(self.locals.iter().map(|local| (local.name.clone(), local.value.clone())));
+        variables
+    }
+
+    /// The names whose innermost binding is a constant.
+    fn visible_constants(&self) -> SymbolSet {
+        let mut constants = self.constants.clone();
+        for local in &self.locals {
+            if local.constant {
+                constants.insert(local.name.clone());
+            } else {
+                constants.remove(&local.name);
+            }
+        }
+        constants
+    }
+
+    /// Returns a copy of the global variables, whatever scope is current.
+    pub fn global_variables(&self) -> BTreeMap<String, Value> {
+        self.scope(0).into_iter().collect()
+    }
+
+    /// Copies of every scope, the globals first and the innermost function
+    /// call last. A call's scope holds its parameters and the variables it
+    /// has assigned; outside any call there is only the global scope.
+    pub fn all_scopes(&self) -> Vec<HashMap<String, Value>> {
+        (0..=self.call_frames.len()).map(|level| self.scope(level)).collect()
+    }
+
+    /// The scope `level` calls deep, 0 being the globals.
+    fn scope(&self, level: usize) -> HashMap<String, Value> {
+        let locals = match level {
+            0 => return self.globals.iter().map(|(name, value)| (name.to_string(), value.clone())).collect(),
+            _ => {
+                let end = self.call_frames.get(level).map_or(self.locals.len(), |frame| frame.first_local);
+                &self.locals[self.call_frames[level - 1].first_local..end]
+            }
+        };
+        locals.iter().map(|local| (local.name.to_string(), local.value.clone())).collect()
+    }
+
+    /// The names of the user functions defined so far, sorted.
+    pub fn function_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.functions.keys().map(|name| name.to_string()).collect();
+        names.sort();
+        names
+    }
+
+    /// Sets the variable `name` in the current scope, as `name = value` in
+    /// a script would. Fails if `name` is a constant.
+    pub fn set_variable(&mut self, name: &str, value: Value) -> Result<()> {
+        self.assign_variable(Symbol::intern(name), value)
+    }
+
+    /// Removes the variable `name` from the current scope, returning its
+    /// value. Constants are left alone.
+    pub fn remove_variable(&mut self, name: &str) -> Option<Value> {
+        let name = Symbol::lookup(name)?;
+        if self.is_constant(&name) {
+            return None;
+        }
+        match self.local_index(&name) {
+            // Only the innermost call's locals can go without moving another
+            // call's.
+            Some(index) if self.call_frames.last().is_some_and(|frame| index >= frame.first_local) => {
+                Some(self.locals.remove(index).value)
+            }
+            Some(_) => None,
+            None => self.globals.remove(&name),
+        }
+    }
+
+    /// Sets the language of error messages. The setting is process-wide: it
+    /// applies to every error displayed afterwards, not only this runtime's.
+    pub fn set_error_language(&mut self, language: ErrorLanguage) {
+        ErrorLanguage::set_current(language);
+    }
+
+    /// The callbacks run as the program executes. Set a field to install a
+    /// hook and to `None` to remove it.
+    pub fn hooks_mut(&mut self) -> &mut RuntimeHooks {
+        &mut self.hooks
+    }
+
+    /// Installs a hook called before each statement: `hooks.on_statement`.
+    pub fn set_step_hook(&mut self, hook: StepHook) {
+        self.hooks.on_statement = Some(hook);
+    }
+
+    pub fn clear_step_hook(&mut self) {
+        self.hooks.on_statement = None;
+    }
+
+    /// Number of user function calls currently executing (0 at the top level).
+    pub fn call_depth(&self) -> usize {
+        self.call_depth
+    }
+    fn call_user_function(&mut self, name: &Symbol, function: &UserFunction, args: Vec<Value>) -> Result<Value> {
+        let count = args.len();
+        self.arguments.extend(args);
+        self.call_with_arguments(name, function, count)
+    }
+
+    /// Calls `function` with the last `count` values of `arguments`, which
+    /// it takes off the stack.
+    fn call_with_arguments(&mut self, name: &Symbol, function: &UserFunction, count: usize) -> Result<Value> {
+        if let Some(hook) = &mut self.hooks.on_call {
+            hook(name, &self.arguments[self.arguments.len() - count..]);
+        }
+        if !self.profiling {
+            return self.run_user_function(name, function, count);
+        }
+        let start = Instant::now();
+        let result = self.run_user_function(name, function, count);
+        self.record_call(name, start.elapsed());
+        result
+    }
+
+    fn run_user_function(&mut self, name: &Symbol, function: &UserFunction, count: usize) -> Result<Value> {
+        let UserFunction { params, rest, body, .. } = function;
+        let required = params.iter().take_while(|(_, default)| default.is_none()).count();
+        let max = if rest.is_some() { None } else { Some(params.len()) };
+        if count < required || max.is_some_and(|max| count > max) {
+            self.arguments.truncate(self.arguments.len() - count);
+            return Err(arity_error(name, required, max, count));
+        }
+        // The body sees the caller's variables; whatever it binds is its own
+        // and goes when it returns.
+        self.call_frames.push(CallFrame { first_local: self.locals.len(), mask: self.locals_mask });
+        let result = self.bind_parameters(params, rest.as_ref(), count).and_then(|()| {
+            // A function body starts its own expression nesting.
+            let expression_depth = std::mem::take(&mut self.expression_depth);
+            self.call_depth += 1;
+            let result = self.execute_function_body(body);
+            self.call_depth -= 1;
+            self.expression_depth = expression_depth;
+            result
+        });
+        let frame = self.call_frames.pop().expect("call frame pushed above");
+        self.locals.truncate(frame.first_local);
+        self.locals_mask = frame.mask;
+        result
+    }
+
+    /// Where the innermost local binding of `name` is in `locals`.
+    fn local_index(&self, name: &Symbol) -> Option<usize> {
+        if self.locals_mask & local_bit(name) == 0 {
+            return None;
+        }
+        self.locals.iter().rposition(|local| local.name == *name)
+    }
+
+    /// Whether the binding `name` currently refers to is a constant.
+    fn is_constant(&self, name: &Symbol) -> bool {
+        match self.local_index(name) {
+            Some(index) => self.locals[index].constant,
+            None => self.constants.contains(name),
+        }
+    }
+
+    /// Adds a local to the innermost call, shadowing any binding of `name`.
+    fn push_local(&mut self, name: Symbol, value: Value) {
+        self.locals_mask |= local_bit(&name);
+        self.locals.push(Local { name, value, constant: false });
+    }
+
+    /// Binds `name` to `value`: in the innermost call when there is one,
+    /// otherwise as a global.
+    fn bind(&mut self, name: Symbol, value: Value) {
+        let Some(frame) = self.call_frames.last() else {
+            self.globals.insert(name, value);
+            return;
+        };
+        let first = frame.first_local;
+        match self.local_index(&name).filter(|index| *index >= first) {
+            Some(index) => self.locals[index].value = value,
+            None => self.push_local(name, value),
+        }
+    }
+
+    /// Marks the binding of `name` in the current scope as a constant.
+    fn mark_constant(&mut self, name: &Symbol) {
+        let first = match self.call_frames.last() {
+            Some(frame) => frame.first_local,
+            None => {
+                self.constants.insert(name.clone());
+                return;
+            }
+        };
+        if let Some(index) = self.local_index(name).filter(|index| *index >= first) {
+            self.locals[index].constant = true;
+        }
+    }
+
+    /// Binds the last `count` values of `arguments` to parameters in order.
+    /// Omitted trailing arguments take their default, evaluated after the
+    /// earlier parameters are bound so a default can refer to them. Extra
+    /// arguments go to `rest` as a list.
+    fn bind_parameters(&mut self, params: &[Param], rest: Option<&Symbol>, count: usize) -> Result<()> {
+        let start = self.arguments.len() - count;
+        let mut args = self.arguments.drain(start..);
+        let mut bound = 0;
+        for ((param, _), value) in params.iter().zip(args.by_ref()) {
+            // A parameter is a fresh local even if it shares a constant's name.
+            self.locals_mask |= local_bit(param);
+            self.locals.push(Local { name: param.clone(), value, constant: false });
+            bound += 1;
+        }
+        // Without `rest` the arity check left no extra arguments.
+        let extra: Vec<Value> = if rest.is_some() { args.collect() } else { drop(args); Vec::new() };
+        for (param, default) in &params[bound..] {
+            let value = match default {
+                Some(default) => self.evaluate_expression(default)?,
+                None => Value::Nil,
+            };
+            self.push_local(param.clone(), value);
+        }
+        if let Some(rest) = rest {
+            self.push_local(rest.clone(), Value::List(extra));
+        }
+        Ok(())
+    }
+
+    fn execute_function_body(&mut self, body: &[Stmt]) -> Result<Value> {
+        let mut ret = Value::Nil;
+        for stmt in body {
+            match self.execute_statement(stmt) {
+                Ok(v) => ret = v,
+                // An IqraError passes through unchanged, keeping its kind for
+                // `امسك` arms and its line inside the function.
+                Err(e) if e.is::<ExitSignal>() || e.is::<IqraError>() => return Err(e),
+                Err(e) => {
+                    let msg = format!("{}", e);
+                    return Err(anyhow!(IqraError {
+                        kind: "خطأ في تنفيذ الدالة".to_string(),
+                        message_ar: format!("خطأ أثناء تنفيذ الدالة: {}", msg),
+                        message_en: format!("Error during function execution: {}", msg),
+                        suggestion: Some(tr!("suggest.check_function_body")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                }
+            }
+            if let Some(value) = self.returning.take() {
+                return Ok(value);
+            }
+        }
+        Ok(ret)
+    }
+    pub fn new() -> Self {
+            Runtime {
+                globals: SymbolMap::default(),
+                constants: SymbolSet::default(),
+                functions: SymbolMap::default(),
+                returning: None,
+                loop_exit: None,
+                call_frames: Vec::new(),
+                locals: Vec::new(),
+                locals_mask: 0,
+                arguments: Vec::new(),
+                system_executor: Box::new(DefaultSystemExecutor),
+                output: Box::new(StdoutSink),
+                clock: Arc::new(SystemClock),
+                system_info_cache: None,
+                today_cache: None,
+                regex_cache: HashMap::new(),
+                rng: None,
+                hooks: RuntimeHooks::default(),
+                error_reported: false,
+                call_depth: 0,
+                sandboxed: false,
+                limits: Limits::default(),
+                statements_executed: 0,
+                hotspots: HashMap::new(),
+                deadline: None,
+                cancellation: CancellationToken::default(),
+                cancelled: false,
+                warned_aliases: HashSet::new(),
+                profiling: false,
+                profile: HashMap::new(),
+                source_name: None,
+                expression_depth: 0,
+                script_args: Vec::new(),
+                cache_file: None,
+                optimize: false,
+                expand_paths: true,
+            }
+    }
+
+    /// Runs scripts in a sandbox that refuses builtins with side effects on
+    /// the host: `system`, `write_file`, `set_env` and `change_dir`.
+    pub fn set_sandboxed(&mut self, sandboxed: bool) {
+        self.sandboxed = sandboxed;
+    }
+
+    /// Whether the file builtins expand a leading `~` and `$VAR`/`${VAR}`
+    /// in their paths, as `وسع_مسار` does. On by default; a sandboxed
+    /// runtime always takes paths literally.
+    pub fn set_expand_paths(&mut self, expand: bool) {
+        self.expand_paths = expand;
+    }
+
+    /// `path` with `~` and environment variables expanded through the
+    /// executor. Unset variables expand to nothing, with a warning.
+    fn expand_path(&self, path: &str) -> String {
+        let env = |name: &str| self.system_executor.get_env_var(name);
+        let home = paths::home_dir_in(&|name| env(name).map(OsString::from));
+        let home = home.as_ref().map(|home| home.to_string_lossy());
+        path::expand(path, home.as_deref(), &env, &mut |name| {
+            tracing::warn!("unset variable ${} in path '{}' expands to nothing", name, path);
+        })
+    }
+
+    /// `args` with the path of a file builtin expanded, or `None` when
+    /// there is nothing to change.
+    fn expand_path_arg(&self, name: &str, args: &[Value]) -> Option<Vec<Value>> {
+        if !self.expand_paths || self.sandboxed || !PATH_BUILTINS.contains(&canonical_builtin(name)?) {
+            return None;
+        }
+        let Some(Value::String(path)) = args.first() else {
+            return None;
+        };
+        let expanded = self.expand_path(path);
+        if expanded == *path {
+            return None;
+        }
+        let mut args = args.to_vec();
+        args[0] = Value::String(expanded);
+        Some(args)
+    }
+
+    fn ensure_not_sandboxed(&self, name: &str) -> Result<()> {
+        if !self.sandboxed {
+            return Ok(());
+        }
+        Err(anyhow!(IqraError {
+            kind: "غير مسموح في وضع العزل | Not allowed in sandbox".to_string(),
+            message_ar: format!("الدالة '{}' غير مسموحة في وضع العزل", name),
+            message_en: format!("Function '{}' is not allowed in sandbox mode", name),
+            suggestion: Some(tr!("suggest.leave_sandbox")),
+            line: None,
+            column: None,
+            source_name: None,
+        }))
+    }
+
+    fn read_input(&self, prompt: &str) -> Result<String> {
+        self.system_executor.read_line(prompt).map_err(input_error)
+    }
+
+    /// Sets the limits applied to each later run. See [`Limits`].
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+        self.start_run();
+    }
+
+    /// Statements executed since the current run started.
+    pub fn statements_executed(&self) -> u64 {
+        self.statements_executed
+    }
+
+    /// Resets the statement counter and the timeout clock. `run_program` and
+    /// `execute` call this; callers that feed statements one at a time through
+    /// `execute_parsed_statement` call it once before the first.
+    pub fn start_run(&mut self) {
+        self.statements_executed = 0;
+        self.hotspots.clear();
+        self.deadline = self.limits.timeout.map(|timeout| Instant::now() + timeout);
+        // The cancellation stopped the last run, so it's spent.
+        if self.cancelled {
+            self.cancellation.reset();
+            self.cancelled = false;
+        }
+    }
+
+    /// A token that stops this runtime's runs, for a host to cancel from
+    /// another thread. `خريطة_متوازية` workers share it.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    /// Stops this runtime's runs when `token` is cancelled instead, so one
+    /// token can serve runtimes that replace each other.
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation = token;
+    }
+
+    /// The `n` statements that ran most often this run, most first. Empty
+    /// unless a statement or time limit is set, since counting costs time.
+    pub fn hotspots(&self, n: usize) -> Vec<Hotspot> {
+        let mut hotspots: Vec<Hotspot> = self
+            .hotspots
+            .iter()
+            .map(|(&(line, column), &count)| Hotspot { line, column, count })
+            .collect();
+        hotspots.sort_by_key(|spot| (std::cmp::Reverse(spot.count), spot.line, spot.column));
+        hotspots.truncate(n);
+        hotspots
+    }
+
+    fn counts_hotspots(&self) -> bool {
+        self.limits.max_statements.is_some() || self.limits.timeout.is_some()
+    }
+
+    /// Counts one run of the statement at `span`. Loops also count each pass
+    /// here, so an endless loop with an empty body is still the hottest spot.
+    fn count_hotspot(&mut self, span: Span) {
+        if self.counts_hotspots() {
+            *self.hotspots.entry((span.start_line, span.start_col)).or_default() += 1;
+        }
+    }
+
+    /// Adds the statements that ran most to the message of a limit error,
+    /// so the loop that ran away can be found.
+    fn with_hotspots(&self, mut err: anyhow::Error) -> anyhow::Error {
+        let hotspots = self.hotspots(REPORTED_HOTSPOTS);
+        if hotspots.is_empty() {
+            return err;
+        }
+        if let Some(iqra_err) = err.downcast_mut::<IqraError>() {
+            let ar: Vec<String> =
+                hotspots.iter().map(|spot| format!("السطر {}:{} ({} مرة)", spot.line, spot.column, spot.count)).collect();
+            let en: Vec<String> =
+                hotspots.iter().map(|spot| format!("line {}:{} ({} times)", spot.line, spot.column, spot.count)).collect();
+            iqra_err.message_ar.push_str(&format!("؛ الأكثر تنفيذاً: {}", ar.join("، ")));
+            iqra_err.message_en.push_str(&format!("; most executed: {}", en.join(", ")));
+        }
+        err
+    }
+
+    fn limit_reached(&self) -> bool {
+        self.limits.max_statements.is_some_and(|max| self.statements_executed > max)
+            || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            || self.cancellation.is_cancelled()
+    }
+
+    /// Counts one step of execution and fails once a limit is passed. A limit
+    /// error cannot be caught by `حاول`, so a script can't loop past it.
+    fn check_limits(&mut self) -> Result<()> {
+        if self.cancellation.is_cancelled() {
+            return Err(self.cancelled_error());
+        }
+        self.statements_executed += 1;
+        if let Some(max) = self.limits.max_statements
+            && self.statements_executed > max
+        {
+            return Err(self.with_hotspots(anyhow!(IqraError {
+                kind: "تجاوز حد الجمل | Statement limit exceeded".to_string(),
+                message_ar: format!("تجاوز البرنامج الحد الأقصى من الجمل ({})", max),
+                message_en: format!("The program exceeded the limit of {} statements", max),
+                suggestion: Some(tr!("suggest.endless_loop")),
+                line: None,
+                column: None,
+                source_name: None,
+            })));
+        }
+        if let (Some(deadline), Some(timeout)) = (self.deadline, self.limits.timeout)
+            && Instant::now() >= deadline
+        {
+            return Err(self.with_hotspots(timeout_error(timeout)));
+        }
+        Ok(())
+    }
+
+    /// Fails when a list or map of `len` items would pass
+    /// `max_collection_len`. Checked before the value is built.
+    fn check_collection_len(&self, len: usize) -> Result<()> {
+        match self.limits.max_collection_len {
+            Some(max) if len > max => Err(memory_limit_error(len, max, "عنصراً", "items")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks a list literal, and the lists inside it, against
+    /// `max_collection_len`: the optimizer folds list expressions into
+    /// literals, which would otherwise skip the check the list expressions
+    /// make.
+    fn check_literal(&self, value: &Value) -> Result<()> {
+        if let (Value::List(items), Some(_)) = (value, self.limits.max_collection_len) {
+            self.check_collection_len(items.len())?;
+            items.iter().try_for_each(|item| self.check_literal(item))?;
+        }
+        Ok(())
+    }
+
+    /// Reads the file at `path` as bytes, refusing one bigger than the
+    /// collection limit.
+    fn read_file_bytes(&self, path: &str) -> Result<Vec<u8>> {
+        if let Some(size) = self.system_executor.file_size(path) {
+            self.check_collection_bytes(usize::try_from(size).unwrap_or(usize::MAX))?;
+        }
+        let content = self.system_executor.read_bytes(path).map_err(|e| anyhow!(IqraError {
+            kind: "فشل قراءة الملف".to_string(),
+            message_ar: format!("فشل قراءة الملف: {}", e),
+            message_en: format!("Failed to read file: {}", e),
+            suggestion: Some(tr!("suggest.read_access")),
+            line: None,
+            column: None,
+            source_name: None,
+        }))?;
+        self.check_collection_bytes(content.len())?;
+        Ok(content)
+    }
+
+    /// Fails when a string of `bytes` bytes would pass `max_collection_bytes`.
+    fn check_collection_bytes(&self, bytes: usize) -> Result<()> {
+        match self.limits.max_collection_bytes {
+            Some(max) if bytes > max => Err(memory_limit_error(bytes, max, "بايت", "bytes")),
+            _ => Ok(()),
+        }
+    }
+
+    /// The error a cancelled run fails with, in place of any error the
+    /// cancellation interrupted.
+    fn cancelled_error(&mut self) -> anyhow::Error {
+        self.cancelled = true;
+        anyhow!(IqraError {
+            kind: CANCELLED_ERROR_KIND.to_string(),
+            message_ar: "أُلغي تنفيذ البرنامج".to_string(),
+            message_en: "The program was cancelled".to_string(),
+            suggestion: None,
+            line: None,
+            column: None,
+            source_name: None,
+        })
+    }
+
+    /// Turns call counting and timing on or off. Collected entries are kept
+    /// until `reset_profile`.
+    pub fn set_profiling(&mut self, profiling: bool) {
+        self.profiling = profiling;
+    }
+
+    /// Calls recorded while profiling, keyed by function name.
+    pub fn profile(&self) -> &HashMap<String, ProfileEntry> {
+        &self.profile
+    }
+
+    pub fn reset_profile(&mut self) {
+        self.profile.clear();
+    }
+
+    fn record_call(&mut self, name: &str, elapsed: Duration) {
+        let entry = self.profile.entry(name.to_string()).or_default();
+        entry.calls += 1;
+        entry.total += elapsed;
+    }
+
+    /// Serializes the global variables, constants and user functions to JSON.
+    /// Variables whose values can't be represented (NaN or infinite numbers)
+    /// are left out and listed under `skipped`.
+    pub fn save_state(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.session_state())?)
+    }
+
+    fn session_state(&self) -> SessionState {
+        let mut variables = BTreeMap::new();
+        let mut skipped = Vec::new();
+        for (name, value) in &self.globals {
+            if is_saveable(value) {
+                variables.insert(name.clone(), value.clone());
+            } else {
+                skipped.push(name.clone());
+            }
+        }
+        skipped.sort();
+        let mut constants: Vec<Symbol> = self.constants.iter().cloned().collect();
+        constants.sort();
+        SessionState {
+            version: SESSION_VERSION,
+            variables,
+            constants,
+            functions: self.functions.iter().map(|(name, f)| (name.clone(), UserFunction::clone(f))).collect(),
+            skipped,
+        }
+    }
+
+    /// Restores a session saved by [`Runtime::save_state`] into the global
+    /// scope. Saved names replace existing ones; other variables are kept.
+    /// Returns the names that were skipped when the session was saved.
+    pub fn load_state(&mut self, json: &str) -> Result<Vec<String>> {
+        let state: SessionState = serde_json::from_str(json).map_err(|e| {
+            session_error(format!("ملف الجلسة غير صالح: {}", e), format!("Invalid session file: {}", e))
+        })?;
+        if state.version != SESSION_VERSION {
+            return Err(session_error(
+                format!("إصدار الجلسة {} غير مدعوم", state.version),
+                format!("Unsupported session version {}", state.version),
+            ));
+        }
+        // Like assignments, a session loaded inside a function is its own.
+        for (name, value) in state.variables {
+            self.bind(name, value);
+        }
+        for name in &state.constants {
+            self.mark_constant(name);
+        }
+        self.functions.extend(state.functions.into_iter().map(|(name, f)| (name, Arc::new(f))));
+        Ok(state.skipped.into_iter().map(String::from).collect())
+    }
+
+    /// Sends the output of `اطبع` and the other printing builtins to `sink`
+    /// instead of standard output.
+    pub fn set_output(&mut self, sink: Box<dyn OutputSink>) {
+        self.output = sink;
+    }
+
+    /// Replaces the source of today's date, so scripts that read the date
+    /// can be tested on a fixed day.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Keeps the values of `خزّن` in `path` instead of
+    /// `IQRA_HOME/cache/script_cache.json`.
+    pub fn set_cache_file(&mut self, path: impl Into<PathBuf>) {
+        self.cache_file = Some(path.into());
+    }
+
+    /// The cache file and its entries, empty if there is no file yet.
+    fn load_cache(&self) -> Result<(String, ScriptCache)> {
+        let path = match &self.cache_file {
+            Some(path) => Some(path.clone()),
+            None => paths::script_cache_file_in(&|name| {
+                self.system_executor.get_env_var(name).map(OsString::from)
+            }),
+        };
+        let Some(path) = path else {
+            return Err(anyhow!(IqraError {
+                kind: "فشل قراءة الملف".to_string(),
+                message_ar: "لا يوجد مجلد منزل لملف الذاكرة المؤقتة".to_string(),
+                message_en: "There is no home directory for the cache file".to_string(),
+                suggestion: Some(tr!("suggest.set_iqra_home")),
+                line: None,
+                column: None,
+                source_name: None,
+            }));
+        };
+        let path = path.to_string_lossy().into_owned();
+        match self.system_executor.read_file(&path) {
+            Ok(json) => Ok((path, ScriptCache::parse(&json))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok((path, ScriptCache::default())),
+            Err(e) => Err(anyhow!(IqraError {
+                kind: "فشل قراءة الملف".to_string(),
+                message_ar: format!("فشل قراءة ملف الذاكرة المؤقتة {}: {}", path, e),
+                message_en: format!("Failed to read the cache file {}: {}", path, e),
+                suggestion: Some(tr!("suggest.read_permission")),
+                line: None,
+                column: None,
+                source_name: None,
+            })),
+        }
+    }
+
+    fn save_cache(&self, path: &str, cache: &ScriptCache) -> Result<()> {
+        let parent = Path::new(path).parent().map(|dir| dir.to_string_lossy().into_owned());
+        parent
+            .filter(|dir| !dir.is_empty())
+            .map_or(Ok(()), |dir| self.system_executor.create_dir_all(&dir))
+            .and_then(|()| self.system_executor.write_file(path, &cache.to_json()))
+            .map_err(|e| anyhow!(IqraError {
+                kind: "فشل كتابة الملف".to_string(),
+                message_ar: format!("فشل كتابة ملف الذاكرة المؤقتة {}: {}", path, e),
+                message_en: format!("Failed to write the cache file {}: {}", path, e),
+                suggestion: Some(tr!("suggest.write_access")),
+                line: None,
+                column: None,
+                source_name: None,
+            }))?;
+        Ok(())
+    }
+
+    /// Seeds the runtime's random source so builtins such as `uuid` produce
+    /// the same sequence on every run.
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+    }
+
+    /// Forgets the system information, today's Hijri date and the compiled
+    /// patterns, so the next builtin that needs one asks again. For
+    /// embedders that keep a runtime for a long time.
+    pub fn clear_caches(&mut self) {
+        self.system_info_cache = None;
+        self.today_cache = None;
+        self.regex_cache.clear();
+    }
+
+    /// What the in-memory caches hold; see [`CacheStatus`].
+    pub fn cache_status(&self) -> CacheStatus {
+        CacheStatus {
+            system_info: self.system_info_cache.is_some(),
+            today: self.today_cache.map(|(day, _)| day),
+            regex_patterns: self.regex_cache.len(),
+        }
+    }
+
+    /// Compiles `pattern` once and reuses it on later calls, so regex builtins
+    /// inside loops don't recompile the same pattern.
+    fn compiled_regex(&mut self, pattern: &str) -> Result<&Regex> {
+        if !self.regex_cache.contains_key(pattern) {
+            let regex = Regex::new(pattern).map_err(|e| anyhow!(IqraError {
+                kind: "نمط غير صالح | Invalid Pattern".to_string(),
+                message_ar: format!("النمط '{}' غير صالح: {}", pattern, e),
+                message_en: format!("Invalid pattern '{}': {}", pattern, e),
+                suggestion: Some(tr!("suggest.pattern_syntax")),
+                line: None,
+                column: None,
+                source_name: None,
+            }))?;
+            self.regex_cache.insert(pattern.to_string(), regex);
+        }
+        Ok(&self.regex_cache[pattern])
+    }
+
+    pub fn new_with_executor(executor: Box<dyn SystemExecutor>) -> Self {
+            Runtime {
+                globals: SymbolMap::default(),
+                constants: SymbolSet::default(),
+                functions: SymbolMap::default(),
+                returning: None,
+                loop_exit: None,
+                call_frames: Vec::new(),
+                locals: Vec::new(),
+                locals_mask: 0,
+                arguments: Vec::new(),
+                system_executor: executor,
+                output: Box::new(StdoutSink),
+                clock: Arc::new(SystemClock),
+                system_info_cache: None,
+                today_cache: None,
+                regex_cache: HashMap::new(),
+                rng: None,
+                hooks: RuntimeHooks::default(),
+                error_reported: false,
+                call_depth: 0,
+                sandboxed: false,
+                limits: Limits::default(),
+                statements_executed: 0,
+                hotspots: HashMap::new(),
+                deadline: None,
+                cancellation: CancellationToken::default(),
+                cancelled: false,
+                warned_aliases: HashSet::new(),
+                profiling: false,
+                profile: HashMap::new(),
+                source_name: None,
+                expression_depth: 0,
+                script_args: Vec::new(),
+                cache_file: None,
+                optimize: false,
+                expand_paths: true,
+            }
+    }
+
+    pub fn execute(&mut self, input: &str) -> Result<Value> {
+        self.run_program(&self.compile(input)?)
+    }
+
+    /// Parses `input` into a program for this runtime, optimized when
+    /// `set_optimize(true)` was called.
+    pub fn compile(&self, input: &str) -> Result<Program> {
+        Program::compile(input).map(|program| self.prepare(program))
+    }
+
+    fn prepare(&self, program: Program) -> Program {
+        if self.optimize { program.optimized() } else { program }
+    }
+
+    /// Makes `compile`, `execute` and `execute_named` fold constant
+    /// expressions and drop dead `اذا` branches; see `optimizer`. Off by
+    /// default, so the REPL and the debugger step through the source as
+    /// written.
+    pub fn set_optimize(&mut self, optimize: bool) {
+        self.optimize = optimize;
+    }
+
+    /// Like [`Runtime::execute`], naming `name` (a file path, `<repl>` or
+    /// `<cmdline>`) as the source in errors.
+    pub fn execute_named(&mut self, input: &str, name: &str) -> Result<Value> {
+        let previous = self.source_name.replace(name.to_string());
+        let result = Program::compile_named(input, name)
+            .and_then(|program| self.run_program(&self.prepare(program)));
+        self.source_name = previous;
+        result
+    }
+
+    /// Sets the source named in the errors of statements run afterwards, for
+    /// callers that use `execute_parsed_statement` directly.
+    pub fn set_source_name(&mut self, name: Option<String>) {
+        self.source_name = name;
+    }
+
+    /// Sets the arguments the script reads with `وسائط()`/`args()`.
+    pub fn set_script_args(&mut self, args: Vec<String>) {
+        self.script_args = args;
+    }
+
+    /// Runs an already-compiled program and returns its last value.
+    pub fn run_program(&mut self, program: &Program) -> Result<Value> {
+        self.start_run();
+        let mut last_value = Value::Nil;
+        for stmt in program.statements() {
+            last_value = self.execute_parsed_statement(stmt)?;
+        }
+
+        Ok(last_value)
+    }
+
+    /// Executes a single already-parsed top-level statement (used by the REPL
+    /// step mode and `run_file`).
+    pub fn execute_parsed_statement(&mut self, stmt: &Stmt) -> Result<Value> {
+        let (value, loop_exit) = self.execute_loop_statement(stmt)?;
+        match loop_exit {
+            Some(loop_exit) => Err(loop_exit.outside_loop_error(Some(stmt.span.start_line), None)),
+            None => Ok(value),
+        }
+    }
+
+    /// Executes a statement from the body of a loop whose iterations the
+    /// caller runs itself, as the step mode does. A `توقف` or `استمر` it
+    /// reaches is returned for the caller to act on.
+    pub(crate) fn execute_loop_statement(&mut self, stmt: &Stmt) -> Result<(Value, Option<LoopExit>)> {
+        let value = self.execute_statement(stmt).map_err(|err| match &self.source_name {
+            Some(name) => IqraError::with_source_name(err, name),
+            None => err,
+        })?;
+        if self.returning.take().is_none() {
+            return Ok((value, self.loop_exit.take()));
+        }
+        Err(anyhow!(IqraError {
+            kind: "ارجع خارج دالة".to_string(),
+            message_ar: "لا يمكن استخدام ارجع خارج دالة".to_string(),
+            message_en: "return can only be used inside a function".to_string(),
+            suggestion: Some(tr!("suggest.return_in_function")),
+            line: Some(stmt.span.start_line),
+            column: None,
+            source_name: self.source_name.clone(),
+        }))
+    }
+
+    /// Evaluates an already-parsed expression (used by the REPL step mode).
+    pub fn evaluate_parsed_expression(&mut self, expr: &Expr) -> Result<Value> {
+        self.evaluate_expression(expr)
+    }
+
+    fn execute_statement(&mut self, stmt: &Stmt) -> Result<Value> {
+        self.error_reported = false;
+        self.count_hotspot(stmt.span);
+        self.check_limits()
+            .and_then(|()| self.execute_statement_kind(stmt))
+            .map_err(|err| self.statement_error(err, stmt.span))
+    }
+
+    /// Puts the statement's position on an error and reports it to
+    /// `on_error`, unless a nested statement already did while the error was
+    /// on its way out.
+    fn statement_error(&mut self, err: anyhow::Error, span: Span) -> anyhow::Error {
+        let err = IqraError::with_position(err, span.start_line, span.start_col);
+        if !self.error_reported
+            && let Some(hook) = &mut self.hooks.on_error
+            && let Some(iqra_err) = err.downcast_ref::<IqraError>()
+        {
+            hook(iqra_err);
+            self.error_reported = true;
+        }
+        err
+    }
+
+    fn execute_statement_kind(&mut self, stmt: &Stmt) -> Result<Value> {
+            if let Some(mut hook) = self.hooks.on_statement.take() {
+                let action = hook(stmt, self);
+                self.hooks.on_statement = Some(hook);
+                if action == DebugAction::Abort {
+                    return Err(anyhow!(IqraError {
+                        kind: "إيقاف التنفيذ | Execution Stopped".to_string(),
+                        message_ar: format!("أوقف المصحح التنفيذ عند السطر {}", stmt.span.start_line),
+                        message_en: format!("Execution stopped by the debugger at line {}", stmt.span.start_line),
+                        suggestion: None,
+                        line: Some(stmt.span.start_line),
+                        column: None,
+                        source_name: None,
+                    }));
+                }
+            }
+            match &stmt.kind {
+                StmtKind::Expression(expr) => {
+                    self.evaluate_expression(expr).map_err(|err| comparison_statement_hint(expr, err))
+                }
+                StmtKind::Assignment { name, value } => {
+                    let val = self.evaluate_expression(value)?;
+                    self.assign_variable(name.clone(), val.clone())?;
+                    Ok(val)
+                }
+                StmtKind::MultiAssignment { names, values } => {
+                    // Evaluate every value before binding any name so `أ، ب = ب، أ` swaps.
+                    let values: Vec<Value> =
+                        values.iter().map(|value| self.evaluate_expression(value)).collect::<Result<_>>()?;
+                    let values = match values.as_slice() {
+                        [Value::List(items)] if names.len() > 1 => items.clone(),
+                        _ => values,
+                    };
+                    if values.len() != names.len() {
+                        return Err(anyhow!(IqraError {
+                            kind: "عدد القيم لا يطابق عدد المتغيرات".to_string(),
+                            message_ar: format!("الإسناد إلى {} متغيرات يحتاج {} قيم، لكن وُجدت {}", names.len(), names.len(), values.len()),
+                            message_en: format!("Assigning to {} variables needs {} values, but got {}", names.len(), names.len(), values.len()),
+                            suggestion: Some(tr!("suggest.match_value_count")),
+                            line: None,
+                            column: None,
+                            source_name: None,
+                        }));
+                    }
+                    for (name, value) in names.iter().zip(values.iter()) {
+                        self.assign_variable(name.clone(), value.clone())?;
+                    }
+                    Ok(Value::List(values))
+                }
+                StmtKind::ConstAssignment { name, value } => {
+                    let val = self.evaluate_expression(value)?;
+                    self.assign_variable(name.clone(), val.clone())?;
+                    self.mark_constant(name);
+                    Ok(val)
+                }
+            StmtKind::If { condition, then_branch, else_branch } => {
+                let condition_value = self.evaluate_expression(condition)?;
+                if condition_value.is_truthy() {
+                    self.execute_block(then_branch)
+                } else if let Some(else_branch) = else_branch {
+                    self.execute_block(else_branch)
+                } else {
+                    Ok(Value::Nil)
+                }
+            }
+            StmtKind::While { condition, body } => {
+                let mut last_value = Value::Nil;
+                while self.returning.is_none() && self.evaluate_expression(condition)?.is_truthy() {
+                    self.count_hotspot(stmt.span);
+                    self.check_limits()?;
+                    last_value = self.execute_block(body)?;
+                    if self.loop_exit.take() == Some(LoopExit::Break) {
+                        break;
+                    }
+                }
+                Ok(last_value)
+            }
+            StmtKind::DoWhile { body, condition } => {
+                // The condition is checked after each pass, so the body
+                // always runs at least once.
+                let mut last_value;
+                loop {
+                    self.count_hotspot(stmt.span);
+                    self.check_limits()?;
+                    last_value = self.execute_block(body)?;
+                    if self.loop_exit.take() == Some(LoopExit::Break)
+                        || self.returning.is_some()
+                        || !self.evaluate_expression(condition)?.is_truthy()
+                    {
+                        break;
+                    }
+                }
+                Ok(last_value)
+            }
+            StmtKind::Loop(body) => {
+                let mut last_value = Value::Nil;
+                while self.returning.is_none() {
+                    self.count_hotspot(stmt.span);
+                    self.check_limits()?;
+                    last_value = self.execute_block(body)?;
+                    if self.loop_exit.take() == Some(LoopExit::Break) {
+                        break;
+                    }
+                }
+                Ok(last_value)
+            }
+            StmtKind::Break => {
+                self.loop_exit = Some(LoopExit::Break);
+                Ok(Value::Nil)
+            }
+            StmtKind::Continue => {
+                self.loop_exit = Some(LoopExit::Continue);
+                Ok(Value::Nil)
+            }
+            StmtKind::Block(statements) => self.execute_block(statements),
+            StmtKind::FunctionDef { name, params, rest, body, doc } => {
+                let function = UserFunction {
+                    params: params.clone(),
+                    rest: rest.clone(),
+                    body: body.clone(),
+                    doc: doc.clone(),
+                };
+                self.functions.insert(name.clone(), Arc::new(function));
+                Ok(Value::Nil)
+            }
+            StmtKind::Return(expr) => {
+                let val = self.evaluate_expression(expr)?;
+                self.returning = Some(val);
+                Ok(Value::Nil)
+            }
+            StmtKind::Match { subject, arms, default } => {
+                let subject = self.evaluate_expression(subject)?;
+                match self.select_match_arm(&subject, arms, default)? {
+                    Some(body) => self.execute_block(body),
+                    None => Ok(Value::Nil),
+                }
+            }
+            StmtKind::TryCatch { try_block, catches } => {
+                // Execute try block
+                match self.execute_block(try_block) {
+                    Ok(val) => Ok(val),
+                    Err(_) if self.cancellation.is_cancelled() => Err(self.cancelled_error()),
+                    Err(e) if e.is::<ExitSignal>() || self.limit_reached() || is_nesting_error(&e) => Err(e),
+                    Err(e) => {
+                        // The first arm whose kind matches, or that has none
+                        let arm = catches.iter().find(|arm| {
+                            arm.kind.as_ref().is_none_or(|kind| IqraError::kind_matches(&e, kind))
+                        });
+                        let Some(arm) = arm else { return Err(e) };
+                        // Optionally bind error to variable
+                        if let Some(var) = &arm.error_var {
+                            self.assign_variable(var.clone(), Value::String(format!("{}", e)))?;
+                        }
+                        // Execute catch block
+                        self.execute_block(&arm.body)
+                    }
+                }
+            },
+        }
+    }
+
+    /// Stores `value` in the current scope, refusing to overwrite a constant.
+    fn assign_variable(&mut self, name: Symbol, value: Value) -> Result<()> {
+        if self.is_constant(&name) {
+            return Err(anyhow!(IqraError {
+                kind: "إعادة تعيين ثابت | Cannot reassign constant".to_string(),
+                message_ar: format!("لا يمكن إعادة تعيين الثابت: {}", name),
+                message_en: format!("Cannot reassign constant: {}", name),
+                suggestion: Some(tr!("suggest.constant_name")),
+                line: None,
+                column: None,
+                source_name: None,
+            }));
+        }
+        if let Some(hook) = &mut self.hooks.on_assign {
+            hook(&name, &value);
+        }
+        self.bind(name, value);
+        Ok(())
+    }
+
+    /// Returns the body of the first arm with a value equal to `subject`, or
+    /// the default body. Arms after the matching one are not evaluated.
+    pub fn select_match_arm<'a>(
+        &mut self,
+        subject: &Value,
+        arms: &'a [MatchArm],
+        default: &'a Option<Vec<Stmt>>,
+    ) -> Result<Option<&'a [Stmt]>> {
+        for arm in arms {
+            for value in &arm.values {
+                if self.evaluate_expression(value)? == *subject {
+                    return Ok(Some(&arm.body));
+                }
+            }
+        }
+        Ok(default.as_deref())
+    }
+
+    fn execute_block(&mut self, statements: &[Stmt]) -> Result<Value> {
+        // Lazy evaluation: only evaluate statements as needed (e.g., for early return)
+        let mut last_value = Value::Nil;
+        for stmt in statements {
+            last_value = self.execute_statement(stmt)?;
+            // After `ارجع`, the rest of every enclosing block is skipped up to
+            // the function call, and after `توقف`/`استمر` up to the loop.
+            if self.returning.is_some() || self.loop_exit.is_some() {
+                break;
+            }
+        }
+        Ok(last_value)
+    }
+
+    /// Evaluates `expr`, failing instead of overflowing the stack when
+    /// expressions nest more than `DEFAULT_MAX_NESTING` deep, which parsed
+    /// source can't reach but a hand-built or deserialized AST can.
+    fn evaluate_expression(&mut self, expr: &Expr) -> Result<Value> {
+        if self.expression_depth >= DEFAULT_MAX_NESTING {
+            return Err(nesting_error(DEFAULT_MAX_NESTING));
+        }
+        self.expression_depth += 1;
+        let result = self.evaluate_expression_kind(expr);
+        self.expression_depth -= 1;
+        result
+    }
+
+    fn evaluate_expression_kind(&mut self, expr: &Expr) -> Result<Value> {
+        match &expr.kind {
+            ExprKind::Literal(value) => {
+                self.check_literal(value)?;
+                Ok(value.clone())
+            }
+            ExprKind::Identifier(name) => self.variable(name).cloned(),
+            ExprKind::Binary { left, operator: BinaryOp::Coalesce, right } => {
+                match self.evaluate_expression(left) {
+                    Ok(Value::Nil) => self.evaluate_expression(right),
+                    Ok(value) => Ok(value),
+                    Err(e) if is_missing_value(&e) => self.evaluate_expression(right),
+                    Err(e) => Err(e),
+                }
+            }
+            ExprKind::Binary { left, operator, right } => {
+                // Variables and literals are borrowed where they are.
+                if let (Some(left), Some(right)) = (self.simple_operand(left), self.simple_operand(right)) {
+                    return self.binary(expr, left?, operator, right?);
+                }
+                let left = self.operand(left)?;
+                let right = self.operand(right)?;
+                let (left, right) = (self.resolve(&left)?, self.resolve(&right)?);
+                self.binary(expr, left, operator, right)
+            }
+            ExprKind::Unary { operator, operand } => {
+                let operand_val = self.evaluate_expression(operand)?;
+                Self::evaluate_unary_op(operator, &operand_val)
+            }
+            ExprKind::Call { name, args } if let Some(function) = self.functions.get(name) => {
+                // Held while the body runs, in case it redefines the function.
+                let function = Arc::clone(function);
+                let base = self.arguments.len();
+                for arg in args {
+                    match self.evaluate_expression(arg) {
+                        Ok(value) => self.arguments.push(value),
+                        Err(err) => {
+                            self.arguments.truncate(base);
+                            return Err(err);
+                        }
+                    }
+                }
+                self.call_with_arguments(name, &function, args.len())
+            }
+            _ => self.evaluate_compound_expression(expr),
+        }
+    }
+
+    /// The expressions `evaluate_expression_kind` leaves out, kept apart so
+    /// the frame of the hot path through variables, operators and calls
+    /// to user functions stays small.
+    #[inline(never)]
+    fn evaluate_compound_expression(&mut self, expr: &Expr) -> Result<Value> {
+        match &expr.kind {
+            ExprKind::Call { name, args }
+                if canonical_builtin(name).is_some_and(|name| READ_ONLY_BUILTINS.contains(&name))
+                    && !self.profiling
+                    && self.hooks.on_call.is_none() =>
+            {
+                self.warn_if_deprecated(name);
+                let operands =
+                    args.iter().map(|arg| self.operand(arg)).collect::<Result<Vec<_>>>()?;
+                let values =
+                    operands.iter().map(|operand| self.resolve(operand)).collect::<Result<Vec<_>>>()?;
+                self.read_only_builtin(name, &values)
+            }
+            ExprKind::Call { name, args } => {
+                let mut arg_values = Vec::with_capacity(args.len());
+                for arg in args {
+                    arg_values.push(self.evaluate_expression(arg)?);
+                }
+                self.call_function(name, arg_values)
+            }
+            ExprKind::BuiltinCall { name, args } => {
+                let arg_values: Result<Vec<Value>> =
+                    args.iter().map(|arg| self.evaluate_expression(arg)).collect();
+                self.call_builtin(name, &arg_values?)
+            }
+            ExprKind::List(elements) => {
+                self.check_collection_len(elements.len())?;
+                let values: Result<Vec<Value>> =
+                    elements.iter().map(|elem| self.evaluate_expression(elem)).collect();
+                Ok(Value::List(values?))
+            }
+            ExprKind::Index { .. } if let Some(operand) = self.place(expr)? => {
+                self.resolve(&operand).cloned()
+            }
+            ExprKind::Index { object, index } => {
+                let obj_val = self.evaluate_expression(object)?;
+                let idx_val = self.evaluate_expression(index)?;
+                self.evaluate_index(&obj_val, &idx_val)
+            }
+            ExprKind::SafeEval(expr) => {
+                let (succeeded, value, error) = match self.evaluate_expression(expr) {
+                    Ok(value) => (true, value, Value::Nil),
+                    Err(_) if self.cancellation.is_cancelled() => return Err(self.cancelled_error()),
+                    Err(e) if e.is::<ExitSignal>() || self.limit_reached() || is_nesting_error(&e) => {
+                        return Err(e);
+                    }
+                    Err(e) => (false, Value::Nil, error_map(&e)),
+                };
+                Ok(Value::Map(HashMap::from([
+                    ("نجح".to_string(), Value::Bool(succeeded)),
+                    ("قيمة".to_string(), value),
+                    ("خطأ".to_string(), error),
+                ])))
+            }
+            _ => unreachable!("handled by evaluate_expression_kind"),
+        }
+    }
+
+    /// The suggestion of an error about calling the undefined function
+    /// `name`, from the user's functions and the builtins.
+    fn function_suggestion(&self, name: &str) -> String {
+        let candidates = self.functions.keys().map(Symbol::as_str).chain(BUILTIN_FUNCTIONS.iter().copied());
+        name_suggestion(name, candidates, tr!("suggest.check_function_name"))
+    }
+
+    /// The variable `name` in the current scope.
+    fn variable(&self, name: &Symbol) -> Result<&Value> {
+        if let Some(index) = self.local_index(name) {
+            return Ok(&self.locals[index].value);
+        }
+        self.globals.get(name).ok_or_else(|| anyhow!(IqraError {
+            kind: "متغير غير معرف".to_string(),
+            message_ar: format!("المتغير غير معرف: {}", name),
+            message_en: format!("Undefined variable: {}", name),
+            suggestion: Some(name_suggestion(
+                name,
+                self.globals.keys().chain(self.locals.iter().map(|local| &local.name)).map(Symbol::as_str),
+                tr!("suggest.define_variable"),
+            )),
+            line: None,
+            column: None,
+            source_name: None,
+        }))
+    }
+
+    /// The variable and evaluated indexes of `س` or `س[أ][ب]`, or `None` for
+    /// any other expression. Each index is checked as soon as it is evaluated,
+    /// so errors come in the same order as evaluating the expression would.
+    /// A byte of a `Bytes` value is a new number rather than a place, so
+    /// indexing one gives an `Operand::Value`.
+    fn place(&mut self, expr: &Expr) -> Result<Option<Operand<'static>>> {
+        let mut indexes = Vec::new();
+        let mut base = expr;
+        while let ExprKind::Index { object, index } = &base.kind {
+            indexes.push(index.as_ref());
+            base = object;
+        }
+        let ExprKind::Identifier(name) = &base.kind else {
+            return Ok(None);
+        };
+        self.variable(name)?;
+        let mut keys = Vec::with_capacity(indexes.len());
+        let mut indexes = indexes.into_iter().rev();
+        while let Some(index) = indexes.next() {
+            let key = self.evaluate_expression(index)?;
+            if let Value::Bytes(bytes) = self.place_value(name, &keys)? {
+                let mut value = byte_at(bytes, &key)?;
+                // Indexing the number further fails, after evaluating the index.
+                for index in indexes {
+                    let key = self.evaluate_expression(index)?;
+                    value = index_ref(&value, &key)?.clone();
+                }
+                return Ok(Some(Operand::Value(value)));
+            }
+            keys.push(key);
+            self.place_value(name, &keys)?;
+        }
+        Ok(Some(Operand::Place(name.clone(), keys)))
+    }
+
+    fn place_value(&self, name: &Symbol, keys: &[Value]) -> Result<&Value> {
+        keys.iter().try_fold(self.variable(name)?, |value, key| index_ref(value, key))
+    }
+
+    /// The value of a variable or a literal, borrowed; `None` for any other
+    /// expression.
+    fn simple_operand<'a>(&'a self, expr: &'a Expr) -> Option<Result<&'a Value>> {
+        match &expr.kind {
+            ExprKind::Literal(value) => Some(self.check_literal(value).map(|()| value)),
+            ExprKind::Identifier(name) => Some(self.variable(name)),
+            _ => None,
+        }
+    }
+
+    /// Applies the operator of the binary `expr` to its evaluated operands.
+    fn binary(&self, expr: &Expr, left: &Value, operator: &BinaryOp, right: &Value) -> Result<Value> {
+        if let (BinaryOp::Add, Value::String(a), Value::String(b)) = (operator, left, right) {
+            self.check_collection_bytes(a.len() + b.len())?;
+        }
+        Self::evaluate_binary_op(left, operator, right).map_err(|err| chained_comparison_hint(expr, left, right, err))
+    }
+
+    /// Evaluates `expr` as far as it can without copying variables or
+    /// literals; `resolve` then borrows the value.
+    fn operand<'e>(&mut self, expr: &'e Expr) -> Result<Operand<'e>> {
+        if let ExprKind::Literal(value) = &expr.kind {
+            self.check_literal(value)?;
+            return Ok(Operand::Literal(value));
+        }
+        Ok(match self.place(expr)? {
+            Some(operand) => operand,
+            None => Operand::Value(self.evaluate_expression(expr)?),
+        })
+    }
+
+    fn resolve<'a>(&'a self, operand: &'a Operand) -> Result<&'a Value> {
+        match operand {
+            Operand::Literal(value) => Ok(value),
+            Operand::Place(name, keys) => self.place_value(name, keys),
+            Operand::Value(value) => Ok(value),
+        }
+    }
+
+    /// Applies a binary operator to two values, as evaluation and the
+    /// optimizer's constant folding both do.
+    pub(crate) fn evaluate_binary_op(left: &Value, op: &BinaryOp, right: &Value) -> Result<Value> {
+        match op {
+            BinaryOp::Add => match (left, right) {
+                (Value::String(a), Value::String(b)) => Ok(Value::String(format!("{}{}", a, b))),
+                _ => numeric_op(left, right, i64::checked_add, |a, b| a + b).unwrap_or_else(|| Err(anyhow!(IqraError {
                     kind: "جمع غير صالح".to_string(),
                     message_ar: "معاملات غير صالحة للجمع".to_string(),
                     message_en: "Invalid operands for addition".to_string(),
-                    suggestion: Some("تأكد أن الطرفين أرقام أو نصوص".to_string()),
+                    suggestion: Some(tr!("suggest.numbers_or_texts")),
                     line: None,
-                })),
+                    column: None,
+                    source_name: None,
+                }))),
             },
-            BinaryOp::Subtract => match (left, right) {
-                (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a - b)),
-                _ => Err(anyhow!(IqraError {
+            BinaryOp::Subtract => numeric_op(left, right, i64::checked_sub, |a, b| a - b).unwrap_or_else(|| {
+                Err(anyhow!(IqraError {
                     kind: "طرح غير صالح".to_string(),
                     message_ar: "معاملات غير صالحة للطرح".to_string(),
                     message_en: "Invalid operands for subtraction".to_string(),
-                    suggestion: Some("استخدم أرقام فقط".to_string()),
+                    suggestion: Some(tr!("suggest.numbers_only")),
                     line: None,
-                })),
-            },
-            BinaryOp::Multiply => match (left, right) {
-                (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a * b)),
-                _ => Err(anyhow!(IqraError {
+                    column: None,
+                    source_name: None,
+                }))
+            }),
+            BinaryOp::Multiply => numeric_op(left, right, i64::checked_mul, |a, b| a * b).unwrap_or_else(|| {
+                Err(anyhow!(IqraError {
                     kind: "ضرب غير صالح".to_string(),
                     message_ar: "معاملات غير صالحة للضرب".to_string(),
                     message_en: "Invalid operands for multiplication".to_string(),
-                    suggestion: Some("استخدم أرقام فقط".to_string()),
+                    suggestion: Some(tr!("suggest.numbers_only")),
                     line: None,
-                })),
-            },
-            BinaryOp::Divide => match (left, right) {
-                (Value::Number(a), Value::Number(b)) => {
-                    if *b == 0.0 {
+                    column: None,
+                    source_name: None,
+                }))
+            }),
+            // Int / Int stays an Int when the division is exact and becomes a
+            // Number otherwise, so ٦ / ٣ is ٢ and ٧ / ٢ is ٣٫٥.
+            BinaryOp::Divide => match (left.as_number(), right.as_number()) {
+                (Some(_), Some(b)) => {
+                    if b == 0.0 {
                         Err(anyhow!(IqraError {
                             kind: "قسمة على صفر".to_string(),
                             message_ar: "القسمة على صفر".to_string(),
                             message_en: "Division by zero".to_string(),
-                            suggestion: Some("تأكد أن المقسوم عليه ليس صفراً".to_string()),
+                            suggestion: Some(tr!("suggest.nonzero_divisor")),
                             line: None,
+                            column: None,
+                            source_name: None,
                         }))
                     } else {
-                        Ok(Value::Number(a / b))
+                        // i64::MIN / -1 has no Int result, so the remainder is
+                        // checked too rather than computed with `%`.
+                        match (left, right) {
+                            (Value::Int(a), Value::Int(b)) => match a.checked_rem(*b) {
+                                Some(0) => a.checked_div(*b).map(Value::Int).ok_or_else(overflow_error),
+                                Some(_) => Ok(Value::Number(*a as f64 / *b as f64)),
+                                None => Err(overflow_error()),
+                            },
+                            _ => Ok(Value::Number(left.as_number().unwrap() / b)),
+                        }
                     }
                 }
                 _ => Err(anyhow!(IqraError {
                     kind: "قسمة غير صالحة".to_string(),
                     message_ar: "معاملات غير صالحة للقسمة".to_string(),
                     message_en: "Invalid operands for division".to_string(),
-                    suggestion: Some("استخدم أرقام فقط".to_string()),
+                    suggestion: Some(tr!("suggest.numbers_only")),
                     line: None,
+                    column: None,
+                    source_name: None,
                 })),
             },
-            BinaryOp::Modulo => match (left, right) {
-                (Value::Number(a), Value::Number(b)) => {
-                    if *b == 0.0 {
+            BinaryOp::Modulo => match (left.as_number(), right.as_number()) {
+                (Some(_), Some(b)) => {
+                    if b == 0.0 {
                         Err(anyhow!(IqraError {
                             kind: "قسمة باقية على صفر".to_string(),
                             message_ar: "القسمة الباقية على صفر".to_string(),
                             message_en: "Modulo by zero".to_string(),
-                            suggestion: Some("تأكد أن المقسوم عليه ليس صفراً".to_string()),
+                            suggestion: Some(tr!("suggest.nonzero_divisor")),
                             line: None,
+                            column: None,
+                            source_name: None,
                         }))
                     } else {
-                        Ok(Value::Number(a % b))
+                        numeric_op(left, right, i64::checked_rem, |a, b| a % b).unwrap()
                     }
                 }
                 _ => Err(anyhow!(IqraError {
                     kind: "قسمة باقية غير صالحة".to_string(),
                     message_ar: "معاملات غير صالحة للقسمة الباقية".to_string(),
                     message_en: "Invalid operands for modulo".to_string(),
-                    suggestion: Some("استخدم أرقام فقط".to_string()),
+                    suggestion: Some(tr!("suggest.numbers_only")),
                     line: None,
+                    column: None,
+                    source_name: None,
                 })),
             },
             BinaryOp::Equal => Ok(Value::Bool(left == right)),
             BinaryOp::NotEqual => Ok(Value::Bool(left != right)),
-            BinaryOp::Less => match (left, right) {
-                (Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a < b)),
-                _ => Err(anyhow!(IqraError {
-                    kind: "مقارنة غير صالحة".to_string(),
-                    message_ar: "معاملات غير صالحة للمقارنة".to_string(),
-                    message_en: "Invalid operands for comparison".to_string(),
-                    suggestion: Some("استخدم أرقام فقط".to_string()),
-                    line: None,
-                })),
-            },
-            BinaryOp::LessEqual => match (left, right) {
-                (Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a <= b)),
-                _ => Err(anyhow!(IqraError {
-                    kind: "مقارنة غير صالحة".to_string(),
-                    message_ar: "معاملات غير صالحة للمقارنة".to_string(),
-                    message_en: "Invalid operands for comparison".to_string(),
-                    suggestion: Some("استخدم أرقام فقط".to_string()),
-                    line: None,
-                })),
-            },
-            BinaryOp::Greater => match (left, right) {
-                (Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a > b)),
-                _ => Err(anyhow!(IqraError {
-                    kind: "مقارنة غير صالحة".to_string(),
-                    message_ar: "معاملات غير صالحة للمقارنة".to_string(),
-                    message_en: "Invalid operands for comparison".to_string(),
-                    suggestion: Some("استخدم أرقام فقط".to_string()),
-                    line: None,
-                })),
-            },
-            BinaryOp::GreaterEqual => match (left, right) {
-                (Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a >= b)),
-                _ => Err(anyhow!(IqraError {
+            BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual => {
+                let ordering = left.compare_numbers(right).ok_or_else(|| anyhow!(IqraError {
                     kind: "مقارنة غير صالحة".to_string(),
                     message_ar: "معاملات غير صالحة للمقارنة".to_string(),
                     message_en: "Invalid operands for comparison".to_string(),
-                    suggestion: Some("استخدم أرقام فقط".to_string()),
+                    suggestion: Some(tr!("suggest.numbers_only")),
                     line: None,
-                })),
-            },
+                    column: None,
+                    source_name: None,
+                }))?;
+                Ok(Value::Bool(match op {
+                    BinaryOp::Less => ordering.is_lt(),
+                    BinaryOp::LessEqual => ordering.is_le(),
+                    BinaryOp::Greater => ordering.is_gt(),
+                    _ => ordering.is_ge(),
+                }))
+            }
             BinaryOp::And => Ok(Value::Bool(left.is_truthy() && right.is_truthy())),
             BinaryOp::Or => Ok(Value::Bool(left.is_truthy() || right.is_truthy())),
+            BinaryOp::Coalesce => Ok(if left.is_nil() { right.clone() } else { left.clone() }),
         }
     }
 
-    fn evaluate_unary_op(&self, op: &UnaryOp, operand: &Value) -> Result<Value> {
+    pub(crate) fn evaluate_unary_op(op: &UnaryOp, operand: &Value) -> Result<Value> {
         match op {
             UnaryOp::Not => Ok(Value::Bool(!operand.is_truthy())),
             UnaryOp::Minus => match operand {
                 Value::Number(n) => Ok(Value::Number(-n)),
+                Value::Int(n) => n.checked_neg().map(Value::Int).ok_or_else(overflow_error),
                 _ => Err(anyhow!(IqraError {
                     kind: "سالب أحادي غير صالح".to_string(),
                     message_ar: "معامل غير صالح للسالب الأحادي".to_string(),
                     message_en: "Invalid operand for unary minus".to_string(),
-                    suggestion: Some("استخدم رقم فقط".to_string()),
+                    suggestion: Some(tr!("suggest.number_only")),
                     line: None,
+                    column: None,
+                    source_name: None,
                 })),
             },
         }
     }
 
     fn evaluate_index(&self, object: &Value, index: &Value) -> Result<Value> {
-        match (object, index) {
-            (Value::List(list), Value::Number(n)) => {
-                let idx = *n as usize;
-                list.get(idx).cloned().ok_or_else(|| anyhow!(IqraError {
-                    kind: "فهرسة خارج النطاق".to_string(),
-                    message_ar: format!("الفهرس خارج النطاق: {}", idx),
-                    message_en: format!("Index out of bounds: {}", idx),
-                    suggestion: Some("تأكد من أن الفهرس ضمن حدود القائمة".to_string()),
-                    line: None,
-                }))
+        match object {
+            Value::Bytes(bytes) => byte_at(bytes, index),
+            _ => index_ref(object, index).cloned(),
+        }
+    }
+
+    /// Calls the user function `name`, or the builtin if there is none.
+    fn call_function(&mut self, name: &Symbol, args: Vec<Value>) -> Result<Value> {
+        match self.functions.get(name) {
+            Some(function) => {
+                // Held while the body runs, in case it redefines the function.
+                let function = Arc::clone(function);
+                self.call_user_function(name, &function, args)
             }
-            (Value::Map(map), Value::String(key)) => {
-                map.get(key).cloned().ok_or_else(|| anyhow!(IqraError {
-                    kind: "مفتاح غير موجود".to_string(),
-                    message_ar: format!("المفتاح غير موجود: {}", key),
-                    message_en: format!("Key not found: {}", key),
-                    suggestion: Some("تأكد من وجود المفتاح في القاموس".to_string()),
+            None => self.call_builtin(name, &args),
+        }
+    }
+
+    /// A runtime for one `parallel_map` worker: it sees this runtime's
+    /// functions and current variables, runs under the same sandbox and
+    /// limits, and captures its output so it can be printed in order. Host
+    /// access goes through the default executor.
+    fn child_runtime(&self) -> (Runtime, CapturedOutput) {
+        let output = CapturedOutput::new();
+        let mut child = Runtime::new();
+        child.functions = self.functions.clone();
+        child.globals = self.visible_variables();
+        child.constants = self.visible_constants();
+        child.sandboxed = self.sandboxed;
+        child.limits = self.limits;
+        child.script_args = self.script_args.clone();
+        child.cache_file = self.cache_file.clone();
+        child.clock = Arc::clone(&self.clock);
+        child.cancellation = self.cancellation.clone();
+        child.output = Box::new(output.clone());
+        child.start_run();
+        (child, output)
+    }
+
+    /// `parallel_map(list, function_name)`: calls the function on every
+    /// element, splitting the list across threads. Results keep the list's
+    /// order; changes a worker makes to variables are discarded.
+    fn parallel_map(&mut self, name: &str, args: &[Value]) -> Result<Value> {
+        if args.len() != 2 {
+            return Err(arity_error(name, 2, Some(2), args.len()));
+        }
+        let (list, function) = match (&args[0], &args[1]) {
+            (Value::List(list), Value::String(function)) => (list, function.as_str()),
+            _ => {
+                return Err(anyhow!(IqraError {
+                    kind: "نوع وسيط غير صحيح".to_string(),
+                    message_ar: format!("{} تتوقع قائمة واسم دالة", name),
+                    message_en: format!("{} expects a list and a function name", name),
+                    suggestion: Some(tr!("suggest.example_parallel_map")),
                     line: None,
-                }))
+                    column: None,
+                    source_name: None,
+                }));
             }
-            _ => Err(anyhow!(IqraError {
-                kind: "عملية فهرسة غير صالحة".to_string(),
-                message_ar: "عملية فهرسة غير صالحة".to_string(),
-                message_en: "Invalid indexing operation".to_string(),
-                suggestion: Some("استخدم قائمة أو قاموس مع فهرس مناسب".to_string()),
+        };
+        let user_function = Symbol::lookup(function).filter(|symbol| self.functions.contains_key(symbol));
+        if user_function.is_none() && canonical_builtin(function).is_none() {
+            return Err(anyhow!(IqraError {
+                kind: "دالة غير معرفة".to_string(),
+                message_ar: format!("الدالة غير معرفة: {}", function),
+                message_en: format!("Undefined function: {}", function),
+                suggestion: Some(self.function_suggestion(function)),
                 line: None,
-            })),
+                column: None,
+                source_name: None,
+            }));
+        }
+        // Only a builtin name is left to intern, so the table can't grow
+        // with arbitrary strings.
+        let function = user_function.unwrap_or_else(|| Symbol::intern(function));
+        if list.is_empty() {
+            return Ok(Value::List(Vec::new()));
         }
+        let workers = std::thread::available_parallelism().map_or(1, |n| n.get()).min(list.len());
+        let chunk_size = list.len().div_ceil(workers);
+        let function = &function;
+        let children: Vec<_> = list.chunks(chunk_size).map(|chunk| (chunk, self.child_runtime())).collect();
+        let results: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = children
+                .into_iter()
+                .map(|(chunk, (mut child, output))| {
+                    scope.spawn(move || {
+                        let mut values = Vec::with_capacity(chunk.len());
+                        for (offset, item) in chunk.iter().enumerate() {
+                            match child.call_function(function, vec![item.clone()]) {
+                                Ok(value) => values.push(value),
+                                Err(err) => return (Err((offset, err)), output),
+                            }
+                        }
+                        (Ok(values), output)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().expect("parallel_map worker panicked")).collect()
+        });
+        if self.cancellation.is_cancelled() {
+            return Err(self.cancelled_error());
+        }
+        let mut mapped = Vec::with_capacity(list.len());
+        for (chunk_index, (result, output)) in results.into_iter().enumerate() {
+            self.output.write(&output.take());
+            self.output.write_error(&output.take_errors());
+            match result {
+                Ok(values) => mapped.extend(values),
+                Err((offset, err)) => return Err(element_error(chunk_index * chunk_size + offset, err)),
+            }
+        }
+        Ok(Value::List(mapped))
     }
 
     pub fn call_builtin(&mut self, name: &str, args: &[Value]) -> Result<Value> {
-        match name {
-            // Arabic and English print functions
-            "اطبع" | "print" => {
-                if args.is_empty() {
-                    println!();
-                } else {
-                    for (i, arg) in args.iter().enumerate() {
-                        if i > 0 {
-                            print!(" ");
-                        }
-                        print!("{}", arg);
-                    }
-                    println!();
+        if let Some(hook) = &mut self.hooks.on_call {
+            hook(name, args);
+        }
+        self.warn_if_deprecated(name);
+        let expanded = self.expand_path_arg(name, args);
+        let args = expanded.as_deref().unwrap_or(args);
+        if !self.profiling {
+            return self.run_builtin(name, args);
+        }
+        let start = Instant::now();
+        let result = self.run_builtin(name, args);
+        self.record_call(name, start.elapsed());
+        result
+    }
+
+    /// Warns on the error output the first time `name`, a deprecated alias
+    /// in [`BUILTIN_ALIASES`], is called.
+    fn warn_if_deprecated(&mut self, name: &str) {
+        let Some(alias) = BUILTIN_ALIASES.iter().find(|alias| alias.name == name) else {
+            return;
+        };
+        let Some(deprecation) = alias.deprecation else { return };
+        if self.warned_aliases.insert(alias.name) {
+            let warning = tr!(deprecation, name = alias.name, target = alias.target);
+            self.output.write_error(&format!("{}\n", warning));
+        }
+    }
+
+    /// The builtins in `READ_ONLY_BUILTINS`, which take their arguments by
+    /// reference so a call like `طول(قائمة)` reads the variable in place.
+    fn read_only_builtin(&self, name: &str, args: &[&Value]) -> Result<Value> {
+        match canonical_builtin(name).unwrap_or(name) {
+            "عنصر" => {
+                if args.len() != 2 {
+                    return Err(anyhow!(IqraError {
+                        kind: "عدد وسائط غير صحيح".to_string(),
+                        message_ar: "دالة عنصر تتوقع وسيطين".to_string(),
+                        message_en: "get expects 2 arguments".to_string(),
+                        suggestion: Some(tr!("suggest.list_and_index")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
                 }
-                Ok(Value::Nil)
+                index_ref(args[0], args[1]).cloned()
             }
 
-            // List functions
-            "list" | "قائمة" => Ok(Value::List(args.to_vec())),
+            "يحتوي" => {
+                if args.len() != 2 {
+                    return Err(anyhow!(IqraError {
+                        kind: "عدد وسائط غير صحيح".to_string(),
+                        message_ar: "دالة يحتوي تتوقع وسيطين".to_string(),
+                        message_en: "contains expects 2 arguments".to_string(),
+                        suggestion: Some(tr!("suggest.list_and_search_value")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                }
+                match &args[0] {
+                    Value::List(list) => Ok(Value::Bool(list.contains(args[1]))),
+                    _ => Err(anyhow!(IqraError {
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: "دالة يحتوي تتوقع قائمة كوسيط أول".to_string(),
+                        message_en: "contains expects a list as first argument".to_string(),
+                        suggestion: Some(tr!("suggest.first_is_list")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })),
+                }
+            }
+
+            "جلب_عنصر" => {
+                if args.len() != 2 {
+                    return Err(anyhow!(IqraError {
+                        kind: "عدد وسائط غير صحيح".to_string(),
+                        message_ar: "دالة جلب_عنصر تتوقع وسيطين".to_string(),
+                        message_en: "map_get expects 2 arguments".to_string(),
+                        suggestion: Some(tr!("suggest.dict_and_key")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                }
+                index_ref(args[0], args[1]).cloned()
+            }
+
+            "نوع" => {
+                if args.len() != 1 {
+                    return Err(anyhow!(IqraError {
+                        kind: "عدد وسائط غير صحيح".to_string(),
+                        message_ar: "type تتوقع وسيطاً واحداً".to_string(),
+                        message_en: "type expects 1 argument".to_string(),
+                        suggestion: Some(tr!("suggest.one_value")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                }
+                Ok(Value::String(args[0].type_name().to_string()))
+            }
 
-            "list_len" | "طول_القائمة" => {
+            "طول" => {
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
-                        message_ar: "دالة طول_القائمة تتوقع وسيطاً واحداً".to_string(),
-                        message_en: "list_len expects 1 argument".to_string(),
-                        suggestion: Some("استخدم قائمة واحدة فقط".to_string()),
+                        message_ar: format!("{} تتوقع وسيطاً واحداً", name),
+                        message_en: format!("{} expects 1 argument", name),
+                        suggestion: Some(tr!("suggest.one_text_or_list")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                }
+                // The deprecated names only ever counted lists, and still do.
+                if matches!(name, "طول_القائمة" | "list_len") && !matches!(args[0], Value::List(_)) {
+                    return Err(anyhow!(IqraError {
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: format!("{} تتوقع قائمة", name),
+                        message_en: format!("{} expects a list", name),
+                        suggestion: Some(tr!("suggest.list_only")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match &args[0] {
-                    Value::List(list) => Ok(Value::Number(list.len() as f64)),
+                    Value::String(s) => Ok(Value::Int(s.chars().count() as i64)),
+                    Value::List(l) => Ok(Value::Int(l.len() as i64)),
+                    Value::Set(s) => Ok(Value::Int(s.len() as i64)),
+                    Value::Bytes(b) => Ok(Value::Int(b.len() as i64)),
+                    Value::Map(m) => Ok(Value::Int(m.len() as i64)),
                     _ => Err(anyhow!(IqraError {
                         kind: "نوع وسيط غير صحيح".to_string(),
-                        message_ar: "دالة طول_القائمة تتوقع قائمة".to_string(),
-                        message_en: "list_len expects a list".to_string(),
-                        suggestion: Some("تأكد أن الوسيط هو قائمة".to_string()),
+                        message_ar: format!("{} يتوقع نصاً أو قائمة أو قاموساً", name),
+                        message_en: format!("{} expects a string, list or map", name),
+                        suggestion: Some(tr!("suggest.text_or_list")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            "get" | "عنصر" => {
-                if args.len() != 2 {
+            _ => unreachable!("{} is not a read-only builtin", name),
+        }
+    }
+
+    /// Runs the builtin `name`, by whichever of its names it was called.
+    /// Arms match the Arabic name; messages use `name` as written.
+    fn run_builtin(&mut self, name: &str, args: &[Value]) -> Result<Value> {
+        match canonical_builtin(name).unwrap_or(name) {
+            // Arabic and English print functions
+            "اطبع" => {
+                self.output.write(&format!("{}\n", joined(args, " ")));
+                Ok(Value::Nil)
+            }
+
+            "اطبع_بدون_سطر" => {
+                self.output.write(&joined(args, " "));
+                Ok(Value::Nil)
+            }
+
+            "اطبع_مفصول" => {
+                let Some((first, values)) = args.split_first() else {
+                    return Err(arity_error(name, 1, None, 0));
+                };
+                let Value::String(separator) = first else {
                     return Err(anyhow!(IqraError {
-                        kind: "عدد وسائط غير صحيح".to_string(),
-                        message_ar: "دالة عنصر تتوقع وسيطين".to_string(),
-                        message_en: "get expects 2 arguments".to_string(),
-                        suggestion: Some("استخدم قائمة وفهرس".to_string()),
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: format!("الفاصل في {} يجب أن يكون نصاً، وليس {}", name, first.type_name()),
+                        message_en: format!("The separator for {} must be a string, not {}", name, first.type_name()),
+                        suggestion: Some(tr!("suggest.example_print_sep")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
+                };
+                self.output.write(&format!("{}\n", joined(values, separator)));
+                Ok(Value::Nil)
+            }
+
+            "اطبع_خطأ" => {
+                self.output.write_error(&format!("{}\n", joined(args, " ")));
+                Ok(Value::Nil)
+            }
+
+            "اطبع_ملون" => {
+                if !(2..=3).contains(&args.len()) {
+                    return Err(arity_error(name, 2, Some(3), args.len()));
+                }
+                let (Value::String(color), style) = (&args[1], args.get(2)) else {
+                    return Err(color_error("color", &args[1]));
+                };
+                let mut ansi = match color_style(color) {
+                    Some(ansi) => ansi,
+                    None => return Err(color_error("color", &args[1])),
+                };
+                match style {
+                    None => {}
+                    Some(Value::String(style)) => {
+                        ansi = match style.as_str() {
+                            "عريض" | "bold" => ansi.bold(),
+                            "مائل" | "italic" => ansi.italic(),
+                            "تحته_خط" | "underline" => ansi.underline(),
+                            _ => return Err(color_error("style", &args[2])),
+                        }
+                    }
+                    Some(other) => return Err(color_error("style", other)),
                 }
-                self.evaluate_index(&args[0], &args[1])
+                let text = format!("{}", args[0]);
+                let colored = self.output.is_tty() && self.system_executor.get_env_var("NO_COLOR").is_none();
+                let line = if colored { format!("{}", text.style(ansi)) } else { text };
+                self.output.write(&format!("{}\n", line));
+                Ok(Value::Nil)
             }
 
-            "append" | "أضف" => {
+            // List functions
+            "قائمة" => Ok(Value::List(args.to_vec())),
+
+            "أضف" => {
                 if args.len() != 2 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "دالة أضف تتوقع وسيطين".to_string(),
                         message_en: "append expects 2 arguments".to_string(),
-                        suggestion: Some("استخدم قائمة وقيمة للإضافة".to_string()),
+                        suggestion: Some(tr!("suggest.list_and_new_value")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match &args[0] {
                     Value::List(list) => {
+                        self.check_collection_len(list.len() + 1)?;
                         let mut new_list = list.clone();
                         new_list.push(args[1].clone());
                         Ok(Value::List(new_list))
@@ -652,20 +3489,89 @@ impl Runtime {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "دالة أضف تتوقع قائمة كوسيط أول".to_string(),
                         message_en: "append expects a list as first argument".to_string(),
-                        suggestion: Some("تأكد أن الوسيط الأول هو قائمة".to_string()),
+                        suggestion: Some(tr!("suggest.first_is_list")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })),
+                }
+            }
+
+            // Sets: `مجموعة(١، ٢)` or `مجموعة(قائمة)` to deduplicate a list.
+            "مجموعة" => {
+                let values = match args {
+                    [Value::List(items)] => items.as_slice(),
+                    _ => args,
+                };
+                Ok(Value::Set(ValueSet::from_values(values)?))
+            }
+
+            "أضف_لمجموعة" => {
+                if args.len() != 2 {
+                    return Err(arity_error(name, 2, Some(2), args.len()));
+                }
+                let mut set = set_arg(name, &args[0])?.clone();
+                set.insert(&args[1])?;
+                Ok(Value::Set(set))
+            }
+
+            "في_مجموعة؟" => {
+                if args.len() != 2 {
+                    return Err(arity_error(name, 2, Some(2), args.len()));
+                }
+                Ok(Value::Bool(set_arg(name, &args[0])?.contains(&args[1])))
+            }
+
+            "اتحاد" | "تقاطع" | "فرق" => {
+                if args.len() != 2 {
+                    return Err(arity_error(name, 2, Some(2), args.len()));
+                }
+                let (a, b) = (set_arg(name, &args[0])?, set_arg(name, &args[1])?);
+                Ok(Value::Set(match name {
+                    "union" | "اتحاد" => a.union(b),
+                    "intersection" | "تقاطع" => a.intersection(b),
+                    _ => a.difference(b),
+                }))
+            }
+
+            "إلى_قائمة" => {
+                if args.len() != 1 {
+                    return Err(arity_error(name, 1, Some(1), args.len()));
+                }
+                match &args[0] {
+                    Value::Set(set) => Ok(Value::List(set.iter().cloned().collect())),
+                    Value::List(list) => Ok(Value::List(list.clone())),
+                    other => Err(anyhow!(IqraError {
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: format!("{} تتوقع مجموعة أو قائمة، لكن وصلها {}", name, other.type_name_ar()),
+                        message_en: format!("{} expects a set or list, got {}", name, other.type_name()),
+                        suggestion: None,
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            "remove" | "احذف" => {
+            "خريطة_متوازية" => self.parallel_map(name, args),
+
+            // Removes a user function, so calls reach the builtin it hid again.
+            // Returns whether there was one to remove.
+            "احذف_دالة" => {
+                let [function] = string_args::<1>(name, args)?;
+                Ok(Value::Bool(Symbol::lookup(function).and_then(|symbol| self.functions.remove(&symbol)).is_some()))
+            }
+
+            "احذف" => {
                 if args.len() != 2 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "دالة احذف تتوقع وسيطين".to_string(),
                         message_en: "remove expects 2 arguments".to_string(),
-                        suggestion: Some("استخدم قائمة وقيمة للحذف".to_string()),
+                        suggestion: Some(tr!("suggest.list_and_old_value")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match &args[0] {
@@ -682,43 +3588,25 @@ impl Runtime {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "دالة احذف تتوقع قائمة كوسيط أول".to_string(),
                         message_en: "remove expects a list as first argument".to_string(),
-                        suggestion: Some("تأكد أن الوسيط الأول هو قائمة".to_string()),
-                        line: None,
-                    })),
-                }
-            }
-
-            "contains" | "يحتوي" => {
-                if args.len() != 2 {
-                    return Err(anyhow!(IqraError {
-                        kind: "عدد وسائط غير صحيح".to_string(),
-                        message_ar: "دالة يحتوي تتوقع وسيطين".to_string(),
-                        message_en: "contains expects 2 arguments".to_string(),
-                        suggestion: Some("استخدم قائمة وقيمة للبحث".to_string()),
-                        line: None,
-                    }));
-                }
-                match &args[0] {
-                    Value::List(list) => Ok(Value::Bool(list.contains(&args[1]))),
-                    _ => Err(anyhow!(IqraError {
-                        kind: "نوع وسيط غير صحيح".to_string(),
-                        message_ar: "دالة يحتوي تتوقع قائمة كوسيط أول".to_string(),
-                        message_en: "contains expects a list as first argument".to_string(),
-                        suggestion: Some("تأكد أن الوسيط الأول هو قائمة".to_string()),
+                        suggestion: Some(tr!("suggest.first_is_list")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
             // Map functions
-            "map" | "قاموس" => {
-                if args.len() % 2 != 0 {
+            "قاموس" => {
+                if !args.len().is_multiple_of(2) {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "دالة قاموس تتوقع عدد زوجي من الوسائط".to_string(),
                         message_en: "map expects an even number of arguments".to_string(),
-                        suggestion: Some("استخدم أزواج مفتاح/قيمة".to_string()),
+                        suggestion: Some(tr!("suggest.key_value_pairs")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 let mut map = HashMap::new();
@@ -730,39 +3618,33 @@ impl Runtime {
                             kind: "نوع مفتاح غير صحيح".to_string(),
                             message_ar: "مفاتيح القاموس يجب أن تكون نصوصاً".to_string(),
                             message_en: "map keys must be strings".to_string(),
-                            suggestion: Some("تأكد أن جميع المفاتيح نصوص".to_string()),
+                            suggestion: Some(tr!("suggest.text_keys")),
                             line: None,
+                            column: None,
+                            source_name: None,
                         }));
                     }
                 }
                 Ok(Value::Map(map))
             }
 
-            "map_get" | "جلب_عنصر" => {
-                if args.len() != 2 {
-                    return Err(anyhow!(IqraError {
-                        kind: "عدد وسائط غير صحيح".to_string(),
-                        message_ar: "دالة جلب_عنصر تتوقع وسيطين".to_string(),
-                        message_en: "map_get expects 2 arguments".to_string(),
-                        suggestion: Some("استخدم قاموس ومفتاح".to_string()),
-                        line: None,
-                    }));
-                }
-                self.evaluate_index(&args[0], &args[1])
-            }
-
-            "map_set" | "تعيين_عنصر" => {
+            "تعيين_عنصر" => {
                 if args.len() != 3 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "دالة تعيين_عنصر تتوقع 3 وسائط".to_string(),
                         message_en: "map_set expects 3 arguments".to_string(),
-                        suggestion: Some("استخدم قاموس، مفتاح، وقيمة".to_string()),
+                        suggestion: Some(tr!("suggest.map_key_value")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match (&args[0], &args[1]) {
                     (Value::Map(map), Value::String(key)) => {
+                        if !map.contains_key(key) {
+                            self.check_collection_len(map.len() + 1)?;
+                        }
                         let mut new_map = map.clone();
                         new_map.insert(key.clone(), args[2].clone());
                         Ok(Value::Map(new_map))
@@ -771,20 +3653,24 @@ impl Runtime {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "map_set تتوقع قاموس ومفتاح نصي".to_string(),
                         message_en: "map_set expects a map and string key".to_string(),
-                        suggestion: Some("تأكد أن الوسيط الأول قاموس والثاني نص".to_string()),
+                        suggestion: Some(tr!("suggest.dict_and_text")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            "map_remove" | "حذف_عنصر" => {
+            "حذف_عنصر" => {
                 if args.len() != 2 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "دالة حذف_عنصر تتوقع وسيطين".to_string(),
                         message_en: "map_remove expects 2 arguments".to_string(),
-                        suggestion: Some("استخدم قاموس ومفتاح".to_string()),
+                        suggestion: Some(tr!("suggest.dict_and_key")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match (&args[0], &args[1]) {
@@ -797,227 +3683,665 @@ impl Runtime {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "map_remove تتوقع قاموس ومفتاح نصي".to_string(),
                         message_en: "map_remove expects a map and string key".to_string(),
-                        suggestion: Some("تأكد أن الوسيط الأول قاموس والثاني نص".to_string()),
+                        suggestion: Some(tr!("suggest.dict_and_text")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            // Type and conversion functions
-            "type" | "نوع" => {
+            // [key, value] pairs in sorted key order.
+            "عناصر" => {
+                if args.len() != 1 {
+                    return Err(arity_error(name, 1, Some(1), args.len()));
+                }
+                Ok(Value::List(collections::items(map_arg(name, &args[0])?)))
+            }
+
+            // With a true second argument, also returns the colliding keys:
+            // [inverted, collisions].
+            "اقلب" => {
+                if !(1..=2).contains(&args.len()) {
+                    return Err(arity_error(name, 1, Some(2), args.len()));
+                }
+                let (inverted, collisions) = collections::invert(map_arg(name, &args[0])?);
+                match args.get(1) {
+                    None | Some(Value::Bool(false)) => Ok(Value::Map(inverted)),
+                    Some(Value::Bool(true)) => Ok(Value::List(vec![
+                        Value::Map(inverted),
+                        Value::List(collisions.into_iter().map(Value::String).collect()),
+                    ])),
+                    Some(other) => Err(anyhow!(IqraError {
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: format!("الوسيط الثاني لـ {} قيمة منطقية، لكن وصلها {}", name, other.type_name_ar()),
+                        message_en: format!("The second argument of {} is a boolean, got {}", name, other.type_name()),
+                        suggestion: Some(tr!("suggest.invert_duplicates")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })),
+                }
+            }
+
+            "رقّم" => {
+                if !(1..=2).contains(&args.len()) {
+                    return Err(arity_error(name, 1, Some(2), args.len()));
+                }
+                let list = list_arg(name, &args[0])?;
+                let start = match args.get(1) {
+                    None => 0,
+                    Some(Value::Int(start)) => *start,
+                    Some(other) => {
+                        return Err(anyhow!(IqraError {
+                            kind: "نوع وسيط غير صحيح".to_string(),
+                            message_ar: format!("بداية الترقيم في {} عدد صحيح، لكن وصلها {}", name, other.type_name_ar()),
+                            message_en: format!("The start index of {} is a whole number, got {}", name, other.type_name()),
+                            suggestion: Some(tr!("suggest.example_enumerate")),
+                            line: None,
+                            column: None,
+                            source_name: None,
+                        }));
+                    }
+                };
+                Ok(Value::List(collections::enumerate(list, start)))
+            }
+
+            "نطاق_فهارس" => {
+                if args.len() != 1 {
+                    return Err(arity_error(name, 1, Some(1), args.len()));
+                }
+                Ok(Value::List(collections::indices(list_arg(name, &args[0])?)))
+            }
+
+            "عد_التكرار" => {
                 if args.len() != 1 {
+                    return Err(arity_error(name, 1, Some(1), args.len()));
+                }
+                Ok(Value::Map(collections::count_by(list_arg(name, &args[0])?)))
+            }
+
+            // Groups a list of maps by a field. Grouping by a callback waits
+            // for function values.
+            "جمّع_حسب" => {
+                if args.len() != 2 {
+                    return Err(arity_error(name, 2, Some(2), args.len()));
+                }
+                let list = list_arg(name, &args[0])?;
+                let Value::String(field) = &args[1] else {
                     return Err(anyhow!(IqraError {
-                        kind: "عدد وسائط غير صحيح".to_string(),
-                        message_ar: "type تتوقع وسيطاً واحداً".to_string(),
-                        message_en: "type expects 1 argument".to_string(),
-                        suggestion: Some("استخدم قيمة واحدة فقط".to_string()),
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: format!("الوسيط الثاني لـ {} اسم حقل نصي، لكن وصلها {}", name, args[1].type_name_ar()),
+                        message_en: format!("The second argument of {} is a field name, got {}", name, args[1].type_name()),
+                        suggestion: Some(tr!("suggest.example_group_by")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
+                };
+                collections::group_by(list, field).map(Value::Map).map_err(|index| anyhow!(IqraError {
+                    kind: "حقل غير موجود | Missing Field".to_string(),
+                    message_ar: format!("العنصر {} ليس قاموساً فيه الحقل '{}'", index, field),
+                    message_en: format!("Element {} is not a map with the field '{}'", index, field),
+                    suggestion: Some(tr!("suggest.field_in_each")),
+                    line: None,
+                    column: None,
+                    source_name: None,
+                }))
+            }
+
+            "افرز_قيم" => {
+                if args.len() != 2 {
+                    return Err(arity_error(name, 2, Some(2), args.len()));
+                }
+                let list = list_arg(name, &args[0])?;
+                match &args[1] {
+                    Value::Int(n) if *n >= 0 => Ok(Value::List(collections::tally_top(list, *n as usize))),
+                    _ => Err(anyhow!(IqraError {
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: format!("الوسيط الثاني لـ {} عدد صحيح غير سالب", name),
+                        message_en: format!("The second argument of {} is a non-negative whole count", name),
+                        suggestion: Some(tr!("suggest.example_tally_top")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })),
                 }
-                Ok(Value::String(args[0].type_name().to_string()))
             }
 
-            "to_number" | "إلى_رقم" => {
-                if args.len() != 1 {
+            // Type and conversion functions
+            // Calls recorded so far while profiling: {name: {calls, total_ms, avg_ms}}.
+            "ملف_الأداء" => {
+                if !args.is_empty() {
+                    return Err(arity_error(name, 0, Some(0), args.len()));
+                }
+                let report = self
+                    .profile
+                    .iter()
+                    .map(|(function, entry)| {
+                        let total_ms = entry.total.as_secs_f64() * 1000.0;
+                        let stats = HashMap::from([
+                            ("calls".to_string(), Value::Int(entry.calls as i64)),
+                            ("total_ms".to_string(), Value::Number(total_ms)),
+                            ("avg_ms".to_string(), Value::Number(total_ms / entry.calls as f64)),
+                        ]);
+                        (function.clone(), Value::Map(stats))
+                    })
+                    .collect();
+                Ok(Value::Map(report))
+            }
+
+            // Saves the session to a file and returns the names of variables left out.
+            "احفظ_الحالة" => {
+                self.ensure_not_sandboxed(name)?;
+                let [path] = string_args::<1>(name, args)?;
+                let state = self.session_state();
+                let json = serde_json::to_string_pretty(&state)?;
+                self.system_executor.write_file(path, &json).map_err(|e| anyhow!(IqraError {
+                    kind: "فشل كتابة الملف".to_string(),
+                    message_ar: format!("فشل كتابة الملف: {}", e),
+                    message_en: format!("Failed to write file: {}", e),
+                    suggestion: Some(tr!("suggest.write_access")),
+                    line: None,
+                    column: None,
+                    source_name: None,
+                }))?;
+                Ok(Value::List(state.skipped.into_iter().map(|name| Value::String(name.into())).collect()))
+            }
+
+            "استرجع_الحالة" => {
+                let [path] = string_args::<1>(name, args)?;
+                let json = self.system_executor.read_file(path).map_err(|e| anyhow!(IqraError {
+                    kind: "فشل قراءة الملف".to_string(),
+                    message_ar: format!("فشل قراءة الملف: {}", e),
+                    message_en: format!("Failed to read file: {}", e),
+                    suggestion: Some(tr!("suggest.file_exists")),
+                    line: None,
+                    column: None,
+                    source_name: None,
+                }))?;
+                self.load_state(&json)?;
+                Ok(Value::Nil)
+            }
+
+            // With no arguments, lists every builtin by category. With a name,
+            // returns the `///` doc comment of a user function (nil when it has
+            // none) or the documentation map of a builtin.
+            "مساعدة" => {
+                if args.is_empty() {
+                    return Ok(builtins::help_index());
+                }
+                let [function_name] = string_args::<1>(name, args)?;
+                if let Some(function) = Symbol::lookup(function_name).and_then(|symbol| self.functions.get(&symbol)) {
+                    return Ok(function.doc.clone().map(Value::String).unwrap_or(Value::Nil));
+                }
+                if let Some(doc) = builtins::builtin_doc(function_name) {
+                    return Ok(doc.to_value());
+                }
+                let candidates = BUILTIN_FUNCTIONS.iter().copied().chain(self.functions.keys().map(Symbol::as_str));
+                Err(anyhow!(IqraError {
+                    kind: "دالة غير معرفة".to_string(),
+                    message_ar: format!("الدالة غير معرفة: {}", function_name),
+                    message_en: format!("Undefined function: {}", function_name),
+                    suggestion: Some(analyzer::closest_name(function_name, candidates).map_or_else(
+                        || tr!("suggest.help_lists_builtins"),
+                        |close| analyzer::did_you_mean(&close),
+                    )),
+                    line: None,
+                    column: None,
+                    source_name: None,
+                }))
+            }
+
+            "تذكر" => {
+                self.ensure_not_sandboxed(name)?;
+                let [key] = string_args::<1>(name, args)?;
+                let (_, cache) = self.load_cache()?;
+                Ok(cache.get(key, self.clock.now().timestamp()).cloned().unwrap_or(Value::Nil))
+            }
+
+            "خزّن" => {
+                self.ensure_not_sandboxed(name)?;
+                if !(2..=3).contains(&args.len()) {
+                    return Err(arity_error(name, 2, Some(3), args.len()));
+                }
+                let Value::String(key) = &args[0] else {
                     return Err(anyhow!(IqraError {
-                        kind: "عدد وسائط غير صحيح".to_string(),
-                        message_ar: "إلى_رقم تتوقع وسيطاً واحداً".to_string(),
-                        message_en: "to_number expects 1 argument".to_string(),
-                        suggestion: Some("استخدم قيمة واحدة فقط".to_string()),
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: format!("المفتاح في {} يجب أن يكون نصاً، وليس {}", name, args[0].type_name()),
+                        message_en: format!("The key for {} must be a string, not {}", name, args[0].type_name()),
+                        suggestion: Some(tr!("suggest.example_cache_set")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
+                };
+                let now = self.clock.now().timestamp();
+                let expires_at = match args.get(2) {
+                    None | Some(Value::Nil) => None,
+                    Some(ttl) => match ttl.as_number() {
+                        Some(seconds) if seconds > 0.0 => Some(now.saturating_add(seconds.ceil() as i64)),
+                        _ => return Err(anyhow!(IqraError {
+                            kind: "قيمة غير صالحة".to_string(),
+                            message_ar: format!("مدة الصلاحية في {} يجب أن تكون عدد ثوانٍ موجباً", name),
+                            message_en: format!("The time to live for {} must be a positive number of seconds", name),
+                            suggestion: Some(tr!("suggest.example_cache_ttl")),
+                            line: None,
+                            column: None,
+                            source_name: None,
+                        })),
+                    },
+                };
+                let (path, mut cache) = self.load_cache()?;
+                cache.remove_expired(now);
+                cache.set(key, args[1].clone(), expires_at);
+                self.save_cache(&path, &cache)?;
+                Ok(Value::Nil)
+            }
+
+            "امسح_الذاكرة" => {
+                self.ensure_not_sandboxed(name)?;
+                if !args.is_empty() {
+                    return Err(arity_error(name, 0, Some(0), args.len()));
                 }
-                match &args[0] {
-                    Value::Number(n) => Ok(Value::Number(*n)),
-                    Value::String(s) => {
-                        let ascii_str = s
-                            .chars()
-                            .map(|ch| match ch {
-                                '٠' => '0',
-                                '١' => '1',
-                                '٢' => '2',
-                                '٣' => '3',
-                                '٤' => '4',
-                                '٥' => '5',
-                                '٦' => '6',
-                                '٧' => '7',
-                                '٨' => '8',
-                                '٩' => '9',
-                                _ => ch,
-                            })
-                            .collect::<String>();
-
-                        ascii_str
-                            .parse::<f64>()
-                            .map(Value::Number)
-                            .map_err(|_| anyhow!(IqraError {
-                                kind: "تحويل غير صالح".to_string(),
-                                message_ar: format!("لا يمكن تحويل '{}' إلى رقم", s),
-                                message_en: format!("Cannot convert '{}' to number", s),
-                                suggestion: Some("تأكد أن النص يمثل رقماً صحيحاً".to_string()),
-                                line: None,
-                            }))
+                let (path, cache) = self.load_cache()?;
+                if !cache.is_empty() {
+                    self.save_cache(&path, &ScriptCache::default())?;
+                }
+                Ok(Value::Nil)
+            }
+
+            // Names in the language of the call, without aliases.
+            "قائمة_الدوال" => {
+                if !args.is_empty() {
+                    return Err(arity_error(name, 0, Some(0), args.len()));
+                }
+                let english = usize::from(canonical_builtin(name) != Some(name));
+                Ok(Value::List(
+                    BUILTIN_FUNCTIONS
+                        .iter()
+                        .skip(english)
+                        .step_by(2)
+                        .map(|name| Value::String(name.to_string()))
+                        .collect(),
+                ))
+            }
+
+            "المتغيرات_العامة" | "المتغيرات_المحلية" => {
+                if !args.is_empty() {
+                    return Err(arity_error(name, 0, Some(0), args.len()));
+                }
+                let level = match canonical_builtin(name) {
+                    Some("المتغيرات_العامة") => 0,
+                    _ => self.call_frames.len(),
+                };
+                Ok(Value::Map(self.scope(level)))
+            }
+
+            "إلى_رقم" => {
+                if !(1..=2).contains(&args.len()) {
+                    return Err(arity_error(name, 1, Some(2), args.len()));
+                }
+                let strict = match args.get(1) {
+                    None => false,
+                    Some(Value::Bool(strict)) => *strict,
+                    Some(_) => {
+                        return Err(anyhow!(IqraError {
+                            kind: "نوع وسيط غير صحيح".to_string(),
+                            message_ar: "الوسيط الثاني لـ إلى_رقم (صارم) يجب أن يكون صحيح أو خطأ".to_string(),
+                            message_en: "The second argument of to_number (strict) must be true or false".to_string(),
+                            suggestion: Some(tr!("suggest.example_strict_number")),
+                            line: None,
+                            column: None,
+                            source_name: None,
+                        }));
                     }
+                };
+                match &args[0] {
+                    Value::Number(_) | Value::Int(_) => Ok(args[0].clone()),
+                    Value::String(s) => parse_number_text(s, strict).map_err(|bad| {
+                        let (detail_ar, detail_en) = match bad {
+                            Some(ch) => (format!(": الحرف '{}' غير متوقع", ch), format!(": unexpected character '{}'", ch)),
+                            None => (String::new(), String::new()),
+                        };
+                        anyhow!(IqraError {
+                            kind: "تحويل غير صالح".to_string(),
+                            message_ar: format!("لا يمكن تحويل '{}' إلى رقم{}", s, detail_ar),
+                            message_en: format!("Cannot convert '{}' to number{}", s, detail_en),
+                            suggestion: Some(tr!("suggest.valid_number_text")),
+                            line: None,
+                            column: None,
+                            source_name: None,
+                        })
+                    }),
                     _ => Err(anyhow!(IqraError {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "لا يمكن تحويل القيمة إلى رقم".to_string(),
                         message_en: "Cannot convert to number".to_string(),
-                        suggestion: Some("استخدم نصاً أو رقماً فقط".to_string()),
+                        suggestion: Some(tr!("suggest.text_or_number")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            "to_string" | "إلى_نص" => {
+            "إلى_نص" => {
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "إلى_نص تتوقع وسيطاً واحداً".to_string(),
                         message_en: "to_string expects 1 argument".to_string(),
-                        suggestion: Some("استخدم قيمة واحدة فقط".to_string()),
+                        suggestion: Some(tr!("suggest.one_value")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 Ok(Value::String(format!("{}", args[0])))
             }
 
-            "is_number" | "رقم؟" => {
+            "رقم؟" => {
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "رقم؟ تتوقع وسيطاً واحداً".to_string(),
                         message_en: "is_number expects 1 argument".to_string(),
-                        suggestion: Some("استخدم قيمة واحدة فقط".to_string()),
+                        suggestion: Some(tr!("suggest.one_value")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
-                Ok(Value::Bool(matches!(args[0], Value::Number(_))))
+                Ok(Value::Bool(matches!(args[0], Value::Number(_) | Value::Int(_))))
             }
 
-            "is_string" | "نص؟" => {
+            "نص؟" => {
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "نص؟ تتوقع وسيطاً واحداً".to_string(),
                         message_en: "is_string expects 1 argument".to_string(),
-                        suggestion: Some("استخدم قيمة واحدة فقط".to_string()),
+                        suggestion: Some(tr!("suggest.one_value")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 Ok(Value::Bool(matches!(args[0], Value::String(_))))
             }
 
-            "len" | "طول" => {
+            "منطقي؟" => {
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
-                        message_ar: "طول تتوقع وسيطاً واحداً".to_string(),
-                        message_en: "len expects 1 argument".to_string(),
-                        suggestion: Some("استخدم نصاً أو قائمة واحدة فقط".to_string()),
+                        message_ar: "منطقي؟ تتوقع وسيطاً واحداً".to_string(),
+                        message_en: "is_bool expects 1 argument".to_string(),
+                        suggestion: Some(tr!("suggest.one_value")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
-                match &args[0] {
-                    Value::String(s) => Ok(Value::Number(s.chars().count() as f64)),
-                    Value::List(l) => Ok(Value::Number(l.len() as f64)),
-                    _ => Err(anyhow!(IqraError {
-                        kind: "نوع وسيط غير صحيح".to_string(),
-                        message_ar: "طول يتوقع نصاً أو قائمة".to_string(),
-                        message_en: "len expects a string or list".to_string(),
-                        suggestion: Some("استخدم نصاً أو قائمة فقط".to_string()),
+                Ok(Value::Bool(matches!(args[0], Value::Bool(_))))
+            }
+
+            "إلى_منطقي" => {
+                if args.len() != 1 {
+                    return Err(anyhow!(IqraError {
+                        kind: "عدد وسائط غير صحيح".to_string(),
+                        message_ar: "إلى_منطقي تتوقع وسيطاً واحداً".to_string(),
+                        message_en: "to_bool expects 1 argument".to_string(),
+                        suggestion: Some(tr!("suggest.one_value")),
                         line: None,
-                    })),
+                        column: None,
+                        source_name: None,
+                    }));
                 }
+                Ok(Value::Bool(args[0].is_truthy()))
             }
 
             // Math functions
-            "sum" | "جمع" => {
+            "جمع" => {
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "جمع تتوقع وسيطاً واحداً".to_string(),
                         message_en: "sum expects 1 argument".to_string(),
-                        suggestion: Some("استخدم قائمة واحدة فقط".to_string()),
+                        suggestion: Some(tr!("suggest.one_list")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match &args[0] {
                     Value::List(list) => {
-                        let mut total = 0.0;
+                        let mut total = Value::Int(0);
                         for item in list {
-                            if let Value::Number(n) = item {
-                                total += n;
+                            if item.as_number().is_some() {
+                                total = numeric_op(&total, item, i64::checked_add, |a, b| a + b).unwrap()?;
                             } else {
                                 return Err(anyhow!(IqraError {
                                     kind: "نوع عنصر غير صحيح".to_string(),
                                     message_ar: "جمع يتوقع قائمة أرقام فقط".to_string(),
                                     message_en: "sum expects a list of numbers".to_string(),
-                                    suggestion: Some("تأكد أن جميع العناصر أرقام".to_string()),
+                                    suggestion: Some(tr!("suggest.all_numbers")),
                                     line: None,
+                                    column: None,
+                                    source_name: None,
                                 }));
                             }
                         }
-                        Ok(Value::Number(total))
+                        Ok(total)
                     }
                     _ => Err(anyhow!(IqraError {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "جمع يتوقع قائمة".to_string(),
                         message_en: "sum expects a list".to_string(),
-                        suggestion: Some("استخدم قائمة فقط".to_string()),
+                        suggestion: Some(tr!("suggest.list_only")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })),
+                }
+            }
+
+            "متوسط" => {
+                if args.len() != 1 {
+                    return Err(anyhow!(IqraError {
+                        kind: "عدد وسائط غير صحيح".to_string(),
+                        message_ar: "متوسط يتوقع وسيطاً واحداً".to_string(),
+                        message_en: "average expects 1 argument".to_string(),
+                        suggestion: Some(tr!("suggest.one_list")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                }
+                match &args[0] {
+                    Value::List(list) => {
+                        if list.is_empty() {
+                            return Ok(Value::Number(0.0));
+                        }
+                        let mut total = 0.0;
+                        for item in list {
+                            if let Some(n) = item.as_number() {
+                                total += n;
+                            } else {
+                                return Err(anyhow!(IqraError {
+                                    kind: "نوع عنصر غير صحيح".to_string(),
+                                    message_ar: "متوسط يتوقع قائمة أرقام فقط".to_string(),
+                                    message_en: "average expects a list of numbers".to_string(),
+                                    suggestion: Some(tr!("suggest.all_numbers")),
+                                    line: None,
+                                    column: None,
+                                    source_name: None,
+                                }));
+                            }
+                        }
+                        Ok(Value::Number(total / list.len() as f64))
+                    }
+                    _ => Err(anyhow!(IqraError {
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: "متوسط يتوقع قائمة".to_string(),
+                        message_en: "average expects a list".to_string(),
+                        suggestion: Some(tr!("suggest.list_only")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            "average" | "متوسط" => {
+            // An empty list has no median, so it is an error like max and min.
+            "وسيط" => {
+                if args.len() != 1 {
+                    return Err(arity_error(name, 1, Some(1), args.len()));
+                }
+                let median = stats::median(&numbers_arg(name, &args[0])?);
+                median.map(Value::Number).ok_or_else(|| too_few_values_error(name, 1))
+            }
+
+            // The most frequent element, or a list of them on a tie. An empty
+            // list has no most frequent element and gives an empty list.
+            "منوال" => {
+                if args.len() != 1 {
+                    return Err(arity_error(name, 1, Some(1), args.len()));
+                }
+                numbers_arg(name, &args[0])?;
+                match stats::modes(list_arg(name, &args[0])?).as_slice() {
+                    [single] => Ok((*single).clone()),
+                    modes => Ok(Value::List(modes.iter().map(|value| (*value).clone()).collect())),
+                }
+            }
+
+            // Population statistics by default; a true second argument
+            // divides by n - 1 for a sample.
+            "تباين" | "انحراف_معياري" => {
+                if !(1..=2).contains(&args.len()) {
+                    return Err(arity_error(name, 1, Some(2), args.len()));
+                }
+                let values = numbers_arg(name, &args[0])?;
+                let sample = match args.get(1) {
+                    None => false,
+                    Some(Value::Bool(sample)) => *sample,
+                    Some(other) => {
+                        return Err(anyhow!(IqraError {
+                            kind: "نوع وسيط غير صحيح".to_string(),
+                            message_ar: format!("الوسيط الثاني لـ {} قيمة منطقية، لكن وصلها {}", name, other.type_name_ar()),
+                            message_en: format!("The second argument of {} is a boolean, got {}", name, other.type_name()),
+                            suggestion: Some(tr!("suggest.sample_or_population")),
+                            line: None,
+                            column: None,
+                            source_name: None,
+                        }));
+                    }
+                };
+                let result = match name {
+                    "variance" | "تباين" => stats::variance(&values, sample),
+                    _ => stats::stddev(&values, sample),
+                };
+                result.map(Value::Number).ok_or_else(|| too_few_values_error(name, 1 + usize::from(sample)))
+            }
+
+            "مئين" => {
+                if args.len() != 2 {
+                    return Err(arity_error(name, 2, Some(2), args.len()));
+                }
+                let values = numbers_arg(name, &args[0])?;
+                let p = match args[1].as_number() {
+                    Some(p) if (0.0..=100.0).contains(&p) => p,
+                    _ => {
+                        return Err(anyhow!(IqraError {
+                            kind: "نوع وسيط غير صحيح".to_string(),
+                            message_ar: format!("الوسيط الثاني لـ {} رقم بين ٠ و١٠٠", name),
+                            message_en: format!("The second argument of {} is a number from 0 to 100", name),
+                            suggestion: Some(tr!("suggest.example_percentile")),
+                            line: None,
+                            column: None,
+                            source_name: None,
+                        }));
+                    }
+                };
+                let percentile = stats::percentile(&values, p);
+                percentile.map(Value::Number).ok_or_else(|| too_few_values_error(name, 1))
+            }
+
+            "انقل_مصفوفة" => {
                 if args.len() != 1 {
+                    return Err(arity_error(name, 1, Some(1), args.len()));
+                }
+                Ok(Matrix::parse(&args[0]).map_err(|err| matrix_error(name, err))?.transpose())
+            }
+
+            "أعد_تشكيل" => {
+                if args.len() != 3 {
+                    return Err(arity_error(name, 3, Some(3), args.len()));
+                }
+                let (Value::Int(rows @ 0..), Value::Int(cols @ 0..)) = (&args[1], &args[2]) else {
                     return Err(anyhow!(IqraError {
-                        kind: "عدد وسائط غير صحيح".to_string(),
-                        message_ar: "متوسط يتوقع وسيطاً واحداً".to_string(),
-                        message_en: "average expects 1 argument".to_string(),
-                        suggestion: Some("استخدم قائمة واحدة فقط".to_string()),
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: format!("{} تتوقع عدد الصفوف والأعمدة أعداداً صحيحة غير سالبة", name),
+                        message_en: format!("{} expects non-negative whole numbers of rows and columns", name),
+                        suggestion: Some(tr!("suggest.example_reshape")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
+                };
+                matrix::reshape(&args[0], *rows as usize, *cols as usize).map_err(|err| matrix_error(name, err))
+            }
+
+            "اجمع_مصفوفات" => {
+                if args.len() != 2 {
+                    return Err(arity_error(name, 2, Some(2), args.len()));
                 }
-                match &args[0] {
-                    Value::List(list) => {
-                        if list.is_empty() {
-                            return Ok(Value::Number(0.0));
-                        }
-                        let mut total = 0.0;
-                        for item in list {
-                            if let Value::Number(n) = item {
-                                total += n;
-                            } else {
-                                return Err(anyhow!(IqraError {
-                                    kind: "نوع عنصر غير صحيح".to_string(),
-                                    message_ar: "متوسط يتوقع قائمة أرقام فقط".to_string(),
-                                    message_en: "average expects a list of numbers".to_string(),
-                                    suggestion: Some("تأكد أن جميع العناصر أرقام".to_string()),
-                                    line: None,
-                                }));
-                            }
-                        }
-                        Ok(Value::Number(total / list.len() as f64))
-                    }
-                    _ => Err(anyhow!(IqraError {
-                        kind: "نوع وسيط غير صحيح".to_string(),
-                        message_ar: "متوسط يتوقع قائمة".to_string(),
-                        message_en: "average expects a list".to_string(),
-                        suggestion: Some("استخدم قائمة فقط".to_string()),
-                        line: None,
-                    })),
+                let (left, right) = (numeric_matrix(name, &args[0])?, numeric_matrix(name, &args[1])?);
+                if left.shape() != right.shape() {
+                    let err = MatrixError::ShapeMismatch { left: left.shape(), right: right.shape() };
+                    return Err(matrix_error(name, err));
+                }
+                let rows = (0..left.rows).map(|row| {
+                    let cells = (0..left.cols).map(|col| {
+                        numeric_op(left.get(row, col), right.get(row, col), i64::checked_add, |a, b| a + b).unwrap()
+                    });
+                    cells.collect::<Result<Vec<_>>>().map(Value::List)
+                });
+                Ok(Value::List(rows.collect::<Result<_>>()?))
+            }
+
+            "اضرب_مصفوفات" => {
+                if args.len() != 2 {
+                    return Err(arity_error(name, 2, Some(2), args.len()));
+                }
+                let (left, right) = (numeric_matrix(name, &args[0])?, numeric_matrix(name, &args[1])?);
+                if left.cols != right.rows {
+                    let err = MatrixError::InnerMismatch { left: left.shape(), right: right.shape() };
+                    return Err(matrix_error(name, err));
                 }
+                let rows = (0..left.rows).map(|row| {
+                    let cells = (0..right.cols).map(|col| {
+                        (0..left.cols).try_fold(Value::Int(0), |total, k| {
+                            let product =
+                                numeric_op(left.get(row, k), right.get(k, col), i64::checked_mul, |a, b| a * b).unwrap()?;
+                            numeric_op(&total, &product, i64::checked_add, |a, b| a + b).unwrap()
+                        })
+                    });
+                    cells.collect::<Result<Vec<_>>>().map(Value::List)
+                });
+                Ok(Value::List(rows.collect::<Result<_>>()?))
             }
 
-            "max" | "أكبر" => {
+            "أكبر" => {
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "أكبر تتوقع وسيطاً واحداً".to_string(),
                         message_en: "max expects 1 argument".to_string(),
-                        suggestion: Some("استخدم قائمة واحدة فقط".to_string()),
+                        suggestion: Some(tr!("suggest.one_list")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match &args[0] {
@@ -1027,46 +4351,54 @@ impl Runtime {
                                 kind: "قائمة فارغة".to_string(),
                                 message_ar: "أكبر تتوقع قائمة غير فارغة".to_string(),
                                 message_en: "max expects a non-empty list".to_string(),
-                                suggestion: Some("استخدم قائمة فيها عناصر".to_string()),
+                                suggestion: Some(tr!("suggest.non_empty_list")),
                                 line: None,
+                                column: None,
+                                source_name: None,
                             }));
                         }
-                        let mut max_val = f64::NEG_INFINITY;
+                        let mut max_val = &list[0];
                         for item in list {
-                            if let Value::Number(n) = item {
-                                if *n > max_val {
-                                    max_val = *n;
+                            if let Some(ordering) = item.compare_numbers(max_val) {
+                                if ordering.is_gt() {
+                                    max_val = item;
                                 }
                             } else {
                                 return Err(anyhow!(IqraError {
                                     kind: "نوع عنصر غير صحيح".to_string(),
                                     message_ar: "أكبر يتوقع قائمة أرقام فقط".to_string(),
                                     message_en: "max expects a list of numbers".to_string(),
-                                    suggestion: Some("تأكد أن جميع العناصر أرقام".to_string()),
+                                    suggestion: Some(tr!("suggest.all_numbers")),
                                     line: None,
+                                    column: None,
+                                    source_name: None,
                                 }));
                             }
                         }
-                        Ok(Value::Number(max_val))
+                        Ok(max_val.clone())
                     }
                     _ => Err(anyhow!(IqraError {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "أكبر يتوقع قائمة".to_string(),
                         message_en: "max expects a list".to_string(),
-                        suggestion: Some("استخدم قائمة فقط".to_string()),
+                        suggestion: Some(tr!("suggest.list_only")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            "min" | "أصغر" => {
+            "أصغر" => {
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "أصغر تتوقع وسيطاً واحداً".to_string(),
                         message_en: "min expects 1 argument".to_string(),
-                        suggestion: Some("استخدم قائمة واحدة فقط".to_string()),
+                        suggestion: Some(tr!("suggest.one_list")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match &args[0] {
@@ -1076,72 +4408,278 @@ impl Runtime {
                                 kind: "قائمة فارغة".to_string(),
                                 message_ar: "أصغر تتوقع قائمة غير فارغة".to_string(),
                                 message_en: "min expects a non-empty list".to_string(),
-                                suggestion: Some("استخدم قائمة فيها عناصر".to_string()),
+                                suggestion: Some(tr!("suggest.non_empty_list")),
                                 line: None,
+                                column: None,
+                                source_name: None,
                             }));
                         }
-                        let mut min_val = f64::INFINITY;
+                        let mut min_val = &list[0];
                         for item in list {
-                            if let Value::Number(n) = item {
-                                if *n < min_val {
-                                    min_val = *n;
+                            if let Some(ordering) = item.compare_numbers(min_val) {
+                                if ordering.is_lt() {
+                                    min_val = item;
                                 }
                             } else {
                                 return Err(anyhow!(IqraError {
                                     kind: "نوع عنصر غير صحيح".to_string(),
                                     message_ar: "أصغر يتوقع قائمة أرقام فقط".to_string(),
                                     message_en: "min expects a list of numbers".to_string(),
-                                    suggestion: Some("تأكد أن جميع العناصر أرقام".to_string()),
+                                    suggestion: Some(tr!("suggest.all_numbers")),
                                     line: None,
+                                    column: None,
+                                    source_name: None,
                                 }));
                             }
                         }
-                        Ok(Value::Number(min_val))
+                        Ok(min_val.clone())
                     }
                     _ => Err(anyhow!(IqraError {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "أصغر يتوقع قائمة".to_string(),
                         message_en: "min expects a list".to_string(),
-                        suggestion: Some("استخدم قائمة فقط".to_string()),
+                        suggestion: Some(tr!("suggest.list_only")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })),
+                }
+            }
+
+            "بين؟" => {
+                let [value, low, high] = args else {
+                    return Err(arity_error(name, 3, Some(3), args.len()));
+                };
+                match (value.compare_numbers(low), value.compare_numbers(high)) {
+                    (Some(from_low), Some(from_high)) => Ok(Value::Bool(from_low.is_ge() && from_high.is_le())),
+                    _ => Err(anyhow!(IqraError {
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: "بين؟ تتوقع ثلاثة أرقام".to_string(),
+                        message_en: "between expects three numbers".to_string(),
+                        suggestion: Some(tr!("suggest.numbers_only")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
             // String functions
-            "word_count" | "عدد_الكلمات" => {
+            "عدد_الكلمات" => {
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "عدد_الكلمات تتوقع وسيطاً واحداً".to_string(),
                         message_en: "word_count expects 1 argument".to_string(),
-                        suggestion: Some("استخدم نصاً واحداً فقط".to_string()),
+                        suggestion: Some(tr!("suggest.one_text")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match &args[0] {
                     Value::String(s) => {
                         let count = s.split_whitespace().count();
-                        Ok(Value::Number(count as f64))
+                        Ok(Value::Int(count as i64))
                     }
                     _ => Err(anyhow!(IqraError {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "عدد_الكلمات تتوقع نصاً".to_string(),
                         message_en: "word_count expects a string".to_string(),
-                        suggestion: Some("استخدم نصاً فقط".to_string()),
+                        suggestion: Some(tr!("suggest.text_only")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })),
+                }
+            }
+
+            "نسق_رقم" => {
+                if !(2..=3).contains(&args.len()) {
+                    return Err(arity_error(name, 2, Some(3), args.len()));
+                }
+                let decimals = match &args[1] {
+                    Value::Int(d) if *d >= 0 => *d as usize,
+                    _ => {
+                        return Err(anyhow!(IqraError {
+                            kind: "نوع وسيط غير صحيح".to_string(),
+                            message_ar: "نسق_رقم تتوقع عدد خانات عشرية صحيحاً غير سالب".to_string(),
+                            message_en: "format_number expects a non-negative whole number of decimals".to_string(),
+                            suggestion: Some(tr!("suggest.example_format_number")),
+                            line: None,
+                            column: None,
+                            source_name: None,
+                        }));
+                    }
+                };
+                let separator = match args.get(2) {
+                    None => None,
+                    Some(Value::String(sep)) => Some(sep.as_str()),
+                    Some(_) => {
+                        return Err(anyhow!(IqraError {
+                            kind: "نوع وسيط غير صحيح".to_string(),
+                            message_ar: "فاصل الآلاف في نسق_رقم يجب أن يكون نصاً".to_string(),
+                            message_en: "format_number expects the thousands separator to be a string".to_string(),
+                            suggestion: Some(tr!("suggest.separator_text")),
+                            line: None,
+                            column: None,
+                            source_name: None,
+                        }));
+                    }
+                };
+                format_number(&args[0], decimals, separator).map(Value::String).ok_or_else(|| {
+                    anyhow!(IqraError {
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: "نسق_رقم تتوقع رقماً".to_string(),
+                        message_en: "format_number expects a number".to_string(),
+                        suggestion: Some(tr!("suggest.number_only")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })
+                })
+            }
+
+            "نسق" => {
+                let Some((template, values)) = args.split_first() else {
+                    return Err(arity_error(name, 1, None, 0));
+                };
+                let Value::String(template) = template else {
+                    return Err(anyhow!(IqraError {
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: format!("{} تتوقع نص القالب أولاً", name),
+                        message_en: format!("{} expects a template string first", name),
+                        suggestion: Some(tr!("suggest.example_format")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                };
+                format::render(template, values).map(Value::String).map_err(|err| format_error(name, err))
+            }
+
+            "كرر_نص" => {
+                if args.len() != 2 {
+                    return Err(arity_error(name, 2, Some(2), args.len()));
+                }
+                match (&args[0], &args[1]) {
+                    (Value::String(text), Value::Int(count)) if *count >= 0 => {
+                        self.check_collection_bytes(text.len().saturating_mul(*count as usize))?;
+                        Ok(Value::String(text.repeat(*count as usize)))
+                    }
+                    _ => Err(anyhow!(IqraError {
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: format!("{} تتوقع نصاً وعدداً صحيحاً غير سالب", name),
+                        message_en: format!("{} expects a string and a non-negative whole count", name),
+                        suggestion: Some(tr!("suggest.example_repeat")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })),
+                }
+            }
+
+            "احشِ_يمين" | "احشِ_يسار" | "وسّط" => {
+                // Every column of the result takes at least a byte.
+                if let Some(Value::Int(width)) = args.get(1) {
+                    self.check_collection_bytes(usize::try_from(*width).unwrap_or(0))?;
+                }
+                pad_text(name, args)
+            }
+
+            "أزل_التشكيل" => {
+                let [text] = string_args::<1>(name, args)?;
+                Ok(Value::String(arabic::strip_diacritics(text)))
+            }
+
+            "طبع_نص" => {
+                if !(1..=2).contains(&args.len()) {
+                    return Err(arity_error(name, 1, Some(2), args.len()));
+                }
+                let Value::String(text) = &args[0] else {
+                    return Err(anyhow!(IqraError {
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: format!("{} تتوقع نصاً، لكن وصلها {}", name, args[0].type_name_ar()),
+                        message_en: format!("{} expects a string, got {}", name, args[0].type_name()),
+                        suggestion: None,
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                };
+                let options = normalize_options(name, args.get(1))?;
+                Ok(Value::String(arabic::normalize_arabic(text, &options)))
+            }
+
+            "وحد_الأرقام" => {
+                let [text, script] = string_args::<2>(name, args)?;
+                let script = match script {
+                    "arabic" | "عربي" => DigitScript::Arabic,
+                    "ascii" | "لاتيني" => DigitScript::Ascii,
+                    other => {
+                        return Err(anyhow!(IqraError {
+                            kind: "خيار غير صالح | Invalid Option".to_string(),
+                            message_ar: format!("نظام أرقام غير معروف: {}", other),
+                            message_en: format!("Unknown digit set '{}'", other),
+                            suggestion: Some(tr!("suggest.digit_sets")),
+                            line: None,
+                            column: None,
+                            source_name: None,
+                        }));
+                    }
+                };
+                Ok(Value::String(arabic::normalize_digits(text, script)))
+            }
+
+            "رمز_حرف" => {
+                let [text] = string_args::<1>(name, args)?;
+                match text.chars().next() {
+                    Some(ch) => Ok(Value::Int(ch as i64)),
+                    None => Err(anyhow!(IqraError {
+                        kind: "نص فارغ | Empty String".to_string(),
+                        message_ar: format!("{} تتوقع نصاً فيه حرف واحد على الأقل", name),
+                        message_en: format!("{} expects a non-empty string", name),
+                        suggestion: None,
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            "reverse" | "عكس" => {
+            // Rejects surrogates (U+D800..U+DFFF) and values above U+10FFFF.
+            "حرف_من_رمز" => {
+                if args.len() != 1 {
+                    return Err(arity_error(name, 1, Some(1), args.len()));
+                }
+                let ch = args[0]
+                    .as_int()
+                    .and_then(|code| u32::try_from(code).ok())
+                    .and_then(char::from_u32);
+                match ch {
+                    Some(ch) => Ok(Value::String(ch.to_string())),
+                    None => Err(anyhow!(IqraError {
+                        kind: "رمز غير صالح | Invalid Code Point".to_string(),
+                        message_ar: format!("{} ليس رمز يونيكود صالحاً", args[0]),
+                        message_en: format!("{} is not a valid Unicode code point", args[0]),
+                        suggestion: Some(tr!("suggest.code_point")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })),
+                }
+            }
+
+            "عكس" => {
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "عكس تتوقع وسيطاً واحداً".to_string(),
                         message_en: "reverse expects 1 argument".to_string(),
-                        suggestion: Some("استخدم نصاً أو قائمة واحدة فقط".to_string()),
+                        suggestion: Some(tr!("suggest.one_text_or_list")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match &args[0] {
@@ -1158,33 +4696,196 @@ impl Runtime {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "عكس يتوقع نصاً أو قائمة".to_string(),
                         message_en: "reverse expects a string or list".to_string(),
-                        suggestion: Some("استخدم نصاً أو قائمة فقط".to_string()),
+                        suggestion: Some(tr!("suggest.text_or_list")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            // Date functions
-            "today" | "تاريخ_اليوم" => {
-                if let Some(ref cached) = self.today_cache {
-                    Ok(Value::String(cached.clone()))
+            "تطابق؟" => {
+                let [text, pattern] = string_args::<2>(name, args)?;
+                Ok(Value::Bool(self.compiled_regex(pattern)?.is_match(text)))
+            }
+
+            "ابحث_نمط" => {
+                let [text, pattern] = string_args::<2>(name, args)?;
+                let regex = self.compiled_regex(pattern)?;
+                let text_of = |m: Option<regex::Match>| m.map_or(Value::Nil, |m| Value::String(m.as_str().to_string()));
+                let matches = if regex.captures_len() > 1 {
+                    regex
+                        .captures_iter(text)
+                        .map(|caps| Value::List(caps.iter().map(text_of).collect()))
+                        .collect()
                 } else {
-                    use chrono::Local;
-                    let today = Local::now().format("%Y-%m-%d").to_string();
-                    self.today_cache = Some(today.clone());
-                    Ok(Value::String(today))
+                    regex.find_iter(text).map(|m| text_of(Some(m))).collect()
+                };
+                Ok(Value::List(matches))
+            }
+
+            "استبدل_نمط" => {
+                let [text, pattern, replacement] = string_args::<3>(name, args)?;
+                let regex = self.compiled_regex(pattern)?;
+                Ok(Value::String(regex.replace_all(text, replacement).into_owned()))
+            }
+
+            "ترميز٦٤" => {
+                let [text] = string_args::<1>(name, args)?;
+                Ok(Value::String(BASE64.encode(text)))
+            }
+
+            "فك٦٤" => {
+                let [encoded] = string_args::<1>(name, args)?;
+                BASE64
+                    .decode(encoded.trim())
+                    .ok()
+                    .and_then(|bytes| String::from_utf8(bytes).ok())
+                    .map(Value::String)
+                    .ok_or_else(|| anyhow!(IqraError {
+                        kind: "ترميز غير صالح".to_string(),
+                        message_ar: format!("'{}' ليس ترميز base64 صالحاً لنص", encoded),
+                        message_en: format!("'{}' is not valid base64-encoded text", encoded),
+                        suggestion: Some(tr!("suggest.base64_text")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }))
+            }
+
+            "نص_إلى_بايتات" => {
+                if !(1..=2).contains(&args.len()) {
+                    return Err(arity_error(name, 1, Some(2), args.len()));
+                }
+                let [text] = string_args::<1>(name, &args[..1])?;
+                let encoding = encoding_arg(name, args.get(1))?;
+                let encoded = encode_text(text, encoding)?;
+                self.check_collection_bytes(encoded.len())?;
+                Ok(Value::Bytes(encoded))
+            }
+
+            "بايتات_إلى_نص" => {
+                if !(1..=2).contains(&args.len()) {
+                    return Err(arity_error(name, 1, Some(2), args.len()));
+                }
+                let Value::Bytes(data) = &args[0] else {
+                    return Err(anyhow!(IqraError {
+                        kind: "نوع وسيط غير صحيح".to_string(),
+                        message_ar: format!("{} تتوقع بايتات، لكن وصلها {}", name, args[0].type_name_ar()),
+                        message_en: format!("{} expects bytes, got {}", name, args[0].type_name()),
+                        suggestion: Some(tr!("suggest.make_bytes")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    }));
+                };
+                let encoding = encoding_arg(name, args.get(1))?;
+                decode_text(data, encoding).map(Value::String)
+            }
+
+            "تجزئة" => {
+                let [text] = string_args::<1>(name, args)?;
+                Ok(Value::String(sha256_hex(text.as_bytes())))
+            }
+
+            "تجزئة_ملف" => {
+                let [path] = string_args::<1>(name, args)?;
+                match self.system_executor.read_file(path) {
+                    Ok(content) => Ok(Value::String(sha256_hex(content.as_bytes()))),
+                    Err(e) => Err(anyhow!(IqraError {
+                        kind: "فشل قراءة الملف".to_string(),
+                        message_ar: format!("فشل قراءة الملف: {}", e),
+                        message_en: format!("Failed to read file: {}", e),
+                        suggestion: Some(tr!("suggest.read_access")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })),
+                }
+            }
+
+            "معرف_فريد" => {
+                if !args.is_empty() {
+                    return Err(arity_error(name, 0, Some(0), args.len()));
+                }
+                let mut bytes = [0u8; 16];
+                match &mut self.rng {
+                    Some(rng) => rng.fill_bytes(&mut bytes),
+                    None => rand::thread_rng().fill_bytes(&mut bytes),
+                }
+                Ok(Value::String(uuid::Builder::from_random_bytes(bytes).into_uuid().to_string()))
+            }
+
+            // Date functions
+            "تاريخ_اليوم" => {
+                Ok(Value::String(self.clock.today().format("%Y-%m-%d").to_string()))
+            }
+
+            "تاريخ_هجري" => {
+                if !args.is_empty() {
+                    return Err(arity_error(name, 0, Some(0), args.len()));
+                }
+                let day = self.clock.today();
+                let today = match self.today_cache {
+                    Some((cached_day, today)) if cached_day == day => today,
+                    _ => {
+                        let today = HijriDate::from_gregorian(day).ok_or_else(|| date_error(name, "today"))?;
+                        self.today_cache = Some((day, today));
+                        today
+                    }
+                };
+                Ok(Value::String(today.to_string()))
+            }
+
+            "إلى_هجري" => {
+                let [date] = string_args::<1>(name, args)?;
+                NaiveDate::parse_from_str(&to_ascii_digits(date.trim()), "%Y-%m-%d")
+                    .ok()
+                    .and_then(HijriDate::from_gregorian)
+                    .map(|hijri| Value::String(hijri.to_string()))
+                    .ok_or_else(|| date_error(name, date))
+            }
+
+            "من_هجري" => {
+                let [date] = string_args::<1>(name, args)?;
+                HijriDate::parse(date)
+                    .and_then(|hijri| hijri.to_gregorian())
+                    .map(|gregorian| Value::String(gregorian.format("%Y-%m-%d").to_string()))
+                    .ok_or_else(|| date_error(name, date))
+            }
+
+            "اسم_الشهر_الهجري" => {
+                if args.len() != 1 {
+                    return Err(arity_error(name, 1, Some(1), args.len()));
+                }
+                match &args[0] {
+                    Value::Int(month) if (1..=12).contains(month) => {
+                        Ok(Value::String(hijri::MONTH_NAMES[*month as usize - 1].to_string()))
+                    }
+                    other => Err(anyhow!(IqraError {
+                        kind: "فهرسة خارج النطاق".to_string(),
+                        message_ar: format!("رقم الشهر الهجري يجب أن يكون من ١ إلى ١٢، وصلها: {}", other),
+                        message_en: format!("A Hijri month number must be 1 to 12, got: {}", other),
+                        suggestion: Some(tr!("suggest.example_hijri_month")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })),
                 }
             }
 
             // System functions
-            "system" | "نفذ_أمر" => {
+            "نفذ_أمر" => {
+                self.ensure_not_sandboxed(name)?;
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "نفذ_أمر تتوقع وسيطاً واحداً".to_string(),
                         message_en: "system expects 1 argument".to_string(),
-                        suggestion: Some("استخدم نصاً يمثل الأمر".to_string()),
+                        suggestion: Some(tr!("suggest.command_text")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match &args[0] {
@@ -1194,28 +4895,35 @@ impl Runtime {
                             kind: "فشل تنفيذ أمر النظام".to_string(),
                             message_ar: format!("فشل تنفيذ الأمر: {}", e),
                             message_en: format!("System command failed: {}", e),
-                            suggestion: Some("تأكد من صحة الأمر وصلاحيات التنفيذ".to_string()),
+                            suggestion: Some(tr!("suggest.command_permission")),
                             line: None,
+                            column: None,
+                            source_name: None,
                         })),
                     },
                     _ => Err(anyhow!(IqraError {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "نفذ_أمر يتوقع نصاً يمثل الأمر".to_string(),
                         message_en: "system expects a string command".to_string(),
-                        suggestion: Some("استخدم نصاً فقط".to_string()),
+                        suggestion: Some(tr!("suggest.text_only")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            "system_with_io" | "نفذ_أمر_بمدخل" => {
+            "نفذ_أمر_بمدخل" => {
+                self.ensure_not_sandboxed(name)?;
                 if args.len() != 2 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "نفذ_أمر_بمدخل تتوقع وسيطين".to_string(),
                         message_en: "system_with_io expects 2 arguments".to_string(),
-                        suggestion: Some("استخدم نصين: الأمر والمدخل".to_string()),
+                        suggestion: Some(tr!("suggest.command_and_input")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match (&args[0], &args[1]) {
@@ -1226,8 +4934,10 @@ impl Runtime {
                                 kind: "فشل تنفيذ أمر النظام".to_string(),
                                 message_ar: format!("فشل تنفيذ الأمر بمدخل: {}", e),
                                 message_en: format!("System command failed: {}", e),
-                                suggestion: Some("تأكد من صحة الأمر والمدخل وصلاحيات التنفيذ".to_string()),
+                                suggestion: Some(tr!("suggest.command_access")),
                                 line: None,
+                                column: None,
+                                source_name: None,
                             })),
                         }
                     }
@@ -1235,51 +4945,128 @@ impl Runtime {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "نفذ_أمر_بمدخل يتوقع نصين".to_string(),
                         message_en: "system_with_io expects string arguments".to_string(),
-                        suggestion: Some("استخدم نصين فقط".to_string()),
+                        suggestion: Some(tr!("suggest.two_texts")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })),
+                }
+            }
+
+            // Unlike نفذ_أمر_بمدخل, keeps stderr and the exit code and leaves the
+            // output untrimmed: {مخرج، أخطاء، رمز_الخروج، نجح}.
+            "نفذ_أمر_بمدخل_كامل" => {
+                self.ensure_not_sandboxed(name)?;
+                let [cmd, input] = string_args::<2>(name, args)?;
+                match self.system_executor.exec_with_io_full(cmd, input) {
+                    Ok(output) => Ok(output.to_value()),
+                    Err(e) => Err(anyhow!(IqraError {
+                        kind: "فشل تنفيذ أمر النظام".to_string(),
+                        message_ar: format!("فشل تنفيذ الأمر بمدخل: {}", e),
+                        message_en: format!("System command failed: {}", e),
+                        suggestion: Some(tr!("suggest.command_access")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            "read_file" | "اقرأ_ملف" => {
+            "اقرأ_ملف" => {
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "اقرأ_ملف تتوقع وسيطاً واحداً".to_string(),
                         message_en: "read_file expects 1 argument".to_string(),
-                        suggestion: Some("استخدم نصاً يمثل المسار".to_string()),
+                        suggestion: Some(tr!("suggest.path_text")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match &args[0] {
-                    Value::String(path) => match self.system_executor.read_file(path) {
-                        Ok(content) => Ok(Value::String(content)),
-                        Err(e) => Err(anyhow!(IqraError {
-                            kind: "فشل قراءة الملف".to_string(),
-                            message_ar: format!("فشل قراءة الملف: {}", e),
-                            message_en: format!("Failed to read file: {}", e),
-                            suggestion: Some("تأكد من صحة المسار وصلاحيات القراءة".to_string()),
-                            line: None,
-                        })),
-                    },
+                    Value::String(path) => {
+                        // Refuse a file that is too big before reading it.
+                        if let Some(size) = self.system_executor.file_size(path) {
+                            self.check_collection_bytes(usize::try_from(size).unwrap_or(usize::MAX))?;
+                        }
+                        match self.system_executor.read_file(path) {
+                            Ok(content) => {
+                                self.check_collection_bytes(content.len())?;
+                                Ok(Value::String(content))
+                            }
+                            Err(e) => Err(anyhow!(IqraError {
+                                kind: "فشل قراءة الملف".to_string(),
+                                message_ar: format!("فشل قراءة الملف: {}", e),
+                                message_en: format!("Failed to read file: {}", e),
+                                suggestion: Some(tr!("suggest.read_access")),
+                                line: None,
+                                column: None,
+                                source_name: None,
+                            })),
+                        }
+                    }
                     _ => Err(anyhow!(IqraError {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "اقرأ_ملف يتوقع نصاً يمثل المسار".to_string(),
                         message_en: "read_file expects a string path".to_string(),
-                        suggestion: Some("استخدم نصاً فقط".to_string()),
+                        suggestion: Some(tr!("suggest.text_only")),
+                        line: None,
+                        column: None,
+                        source_name: None,
+                    })),
+                }
+            }
+
+            "اقرأ_بايتات" => {
+                let [path] = string_args::<1>(name, args)?;
+                Ok(Value::Bytes(self.read_file_bytes(path)?))
+            }
+
+            "اقرأ_ملف_بترميز" => {
+                let [path, _] = string_args::<2>(name, args)?;
+                let encoding = encoding_arg(name, Some(&args[1]))?;
+                Ok(Value::String(decode_text(&self.read_file_bytes(path)?, encoding)?))
+            }
+
+            "اكتب_ملف_بترميز" => {
+                self.ensure_not_sandboxed(name)?;
+                let [path, text, _] = string_args::<3>(name, args)?;
+                let encoded = encode_text(text, encoding_arg(name, Some(&args[2]))?)?;
+                match self.system_executor.write_bytes(path, &encoded) {
+                    Ok(success) => Ok(Value::Bool(success)),
+                    Err(e) => Err(anyhow!(IqraError {
+                        kind: "فشل كتابة الملف".to_string(),
+                        message_ar: format!("فشل كتابة الملف: {}", e),
+                        message_en: format!("Failed to write file: {}", e),
+                        suggestion: Some(tr!("suggest.write_access")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            "write_file" | "اكتب_ملف" => {
+            "خمّن_الترميز" => {
+                let [path] = string_args::<1>(name, args)?;
+                let guess = bytes::detect(&self.read_file_bytes(path)?);
+                Ok(Value::Map(HashMap::from([
+                    ("ترميز".to_string(), Value::String(bytes::label(guess.encoding).to_string())),
+                    ("ثقة".to_string(), Value::Number(guess.confidence)),
+                ])))
+            }
+
+            "اكتب_ملف" => {
+                self.ensure_not_sandboxed(name)?;
                 if args.len() != 2 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "اكتب_ملف تتوقع وسيطين".to_string(),
                         message_en: "write_file expects 2 arguments".to_string(),
-                        suggestion: Some("استخدم نصين: المسار والمحتوى".to_string()),
+                        suggestion: Some(tr!("suggest.path_and_content")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match (&args[0], &args[1]) {
@@ -1290,8 +5077,10 @@ impl Runtime {
                                 kind: "فشل كتابة الملف".to_string(),
                                 message_ar: format!("فشل كتابة الملف: {}", e),
                                 message_en: format!("Failed to write file: {}", e),
-                                suggestion: Some("تأكد من صحة المسار وصلاحيات الكتابة".to_string()),
+                                suggestion: Some(tr!("suggest.write_access")),
                                 line: None,
+                                column: None,
+                                source_name: None,
                             })),
                         }
                     }
@@ -1299,20 +5088,55 @@ impl Runtime {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "اكتب_ملف يتوقع نصين".to_string(),
                         message_en: "write_file expects string arguments".to_string(),
-                        suggestion: Some("استخدم نصين فقط".to_string()),
+                        suggestion: Some(tr!("suggest.two_texts")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            "list_files" | "قائمة_ملفات" => {
+            "وصل_مسار" => {
+                if args.is_empty() {
+                    return Err(arity_error(name, 1, None, 0));
+                }
+                let parts = args.iter().map(|arg| arg.as_string().ok_or_else(|| anyhow!(IqraError {
+                    kind: "نوع وسيط غير صحيح".to_string(),
+                    message_ar: format!("{} تتوقع نصوصاً فقط", name),
+                    message_en: format!("{} expects string arguments", name),
+                    suggestion: Some(tr!("suggest.texts_only")),
+                    line: None,
+                    column: None,
+                    source_name: None,
+                })));
+                Ok(Value::String(path::join(&parts.collect::<Result<Vec<_>>>()?)))
+            }
+
+            "وسع_مسار" => {
+                let [file] = string_args::<1>(name, args)?;
+                Ok(Value::String(self.expand_path(file)))
+            }
+
+            "اسم_الملف" => {
+                let [file] = string_args::<1>(name, args)?;
+                Ok(Value::String(path::basename(file).to_string()))
+            }
+
+            "مجلد_الملف" => {
+                let [file] = string_args::<1>(name, args)?;
+                Ok(Value::String(path::dirname(file).to_string()))
+            }
+
+            "قائمة_ملفات" => {
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "قائمة_ملفات تتوقع وسيطاً واحداً".to_string(),
                         message_en: "list_files expects 1 argument".to_string(),
-                        suggestion: Some("استخدم نصاً يمثل المسار".to_string()),
+                        suggestion: Some(tr!("suggest.path_text")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match &args[0] {
@@ -1326,28 +5150,34 @@ impl Runtime {
                             kind: "فشل جلب قائمة الملفات".to_string(),
                             message_ar: format!("فشل جلب قائمة الملفات: {}", e),
                             message_en: format!("Failed to list files: {}", e),
-                            suggestion: Some("تأكد من صحة المسار وصلاحيات القراءة".to_string()),
+                            suggestion: Some(tr!("suggest.read_access")),
                             line: None,
+                            column: None,
+                            source_name: None,
                         })),
                     },
                     _ => Err(anyhow!(IqraError {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "قائمة_ملفات تتوقع نصاً يمثل المسار".to_string(),
                         message_en: "list_files expects a string path".to_string(),
-                        suggestion: Some("استخدم نصاً فقط".to_string()),
+                        suggestion: Some(tr!("suggest.text_only")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            "env_var" | "متغير_بيئة" => {
+            "متغير_بيئة" => {
                 if args.len() != 1 {
                     return Err(anyhow!(IqraError {
                         kind: "عدد وسائط غير صحيح".to_string(),
                         message_ar: "متغير_بيئة تتوقع وسيطاً واحداً".to_string(),
                         message_en: "env_var expects 1 argument".to_string(),
-                        suggestion: Some("استخدم نصاً يمثل اسم المتغير".to_string()),
+                        suggestion: Some(tr!("suggest.variable_name_text")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     }));
                 }
                 match &args[0] {
@@ -1359,21 +5189,174 @@ impl Runtime {
                         kind: "نوع وسيط غير صحيح".to_string(),
                         message_ar: "متغير_بيئة يتوقع نصاً يمثل اسم المتغير".to_string(),
                         message_en: "env_var expects a string name".to_string(),
-                        suggestion: Some("استخدم نصاً فقط".to_string()),
+                        suggestion: Some(tr!("suggest.text_only")),
                         line: None,
+                        column: None,
+                        source_name: None,
                     })),
                 }
             }
 
-            "system_info" | "معلومات_النظام" => {
+            "عين_متغير_بيئة" => {
+                let [var, value] = string_args::<2>(name, args)?;
+                self.ensure_not_sandboxed(name)?;
+                self.system_executor.set_env_var(var, value).map(|_| Value::Nil).map_err(|e| anyhow!(IqraError {
+                    kind: "فشل تعيين متغير البيئة".to_string(),
+                    message_ar: format!("فشل تعيين متغير البيئة '{}': {}", var, e),
+                    message_en: format!("Failed to set environment variable '{}': {}", var, e),
+                    suggestion: Some(tr!("suggest.env_name")),
+                    line: None,
+                    column: None,
+                    source_name: None,
+                }))
+            }
+
+            "مجلد_حالي" => {
                 if !args.is_empty() {
-                    return Err(anyhow!(IqraError {
-                        kind: "عدد وسائط غير صحيح".to_string(),
-                        message_ar: "معلومات_النظام لا تتوقع وسائط".to_string(),
-                        message_en: "system_info expects no arguments".to_string(),
-                        suggestion: Some("لا تستخدم وسائط مع هذه الدالة".to_string()),
+                    return Err(arity_error(name, 0, Some(0), args.len()));
+                }
+                self.system_executor.current_dir().map(Value::String).map_err(|e| anyhow!(IqraError {
+                    kind: "فشل قراءة المجلد الحالي".to_string(),
+                    message_ar: format!("فشل قراءة المجلد الحالي: {}", e),
+                    message_en: format!("Failed to read current directory: {}", e),
+                    suggestion: None,
+                    line: None,
+                    column: None,
+                    source_name: None,
+                }))
+            }
+
+            "غير_مجلد" => {
+                let [path] = string_args::<1>(name, args)?;
+                self.ensure_not_sandboxed(name)?;
+                self.system_executor.change_dir(path).map(|_| Value::Nil).map_err(|e| anyhow!(IqraError {
+                    kind: "فشل تغيير المجلد".to_string(),
+                    message_ar: format!("فشل تغيير المجلد إلى '{}': {}", path, e),
+                    message_en: format!("Failed to change directory to '{}': {}", path, e),
+                    suggestion: Some(tr!("suggest.directory_access")),
+                    line: None,
+                    column: None,
+                    source_name: None,
+                }))
+            }
+
+            "معرف_عملية" => {
+                if !args.is_empty() {
+                    return Err(arity_error(name, 0, Some(0), args.len()));
+                }
+                Ok(Value::Int(self.system_executor.process_id() as i64))
+            }
+
+            "وسائط" => {
+                if !args.is_empty() {
+                    return Err(arity_error(name, 0, Some(0), args.len()));
+                }
+                Ok(Value::List(self.script_args.iter().cloned().map(Value::String).collect()))
+            }
+
+            "اخرج" => {
+                if args.len() > 1 {
+                    return Err(arity_error(name, 0, Some(1), args.len()));
+                }
+                let code = match args.first() {
+                    None => 0,
+                    Some(Value::Int(code)) => i32::try_from(*code).map_err(|_| anyhow!(IqraError {
+                        kind: "رمز خروج غير صالح".to_string(),
+                        message_ar: format!("رمز الخروج {} خارج المدى", code),
+                        message_en: format!("Exit code {} is out of range", code),
+                        suggestion: Some(tr!("suggest.exit_code_range")),
                         line: None,
-                    }));
+                        column: None,
+                        source_name: None,
+                    }))?,
+                    Some(_) => {
+                        return Err(anyhow!(IqraError {
+                            kind: "نوع وسيط غير صحيح".to_string(),
+                            message_ar: "اخرج تتوقع رقماً صحيحاً".to_string(),
+                            message_en: "exit expects a whole number".to_string(),
+                            suggestion: Some(tr!("suggest.example_exit")),
+                            line: None,
+                            column: None,
+                            source_name: None,
+                        }));
+                    }
+                };
+                Err(anyhow!(ExitSignal(code)))
+            }
+
+            "أكد؟" => {
+                let [prompt] = string_args::<1>(name, args)?;
+                let prompt = format!("{} (نعم/لا | y/n): ", prompt);
+                for _ in 0..PROMPT_ATTEMPTS {
+                    let answer = self.read_input(&prompt)?;
+                    match answer.trim().to_lowercase().as_str() {
+                        "نعم" | "ن" | "y" | "yes" => return Ok(Value::Bool(true)),
+                        "لا" | "ل" | "n" | "no" => return Ok(Value::Bool(false)),
+                        _ => {}
+                    }
+                }
+                Ok(Value::Nil)
+            }
+
+            "اختر" => {
+                let (prompt, options) = match args {
+                    [Value::String(prompt), Value::List(options)] if !options.is_empty() => (prompt, options),
+                    [_, _] => {
+                        return Err(anyhow!(IqraError {
+                            kind: "نوع وسيط غير صحيح".to_string(),
+                            message_ar: "اختر تتوقع نصاً وقائمة خيارات غير فارغة".to_string(),
+                            message_en: "choose expects a prompt string and a non-empty list of options".to_string(),
+                            suggestion: Some(tr!("suggest.example_choose")),
+                            line: None,
+                            column: None,
+                            source_name: None,
+                        }));
+                    }
+                    _ => return Err(arity_error(name, 2, Some(2), args.len())),
+                };
+                let mut menu = format!("{}\n", prompt);
+                for (i, option) in options.iter().enumerate() {
+                    menu.push_str(&format!("{}. {}\n", i + 1, option));
+                }
+                menu.push_str("> ");
+                for _ in 0..PROMPT_ATTEMPTS {
+                    let answer = self.read_input(&menu)?;
+                    let answer = answer.trim();
+                    let by_number = to_ascii_digits(answer).parse::<usize>().ok().and_then(|n| n.checked_sub(1));
+                    if let Some(option) = by_number.and_then(|i| options.get(i)) {
+                        return Ok(option.clone());
+                    }
+                    if let Some(option) = options.iter().find(|option| format!("{}", option) == answer) {
+                        return Ok(option.clone());
+                    }
+                }
+                Ok(Value::Nil)
+            }
+
+            "كلمة_سر" => {
+                let [prompt] = string_args::<1>(name, args)?;
+                self.system_executor.read_password(prompt).map(Value::String).map_err(input_error)
+            }
+
+            "معلومات_النظام" => {
+                let refresh = match args {
+                    [] => false,
+                    [Value::Bool(refresh)] => *refresh,
+                    [_] => {
+                        return Err(anyhow!(IqraError {
+                            kind: "نوع وسيط غير صحيح".to_string(),
+                            message_ar: "معلومات_النظام تتوقع قيمة منطقية تحديث".to_string(),
+                            message_en: "system_info expects a boolean refresh".to_string(),
+                            suggestion: Some(tr!("suggest.refresh_flag")),
+                            line: None,
+                            column: None,
+                            source_name: None,
+                        }));
+                    }
+                    _ => return Err(arity_error(name, 0, Some(1), args.len())),
+                };
+                if refresh {
+                    self.system_info_cache = None;
                 }
                 if let Some(ref cached) = self.system_info_cache {
                     let map_values: HashMap<String, Value> =
@@ -1391,19 +5374,27 @@ impl Runtime {
                             kind: "فشل جلب معلومات النظام".to_string(),
                             message_ar: format!("فشل جلب معلومات النظام: {}", e),
                             message_en: format!("Failed to get system info: {}", e),
-                            suggestion: Some("تأكد من صلاحيات النظام".to_string()),
+                            suggestion: Some(tr!("suggest.system_permission")),
                             line: None,
+                            column: None,
+                            source_name: None,
                         })),
                     }
                 }
             }
 
+            canonical if READ_ONLY_BUILTINS.contains(&canonical) => {
+                self.read_only_builtin(name, &args.iter().collect::<Vec<_>>())
+            }
+
             _ => Err(anyhow!(IqraError {
                 kind: "دالة غير معرفة".to_string(),
                 message_ar: format!("دالة غير معرفة: {}", name),
                 message_en: format!("Unknown function: {}", name),
-                suggestion: Some("تأكد من كتابة اسم الدالة بشكل صحيح".to_string()),
+                suggestion: Some(self.function_suggestion(name)),
                 line: None,
+                column: None,
+                source_name: None,
             })),
         }
     }
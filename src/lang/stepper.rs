@@ -0,0 +1,231 @@
+//! التنفيذ خطوة بخطوة | Statement-by-statement execution for the REPL step mode.
+//!
+//! The program is parsed once; each call to [`Stepper::next_step`] runs the next
+//! statement, descending into the bodies of `اذا`/`بينما`/`افعل`/`كرر`/`طابق`/blocks
+//! one inner statement at a time instead of executing them as a whole.
+
+use crate::lang::lexer::{Lexer, Span};
+use crate::lang::parser::{Parser, Stmt, StmtKind};
+use crate::lang::runtime::{LoopExit, Runtime};
+use crate::lang::value::Value;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// تغيير في قيمة متغير | A variable whose value changed during a step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableChange {
+    pub name: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+/// نتيجة خطوة واحدة | The outcome of a single step.
+#[derive(Debug, Clone)]
+pub struct Step {
+    /// Span of the statement that was stepped.
+    pub span: Span,
+    /// Value of the statement, or of the condition for `اذا`/`بينما`.
+    pub result: Value,
+    /// Variables changed by this step, sorted by name.
+    pub changes: Vec<VariableChange>,
+}
+
+#[derive(Debug)]
+struct Frame {
+    statements: Vec<Stmt>,
+    index: usize,
+    /// Whether `statements` is the body of a loop, the frame `توقف` and
+    /// `استمر` leave.
+    loop_body: bool,
+    /// Whether the current statement, an `افعل`, has run its body once, so
+    /// its condition is due.
+    entered: bool,
+}
+
+impl Frame {
+    fn new(statements: &[Stmt], loop_body: bool) -> Self {
+        Frame { statements: statements.to_vec(), index: 0, loop_body, entered: false }
+    }
+}
+
+#[derive(Debug)]
+pub struct Stepper {
+    program: Vec<Stmt>,
+    frames: Vec<Frame>,
+}
+
+impl Stepper {
+    /// Parses the whole program once and prepares to step through it.
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parser = Parser::new(Lexer::new(source));
+        Ok(Self::new(parser.parse()?))
+    }
+
+    pub fn new(program: Vec<Stmt>) -> Self {
+        let frames = vec![Frame::new(&program, false)];
+        Stepper { program, frames }
+    }
+
+    /// Starts again from the first statement, re-using the parsed program.
+    pub fn restart(&mut self) {
+        self.frames = vec![Frame::new(&self.program, false)];
+    }
+
+    /// Returns the statement that the next call to `next_step` will run.
+    pub fn peek(&mut self) -> Option<&Stmt> {
+        self.unwind();
+        let frame = self.frames.last()?;
+        frame.statements.get(frame.index)
+    }
+
+    pub fn is_finished(&mut self) -> bool {
+        self.peek().is_none()
+    }
+
+    /// Runs the next statement against `runtime`.
+    ///
+    /// Returns `None` once the program is finished. A failing statement is
+    /// skipped so that stepping can continue with the one after it.
+    pub fn next_step(&mut self, runtime: &mut Runtime) -> Option<Result<Step>> {
+        let stmt = self.peek()?.clone();
+        let before = runtime.get_variables();
+        let result = self.run(&stmt, runtime);
+        let after = runtime.get_variables();
+        Some(result.map(|result| Step {
+            span: stmt.span,
+            result,
+            changes: diff_variables(&before, &after),
+        }))
+    }
+
+    fn run(&mut self, stmt: &Stmt, runtime: &mut Runtime) -> Result<Value> {
+        match &stmt.kind {
+            StmtKind::If { condition, then_branch, else_branch } => {
+                self.advance();
+                let value = runtime.evaluate_parsed_expression(condition)?;
+                if value.is_truthy() {
+                    self.push(then_branch);
+                } else if let Some(else_branch) = else_branch {
+                    self.push(else_branch);
+                }
+                Ok(value)
+            }
+            StmtKind::While { condition, body } => {
+                // The loop statement stays current while its body runs, so the
+                // condition is checked again once the body frame is exhausted.
+                let value = runtime.evaluate_parsed_expression(condition)?;
+                if value.is_truthy() {
+                    self.push_loop_body(body);
+                } else {
+                    self.advance();
+                }
+                Ok(value)
+            }
+            StmtKind::DoWhile { body, condition } => {
+                // As with `بينما`, but the first step enters the body without
+                // checking the condition.
+                let frame = self.frames.last_mut().unwrap();
+                if !std::mem::replace(&mut frame.entered, true) {
+                    self.push_loop_body(body);
+                    return Ok(Value::Nil);
+                }
+                let value = runtime.evaluate_parsed_expression(condition)?;
+                if value.is_truthy() {
+                    self.push_loop_body(body);
+                } else {
+                    self.advance();
+                }
+                Ok(value)
+            }
+            StmtKind::Loop(body) => {
+                // Stays current until a `توقف` in the body moves past it.
+                self.push_loop_body(body);
+                Ok(Value::Nil)
+            }
+            StmtKind::Match { subject, arms, default } => {
+                self.advance();
+                let value = runtime.evaluate_parsed_expression(subject)?;
+                if let Some(body) = runtime.select_match_arm(&value, arms, default)? {
+                    self.push(body);
+                }
+                Ok(value)
+            }
+            StmtKind::Block(statements) => {
+                self.advance();
+                self.push(statements);
+                Ok(Value::Nil)
+            }
+            _ => {
+                self.advance();
+                if !self.frames.iter().any(|frame| frame.loop_body) {
+                    return runtime.execute_parsed_statement(stmt);
+                }
+                let (value, loop_exit) = runtime.execute_loop_statement(stmt)?;
+                if let Some(loop_exit) = loop_exit {
+                    self.exit_loop_body(loop_exit);
+                }
+                Ok(value)
+            }
+        }
+    }
+
+    fn advance(&mut self) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.index += 1;
+            frame.entered = false;
+        }
+    }
+
+    fn push(&mut self, statements: &[Stmt]) {
+        self.frames.push(Frame::new(statements, false));
+    }
+
+    fn push_loop_body(&mut self, statements: &[Stmt]) {
+        self.frames.push(Frame::new(statements, true));
+    }
+
+    /// Leaves the innermost loop body. After `استمر` the loop statement is
+    /// current again, to check its condition; after `توقف` it is skipped.
+    fn exit_loop_body(&mut self, loop_exit: LoopExit) {
+        while let Some(frame) = self.frames.pop() {
+            if frame.loop_body {
+                break;
+            }
+        }
+        if loop_exit == LoopExit::Break {
+            self.advance();
+        }
+    }
+
+    /// Pops finished inner frames so the top frame points at a pending statement.
+    fn unwind(&mut self) {
+        while self.frames.len() > 1 {
+            let frame = self.frames.last().unwrap();
+            if frame.index < frame.statements.len() {
+                break;
+            }
+            self.frames.pop();
+        }
+    }
+}
+
+/// Computes the variables added, changed, or removed between two snapshots.
+pub fn diff_variables(
+    before: &HashMap<String, Value>,
+    after: &HashMap<String, Value>,
+) -> Vec<VariableChange> {
+    let mut changes: Vec<VariableChange> = after
+        .iter()
+        .filter(|(name, value)| before.get(*name) != Some(value))
+        .map(|(name, value)| VariableChange {
+            name: name.clone(),
+            old: before.get(name).cloned(),
+            new: Some(value.clone()),
+        })
+        .collect();
+    changes.extend(before.iter().filter(|(name, _)| !after.contains_key(*name)).map(
+        |(name, value)| VariableChange { name: name.clone(), old: Some(value.clone()), new: None },
+    ));
+    changes.sort_by(|a, b| a.name.cmp(&b.name));
+    changes
+}
@@ -0,0 +1,193 @@
+//! الرموز | Interned names. The lexer interns every identifier once, so the
+//! AST, variable scopes and the function table share one copy of each name,
+//! and comparing or hashing a name looks at its address instead of its bytes.
+//!
+//! The table only holds weak references: a name is freed once no symbol for
+//! it is left, so a long-running server that parses many documents doesn't
+//! keep every name it has seen.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+/// An interned name. Two symbols are equal exactly when their names are.
+#[derive(Clone)]
+pub struct Symbol(Arc<str>);
+
+/// A map keyed by symbols, hashing only their addresses.
+pub type SymbolMap<V> = HashMap<Symbol, V, BuildHasherDefault<SymbolHasher>>;
+
+pub type SymbolSet = HashSet<Symbol, BuildHasherDefault<SymbolHasher>>;
+
+/// Hashes a symbol's address with one multiplication. Addresses are
+/// unique, so a cryptographic hash would buy nothing; the high bits of the
+/// product are folded down because aligned addresses end in zero bits.
+#[derive(Default)]
+pub struct SymbolHasher(u64);
+
+impl Hasher for SymbolHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u64(u64::from(byte));
+        }
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = (self.0 ^ n).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.write_u64(n as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0 ^ (self.0 >> 32)
+    }
+}
+
+/// Names with a live symbol, and some whose symbols are gone. Dead entries
+/// are swept when the table has doubled since the last sweep.
+#[derive(Default)]
+struct Table {
+    names: HashMap<Box<str>, Weak<str>>,
+    sweep_at: usize,
+}
+
+/// The table isn't swept before it holds this many names.
+const MIN_SWEEP: usize = 1024;
+
+fn table() -> std::sync::MutexGuard<'static, Table> {
+    static TABLE: OnceLock<Mutex<Table>> = OnceLock::new();
+    TABLE.get_or_init(Default::default).lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl Symbol {
+    /// The symbol for `name`, adding it to the table if no symbol for it is
+    /// alive.
+    pub fn intern(name: &str) -> Symbol {
+        let mut table = table();
+        if let Some(interned) = table.names.get(name).and_then(Weak::upgrade) {
+            return Symbol(interned);
+        }
+        if table.names.len() >= table.sweep_at.max(MIN_SWEEP) {
+            table.names.retain(|_, interned| interned.strong_count() > 0);
+            table.sweep_at = table.names.len() * 2;
+        }
+        let interned: Arc<str> = Arc::from(name);
+        table.names.insert(name.into(), Arc::downgrade(&interned));
+        Symbol(interned)
+    }
+
+    /// The symbol for `name` if one is alive. Names without one can't be
+    /// bound to anything, and looking them up doesn't add them.
+    pub fn lookup(name: &str) -> Option<Symbol> {
+        table().names.get(name).and_then(Weak::upgrade).map(Symbol)
+    }
+
+    /// How many names the table holds, including any not yet swept.
+    pub fn table_len() -> usize {
+        table().names.len()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Symbol) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.0.as_ptr() as usize).hash(state);
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Symbol) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Symbols sort by name, so sorted output doesn't depend on addresses.
+impl Ord for Symbol {
+    fn cmp(&self, other: &Symbol) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Symbol {
+        Symbol::intern(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Symbol {
+        Symbol::intern(&name)
+    }
+}
+
+impl From<Symbol> for String {
+    fn from(symbol: Symbol) -> String {
+        symbol.0.to_string()
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Symbol, D::Error> {
+        String::deserialize(deserializer).map(Symbol::from)
+    }
+}
@@ -1,22 +1,29 @@
+use crate::i18n::{ErrorLanguage, Word};
 use crate::lang::runtime::IqraError;
+use crate::tr;
 use anyhow::{Result, anyhow};
 impl Value {
     pub fn to_number(&self) -> Result<f64> {
         match self {
             Value::Number(n) => Ok(*n),
+            Value::Int(n) => Ok(*n as f64),
             Value::String(s) => s.parse::<f64>().map_err(|_| anyhow!(IqraError {
                 kind: "تحويل إلى رقم | To Number".to_string(),
                 message_ar: format!("لا يمكن تحويل السلسلة '{}' إلى رقم.", s),
                 message_en: format!("Cannot convert string '{}' to number.", s),
-                suggestion: Some("تأكد من أن السلسلة تمثل رقمًا صالحًا | Ensure the string is a valid number".to_string()),
+                suggestion: Some(tr!("suggest.valid_number_string")),
                 line: None,
+                column: None,
+                source_name: None,
             })),
             _ => Err(anyhow!(IqraError {
                 kind: "تحويل إلى رقم | To Number".to_string(),
                 message_ar: format!("لا يمكن تحويل النوع '{}' إلى رقم.", self.type_name_ar()),
                 message_en: format!("Cannot convert type '{}' to number.", self.type_name()),
-                suggestion: Some("استخدم نوعًا مناسبًا | Use a suitable type".to_string()),
+                suggestion: Some(tr!("suggest.suitable_type")),
                 line: None,
+                column: None,
+                source_name: None,
             })),
         }
     }
@@ -24,15 +31,18 @@ impl Value {
     pub fn to_string(&self) -> Result<String> {
         match self {
             Value::String(s) => Ok(s.clone()),
-            Value::Number(n) => Ok(n.to_string()),
+            Value::Number(n) => Ok(format_float(*n)),
+            Value::Int(n) => Ok(n.to_string()),
             Value::Bool(b) => Ok(if *b { "صحيح".to_string() } else { "خطأ".to_string() }),
-            Value::Nil => Ok("فارغ".to_string()),
+            Value::Nil => Ok(nil_text(ErrorLanguage::current(), interactive_display()).to_string()),
             _ => Err(anyhow!(IqraError {
                 kind: "تحويل إلى سلسلة | To String".to_string(),
                 message_ar: format!("لا يمكن تحويل النوع '{}' إلى سلسلة.", self.type_name_ar()),
                 message_en: format!("Cannot convert type '{}' to string.", self.type_name()),
-                suggestion: Some("استخدم نوعًا مناسبًا | Use a suitable type".to_string()),
+                suggestion: Some(tr!("suggest.suitable_type")),
                 line: None,
+                column: None,
+                source_name: None,
             })),
         }
     }
@@ -44,8 +54,10 @@ impl Value {
                 kind: "تحويل إلى قائمة | To List".to_string(),
                 message_ar: format!("لا يمكن تحويل النوع '{}' إلى قائمة.", self.type_name_ar()),
                 message_en: format!("Cannot convert type '{}' to list.", self.type_name()),
-                suggestion: Some("استخدم نوعًا مناسبًا | Use a suitable type".to_string()),
+                suggestion: Some(tr!("suggest.suitable_type")),
                 line: None,
+                column: None,
+                source_name: None,
             })),
         }
     }
@@ -57,8 +69,10 @@ impl Value {
                 kind: "تحويل إلى قاموس | To Map".to_string(),
                 message_ar: format!("لا يمكن تحويل النوع '{}' إلى قاموس.", self.type_name_ar()),
                 message_en: format!("Cannot convert type '{}' to map.", self.type_name()),
-                suggestion: Some("استخدم نوعًا مناسبًا | Use a suitable type".to_string()),
+                suggestion: Some(tr!("suggest.suitable_type")),
                 line: None,
+                column: None,
+                source_name: None,
             })),
         }
     }
@@ -67,24 +81,188 @@ impl Value {
         match self {
             Value::Nil => "فارغ",
             Value::Bool(_) => "منطقي",
-            Value::Number(_) => "رقم",
+            Value::Number(_) | Value::Int(_) => "رقم",
             Value::String(_) => "سلسلة",
             Value::List(_) => "قائمة",
             Value::Map(_) => "قاموس",
+            Value::Set(_) => "مجموعة",
+            Value::Bytes(_) => "بايتات",
         }
     }
 }
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use unicode_width::UnicodeWidthStr;
 
-#[derive(Debug, Clone, PartialEq)]
+/// `Int` and `Number` are both numbers to the language: arithmetic between two
+/// `Int`s stays exact, and mixing in a `Number` promotes the result to `Number`.
+/// Serialized with the variant as tag, e.g. `{"Int": 1}`, so saving and loading
+/// keeps `Int` and `Number` apart. `Bytes` are saved as base64 text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Value {
     Nil,
     Bool(bool),
     Number(f64),
+    Int(i64),
     String(String),
     List(Vec<Value>),
     Map(HashMap<String, Value>),
+    Set(ValueSet),
+    /// Binary data, such as a file read with `اقرأ_بايتات`.
+    Bytes(#[serde(with = "base64_bytes")] Vec<u8>),
+}
+
+/// Serializes `Value::Bytes` as base64 text rather than a list of numbers.
+mod base64_bytes {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        STANDARD.decode(text).map_err(serde::de::Error::custom)
+    }
+}
+
+/// How many bytes `Value::Bytes` shows before `…`.
+const BYTES_PREVIEW: usize = 8;
+
+/// The identity of a set member. `Int` and integral `Number`s share a key so
+/// `١` and `١٫٠` are the same member, as they are equal values.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SetKey {
+    Nil,
+    Bool(bool),
+    Int(i64),
+    Float(u64),
+    String(String),
+}
+
+impl SetKey {
+    fn of(value: &Value) -> Option<SetKey> {
+        match value {
+            Value::Nil => Some(SetKey::Nil),
+            Value::Bool(b) => Some(SetKey::Bool(*b)),
+            Value::Int(n) => Some(SetKey::Int(*n)),
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < i64::MAX as f64 => {
+                Some(SetKey::Int(*n as i64))
+            }
+            Value::Number(n) => Some(SetKey::Float(n.to_bits())),
+            Value::String(s) => Some(SetKey::String(s.clone())),
+            Value::List(_) | Value::Map(_) | Value::Set(_) | Value::Bytes(_) => None,
+        }
+    }
+}
+
+/// مجموعة | A set that keeps members in insertion order. Members are nil,
+/// booleans, numbers and strings; lists, maps, sets and bytes can't be members.
+#[derive(Debug, Clone, Default)]
+pub struct ValueSet {
+    items: Vec<Value>,
+    keys: HashSet<SetKey>,
+}
+
+impl ValueSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a set from `values`, dropping repeats and keeping first occurrences.
+    pub fn from_values<'a>(values: impl IntoIterator<Item = &'a Value>) -> Result<ValueSet> {
+        let mut set = ValueSet::new();
+        for value in values {
+            set.insert(value)?;
+        }
+        Ok(set)
+    }
+
+    /// Adds `value`, returning whether it was new. Fails for lists, maps and sets.
+    pub fn insert(&mut self, value: &Value) -> Result<bool> {
+        let key = SetKey::of(value).ok_or_else(|| anyhow!(IqraError {
+            kind: "عنصر غير صالح للمجموعة | Invalid Set Member".to_string(),
+            message_ar: format!("لا يمكن وضع {} في مجموعة", value.type_name_ar()),
+            message_en: format!("A {} cannot be a set member", value.type_name()),
+            suggestion: Some(tr!("suggest.set_members")),
+            line: None,
+            column: None,
+            source_name: None,
+        }))?;
+        if !self.keys.insert(key) {
+            return Ok(false);
+        }
+        self.items.push(value.clone());
+        Ok(true)
+    }
+
+    pub fn contains(&self, value: &Value) -> bool {
+        SetKey::of(value).is_some_and(|key| self.keys.contains(&key))
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Members in insertion order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Value> {
+        self.items.iter()
+    }
+
+    /// Members of `self` followed by the members of `other` not in `self`.
+    pub fn union(&self, other: &ValueSet) -> ValueSet {
+        let mut set = self.clone();
+        for value in other.iter() {
+            // Members of another set are always valid members.
+            let _ = set.insert(value);
+        }
+        set
+    }
+
+    pub fn intersection(&self, other: &ValueSet) -> ValueSet {
+        self.filtered(|value| other.contains(value))
+    }
+
+    pub fn difference(&self, other: &ValueSet) -> ValueSet {
+        self.filtered(|value| !other.contains(value))
+    }
+
+    fn filtered(&self, keep: impl Fn(&Value) -> bool) -> ValueSet {
+        let mut set = ValueSet::new();
+        for value in self.iter().filter(|value| keep(value)) {
+            let _ = set.insert(value);
+        }
+        set
+    }
+}
+
+/// Serialized as the list of members.
+impl Serialize for ValueSet {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for ValueSet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let items = Vec::<Value>::deserialize(deserializer)?;
+        ValueSet::from_values(&items).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Two sets are equal when they have the same members, in any order.
+impl PartialEq for ValueSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|value| other.contains(value))
+    }
 }
 
 impl Value {
@@ -92,14 +270,20 @@ impl Value {
         matches!(self, Value::Nil)
     }
 
+    /// Whether the value counts as true in a condition: everything but nil,
+    /// `خطأ`, zero, and an empty string, list, map, set or byte string.
+    /// `إلى_منطقي` exposes the same rule.
     pub fn is_truthy(&self) -> bool {
         match self {
             Value::Nil => false,
             Value::Bool(b) => *b,
             Value::Number(n) => *n != 0.0,
+            Value::Int(n) => *n != 0,
             Value::String(s) => !s.is_empty(),
             Value::List(l) => !l.is_empty(),
             Value::Map(m) => !m.is_empty(),
+            Value::Set(s) => !s.is_empty(),
+            Value::Bytes(b) => !b.is_empty(),
         }
     }
 
@@ -107,20 +291,39 @@ impl Value {
         match self {
             Value::Nil => "nil",
             Value::Bool(_) => "bool",
-            Value::Number(_) => "number",
+            Value::Number(_) | Value::Int(_) => "number",
             Value::String(_) => "string",
             Value::List(_) => "list",
             Value::Map(_) => "map",
+            Value::Set(_) => "set",
+            Value::Bytes(_) => "bytes",
         }
     }
 
+    /// Returns the value as `f64` for either numeric variant.
     pub fn as_number(&self) -> Option<f64> {
         match self {
             Value::Number(n) => Some(*n),
+            Value::Int(n) => Some(*n as f64),
             _ => None,
         }
     }
 
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Value::Int(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Orders two numeric values, comparing `Int`s exactly.
+    pub fn compare_numbers(&self, other: &Value) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+            _ => self.as_number()?.partial_cmp(&other.as_number()?),
+        }
+    }
+
     pub fn as_string(&self) -> Option<&str> {
         match self {
             Value::String(s) => Some(s),
@@ -141,20 +344,230 @@ impl Value {
             _ => None,
         }
     }
+
+    /// Renders the value for the REPL: lists and maps with more than one
+    /// element go on indented lines, starting at nesting level `indent`.
+    pub fn pretty(&self, indent: usize) -> String {
+        self.pretty_with(indent, &PrettyLimits::default())
+    }
+
+    /// Like [`Value::pretty`], but collections deeper than `limits.max_depth`
+    /// or longer than `limits.max_items`, and strings longer than
+    /// `limits.max_chars`, are cut short with `…`.
+    pub fn pretty_with(&self, indent: usize, limits: &PrettyLimits) -> String {
+        let mut out = String::new();
+        self.write_pretty(&mut out, indent, 0, limits);
+        out
+    }
+
+    fn write_pretty(&self, out: &mut String, indent: usize, depth: usize, limits: &PrettyLimits) {
+        let (open, close, entries): (&str, &str, Vec<(Option<&str>, &Value)>) = match self {
+            Value::List(l) => ("[", "]", l.iter().map(|v| (None, v)).collect()),
+            Value::Set(s) => ("مجموعة{", "}", s.iter().map(|v| (None, v)).collect()),
+            Value::Map(m) => {
+                let mut entries: Vec<_> = m.iter().map(|(k, v)| (Some(k.as_str()), v)).collect();
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+                ("{", "}", entries)
+            }
+            Value::String(text) => {
+                match text.char_indices().nth(limits.max_chars) {
+                    Some((end, _)) => {
+                        let total = text.chars().count();
+                        let length = (arabic_digits(total), total.to_string());
+                        let note = tr!("repl.text_length", length = Word(&length.0, &length.1));
+                        out.push_str(&format!("{}… ({})", &text[..end], note));
+                    }
+                    None => out.push_str(text),
+                }
+                return;
+            }
+            _ => {
+                out.push_str(&format!("{}", self));
+                return;
+            }
+        };
+        if !entries.is_empty() && depth >= limits.max_depth {
+            out.push_str(&format!("{}…{}", open, close));
+            return;
+        }
+        let write_entry = |out: &mut String, key: Option<&str>, value: &Value, indent: usize| {
+            if let Some(key) = key {
+                out.push_str(&format!("{}: ", key));
+            }
+            value.write_pretty(out, indent, depth + 1, limits);
+        };
+        if entries.len() <= 1 {
+            out.push_str(open);
+            for (key, value) in entries {
+                write_entry(out, key, value, indent);
+            }
+            out.push_str(close);
+            return;
+        }
+
+        let pad = "  ".repeat(indent + 1);
+        out.push_str(open);
+        out.push('\n');
+        let shown = entries.len().min(limits.max_items);
+        for (i, (key, value)) in entries.iter().take(shown).enumerate() {
+            out.push_str(&pad);
+            write_entry(out, *key, value, indent + 1);
+            if i + 1 < entries.len() {
+                out.push(',');
+            }
+            out.push('\n');
+        }
+        if shown < entries.len() {
+            let hidden = entries.len() - shown;
+            let count = (arabic_digits(hidden), hidden.to_string());
+            let more = tr!("repl.more_items", count = Word(&count.0, &count.1));
+            out.push_str(&format!("{}{}\n", pad, more));
+        }
+        out.push_str(&"  ".repeat(indent));
+        out.push_str(close);
+    }
+}
+
+/// حدود العرض | How much of a nested value [`Value::pretty_with`] shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrettyLimits {
+    /// Collections nested deeper than this are shown as `[…]` or `{…}`.
+    pub max_depth: usize,
+    /// Elements after this many are summarized as `… and N more`, in the
+    /// language set with `--lang`.
+    pub max_items: usize,
+    /// Strings longer than this many characters end in `…` and their length.
+    pub max_chars: usize,
+}
+
+impl PrettyLimits {
+    /// No limits, for `:كامل` in the REPL.
+    pub const UNLIMITED: PrettyLimits =
+        PrettyLimits { max_depth: usize::MAX, max_items: usize::MAX, max_chars: usize::MAX };
+}
+
+impl Default for PrettyLimits {
+    fn default() -> Self {
+        PrettyLimits { max_depth: 6, max_items: 50, max_chars: 1000 }
+    }
+}
+
+/// `n` written with Arabic-Indic digits, as in `٩٩٩٥٠`.
+fn arabic_digits(n: usize) -> String {
+    n.to_string()
+        .chars()
+        .map(|d| char::from_u32(d as u32 - '0' as u32 + '٠' as u32).unwrap_or(d))
+        .collect()
+}
+
+/// عرض النص | The number of terminal columns `text` takes: Arabic and Latin
+/// letters take one, combining marks such as harakat none, and wide (CJK)
+/// characters two.
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+static INTERACTIVE_DISPLAY: AtomicBool = AtomicBool::new(false);
+
+/// Marks the process as the REPL, where nil shows both names when both
+/// languages are on. Scripts leave it off so their output stays the same.
+pub fn set_interactive_display(interactive: bool) {
+    INTERACTIVE_DISPLAY.store(interactive, Ordering::Relaxed);
+}
+
+fn interactive_display() -> bool {
+    INTERACTIVE_DISPLAY.load(Ordering::Relaxed)
+}
+
+/// How nil is shown: `فارغ` in Arabic, `nil` in English, and with both
+/// languages `فارغ|nil` in the REPL but `فارغ` in scripts.
+pub fn nil_text(language: ErrorLanguage, interactive: bool) -> &'static str {
+    match language {
+        ErrorLanguage::Arabic => "فارغ",
+        ErrorLanguage::English => "nil",
+        ErrorLanguage::Both if interactive => "فارغ|nil",
+        ErrorLanguage::Both => "فارغ",
+    }
+}
+
+/// Formats a float for display in the shortest form that reads back as the
+/// same value, so distinct numbers never print alike. `٠٫١ + ٠٫٢` therefore
+/// prints `0.30000000000000004`; `نسق_رقم` rounds on request.
+pub fn format_float(n: f64) -> String {
+    if n == 0.0 {
+        // No `-0`.
+        return "0".to_string();
+    }
+    format!("{}", n)
+}
+
+/// Formats `value` with a fixed number of `decimals`, grouping the integer
+/// part in threes with `separator` when one is given.
+pub fn format_number(value: &Value, decimals: usize, separator: Option<&str>) -> Option<String> {
+    let (negative, digits) = match value {
+        Value::Int(n) => {
+            let mut digits = n.unsigned_abs().to_string();
+            if decimals > 0 {
+                digits.push('.');
+                digits.push_str(&"0".repeat(decimals));
+            }
+            (*n < 0, digits)
+        }
+        Value::Number(n) if !n.is_finite() => return Some(n.to_string()),
+        Value::Number(n) => (*n < 0.0, format!("{:.*}", decimals, n.abs())),
+        _ => return None,
+    };
+    let (int_part, frac_part) = match digits.split_once('.') {
+        Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+        None => (digits.as_str(), None),
+    };
+
+    let mut out = String::new();
+    if negative && digits.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        out.push('-');
+    }
+    for (i, ch) in int_part.chars().enumerate() {
+        if let Some(sep) = separator
+            && i > 0
+            && (int_part.len() - i) % 3 == 0
+        {
+            out.push_str(sep);
+        }
+        out.push(ch);
+    }
+    if let Some(frac_part) = frac_part {
+        out.push('.');
+        out.push_str(frac_part);
+    }
+    Some(out)
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Nil, Value::Nil) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Number(_) | Value::Int(_), Value::Number(_) | Value::Int(_)) => {
+                self.as_number() == other.as_number()
+            }
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::List(a), Value::List(b)) => a == b,
+            (Value::Map(a), Value::Map(b)) => a == b,
+            (Value::Set(a), Value::Set(b)) => a == b,
+            (Value::Bytes(a), Value::Bytes(b)) => a == b,
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Value::Nil => write!(f, "فارغ"),
+            Value::Nil => write!(f, "{}", nil_text(ErrorLanguage::current(), interactive_display())),
             Value::Bool(b) => write!(f, "{}", if *b { "صحيح" } else { "خطأ" }),
-            Value::Number(n) => {
-                if n.fract() == 0.0 {
-                    write!(f, "{}", *n as i64)
-                } else {
-                    write!(f, "{}", n)
-                }
-            }
+            Value::Number(n) => write!(f, "{}", format_float(*n)),
+            Value::Int(n) => write!(f, "{}", n),
             Value::String(s) => write!(f, "{}", s),
             Value::List(l) => {
                 write!(f, "[")?;
@@ -167,17 +580,39 @@ impl fmt::Display for Value {
                 write!(f, "]")
             }
             Value::Map(m) => {
+                // Sorted by key, so the same map always prints the same way.
+                let mut entries: Vec<_> = m.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
                 write!(f, "{{")?;
-                let mut first = true;
-                for (k, v) in m {
-                    if !first {
+                for (i, (k, v)) in entries.into_iter().enumerate() {
+                    if i > 0 {
                         write!(f, ", ")?;
                     }
                     write!(f, "{}: {}", k, v)?;
-                    first = false;
                 }
                 write!(f, "}}")
             }
+            Value::Set(s) => {
+                write!(f, "مجموعة{{")?;
+                for (i, v) in s.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                write!(f, "}}")
+            }
+            Value::Bytes(b) if b.is_empty() => write!(f, "<bytes: 0>"),
+            Value::Bytes(b) => {
+                write!(f, "<bytes: {}, أول:", b.len())?;
+                for byte in b.iter().take(BYTES_PREVIEW) {
+                    write!(f, " {:02x}", byte)?;
+                }
+                if b.len() > BYTES_PREVIEW {
+                    write!(f, " …")?;
+                }
+                write!(f, ">")
+            }
         }
     }
 }
@@ -196,7 +631,13 @@ impl From<f64> for Value {
 
 impl From<i32> for Value {
     fn from(n: i32) -> Self {
-        Value::Number(n as f64)
+        Value::Int(n as i64)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Value::Int(n)
     }
 }
 
@@ -212,14 +653,260 @@ impl From<&str> for Value {
     }
 }
 
+impl From<Vec<u8>> for Value {
+    fn from(b: Vec<u8>) -> Self {
+        Value::Bytes(b)
+    }
+}
+
 impl From<Vec<Value>> for Value {
     fn from(l: Vec<Value>) -> Self {
         Value::List(l)
     }
 }
 
+impl From<ValueSet> for Value {
+    fn from(s: ValueSet) -> Self {
+        Value::Set(s)
+    }
+}
+
 impl From<HashMap<String, Value>> for Value {
     fn from(m: HashMap<String, Value>) -> Self {
         Value::Map(m)
     }
 }
+
+/// The error returned when a `Value` doesn't convert to the requested Rust
+/// type. `expected_ar` and `expected_en` name that type.
+fn conversion_error(value: &Value, expected_ar: &str, expected_en: &str) -> anyhow::Error {
+    anyhow!(IqraError {
+        kind: "تحويل غير صالح | Invalid Conversion".to_string(),
+        message_ar: format!("لا يمكن تحويل {} إلى {}", value.type_name_ar(), expected_ar),
+        message_en: format!("Cannot convert {} to {}", value.type_name(), expected_en),
+        suggestion: None,
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+/// Accepts both numeric variants.
+impl TryFrom<&Value> for f64 {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Value) -> Result<f64> {
+        value.as_number().ok_or_else(|| conversion_error(value, "رقم", "f64"))
+    }
+}
+
+/// Accepts `Int`, and `Number`s with no fractional part that fit in an `i64`.
+impl TryFrom<&Value> for i64 {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Value) -> Result<i64> {
+        match value {
+            Value::Int(n) => Ok(*n),
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < i64::MAX as f64 => Ok(*n as i64),
+            _ => Err(conversion_error(value, "عدد صحيح", "i64")),
+        }
+    }
+}
+
+impl TryFrom<&Value> for bool {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Value) -> Result<bool> {
+        match value {
+            Value::Bool(b) => Ok(*b),
+            _ => Err(conversion_error(value, "منطقي", "bool")),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a Value> for &'a str {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &'a Value) -> Result<&'a str> {
+        value.as_string().ok_or_else(|| conversion_error(value, "سلسلة", "string"))
+    }
+}
+
+impl<'a> TryFrom<&'a Value> for &'a Vec<Value> {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &'a Value) -> Result<&'a Vec<Value>> {
+        value.as_list().ok_or_else(|| conversion_error(value, "قائمة", "list"))
+    }
+}
+
+impl<'a> TryFrom<&'a Value> for &'a HashMap<String, Value> {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &'a Value) -> Result<&'a HashMap<String, Value>> {
+        value.as_map().ok_or_else(|| conversion_error(value, "قاموس", "map"))
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Value) -> Result<f64> {
+        f64::try_from(&value)
+    }
+}
+
+impl TryFrom<Value> for i64 {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Value) -> Result<i64> {
+        i64::try_from(&value)
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Value) -> Result<bool> {
+        bool::try_from(&value)
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Value) -> Result<String> {
+        match value {
+            Value::String(s) => Ok(s),
+            other => Err(conversion_error(&other, "سلسلة", "string")),
+        }
+    }
+}
+
+impl TryFrom<Value> for Vec<Value> {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Value) -> Result<Vec<Value>> {
+        match value {
+            Value::List(l) => Ok(l),
+            other => Err(conversion_error(&other, "قائمة", "list")),
+        }
+    }
+}
+
+impl TryFrom<Value> for HashMap<String, Value> {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Value) -> Result<HashMap<String, Value>> {
+        match value {
+            Value::Map(m) => Ok(m),
+            other => Err(conversion_error(&other, "قاموس", "map")),
+        }
+    }
+}
+
+/// One step of a path given to [`Value::get_path`].
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+fn path_error(path: &str, message_ar: String, message_en: String) -> anyhow::Error {
+    anyhow!(IqraError {
+        kind: "مسار غير صالح | Invalid Path".to_string(),
+        message_ar: format!("{} (في المسار '{}')", message_ar, path),
+        message_en: format!("{} (in path '{}')", message_en, path),
+        suggestion: Some(tr!("suggest.example_path")),
+        line: None,
+        column: None,
+        source_name: None,
+    })
+}
+
+/// Splits `a.b[2].c` into keys and list indexes.
+fn parse_path(path: &str) -> Result<Vec<PathSegment>> {
+    let mut segments = Vec::new();
+    for part in path.split('.') {
+        let (key, mut indexes) = part.split_once('[').unwrap_or((part, ""));
+        if !key.is_empty() {
+            segments.push(PathSegment::Key(key.to_string()));
+        } else if indexes.is_empty() {
+            return Err(path_error(path, "جزء فارغ في المسار".to_string(), "Empty path segment".to_string()));
+        }
+        while !indexes.is_empty() {
+            let (index, rest) = indexes.split_once(']').ok_or_else(|| {
+                path_error(path, "قوس ] مفقود".to_string(), "Missing ]".to_string())
+            })?;
+            let index = index.trim().parse::<usize>().map_err(|_| {
+                path_error(
+                    path,
+                    format!("فهرس غير صالح: {}", index),
+                    format!("Invalid index: {}", index),
+                )
+            })?;
+            segments.push(PathSegment::Index(index));
+            indexes = match rest.strip_prefix('[') {
+                Some(next) => next,
+                None if rest.is_empty() => "",
+                None => {
+                    return Err(path_error(
+                        path,
+                        format!("نص غير متوقع بعد الفهرس: {}", rest),
+                        format!("Unexpected text after index: {}", rest),
+                    ));
+                }
+            };
+        }
+    }
+    Ok(segments)
+}
+
+impl Value {
+    /// Follows a path of map keys and list indexes, such as `a.b[2].c`, into
+    /// nested values.
+    ///
+    /// ```
+    /// use iqra::lang::runtime::Runtime;
+    ///
+    /// let result = Runtime::new()
+    ///     .execute(r#"قاموس("اسم", "سارة", "درجات", [90, 85, 77])"#)
+    ///     .unwrap();
+    /// let name: &str = result.get_path("اسم").unwrap().try_into().unwrap();
+    /// let second: i64 = result.get_path("درجات[1]").unwrap().try_into().unwrap();
+    /// assert_eq!((name, second), ("سارة", 85));
+    /// assert!(result.get_path("درجات[5]").is_err());
+    /// ```
+    pub fn get_path(&self, path: &str) -> Result<&Value> {
+        let mut current = self;
+        for segment in parse_path(path)? {
+            current = match (&segment, current) {
+                (PathSegment::Key(key), Value::Map(map)) => map.get(key).ok_or_else(|| {
+                    path_error(path, format!("المفتاح غير موجود: {}", key), format!("Key not found: {}", key))
+                })?,
+                (PathSegment::Index(index), Value::List(list)) => list.get(*index).ok_or_else(|| {
+                    path_error(
+                        path,
+                        format!("الفهرس خارج النطاق: {}", index),
+                        format!("Index out of bounds: {}", index),
+                    )
+                })?,
+                (PathSegment::Key(key), other) => {
+                    return Err(path_error(
+                        path,
+                        format!("لا يمكن جلب المفتاح '{}' من {}", key, other.type_name_ar()),
+                        format!("Cannot get key '{}' from a {}", key, other.type_name()),
+                    ));
+                }
+                (PathSegment::Index(index), other) => {
+                    return Err(path_error(
+                        path,
+                        format!("لا يمكن جلب الفهرس {} من {}", index, other.type_name_ar()),
+                        format!("Cannot get index {} from a {}", index, other.type_name()),
+                    ));
+                }
+            };
+        }
+        Ok(current)
+    }
+}
@@ -1,4 +1,8 @@
 pub mod cli;
+pub mod i18n;
 pub mod lang;
+pub mod lsp;
+pub mod paths;
+pub mod pkg;
 
 pub use lang::runtime::Runtime;
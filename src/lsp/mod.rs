@@ -0,0 +1,347 @@
+//! خادم اللغة | A minimal Language Server Protocol server for `.iqra` files,
+//! speaking JSON-RPC over stdio (`iqra lsp`).
+//!
+//! It offers diagnostics from the parser and the analyzer, completion of
+//! keywords, builtins and the document's own functions, and hover text.
+
+use crate::lang::analyzer::analyze_with_comments;
+use crate::lang::builtins::{BuiltinDoc, builtin_doc};
+use crate::lang::lexer::{KEYWORDS, Lexer, Token};
+use crate::lang::parser::Parser;
+use crate::lang::runtime::{BUILTIN_FUNCTIONS, ErrorLanguage};
+use crate::tr;
+use anyhow::{Result, bail};
+use serde_json::{Value as Json, json};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Completion item kinds from the LSP specification.
+const KIND_FUNCTION: u8 = 3;
+const KIND_KEYWORD: u8 = 14;
+
+/// خادم اللغة | Open documents and the protocol handlers.
+#[derive(Debug, Default)]
+pub struct LanguageServer {
+    documents: HashMap<String, String>,
+    exited: bool,
+}
+
+impl LanguageServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the client sent `exit`.
+    pub fn has_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Handles one incoming message and returns the messages to send back:
+    /// a response for requests, plus any notifications such as diagnostics.
+    pub fn handle_message(&mut self, message: &Json) -> Vec<Json> {
+        let method = message.get("method").and_then(Json::as_str).unwrap_or_default();
+        let params = message.get("params").cloned().unwrap_or(Json::Null);
+        let id = message.get("id").cloned();
+
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "completionProvider": {},
+                    "hoverProvider": true,
+                },
+                "serverInfo": { "name": "iqra", "version": env!("CARGO_PKG_VERSION") },
+            }),
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                return self.update(
+                    document["uri"].as_str().unwrap_or_default(),
+                    document["text"].as_str(),
+                );
+            }
+            "textDocument/didChange" => {
+                // Full synchronization: the last change holds the whole text.
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|c| c.last())
+                    .and_then(|c| c["text"].as_str());
+                return self
+                    .update(params["textDocument"]["uri"].as_str().unwrap_or_default(), text);
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                self.documents.remove(uri);
+                return vec![publish_diagnostics(uri, Vec::new())];
+            }
+            "textDocument/completion" => {
+                self.completion(params["textDocument"]["uri"].as_str().unwrap_or_default())
+            }
+            "textDocument/hover" => self.hover(&params),
+            "shutdown" => Json::Null,
+            "exit" => {
+                self.exited = true;
+                return Vec::new();
+            }
+            _ => match id {
+                Some(id) => {
+                    return vec![json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": METHOD_NOT_FOUND, "message": format!("Unknown method '{}'", method) },
+                    })];
+                }
+                // Notifications we don't handle, such as `initialized`.
+                None => return Vec::new(),
+            },
+        };
+        match id {
+            Some(id) => vec![json!({ "jsonrpc": "2.0", "id": id, "result": result })],
+            None => Vec::new(),
+        }
+    }
+
+    fn update(&mut self, uri: &str, text: Option<&str>) -> Vec<Json> {
+        let Some(text) = text else {
+            return Vec::new();
+        };
+        self.documents.insert(uri.to_string(), text.to_string());
+        vec![publish_diagnostics(uri, diagnostics(text))]
+    }
+
+    fn completion(&self, uri: &str) -> Json {
+        let source = self.documents.get(uri).map(String::as_str).unwrap_or_default();
+        let mut items: Vec<Json> = KEYWORDS
+            .iter()
+            .map(|keyword| json!({ "label": keyword, "kind": KIND_KEYWORD }))
+            .collect();
+        items.extend(BUILTIN_FUNCTIONS.iter().map(|name| {
+            json!({ "label": name, "kind": KIND_FUNCTION, "detail": "دالة مدمجة | builtin function" })
+        }));
+        items.extend(user_functions(source).into_iter().map(|(name, params)| {
+            json!({ "label": name, "kind": KIND_FUNCTION, "detail": format!("دالة {}({})", name, params.join("، ")) })
+        }));
+        Json::Array(items)
+    }
+
+    fn hover(&self, params: &Json) -> Json {
+        let source = self.documents.get(params["textDocument"]["uri"].as_str().unwrap_or_default());
+        let (line, character) =
+            (params["position"]["line"].as_u64(), params["position"]["character"].as_u64());
+        let (Some(source), Some(line), Some(character)) = (source, line, character) else {
+            return Json::Null;
+        };
+        let Some(word) = word_at(source, line as usize, character as usize) else {
+            return Json::Null;
+        };
+
+        let text = if let Some((_, params)) =
+            user_functions(source).into_iter().find(|(name, _)| *name == word)
+        {
+            format!("```iqra\nدالة {}({})\n```", word, params.join("، "))
+        } else if let Some(doc) = builtin_doc(&word) {
+            builtin_hover(doc, ErrorLanguage::current())
+        } else if KEYWORDS.contains(&word.as_str()) {
+            format!("**{}**\n\nكلمة محجوزة | keyword", word)
+        } else {
+            return Json::Null;
+        };
+        json!({ "contents": { "kind": "markdown", "value": text } })
+    }
+}
+
+/// A builtin's names, description and parameters as hover markdown, in
+/// `language`.
+fn builtin_hover(doc: &BuiltinDoc, language: ErrorLanguage) -> String {
+    let mut text = format!("**{}** / **{}**\n\n", doc.arabic, doc.english);
+    match language {
+        ErrorLanguage::Arabic => text.push_str(doc.description_ar),
+        ErrorLanguage::English => text.push_str(doc.description_en),
+        ErrorLanguage::Both => {
+            text.push_str(&format!("{}\n\n{}", doc.description_ar, doc.description_en))
+        }
+    }
+    if !doc.params.is_empty() {
+        // Each parameter is written "Arabic | English".
+        let params: Vec<String> = doc
+            .params
+            .iter()
+            .map(|param| match param.split_once(" | ") {
+                Some((ar, en)) => language.pick(ar, en),
+                None => param.to_string(),
+            })
+            .map(|param| format!("`{}`", param))
+            .collect();
+        text.push_str(&format!("\n\n{}: {}", tr!("label.params"), params.join(", ")));
+    }
+    text
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Json>) -> Json {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+/// Parse errors and analyzer warnings for `source`, each covering the
+/// trimmed text of its line.
+pub fn diagnostics(source: &str) -> Vec<Json> {
+    let language = ErrorLanguage::current();
+    let mut parser = Parser::new(Lexer::new(source));
+    let (statements, errors) = parser.parse_with_recovery();
+    if errors.is_empty() {
+        analyze_with_comments(&statements, parser.comments())
+            .iter()
+            .map(|warning| {
+                diagnostic(
+                    source,
+                    warning.line,
+                    None,
+                    SEVERITY_WARNING,
+                    warning.message_in(language),
+                )
+            })
+            .collect()
+    } else {
+        errors
+            .iter()
+            .map(|err| {
+                let line = err.line.unwrap_or(1);
+                diagnostic(source, line, err.column, SEVERITY_ERROR, err.message_in(language))
+            })
+            .collect()
+    }
+}
+
+/// A diagnostic from `column` (1-based, in characters) to the end of the
+/// line, or over the whole trimmed line when there is no column.
+fn diagnostic(
+    source: &str,
+    line: usize,
+    column: Option<usize>,
+    severity: u8,
+    message: String,
+) -> Json {
+    let text = source.lines().nth(line.saturating_sub(1)).unwrap_or_default();
+    let indent = text.len() - text.trim_start().len();
+    let offset = match column {
+        Some(column) => {
+            text.char_indices().nth(column.saturating_sub(1)).map_or(text.len(), |(at, _)| at)
+        }
+        None => indent,
+    };
+    let start = utf16_len(&text[..offset]);
+    let end = utf16_len(text.trim_end()).max(start);
+    let line = line.saturating_sub(1);
+    json!({
+        "range": {
+            "start": { "line": line, "character": start },
+            "end": { "line": line, "character": end },
+        },
+        "severity": severity,
+        "source": "iqra",
+        "message": message,
+    })
+}
+
+/// LSP columns count UTF-16 code units.
+fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// Names and parameters of the functions defined in `source`. Works from the
+/// token stream so it still finds functions while the document has errors.
+pub fn user_functions(source: &str) -> Vec<(String, Vec<String>)> {
+    let tokens: Vec<Token> = Lexer::new(source).filter_map(|token| Some(token.ok()?.0)).collect();
+
+    let mut functions = Vec::new();
+    for (i, window) in tokens.windows(2).enumerate() {
+        if let [Token::Function, Token::Identifier(name)] = window {
+            let params = tokens[i + 2..]
+                .iter()
+                .skip(1)
+                .take_while(|token| **token != Token::RightParen)
+                .filter_map(|token| match token {
+                    Token::Identifier(param) => Some(param.to_string()),
+                    _ => None,
+                })
+                .collect();
+            functions.push((name.to_string(), params));
+        }
+    }
+    functions
+}
+
+/// The identifier under a zero-based line and UTF-16 column.
+fn word_at(source: &str, line: usize, character: usize) -> Option<String> {
+    let text = source.lines().nth(line)?;
+    let chars: Vec<char> = text.chars().collect();
+    let mut units = 0;
+    let mut index = chars.len();
+    for (i, ch) in chars.iter().enumerate() {
+        if units + ch.len_utf16() > character {
+            index = i;
+            break;
+        }
+        units += ch.len_utf16();
+    }
+    let is_word = |ch: &char| ch.is_alphanumeric() || *ch == '_' || *ch == '؟';
+    // The cursor may sit just after the word.
+    if index == chars.len() || !is_word(&chars[index]) {
+        index = index.checked_sub(1).filter(|i| is_word(&chars[*i]))?;
+    }
+    let start = (0..index).rev().take_while(|i| is_word(&chars[*i])).last().unwrap_or(index);
+    let end = (index..chars.len()).take_while(|i| is_word(&chars[*i])).last()? + 1;
+    Some(chars[start..end].iter().collect())
+}
+
+/// Reads one `Content-Length` framed message. Returns `None` at end of input.
+pub fn read_message(reader: &mut impl BufRead) -> Result<Option<Json>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = Some(value.trim().parse::<usize>()?);
+        }
+    }
+    let Some(length) = length else {
+        bail!("Missing Content-Length header");
+    };
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+/// Writes one message with its `Content-Length` header.
+pub fn write_message(writer: &mut impl Write, message: &Json) -> Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Serves LSP requests from `input` until the client sends `exit`.
+pub fn serve(mut input: impl BufRead, mut output: impl Write) -> Result<()> {
+    let mut server = LanguageServer::new();
+    while let Some(message) = read_message(&mut input)? {
+        for reply in server.handle_message(&message) {
+            write_message(&mut output, &reply)?;
+        }
+        if server.has_exited() {
+            break;
+        }
+    }
+    Ok(())
+}
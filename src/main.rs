@@ -1,53 +1,104 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
-use iqra::cli::{run_file, run_repl};
+use std::process::ExitCode;
+use clap::Parser;
+use iqra::cli::args::{Cli, Commands, version_info, write_completions};
+use iqra::cli::bench::{BenchOptions, run_bench};
+use iqra::cli::doc::document;
+use iqra::cli::{RunOptions, check_file, join_code, run_code, run_file, run_repl, watch_file};
+use iqra::cli::serve::serve;
+use iqra::lang::runtime::{ErrorLanguage, Limits};
+use iqra::tr;
+use std::time::Duration;
 use tracing::Level;
 use tracing_subscriber::FmtSubscriber;
 
-#[derive(Parser)]
-#[command(name = "iqra")]
-#[command(about = "Iqra - Arabic-first scripting language", long_about = None)]
-struct Cli {
-    #[command(subcommand)]
-    command: Commands,
-}
-
-#[derive(Subcommand)]
-enum Commands {
-    /// Start interactive REPL
-    Repl,
-    /// Run a script file
-    Run {
-        /// Path to the script file
-        file: String,
-    },
-    /// Run code directly from command line
-    Code {
-        /// Code to execute
-        #[arg(short, long)]
-        code: String,
-    },
-}
-
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
     // Initialize tracing
     let subscriber = FmtSubscriber::builder().with_max_level(Level::INFO).finish();
     tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
 
     let cli = Cli::parse();
+    ErrorLanguage::set_current(cli.lang);
 
     match cli.command {
-        Commands::Repl => run_repl()?,
-        Commands::Run { file } => run_file(&file)?,
-        Commands::Code { code } => {
-            use iqra::lang::runtime::Runtime;
-            let mut runtime = Runtime::new();
-            let result = runtime.execute(&code)?;
-            if !result.is_nil() {
-                println!("{}", result);
+        Commands::Repl { preload, prompt } => run_repl(&preload, prompt.as_deref())?,
+        Commands::Run {
+            file,
+            no_warnings,
+            sandbox,
+            watch,
+            trace,
+            trace_limit,
+            profile,
+            no_optimize,
+            emit_state,
+            max_statements,
+            timeout_ms,
+            print_result,
+            auto_install,
+        } => {
+            let options = RunOptions {
+                warnings: !no_warnings,
+                sandbox,
+                trace: trace.then_some(trace_limit),
+                profile,
+                args: Vec::new(),
+                optimize: !no_optimize,
+                emit_state,
+                limits: Limits {
+                    max_statements,
+                    timeout: timeout_ms.map(Duration::from_millis),
+                    ..Limits::default()
+                },
+                print_result,
+                auto_install,
+            };
+            if !watch {
+                return run_file(&file, &options);
             }
+            watch_file(&file, || {
+                // Clear the screen, then show when this run started.
+                print!("\x1b[2J\x1b[H");
+                println!("[{}] {}", chrono::Local::now().format("%H:%M:%S"), file);
+                if let Err(err) = run_file(&file, &options) {
+                    eprintln!("{}: {}", tr!("label.error"), err);
+                }
+            })?;
+        }
+        Commands::Check { file } => return check_file(&file),
+        Commands::Doc { path, format } => print!("{}", document(&path, format)?),
+        Commands::Bench { file_or_code, iterations, warmup, json } => {
+            return run_bench(&file_or_code, &BenchOptions { iterations, warmup, json });
+        }
+        Commands::Serve { stdin_rpc, max_statements, timeout_ms } => {
+            if !stdin_rpc {
+                anyhow::bail!("serve currently supports only --stdin-rpc");
+            }
+            let limits = Limits {
+                max_statements: Some(max_statements),
+                timeout: Some(Duration::from_millis(timeout_ms)),
+                ..Limits::default()
+            };
+            serve(std::io::stdin().lock(), std::io::stdout().lock(), limits)?;
+        }
+        Commands::Lsp => iqra::lsp::serve(std::io::stdin().lock(), std::io::stdout().lock())?,
+        Commands::Completions { shell } => write_completions(shell, &mut std::io::stdout()),
+        Commands::Version => print!("{}", version_info()),
+        Commands::Code { code, source, escape, sandbox, trace, trace_limit } => {
+            // clap refuses `-c` together with a positional source, but keep
+            // every piece anyway rather than rely on it.
+            let pieces: Vec<String> = code.into_iter().chain(source).collect();
+            let code = join_code(&pieces, escape);
+            let options = RunOptions {
+                warnings: false,
+                sandbox,
+                trace: trace.then_some(trace_limit),
+                print_result: true,
+                ..RunOptions::default()
+            };
+            return run_code(&code, &options);
         }
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
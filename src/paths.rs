@@ -0,0 +1,87 @@
+//! المسارات | Where iqra keeps its files.
+//!
+//! `IQRA_HOME` (default `~/.iqra`) holds the REPL's `init.iqra`, under
+//! `packages/` the packages installed with `iqra-pkg install --global`, and
+//! under `cache/` the file behind the `خزّن`/`تذكر` builtins.
+//! Project-local packages live in `./iqra_packages`. Every function has an
+//! `_in` form that reads variables through a lookup function, so tests can
+//! supply their own environment.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Directory of project-local packages, relative to the project.
+pub const LOCAL_PACKAGES: &str = "iqra_packages";
+
+/// Manifest of installed packages, kept next to the packages directory.
+pub const INSTALL_MANIFEST: &str = "iqra-pkg.json";
+
+/// The user's home directory: `HOME`, then Windows' `USERPROFILE`, then
+/// `HOMEDRIVE` joined with `HOMEPATH`.
+pub fn home_dir() -> Option<PathBuf> {
+    home_dir_in(&|name| std::env::var_os(name))
+}
+
+pub fn home_dir_in(env: &dyn Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let non_empty = |name| env(name).filter(|value| !value.is_empty());
+    if let Some(home) = non_empty("HOME").or_else(|| non_empty("USERPROFILE")) {
+        return Some(PathBuf::from(home));
+    }
+    let mut home = PathBuf::from(non_empty("HOMEDRIVE")?);
+    // HOMEPATH starts with a separator, which `push` would treat as a root.
+    let path = non_empty("HOMEPATH")?;
+    home.push(path.to_string_lossy().trim_start_matches(['\\', '/']));
+    Some(home)
+}
+
+/// `IQRA_HOME`, or `.iqra` in the home directory.
+pub fn iqra_home() -> Option<PathBuf> {
+    iqra_home_in(&|name| std::env::var_os(name))
+}
+
+pub fn iqra_home_in(env: &dyn Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    match env("IQRA_HOME").filter(|value| !value.is_empty()) {
+        Some(home) => Some(PathBuf::from(home)),
+        None => Some(home_dir_in(env)?.join(".iqra")),
+    }
+}
+
+/// The file loaded at the start of every REPL session.
+pub fn init_file() -> Option<PathBuf> {
+    Some(iqra_home()?.join("init.iqra"))
+}
+
+/// Where `iqra-pkg install --global` puts packages.
+pub fn global_packages_dir() -> Option<PathBuf> {
+    global_packages_dir_in(&|name| std::env::var_os(name))
+}
+
+pub fn global_packages_dir_in(env: &dyn Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    Some(iqra_home_in(env)?.join("packages"))
+}
+
+/// Directories searched for an installed package, project-local first.
+pub fn package_search_path(project: &Path) -> Vec<PathBuf> {
+    package_search_path_in(project, &|name| std::env::var_os(name))
+}
+
+pub fn package_search_path_in(
+    project: &Path,
+    env: &dyn Fn(&str) -> Option<OsString>,
+) -> Vec<PathBuf> {
+    std::iter::once(project.join(LOCAL_PACKAGES)).chain(global_packages_dir_in(env)).collect()
+}
+
+/// The first installed copy of `package` along the search path.
+pub fn find_package(project: &Path, package: &str) -> Option<PathBuf> {
+    package_search_path(project).into_iter().map(|dir| dir.join(package)).find(|path| path.exists())
+}
+
+/// The file `خزّن` and `تذكر` keep cached values in.
+pub fn script_cache_file() -> Option<PathBuf> {
+    script_cache_file_in(&|name| std::env::var_os(name))
+}
+
+pub fn script_cache_file_in(env: &dyn Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    Some(iqra_home_in(env)?.join("cache").join("script_cache.json"))
+}
@@ -0,0 +1,634 @@
+//! مدير الحزم | Library code behind the `iqra-pkg` binary.
+//!
+//! Holds what the package manager needs beyond plain HTTP calls: parsing
+//! `search` arguments, reading the registry's search response into
+//! [`SearchResult`]s, rendering them as a table, finding the script
+//! `iqra-pkg run` executes in an installed package, packing packages for
+//! `publish`, parsing `yank` arguments, and downloading several packages at
+//! once. The registry itself is reached through [`RegistryClient`].
+
+pub mod registry;
+
+pub use registry::{
+    CHECKSUM_HEADER, FsRegistry, HttpRegistry, RegistryClient, registry_for, registry_url,
+};
+
+use crate::lang::analyzer::{closest_name, did_you_mean};
+use crate::lang::value::display_width;
+use crate::tr;
+use anyhow::{Result, anyhow};
+use owo_colors::{OwoColorize, Style};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+/// Name of the manifest inside an installed package's directory.
+pub const PACKAGE_MANIFEST: &str = "iqra-package.json";
+
+/// Name of the manifest at the root of a project, next to its
+/// `iqra_packages`.
+pub const PROJECT_MANIFEST: &str = "iqra.json";
+
+/// Entry point of a package whose manifest names none.
+pub const DEFAULT_ENTRY: &str = "main.iqra";
+
+/// Downloads running at once when `--jobs` isn't given.
+pub const DEFAULT_JOBS: usize = 4;
+
+/// Results per page when `--limit` isn't given.
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// نتيجة بحث | One package in the registry's search response. Only `name`
+/// is required; registries that leave out the rest still work.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SearchResult {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub downloads: Option<u64>,
+}
+
+/// The arguments of `iqra-pkg search <query> [--limit N] [--page M] [--exact]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchOptions {
+    pub query: String,
+    pub limit: usize,
+    pub page: usize,
+    /// Keep only packages named exactly `query`.
+    pub exact: bool,
+}
+
+impl SearchOptions {
+    /// Parses the arguments after `search`. Errors are messages in the
+    /// `--lang` language, ready to print.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut query = None;
+        let mut limit = DEFAULT_SEARCH_LIMIT;
+        let mut page = 1;
+        let mut exact = false;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--limit" | "--حد" => limit = count_flag(arg, args.next())?,
+                "--page" | "--صفحة" => page = count_flag(arg, args.next())?,
+                "--exact" | "--مطابق" => exact = true,
+                flag if flag.starts_with("--") => {
+                    return Err(tr!("pkg.unknown_option", option = flag));
+                }
+                _ if query.is_none() => query = Some(arg.clone()),
+                _ => return Err(tr!("pkg.unexpected_argument", arg = arg)),
+            }
+        }
+        let query = query.ok_or_else(|| tr!("pkg.specify_query"))?;
+        Ok(SearchOptions { query, limit, page, exact })
+    }
+
+    /// Query string pairs for the registry's `/packages` endpoint.
+    pub fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("q", self.query.clone()),
+            ("limit", self.limit.to_string()),
+            ("page", self.page.to_string()),
+        ]
+    }
+}
+
+/// Environment variable holding the registry token `yank` sends.
+pub const REGISTRY_TOKEN_VAR: &str = "IQRA_REGISTRY_TOKEN";
+
+/// The arguments of `iqra-pkg yank <package> --version X.Y.Z [--yes]`.
+/// Yanking withdraws a published version for everyone, so unlike `remove`
+/// it names the version explicitly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YankOptions {
+    pub package: String,
+    pub version: String,
+    /// Skip the confirmation prompt.
+    pub yes: bool,
+}
+
+impl YankOptions {
+    /// Parses the arguments after `yank`. Errors are messages in the
+    /// `--lang` language, ready to print.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut package = None;
+        let mut version = None;
+        let mut yes = false;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--version" | "--إصدار" => {
+                    let value = args.next().filter(|value| parse_version(value).is_some());
+                    let value = value.ok_or_else(|| tr!("pkg.flag_needs_version", flag = arg))?;
+                    version = Some(value.clone());
+                }
+                "--yes" | "-y" | "--نعم" => yes = true,
+                flag if flag.starts_with('-') => {
+                    return Err(tr!("pkg.unknown_option", option = flag));
+                }
+                _ if package.is_none() => package = Some(arg.clone()),
+                _ => return Err(tr!("pkg.unexpected_argument", arg = arg)),
+            }
+        }
+        let package = package.ok_or_else(|| tr!("pkg.specify_yank"))?;
+        let version = version.ok_or_else(|| tr!("pkg.specify_yank_version", package = package))?;
+        Ok(YankOptions { package, version, yes })
+    }
+}
+
+/// Whether `answer` to a yes/no prompt means yes.
+pub fn confirmed(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes" | "ن" | "نعم")
+}
+
+/// The positive number after `flag`.
+fn count_flag(flag: &str, value: Option<&String>) -> Result<usize, String> {
+    value
+        .and_then(|value| value.parse().ok())
+        .filter(|count| *count > 0)
+        .ok_or_else(|| tr!("pkg.flag_needs_count", flag = flag))
+}
+
+/// Reads the body of a search response: a JSON array of packages.
+pub fn parse_search_results(body: &str) -> Result<Vec<SearchResult>> {
+    serde_json::from_str(body).map_err(|e| anyhow!(tr!("pkg.invalid_response", error = e)))
+}
+
+/// Renders the results of a search for `options`: a table of name,
+/// version, author, downloads and description with the query highlighted
+/// in names and descriptions, or a "no results" line. When no package is
+/// named exactly like the query, a close name from the results is
+/// suggested. `--exact` leaves out every other package.
+pub fn render_search_results(
+    results: &[SearchResult],
+    options: &SearchOptions,
+    color: bool,
+) -> String {
+    let exact: Vec<&SearchResult> = results.iter().filter(|r| r.name == options.query).collect();
+    let shown: Vec<&SearchResult> =
+        if options.exact { exact.clone() } else { results.iter().collect() };
+    let mut out = String::new();
+    if shown.is_empty() {
+        out.push_str(&format!("{}\n", tr!("pkg.no_results")));
+    } else {
+        let highlight = if color { Style::new().yellow().bold() } else { Style::new() };
+        let header = [
+            tr!("pkg.column.name"),
+            tr!("pkg.column.version"),
+            tr!("pkg.column.author"),
+            tr!("pkg.column.downloads"),
+            tr!("pkg.column.description"),
+        ];
+        let rows: Vec<[String; 5]> = shown
+            .iter()
+            .map(|r| {
+                [
+                    r.name.clone(),
+                    r.version.clone().unwrap_or_else(|| "-".to_string()),
+                    r.author.clone().unwrap_or_else(|| "-".to_string()),
+                    r.downloads.map_or_else(|| "-".to_string(), |n| n.to_string()),
+                    r.description.clone(),
+                ]
+            })
+            .collect();
+        let mut widths = header.each_ref().map(|cell| display_width(cell));
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(display_width(cell));
+            }
+        }
+        out.push_str(&table_row(&header, &widths, |_, cell| cell.to_string()));
+        let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+        out.push_str(&table_row(&rule, &widths, |_, cell| cell.to_string()));
+        for row in &rows {
+            // Only the name and description are searched, so only they are highlighted.
+            out.push_str(&table_row(row, &widths, |column, cell| match column {
+                0 | 4 => highlight_match(cell, &options.query, highlight),
+                _ => cell.to_string(),
+            }));
+        }
+    }
+    if exact.is_empty() {
+        let names = results.iter().map(|r| r.name.as_str());
+        if let Some(name) = closest_name(&options.query, names) {
+            out.push_str(&format!("{}\n", did_you_mean(&name)));
+        }
+    }
+    out
+}
+
+/// One table line. Cells are padded by display width, so Arabic names and
+/// marks line up; `style` gets each column index and cell after the cell is
+/// measured. The last column isn't padded.
+fn table_row(
+    cells: &[String],
+    widths: &[usize; 5],
+    style: impl Fn(usize, &str) -> String,
+) -> String {
+    let last = cells.len() - 1;
+    let cells: Vec<String> = cells
+        .iter()
+        .enumerate()
+        .map(|(i, cell)| {
+            let padding = if i == last { 0 } else { widths[i] - display_width(cell) };
+            format!("{}{}", style(i, cell), " ".repeat(padding))
+        })
+        .collect();
+    format!("{}\n", cells.join("  ").trim_end())
+}
+
+/// `text` with every occurrence of `query` in `style`.
+fn highlight_match(text: &str, query: &str, style: Style) -> String {
+    if query.is_empty() {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(index) = rest.find(query) {
+        out.push_str(&rest[..index]);
+        out.push_str(&query.style(style).to_string());
+        rest = &rest[index + query.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// بيان الحزمة | The `iqra-package.json` of an installed package. Every
+/// field is optional, but publishing needs a version; paths are relative to
+/// the package directory.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct PackageManifest {
+    /// Defaults to the package's directory name.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub entry: Option<String>,
+    /// Named scripts, run with `iqra-pkg run <package> --script <name>`.
+    #[serde(default)]
+    pub scripts: BTreeMap<String, String>,
+    /// Packages the project needs, by name, with the version wanted. Only
+    /// presence is checked so far; the version is recorded for later.
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+}
+
+impl PackageManifest {
+    /// Reads the manifest in `package_dir`; a package without one gets the
+    /// default manifest.
+    pub fn load(package_dir: &Path) -> Result<Self> {
+        let path = package_dir.join(PACKAGE_MANIFEST);
+        if !path.exists() {
+            return Ok(PackageManifest::default());
+        }
+        PackageManifest::read(&path)
+    }
+
+    /// Reads the manifest file at `path`, an `iqra-package.json` or a
+    /// project's `iqra.json`.
+    pub fn read(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path)?;
+        serde_json::from_str(&data)
+            .map_err(|e| anyhow!(tr!("pkg.invalid_manifest", path = path.display(), error = e)))
+    }
+
+    /// The project `start` belongs to: the nearest directory, `start` or one
+    /// of its parents, holding an `iqra.json`.
+    pub fn find_project(start: &Path) -> Option<PathBuf> {
+        start.ancestors().find(|dir| dir.join(PROJECT_MANIFEST).is_file()).map(Path::to_path_buf)
+    }
+
+    /// The dependencies installed neither in `project`'s `iqra_packages` nor
+    /// globally, sorted by name.
+    pub fn missing_dependencies(&self, project: &Path) -> Vec<String> {
+        self.dependencies
+            .keys()
+            .filter(|name| crate::paths::find_package(project, name).is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// The metadata sent with `iqra-pkg publish` for the package in the
+    /// directory `package`.
+    pub fn metadata(&self, package: &str) -> Result<PackageMetadata> {
+        let version = self.version.clone().ok_or_else(|| {
+            anyhow!(tr!("pkg.manifest_needs_version", manifest = PACKAGE_MANIFEST))
+        })?;
+        if parse_version(&version).is_none() {
+            return Err(anyhow!(tr!("pkg.invalid_version", version = version)));
+        }
+        Ok(PackageMetadata {
+            name: self.name.clone().unwrap_or_else(|| package.to_string()),
+            version,
+            author: self.author.clone().unwrap_or_default(),
+            description: self.description.clone().unwrap_or_default(),
+            lang: "ar".to_string(),
+            checksum: None,
+        })
+    }
+
+    /// What the manifest declares, for error messages.
+    fn describe(&self) -> String {
+        let mut lines = vec![
+            tr!("pkg.manifest_declares"),
+            format!("  entry: {}", self.entry.as_deref().unwrap_or(DEFAULT_ENTRY)),
+        ];
+        lines.extend(
+            self.scripts.iter().map(|(name, path)| format!("  scripts.{}: {}", name, path)),
+        );
+        lines.join("\n")
+    }
+}
+
+/// The file `iqra-pkg run` executes for the package installed at
+/// `package_path`: the named script when `script` is given, otherwise the
+/// manifest's entry. A package installed as a single file is its own entry.
+pub fn resolve_entry(package_path: &Path, script: Option<&str>) -> Result<PathBuf> {
+    if package_path.is_file() && script.is_none() {
+        return Ok(package_path.to_path_buf());
+    }
+    if !package_path.is_dir() {
+        return Err(anyhow!(tr!("pkg.not_installed_at", path = package_path.display())));
+    }
+    let manifest = PackageManifest::load(package_path)?;
+    let relative = match script {
+        Some(name) => manifest.scripts.get(name).cloned().ok_or_else(|| {
+            anyhow!("{}\n{}", tr!("pkg.no_script", name = name), manifest.describe())
+        })?,
+        None => manifest.entry.clone().unwrap_or_else(|| DEFAULT_ENTRY.to_string()),
+    };
+    let path = package_path.join(&relative);
+    if !path.is_file() {
+        return Err(anyhow!(
+            "{}\n{}",
+            tr!("pkg.entry_not_found", path = path.display()),
+            manifest.describe()
+        ));
+    }
+    Ok(path)
+}
+
+/// The metadata form field of a publish request.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PackageMetadata {
+    pub name: String,
+    pub version: String,
+    pub author: String,
+    pub description: String,
+    pub lang: String,
+    /// `sha256:` and the archive's digest in hex, for the registry to check
+    /// the upload against.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+}
+
+/// What `GET /packages/{name}` says about a published package.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct RegistryPackage {
+    #[serde(default)]
+    pub versions: Vec<String>,
+    #[serde(default)]
+    pub latest: Option<String>,
+    /// The digest of each version's archive as `sha256:<hex>`, when the
+    /// registry keeps them.
+    #[serde(default)]
+    pub checksums: BTreeMap<String, String>,
+}
+
+impl RegistryPackage {
+    /// `latest`, or else the highest of `versions`.
+    pub fn latest_version(&self) -> Option<String> {
+        self.latest.clone().or_else(|| {
+            self.versions
+                .iter()
+                .max_by(|a, b| compare_versions(a, b).unwrap_or(Ordering::Equal))
+                .cloned()
+        })
+    }
+
+    /// The digest of the version `latest/download` serves, if recorded.
+    pub fn latest_checksum(&self) -> Option<&str> {
+        self.checksums.get(&self.latest_version()?).map(String::as_str)
+    }
+}
+
+/// The `X.Y.Z` numbers of a version; a missing minor or patch counts as 0.
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let parts: Option<Vec<u64>> = version.split('.').map(|part| part.parse().ok()).collect();
+    parts.filter(|parts| (1..=3).contains(&parts.len())).map(|mut parts| {
+        parts.resize(3, 0);
+        parts
+    })
+}
+
+/// Compares two `X.Y.Z` versions, or `None` if either isn't one.
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    Some(parse_version(a)?.cmp(&parse_version(b)?))
+}
+
+/// Checks `version` against what the registry already has. Publishing a
+/// version that exists fails unless `force` is set; the message names the
+/// registry's latest version to bump from. Returns a warning for a version
+/// older than the latest, which is allowed.
+pub fn check_publish_version(
+    version: &str,
+    registry: &RegistryPackage,
+    force: bool,
+) -> Result<Option<String>> {
+    let latest = registry.latest_version();
+    let exists =
+        registry.versions.iter().any(|v| compare_versions(v, version) == Some(Ordering::Equal))
+            || latest.as_deref().and_then(|latest| compare_versions(latest, version))
+                == Some(Ordering::Equal);
+    let latest = latest.unwrap_or_default();
+    if exists && !force {
+        return Err(anyhow!(tr!(
+            "pkg.already_published",
+            version = version,
+            latest = latest,
+            manifest = PACKAGE_MANIFEST
+        )));
+    }
+    if exists {
+        return Ok(Some(tr!("pkg.version_replaced", version = version)));
+    }
+    Ok((compare_versions(version, &latest) == Some(Ordering::Less))
+        .then(|| tr!("pkg.version_older", version = version, latest = latest)))
+}
+
+/// أرشيف الحزمة | A package packed for upload as a tar archive.
+#[derive(Debug, Clone)]
+pub struct PackageArchive {
+    /// Paths inside the archive, relative to the package, in archive order.
+    pub files: Vec<String>,
+    pub bytes: Vec<u8>,
+}
+
+impl PackageArchive {
+    /// Packs every file under `package_dir`, in sorted path order, or the
+    /// file itself when the package is a single file.
+    pub fn build(package_path: &Path) -> Result<Self> {
+        let mut entries = Vec::new();
+        if package_path.is_file() {
+            let name = package_path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            entries.push((name, package_path.to_path_buf()));
+        } else {
+            collect_files(package_path, "", &mut entries)?;
+        }
+        entries.sort();
+        let mut bytes = Vec::new();
+        for (name, path) in &entries {
+            append_tar_entry(&mut bytes, name, &fs::read(path)?)?;
+        }
+        // A tar archive ends with two empty blocks.
+        bytes.resize(bytes.len() + 2 * TAR_BLOCK, 0);
+        Ok(PackageArchive { files: entries.into_iter().map(|(name, _)| name).collect(), bytes })
+    }
+
+    /// `sha256:` and the digest of `bytes` in hex, as `publish` sends it.
+    pub fn checksum(&self) -> String {
+        format!("sha256:{}", registry::sha256_hex(&self.bytes))
+    }
+}
+
+const TAR_BLOCK: usize = 512;
+
+/// Adds the files under `dir` to `entries` as (archive path, file path).
+fn collect_files(dir: &Path, prefix: &str, entries: &mut Vec<(String, PathBuf)>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        if entry.file_type()?.is_dir() {
+            collect_files(&entry.path(), &format!("{}/", name), entries)?;
+        } else {
+            entries.push((name, entry.path()));
+        }
+    }
+    Ok(())
+}
+
+/// Appends one regular file to a ustar archive: a header block, then the
+/// contents padded to a whole block.
+fn append_tar_entry(archive: &mut Vec<u8>, name: &str, contents: &[u8]) -> Result<()> {
+    if name.len() > 100 {
+        return Err(anyhow!(tr!("pkg.path_too_long", path = name)));
+    }
+    let mut header = [0u8; TAR_BLOCK];
+    let mut field =
+        |offset: usize, value: &[u8]| header[offset..offset + value.len()].copy_from_slice(value);
+    field(0, name.as_bytes());
+    field(100, b"0000644\0");
+    field(108, b"0000000\0");
+    field(116, b"0000000\0");
+    field(124, format!("{:011o}\0", contents.len()).as_bytes());
+    field(136, b"00000000000\0");
+    field(148, b"        ");
+    field(156, b"0");
+    field(257, b"ustar\0");
+    field(263, b"00");
+    let checksum: u32 = header.iter().map(|&byte| u32::from(byte)).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+    archive.extend_from_slice(&header);
+    archive.extend_from_slice(contents);
+    archive.resize(archive.len().next_multiple_of(TAR_BLOCK), 0);
+    Ok(())
+}
+
+/// نتيجة تنزيل | How downloading one package went.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadOutcome {
+    pub package: String,
+    /// The version installed, or the reason on failure, ready to print.
+    pub result: Result<String, String>,
+}
+
+/// Reads the `iqra-pkg.json` at `path`, mapping each installed package to
+/// its version; a missing or unreadable file is empty.
+pub fn read_install_manifest(path: &Path) -> HashMap<String, String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_install_manifest(path: &Path, manifest: &HashMap<String, String>) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(manifest)?)?;
+    Ok(())
+}
+
+/// Installs the latest version of each of `packages` from `registry` into
+/// `dir`, running up to `jobs` downloads at once. A failed download doesn't
+/// stop the others. Outcomes are in the order of `packages`.
+pub fn download_packages(
+    registry: &dyn RegistryClient,
+    packages: &[String],
+    dir: &Path,
+    jobs: usize,
+) -> Vec<DownloadOutcome> {
+    let next = AtomicUsize::new(0);
+    let mut outcomes: Vec<(usize, DownloadOutcome)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.clamp(1, packages.len().max(1)))
+            .map(|_| {
+                let next = &next;
+                scope.spawn(move || {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, AtomicOrdering::Relaxed);
+                        let Some(package) = packages.get(index) else { break };
+                        let result = registry.install(package, dir).map_err(|err| err.to_string());
+                        done.push((index, DownloadOutcome { package: package.clone(), result }));
+                    }
+                    done
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("download worker panicked"))
+            .collect()
+    });
+    outcomes.sort_by_key(|(index, _)| *index);
+    outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
+/// A table of each package and how its download went, then the counts.
+pub fn render_download_summary(outcomes: &[DownloadOutcome]) -> String {
+    let header = tr!("pkg.column.package");
+    let width = outcomes
+        .iter()
+        .map(|o| display_width(&o.package))
+        .chain([display_width(&header)])
+        .max()
+        .unwrap_or(0);
+    let row = |package: &str, result: &str| {
+        format!("{}{}  {}\n", package, " ".repeat(width - display_width(package)), result)
+    };
+    let mut out = row(&header, &tr!("pkg.column.result"));
+    for outcome in outcomes {
+        let result = match &outcome.result {
+            Ok(_) => tr!("pkg.download_ok"),
+            Err(reason) => format!("{}: {}", tr!("label.failed"), reason),
+        };
+        out.push_str(&row(&outcome.package, &result));
+    }
+    let failed = outcomes.iter().filter(|o| o.result.is_err()).count();
+    let succeeded = outcomes.len() - failed;
+    out.push_str(&format!(
+        "{}\n",
+        tr!("pkg.download_counts", succeeded = succeeded, failed = failed)
+    ));
+    out
+}
@@ -0,0 +1,486 @@
+//! السجلات | Where `iqra-pkg` gets packages from. [`HttpRegistry`] talks
+//! to a registry server; [`FsRegistry`] reads a directory, such as a
+//! shared drive in a lab with no internet, laid out as
+//!
+//! ```text
+//! packages/<name>/index.json
+//! packages/<name>/<version>/archive.tar.gz
+//! ```
+//!
+//! where `index.json` lists the published versions, as the server's
+//! `GET /packages/<name>` does, plus the package's description and author
+//! and, optionally, each archive's digest under `checksums`.
+
+use super::{
+    PackageArchive, PackageMetadata, RegistryPackage, SearchOptions, SearchResult,
+    compare_versions, parse_search_results,
+};
+use crate::tr;
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// What `install` records when the registry doesn't say which version it
+/// sent.
+pub const LATEST: &str = "latest";
+
+/// The response header in which a registry server sends the SHA-256 of a
+/// download, in hex with or without a `sha256:` prefix.
+pub const CHECKSUM_HEADER: &str = "x-checksum-sha256";
+
+/// Largest archive a directory registry will unpack, so a corrupt or
+/// hostile archive can't fill the disk.
+const MAX_UNPACKED_SIZE: usize = 64 * 1024 * 1024;
+
+/// سجل الحزم | A source of packages. Errors are messages in the `--lang`
+/// language, ready to print.
+pub trait RegistryClient: Sync {
+    /// Installs the latest version of `package` into `dir`, returning the
+    /// version installed, or [`LATEST`] when the registry doesn't say.
+    fn install(&self, package: &str, dir: &Path) -> Result<String>;
+
+    /// One page of the packages matching `options.query`.
+    fn search(&self, options: &SearchOptions) -> Result<Vec<SearchResult>>;
+
+    /// What the registry has published of `package`; empty when nothing.
+    fn package(&self, package: &str) -> Result<RegistryPackage>;
+
+    /// Uploads `archive` as the version of the package `metadata` names.
+    fn publish(&self, metadata: &PackageMetadata, archive: PackageArchive) -> Result<()>;
+
+    /// Withdraws `version` of `package` for everyone, authorized by `token`.
+    fn yank(&self, package: &str, version: &str, token: &str) -> Result<()>;
+}
+
+/// The registry URL from the environment: `MOCKITO_SERVER_URL` in tests,
+/// then `IQRA_REGISTRY_URL`, then the public registry.
+pub fn registry_url() -> String {
+    std::env::var("MOCKITO_SERVER_URL")
+        .or_else(|_| std::env::var("IQRA_REGISTRY_URL"))
+        .unwrap_or_else(|_| "https://iqra-registry.example.com".to_string())
+}
+
+/// The registry at `url`: a directory for a `file://` URL, otherwise a
+/// registry server.
+pub fn registry_for(url: &str) -> Box<dyn RegistryClient> {
+    match url.strip_prefix("file://") {
+        Some(dir) => Box::new(FsRegistry::new(dir)),
+        None => Box::new(HttpRegistry::new(url)),
+    }
+}
+
+fn connection_error() -> anyhow::Error {
+    anyhow!(tr!("registry.connection_error"))
+}
+
+/// A registry server.
+pub struct HttpRegistry {
+    client: reqwest::blocking::Client,
+    url: String,
+}
+
+impl HttpRegistry {
+    pub fn new(url: &str) -> Self {
+        HttpRegistry { client: reqwest::blocking::Client::new(), url: url.to_string() }
+    }
+
+    /// Streams `url` into the file `partial`, checking it against the
+    /// response's `Content-Length` and [`CHECKSUM_HEADER`], or when that
+    /// isn't sent the digest `recorded` gives. A body cut short is resumed
+    /// once with a `Range` request if the server accepts ranges.
+    fn download(
+        &self,
+        url: &str,
+        partial: &Path,
+        recorded: impl FnOnce() -> Option<String>,
+    ) -> Result<()> {
+        let mut response = self.client.get(url).send().map_err(|_| connection_error())?;
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP {}", response.status().as_u16()));
+        }
+        let expected = response.content_length();
+        let checksum = header(&response, CHECKSUM_HEADER);
+        let resumable = header(&response, "accept-ranges").is_some_and(|ranges| ranges == "bytes");
+        let mut file = File::create(partial)?;
+        let mut complete = response.copy_to(&mut file).is_ok();
+        let mut received = file.metadata()?.len();
+        if let Some(expected) = expected
+            && received < expected
+            && resumable
+        {
+            let rest = self
+                .client
+                .get(url)
+                .header(reqwest::header::RANGE, format!("bytes={}-", received))
+                .send();
+            if let Ok(mut rest) = rest
+                && rest.status() == reqwest::StatusCode::PARTIAL_CONTENT
+            {
+                complete = rest.copy_to(&mut file).is_ok();
+                received = file.metadata()?.len();
+            }
+        }
+        file.sync_all()?;
+        match expected {
+            Some(expected) if received != expected => {
+                return Err(anyhow!(tr!(
+                    "registry.incomplete_download",
+                    received = received,
+                    expected = expected
+                )));
+            }
+            None if !complete => {
+                return Err(anyhow!(tr!("registry.download_interrupted")));
+            }
+            _ => {}
+        }
+        if let Some(checksum) = checksum.or_else(recorded) {
+            let mut hasher = Sha256::new();
+            io::copy(&mut File::open(partial)?, &mut hasher)?;
+            verify_checksum(&checksum, &format!("{:x}", hasher.finalize()))?;
+        }
+        Ok(())
+    }
+}
+
+/// The SHA-256 of `bytes` in hex.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Checks the hex digest `actual` against `expected`, which may start with
+/// `sha256:`. The error names both.
+fn verify_checksum(expected: &str, actual: &str) -> Result<()> {
+    let expected = expected.trim();
+    let expected = expected.strip_prefix("sha256:").unwrap_or(expected);
+    if actual.eq_ignore_ascii_case(expected) {
+        return Ok(());
+    }
+    Err(anyhow!(tr!("registry.checksum_mismatch", expected = expected, actual = actual)))
+}
+
+fn header(response: &reqwest::blocking::Response, name: &str) -> Option<String> {
+    response.headers().get(name)?.to_str().ok().map(str::to_string)
+}
+
+impl RegistryClient for HttpRegistry {
+    fn install(&self, package: &str, dir: &Path) -> Result<String> {
+        // The name becomes a path under `dir`, so it must not leave it.
+        if !is_plain_name(package) {
+            return Err(anyhow!(tr!("registry.not_found", package = package)));
+        }
+        let url = format!("{}/packages/{}/latest/download", self.url, package);
+        // Downloaded next to the package and renamed into place, so a failed
+        // download never leaves a partial package behind.
+        let partial = dir.join(format!(".{}.part", package));
+        let recorded = || Some(self.package(package).ok()?.latest_checksum()?.to_string());
+        let downloaded = self
+            .download(&url, &partial, recorded)
+            .and_then(|()| Ok(fs::rename(&partial, dir.join(package))?));
+        if downloaded.is_err() {
+            let _ = fs::remove_file(&partial);
+        }
+        downloaded.map(|()| LATEST.to_string())
+    }
+
+    fn search(&self, options: &SearchOptions) -> Result<Vec<SearchResult>> {
+        let url = format!("{}/packages", self.url);
+        let response = self
+            .client
+            .get(&url)
+            .query(&options.query_pairs())
+            .send()
+            .map_err(|_| connection_error())?;
+        if !response.status().is_success() {
+            return Err(anyhow!(tr!("registry.search_failed", status = response.status())));
+        }
+        parse_search_results(&response.text().unwrap_or_default())
+    }
+
+    fn package(&self, package: &str) -> Result<RegistryPackage> {
+        let url = format!("{}/packages/{}", self.url, package);
+        match self.client.get(&url).send() {
+            Ok(response) if response.status().is_success() => {
+                Ok(response.json::<RegistryPackage>().unwrap_or_default())
+            }
+            Ok(_) => Ok(RegistryPackage::default()),
+            Err(_) => Err(connection_error()),
+        }
+    }
+
+    fn publish(&self, metadata: &PackageMetadata, archive: PackageArchive) -> Result<()> {
+        let url = format!("{}/packages", self.url);
+        let archive_name = format!("{}-{}.tar", metadata.name, metadata.version);
+        // Sent twice, in the metadata and on its own, so the server can
+        // check the upload whichever it reads.
+        let checksum = archive.checksum();
+        let metadata = PackageMetadata { checksum: Some(checksum.clone()), ..metadata.clone() };
+        let form = reqwest::blocking::multipart::Form::new()
+            .text("metadata", serde_json::to_string(&metadata)?)
+            .text("checksum", checksum)
+            .part(
+                "archive",
+                reqwest::blocking::multipart::Part::bytes(archive.bytes).file_name(archive_name),
+            );
+        let response =
+            self.client.post(&url).multipart(form).send().map_err(|_| connection_error())?;
+        if !response.status().is_success() {
+            return Err(anyhow!(tr!("registry.publish_failed", status = response.status())));
+        }
+        Ok(())
+    }
+
+    fn yank(&self, package: &str, version: &str, token: &str) -> Result<()> {
+        let url = format!("{}/packages/{}/{}", self.url, package, version);
+        let response =
+            self.client.delete(&url).bearer_auth(token).send().map_err(|_| connection_error())?;
+        if !response.status().is_success() {
+            return Err(anyhow!(tr!("registry.yank_failed", status = response.status())));
+        }
+        Ok(())
+    }
+}
+
+/// `packages/<name>/index.json` in a directory registry.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct PackageIndex {
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    versions: Vec<String>,
+    #[serde(default)]
+    latest: Option<String>,
+    #[serde(default)]
+    checksums: BTreeMap<String, String>,
+}
+
+impl PackageIndex {
+    /// `latest`, or else the highest of `versions`.
+    fn latest(&self) -> Option<String> {
+        self.latest.clone().or_else(|| {
+            self.versions
+                .iter()
+                .max_by(|a, b| compare_versions(a, b).unwrap_or(Ordering::Equal))
+                .cloned()
+        })
+    }
+}
+
+/// A registry kept in a directory; see the module docs for its layout.
+/// It can be installed and searched from, but not published to.
+pub struct FsRegistry {
+    root: PathBuf,
+}
+
+impl FsRegistry {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FsRegistry { root: root.into() }
+    }
+
+    fn package_dir(&self, package: &str) -> PathBuf {
+        self.root.join("packages").join(package)
+    }
+
+    /// The index of `package`, or `None` when the registry doesn't have it.
+    fn index(&self, package: &str) -> Result<Option<PackageIndex>> {
+        let path = self.package_dir(package).join("index.json");
+        if !is_plain_name(package) || !path.is_file() {
+            return Ok(None);
+        }
+        let json = fs::read_to_string(&path)?;
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| anyhow!(tr!("registry.invalid_index", path = path.display(), error = e)))
+    }
+
+    fn read_only(&self) -> anyhow::Error {
+        anyhow!(tr!("registry.read_only", dir = self.root.display()))
+    }
+}
+
+impl RegistryClient for FsRegistry {
+    fn install(&self, package: &str, dir: &Path) -> Result<String> {
+        let not_found = || anyhow!(tr!("registry.not_found", package = package));
+        let index = self.index(package)?.ok_or_else(not_found)?;
+        let version = index.latest().ok_or_else(not_found)?;
+        let archive = self.package_dir(package).join(&version).join("archive.tar.gz");
+        let bytes = fs::read(&archive).map_err(|e| {
+            anyhow!(tr!("registry.read_failed", path = archive.display(), error = e))
+        })?;
+        if let Some(checksum) = index.checksums.get(&version) {
+            verify_checksum(checksum, &sha256_hex(&bytes))?;
+        }
+        let files = unpack_tar(&gunzip(&bytes)?)?;
+        // Replace whatever was installed before, a file or a directory.
+        let target = dir.join(package);
+        if target.is_dir() {
+            fs::remove_dir_all(&target)?;
+        } else if target.exists() {
+            fs::remove_file(&target)?;
+        }
+        for (name, contents) in files {
+            let path = target.join(name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, contents)?;
+        }
+        Ok(version)
+    }
+
+    fn search(&self, options: &SearchOptions) -> Result<Vec<SearchResult>> {
+        let mut names: Vec<String> = match fs::read_dir(self.root.join("packages")) {
+            Ok(entries) => entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect(),
+            Err(e) => {
+                return Err(anyhow!(tr!(
+                    "registry.read_registry_failed",
+                    dir = self.root.display(),
+                    error = e
+                )));
+            }
+        };
+        names.sort();
+        let mut results = Vec::new();
+        for name in names {
+            let Some(index) = self.index(&name)? else { continue };
+            if name.contains(&options.query) || index.description.contains(&options.query) {
+                let version = index.latest();
+                results.push(SearchResult {
+                    name,
+                    description: index.description,
+                    version,
+                    author: index.author,
+                    downloads: None,
+                });
+            }
+        }
+        Ok(results
+            .into_iter()
+            .skip((options.page - 1) * options.limit)
+            .take(options.limit)
+            .collect())
+    }
+
+    fn package(&self, package: &str) -> Result<RegistryPackage> {
+        Ok(self
+            .index(package)?
+            .map(|index| RegistryPackage {
+                latest: index.latest(),
+                versions: index.versions,
+                checksums: index.checksums,
+            })
+            .unwrap_or_default())
+    }
+
+    fn publish(&self, _metadata: &PackageMetadata, _archive: PackageArchive) -> Result<()> {
+        Err(self.read_only())
+    }
+
+    fn yank(&self, _package: &str, _version: &str, _token: &str) -> Result<()> {
+        Err(self.read_only())
+    }
+}
+
+/// Whether `name` is a single path component that stays inside its parent.
+fn is_plain_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!((components.next(), components.next()), (Some(Component::Normal(_)), None))
+}
+
+/// CRC-32 as gzip uses it.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// `bytes` compressed in the gzip format.
+pub fn gzip(bytes: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+    out.extend(miniz_oxide::deflate::compress_to_vec(bytes, 6));
+    out.extend(crc32(bytes).to_le_bytes());
+    out.extend((bytes.len() as u32).to_le_bytes());
+    out
+}
+
+/// The contents of the gzip data `bytes`, checked against its CRC.
+pub fn gunzip(bytes: &[u8]) -> Result<Vec<u8>> {
+    let invalid = || anyhow!(tr!("registry.invalid_gzip"));
+    if bytes.len() < 18 || bytes[..3] != [0x1f, 0x8b, 8] {
+        return Err(invalid());
+    }
+    let flags = bytes[3];
+    let mut pos = 10;
+    if flags & 0x04 != 0 {
+        let extra = bytes.get(pos..pos + 2).ok_or_else(invalid)?;
+        pos += 2 + usize::from(u16::from_le_bytes([extra[0], extra[1]]));
+    }
+    // The file name, then the comment, each ending in a zero byte.
+    for flag in [0x08, 0x10] {
+        if flags & flag != 0 {
+            let end = bytes.get(pos..).and_then(|rest| rest.iter().position(|&b| b == 0));
+            pos += end.ok_or_else(invalid)? + 1;
+        }
+    }
+    if flags & 0x02 != 0 {
+        pos += 2;
+    }
+    let body = bytes.get(pos..bytes.len() - 8).ok_or_else(invalid)?;
+    let contents = miniz_oxide::inflate::decompress_to_vec_with_limit(body, MAX_UNPACKED_SIZE)
+        .map_err(|_| invalid())?;
+    let trailer = &bytes[bytes.len() - 8..];
+    if trailer[..4] != crc32(&contents).to_le_bytes() {
+        return Err(invalid());
+    }
+    Ok(contents)
+}
+
+/// The regular files in the tar archive `bytes`, as (path, contents).
+/// Paths that would leave the package, such as `../x` or `/etc/x`, are
+/// refused.
+pub fn unpack_tar(bytes: &[u8]) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    const BLOCK: usize = 512;
+    let invalid = || anyhow!(tr!("registry.invalid_tar"));
+    let text = |field: &[u8]| {
+        let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+        String::from_utf8_lossy(&field[..end]).into_owned()
+    };
+    let mut files = Vec::new();
+    let mut pos = 0;
+    while let Some(header) = bytes.get(pos..pos + BLOCK) {
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        let size =
+            usize::from_str_radix(text(&header[124..136]).trim(), 8).map_err(|_| invalid())?;
+        let prefix = text(&header[345..500]);
+        let name = text(&header[..100]);
+        let name = if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) };
+        let contents = bytes.get(pos + BLOCK..pos + BLOCK + size).ok_or_else(invalid)?;
+        // Directories are made as files need them; links and the like are skipped.
+        if matches!(header[156], b'0' | 0) {
+            let path = PathBuf::from(&name);
+            if !path.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) {
+                return Err(anyhow!(tr!("registry.unsafe_path", path = name)));
+            }
+            files.push((path, contents.to_vec()));
+        }
+        pos += BLOCK + size.next_multiple_of(BLOCK);
+    }
+    Ok(files)
+}
@@ -0,0 +1,128 @@
+use assert_cmd::Command;
+use iqra::lang::analyzer::{Warning, WarningKind, analyze, closest_name, levenshtein};
+use iqra::lang::lexer::Lexer;
+use iqra::lang::parser::Parser;
+use iqra::lang::runtime::Runtime;
+use predicates::prelude::*;
+use std::fs;
+
+fn warnings(source: &str) -> Vec<Warning> {
+    let program = Parser::new(Lexer::new(source)).parse().unwrap();
+    analyze(&program)
+}
+
+fn kinds(source: &str) -> Vec<(WarningKind, String, usize)> {
+    warnings(source).into_iter().map(|w| (w.kind, w.name, w.line)).collect()
+}
+
+#[test]
+fn test_levenshtein_and_closest_name() {
+    assert_eq!(levenshtein("نتيجه", "نتيجة"), 1);
+    assert_eq!(levenshtein("", "abc"), 3);
+    assert_eq!(levenshtein("kitten", "sitting"), 3);
+    assert_eq!(closest_name("نتيجه", ["نتيجة", "عدد"]), Some("نتيجة".to_string()));
+    assert_eq!(closest_name("س", ["ص"]), None);
+    assert_eq!(closest_name("total", ["count"]), None);
+}
+
+#[test]
+fn test_typo_is_reported_with_suggestion() {
+    let found = warnings("نتيجه = ١\nاطبع(نتيجة)\n");
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].kind, WarningKind::UnusedVariable);
+    assert_eq!(found[0].name, "نتيجه");
+    assert_eq!(found[1].kind, WarningKind::UndefinedVariable);
+    assert_eq!(found[1].line, 2);
+    assert_eq!(found[1].suggestion.as_deref(), Some("هل تقصد 'نتيجه'؟ | Did you mean 'نتيجه'?"));
+}
+
+#[test]
+fn test_read_before_assignment() {
+    assert_eq!(
+        kinds("اطبع(س)\nس = ١\nاطبع(س)\n"),
+        vec![(WarningKind::UseBeforeAssignment, "س".to_string(), 1)]
+    );
+}
+
+#[test]
+fn test_function_shadowing_builtin_and_undefined_call() {
+    let found = kinds("دالة اطبع(س) {\n    ارجع س\n}\nاطبع(١)\nطبع(٢)\n");
+    assert_eq!(
+        found,
+        vec![
+            (WarningKind::ShadowedBuiltin, "اطبع".to_string(), 1),
+            (WarningKind::UndefinedFunction, "طبع".to_string(), 5),
+        ]
+    );
+}
+
+#[test]
+fn test_functions_see_globals_and_params() {
+    let source = "دالة زد(س) {\n    ارجع س + خطوة\n}\nخطوة = ١\nاطبع(زد(٢))\n";
+    assert!(warnings(source).is_empty(), "{:?}", warnings(source));
+}
+
+#[test]
+fn test_catch_variable_and_underscore_names_are_not_warned() {
+    let source = "_مؤقت = ١\nجرب {\n    اطبع(١)\n} امسك (خطأ) {\n    اطبع(خطأ)\n}\n";
+    assert!(warnings(source).is_empty(), "{:?}", warnings(source));
+}
+
+#[test]
+fn test_runtime_undefined_variable_suggests_close_name() {
+    let mut runtime = Runtime::new();
+    let err = runtime.execute("نتيجه = ١\nنتيجة + ١").unwrap_err();
+    assert!(err.to_string().contains("هل قصدت: نتيجه؟ | Did you mean: نتيجه?"), "{}", err);
+}
+
+#[test]
+fn test_check_and_run_report_warnings() {
+    let path = std::env::temp_dir().join(format!("iqra_analyzer_{}.iqra", std::process::id()));
+    fs::write(&path, "غير_مستخدم = ١\nاطبع(\"تم\")\n").unwrap();
+    let path = path.to_string_lossy().into_owned();
+
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["--lang", "en", "check", &path])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Variable 'غير_مستخدم' is assigned but never used"));
+
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["run", &path])
+        .assert()
+        .success()
+        .stdout("تم\n")
+        .stderr(predicate::str::contains("غير_مستخدم"));
+
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["run", "--no-warnings", &path])
+        .assert()
+        .success()
+        .stderr("");
+}
+
+#[test]
+fn test_endless_loops_are_reported() {
+    let endless = |source: &str| -> Vec<(String, usize)> {
+        kinds(source)
+            .into_iter()
+            .filter(|(kind, _, _)| *kind == WarningKind::EndlessLoop)
+            .map(|(_, name, line)| (name, line))
+            .collect()
+    };
+    assert_eq!(endless("كرر {\n    اطبع(١)\n}\n"), [("كرر".to_string(), 1)]);
+    assert_eq!(endless("س = ٠\nwhile true {\n    س = س + ١\n}\n"), [("بينما".to_string(), 2)]);
+    // A break that only leaves an inner loop doesn't end the outer one.
+    assert_eq!(endless("loop {\n    while صحيح {\n        break\n    }\n}\n").len(), 1);
+    for source in [
+        "كرر {\n    اذا صحيح {\n        توقف\n    }\n}\n",
+        "دالة د() {\n    كرر {\n        ارجع ١\n    }\n}\nد()\n",
+        "بينما صحيح {\n    جرب {\n        توقف\n    } امسك (خ) {\n    }\n}\n",
+        "س = ٠\nبينما س < ٣ {\n    س = س + ١\n}\n",
+    ] {
+        assert_eq!(endless(source), [], "{}", source);
+    }
+}
@@ -0,0 +1,81 @@
+use iqra::lang::builtins::arabic::{
+    DigitScript, NormalizeOptions, is_diacritic, normalize_arabic, normalize_digits,
+    strip_diacritics,
+};
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn text(code: &str) -> String {
+    match Runtime::new().execute(code).unwrap() {
+        Value::String(s) => s,
+        other => panic!("expected a string, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_strip_diacritics_from_classical_text() {
+    assert_eq!(strip_diacritics("بِسْمِ اللَّهِ الرَّحْمَٰنِ الرَّحِيمِ"), "بسم الله الرحمن الرحيم");
+    // Tanween, the superscript alef and the small high Quranic marks.
+    assert_eq!(strip_diacritics("كِتَابٌ عِلْمًا"), "كتاب علما");
+    assert_eq!(strip_diacritics("ذَٰلِكَ ٱلْكِتَٰبُ لَا رَيْبَ ۛ فِيهِ ۛ"), "ذلك ٱلكتب لا ريب  فيه ");
+    assert_eq!(strip_diacritics("مُحَمَّد and 123"), "محمد and 123");
+    assert!(is_diacritic('\u{0651}'));
+    assert!(!is_diacritic('ـ'));
+    assert!(!is_diacritic('ا'));
+}
+
+#[test]
+fn test_normalize_unifies_spelling_variants() {
+    let options = NormalizeOptions::default();
+    assert_eq!(normalize_arabic("أحمد إبراهيم آمنة ٱلكتاب", &options), "احمد ابراهيم امنه الكتاب");
+    assert_eq!(normalize_arabic("مستشفى المدينة", &options), "مستشفي المدينه");
+    assert_eq!(normalize_arabic("  جميـــــل   جداً\n", &options), "جميل جداً");
+    // Dialect spellings of the same word meet after normalizing.
+    assert_eq!(normalize_arabic("إزيك", &options), normalize_arabic("ازيك", &options));
+    assert_eq!(normalize_arabic("على", &options), normalize_arabic("علي", &options));
+}
+
+#[test]
+fn test_normalize_options_turn_transforms_off() {
+    let mut options = NormalizeOptions::default();
+    assert!(options.set("تاء_مربوطة", false));
+    assert!(options.set("whitespace", false));
+    assert!(!options.set("unknown", true));
+    assert_eq!(normalize_arabic("مدرسة  أولى", &options), "مدرسة  اولي");
+}
+
+#[test]
+fn test_normalize_digits_both_ways() {
+    assert_eq!(normalize_digits("الصفحة 12 من 340", DigitScript::Arabic), "الصفحة ١٢ من ٣٤٠");
+    assert_eq!(normalize_digits("١٤٤٥هـ - 2024م", DigitScript::Ascii), "1445هـ - 2024م");
+    assert_eq!(normalize_digits("۱۲۳", DigitScript::Ascii), "123");
+    assert_eq!(normalize_digits("no digits", DigitScript::Arabic), "no digits");
+}
+
+#[test]
+fn test_builtins_in_both_languages() {
+    assert_eq!(text("أزل_التشكيل(\"السَّلامُ عَلَيْكُمْ\")"), "السلام عليكم");
+    assert_eq!(text("strip_diacritics(\"قُرْآن\")"), "قرآن");
+    assert_eq!(text("طبع_نص(\"إسلامـــية\")"), "اسلاميه");
+    assert_eq!(text("normalize_arabic(\"مدرسة\", map(\"teh_marbuta\", false))"), "مدرسة");
+    assert_eq!(text("وحد_الأرقام(\"رقم 7\"، \"عربي\")"), "رقم ٧");
+    assert_eq!(text("normalize_digits(\"٢٠٢٤\", \"ascii\")"), "2024");
+}
+
+#[test]
+fn test_comparing_normalized_text() {
+    let code = "دالة طبيعي(س) {\n    ارجع طبع_نص(أزل_التشكيل(س))\n}\n\
+                طبيعي(\"إِلَى الْمَدْرَسَةِ\") == طبيعي(\"الى المدرسه\")";
+    assert_eq!(Runtime::new().execute(code).unwrap(), Value::Bool(true));
+}
+
+#[test]
+fn test_bad_arguments_are_errors() {
+    let err = Runtime::new().execute("طبع_نص(\"أ\"، قاموس(\"حروف\"، صحيح))").unwrap_err();
+    let message = format!("{}", err);
+    assert!(message.contains("حروف"), "{}", message);
+    assert!(Runtime::new().execute("طبع_نص(\"أ\"، قاموس(\"ألف\"، ١))").is_err());
+    assert!(Runtime::new().execute("طبع_نص(\"أ\"، ١)").is_err());
+    assert!(Runtime::new().execute("وحد_الأرقام(\"١\"، \"روماني\")").is_err());
+    assert!(Runtime::new().execute("أزل_التشكيل(٥)").is_err());
+}
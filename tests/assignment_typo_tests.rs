@@ -0,0 +1,75 @@
+use iqra::lang::analyzer::{WarningKind, analyze};
+use iqra::lang::lexer::Lexer;
+use iqra::lang::parser::Parser;
+use iqra::lang::runtime::{IqraError, Runtime};
+
+fn error(code: &str) -> IqraError {
+    Runtime::new().execute(code).unwrap_err().downcast::<IqraError>().unwrap()
+}
+
+#[test]
+fn test_single_equals_in_if_condition_suggests_double_equals() {
+    let err = error("س = ١\nاذا س = ٥ {\n    اطبع(س)\n}\n");
+    assert_eq!(err.kind, "إسناد داخل شرط");
+    assert_eq!(err.suggestion.as_deref(), Some("هل قصدت ==؟ | Did you mean ==?"));
+    assert_eq!(err.line, Some(2));
+}
+
+#[test]
+fn test_single_equals_in_while_condition_suggests_double_equals() {
+    let err = error("n = 0\n\nwhile n = 3 {\n    n = n + 1\n}\n");
+    assert_eq!(err.suggestion.as_deref(), Some("هل قصدت ==؟ | Did you mean ==?"));
+    assert_eq!(err.line, Some(3));
+}
+
+#[test]
+fn test_double_equals_statement_on_undefined_name_suggests_assignment() {
+    let err = error("عدد == ٥\n");
+    assert!(err.message_en.contains("Undefined variable: عدد"));
+    assert_eq!(err.suggestion.as_deref(), Some("هل قصدت =؟ | Did you mean =?"));
+
+    // Comparisons inside larger expressions keep the usual hint.
+    let err = error("اطبع(عدد == ٥)\n");
+    assert_ne!(err.suggestion.as_deref(), Some("هل قصدت =؟ | Did you mean =?"));
+}
+
+#[test]
+fn test_discarded_comparison_is_warned() {
+    let program = Parser::new(Lexer::new("س = ١\nس == ٢\nاطبع(س)\nس == ١\n")).parse().unwrap();
+    let warnings: Vec<_> = analyze(&program)
+        .into_iter()
+        .filter(|warning| warning.kind == WarningKind::UnusedComparison)
+        .collect();
+    // The final comparison is the program's value, so only line 2 is reported.
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].line, 2);
+    assert_eq!(warnings[0].name, "س");
+}
+
+#[test]
+fn test_assignment_inside_condition_is_reported_at_the_equals_sign() {
+    let cases = [
+        ("عدد = ٠\nبينما عدد = عدد + ١ < ١٠ {\n    اطبع(عدد)\n}\n", 2, 11),
+        ("n = 0\nwhile (n = n + 1) < 10 {\n    print(n)\n}\n", 2, 10),
+        ("س = ١\nاذا (س = ٥) {\n    اطبع(س)\n}\n", 2, 8),
+        ("x = 1\nif x > 0 and f(x = 2) {\n    print(x)\n}\n", 2, 18),
+    ];
+    for (code, line, column) in cases {
+        let err = error(code);
+        assert_eq!(err.message_ar, format!("التعيين غير مسموح داخل الشرط (العمود {})", column));
+        assert_eq!(
+            err.message_en,
+            format!("Assignment is not allowed inside a condition (column {})", column)
+        );
+        assert_eq!(err.line, Some(line), "{}", code);
+    }
+}
+
+#[test]
+fn test_assignment_inside_condition_reports_a_single_error() {
+    let code = "عدد = ٠\nبينما عدد = عدد + ١ < ١٠ {\n    اطبع(عدد)\n}\nاطبع(عدد)\n";
+    let (statements, errors) = Parser::new(Lexer::new(code)).parse_with_recovery();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, "إسناد داخل شرط");
+    assert_eq!(statements.len(), 2);
+}
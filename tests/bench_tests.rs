@@ -0,0 +1,70 @@
+use assert_cmd::Command;
+use iqra::cli::bench::{render_stats, summarize};
+use iqra::lang::program::Program;
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+use std::time::Duration;
+
+fn ms(values: &[u64]) -> Vec<Duration> {
+    values.iter().map(|v| Duration::from_millis(*v)).collect()
+}
+
+#[test]
+fn test_summarize_odd_number_of_runs() {
+    let stats = summarize(&ms(&[30, 10, 20, 50, 40])).unwrap();
+    assert_eq!(stats.iterations, 5);
+    assert_eq!(stats.min_ms, 10.0);
+    assert_eq!(stats.median_ms, 30.0);
+    assert_eq!(stats.mean_ms, 30.0);
+    assert_eq!(stats.max_ms, 50.0);
+}
+
+#[test]
+fn test_summarize_even_number_of_runs() {
+    let stats = summarize(&[
+        Duration::from_micros(1500),
+        Duration::from_millis(4),
+        Duration::from_millis(1),
+        Duration::from_millis(2),
+    ])
+    .unwrap();
+    assert_eq!(stats.median_ms, 1.75);
+    assert_eq!(stats.mean_ms, 2.125);
+    assert_eq!(summarize(&[]), None);
+}
+
+#[test]
+fn test_render_stats() {
+    let stats = summarize(&ms(&[2, 4])).unwrap();
+    let report = render_stats("x.iqra", &stats);
+    assert!(report.starts_with("x.iqra\n"), "{}", report);
+    assert!(report.contains("median:   3.000 ms"), "{}", report);
+}
+
+#[test]
+fn test_compiled_program_runs_on_many_runtimes() {
+    let program = Program::compile("س = ٢\nس * ٣").unwrap();
+    for _ in 0..3 {
+        assert_eq!(Runtime::new().run_program(&program).unwrap(), Value::Int(6));
+    }
+    let err = Program::compile("س = (").unwrap_err().to_string();
+    assert!(err.contains("Line: 1"), "{}", err);
+}
+
+#[test]
+fn test_bench_command_json_output() {
+    let output = Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["bench", "اطبع(١ + ٢)", "--iterations", "3", "--warmup", "1", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["iterations"], 3);
+    for key in ["min_ms", "median_ms", "mean_ms", "max_ms"] {
+        assert!(json[key].as_f64().unwrap() >= 0.0, "{}", key);
+    }
+
+    Command::cargo_bin("iqra").unwrap().args(["bench", "س = ("]).assert().code(2);
+    Command::cargo_bin("iqra").unwrap().args(["bench", "غير_معرف"]).assert().code(1);
+}
@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn error(code: &str) -> String {
+    format!("{}", Runtime::new().execute(code).unwrap_err())
+}
+
+#[test]
+fn test_indexing_a_large_list_does_not_copy_it() {
+    // Reads each of 10,000 elements ten times: 100,000 index operations.
+    let items = (0..10_000).map(|i| i.to_string()).collect::<Vec<_>>().join(", ");
+    let reads = ["قائمة[ي]"; 10].join(" + ");
+    let code = format!(
+        "قائمة = [{}]\nمجموع = ٠\nي = ٠\n\
+         بينما ي < طول(قائمة) {{\n    مجموع = مجموع + {}\n    ي = ي + ١\n}}\nمجموع",
+        items, reads
+    );
+    let start = Instant::now();
+    let sum = eval(&code);
+    let elapsed = start.elapsed();
+    assert_eq!(sum, Value::Int(10 * (0..10_000).sum::<i64>()));
+    assert!(elapsed < Duration::from_secs(1), "took {:?}", elapsed);
+}
+
+#[test]
+fn test_nested_indexes_read_in_place() {
+    let code = "جدول = [[١، ٢]، [٣، قاموس(\"أ\"، [٤، ٥])]]\nجدول[١][١][\"أ\"][٠] + جدول[٠][١]";
+    assert_eq!(eval(code), Value::Int(6));
+    assert_eq!(eval("س = [١، ٢]\nس == [١، ٢]"), Value::Bool(true));
+    assert_eq!(eval("س = [٧، ٨]\nعنصر(س، ١) + طول_القائمة(س)"), Value::Int(10));
+    assert_eq!(eval("س = [\"أ\"]\nيحتوي(س، \"أ\")"), Value::Bool(true));
+    assert_eq!(eval("ق = قاموس(\"م\"، ٣)\nجلب_عنصر(ق، \"م\")"), Value::Int(3));
+    assert_eq!(eval("ق = قاموس()\nنوع(ق)"), eval("نوع(قاموس())"));
+}
+
+#[test]
+fn test_user_functions_still_shadow_read_only_builtins() {
+    assert_eq!(eval("دالة طول(س) {\n    ارجع ٤٢\n}\nطول([١])"), Value::Int(42));
+}
+
+#[test]
+fn test_errors_are_unchanged() {
+    assert!(error("قائمه[٠]").contains("Undefined variable: قائمه"));
+    // The first bad index fails before later indexes are evaluated.
+    assert!(error("س = [[١]]\nس[٣][غير_معرف]").contains("Index out of bounds: 3"));
+    assert!(error("س = [[١]]\nس[٠][غير_معرف]").contains("Undefined variable: غير_معرف"));
+    assert!(error("س = ٥\nس[٠]").contains("Invalid indexing operation"));
+    assert!(error("س = [١]\nطول(س، س)").contains("طول expects 1 argument"));
+    assert!(error("س = ٥\nطول(س)").contains("طول expects a string, list or map"));
+}
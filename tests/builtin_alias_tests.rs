@@ -0,0 +1,92 @@
+use assert_cmd::Command;
+use iqra::lang::builtins::builtin_doc;
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::runtime::{
+    BUILTIN_ALIASES, BUILTIN_FUNCTIONS, IqraError, Runtime, canonical_builtin,
+};
+use iqra::lang::value::Value;
+
+fn run(code: &str) -> (Value, String) {
+    let output = CapturedOutput::new();
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(output.clone()));
+    let value = runtime.execute(code).unwrap();
+    (value, output.errors())
+}
+
+#[test]
+fn test_names_resolve_to_the_arabic_builtin() {
+    assert_eq!(canonical_builtin("print"), Some("اطبع"));
+    assert_eq!(canonical_builtin("اطبع"), Some("اطبع"));
+    assert_eq!(canonical_builtin("list_len"), Some("طول"));
+    assert_eq!(canonical_builtin("طول_القائمة"), Some("طول"));
+    assert_eq!(canonical_builtin("مجهول"), None);
+    for alias in BUILTIN_ALIASES {
+        assert!(!BUILTIN_FUNCTIONS.contains(&alias.name), "{} is listed", alias.name);
+        assert!(BUILTIN_FUNCTIONS.contains(&alias.target), "{} is not a builtin", alias.target);
+    }
+}
+
+#[test]
+fn test_deprecated_alias_warns_once_and_still_works() {
+    let (value, errors) =
+        run("س = [١، ٢، ٣]\nlist_len(س) + list_len(س) + طول_القائمة([١]) + طول_القائمة([])");
+    assert_eq!(value, Value::Int(7));
+    assert_eq!(
+        errors,
+        "تحذير: استخدم len بدلاً من list_len (list_len تعدّ القوائم فقط، وlen تعدّ النصوص والقواميس أيضاً) \
+         | Warning: use len instead of list_len (list_len only counts lists; len also counts strings and maps)\n\
+         تحذير: استخدم طول بدلاً من طول_القائمة (طول_القائمة تعدّ القوائم فقط، وطول تعدّ النصوص والقواميس أيضاً) \
+         | Warning: use طول instead of طول_القائمة (طول_القائمة only counts lists; طول also counts strings and maps)\n"
+    );
+}
+
+#[test]
+fn test_aliases_reach_the_general_builtin() {
+    // Undeprecated names don't warn, and count maps too.
+    let (value, errors) = run("len([١]) + طول([١]) + len(قاموس(\"أ\"، ١، \"ب\"، ٢))");
+    assert_eq!(value, Value::Int(4));
+    assert_eq!(errors, "");
+    assert_eq!(builtin_doc("list_len").unwrap().english, "len");
+}
+
+#[test]
+fn test_old_names_still_only_count_lists() {
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(CapturedOutput::new()));
+    let err = runtime.execute("list_len(\"abc\")").unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.message_en, "list_len expects a list");
+    let err =
+        runtime.execute("طول_القائمة([١]، [٢])").unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.message_ar, "طول_القائمة تتوقع وسيطاً واحداً");
+    let err = runtime.execute("len(١)").unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.message_en, "len expects a string, list or map");
+}
+
+#[test]
+fn test_deprecation_warning_follows_the_language() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["--lang", "en", "code", "-c", "اطبع(list_len([١]))"])
+        .assert()
+        .success()
+        .stdout("1\n")
+        .stderr(
+            "Warning: use len instead of list_len (list_len only counts lists; len also counts strings and maps)\n",
+        );
+}
+
+#[test]
+fn test_list_builtins_returns_canonical_names() {
+    let (Value::List(arabic), _) = run("قائمة_الدوال()") else { panic!() };
+    let (Value::List(english), _) = run("list_builtins()") else { panic!() };
+    assert_eq!(arabic.len() * 2, BUILTIN_FUNCTIONS.len());
+    assert_eq!(arabic.len(), english.len());
+    assert_eq!(arabic[0], Value::String("اطبع".to_string()));
+    assert_eq!(english[0], Value::String("print".to_string()));
+    for removed in ["list_len", "طول_القائمة"] {
+        let name = Value::String(removed.to_string());
+        assert!(!arabic.contains(&name) && !english.contains(&name));
+    }
+    assert!(english.contains(&Value::String("list_builtins".to_string())));
+}
@@ -0,0 +1,53 @@
+use iqra::lang::analyzer::{WarningKind, analyze};
+use iqra::lang::lexer::Lexer;
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::parser::Parser;
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+const SHADOWING: &str = "دالة اطبع(س) { ارجع \"مخفي\" }\n";
+
+fn runtime_with_output() -> (Runtime, CapturedOutput) {
+    let output = CapturedOutput::new();
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(output.clone()));
+    (runtime, output)
+}
+
+#[test]
+fn test_shadowing_warns_and_suggests_the_escape() {
+    let program = Parser::new(Lexer::new(SHADOWING)).parse().unwrap();
+    let warnings = analyze(&program);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::ShadowedBuiltin);
+    assert!(warnings[0].suggestion.as_deref().unwrap().contains("مدمج.اطبع(...)"));
+}
+
+#[test]
+fn test_escape_reaches_the_hidden_builtin() {
+    let (mut runtime, output) = runtime_with_output();
+    runtime.execute(SHADOWING).unwrap();
+    assert_eq!(runtime.execute("اطبع(١)").unwrap(), Value::String("مخفي".to_string()));
+    assert_eq!(output.contents(), "");
+    runtime.execute("مدمج.اطبع(\"مرحبا\")\nbuiltin.print(2)").unwrap();
+    assert_eq!(output.contents(), "مرحبا\n2\n");
+}
+
+#[test]
+fn test_undefine_restores_normal_dispatch() {
+    let (mut runtime, output) = runtime_with_output();
+    runtime.execute(SHADOWING).unwrap();
+    assert_eq!(runtime.execute("احذف_دالة(\"اطبع\")").unwrap(), Value::Bool(true));
+    runtime.execute("اطبع(\"عاد\")").unwrap();
+    assert_eq!(output.contents(), "عاد\n");
+    assert_eq!(runtime.execute("undefine(\"اطبع\")").unwrap(), Value::Bool(false));
+}
+
+#[test]
+fn test_escape_needs_a_builtin_name() {
+    let err = Runtime::new().execute("دالة مربع(س) { ارجع س * س }\nمدمج.مربع(٢)").unwrap_err();
+    assert!(err.to_string().contains("Unknown function: مربع"), "{}", err);
+    assert!(Runtime::new().execute("مدمج.(١)").is_err());
+    // Without a dot the qualifier is an ordinary name.
+    assert_eq!(Runtime::new().execute("مدمج = ٣\nمدمج").unwrap(), Value::Int(3));
+}
@@ -0,0 +1,94 @@
+use iqra::lang::builtins::{BUILTIN_DOCS, CATEGORIES, builtin_doc, render_help};
+use iqra::lang::runtime::{BUILTIN_FUNCTIONS, IqraError, Runtime};
+use iqra::lang::value::Value;
+
+fn field(value: &Value, key: &str) -> Value {
+    let Value::Map(map) = value else { panic!("expected a map, got {:?}", value) };
+    map.get(key).cloned().unwrap_or_else(|| panic!("missing key {}", key))
+}
+
+#[test]
+fn test_every_builtin_has_bilingual_help() {
+    for pair in BUILTIN_FUNCTIONS.chunks(2) {
+        let doc = builtin_doc(pair[0]).unwrap_or_else(|| panic!("no help for {}", pair[0]));
+        assert_eq!((doc.arabic, doc.english), (pair[0], pair[1]));
+        assert!(!doc.description_ar.trim().is_empty(), "empty Arabic help for {}", pair[0]);
+        assert!(!doc.description_en.trim().is_empty(), "empty English help for {}", pair[1]);
+        assert!(!doc.examples.is_empty(), "no examples for {}", pair[0]);
+    }
+    assert_eq!(BUILTIN_DOCS.len() * 2, BUILTIN_FUNCTIONS.len());
+    for doc in BUILTIN_DOCS {
+        assert!(CATEGORIES.contains(&doc.category), "{} has category {}", doc.arabic, doc.category);
+    }
+}
+
+#[test]
+fn test_help_for_a_builtin_returns_its_documentation() {
+    let help = Runtime::new().execute("مساعدة(\"اطبع\")").unwrap();
+    assert_eq!(
+        field(&help, "description"),
+        Value::String("Prints the values on one line, separated by spaces".to_string())
+    );
+    assert!(matches!(field(&help, "وصف"), Value::String(text) if text.contains("يطبع")));
+    assert!(matches!(field(&help, "أمثلة"), Value::List(examples) if !examples.is_empty()));
+    assert_eq!(field(&help, "معاملات"), field(&help, "params"));
+    // The English name finds the same entry.
+    assert_eq!(Runtime::new().execute("help(\"print\")").unwrap(), help);
+}
+
+#[test]
+fn test_help_without_arguments_groups_builtins_by_category() {
+    let index = Runtime::new().execute("help()").unwrap();
+    let Value::Map(map) = &index else { panic!("expected a map") };
+    assert_eq!(map.len(), CATEGORIES.len());
+    let Value::List(output) = field(&index, "إخراج | Output") else {
+        panic!("expected a list")
+    };
+    assert_eq!(output[0], Value::String("اطبع | print".to_string()));
+    let total: usize = map
+        .values()
+        .map(|names| if let Value::List(names) = names { names.len() } else { 0 })
+        .sum();
+    assert_eq!(total, BUILTIN_DOCS.len());
+}
+
+#[test]
+fn test_user_functions_keep_their_doc_comments() {
+    let mut runtime = Runtime::new();
+    runtime.execute("/// ضعف العدد\nدالة ضعف(س) {\n    ارجع س * ٢\n}").unwrap();
+    assert_eq!(runtime.execute("مساعدة(\"ضعف\")").unwrap(), Value::String("ضعف العدد".to_string()));
+}
+
+#[test]
+fn test_unknown_name_suggests_the_closest_builtin() {
+    let err = Runtime::new().execute("مساعدة(\"اطبغ\")").unwrap_err();
+    let err = err.downcast_ref::<IqraError>().unwrap();
+    assert_eq!(err.suggestion.as_deref(), Some("هل تقصد 'اطبع'؟ | Did you mean 'اطبع'?"));
+    let err = Runtime::new().execute("help(\"pirnt\")").unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<IqraError>().unwrap().suggestion.as_deref(),
+        Some("هل تقصد 'print'؟ | Did you mean 'print'?")
+    );
+}
+
+#[test]
+fn test_render_help_is_multi_line() {
+    let help = Runtime::new().execute("مساعدة(\"كرر_نص\")").unwrap();
+    assert_eq!(
+        render_help(&help).unwrap(),
+        "كرر_نص | repeat\n  النص مكرراً عدداً من المرات\n  The text repeated a number of times\n\
+         المعاملات | Params:\n  نص | text\n  عدد | count\n\
+         أمثلة | Examples:\n  كرر_نص(\"-\"، ١٠)"
+    );
+    let index = render_help(&Runtime::new().execute("مساعدة()").unwrap()).unwrap();
+    assert!(
+        index.starts_with(
+            "إخراج | Output:\n  اطبع | print\n  اطبع_ملون | print_color\n  \
+             اطبع_بدون_سطر | print_inline\n  اطبع_مفصول | print_sep\n  اطبع_خطأ | print_error\n\
+             قوائم | Lists:"
+        ),
+        "{}",
+        index
+    );
+    assert_eq!(render_help(&Value::Int(1)), None);
+}
@@ -0,0 +1,86 @@
+use iqra::lang::runtime::{IqraError, Runtime};
+use iqra::lang::value::Value;
+use std::fs;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn error(code: &str) -> IqraError {
+    Runtime::new().execute(code).unwrap_err().downcast::<IqraError>().unwrap()
+}
+
+#[test]
+fn test_arabic_round_trips_through_windows_1256() {
+    assert_eq!(
+        eval("نص_إلى_بايتات(\"سلام\"، \"windows-1256\")"),
+        Value::Bytes(vec![0xd3, 0xe1, 0xc7, 0xe3])
+    );
+    assert_eq!(
+        eval("بايتات_إلى_نص(نص_إلى_بايتات(\"مرحبا بالعالم\"، \"cp1256\")، \"Windows-1256\")"),
+        Value::String("مرحبا بالعالم".to_string())
+    );
+    // utf-8 is the default both ways.
+    assert_eq!(eval("طول(to_bytes(\"سلام\"))"), Value::Int(8));
+    assert_eq!(eval("from_bytes(to_bytes(\"سلام\", \"utf8\"))"), Value::String("سلام".to_string()));
+}
+
+#[test]
+fn test_bytes_display_index_and_truthiness() {
+    assert_eq!(
+        format!("{}", Value::Bytes(vec![0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0, 0])),
+        "<bytes: 10, أول: 89 50 4e 47 0d 0a 1a 0a …>"
+    );
+    assert_eq!(format!("{}", Value::Bytes(vec![0x61])), "<bytes: 1, أول: 61>");
+    assert_eq!(format!("{}", Value::Bytes(Vec::new())), "<bytes: 0>");
+    assert!(!Value::Bytes(Vec::new()).is_truthy());
+    assert_eq!(Value::Bytes(vec![1]).type_name(), "bytes");
+    assert_eq!(Value::Bytes(vec![1]).type_name_ar(), "بايتات");
+
+    assert_eq!(eval("ب = نص_إلى_بايتات(\"abc\")\nب[١] + ب[٢]"), Value::Int(98 + 99));
+    assert_eq!(eval("نص_إلى_بايتات(\"abc\")[٠]"), Value::Int(97));
+    assert_eq!(eval("ق = [نص_إلى_بايتات(\"xy\")]\nق[٠][١]"), Value::Int(121));
+    assert_eq!(error("ب = نص_إلى_بايتات(\"ab\")\nب[٢]").kind, "فهرسة خارج النطاق");
+    assert_eq!(error("ب = نص_إلى_بايتات(\"ab\")\nب[٠][٠]").kind, "عملية فهرسة غير صالحة");
+}
+
+#[test]
+fn test_negative_and_fractional_indexes_are_rejected() {
+    let err = error("س = [١، ٢]\nس[-١]");
+    assert_eq!(err.kind, "فهرس غير صالح");
+    assert_eq!(err.message_en, "Index must be a non-negative whole number: -1");
+    let err = error("س = [١، ٢]\nس[-1.5]");
+    assert_eq!(err.message_en, "Index must be a non-negative whole number: -1.5");
+    let err = error("ب = نص_إلى_بايتات(\"ab\")\nب[0.5]");
+    assert_eq!(err.message_en, "Index must be a non-negative whole number: 0.5");
+    assert_eq!(eval("س = [١، ٢]\nس[1.0]"), Value::Int(2));
+}
+
+#[test]
+fn test_bad_encodings_are_reported() {
+    let err = error("نص_إلى_بايتات(\"س\"، \"latin9\")");
+    assert_eq!(err.kind, "ترميز غير معروف");
+    assert!(err.suggestion.unwrap().contains("windows-1256"));
+    let err = error("نص_إلى_بايتات(\"سلام 😀\"، \"windows-1256\")");
+    assert_eq!(err.message_en, "'😀' cannot be represented in windows-1256");
+    let err = error("بايتات_إلى_نص(نص_إلى_بايتات(\"سلام\"، \"windows-1256\"))");
+    assert_eq!(err.message_en, "The bytes are not valid UTF-8 text");
+    assert_eq!(error("بايتات_إلى_نص(\"نص\")").kind, "نوع وسيط غير صحيح");
+}
+
+#[test]
+fn test_read_bytes_returns_the_file_as_is() {
+    let path = std::env::temp_dir().join(format!("iqra_bytes_{}.bin", std::process::id()));
+    fs::write(&path, [0x89, b'P', b'N', b'G', 0xff]).unwrap();
+    let code = format!("ب = اقرأ_بايتات({:?})\n[طول(ب)، ب[٤]]", path.to_string_lossy());
+    assert_eq!(eval(&code), Value::List(vec![Value::Int(5), Value::Int(255)]));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_bytes_serialize_as_base64() {
+    let value = Value::Bytes("مرحبا".as_bytes().to_vec());
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"Bytes":"2YXYsdit2KjYpw=="}"#);
+    assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), value);
+}
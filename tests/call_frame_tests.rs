@@ -0,0 +1,53 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+#[test]
+fn test_recursive_calls_return_the_right_values() {
+    let code = "دالة فيب(ن) {\n    اذا ن < ٢ {\n        ارجع ن\n    }\n    ارجع فيب(ن - ١) + فيب(ن - ٢)\n}\nفيب(٢٠)";
+    assert_eq!(eval(code), Value::Int(6765));
+}
+
+#[test]
+fn test_call_changes_are_undone_on_return() {
+    let mut runtime = Runtime::new();
+    let code = "س = ١\nن = \"خارجي\"\n\
+                دالة غير(ن) {\n    س = ٩\n    جديد = ٣\n    ثابت ح = ٢\n    ارجع س + ن\n}\n\
+                ناتج = غير(١٠)";
+    runtime.execute(code).unwrap();
+    let variables = runtime.get_variables();
+    assert_eq!(variables.get("ناتج"), Some(&Value::Int(19)));
+    assert_eq!(variables.get("س"), Some(&Value::Int(1)));
+    assert_eq!(variables.get("ن"), Some(&Value::String("خارجي".to_string())));
+    assert_eq!(variables.get("جديد"), None);
+    // The constant declared inside the call is gone too.
+    runtime.execute("ح = ٥").unwrap();
+}
+
+#[test]
+fn test_call_changes_are_undone_after_an_error() {
+    let mut runtime = Runtime::new();
+    runtime.execute("س = ١\nدالة فاشلة() {\n    س = ٢\n    ارجع ١ / ٠\n}").unwrap();
+    assert!(runtime.execute("فاشلة()").is_err());
+    assert_eq!(runtime.get_variables().get("س"), Some(&Value::Int(1)));
+}
+
+#[test]
+fn test_return_leaves_loops_and_try_blocks() {
+    let code = "دالة أول_أكبر(قيم، حد) {\n    ي = ٠\n    بينما ي < طول(قيم) {\n        اذا قيم[ي] > حد {\n            ارجع قيم[ي]\n        }\n        ي = ي + ١\n    }\n    ارجع -١\n}\n\
+                أول_أكبر([١، ٥، ٩]، ٣)";
+    assert_eq!(eval(code), Value::Int(5));
+    let code = "دالة آمنة() {\n    جرب {\n        ارجع \"من جرب\"\n    } امسك {\n        ارجع \"من امسك\"\n    }\n    ارجع \"بعد\"\n}\nآمنة()";
+    assert_eq!(eval(code), Value::String("من جرب".to_string()));
+}
+
+#[test]
+fn test_return_value_does_not_leak_into_the_caller() {
+    let code = "دالة واحد() {\n    ارجع ١\n}\nدالة اثنان() {\n    واحد()\n    ارجع ٢\n}\nاثنان()";
+    assert_eq!(eval(code), Value::Int(2));
+    let err = Runtime::new().execute("اذا صحيح {\n    ارجع ١\n}").unwrap_err();
+    assert!(err.to_string().contains("return can only be used inside a function"), "{}", err);
+}
@@ -0,0 +1,86 @@
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::runtime::{CANCELLED_ERROR_KIND, IqraError, Runtime};
+use iqra::lang::value::Value;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Runs `code` on another thread, cancels it `settle` after it has printed
+/// its first line, and returns the error and how long it took to arrive
+/// after the cancellation.
+fn cancel_while_running(code: &str, settle: Duration) -> (IqraError, Duration) {
+    let output = CapturedOutput::new();
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(output.clone()));
+    let token = runtime.cancellation_token();
+    let (sender, results) = mpsc::channel();
+    let code = code.to_string();
+    thread::spawn(move || {
+        let _ = sender.send(runtime.execute(&code));
+    });
+    while output.contents().is_empty() {
+        thread::sleep(Duration::from_millis(5));
+    }
+    thread::sleep(settle);
+    let cancelled_at = Instant::now();
+    token.cancel();
+    let result = results.recv_timeout(Duration::from_secs(5)).expect("the run was not cancelled");
+    let err = result.unwrap_err().downcast::<IqraError>().unwrap();
+    (err, cancelled_at.elapsed())
+}
+
+#[test]
+fn test_cancel_stops_an_infinite_loop() {
+    let (err, waited) = cancel_while_running("اطبع(\"بدأ\")\nبينما صحيح {\n}\n", Duration::ZERO);
+    assert_eq!(err.kind, CANCELLED_ERROR_KIND);
+    assert_eq!(err.message_en, "The program was cancelled");
+    assert!(waited < Duration::from_secs(1), "{:?}", waited);
+}
+
+#[test]
+fn test_cancellation_cannot_be_caught() {
+    let (err, _) = cancel_while_running(
+        "اطبع(\"بدأ\")\nبينما صحيح {\n    جرب {\n        س = ١ / ٠\n    } امسك (خ) {\n    }\n}\n",
+        Duration::ZERO,
+    );
+    assert_eq!(err.kind, CANCELLED_ERROR_KIND);
+}
+
+#[test]
+fn test_cancel_stops_parallel_workers() {
+    // Workers' output is only shown when they finish, so the script prints
+    // first and the workers get a moment to start looping.
+    let (err, waited) = cancel_while_running(
+        "دالة دوّر(ن) {\n    بينما صحيح {\n    }\n}\nاطبع(\"بدأ\")\nخريطة_متوازية([١، ٢، ٣]، \"دوّر\")\n",
+        Duration::from_millis(50),
+    );
+    assert_eq!(err.kind, CANCELLED_ERROR_KIND);
+    assert!(waited < Duration::from_secs(1), "{:?}", waited);
+}
+
+#[test]
+fn test_a_cancellation_stops_one_run() {
+    let mut runtime = Runtime::new();
+    let token = runtime.cancellation_token();
+    // Cancelled before it starts, the next run stops at its first statement.
+    token.cancel();
+    let err = runtime.execute("س = ١").unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.kind, CANCELLED_ERROR_KIND);
+    assert_eq!(err.line, Some(1));
+    assert!(runtime.execute("س = ٢\nس").is_ok_and(|value| value == Value::Int(2)));
+
+    token.cancel();
+    token.reset();
+    assert!(runtime.execute("س").is_ok());
+}
+
+#[test]
+fn test_one_token_can_serve_a_replacement_runtime() {
+    let token = Runtime::new().cancellation_token();
+    let mut runtime = Runtime::new();
+    runtime.set_cancellation_token(token.clone());
+    token.cancel();
+    assert!(runtime.execute("١").is_err());
+    assert!(runtime.execute("١").is_ok());
+    assert!(!token.is_cancelled());
+}
@@ -0,0 +1,82 @@
+use iqra::lang::lexer::Lexer;
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::parser::{Parser, StmtKind};
+use iqra::lang::runtime::{IqraError, Runtime};
+
+fn run(code: &str) -> (String, anyhow::Result<()>) {
+    let output = CapturedOutput::new();
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(output.clone()));
+    let result = runtime.execute(code).map(|_| ());
+    (output.contents(), result)
+}
+
+/// A try whose body fails with `failure`, followed by three arms.
+fn with_arms(failure: &str) -> String {
+    format!(
+        "جرب {{\n    {}\n}} امسك \"قسمة على صفر\" (خ) {{\n    اطبع(\"قسمة\")\n}}\nامسك \"Index out of range\" {{\n    اطبع(\"فهرس\")\n}} امسك (خ) {{\n    اطبع(\"غيره\")\n}}\n",
+        failure
+    )
+}
+
+#[test]
+fn test_arms_are_parsed_in_order() {
+    let statements = Parser::new(Lexer::new(&with_arms("١"))).parse().unwrap();
+    assert_eq!(statements.len(), 1);
+    let StmtKind::TryCatch { catches, .. } = &statements[0].kind else { panic!() };
+    let kinds: Vec<Option<&str>> = catches.iter().map(|arm| arm.kind.as_deref()).collect();
+    assert_eq!(kinds, [Some("قسمة على صفر"), Some("Index out of range"), None]);
+    assert!(catches[0].error_var.as_ref().is_some_and(|var| *var == "خ"));
+    assert!(catches[1].error_var.is_none());
+    assert_eq!(statements[0].span.end_line, 10);
+}
+
+#[test]
+fn test_the_first_matching_arm_runs() {
+    let (output, result) = run(&with_arms("س = ١ / ٠"));
+    result.unwrap();
+    assert_eq!(output, "قسمة\n");
+}
+
+#[test]
+fn test_unmatched_errors_fall_to_the_catch_all() {
+    let (output, result) = run(&with_arms("اطبع(مجهول)"));
+    result.unwrap();
+    assert_eq!(output, "غيره\n");
+}
+
+#[test]
+fn test_errors_no_arm_matches_are_raised_again() {
+    let code = "جرب {\n    اطبع(مجهول)\n} امسك \"قسمة على صفر\" {\n    اطبع(\"قسمة\")\n} امسك أخطاء \"timeout\" {\n    اطبع(\"مهلة\")\n}\n";
+    let (output, result) = run(code);
+    assert_eq!(output, "");
+    let err = result.unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.kind, "متغير غير معرف");
+    assert_eq!(err.line, Some(2));
+
+    // An outer try catches what the inner one passed on.
+    let (output, result) =
+        run(&format!("جرب {{\n{}}} امسك (خ) {{\n    اطبع(\"خارجي\")\n}}\n", code));
+    result.unwrap();
+    assert_eq!(output, "خارجي\n");
+}
+
+#[test]
+fn test_arms_match_errors_raised_inside_functions() {
+    let code = format!(
+        "دالة اقسم(أ، ب) {{\n    ارجع أ / ب\n}}\nدالة خارجية() {{\n    ارجع اقسم(١، ٠)\n}}\n{}",
+        with_arms("اقسم(١، ٠)")
+    );
+    let (output, result) = run(&code);
+    result.unwrap();
+    assert_eq!(output, "قسمة\n");
+
+    // Through two calls, and keeping the line inside the function.
+    let (output, result) = run(&code.replace("    اقسم(١، ٠)\n}", "    خارجية()\n}"));
+    result.unwrap();
+    assert_eq!(output, "قسمة\n");
+    let err = run("دالة اقسم(أ، ب) {\n    ارجع أ / ب\n}\nاقسم(١، ٠)\n").1.unwrap_err();
+    let err = err.downcast::<IqraError>().unwrap();
+    assert_eq!(err.kind, "قسمة على صفر");
+    assert_eq!(err.line, Some(2));
+}
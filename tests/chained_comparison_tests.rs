@@ -0,0 +1,81 @@
+use iqra::lang::runtime::{IqraError, Runtime};
+use iqra::lang::value::Value;
+
+fn error(source: &str) -> IqraError {
+    Runtime::new().execute(source).unwrap_err().downcast::<IqraError>().unwrap()
+}
+
+/// The error of `source` run through the optimizer first, as `iqra run` does.
+fn optimized_error(source: &str) -> IqraError {
+    let mut runtime = Runtime::new();
+    runtime.set_optimize(true);
+    runtime.execute(source).unwrap_err().downcast::<IqraError>().unwrap()
+}
+
+#[test]
+fn test_chained_comparison_suggests_two_comparisons() {
+    let err = error("س = ٥\nاطبع(١ < س < ١٠)");
+    assert_eq!(err.kind, "مقارنة غير صالحة");
+    assert!(err.message_en.contains("Comparisons do not chain"), "{}", err.message_en);
+    assert_eq!(err.line, Some(2));
+    assert_eq!(err.suggestion.as_deref(), Some("استخدم: ١ < س و س < ١٠ | Use: 1 < س and س < 10"));
+}
+
+#[test]
+fn test_inclusive_chain_also_suggests_between() {
+    let suggestion = error("س = ٥\nس_صحيح = ١ <= س <= ١٠").suggestion.unwrap();
+    assert!(suggestion.contains("أو بين؟(س، ١، ١٠)"), "{}", suggestion);
+    assert!(suggestion.contains("or between(س, 1, 10)"), "{}", suggestion);
+    let suggestion = error("س = ٥\nاطبع(١٠ >= س >= ٠٫٥)").suggestion.unwrap();
+    assert!(suggestion.contains("بين؟(س، ٠٫٥، ١٠)"), "{}", suggestion);
+}
+
+#[test]
+fn test_chain_of_larger_expressions_gets_a_general_hint() {
+    let suggestion = error("س = ٥\nاطبع(١ < س * ٢ < ١٠)").suggestion.unwrap();
+    assert!(suggestion.contains("Compare each pair on its own"), "{}", suggestion);
+}
+
+#[test]
+fn test_boolean_against_number_gets_the_hint() {
+    // The optimizer folds `١ < ٢` to صحيح before the second comparison runs.
+    for source in ["اطبع(١ < ٢ < ٣)", "اطبع(صحيح < ٣)", "اطبع(٣ >= خطأ)"] {
+        let err = optimized_error(source);
+        assert_eq!(
+            err.message_en, "Comparisons do not chain: a boolean can't be compared with a number",
+            "{}",
+            source
+        );
+        assert!(err.suggestion.unwrap().contains("Compare each pair on its own"), "{}", source);
+    }
+}
+
+#[test]
+fn test_other_comparison_errors_are_unchanged() {
+    let err = error("اطبع(\"أ\" < ١)");
+    assert_eq!(err.message_en, "Invalid operands for comparison");
+    let err = error("اطبع(صحيح < \"أ\")");
+    assert_eq!(err.message_en, "Invalid operands for comparison");
+}
+
+#[test]
+fn test_between_is_inclusive() {
+    let mut runtime = Runtime::new();
+    let cases = [
+        ("بين؟(٥، ١، ١٠)", true),
+        ("بين؟(١، ١، ١٠)", true),
+        ("between(10, 1, 10)", true),
+        ("بين؟(٠٫٥، ١، ١٠)", false),
+        ("بين؟(١١، ١، ١٠)", false),
+        ("بين؟(٥، ١٠، ١)", false),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(runtime.execute(source).unwrap(), Value::Bool(expected), "{}", source);
+    }
+}
+
+#[test]
+fn test_between_rejects_bad_arguments() {
+    assert_eq!(error("بين؟(\"أ\"، ١، ٢)").message_en, "between expects three numbers");
+    assert_eq!(error("بين؟(١، ٢)").kind, "عدد وسائط غير صحيح");
+}
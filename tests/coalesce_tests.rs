@@ -0,0 +1,48 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+const SETTINGS: &str =
+    "دالة لا_شيء() {}\nإعدادات = قاموس(\"لون\"، \"أحمر\"، \"حجم\"، لا_شيء())\n";
+
+#[test]
+fn test_missing_key_uses_the_default() {
+    let code = format!("{}إعدادات[\"خط\"] ?? \"أزرق\"", SETTINGS);
+    assert_eq!(eval(&code), Value::String("أزرق".to_string()));
+    assert_eq!(eval("[١، ٢][٥] وإلا_قيمة ٠"), Value::Int(0));
+}
+
+#[test]
+fn test_present_key_keeps_its_value() {
+    let code = format!("{}قيمة = إعدادات[\"لون\"] ?? \"أزرق\"\nقيمة", SETTINGS);
+    assert_eq!(eval(&code), Value::String("أحمر".to_string()));
+    assert_eq!(eval("خطأ ?? صحيح"), Value::Bool(false));
+}
+
+#[test]
+fn test_nil_value_uses_the_default() {
+    let code = format!("{}إعدادات[\"حجم\"] ?? ١٢", SETTINGS);
+    assert_eq!(eval(&code), Value::Int(12));
+}
+
+#[test]
+fn test_default_is_only_evaluated_when_needed() {
+    assert_eq!(eval("١ ?? ١ / ٠"), Value::Int(1));
+    assert_eq!(eval("قاموس()[\"أ\"] ?? قاموس()[\"ب\"] ?? ٣"), Value::Int(3));
+}
+
+#[test]
+fn test_binds_below_other_operators() {
+    assert_eq!(eval("قاموس()[\"أ\"] ?? ١ + ٢"), Value::Int(3));
+    assert_eq!(eval("[٥][٠] + ١ ?? ٠"), Value::Int(6));
+}
+
+#[test]
+fn test_other_errors_propagate() {
+    assert!(Runtime::new().execute("(١ - \"أ\") ?? ٠").is_err());
+    assert!(Runtime::new().execute("غير_معرف ?? ٠").is_err());
+    assert!(Runtime::new().execute("٥[٠] ?? ٠").is_err());
+}
@@ -0,0 +1,67 @@
+use assert_cmd::Command;
+use iqra::cli::join_code;
+use predicates::str::contains;
+
+fn iqra() -> Command {
+    let mut command = Command::cargo_bin("iqra").unwrap();
+    command.env("IQRA_LANG", "ar").env("NO_COLOR", "1");
+    command
+}
+
+#[test]
+fn test_repeated_code_flags_become_lines() {
+    iqra()
+        .args([
+            "code",
+            "-c",
+            "دالة ضعف(ن) {",
+            "-c",
+            "    ارجع ن * ٢",
+            "-c",
+            "}",
+            "-c",
+            "اطبع(ضعف(٢١))",
+        ])
+        .assert()
+        .success()
+        .stdout("42\n");
+}
+
+#[test]
+fn test_code_as_positional_argument() {
+    iqra().args(["code", "س = ٤\nاطبع(س + ١)"]).assert().success().stdout("5\n");
+    // Code from -c and a positional source together is refused, not dropped.
+    for args in [["code", "-c", "اطبع(١)", "اطبع(٢)"], ["code", "اطبع(٢)", "-c", "اطبع(١)"]]
+    {
+        iqra().args(args).assert().code(2).stdout("").stderr(contains("cannot be used with"));
+    }
+}
+
+#[test]
+fn test_escape_turns_backslash_n_into_newlines() {
+    iqra()
+        .args(["code", "--escape", "س = ٠\\nبينما س < ٣ {\\n    س = س + ١\\n}\\nاطبع(س)"])
+        .assert()
+        .success()
+        .stdout("3\n");
+    assert_eq!(join_code(&["أ\\nب".to_string()], false), "أ\\nب");
+    assert_eq!(join_code(&["أ\\nب".to_string(), "ج".to_string()], true), "أ\nب\nج");
+}
+
+#[test]
+fn test_parse_and_runtime_errors_exit_differently() {
+    iqra()
+        .args(["code", "-c", "اطبع(١)", "-c", "س = ("])
+        .assert()
+        .code(2)
+        .stdout("")
+        .stderr(contains("--> <cmdline>:2"))
+        .stderr(contains("2 | \u{2066}س = ("));
+    iqra()
+        .args(["code", "-c", "اطبع(١)", "-c", "اطبع(غير_معرف)"])
+        .assert()
+        .code(1)
+        .stdout("1\n")
+        .stderr(contains("خطأ[متغير غير معرف]"))
+        .stderr(contains("--> <cmdline>:2"));
+}
@@ -0,0 +1,80 @@
+use iqra::lang::lexer::Lexer;
+use iqra::lang::parser::Parser;
+use iqra::lang::runtime::{IqraError, Runtime};
+use iqra::lang::value::Value;
+
+fn run(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn parse_error(code: &str) -> IqraError {
+    Runtime::new().execute(code).unwrap_err().downcast::<IqraError>().unwrap()
+}
+
+#[test]
+fn test_if_condition_starting_with_parenthesized_groups() {
+    let code = "س = ٥\nص = ٣\nن = ٠\nاذا (س > ١) و (ص > ٢) {\n    ن = ١\n}\nن";
+    assert_eq!(run(code), Value::Int(1));
+    let code = "س = ٥\nص = ١\nن = ٠\nاذا (س > ١) و (ص > ٢) {\n    ن = ١\n} وإلا {\n    ن = ٢\n}\nن";
+    assert_eq!(run(code), Value::Int(2));
+    let code = "س = ٠\nن = ٠\nاذا (س > ١) أو (س == ٠) {\n    ن = ١\n}\nن";
+    assert_eq!(run(code), Value::Int(1));
+}
+
+#[test]
+fn test_nested_parentheses_in_conditions() {
+    let code = "س = ٥\nص = ٣\nن = ٠\nاذا ((س > ١) و (ص > ٢)) أو خطأ {\n    ن = ١\n}\nن";
+    assert_eq!(run(code), Value::Int(1));
+    let code = "س = ٥\nن = ٠\nاذا ليس ((س < ١) أو (س > ٩)) {\n    ن = ١\n}\nن";
+    assert_eq!(run(code), Value::Int(1));
+}
+
+#[test]
+fn test_mixed_arabic_and_ascii_logical_operators() {
+    let code = "س = ٥\nص = ٣\nن = ٠\nاذا (س > ١) && (ص > ٢) أو (س < 0) {\n    ن = ١\n}\nن";
+    assert_eq!(run(code), Value::Int(1));
+    let code = "x = 5\ny = 3\nn = 0\nif (x > 1) and (y > 2) || not (x) {\n    n = 1\n}\nn";
+    assert_eq!(run(code), Value::Int(1));
+    let code = "س = ٠\nاذا (س > ١) و (س < ٩) {\n    س = ١\n} وإلا اذا !(س > ١) and (س == ٠) {\n    س = ٧\n}\nس";
+    assert_eq!(run(code), Value::Int(7));
+}
+
+#[test]
+fn test_while_condition_with_parenthesized_groups() {
+    let code = "س = ٠\nص = ١٠\nبينما (س < ٣) و (ص > ٠) {\n    س = س + ١\n    ص = ص - ١\n}\n[س، ص]";
+    assert_eq!(run(code), run("[٣، ٧]"));
+    let code = "n = 0\nwhile (n < 5) && ((n % 2 == 0) || (n < 10)) {\n    n = n + 1\n}\nn";
+    assert_eq!(run(code), Value::Int(5));
+}
+
+#[test]
+fn test_unbalanced_paren_is_reported_at_the_body_brace() {
+    let err = parse_error("س = ٥\nص = ٣\nاذا (س > ١ و (ص > ٢) {\n    اطبع(س)\n}\n");
+    assert_eq!(err.kind, "قوس غير مغلق");
+    assert_eq!((err.line, err.column), (Some(3), Some(22)));
+    assert!(err.message_en.contains("The ( at line 3 column 5 is not closed"), "{}", err.message_en);
+
+    let err = parse_error("س = ٠\nبينما (س < ٣ {\n    س = س + ١\n}\n");
+    assert_eq!((err.line, err.column), (Some(2), Some(14)));
+    assert!(err.message_en.contains("found { where ) was expected"), "{}", err.message_en);
+}
+
+#[test]
+fn test_stray_paren_and_incomplete_condition_are_reported_where_they_are() {
+    let err = parse_error("س = ٥\nاذا (س > ١)) {\n    اطبع(س)\n}\n");
+    assert_eq!(err.kind, "قوس غير مفتوح");
+    assert_eq!((err.line, err.column), (Some(2), Some(12)));
+
+    let err = parse_error("س = ٥\nاذا (س > ١) و {\n    اطبع(س)\n}\n");
+    assert_eq!(err.kind, "شرط ناقص");
+    assert_eq!((err.line, err.column), (Some(2), Some(15)));
+}
+
+#[test]
+fn test_recovery_goes_on_after_an_unclosed_paren() {
+    let code = "اذا (س > ١ {\n    اطبع(١)\n}\nاطبع(٢\nاطبع(٣)\n";
+    let (statements, errors) = Parser::new(Lexer::new(code)).parse_with_recovery();
+    let lines: Vec<_> = errors.iter().map(|err| (err.kind.as_str(), err.line)).collect();
+    assert_eq!(lines, [("قوس غير مغلق", Some(1)), ("قوس غير مغلق", Some(4))]);
+    assert_eq!(statements.len(), 1);
+}
@@ -0,0 +1,47 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn run_err(code: &str) -> String {
+    Runtime::new().execute(code).unwrap_err().to_string()
+}
+
+#[test]
+fn test_constant_is_defined_with_arabic_decimal() {
+    let mut runtime = Runtime::new();
+    runtime.execute("ثابت نسبة = ٢٫٥\nمساحة = نسبة * ٢ * ٢").unwrap();
+    assert_eq!(runtime.get_variables().get("نسبة"), Some(&Value::Number(2.5)));
+    assert_eq!(runtime.get_variables().get("مساحة"), Some(&Value::Number(10.0)));
+}
+
+#[test]
+fn test_reassigning_constant_at_top_level_fails() {
+    let err = run_err("ثابت باي = ٣٫١٤\nباي = ٣");
+    assert!(err.contains("Cannot reassign constant: باي"), "{}", err);
+    assert!(err.contains("Line: 2"), "{}", err);
+
+    let err = run_err("const LIMIT = 10\nconst LIMIT = 20");
+    assert!(err.contains("Cannot reassign constant: LIMIT"), "{}", err);
+}
+
+#[test]
+fn test_constant_is_visible_but_protected_inside_function() {
+    let mut runtime = Runtime::new();
+    runtime.execute("ثابت حد = ١٠\nدالة ضمن(س) {\n    ارجع حد - س\n}\nنتيجة = ضمن(٥)").unwrap();
+    assert_eq!(runtime.get_variables().get("نتيجة"), Some(&Value::Number(5.0)));
+
+    let err = run_err("ثابت حد = ١٠\nدالة غير(س) {\n    حد = س\n}\nغير(٢)");
+    assert!(err.contains("Cannot reassign constant: حد"), "{}", err);
+}
+
+#[test]
+fn test_constant_declared_in_function_does_not_leak() {
+    let mut runtime = Runtime::new();
+    runtime.execute("دالة ف() {\n    ثابت محلي = ١\n    ارجع محلي\n}\nف()\nمحلي = ٢").unwrap();
+    assert_eq!(runtime.get_variables().get("محلي"), Some(&Value::Number(2.0)));
+}
+
+#[test]
+fn test_const_requires_name() {
+    let err = run_err("ثابت = ٣");
+    assert!(err.contains("Expected a name after const"), "{}", err);
+}
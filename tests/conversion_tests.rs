@@ -0,0 +1,71 @@
+use iqra::lang::runtime::{IqraError, Runtime};
+use iqra::lang::value::Value;
+use std::collections::HashMap;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn error_en(err: anyhow::Error) -> String {
+    err.downcast_ref::<IqraError>().expect("IqraError").message_en.clone()
+}
+
+#[test]
+fn test_owned_conversions() {
+    assert_eq!(f64::try_from(eval("١٫٥")).unwrap(), 1.5);
+    assert_eq!(f64::try_from(eval("٢")).unwrap(), 2.0);
+    assert_eq!(i64::try_from(eval("٤٠ + ٢")).unwrap(), 42);
+    assert_eq!(i64::try_from(Value::Number(3.0)).unwrap(), 3);
+    assert!(bool::try_from(eval("١ < ٢")).unwrap());
+    assert_eq!(String::try_from(eval("\"سلام\"")).unwrap(), "سلام");
+    assert_eq!(Vec::<Value>::try_from(eval("[١، ٢]")).unwrap(), vec![Value::Int(1), Value::Int(2)]);
+    let map = HashMap::<String, Value>::try_from(eval("قاموس(\"أ\"، ١)")).unwrap();
+    assert_eq!(map.get("أ"), Some(&Value::Int(1)));
+}
+
+#[test]
+fn test_reference_conversions() {
+    let value = eval("[\"أ\"، ٢٫٥]");
+    let list: &Vec<Value> = (&value).try_into().unwrap();
+    let text: &str = (&list[0]).try_into().unwrap();
+    let number: f64 = (&list[1]).try_into().unwrap();
+    assert_eq!((text, number), ("أ", 2.5));
+}
+
+#[test]
+fn test_wrong_type_is_a_descriptive_error() {
+    assert_eq!(
+        error_en(i64::try_from(Value::Number(1.5)).unwrap_err()),
+        "Cannot convert number to i64"
+    );
+    assert_eq!(
+        error_en(String::try_from(Value::Int(1)).unwrap_err()),
+        "Cannot convert number to string"
+    );
+    assert_eq!(error_en(bool::try_from(Value::Nil).unwrap_err()), "Cannot convert nil to bool");
+}
+
+#[test]
+fn test_get_path_digs_into_nested_values() {
+    let value = eval("قاموس(\"أ\"، قاموس(\"ب\"، [١، ٢، قاموس(\"ج\"، \"هنا\")]))\n");
+    assert_eq!(value.get_path("أ.ب[2].ج").unwrap(), &Value::String("هنا".to_string()));
+    assert_eq!(value.get_path("أ.ب[0]").unwrap(), &Value::Int(1));
+    assert_eq!(eval("[[١، ٢]، [٣]]").get_path("[0][1]").unwrap(), &Value::Int(2));
+}
+
+#[test]
+fn test_get_path_errors_name_the_segment() {
+    let value = eval("قاموس(\"أ\"، [١، ٢])");
+    assert_eq!(error_en(value.get_path("ب").unwrap_err()), "Key not found: ب (in path 'ب')");
+    assert_eq!(
+        error_en(value.get_path("أ[5]").unwrap_err()),
+        "Index out of bounds: 5 (in path 'أ[5]')"
+    );
+    assert_eq!(
+        error_en(value.get_path("أ.ج").unwrap_err()),
+        "Cannot get key 'ج' from a list (in path 'أ.ج')"
+    );
+    assert!(value.get_path("أ[x]").is_err());
+    assert!(value.get_path("أ..ب").is_err());
+    assert!(value.get_path("أ[1").is_err());
+}
@@ -0,0 +1,169 @@
+use iqra::cli::debugger::{DebugCommand, Debugger};
+use iqra::lang::runtime::{DebugAction, Runtime};
+use std::sync::{Arc, Mutex};
+
+const PROGRAM: &str = "دالة ضعف(س) {\n    ارجع س * ٢\n}\nأ = ضعف(٢)\nب = ١\n";
+
+/// Attaches `debugger` with a prompt that always answers `answer` and records
+/// the (line, depth) of every pause.
+fn record_pauses(
+    debugger: &Debugger,
+    runtime: &mut Runtime,
+    answer: DebugCommand,
+) -> Arc<Mutex<Vec<(usize, usize)>>> {
+    let pauses = Arc::new(Mutex::new(Vec::new()));
+    let recorded = pauses.clone();
+    debugger.attach(runtime, move |stmt, runtime, _| {
+        recorded.lock().unwrap().push((stmt.span.start_line, runtime.call_depth()));
+        answer.clone()
+    });
+    pauses
+}
+
+#[test]
+fn test_step_hook_sees_every_statement() {
+    let mut runtime = Runtime::new();
+    let count = Arc::new(Mutex::new(0));
+    let counter = count.clone();
+    runtime.set_step_hook(Box::new(move |_, _| {
+        *counter.lock().unwrap() += 1;
+        DebugAction::Continue
+    }));
+    runtime.execute(PROGRAM).unwrap();
+    // definition, two assignments, and the return inside the function body
+    assert_eq!(*count.lock().unwrap(), 4);
+
+    runtime.clear_step_hook();
+    runtime.execute("ج = ٣").unwrap();
+    assert_eq!(*count.lock().unwrap(), 4);
+}
+
+#[test]
+fn test_breakpoint_in_while_loop_stops_every_iteration() {
+    let mut runtime = Runtime::new();
+    let debugger = Debugger::new();
+    debugger.add_breakpoint(3);
+    debugger.continue_running();
+    let pauses = record_pauses(&debugger, &mut runtime, DebugCommand::Continue);
+
+    runtime.execute("عدد = ٠\nبينما عدد < ٣ {\n    عدد = عدد + ١\n}\n").unwrap();
+    assert_eq!(*pauses.lock().unwrap(), vec![(3, 0), (3, 0), (3, 0)]);
+}
+
+#[test]
+fn test_step_in_enters_function_calls() {
+    let mut runtime = Runtime::new();
+    let debugger = Debugger::new();
+    let pauses = record_pauses(&debugger, &mut runtime, DebugCommand::StepIn);
+
+    runtime.execute(PROGRAM).unwrap();
+    assert_eq!(*pauses.lock().unwrap(), vec![(1, 0), (4, 0), (2, 1), (5, 0)]);
+}
+
+#[test]
+fn test_step_over_skips_function_bodies() {
+    let mut runtime = Runtime::new();
+    let debugger = Debugger::new();
+    let pauses = record_pauses(&debugger, &mut runtime, DebugCommand::StepOver);
+
+    runtime.execute(PROGRAM).unwrap();
+    assert_eq!(*pauses.lock().unwrap(), vec![(1, 0), (4, 0), (5, 0)]);
+}
+
+#[test]
+fn test_breakpoint_inside_function_is_hit_when_running() {
+    let mut runtime = Runtime::new();
+    let debugger = Debugger::new();
+    debugger.add_breakpoint(2);
+    debugger.continue_running();
+    let pauses = record_pauses(&debugger, &mut runtime, DebugCommand::Continue);
+
+    runtime.execute(PROGRAM).unwrap();
+    assert_eq!(*pauses.lock().unwrap(), vec![(2, 1)]);
+}
+
+#[test]
+fn test_quit_aborts_execution() {
+    let mut runtime = Runtime::new();
+    let debugger = Debugger::new();
+    let _pauses = record_pauses(&debugger, &mut runtime, DebugCommand::Quit);
+
+    let err = runtime.execute(PROGRAM).unwrap_err();
+    assert!(err.to_string().contains("Execution stopped by the debugger at line 1"));
+    assert!(!runtime.get_variables().contains_key("أ"));
+}
+
+#[test]
+fn test_breakpoint_commands_list_and_clear() {
+    let debugger = Debugger::new();
+    assert_eq!(DebugCommand::parse("نقطة_توقف ١٢"), Some(DebugCommand::SetBreakpoint(12)));
+    assert_eq!(DebugCommand::parse("break 4"), Some(DebugCommand::SetBreakpoint(4)));
+    assert_eq!(DebugCommand::parse("تابع"), Some(DebugCommand::Continue));
+    assert_eq!(DebugCommand::parse("ادخل"), Some(DebugCommand::StepIn));
+    assert_eq!(DebugCommand::parse("تخط"), Some(DebugCommand::StepOver));
+    assert_eq!(DebugCommand::parse("break"), None);
+
+    debugger.apply(&DebugCommand::parse("نقطة_توقف ١٢").unwrap());
+    debugger.apply(&DebugCommand::parse("break 4").unwrap());
+    let listed = debugger.apply(&DebugCommand::ListBreakpoints).unwrap();
+    assert!(listed.contains("4, 12"), "{}", listed);
+
+    debugger.apply(&DebugCommand::parse("clear 4").unwrap());
+    assert_eq!(debugger.breakpoints(), vec![12]);
+    debugger.apply(&DebugCommand::parse("امسح").unwrap());
+    assert!(debugger.breakpoints().is_empty());
+}
+
+#[test]
+fn test_breakpoint_commands_from_prompt_do_not_resume() {
+    let mut runtime = Runtime::new();
+    let debugger = Debugger::new();
+    // Popped from the end: set a breakpoint first, then continue.
+    let answers =
+        Arc::new(Mutex::new(vec![DebugCommand::Continue, DebugCommand::SetBreakpoint(5)]));
+    let pauses = Arc::new(Mutex::new(Vec::new()));
+    let recorded = pauses.clone();
+    debugger.attach(&mut runtime, move |stmt, _, _| {
+        recorded.lock().unwrap().push(stmt.span.start_line);
+        answers.lock().unwrap().pop().unwrap_or(DebugCommand::Continue)
+    });
+
+    runtime.execute(PROGRAM).unwrap();
+    // Paused at line 1 (asked twice: continue after setting the breakpoint), then at line 5.
+    assert_eq!(*pauses.lock().unwrap(), vec![1, 1, 5]);
+    assert_eq!(debugger.breakpoints(), vec![5]);
+}
+
+#[test]
+fn test_breakpoint_command_messages_reach_the_prompt() {
+    let mut runtime = Runtime::new();
+    let debugger = Debugger::new();
+    // Popped from the end.
+    let answers = Arc::new(Mutex::new(vec![
+        DebugCommand::Continue,
+        DebugCommand::ClearBreakpoint(Some(9)),
+        DebugCommand::ListBreakpoints,
+        DebugCommand::SetBreakpoint(5),
+    ]));
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let recorded = messages.clone();
+    debugger.attach(&mut runtime, move |_, _, message| {
+        recorded.lock().unwrap().push(message);
+        answers.lock().unwrap().pop().unwrap_or(DebugCommand::Continue)
+    });
+
+    runtime.execute(PROGRAM).unwrap();
+    let messages = messages.lock().unwrap();
+    let shown: Vec<Option<&str>> = messages.iter().map(Option::as_deref).collect();
+    assert_eq!(
+        shown,
+        vec![
+            None,
+            Some("نقطة توقف عند السطر 5 | Breakpoint set at line 5"),
+            Some("نقاط التوقف: 5 | Breakpoints: 5"),
+            Some("لا توجد نقطة توقف عند 9 | No breakpoint at line 9"),
+            // A fresh pause, at the breakpoint on line 5.
+            None,
+        ]
+    );
+}
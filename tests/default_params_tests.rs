@@ -0,0 +1,56 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+const GREET: &str =
+    "دالة رحب(اسم, تحية = \"مرحبا\", علامة = \"!\") {\n    ارجع تحية + \" \" + اسم + علامة\n}\n";
+
+fn call(code: &str) -> Value {
+    let mut runtime = Runtime::new();
+    runtime.execute(&format!("{}نتيجة = {}", GREET, code)).unwrap();
+    runtime.get_variables().get("نتيجة").cloned().unwrap()
+}
+
+#[test]
+fn test_defaults_fill_omitted_arguments() {
+    assert_eq!(call("رحب(\"سارة\")"), Value::String("مرحبا سارة!".to_string()));
+    assert_eq!(call("رحب(\"سارة\", \"أهلا\")"), Value::String("أهلا سارة!".to_string()));
+    assert_eq!(call("رحب(\"سارة\", \"أهلا\", \"؟\")"), Value::String("أهلا سارة؟".to_string()));
+}
+
+#[test]
+fn test_all_parameters_defaulted() {
+    let mut runtime = Runtime::new();
+    runtime.execute("دالة عد(بداية = ١) {\n    ارجع بداية * ١٠\n}\nأ = عد()\nب = عد(٣)").unwrap();
+    assert_eq!(runtime.get_variables().get("أ"), Some(&Value::Number(10.0)));
+    assert_eq!(runtime.get_variables().get("ب"), Some(&Value::Number(30.0)));
+}
+
+#[test]
+fn test_default_can_reference_earlier_parameter() {
+    let mut runtime = Runtime::new();
+    runtime
+        .execute("دالة مساحة(طول, عرض = طول) {\n    ارجع طول * عرض\n}\nمربع = مساحة(٤)\nمستطيل = مساحة(٤, ٢)")
+        .unwrap();
+    assert_eq!(runtime.get_variables().get("مربع"), Some(&Value::Number(16.0)));
+    assert_eq!(runtime.get_variables().get("مستطيل"), Some(&Value::Number(8.0)));
+}
+
+#[test]
+fn test_arity_errors_state_expected_and_received() {
+    let mut runtime = Runtime::new();
+    runtime.execute(GREET).unwrap();
+    let err = runtime.execute("رحب(\"أ\", \"ب\", \"ج\", \"د\")").unwrap_err().to_string();
+    assert!(err.contains("Function 'رحب' expects 1 to 3 argument(s) but received 4"), "{}", err);
+    let err = runtime.execute("رحب()").unwrap_err().to_string();
+    assert!(err.contains("expects 1 to 3 argument(s) but received 0"), "{}", err);
+
+    runtime.execute("دالة ثنائي(أ, ب) {\n    ارجع أ\n}").unwrap();
+    let err = runtime.execute("ثنائي(١)").unwrap_err().to_string();
+    assert!(err.contains("expects 2 argument(s) but received 1"), "{}", err);
+}
+
+#[test]
+fn test_required_parameter_after_default_is_rejected() {
+    let err = Runtime::new().execute("دالة خطأ_ترتيب(أ = ١, ب) {\n    ارجع أ\n}").unwrap_err();
+    assert!(err.to_string().contains("Parameter 'ب' without a default follows"), "{}", err);
+}
@@ -0,0 +1,78 @@
+use iqra::lang::runtime::{IqraError, Limits, Runtime};
+use iqra::lang::stepper::Stepper;
+use iqra::lang::value::Value;
+
+fn run(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn parse_error(code: &str) -> IqraError {
+    Runtime::new().execute(code).unwrap_err().downcast::<IqraError>().unwrap()
+}
+
+#[test]
+fn test_body_runs_once_even_when_the_condition_is_false() {
+    assert_eq!(run("س = ٠\nافعل {\n    س = س + ١\n} بينما خطأ\nس"), Value::Int(1));
+    assert_eq!(run("n = 0\ndo {\n    n = n + 1\n} while n < 5\nn"), Value::Int(5));
+    // The `بينما` may start the line after the closing brace.
+    assert_eq!(run("س = ٠\nافعل {\n    س = س + ٢\n}\nبينما س < ٥\nس"), Value::Int(6));
+}
+
+#[test]
+fn test_a_while_loop_right_after_a_do_while() {
+    let code = "أ = ٠\nب = ٠\nافعل {\n    أ = أ + ١\n} بينما أ < ٣\nبينما ب < أ {\n    ب = ب + ٢\n}\n[أ، ب]";
+    assert_eq!(run(code), Runtime::new().execute("[٣، ٤]").unwrap());
+    let code = "أ = ٠\nافعل { أ = أ + ١ } بينما أ < ٣\nبينما أ < ١٠ { أ = أ * ٢ }\nأ";
+    assert_eq!(run(code), Value::Int(12));
+}
+
+#[test]
+fn test_break_and_continue() {
+    // `استمر` goes on to the condition; `توقف` leaves the loop at once.
+    let code = "ع = ٠\nمجموع = ٠\nافعل {\n    ع = ع + ١\n    اذا ع % ٢ == ٠ {\n        استمر\n    }\n    اذا ع > ٧ {\n        توقف\n    }\n    مجموع = مجموع + ع\n} بينما ع < ١٠٠\n[ع، مجموع]";
+    assert_eq!(run(code), Runtime::new().execute("[٩، ١٦]").unwrap());
+    let code = "i = 0\ntotal = 0\nwhile true {\n    i = i + 1\n    if i == 2 { continue }\n    if i > 4 { break }\n    total = total + i\n}\ntotal";
+    assert_eq!(run(code), Value::Int(8));
+    // Only the innermost loop is left.
+    let code = "عدد = ٠\nأ = ٠\nبينما أ < ٣ {\n    أ = أ + ١\n    افعل {\n        عدد = عدد + ١\n        توقف\n    } بينما صحيح\n}\nعدد";
+    assert_eq!(run(code), Value::Int(3));
+    // `ارجع` leaves a do-while inside a function.
+    let code = "دالة أول(ق) {\n    ع = ٠\n    افعل {\n        اذا ق[ع] > ١ { ارجع ق[ع] }\n        ع = ع + ١\n    } بينما صحيح\n}\nأول([١، ٥، ٩])";
+    assert_eq!(run(code), Value::Int(5));
+}
+
+#[test]
+fn test_break_outside_a_loop_is_a_parse_error() {
+    let err = parse_error("س = ١\nتوقف");
+    assert_eq!(err.kind, "خارج حلقة");
+    assert_eq!((err.line, err.column), (Some(2), Some(1)));
+    assert_eq!(err.message_en, "break can only be used inside a loop");
+    // A function body is outside the loop it is defined in.
+    let err = parse_error("بينما صحيح {\n    دالة د() {\n        استمر\n    }\n}");
+    assert_eq!(err.message_ar, "لا يمكن استخدام استمر خارج حلقة");
+    assert_eq!(err.line, Some(3));
+}
+
+#[test]
+fn test_statement_limit_stops_an_endless_do_while() {
+    let mut runtime = Runtime::new();
+    runtime.set_limits(Limits { max_statements: Some(50), ..Limits::default() });
+    let err = runtime.execute("افعل {\n    س = ١\n} بينما صحيح").unwrap_err().to_string();
+    assert!(err.contains("exceeded the limit of 50 statements"), "{}", err);
+}
+
+#[test]
+fn test_stepping_through_a_do_while() {
+    let mut runtime = Runtime::new();
+    let mut stepper = Stepper::parse(
+        "ع = ٠\nافعل {\n    ع = ع + ١\n    اذا ع == ٢ {\n        توقف\n    }\n} بينما صحيح\nع\n",
+    )
+    .unwrap();
+    let mut lines = Vec::new();
+    while let Some(step) = stepper.next_step(&mut runtime) {
+        lines.push(step.unwrap().span.start_line);
+    }
+    // The first pass enters the body without checking the condition.
+    assert_eq!(lines, vec![1, 2, 3, 4, 2, 3, 4, 5, 8]);
+    assert_eq!(runtime.get_variables().get("ع"), Some(&Value::Int(2)));
+}
@@ -0,0 +1,68 @@
+use assert_cmd::Command;
+use iqra::cli::doc::{FileDoc, extract_docs, render_markdown};
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+use predicates::str::contains;
+use std::fs;
+
+const FIXTURE: &str = "tests/fixtures/documented.iqra";
+
+fn fixture_docs() -> FileDoc {
+    let source = fs::read_to_string(FIXTURE).unwrap();
+    FileDoc { file: "documented.iqra".to_string(), functions: extract_docs(&source).unwrap() }
+}
+
+#[test]
+fn test_markdown_matches_snapshot() {
+    let expected = fs::read_to_string("tests/fixtures/documented.md").unwrap();
+    assert_eq!(render_markdown(&[fixture_docs()]), expected);
+}
+
+#[test]
+fn test_doc_comment_must_be_directly_above_function() {
+    let docs =
+        extract_docs("/// منفصل\n\nدالة أ() { ارجع ١ }\n// عادي\nدالة ب() { ارجع ٢ }\n").unwrap();
+    assert_eq!(docs.len(), 2);
+    assert_eq!(docs[0].doc, None);
+    assert_eq!(docs[1].doc, None);
+}
+
+#[test]
+fn test_rest_parameter_is_listed_last() {
+    let docs = fixture_docs();
+    assert_eq!(docs.functions[2].params, vec!["اسم", "تحية", "...بقية"]);
+    assert_eq!(docs.functions[2].line, 15);
+}
+
+#[test]
+fn test_help_returns_doc_of_session_function() {
+    let mut runtime = Runtime::new();
+    let source = fs::read_to_string(FIXTURE).unwrap();
+    runtime.execute(&source).unwrap();
+    assert_eq!(
+        runtime.execute("مساعدة(\"جمع_عددين\")").unwrap(),
+        Value::String("يجمع عددين\nAdds two numbers.".to_string())
+    );
+    assert_eq!(runtime.execute("help(\"بدون_توثيق\")").unwrap(), Value::Nil);
+    assert!(runtime.execute("مساعدة(\"غير_موجودة\")").is_err());
+}
+
+#[test]
+fn test_doc_command_prints_json() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["doc", FIXTURE, "--format", "json"])
+        .assert()
+        .success()
+        .stdout(contains("\"doc\": \"يرحب بالاسم المعطى\""));
+}
+
+#[test]
+fn test_doc_command_rejects_unknown_format() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["doc", FIXTURE, "--format", "html"])
+        .assert()
+        .failure()
+        .stderr(contains("Unknown format"));
+}
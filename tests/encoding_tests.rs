@@ -0,0 +1,139 @@
+use iqra::lang::runtime::{Runtime, SystemExecutor};
+use iqra::lang::value::Value;
+use std::collections::HashMap;
+
+struct FileExecutor;
+
+impl SystemExecutor for FileExecutor {
+    fn exec(&self, _cmd: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn exec_with_io(&self, _cmd: &str, _input: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn read_file(&self, path: &str) -> std::io::Result<String> {
+        match path {
+            "data.txt" => Ok("mocked file content".to_string()),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::NotFound, "not found")),
+        }
+    }
+
+    fn write_file(&self, _path: &str, _content: &str) -> std::io::Result<bool> {
+        Ok(true)
+    }
+
+    fn list_files(&self, _path: &str) -> std::io::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    fn get_env_var(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    fn set_env_var(&self, _name: &str, _value: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn current_dir(&self) -> std::io::Result<String> {
+        Ok("/mock".to_string())
+    }
+
+    fn change_dir(&self, _path: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn process_id(&self) -> u32 {
+        42
+    }
+
+    fn read_line(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn read_password(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn system_info(&self) -> std::io::Result<HashMap<String, String>> {
+        Ok(HashMap::new())
+    }
+}
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn text(s: &str) -> Value {
+    Value::String(s.to_string())
+}
+
+#[test]
+fn test_base64_round_trip() {
+    assert_eq!(eval(r#"ترميز٦٤("مرحبا")"#), text("2YXYsdit2KjYpw=="));
+    assert_eq!(eval(r#"base64_encode("hello")"#), text("aGVsbG8="));
+    assert_eq!(eval(r#"فك٦٤("2YXYsdit2KjYpw==")"#), text("مرحبا"));
+}
+
+#[test]
+fn test_invalid_base64_is_catchable() {
+    let mut runtime = Runtime::new();
+    let err = runtime.execute(r#"base64_decode("not base64!")"#).unwrap_err().to_string();
+    assert!(err.contains("is not valid base64-encoded text"), "{}", err);
+
+    runtime
+        .execute("try {\n    base64_decode(\"%%%\")\n    نتيجة = 1\n} catch (e) {\n    نتيجة = 2\n}")
+        .unwrap();
+    assert_eq!(runtime.get_variables().get("نتيجة"), Some(&Value::Int(2)));
+}
+
+#[test]
+fn test_sha256_known_digests() {
+    assert_eq!(
+        eval(r#"sha256("")"#),
+        text("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+    );
+    assert_eq!(
+        eval(r#"تجزئة("مرحبا")"#),
+        text("80eff1a750bb540045622ad23c148c8875790515e3f768c77d5dff8c1d221b49")
+    );
+}
+
+#[test]
+fn test_sha256_file_uses_executor() {
+    let mut runtime = Runtime::new_with_executor(Box::new(FileExecutor));
+    assert_eq!(
+        runtime.execute(r#"تجزئة_ملف("data.txt")"#).unwrap(),
+        text("60ba2af316577a304eea8e135d9de47cd62e81a6b71f3fe426344c6d6d22280f")
+    );
+    let err = runtime.execute(r#"sha256_file("missing.txt")"#).unwrap_err().to_string();
+    assert!(err.contains("Failed to read file"), "{}", err);
+}
+
+#[test]
+fn test_uuid_is_v4_and_deterministic_with_seed() {
+    let id = match eval("معرف_فريد()") {
+        Value::String(id) => id,
+        other => panic!("expected string, got {:?}", other),
+    };
+    assert_eq!(id.len(), 36);
+    assert_eq!(id.chars().nth(14), Some('4'));
+
+    let seeded = |seed| {
+        let mut runtime = Runtime::new();
+        runtime.set_random_seed(seed);
+        runtime.execute("[uuid(), uuid()]").unwrap()
+    };
+    assert_eq!(seeded(7), seeded(7));
+    assert_ne!(seeded(7), seeded(8));
+}
+
+#[test]
+fn test_encoding_argument_errors() {
+    let mut runtime = Runtime::new();
+    let err = runtime.execute("sha256(5)").unwrap_err().to_string();
+    assert!(err.contains("sha256 expects string arguments"), "{}", err);
+    let err = runtime.execute(r#"uuid("x")"#).unwrap_err().to_string();
+    assert!(err.contains("expects 0 argument(s) but received 1"), "{}", err);
+}
@@ -0,0 +1,56 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn text(value: &str) -> Value {
+    Value::String(value.to_string())
+}
+
+#[test]
+fn test_enumerate_pairs_items_with_indexes() {
+    let expected = Value::List(vec![
+        Value::List(vec![Value::Int(0), text("أ")]),
+        Value::List(vec![Value::Int(1), text("ب")]),
+    ]);
+    assert_eq!(eval("رقّم([\"أ\"، \"ب\"])"), expected);
+    assert_eq!(
+        eval("enumerate([\"أ\"], ١)"),
+        Value::List(vec![Value::List(vec![Value::Int(1), text("أ")])])
+    );
+    assert_eq!(eval("enumerate([])"), Value::List(Vec::new()));
+}
+
+#[test]
+fn test_indices() {
+    assert_eq!(
+        eval("نطاق_فهارس([\"أ\"، \"ب\"، \"ج\"])"),
+        Value::List(vec![Value::Int(0), Value::Int(1), Value::Int(2)])
+    );
+    assert_eq!(eval("indices([])"), Value::List(Vec::new()));
+}
+
+#[test]
+fn test_indexed_report_from_a_while_loop() {
+    let code = "أسماء = [\"علي\"، \"سارة\"، \"عمر\"]\n\
+                مرقمة = رقّم(أسماء، ١)\n\
+                تقرير = \"\"\n\
+                ي = ٠\n\
+                بينما ي < طول(مرقمة) {\n\
+                    رقم، اسم = مرقمة[ي]\n\
+                    تقرير = تقرير + إلى_نص(رقم) + \". \" + اسم + \"\\n\"\n\
+                    ي = ي + ١\n\
+                }\n\
+                تقرير";
+    assert_eq!(eval(code), text("1. علي\n2. سارة\n3. عمر\n"));
+}
+
+#[test]
+fn test_errors() {
+    let error = |code: &str| format!("{}", Runtime::new().execute(code).unwrap_err());
+    assert!(error("رقّم(\"أب\")").contains("expects a list, got string"));
+    assert!(error("رقّم([١]، \"١\")").contains("start index"));
+    assert!(Runtime::new().execute("indices()").is_err());
+}
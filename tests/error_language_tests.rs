@@ -0,0 +1,124 @@
+use assert_cmd::Command;
+use iqra::cli::render_error_with;
+use iqra::lang::runtime::{ErrorLanguage, IqraError, Runtime};
+use predicates::prelude::*;
+
+fn sample_error() -> IqraError {
+    IqraError {
+        kind: "متغير غير معرف".to_string(),
+        message_ar: "المتغير غير معرف: س".to_string(),
+        message_en: "Undefined variable: س".to_string(),
+        suggestion: Some("عرّف المتغير".to_string()),
+        line: Some(3),
+        column: None,
+        source_name: None,
+    }
+}
+
+#[test]
+fn test_error_display_in_each_language() {
+    let err = sample_error();
+    assert_eq!(
+        err.localized(ErrorLanguage::Both).to_string(),
+        "[متغير غير معرف] المتغير غير معرف: س | Undefined variable: س\n\
+         اقتراح | Suggestion: عرّف المتغير\n\
+         السطر: 3 | Line: 3"
+    );
+    assert_eq!(
+        err.localized(ErrorLanguage::Arabic).to_string(),
+        "[متغير غير معرف] المتغير غير معرف: س\nاقتراح: عرّف المتغير\nالسطر: 3"
+    );
+    assert_eq!(
+        err.localized(ErrorLanguage::English).to_string(),
+        "[متغير غير معرف] Undefined variable: س\nSuggestion: عرّف المتغير\nLine: 3"
+    );
+}
+
+#[test]
+fn test_error_language_from_str() {
+    assert_eq!("ar".parse::<ErrorLanguage>(), Ok(ErrorLanguage::Arabic));
+    assert_eq!("EN".parse::<ErrorLanguage>(), Ok(ErrorLanguage::English));
+    assert_eq!("both".parse::<ErrorLanguage>(), Ok(ErrorLanguage::Both));
+    assert!("fr".parse::<ErrorLanguage>().is_err());
+}
+
+#[test]
+fn test_set_error_language_changes_display() {
+    let mut runtime = Runtime::new();
+    runtime.set_error_language(ErrorLanguage::English);
+    let err = runtime.execute("غير_معرف").unwrap_err().to_string();
+    runtime.set_error_language(ErrorLanguage::Both);
+    assert!(err.contains("Undefined variable"), "{}", err);
+    assert!(!err.contains("المتغير غير معرف"), "{}", err);
+}
+
+#[test]
+fn test_render_error_follows_language() {
+    let source = "أ = ١\nب = ٢\nج = س\n";
+    let rendered =
+        render_error_with(source, "x.iqra", &sample_error(), ErrorLanguage::English, false);
+    assert_eq!(
+        rendered,
+        "error[متغير غير معرف]: Undefined variable: س\n\
+         \x20--> x.iqra:3\n\
+         \x20 |\n\
+         3 | \u{2066}ج = س\u{2069}\n\
+         \x20 | ^^^^^\n\
+         \x20 = suggestion: عرّف المتغير\n"
+    );
+    let rendered =
+        render_error_with(source, "x.iqra", &sample_error(), ErrorLanguage::Arabic, false);
+    assert!(rendered.starts_with("خطأ[متغير غير معرف]: المتغير غير معرف: س\n"));
+    assert!(rendered.contains("  = اقتراح: عرّف المتغير"));
+}
+
+#[test]
+fn test_lang_flag_and_env_var() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["--lang", "en", "code", "--code", "غير_معرف"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Undefined variable"))
+        .stderr(predicates::str::contains("المتغير غير معرف").not());
+
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["code", "--code", "غير_معرف"])
+        .env("IQRA_LANG", "ar")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("المتغير غير معرف"))
+        .stderr(predicates::str::contains("Undefined variable").not());
+}
+
+#[test]
+fn test_suggestions_and_warnings_follow_the_lang_flag() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["--lang", "en", "code", "-c", "x = [1][5]"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("= suggestion: Make sure the index is within the list\n"))
+        .stderr(predicates::str::contains("تأكد").not());
+
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["--lang", "ar", "code", "-c", "x = [1][5]"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("= اقتراح: تأكد من أن الفهرس ضمن حدود القائمة\n"));
+
+    let script = std::env::temp_dir().join(format!("iqra_lang_check_{}.iqra", std::process::id()));
+    std::fs::write(&script, "كرر {\n    اطبع(نتيجه)\n}\nنتيجة = ١\n").unwrap();
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["--lang", "en", "check"])
+        .arg(&script)
+        .assert()
+        .stderr(predicates::str::contains("The 'loop' loop has no break or return"))
+        .stderr(predicates::str::contains("suggestion: Add a break where the loop should end"))
+        .stderr(predicates::str::contains("Did you mean 'نتيجة'?"))
+        .stderr(predicates::str::contains("تقصد").not());
+    std::fs::remove_file(script).unwrap();
+}
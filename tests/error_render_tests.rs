@@ -0,0 +1,138 @@
+use assert_cmd::Command;
+use iqra::cli::{render_error, render_error_with};
+use iqra::lang::runtime::{ErrorLanguage, IqraError, Runtime};
+use std::fs;
+
+const SCRIPT: &str = "س = ١\n    ص = غير_معرف + س\n";
+
+fn runtime_error(source: &str) -> IqraError {
+    let err = Runtime::new().execute(source).unwrap_err();
+    err.downcast::<IqraError>().unwrap()
+}
+
+fn sample_error(line: Option<usize>) -> IqraError {
+    IqraError {
+        kind: "خطأ في المتغير".to_string(),
+        message_ar: "المتغير غير معرف".to_string(),
+        message_en: "Undefined variable".to_string(),
+        suggestion: Some("عرّف المتغير قبل استخدامه".to_string()),
+        line,
+        column: None,
+        source_name: None,
+    }
+}
+
+fn write_script(name: &str, source: &str) -> String {
+    let path = std::env::temp_dir().join(format!("iqra_{}_{}.iqra", name, std::process::id()));
+    fs::write(&path, source).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[test]
+fn test_render_error_snapshot() {
+    let rendered = render_error(SCRIPT, "main.iqra", &sample_error(Some(2)));
+    assert_eq!(
+        rendered,
+        "خطأ | error[خطأ في المتغير]: المتغير غير معرف | Undefined variable\n\
+         \x20--> main.iqra:2\n\
+         \x20 |\n\
+         2 |     \u{2066}ص = غير_معرف + س\u{2069}\n\
+         \x20 |     ^^^^^^^^^^^^^^^^\n\
+         \x20 = اقتراح | suggestion: عرّف المتغير قبل استخدامه\n"
+    );
+}
+
+#[test]
+fn test_render_error_points_at_the_column() {
+    let mut err = sample_error(Some(2));
+    err.column = Some(9);
+    assert_eq!(
+        render_error(SCRIPT, "main.iqra", &err),
+        "خطأ | error[خطأ في المتغير]: المتغير غير معرف | Undefined variable\n\
+         \x20--> main.iqra:2\n\
+         \x20 |\n\
+         2 |     \u{2066}ص = غير_معرف + س\u{2069}\n\
+         \x20 |         ^\n\
+         \x20 = اقتراح | suggestion: عرّف المتغير قبل استخدامه\n"
+    );
+}
+
+#[test]
+fn test_render_error_without_line_snapshot() {
+    let mut err = sample_error(None);
+    err.suggestion = None;
+    assert_eq!(
+        render_error(SCRIPT, "main.iqra", &err),
+        "خطأ | error[خطأ في المتغير]: المتغير غير معرف | Undefined variable\n --> main.iqra\n"
+    );
+}
+
+#[test]
+fn test_long_lines_show_a_window_around_the_error() {
+    // A minified script: 60 statements on one line, the 50th of which fails.
+    let mut statements: Vec<String> = (1..60).map(|i| format!("س{} = {}", i, i)).collect();
+    statements.insert(49, "ص = غير_معرف".to_string());
+    let source = statements.join("؛ ");
+    let err = runtime_error(&source);
+    assert_eq!((err.line, err.column), (Some(1), Some(473)));
+    assert_eq!(
+        render_error(&source, "min.iqra", &err),
+        "خطأ | error[متغير غير معرف]: المتغير غير معرف: غير_معرف | Undefined variable: غير_معرف\n\
+         \x20--> min.iqra:1\n\
+         \x20 |\n\
+         1 | …\u{2066}س46 = 46؛ س47 = 47؛ س48 = 48؛ س49 = 49؛ ص = غير_معرف؛ س50 = 50؛ س51 = 51؛ س52 = 52؛ س53 = 53؛ س54 = 54؛ س55 = 55؛ س56 = \u{2069}…\n\
+         \x20 |                                          ^^^^^^^^^^^^\n\
+         \x20 = اقتراح | suggestion: تأكد من تعريف المتغير قبل استخدامه | Make sure the variable is defined before it is used\n"
+    );
+
+    // Without a column the window starts at the beginning of the line.
+    let rendered = render_error(&source, "min.iqra", &sample_error(Some(1)));
+    assert!(rendered.contains("1 | \u{2066}س1 = 1؛ س2 = 2؛"), "{}", rendered);
+    assert!(rendered.contains("\u{2069}…\n  | ^^^^^^\n"), "{}", rendered);
+}
+
+#[test]
+fn test_render_error_with_color_uses_ansi_codes() {
+    let err = sample_error(Some(1));
+    let colored = render_error_with(SCRIPT, "main.iqra", &err, ErrorLanguage::Both, true);
+    assert!(colored.contains("\x1b["));
+    assert!(
+        !render_error_with(SCRIPT, "main.iqra", &err, ErrorLanguage::Both, false).contains("\x1b[")
+    );
+}
+
+#[test]
+fn test_runtime_errors_carry_statement_line() {
+    assert_eq!(runtime_error(SCRIPT).line, Some(2));
+    let nested = "س = ١\nاذا س == ١ {\n    ص = غير_معرف\n}\n";
+    assert_eq!(runtime_error(nested).line, Some(3));
+}
+
+#[test]
+fn test_parse_errors_carry_line() {
+    assert_eq!(runtime_error("س = ١\nص = (١ + \n").line, Some(2));
+}
+
+#[test]
+fn test_run_file_exit_codes() {
+    let runtime_failure = write_script("runtime", SCRIPT);
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["run", &runtime_failure])
+        .env("NO_COLOR", "1")
+        .assert()
+        .code(1)
+        .stderr(predicates::str::contains("2 |     \u{2066}ص = غير_معرف + س\u{2069}"));
+
+    let parse_failure = write_script("parse", "س = (١ +\n");
+    Command::cargo_bin("iqra").unwrap().args(["run", &parse_failure]).assert().code(2);
+
+    let success = write_script("ok", "س = ١ + ٢\nس\n");
+    Command::cargo_bin("iqra").unwrap().args(["run", &success]).assert().success().stdout("");
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["run", "--print-result", &success])
+        .assert()
+        .success()
+        .stdout("3\n");
+}
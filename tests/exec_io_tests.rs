@@ -0,0 +1,145 @@
+use iqra::lang::runtime::{DefaultSystemExecutor, ExecOutput, Runtime, SystemExecutor};
+use iqra::lang::value::Value;
+use std::collections::HashMap;
+
+/// Echoes the input back as stdout, and reports a failure for `fail`.
+struct EchoHost {
+    full: bool,
+}
+
+impl SystemExecutor for EchoHost {
+    fn exec(&self, _cmd: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn exec_with_io(&self, _cmd: &str, input: &str) -> std::io::Result<String> {
+        Ok(input.to_string())
+    }
+
+    fn exec_with_io_full(&self, cmd: &str, input: &str) -> std::io::Result<ExecOutput> {
+        if !self.full {
+            return Ok(ExecOutput {
+                stdout: self.exec_with_io(cmd, input)?,
+                stderr: String::new(),
+                code: Some(0),
+            });
+        }
+        Ok(match cmd {
+            "fail" => {
+                ExecOutput { stdout: String::new(), stderr: "خطأ\n".to_string(), code: Some(3) }
+            }
+            "killed" => ExecOutput { stdout: String::new(), stderr: String::new(), code: None },
+            _ => ExecOutput { stdout: input.to_string(), stderr: String::new(), code: Some(0) },
+        })
+    }
+
+    fn read_file(&self, _path: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn write_file(&self, _path: &str, _content: &str) -> std::io::Result<bool> {
+        Ok(true)
+    }
+
+    fn list_files(&self, _path: &str) -> std::io::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    fn get_env_var(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    fn set_env_var(&self, _name: &str, _value: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn current_dir(&self) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn change_dir(&self, _path: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn process_id(&self) -> u32 {
+        1
+    }
+
+    fn read_line(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn read_password(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn system_info(&self) -> std::io::Result<HashMap<String, String>> {
+        Ok(HashMap::new())
+    }
+}
+
+fn run(full: bool, code: &str) -> HashMap<String, Value> {
+    let mut runtime = Runtime::new_with_executor(Box::new(EchoHost { full }));
+    match runtime.execute(code).unwrap() {
+        Value::Map(map) => map,
+        other => panic!("expected a map, got {:?}", other),
+    }
+}
+
+fn text(value: &str) -> Value {
+    Value::String(value.to_string())
+}
+
+#[test]
+fn test_structured_result() {
+    let result = run(true, "نفذ_أمر_بمدخل_كامل(\"cat\"، \" سطر \\n\")");
+    assert_eq!(result.get("مخرج"), Some(&text(" سطر \n")));
+    assert_eq!(result.get("أخطاء"), Some(&text("")));
+    assert_eq!(result.get("رمز_الخروج"), Some(&Value::Int(0)));
+    assert_eq!(result.get("نجح"), Some(&Value::Bool(true)));
+
+    let failed = run(true, "system_with_io_full(\"fail\", \"\")");
+    assert_eq!(failed.get("أخطاء"), Some(&text("خطأ\n")));
+    assert_eq!(failed.get("رمز_الخروج"), Some(&Value::Int(3)));
+    assert_eq!(failed.get("نجح"), Some(&Value::Bool(false)));
+
+    let killed = run(true, "system_with_io_full(\"killed\", \"\")");
+    assert_eq!(killed.get("رمز_الخروج"), Some(&Value::Nil));
+}
+
+#[test]
+fn test_executors_without_full_output_report_stdout_only() {
+    let result = run(false, "نفذ_أمر_بمدخل_كامل(\"cat\"، \"أ\")");
+    assert_eq!(result.get("مخرج"), Some(&text("أ")));
+    assert_eq!(result.get("نجح"), Some(&Value::Bool(true)));
+}
+
+#[test]
+fn test_refused_in_sandbox() {
+    let mut runtime = Runtime::new_with_executor(Box::new(EchoHost { full: true }));
+    runtime.set_sandboxed(true);
+    assert!(runtime.execute("نفذ_أمر_بمدخل_كامل(\"cat\"، \"أ\")").is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_large_input_round_trips_through_cat() {
+    // Far larger than a pipe buffer, so writing it all before reading would hang.
+    let input = "سطر عربي طويل للاختبار\n".repeat(150_000);
+    let output = DefaultSystemExecutor.exec_with_io_full("cat", &input).unwrap();
+    assert_eq!(output.stdout.len(), input.len());
+    assert!(output.stdout == input);
+    assert_eq!(output.code, Some(0));
+    assert_eq!(DefaultSystemExecutor.exec_with_io("cat", &input).unwrap().len(), input.len());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_stderr_and_exit_code_are_kept() {
+    let output = DefaultSystemExecutor.exec_with_io_full("ls /iqra-no-such-dir", "").unwrap();
+    assert!(!output.stderr.is_empty());
+    assert_ne!(output.code, Some(0));
+    // A command that ignores its input still finishes.
+    let output = DefaultSystemExecutor.exec_with_io_full("true", &"x".repeat(1 << 20)).unwrap();
+    assert_eq!(output.code, Some(0));
+}
@@ -0,0 +1,32 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(source: &str) -> Value {
+    Runtime::new().execute(source).unwrap()
+}
+
+#[test]
+fn test_identifier_led_expression_respects_precedence() {
+    assert_eq!(eval("س = ٥\nس + ٢ * ٣"), Value::Int(11));
+    assert_eq!(eval("س = ٥\nس * ٢ + ٣"), Value::Int(13));
+    assert_eq!(eval("س = ٥\nس - ١ - ١"), Value::Int(3));
+}
+
+#[test]
+fn test_identifier_led_comparison() {
+    assert_eq!(eval("س = ٥\nس + ١ > ٢ * ٣"), Value::Bool(false));
+    assert_eq!(eval("س = ٥\nس == ٥ و س < ٦"), Value::Bool(true));
+}
+
+#[test]
+fn test_call_led_expression_keeps_its_operator() {
+    assert_eq!(eval("طول(\"أبج\") > ٢"), Value::Bool(true));
+    assert_eq!(eval("ق = [١، ٢، ٣]\nجمع(ق) + ١"), Value::Int(7));
+    assert_eq!(eval("دالة ضعف(س) { ارجع س * ٢ }\nضعف(٢) + ضعف(٣) * ٢"), Value::Int(16));
+}
+
+#[test]
+fn test_assignment_and_multi_assignment_still_parse() {
+    assert_eq!(eval("أ = ١ + ٢ * ٣\nأ"), Value::Int(7));
+    assert_eq!(eval("أ، ب = ١، ٢\nأ + ب * ١٠"), Value::Int(21));
+}
@@ -0,0 +1,104 @@
+use iqra::lang::builtins::format::{FormatError, render};
+use iqra::lang::runtime::{IqraError, Runtime};
+use iqra::lang::value::Value;
+
+fn text(s: &str) -> Value {
+    Value::String(s.to_string())
+}
+
+#[test]
+fn test_placeholders_take_arguments_in_order() {
+    let args = [text("صحيح"), Value::Int(8), Value::Int(10)];
+    assert_eq!(render("{}: {} من {}", &args).unwrap(), "صحيح: 8 من 10");
+    assert_eq!(render("بلا خانات", &args).unwrap(), "بلا خانات");
+    assert_eq!(render("", &[]).unwrap(), "");
+    // Arguments left over are ignored.
+    assert_eq!(render("{}", &args).unwrap(), "صحيح");
+}
+
+#[test]
+fn test_explicit_positions() {
+    let args = [text("أ"), text("ب")];
+    assert_eq!(render("{1}{0}{1}", &args).unwrap(), "بأب");
+    assert_eq!(render("{١} {٠}", &args).unwrap(), "ب أ");
+    // Implicit placeholders count on from their own, not from explicit ones.
+    assert_eq!(render("{1} {} {}", &args).unwrap(), "ب أ ب");
+    assert_eq!(render("{ 0 }", &args).unwrap(), "أ");
+}
+
+#[test]
+fn test_fixed_decimals() {
+    let args = [Value::Number(87.256), Value::Int(3), Value::Number(-0.001)];
+    assert_eq!(render("{:.2}", &args).unwrap(), "87.26");
+    assert_eq!(render("{1:.2}", &args).unwrap(), "3.00");
+    assert_eq!(render("{0:.0}", &args).unwrap(), "87");
+    assert_eq!(render("{0:.٢}", &args).unwrap(), "87.26");
+    assert_eq!(render("{2:.1}", &args).unwrap(), "0.0");
+    assert_eq!(render("{:.1} {:.1}", &args).unwrap(), "87.3 3.0");
+}
+
+#[test]
+fn test_escaped_braces() {
+    assert_eq!(render("{{}}", &[]).unwrap(), "{}");
+    assert_eq!(render("{{{}}}", &[Value::Int(1)]).unwrap(), "{1}");
+    assert_eq!(render("}}{{", &[]).unwrap(), "}{");
+}
+
+#[test]
+fn test_values_appear_as_print_shows_them() {
+    let args = [
+        Value::Number(0.1 + 0.2),
+        Value::Bool(true),
+        Value::Nil,
+        Value::List(vec![Value::Int(1), text("ب")]),
+    ];
+    let expected = format!("{} {} {} {}", args[0], args[1], args[2], args[3]);
+    assert_eq!(render("{} {} {} {}", &args).unwrap(), expected);
+    assert!(expected.starts_with("0.30000000000000004 "), "{}", expected);
+}
+
+#[test]
+fn test_too_few_arguments_names_the_position() {
+    assert_eq!(
+        render("{} و {}", &[Value::Int(1)]),
+        Err(FormatError::MissingArgument { position: 1, given: 1 })
+    );
+    assert_eq!(render("{3}", &[]), Err(FormatError::MissingArgument { position: 3, given: 0 }));
+    assert_eq!(render("{:.2}", &[]), Err(FormatError::MissingArgument { position: 0, given: 0 }));
+}
+
+#[test]
+fn test_malformed_templates() {
+    assert_eq!(render("أ {", &[]), Err(FormatError::Unclosed { offset: 3 }));
+    assert_eq!(render("{0", &[Value::Int(1)]), Err(FormatError::Unclosed { offset: 0 }));
+    assert_eq!(render("a } b", &[]), Err(FormatError::UnmatchedClose { offset: 2 }));
+    for placeholder in ["x", "-1", ":2", ":.", ":.x", "0:.2f"] {
+        assert_eq!(
+            render(&format!("{{{}}}", placeholder), &[Value::Int(1)]),
+            Err(FormatError::BadPlaceholder { placeholder: placeholder.to_string() }),
+            "{}",
+            placeholder
+        );
+    }
+    assert_eq!(render("{:.2}", &[text("أ")]), Err(FormatError::NotANumber { position: 0 }));
+}
+
+#[test]
+fn test_format_builtin() {
+    let mut runtime = Runtime::new();
+    assert_eq!(
+        runtime.execute("نسق(\"النتيجة: {} من {}\"، ٨، ١٠)").unwrap(),
+        text("النتيجة: 8 من 10")
+    );
+    assert_eq!(runtime.execute("format(\"{0:.1}%\", 87.26)").unwrap(), text("87.3%"));
+
+    let err = runtime.execute("نسق(\"{} {}\"، ١)").unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.kind, "تنسيق غير صالح | Invalid Format");
+    assert_eq!(err.message_en, "نسق: placeholder {1} has no argument; only 1 given");
+    assert_eq!(err.message_ar, "نسق: لا توجد قيمة للموضع {1}؛ أُعطيت 1 فقط");
+
+    let err = runtime.execute("format(1)").unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.message_en, "format expects a template string first");
+    let err = runtime.execute("format()").unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.kind, "عدد وسائط غير صحيح");
+}
@@ -0,0 +1,104 @@
+use chrono::NaiveDate;
+use iqra::lang::hijri::{FixedClock, HijriDate, days_in_month, is_leap_year};
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+use std::sync::Arc;
+
+/// Gregorian days and their Hijri dates, on which the tabular calendar and
+/// Umm al-Qura agree.
+const KNOWN_DATES: &[(&str, &str)] = &[
+    ("0622-07-19", "0001-01-01"),
+    ("1970-01-01", "1389-10-22"),
+    ("2000-01-01", "1420-09-24"),
+    ("2023-07-19", "1445-01-01"),
+    ("2024-03-11", "1445-09-01"),
+    ("2024-04-10", "1445-10-01"),
+    ("2025-03-01", "1446-09-01"),
+];
+
+fn gregorian(text: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+}
+
+fn on_day(day: &str) -> Runtime {
+    let mut runtime = Runtime::new();
+    runtime.set_clock(Arc::new(FixedClock(gregorian(day))));
+    runtime
+}
+
+fn text(runtime: &mut Runtime, code: &str) -> String {
+    match runtime.execute(code).unwrap() {
+        Value::String(s) => s,
+        other => panic!("expected a string, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_known_dates_convert_both_ways() {
+    for (gregorian_text, hijri_text) in KNOWN_DATES {
+        let hijri = HijriDate::from_gregorian(gregorian(gregorian_text)).unwrap();
+        assert_eq!(hijri.to_string(), *hijri_text, "{}", gregorian_text);
+        assert_eq!(hijri.to_gregorian(), Some(gregorian(gregorian_text)), "{}", hijri_text);
+    }
+}
+
+#[test]
+fn test_every_day_round_trips() {
+    let mut day = gregorian("1900-01-01");
+    let mut previous = HijriDate::from_gregorian(day).unwrap();
+    while day < gregorian("2100-01-01") {
+        day = day.succ_opt().unwrap();
+        let hijri = HijriDate::from_gregorian(day).unwrap();
+        assert!(hijri > previous, "{} after {}", hijri, previous);
+        assert_eq!(hijri.to_gregorian(), Some(day));
+        assert!(hijri.day <= days_in_month(hijri.year, hijri.month));
+        previous = hijri;
+    }
+}
+
+#[test]
+fn test_leap_years_follow_the_thirty_year_cycle() {
+    let leap: Vec<i64> = (1..=30).filter(|year| is_leap_year(*year)).collect();
+    assert_eq!(leap, vec![2, 5, 7, 10, 13, 16, 18, 21, 24, 26, 29]);
+    assert_eq!(days_in_month(1445, 12), 30);
+    assert_eq!(days_in_month(1446, 12), 29);
+    assert_eq!(HijriDate::new(1446, 12, 30), None);
+    assert_eq!(HijriDate::new(1445, 13, 1), None);
+}
+
+#[test]
+fn test_parse_accepts_arabic_digits() {
+    assert_eq!(HijriDate::parse("١٤٤٥-٠٩-٠١"), HijriDate::new(1445, 9, 1));
+    assert_eq!(HijriDate::parse("1445-9-1"), HijriDate::new(1445, 9, 1));
+    assert_eq!(HijriDate::parse("1445-09"), None);
+    assert_eq!(HijriDate::parse("1445-09-01-02"), None);
+}
+
+#[test]
+fn test_today_builtins_use_the_clock() {
+    let mut runtime = on_day("2024-03-11");
+    assert_eq!(text(&mut runtime, "تاريخ_هجري()"), "1445-09-01");
+    assert_eq!(text(&mut runtime, "today()"), "2024-03-11");
+    assert_eq!(text(&mut on_day("2023-07-19"), "hijri_today()"), "1445-01-01");
+}
+
+#[test]
+fn test_conversion_builtins() {
+    let mut runtime = Runtime::new();
+    assert_eq!(text(&mut runtime, "إلى_هجري(\"2000-01-01\")"), "1420-09-24");
+    assert_eq!(text(&mut runtime, "to_hijri(\"٢٠٢٤-٠٤-١٠\")"), "1445-10-01");
+    assert_eq!(text(&mut runtime, "من_هجري(\"١٤٤٥-٠١-٠١\")"), "2023-07-19");
+    assert_eq!(text(&mut runtime, "اسم_الشهر_الهجري(٩)"), "رمضان");
+    assert_eq!(text(&mut runtime, "hijri_month_name(12)"), "ذو الحجة");
+}
+
+#[test]
+fn test_invalid_dates_are_errors() {
+    let mut runtime = Runtime::new();
+    let err = runtime.execute("من_هجري(\"1445-13-01\")").unwrap_err();
+    assert!(format!("{}", err).contains("1445-13-01"), "{}", err);
+    assert!(runtime.execute("إلى_هجري(\"0600-01-01\")").is_err());
+    assert!(runtime.execute("إلى_هجري(\"2024-02-30\")").is_err());
+    assert!(runtime.execute("اسم_الشهر_الهجري(١٣)").is_err());
+    assert!(runtime.execute("تاريخ_هجري(١)").is_err());
+}
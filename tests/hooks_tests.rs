@@ -0,0 +1,83 @@
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+const FIXTURE: &str = "tests/fixtures/trace.iqra";
+
+#[derive(Default)]
+struct Counts {
+    statements: usize,
+    assigns: Vec<(String, Value)>,
+    calls: Vec<(String, usize)>,
+    errors: Vec<(String, Option<usize>)>,
+}
+
+fn counting_runtime() -> (Runtime, Arc<Mutex<Counts>>) {
+    let counts = Arc::new(Mutex::new(Counts::default()));
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(CapturedOutput::new()));
+    let hooks = runtime.hooks_mut();
+    let seen = Arc::clone(&counts);
+    hooks.on_statement = Some(Box::new(move |_, _| {
+        seen.lock().unwrap().statements += 1;
+        iqra::lang::runtime::DebugAction::Continue
+    }));
+    let seen = Arc::clone(&counts);
+    hooks.on_assign = Some(Box::new(move |name, value| {
+        seen.lock().unwrap().assigns.push((name.to_string(), value.clone()));
+    }));
+    let seen = Arc::clone(&counts);
+    hooks.on_call = Some(Box::new(move |name, args| {
+        seen.lock().unwrap().calls.push((name.to_string(), args.len()));
+    }));
+    let seen = Arc::clone(&counts);
+    hooks.on_error = Some(Box::new(move |err| {
+        seen.lock().unwrap().errors.push((err.message_en.clone(), err.line));
+    }));
+    (runtime, counts)
+}
+
+#[test]
+fn test_hooks_count_the_fixture_program() {
+    let (mut runtime, counts) = counting_runtime();
+    runtime.execute(&fs::read_to_string(FIXTURE).unwrap()).unwrap();
+    let counts = counts.lock().unwrap();
+    // س = ٠, دالة, بينما, two loop bodies, ص = ..., ارجع, اطبع.
+    assert_eq!(counts.statements, 8);
+    let assigned: Vec<&str> = counts.assigns.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(assigned, ["س", "س", "س", "ص"]);
+    assert_eq!(counts.assigns[3].1, Value::Int(4));
+    // Operators are not calls; ضعف and اطبع are.
+    assert_eq!(counts.calls, [("ضعف".to_string(), 1), ("اطبع".to_string(), 1)]);
+    assert!(counts.errors.is_empty());
+}
+
+#[test]
+fn test_read_only_builtins_are_reported_as_calls() {
+    let (mut runtime, counts) = counting_runtime();
+    runtime.execute("س = [١، ٢]\nطول(س)").unwrap();
+    assert_eq!(counts.lock().unwrap().calls, [("طول".to_string(), 1)]);
+}
+
+#[test]
+fn test_on_error_fires_once_where_the_error_is_raised() {
+    let (mut runtime, counts) = counting_runtime();
+    let code = "دالة فاشلة() {\n    ارجع ١ / ٠\n}\nجرب {\n    فاشلة()\n} امسك (خ) {\n    س = ١\n}";
+    runtime.execute(code).unwrap();
+    assert_eq!(counts.lock().unwrap().errors, [("Division by zero".to_string(), Some(2))]);
+
+    assert!(runtime.execute("غير_معرف").is_err());
+    let counts = counts.lock().unwrap();
+    assert_eq!(counts.errors.len(), 2);
+    assert_eq!(counts.errors[1].0, "Undefined variable: غير_معرف");
+}
+
+#[test]
+fn test_runtime_without_hooks_runs_unchanged() {
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(CapturedOutput::new()));
+    assert_eq!(runtime.execute(&fs::read_to_string(FIXTURE).unwrap()).unwrap(), Value::Nil);
+    assert!(runtime.hooks_mut().on_call.is_none());
+}
@@ -0,0 +1,82 @@
+use assert_cmd::Command;
+use iqra::lang::runtime::{Hotspot, IqraError, Limits, Runtime};
+use serde_json::{Value as Json, json};
+use std::fs;
+use std::time::Duration;
+
+const NESTED_LOOPS: &str = "خارجي = ٠\nبينما صحيح {\n    خارجي = خارجي + ١\n    داخلي = ٠\n    بينما داخلي < ١٠ {\n        داخلي = داخلي + ١\n    }\n}\n";
+
+#[test]
+fn test_statement_limit_names_the_inner_loop() {
+    let mut runtime = Runtime::new();
+    runtime.set_limits(Limits { max_statements: Some(500), ..Limits::default() });
+    let err = runtime.execute(NESTED_LOOPS).unwrap_err().downcast::<IqraError>().unwrap();
+    let hotspots = runtime.hotspots(3);
+    assert_eq!(hotspots.len(), 3);
+    assert_eq!((hotspots[0].line, hotspots[0].column), (5, 5));
+    assert!(hotspots[0].count > hotspots[1].count, "{:?}", hotspots);
+    let hottest = format!("line 5:5 ({} times)", hotspots[0].count);
+    assert!(
+        err.message_en.contains(&format!("; most executed: {}", hottest)),
+        "{}",
+        err.message_en
+    );
+    assert!(err.message_ar.contains("الأكثر تنفيذاً: السطر 5:5"), "{}", err.message_ar);
+}
+
+#[test]
+fn test_passes_of_an_empty_loop_are_counted() {
+    let mut runtime = Runtime::new();
+    runtime.set_limits(Limits { max_statements: Some(100), ..Limits::default() });
+    let err = runtime.execute("بينما صحيح { }").unwrap_err().downcast::<IqraError>().unwrap();
+    assert!(err.message_en.contains("most executed: line 1:1 (101 times)"), "{}", err.message_en);
+    for code in ["كرر {\n}", "افعل {\n} بينما صحيح"] {
+        runtime.execute(code).unwrap_err();
+        assert_eq!(runtime.hotspots(1), [Hotspot { line: 1, column: 1, count: 101 }], "{}", code);
+    }
+}
+
+#[test]
+fn test_timeout_names_the_hottest_statement() {
+    let mut runtime = Runtime::new();
+    runtime.set_limits(Limits { timeout: Some(Duration::from_millis(50)), ..Limits::default() });
+    let err = runtime.execute(NESTED_LOOPS).unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.kind, "انتهت المهلة | Timeout");
+    assert!(err.message_en.contains("most executed: line 5:5"), "{}", err.message_en);
+}
+
+#[test]
+fn test_no_counting_without_limits() {
+    let mut runtime = Runtime::new();
+    runtime.execute("س = ٠\nبينما س < ١٠ {\n    س = س + ١\n}").unwrap();
+    assert_eq!(runtime.hotspots(3), Vec::<Hotspot>::new());
+
+    // Each run starts counting afresh.
+    runtime.set_limits(Limits { max_statements: Some(1000), ..Limits::default() });
+    runtime.execute("س = ٠\nبينما س < ٤ {\n    س = س + ١\n}").unwrap();
+    runtime.execute("ص = ١").unwrap();
+    assert_eq!(runtime.hotspots(3), [Hotspot { line: 1, column: 1, count: 1 }]);
+}
+
+#[test]
+fn test_state_report_lists_hotspots() {
+    let dir = std::env::temp_dir();
+    let script = dir.join(format!("iqra_hotspots_{}.iqra", std::process::id()));
+    let report = dir.join(format!("iqra_hotspots_{}.json", std::process::id()));
+    fs::write(&script, NESTED_LOOPS).unwrap();
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["run", "--no-warnings", "--max-statements", "300"])
+        .arg(&script)
+        .arg("--emit-state")
+        .arg(&report)
+        .assert()
+        .code(1);
+    let json: Json = serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+    fs::remove_file(&script).unwrap();
+    fs::remove_file(&report).unwrap();
+    let hotspots = json["hotspots"].as_array().unwrap();
+    assert_eq!(hotspots.len(), 3);
+    assert_eq!((&hotspots[0]["line"], &hotspots[0]["column"]), (&json!(5), &json!(5)));
+    assert!(json["error"]["message_en"].as_str().unwrap().contains("most executed: line 5:5"));
+}
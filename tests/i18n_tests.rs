@@ -0,0 +1,88 @@
+use std::collections::HashSet;
+
+use iqra::i18n::{ErrorLanguage, List, MESSAGES, placeholders, tr_in};
+
+#[test]
+fn test_every_message_has_both_translations() {
+    for message in MESSAGES {
+        assert!(!message.ar.trim().is_empty(), "{} has no Arabic text", message.key);
+        assert!(!message.en.trim().is_empty(), "{} has no English text", message.key);
+        assert_ne!(message.ar, message.en, "{} is not translated", message.key);
+    }
+}
+
+#[test]
+fn test_message_keys_are_unique() {
+    let mut keys = HashSet::new();
+    for message in MESSAGES {
+        assert!(keys.insert(message.key), "{} appears twice", message.key);
+    }
+}
+
+#[test]
+fn test_translations_use_the_same_placeholders() {
+    for message in MESSAGES {
+        assert_eq!(placeholders(message.ar), placeholders(message.en), "{}", message.key);
+    }
+}
+
+#[test]
+fn test_placeholders_are_filled_in_each_language() {
+    let args: [(&str, &dyn iqra::i18n::Arg); 2] = [("package", &"hisab"), ("version", &"1.2.0")];
+    assert_eq!(
+        tr_in(ErrorLanguage::English, "pkg.yanked", &args),
+        "Yanked 'hisab' 1.2.0 from the registry"
+    );
+    assert_eq!(tr_in(ErrorLanguage::Arabic, "pkg.yanked", &args), "سُحب 'hisab' 1.2.0 من السجل");
+    assert_eq!(
+        tr_in(ErrorLanguage::Both, "pkg.yanked", &args),
+        "سُحب 'hisab' 1.2.0 من السجل | Yanked 'hisab' 1.2.0 from the registry"
+    );
+}
+
+#[test]
+fn test_lists_use_each_language_comma() {
+    let names = ["أ".to_string(), "ب".to_string()];
+    assert_eq!(
+        tr_in(ErrorLanguage::Both, "suggest.did_you_mean", &[("names", &List(&names))]),
+        "هل قصدت: أ، ب؟ | Did you mean: أ, ب?"
+    );
+}
+
+#[test]
+fn test_placeholders_ignore_other_braces() {
+    let names: Vec<&str> =
+        placeholders("{path} {} {not a name} {path} {var}").into_iter().collect();
+    assert_eq!(names, ["path", "var"]);
+}
+
+#[test]
+fn test_pkg_messages_follow_lang_flag() {
+    use assert_cmd::Command;
+    let output =
+        Command::cargo_bin("iqra-pkg").unwrap().args(["--lang", "en", "remove"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.trim(), "Please specify a package to remove");
+    let output = Command::cargo_bin("iqra-pkg")
+        .unwrap()
+        .env("IQRA_LANG", "ar")
+        .arg("remove")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.trim(), "يرجى تحديد اسم المكتبة للإزالة");
+}
+
+#[test]
+fn test_search_errors_follow_lang_flag() {
+    use assert_cmd::Command;
+    let output =
+        Command::cargo_bin("iqra-pkg").unwrap().args(["--lang", "en", "search"]).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "Please specify a search query");
+    let output = Command::cargo_bin("iqra-pkg")
+        .unwrap()
+        .args(["--lang", "ar", "search", "قائمة", "--limit", "0"])
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "--limit يتطلب عدداً موجباً");
+}
@@ -0,0 +1,63 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn run_err(code: &str) -> String {
+    Runtime::new().execute(code).unwrap_err().to_string()
+}
+
+#[test]
+fn test_integer_literals_stay_exact_beyond_f64_precision() {
+    let result = eval("٩٠٠٧١٩٩٢٥٤٧٤٠٩٩٣ + ١");
+    assert!(matches!(result, Value::Int(9007199254740994)), "{:?}", result);
+    assert_eq!(format!("{}", result), "9007199254740994");
+}
+
+#[test]
+fn test_integer_overflow_is_an_error() {
+    let err = run_err("9223372036854775807 + 1");
+    assert!(err.contains("The result is outside the integer range"), "{}", err);
+
+    let err = run_err("س = 9223372036854775807\nص = س * 2");
+    assert!(err.contains("Line: 2"), "{}", err);
+}
+
+#[test]
+fn test_integer_division_is_exact_or_promotes() {
+    assert!(matches!(eval("٦ / ٣"), Value::Int(2)));
+    assert!(matches!(eval("٧ / ٢"), Value::Number(n) if n == 3.5));
+    assert!(matches!(eval("٧ % ٣"), Value::Int(1)));
+    assert!(run_err("٧ / ٠").contains("Division by zero"));
+}
+
+#[test]
+fn test_mixed_arithmetic_promotes_to_number() {
+    assert!(matches!(eval("٢ + ٠٫٥"), Value::Number(n) if n == 2.5));
+    assert!(matches!(eval("٢ * ١٫٠"), Value::Number(n) if n == 2.0));
+    assert!(matches!(eval("-٥"), Value::Int(-5)));
+    assert_eq!(eval("٢ == ٢٫٠"), Value::Bool(true));
+    assert_eq!(eval("٣ > ٢٫٥"), Value::Bool(true));
+}
+
+#[test]
+fn test_numeric_builtins_accept_integers() {
+    assert!(matches!(eval("طول([١، ٢، ٣])"), Value::Int(3)));
+    assert!(matches!(eval("إلى_رقم(\"٤٢\")"), Value::Int(42)));
+    assert!(matches!(eval("إلى_رقم(\"٤٫٥\")"), Value::Number(n) if n == 4.5));
+    assert!(matches!(eval("جمع([١، ٢، ٣])"), Value::Int(6)));
+    assert!(matches!(eval("جمع([١، ٢٫٥])"), Value::Number(n) if n == 3.5));
+    assert!(matches!(eval("أكبر([١، ٧، ٣])"), Value::Int(7)));
+    assert!(matches!(eval("أصغر([٤، ٢٫٥، ٣])"), Value::Number(n) if n == 2.5));
+    assert_eq!(eval("عنصر([١٠، ٢٠]، ١)"), Value::Int(20));
+}
+
+#[test]
+fn test_dividing_the_smallest_integer_by_minus_one_overflows() {
+    let err = run_err("س = -9223372036854775807 - 1\nص = س / -1");
+    assert!(err.contains("The result is outside the integer range"), "{}", err);
+    let err = run_err("س = -9223372036854775807 - 1\nص = س % -1");
+    assert!(err.contains("The result is outside the integer range"), "{}", err);
+}
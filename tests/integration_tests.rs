@@ -87,6 +87,30 @@ impl SystemExecutor for MockSystemExecutor {
         Some("mocked env value".to_string())
     }
 
+    fn set_env_var(&self, _name: &str, _value: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn current_dir(&self) -> std::io::Result<String> {
+        Ok("/mock".to_string())
+    }
+
+    fn change_dir(&self, _path: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn process_id(&self) -> u32 {
+        42
+    }
+
+    fn read_line(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn read_password(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
     fn system_info(&self) -> std::io::Result<HashMap<String, String>> {
         let mut info = HashMap::new();
         info.insert("os".to_string(), "Linux".to_string());
@@ -0,0 +1,90 @@
+use iqra::lang::lexer::{KEYWORDS, Lexer, Token};
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::runtime::{IqraError, Runtime};
+
+fn run(code: &str) -> (String, anyhow::Result<()>) {
+    let output = CapturedOutput::new();
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(output.clone()));
+    let result = runtime.execute(code).map(|_| ());
+    (output.contents(), result)
+}
+
+#[test]
+fn test_new_aliases_lex_to_their_keywords() {
+    for (word, token) in [
+        ("عدا", Token::Catch),
+        ("وإلّا", Token::Else),
+        ("وإلاّ", Token::Else),
+        ("طالما", Token::While),
+        ("elif", Token::Elif),
+        ("أخطاء", Token::Errors),
+        ("errors", Token::Errors),
+    ] {
+        let tokens = Lexer::tokenize(word).unwrap();
+        assert_eq!(tokens[0].0, token, "{}", word);
+        assert!(KEYWORDS.contains(&word), "{}", word);
+    }
+}
+
+#[test]
+fn test_aliases_run_like_the_keywords_they_stand_for() {
+    let (output, result) = run("س = ٠\nطالما س < ٣ {\n    س = س + ١\n}\nاطبع(س)\n");
+    result.unwrap();
+    assert_eq!(output, "3\n");
+
+    let (output, result) = run("اذا خطأ {\n    اطبع(١)\n} وإلّا {\n    اطبع(٢)\n}\n");
+    result.unwrap();
+    assert_eq!(output, "2\n");
+
+    let (output, result) = run("جرب {\n    س = ١ / ٠\n} عدا (خ) {\n    اطبع(\"مُسك\")\n}\n");
+    result.unwrap();
+    assert_eq!(output, "مُسك\n");
+}
+
+#[test]
+fn test_elif_and_else_if_chain_conditions() {
+    let code = |keyword: &str| {
+        format!(
+            "دالة صنف(ن) {{\n    اذا ن < ٠ {{\n        ارجع \"سالب\"\n    }} {} ن == ٠ {{\n        ارجع \"صفر\"\n    }} وإلا {{\n        ارجع \"موجب\"\n    }}\n}}\nاطبع(صنف(-١)، صنف(٠)، صنف(٥))\n",
+            keyword
+        )
+    };
+    for keyword in ["elif", "وإلا اذا", "else if"] {
+        let (output, result) = run(&code(keyword));
+        result.unwrap();
+        assert_eq!(output, "سالب صفر موجب\n", "{}", keyword);
+    }
+}
+
+#[test]
+fn test_catch_errors_of_a_kind() {
+    let (output, result) =
+        run("جرب {\n    س = ١ / ٠\n} امسك أخطاء \"قسمة على صفر\" (خ) {\n    اطبع(\"قسمة\")\n}\n");
+    result.unwrap();
+    assert_eq!(output, "قسمة\n");
+
+    // Either half of a bilingual kind matches, English ignoring case.
+    let (output, result) = run(
+        "try {\n    x = 9223372036854775807 + 1\n} catch errors \"integer overflow\" {\n    print(\"overflow\")\n}\n",
+    );
+    result.unwrap();
+    assert_eq!(output, "overflow\n");
+}
+
+#[test]
+fn test_errors_of_another_kind_are_not_caught() {
+    let (output, result) =
+        run("جرب {\n    اطبع(مجهول)\n} امسك أخطاء \"قسمة على صفر\" {\n    اطبع(\"قسمة\")\n}\n");
+    assert_eq!(output, "");
+    let err = result.unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.kind, "متغير غير معرف");
+    assert_eq!(err.line, Some(2));
+}
+
+#[test]
+fn test_errors_must_be_followed_by_a_kind() {
+    let (_, result) = run("جرب {\n    ١\n} امسك أخطاء (خ) {\n    ٢\n}\n");
+    let err = result.unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.message_en, "Expected an error kind string after 'errors'");
+}
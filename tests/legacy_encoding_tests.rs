@@ -0,0 +1,154 @@
+use iqra::lang::builtins::bytes::{Guess, detect};
+use iqra::lang::runtime::{IqraError, Runtime, SystemExecutor};
+use iqra::lang::value::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// "السلام عليكم" in each encoding.
+const UTF_8: &[u8] = "السلام عليكم".as_bytes();
+const WINDOWS_1256: &[u8] =
+    &[0xc7, 0xe1, 0xd3, 0xe1, 0xc7, 0xe3, 0x20, 0xda, 0xe1, 0xed, 0xdf, 0xe3];
+const ISO_8859_6: &[u8] = &[0xc7, 0xe4, 0xd3, 0xe4, 0xc7, 0xe5, 0x20, 0xd9, 0xe4, 0xea, 0xe3, 0xe5];
+
+/// Files kept in memory as bytes. Reading one as text fails, so every
+/// builtin under test has to go through the byte-level methods.
+#[derive(Clone, Default)]
+struct ByteFiles(Arc<Mutex<HashMap<String, Vec<u8>>>>);
+
+impl ByteFiles {
+    fn with(files: &[(&str, &[u8])]) -> Self {
+        let map = files.iter().map(|(path, bytes)| (path.to_string(), bytes.to_vec())).collect();
+        ByteFiles(Arc::new(Mutex::new(map)))
+    }
+
+    fn get(&self, path: &str) -> Option<Vec<u8>> {
+        self.0.lock().unwrap().get(path).cloned()
+    }
+}
+
+impl SystemExecutor for ByteFiles {
+    fn exec(&self, _cmd: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn exec_with_io(&self, _cmd: &str, _input: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn read_file(&self, _path: &str) -> std::io::Result<String> {
+        Err(std::io::Error::other("read as text"))
+    }
+
+    fn read_bytes(&self, path: &str) -> std::io::Result<Vec<u8>> {
+        self.get(path).ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "not found"))
+    }
+
+    fn write_file(&self, _path: &str, _content: &str) -> std::io::Result<bool> {
+        Err(std::io::Error::other("written as text"))
+    }
+
+    fn write_bytes(&self, path: &str, content: &[u8]) -> std::io::Result<bool> {
+        self.0.lock().unwrap().insert(path.to_string(), content.to_vec());
+        Ok(true)
+    }
+
+    fn list_files(&self, _path: &str) -> std::io::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    fn get_env_var(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    fn set_env_var(&self, _name: &str, _value: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn current_dir(&self) -> std::io::Result<String> {
+        Ok("/mock".to_string())
+    }
+
+    fn change_dir(&self, _path: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn process_id(&self) -> u32 {
+        42
+    }
+
+    fn read_line(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn read_password(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn system_info(&self) -> std::io::Result<HashMap<String, String>> {
+        Ok(HashMap::new())
+    }
+}
+
+fn runtime(files: &ByteFiles) -> Runtime {
+    Runtime::new_with_executor(Box::new(files.clone()))
+}
+
+fn fixtures() -> ByteFiles {
+    ByteFiles::with(&[("utf8.txt", UTF_8), ("1256.txt", WINDOWS_1256), ("iso.txt", ISO_8859_6)])
+}
+
+#[test]
+fn test_each_encoding_reads_back_the_same_text() {
+    let files = fixtures();
+    let mut runtime = runtime(&files);
+    for (path, encoding) in
+        [("utf8.txt", "utf-8"), ("1256.txt", "windows-1256"), ("iso.txt", "ISO-8859-6")]
+    {
+        let code = format!("اقرأ_ملف_بترميز(\"{}\"، \"{}\")", path, encoding);
+        assert_eq!(
+            runtime.execute(&code).unwrap(),
+            Value::String("السلام عليكم".to_string()),
+            "{}",
+            path
+        );
+    }
+    // The wrong encoding is an error rather than garbled text when the
+    // bytes aren't valid in it.
+    let err = runtime.execute("read_file_encoding(\"1256.txt\", \"utf-8\")").unwrap_err();
+    assert_eq!(err.downcast::<IqraError>().unwrap().kind, "ترميز غير صالح");
+}
+
+#[test]
+fn test_writing_encodes_the_text() {
+    let files = ByteFiles::default();
+    let mut runtime = runtime(&files);
+    runtime.execute("اكتب_ملف_بترميز(\"أ.txt\"، \"السلام عليكم\"، \"windows-1256\")").unwrap();
+    runtime.execute("write_file_encoding(\"ب.txt\", \"السلام عليكم\", \"iso-8859-6\")").unwrap();
+    assert_eq!(files.get("أ.txt").unwrap(), WINDOWS_1256);
+    assert_eq!(files.get("ب.txt").unwrap(), ISO_8859_6);
+
+    let err = runtime.execute("اكتب_ملف_بترميز(\"ج.txt\"، \"€\"، \"iso-8859-6\")").unwrap_err();
+    assert_eq!(err.downcast::<IqraError>().unwrap().kind, "ترميز غير صالح");
+    runtime.set_sandboxed(true);
+    assert!(runtime.execute("اكتب_ملف_بترميز(\"د.txt\"، \"نص\"، \"utf-8\")").is_err());
+    assert_eq!(files.get("د.txt"), None);
+}
+
+#[test]
+fn test_detect_encoding_guesses_each_fixture() {
+    let files = fixtures();
+    let mut runtime = runtime(&files);
+    for (path, expected) in
+        [("utf8.txt", "utf-8"), ("1256.txt", "windows-1256"), ("iso.txt", "iso-8859-6")]
+    {
+        let guess = runtime.execute(&format!("خمّن_الترميز(\"{}\")", path)).unwrap();
+        let guess = guess.as_map().unwrap();
+        assert_eq!(guess["ترميز"], Value::String(expected.to_string()), "{}", path);
+        assert_eq!(guess["ثقة"], Value::Number(1.0), "{}", path);
+    }
+    // ASCII reads the same in every encoding.
+    assert_eq!(detect(b"plain text").confidence, 0.5);
+    // A byte iso-8859-6 leaves undefined settles it.
+    let guess = detect(&[0xc7, 0xe1, 0xff]);
+    assert_eq!(guess, Guess { encoding: encoding_rs::WINDOWS_1256, confidence: 1.0 });
+}
@@ -0,0 +1,144 @@
+use iqra::lang::lexer::{Lexer, Span, Token};
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::parser::Parser;
+use iqra::lang::runtime::Runtime;
+use iqra::lsp::user_functions;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Pieces random programs are built from, so that besides raw bytes the
+/// fuzz inputs reach keywords, strings and escapes.
+const PIECES: &[&str] = &[
+    "اذا",
+    "وإلا",
+    "بينما",
+    "دالة",
+    "ارجع",
+    "جرب",
+    "امسك",
+    "طابق",
+    "حالة",
+    "ثابت",
+    "آمن",
+    "try?",
+    "??",
+    "(",
+    ")",
+    "{",
+    "}",
+    "[",
+    "]",
+    "،",
+    ",",
+    "؛",
+    "=",
+    "==",
+    "!",
+    "<=",
+    "&",
+    "|",
+    "?",
+    "...",
+    ".",
+    "١٢",
+    "3.5",
+    "٣٫",
+    "1.2.3",
+    "99999999999999999999",
+    "\"",
+    "\"نص\"",
+    "\\u{",
+    "\\u{110000}",
+    "\\",
+    "//",
+    "///",
+    "\n",
+    " ",
+    "س",
+    "_",
+    "؟",
+    "@",
+    "#",
+    "\u{200f}",
+    "\u{0}",
+];
+
+fn random_input(rng: &mut StdRng) -> String {
+    let length = rng.gen_range(0..48);
+    if rng.gen_bool(0.5) {
+        let bytes: Vec<u8> = (0..length).map(|_| rng.r#gen()).collect();
+        String::from_utf8_lossy(&bytes).into_owned()
+    } else {
+        (0..length).map(|_| PIECES[rng.gen_range(0..PIECES.len())]).collect()
+    }
+}
+
+#[test]
+fn test_tokenize_returns_tokens_with_spans() {
+    let tokens = Lexer::tokenize("س = ١٢\nاطبع(س)").unwrap();
+    let kinds: Vec<&Token> = tokens.iter().map(|(token, _)| token).collect();
+    assert_eq!(
+        kinds[..4],
+        [&Token::Identifier("س".into()), &Token::Assign, &Token::Int(12), &Token::Newline]
+    );
+    assert_eq!(tokens.len(), 8);
+    assert_eq!(tokens[2].1, Span { start_line: 1, start_col: 5, end_line: 1, end_col: 7 });
+    assert_eq!(tokens[4].1.start_line, 2);
+}
+
+#[test]
+fn test_tokenize_reports_the_first_error() {
+    let err = Lexer::tokenize("س = @\nص = \"مفتوح").unwrap_err();
+    assert!(err.to_string().contains("Unknown character: '@'"), "{}", err);
+}
+
+#[test]
+fn test_iterator_resumes_after_an_error() {
+    let items: Vec<_> = Lexer::new("أ @ ب \"مفتوح").collect();
+    assert_eq!(items.len(), 4);
+    assert!(matches!(&items[0], Ok((Token::Identifier(name), _)) if name == "أ"));
+    assert!(items[1].is_err());
+    assert!(matches!(&items[2], Ok((Token::Identifier(name), _)) if name == "ب"));
+    assert!(items[3].as_ref().unwrap_err().to_string().contains("Unterminated string"));
+}
+
+#[test]
+fn test_lexer_errors_are_parse_errors() {
+    for source in ["س = @", "اطبع(\"مفتوح)", "س = 1.2.3", "\"\\u{zz}\""] {
+        let result = Parser::new(Lexer::new(source)).parse();
+        assert!(result.is_err(), "{:?}", source);
+    }
+}
+
+#[test]
+fn test_leading_byte_order_mark_is_skipped() {
+    let source = std::fs::read_to_string("tests/fixtures/bom.iqra").unwrap();
+    assert!(source.starts_with('\u{FEFF}'));
+    assert!(Lexer::new(&source).all(|item| item.is_ok()));
+    let output = CapturedOutput::new();
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(output.clone()));
+    runtime.execute(&source).unwrap();
+    assert_eq!(output.contents(), "2\n");
+    // Only at the very start.
+    assert!(Lexer::new("س = ١\n\u{FEFF}").any(|item| item.is_err()));
+}
+
+#[test]
+fn test_user_functions_skip_past_bad_characters() {
+    let functions = user_functions("@\nدالة جمع(أ، ب) { ارجع أ + ب }");
+    assert_eq!(functions, vec![("جمع".to_string(), vec!["أ".to_string(), "ب".to_string()])]);
+}
+
+#[test]
+fn test_random_input_never_panics() {
+    let mut rng = StdRng::seed_from_u64(1642);
+    for _ in 0..3000 {
+        let input = random_input(&mut rng);
+        let _ = Lexer::tokenize(&input);
+        let _ = Lexer::new(&input).count();
+        let _ = Parser::new(Lexer::new(&input)).parse();
+        let _ = Parser::new(Lexer::new(&input)).parse_with_recovery();
+        let _ = user_functions(&input);
+    }
+}
@@ -0,0 +1,56 @@
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::runtime::{CANCELLED_ERROR_KIND, IqraError, Limits, Runtime};
+use iqra::lang::stepper::Stepper;
+use iqra::lang::value::Value;
+use std::thread;
+use std::time::Duration;
+
+fn run(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+#[test]
+fn test_loop_runs_until_break() {
+    let code =
+        "عداد = ٠\nكرر {\n    عداد = عداد + ١\n    اذا عداد == ٥ {\n        توقف\n    }\n}\nعداد";
+    assert_eq!(run(code), Value::Int(5));
+    let code = "n = 0\nodd = 0\nloop {\n    n = n + 1\n    if n > 6 { break }\n    if n % 2 == 0 { continue }\n    odd = odd + 1\n}\nodd";
+    assert_eq!(run(code), Value::Int(3));
+    let code = "دالة أول_سالب(ق) {\n    ع = ٠\n    كرر {\n        اذا ق[ع] < ٠ { ارجع ع }\n        ع = ع + ١\n    }\n}\nأول_سالب([٣، ٢، -١])";
+    assert_eq!(run(code), Value::Int(2));
+}
+
+#[test]
+fn test_loop_obeys_the_statement_limit() {
+    let mut runtime = Runtime::new();
+    runtime.set_limits(Limits { max_statements: Some(20), ..Limits::default() });
+    let err = runtime.execute("كرر {\n    س = ١\n}").unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.kind, "تجاوز حد الجمل | Statement limit exceeded");
+}
+
+#[test]
+fn test_cancel_stops_a_loop() {
+    let output = CapturedOutput::new();
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(output.clone()));
+    let token = runtime.cancellation_token();
+    let worker = thread::spawn(move || runtime.execute("اطبع(\"بدأ\")\nكرر {\n}\n"));
+    while output.contents().is_empty() {
+        thread::sleep(Duration::from_millis(5));
+    }
+    token.cancel();
+    let err = worker.join().unwrap().unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.kind, CANCELLED_ERROR_KIND);
+}
+
+#[test]
+fn test_stepping_through_a_loop() {
+    let mut runtime = Runtime::new();
+    let mut stepper =
+        Stepper::parse("ع = ٠\nكرر {\n    ع = ع + ١\n    اذا ع == ٢ { توقف }\n}\nع\n").unwrap();
+    let mut lines = Vec::new();
+    while let Some(step) = stepper.next_step(&mut runtime) {
+        lines.push(step.unwrap().span.start_line);
+    }
+    assert_eq!(lines, vec![1, 2, 3, 4, 2, 3, 4, 4, 6]);
+}
@@ -0,0 +1,134 @@
+use iqra::lsp::{LanguageServer, read_message, serve, write_message};
+use serde_json::{Value as Json, json};
+use std::io::Cursor;
+
+const URI: &str = "file:///درس.iqra";
+
+fn open(server: &mut LanguageServer, text: &str) -> Vec<Json> {
+    server.handle_message(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": URI, "languageId": "iqra", "version": 1, "text": text } },
+    }))
+}
+
+fn request(server: &mut LanguageServer, method: &str, params: Json) -> Json {
+    let replies = server
+        .handle_message(&json!({ "jsonrpc": "2.0", "id": 7, "method": method, "params": params }));
+    assert_eq!(replies.len(), 1, "{:?}", replies);
+    replies[0]["result"].clone()
+}
+
+#[test]
+fn test_initialize_advertises_capabilities() {
+    let mut server = LanguageServer::new();
+    let result = request(&mut server, "initialize", json!({ "capabilities": {} }));
+    assert_eq!(result["capabilities"]["hoverProvider"], true);
+    assert_eq!(result["capabilities"]["textDocumentSync"], 1);
+    assert!(
+        server
+            .handle_message(&json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }))
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_diagnostics_for_parse_errors_and_warnings() {
+    let mut server = LanguageServer::new();
+    let published = open(&mut server, "س = ١\n  إذا (س = ٢) {\n}\n");
+    assert_eq!(published[0]["method"], "textDocument/publishDiagnostics");
+    let diagnostics = published[0]["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], 1);
+    // From the error's column, the `=` that should be `==`, to the line end.
+    assert_eq!(diagnostics[0]["range"]["start"], json!({ "line": 1, "character": 9 }));
+    assert_eq!(diagnostics[0]["range"]["end"], json!({ "line": 1, "character": 15 }));
+    assert!(
+        diagnostics[0]["message"].as_str().unwrap().contains("|"),
+        "bilingual: {}",
+        diagnostics[0]
+    );
+
+    let changed = server.handle_message(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": { "textDocument": { "uri": URI, "version": 2 }, "contentChanges": [{ "text": "غير_مستخدم = ١\n" }] },
+    }));
+    let diagnostics = changed[0]["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], 2);
+    // Warnings carry no column, so they cover the whole line.
+    assert_eq!(diagnostics[0]["range"]["start"], json!({ "line": 0, "character": 0 }));
+    assert_eq!(diagnostics[0]["range"]["end"], json!({ "line": 0, "character": 14 }));
+    assert!(diagnostics[0]["message"].as_str().unwrap().contains("غير_مستخدم"));
+}
+
+#[test]
+fn test_completion_includes_keywords_builtins_and_user_functions() {
+    let mut server = LanguageServer::new();
+    open(&mut server, "دالة جمع_مربعات(أ، ب) {\n    ارجع أ * أ + ب * ب\n}\n");
+    let items = request(
+        &mut server,
+        "textDocument/completion",
+        json!({ "textDocument": { "uri": URI }, "position": { "line": 3, "character": 0 } }),
+    );
+    let labels: Vec<&str> =
+        items.as_array().unwrap().iter().map(|item| item["label"].as_str().unwrap()).collect();
+    for expected in ["بينما", "while", "اطبع", "print", "جمع_مربعات"] {
+        assert!(labels.contains(&expected), "missing {}", expected);
+    }
+    let user = items.as_array().unwrap().iter().find(|item| item["label"] == "جمع_مربعات").unwrap();
+    assert_eq!(user["detail"], "دالة جمع_مربعات(أ، ب)");
+}
+
+#[test]
+fn test_hover_on_builtin_user_function_and_keyword() {
+    let mut server = LanguageServer::new();
+    open(&mut server, "دالة ضعف(س) {\n    ارجع س * ٢\n}\nاطبع(ضعف(٣))\n");
+    let hover = |server: &mut LanguageServer, line: u64, character: u64| {
+        request(
+            server,
+            "textDocument/hover",
+            json!({ "textDocument": { "uri": URI }, "position": { "line": line, "character": character } }),
+        )
+    };
+    let builtin = hover(&mut server, 3, 2);
+    assert_eq!(
+        builtin["contents"]["value"],
+        "**اطبع** / **print**\n\nيطبع القيم في سطر واحد تفصل بينها مسافات\n\n\
+         Prints the values on one line, separated by spaces\n\n\
+         المعاملات | Parameters: `...قيم | ...values`"
+    );
+    let user = hover(&mut server, 3, 8);
+    assert_eq!(user["contents"]["value"], "```iqra\nدالة ضعف(س)\n```");
+    let keyword = hover(&mut server, 0, 4);
+    assert!(keyword["contents"]["value"].as_str().unwrap().contains("keyword"));
+    assert_eq!(hover(&mut server, 3, 12), Json::Null);
+}
+
+#[test]
+fn test_unknown_request_and_framed_session() {
+    let mut server = LanguageServer::new();
+    let replies = server.handle_message(
+        &json!({ "jsonrpc": "2.0", "id": 1, "method": "textDocument/rename", "params": {} }),
+    );
+    assert_eq!(replies[0]["error"]["code"], -32601);
+
+    let mut input = Vec::new();
+    for message in [
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "shutdown" }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+    ] {
+        write_message(&mut input, &message).unwrap();
+    }
+    let mut output = Vec::new();
+    serve(Cursor::new(input), &mut output).unwrap();
+
+    let mut reader = Cursor::new(output);
+    let first = read_message(&mut reader).unwrap().unwrap();
+    assert_eq!(first["id"], 1);
+    let second = read_message(&mut reader).unwrap().unwrap();
+    assert_eq!(second, json!({ "jsonrpc": "2.0", "id": 2, "result": null }));
+    assert!(read_message(&mut reader).unwrap().is_none());
+}
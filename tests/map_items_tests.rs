@@ -0,0 +1,68 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn text(value: &str) -> Value {
+    Value::String(value.to_string())
+}
+
+#[test]
+fn test_items_are_sorted_by_key() {
+    let code = "ق = قاموس(\"ج\"، ٣، \"أ\"، ١، \"ب\"، ٢)\nعناصر(ق)";
+    let expected = Value::List(vec![
+        Value::List(vec![text("أ"), Value::Int(1)]),
+        Value::List(vec![text("ب"), Value::Int(2)]),
+        Value::List(vec![text("ج"), Value::Int(3)]),
+    ]);
+    assert_eq!(eval(code), expected);
+    assert_eq!(eval("items(map())"), Value::List(Vec::new()));
+}
+
+#[test]
+fn test_items_order_is_stable() {
+    let keys: Vec<String> = (0..50).map(|i| format!("\"مفتاح{}\"، {}", i, i)).collect();
+    let code = format!("عناصر(قاموس({}))", keys.join("، "));
+    let first = eval(&code);
+    for _ in 0..5 {
+        assert_eq!(eval(&code), first);
+    }
+    let Value::List(pairs) = first else { panic!("expected a list") };
+    let names: Vec<String> = pairs
+        .iter()
+        .map(|pair| match pair {
+            Value::List(pair) => format!("{}", pair[0]),
+            other => panic!("expected a pair, got {:?}", other),
+        })
+        .collect();
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(names, sorted);
+}
+
+#[test]
+fn test_invert_swaps_keys_and_values() {
+    let inverted = eval("اقلب(قاموس(\"واحد\"، ١، \"اسم\"، \"أحمد\"، \"صح\"، صحيح))");
+    let Value::Map(map) = inverted else { panic!("expected a map") };
+    assert_eq!(map.get("1"), Some(&text("واحد")));
+    assert_eq!(map.get("أحمد"), Some(&text("اسم")));
+    assert_eq!(map.get("صحيح"), Some(&text("صح")));
+}
+
+#[test]
+fn test_invert_collisions_are_last_wins_and_reported() {
+    let code = "ق، متكرر = اقلب(قاموس(\"ب\"، ١، \"أ\"، ١، \"ج\"، ٢)، صحيح)\n[ق[\"1\"]، متكرر]";
+    assert_eq!(eval(code), Value::List(vec![text("ب"), Value::List(vec![text("1")])]));
+    assert_eq!(eval("اقلب(قاموس(\"أ\"، ١)، صحيح)[١]"), Value::List(Vec::new()));
+}
+
+#[test]
+fn test_errors() {
+    let message = format!("{}", Runtime::new().execute("عناصر([١، ٢])").unwrap_err());
+    assert!(message.contains("expects a map, got list"), "{}", message);
+    assert!(Runtime::new().execute("items()").is_err());
+    assert!(Runtime::new().execute("invert(map(), 1)").is_err());
+    assert!(Runtime::new().execute("invert(map(), true, true)").is_err());
+}
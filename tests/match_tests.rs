@@ -0,0 +1,68 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn run(code: &str, name: &str) -> Option<Value> {
+    let mut runtime = Runtime::new();
+    runtime.execute(code).unwrap();
+    runtime.get_variables().get(name).cloned()
+}
+
+#[test]
+fn test_match_on_numbers_with_multiple_values() {
+    let code = r#"
+    يوم = ٦
+    طابق يوم {
+        حالة ١، ٢، ٣، ٤، ٥ {
+            نوع_اليوم = "عمل"
+        }
+        حالة ٦، ٧ {
+            نوع_اليوم = "عطلة"
+        }
+    }
+    "#;
+    assert_eq!(run(code, "نوع_اليوم"), Some(Value::String("عطلة".to_string())));
+}
+
+#[test]
+fn test_match_on_strings_runs_first_matching_arm_only() {
+    let code = r#"
+    لون = "أحمر"
+    عدد = ٠
+    طابق لون {
+        حالة "أخضر" { عدد = عدد + ١ }
+        حالة "أحمر" { عدد = عدد + ١٠ }
+        حالة "أحمر" { عدد = عدد + ١٠٠ }
+        افتراضي { عدد = -١ }
+    }
+    "#;
+    assert_eq!(run(code, "عدد"), Some(Value::Number(10.0)));
+}
+
+#[test]
+fn test_match_default_arm_and_english_keywords() {
+    let code = r#"
+    grade = 42
+    match grade {
+        case 90, 100 { result = "top" }
+        default { result = "other" }
+    }
+    "#;
+    assert_eq!(run(code, "result"), Some(Value::String("other".to_string())));
+
+    let no_default = "x = 3\nmatch x {\n    case 1 { y = 1 }\n}\n";
+    assert_eq!(run(no_default, "y"), None);
+}
+
+#[test]
+fn test_match_arm_values_are_expressions() {
+    let code = "أ = ٤\nطابق أ * ٢ {\n    حالة ٢ + ٦ { ب = صحيح }\n    افتراضي { ب = خطأ }\n}\n";
+    assert_eq!(run(code, "ب"), Some(Value::Bool(true)));
+}
+
+#[test]
+fn test_match_rejects_case_after_default() {
+    let mut runtime = Runtime::new();
+    let err =
+        runtime.execute("طابق ١ {\n    افتراضي { س = ١ }\n    حالة ١ { س = ٢ }\n}\n").unwrap_err();
+    assert!(err.to_string().contains("Expected 'case' or 'default' inside match"), "{}", err);
+}
@@ -0,0 +1,69 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn error(code: &str) -> String {
+    format!("{}", Runtime::new().execute(code).unwrap_err())
+}
+
+fn matrix(rows: &[&[i64]]) -> Value {
+    Value::List(
+        rows.iter().map(|row| Value::List(row.iter().map(|n| Value::Int(*n)).collect())).collect(),
+    )
+}
+
+#[test]
+fn test_multiply_3x2_by_2x3() {
+    let code = "أ = [[١، ٢]، [٣، ٤]، [٥، ٦]]\nب = [[٧، ٨، ٩]، [١٠، ١١، ١٢]]\nاضرب_مصفوفات(أ، ب)";
+    let expected = matrix(&[&[27, 30, 33], &[61, 68, 75], &[95, 106, 117]]);
+    assert_eq!(eval(code), expected);
+    assert_eq!(
+        eval("matrix_multiply([[0.5]], [[2, 4]])"),
+        Value::List(vec![Value::List(vec![Value::Number(1.0), Value::Number(2.0),])])
+    );
+}
+
+#[test]
+fn test_multiply_dimension_mismatch_names_the_dimensions() {
+    let message = error("اضرب_مصفوفات([[١، ٢]، [٣، ٤]، [٥، ٦]]، [[١، ٢]، [٣، ٤]، [٥، ٦]])");
+    assert!(
+        message.contains(
+            "cannot multiply 3x2 by 3x2; the first has 2 columns but the second has 3 rows"
+        ),
+        "{}",
+        message
+    );
+    assert!(message.contains("لا يمكن ضرب 3x2 في 3x2"), "{}", message);
+}
+
+#[test]
+fn test_matrix_add() {
+    assert_eq!(
+        eval("اجمع_مصفوفات([[١، ٢]، [٣، ٤]]، [[١٠، ٢٠]، [٣٠، ٤٠]])"),
+        matrix(&[&[11, 22], &[33, 44]])
+    );
+    let message = error("matrix_add([[1, 2]], [[1], [2]])");
+    assert!(message.contains("the matrices are 1x2 and 2x1"), "{}", message);
+    let message = error("matrix_add([[1, \"أ\"]], [[1, 2]])");
+    assert!(message.contains("row 0, column 1 is not a number"), "{}", message);
+}
+
+#[test]
+fn test_transpose() {
+    assert_eq!(eval("انقل_مصفوفة([[١، ٢، ٣]، [٤، ٥، ٦]])"), matrix(&[&[1, 4], &[2, 5], &[3, 6]]));
+    assert_eq!(eval("transpose([])"), Value::List(Vec::new()));
+    let message = error("انقل_مصفوفة([[١، ٢]، [٣، ٤]، [٥]])");
+    assert!(message.contains("row 2 has 1 items but the first row has 2"), "{}", message);
+    assert!(message.contains("الصف 2"), "{}", message);
+}
+
+#[test]
+fn test_reshape() {
+    assert_eq!(eval("أعد_تشكيل([١، ٢، ٣، ٤، ٥، ٦]، ٢، ٣)"), matrix(&[&[1, 2, 3], &[4, 5, 6]]));
+    assert_eq!(eval("reshape([[1, 2, 3], [4, 5, 6]], 3, 2)"), matrix(&[&[1, 2], &[3, 4], &[5, 6]]));
+    assert!(error("reshape([1, 2, 3], 2, 2)").contains("cannot arrange 3 items into 2 rows of 2"));
+    assert!(error("reshape([1], -1, 1)").contains("non-negative"));
+}
@@ -0,0 +1,107 @@
+use assert_cmd::Command;
+use iqra::cli::serve::Server;
+use iqra::lang::runtime::{IqraError, Limits, Runtime};
+use iqra::lang::value::Value;
+use predicates::str::contains;
+
+const KIND: &str = "تجاوز حد الذاكرة | Memory limit exceeded";
+
+/// A runtime that allows lists and maps of 3 items and strings of 10 bytes.
+fn tiny() -> Runtime {
+    let mut runtime = Runtime::new();
+    runtime.set_limits(Limits {
+        max_collection_len: Some(3),
+        max_collection_bytes: Some(10),
+        ..Limits::default()
+    });
+    runtime
+}
+
+fn memory_error(code: &str) -> IqraError {
+    let err = tiny().execute(code).unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.kind, KIND, "{}", code);
+    err
+}
+
+#[test]
+fn test_repeat_is_refused_before_allocating() {
+    let err = memory_error("كرر_نص(\"أ\"، ١٠٠٠٠٠٠٠٠٠٠)");
+    assert_eq!(err.message_en, "The value would have 20000000000 bytes; the limit is 10");
+    assert_eq!(err.message_ar, "القيمة المطلوبة من 20000000000 بايت والحد 10");
+    assert_eq!(tiny().execute("كرر_نص(\"ab\"، ٥)").unwrap(), Value::String("ab".repeat(5)));
+}
+
+#[test]
+fn test_collections_stop_growing_at_the_limit() {
+    let err = memory_error("س = []\nبينما صحيح {\n    س = أضف(س، ١)\n}\n");
+    assert_eq!(err.message_en, "The value would have 4 items; the limit is 3");
+    assert_eq!(err.line, Some(3));
+    memory_error("[١، ٢، ٣، ٤]");
+    memory_error("ق = قاموس(\"أ\"، ١، \"ب\"، ٢، \"ج\"، ٣)\nتعيين_عنصر(ق، \"د\"، ٤)");
+    // Replacing a key doesn't grow a full map.
+    assert!(
+        tiny().execute("ق = قاموس(\"أ\"، ١، \"ب\"، ٢، \"ج\"، ٣)\nتعيين_عنصر(ق، \"أ\"، ٤)").is_ok()
+    );
+    assert!(tiny().execute("أضف([١، ٢]، ٣)").is_ok());
+}
+
+#[test]
+fn test_folded_list_literals_are_checked() {
+    let mut runtime = tiny();
+    runtime.set_optimize(true);
+    for code in
+        ["س = [١، ٢، ٣، ٤]", "اطبع([١، ٢، ٣، ٤])", "طول([[١، ٢، ٣، ٤]])", "[١] + [١، ٢، ٣، ٤]"]
+    {
+        let err = runtime.execute(code).unwrap_err().downcast::<IqraError>().unwrap();
+        assert_eq!(err.message_en, "The value would have 4 items; the limit is 3", "{}", code);
+    }
+    assert_eq!(runtime.execute("طول([١، ٢، ٣])").unwrap(), Value::Int(3));
+}
+
+#[test]
+fn test_strings_stop_growing_at_the_limit() {
+    let err = memory_error("س = \"\"\nبينما صحيح {\n    س = س + \"أب\"\n}\n");
+    assert_eq!(err.message_en, "The value would have 12 bytes; the limit is 10");
+    memory_error("احشِ_يمين(\"أ\"، ١٠٠٠٠٠٠٠٠٠٠)");
+    assert!(tiny().execute("\"12345\" + \"67890\"").is_ok());
+}
+
+#[test]
+fn test_large_files_are_not_read() {
+    let path = std::env::temp_dir().join(format!("iqra_memory_{}.txt", std::process::id()));
+    std::fs::write(&path, "أكثر من عشرة بايتات").unwrap();
+    let code = format!("اقرأ_ملف({:?})", path.to_string_lossy());
+    let err = memory_error(&code);
+    assert_eq!(err.message_en, "The value would have 35 bytes; the limit is 10");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_limits_are_off_unless_set() {
+    let mut runtime = Runtime::new();
+    assert!(
+        runtime
+            .execute("س = []\nع = ٠\nبينما ع < ١٠٠ {\n    س = أضف(س، ع)\n    ع = ع + ١\n}\n")
+            .is_ok()
+    );
+    let caps = Limits { max_collection_len: Some(5), ..Limits::default() }.with_sandbox_caps();
+    assert_eq!(caps.max_collection_len, Some(5));
+    assert!(caps.max_collection_bytes.is_some());
+}
+
+#[test]
+fn test_sandboxed_modes_are_capped() {
+    let mut server = Server::new(Limits::default());
+    let response = server.handle_line(
+        &serde_json::json!({ "id": 1, "method": "eval", "params": { "code": "كرر_نص(\"أ\"، ١٠٠٠٠٠٠٠٠٠٠)" } })
+            .to_string(),
+    );
+    assert_eq!(response["error"]["kind"], KIND);
+
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["code", "--sandbox", "repeat(\"a\", 10000000000)"])
+        .assert()
+        .failure()
+        .stderr(contains("Memory limit exceeded"));
+}
@@ -0,0 +1,57 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn vars(runtime: &Runtime, names: &[&str]) -> Vec<Option<Value>> {
+    names.iter().map(|name| runtime.get_variables().get(*name).cloned()).collect()
+}
+
+#[test]
+fn test_multiple_assignment_and_swap() {
+    let mut runtime = Runtime::new();
+    runtime.execute("أ، ب = ١، ٢").unwrap();
+    assert_eq!(
+        vars(&runtime, &["أ", "ب"]),
+        vec![Some(Value::Number(1.0)), Some(Value::Number(2.0))]
+    );
+
+    runtime.execute("أ، ب = ب، أ").unwrap();
+    assert_eq!(
+        vars(&runtime, &["أ", "ب"]),
+        vec![Some(Value::Number(2.0)), Some(Value::Number(1.0))]
+    );
+
+    runtime.execute("x, y, z = 1, \"two\", أ + ب").unwrap();
+    assert_eq!(
+        vars(&runtime, &["x", "y", "z"]),
+        vec![
+            Some(Value::Number(1.0)),
+            Some(Value::String("two".to_string())),
+            Some(Value::Number(3.0))
+        ]
+    );
+}
+
+#[test]
+fn test_destructuring_list_from_function() {
+    let mut runtime = Runtime::new();
+    runtime.execute("دالة زوج() {\n    ارجع [\"يمين\", \"يسار\"]\n}\nأ، ب = زوج()").unwrap();
+    assert_eq!(
+        vars(&runtime, &["أ", "ب"]),
+        vec![Some(Value::String("يمين".to_string())), Some(Value::String("يسار".to_string()))]
+    );
+}
+
+#[test]
+fn test_count_mismatch_reports_both_counts() {
+    let err = Runtime::new().execute("أ، ب، ج = ١، ٢").unwrap_err().to_string();
+    assert!(err.contains("Assigning to 3 variables needs 3 values, but got 2"), "{}", err);
+
+    let err = Runtime::new().execute("أ، ب = [١، ٢، ٣]").unwrap_err().to_string();
+    assert!(err.contains("needs 2 values, but got 3"), "{}", err);
+}
+
+#[test]
+fn test_multiple_assignment_respects_constants() {
+    let err = Runtime::new().execute("ثابت أ = ١\nأ، ب = ٢، ٣").unwrap_err().to_string();
+    assert!(err.contains("Cannot reassign constant: أ"), "{}", err);
+}
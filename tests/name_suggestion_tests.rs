@@ -0,0 +1,49 @@
+use iqra::lang::analyzer::closest_names;
+use iqra::lang::runtime::{IqraError, Runtime};
+
+fn suggestion(code: &str) -> Option<String> {
+    let err = Runtime::new().execute(code).unwrap_err();
+    err.downcast::<IqraError>().unwrap().suggestion
+}
+
+#[test]
+fn test_closest_names_keeps_three_within_two_edits() {
+    let candidates = ["عدد_الطلاب", "عدد_الطالب", "عدد_الطلاب٢", "عدد_الكتب", "مجموع"];
+    assert_eq!(
+        closest_names("عدد_الطلااب", candidates, 3),
+        ["عدد_الطلاب", "عدد_الطالب", "عدد_الطلاب٢"]
+    );
+    assert_eq!(closest_names("عدد_الطلااب", candidates, 1), ["عدد_الطلاب"]);
+    assert!(closest_names("س", ["ص"], 3).is_empty());
+    // The same name offered twice is suggested once.
+    assert_eq!(closest_names("اطبغ", ["اطبع", "اطبع"], 3), ["اطبع"]);
+}
+
+#[test]
+fn test_undefined_variable_suggests_close_names() {
+    assert_eq!(
+        suggestion("عدد_الطلاب = ٣٠\nاطبع(عدد_الطلااب)").as_deref(),
+        Some("هل قصدت: عدد_الطلاب؟ | Did you mean: عدد_الطلاب?")
+    );
+    assert_eq!(
+        suggestion("مجموع١ = ١\nمجموع٢ = ٢\nاطبع(مجموع)").as_deref(),
+        Some("هل قصدت: مجموع١، مجموع٢؟ | Did you mean: مجموع١, مجموع٢?")
+    );
+    assert_eq!(
+        suggestion("اطبع(غير_موجود_ابدا)").as_deref(),
+        Some("تأكد من تعريف المتغير قبل استخدامه | Make sure the variable is defined before it is used")
+    );
+}
+
+#[test]
+fn test_undefined_function_suggests_user_functions_and_builtins() {
+    assert_eq!(
+        suggestion("دالة احسب_المعدل(س) {\n    ارجع س\n}\nاحسب_المعدال(١)").as_deref(),
+        Some("هل قصدت: احسب_المعدل؟ | Did you mean: احسب_المعدل?")
+    );
+    assert_eq!(suggestion("اطبغ(١)").as_deref(), Some("هل قصدت: اطبع؟ | Did you mean: اطبع?"));
+    assert_eq!(
+        suggestion("خريطة_متوازية([١]، \"مربغ\")").as_deref(),
+        Some("تأكد من كتابة اسم الدالة بشكل صحيح | Check the spelling of the function name")
+    );
+}
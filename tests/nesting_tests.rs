@@ -0,0 +1,86 @@
+use iqra::lang::lexer::Lexer;
+use iqra::lang::parser::{Expr, ExprKind, Parser};
+use iqra::lang::runtime::{IqraError, Runtime};
+use iqra::lang::value::Value;
+
+// Test threads get 2MB of stack; give deep inputs the 8MB the CLI's main
+// thread has, since unoptimised builds use far more stack per level.
+fn on_main_sized_stack<T: Send>(run: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(8 << 20)
+            .spawn_scoped(scope, run)
+            .unwrap()
+            .join()
+            .unwrap()
+    })
+}
+
+fn parse_error(source: &str) -> IqraError {
+    let err = on_main_sized_stack(|| Parser::new(Lexer::new(source)).parse().unwrap_err());
+    err.downcast::<IqraError>().unwrap()
+}
+
+fn assert_too_deep(err: &IqraError) {
+    assert_eq!(err.kind, "تجاوز عمق التداخل | Nesting too deep");
+    assert!(err.message_en.contains("limit of 256 levels"), "{}", err.message_en);
+}
+
+#[test]
+fn test_ten_thousand_parentheses_are_a_clean_error() {
+    let source = format!("{}١{}", "(".repeat(10_000), ")".repeat(10_000));
+    let err = parse_error(&source);
+    assert_too_deep(&err);
+    assert_eq!(err.line, Some(1));
+}
+
+#[test]
+fn test_unclosed_brackets_braces_and_signs() {
+    assert_too_deep(&parse_error(&"[".repeat(10_000)));
+    assert_too_deep(&parse_error(&format!("{}١", "-".repeat(10_000))));
+    assert_too_deep(&parse_error(&"اذا صحيح {\n".repeat(10_000)));
+}
+
+#[test]
+fn test_long_operator_and_index_chains() {
+    // Built in a loop, but every later pass recurses through the chain.
+    let terms = vec!["١"; 20_000];
+    assert_too_deep(&parse_error(&format!("س = {}", terms.join(" + "))));
+    assert_too_deep(&parse_error(&format!("س = {}", terms.join(" * "))));
+    assert_too_deep(&parse_error(&format!("س = {}", terms.join(" أو "))));
+    assert_too_deep(&parse_error(&format!("س = [٠]{}", "[٠]".repeat(20_000))));
+    // A chain inside parentheses counts with the levels around it.
+    let inner = vec!["١"; 200].join(" + ");
+    assert_too_deep(&parse_error(&format!("{}{}{}", "(".repeat(100), inner, ")".repeat(100))));
+
+    let source = vec!["١"; 200].join(" + ");
+    let result = on_main_sized_stack(|| Runtime::new().execute(&source).unwrap());
+    assert_eq!(result, Value::Int(200));
+}
+
+#[test]
+fn test_nesting_within_the_limit_still_works() {
+    let source = format!("{}٢{}", "(".repeat(100), ")".repeat(100));
+    let result = on_main_sized_stack(|| Runtime::new().execute(&source).unwrap());
+    assert_eq!(result, Value::Int(2));
+}
+
+#[test]
+fn test_max_nesting_is_configurable() {
+    let mut parser = Parser::new(Lexer::new("((١))"));
+    parser.set_max_nesting(2);
+    assert!(parser.parse().is_err());
+}
+
+#[test]
+fn test_evaluating_a_deep_built_ast_is_a_clean_error() {
+    let literal = |n| Expr { kind: ExprKind::Literal(Value::Int(n)), span: Default::default() };
+    let mut expr = literal(0);
+    for _ in 0..10_000 {
+        expr = Expr { kind: ExprKind::SafeEval(Box::new(expr)), span: Default::default() };
+    }
+    let err = Runtime::new().evaluate_parsed_expression(&expr).unwrap_err();
+    assert_too_deep(err.downcast_ref::<IqraError>().unwrap());
+    // Dropping a deep tree recurses too; leak it rather than risk the stack.
+    std::mem::forget(expr);
+}
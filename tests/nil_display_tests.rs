@@ -0,0 +1,68 @@
+use assert_cmd::Command;
+use iqra::lang::runtime::ErrorLanguage;
+use iqra::lang::value::nil_text;
+use predicates::str::contains;
+
+const NIL_SCRIPT: &str = "tests/fixtures/nil_values.iqra";
+
+fn iqra(lang: &str) -> Command {
+    let mut command = Command::cargo_bin("iqra").unwrap();
+    command.env("HOME", std::env::temp_dir()).env("IQRA_LANG", lang);
+    command
+}
+
+#[test]
+fn test_nil_text_follows_language() {
+    assert_eq!(nil_text(ErrorLanguage::Arabic, false), "فارغ");
+    assert_eq!(nil_text(ErrorLanguage::English, false), "nil");
+    assert_eq!(nil_text(ErrorLanguage::Both, false), "فارغ");
+    assert_eq!(nil_text(ErrorLanguage::Arabic, true), "فارغ");
+    assert_eq!(nil_text(ErrorLanguage::English, true), "nil");
+    assert_eq!(nil_text(ErrorLanguage::Both, true), "فارغ|nil");
+}
+
+#[test]
+fn test_scripts_print_nil_in_the_chosen_language() {
+    for (lang, nil) in [("ar", "فارغ"), ("en", "nil"), ("both", "فارغ")] {
+        let expected = format!("أ\n{nil}\n[{nil}]\n{nil}\n");
+        iqra(lang).args(["run", NIL_SCRIPT]).assert().success().stdout(expected);
+    }
+}
+
+#[test]
+fn test_repl_shows_both_names_of_nil() {
+    iqra("both")
+        .arg("repl")
+        .write_stdin("س = اطبع(\"أ\")\nاطبع(س)\n")
+        .assert()
+        .success()
+        .stdout(contains("\nفارغ|nil\n"));
+}
+
+#[test]
+fn test_run_does_not_echo_the_last_value_by_default() {
+    iqra("en")
+        .args(["run", "--no-warnings", "tests/fixtures/last_value.iqra"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn test_run_echoes_the_last_value_with_print_result() {
+    iqra("en")
+        .args(["run", "--no-warnings", "--print-result", "tests/fixtures/last_value.iqra"])
+        .assert()
+        .success()
+        .stdout("4\n");
+    iqra("en")
+        .args(["run", "--print-result", NIL_SCRIPT])
+        .assert()
+        .success()
+        .stdout("أ\nnil\n[nil]\nnil\n");
+}
+
+#[test]
+fn test_code_still_echoes_the_last_value() {
+    iqra("en").args(["code", "-c", "س = ٣; س + ١"]).assert().success().stdout("4\n");
+}
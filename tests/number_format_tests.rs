@@ -0,0 +1,75 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::{Value, format_float};
+
+fn show(code: &str) -> String {
+    format!("{}", Runtime::new().execute(code).unwrap())
+}
+
+#[test]
+fn test_tricky_floats_display_exactly() {
+    let cases: &[(f64, &str)] = &[
+        (0.1 + 0.2, "0.30000000000000004"),
+        (0.3, "0.3"),
+        (1.1 * 3.0, "3.3000000000000003"),
+        (0.7 + 0.1, "0.7999999999999999"),
+        (1.0 / 3.0, "0.3333333333333333"),
+        (2.0 / 3.0, "0.6666666666666666"),
+        (-0.0, "0"),
+        (2.5, "2.5"),
+        (100.0, "100"),
+        (123456789.123, "123456789.123"),
+        (1e21, "1000000000000000000000"),
+        (0.000001, "0.000001"),
+        (f64::INFINITY, "inf"),
+    ];
+    for (n, expected) in cases {
+        assert_eq!(format_float(*n), *expected, "formatting {:?}", n);
+        assert_eq!(format!("{}", Value::Number(*n)), *expected);
+    }
+}
+
+#[test]
+fn test_distinct_floats_print_distinctly() {
+    let cases: &[f64] = &[
+        0.1 + 0.2,
+        0.3,
+        9223372036854775808.0,
+        9223372036854780000.0,
+        1.0 + f64::EPSILON,
+        1.0,
+        123456789.12345679,
+        123456789.1234568,
+    ];
+    for n in cases {
+        let text = format_float(*n);
+        assert_eq!(text.parse::<f64>().unwrap(), *n, "{} reads back as {}", text, n);
+    }
+    // 2^63 as a float, no longer rounded onto its neighbour ...780000.
+    assert_eq!(show("إلى_رقم(\"9223372036854775808\")"), "9223372036854776000");
+    assert_ne!(show("٠٫١ + ٠٫٢"), show("٠٫٣"));
+    assert_eq!(show("إلى_نص(١٫١ * ٣)"), "3.3000000000000003");
+    // Rounding for display is explicit.
+    assert_eq!(show("نسق_رقم(٠٫١ + ٠٫٢، ١)"), "0.3");
+}
+
+#[test]
+fn test_format_number_builtin() {
+    assert_eq!(show("format_number(1234567.891, 2, \"،\")"), "1،234،567.89");
+    assert_eq!(show("نسق_رقم(١٢٣٤٥٦٧٫٨٩١، ٢، \"،\")"), "1،234،567.89");
+    assert_eq!(show("format_number(1234567.891, 1)"), "1234567.9");
+    assert_eq!(show("format_number(-1234, 2, \",\")"), "-1,234.00");
+    assert_eq!(show("format_number(999, 0, \",\")"), "999");
+    assert_eq!(show("format_number(-0.001, 2)"), "0.00");
+    assert_eq!(show("format_number(0.125, 0)"), "0");
+}
+
+#[test]
+fn test_format_number_rejects_bad_arguments() {
+    let mut runtime = Runtime::new();
+    let err = runtime.execute("format_number(\"x\", 2)").unwrap_err().to_string();
+    assert!(err.contains("format_number expects a number"), "{}", err);
+    let err = runtime.execute("format_number(1.5, -1)").unwrap_err().to_string();
+    assert!(err.contains("non-negative whole number of decimals"), "{}", err);
+    let err = runtime.execute("format_number(1.5)").unwrap_err().to_string();
+    assert!(err.contains("expects 2 to 3 argument"), "{}", err);
+}
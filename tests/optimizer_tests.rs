@@ -0,0 +1,148 @@
+use iqra::lang::lexer::Lexer;
+use iqra::lang::optimizer::optimize;
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::parser::{ExprKind, Parser, Stmt, StmtKind};
+use iqra::lang::runtime::{IqraError, Runtime};
+use iqra::lang::value::Value;
+use std::fs;
+
+fn parse(code: &str) -> Vec<Stmt> {
+    Parser::new(Lexer::new(code)).parse().unwrap()
+}
+
+/// The value assigned by the first statement, which must be a literal.
+fn folded_literal(code: &str) -> Value {
+    match &optimize(parse(code))[0].kind {
+        StmtKind::Assignment { value, .. } => match &value.kind {
+            ExprKind::Literal(value) => value.clone(),
+            other => panic!("{} was not folded: {:?}", code, other),
+        },
+        other => panic!("expected an assignment, got {:?}", other),
+    }
+}
+
+/// Runs `code` and returns what it printed and its value or error, with
+/// or without the optimizer.
+fn run(code: &str, optimize: bool) -> (String, Result<Value, String>) {
+    let output = CapturedOutput::new();
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(output.clone()));
+    runtime.set_optimize(optimize);
+    let result = runtime.execute(code).map_err(|err| format!("{}", err));
+    (output.contents(), result)
+}
+
+#[test]
+fn test_constant_arithmetic_is_folded() {
+    assert_eq!(folded_literal("ثواني = ٦٠ * ٦٠ * ٢٤\n"), Value::Int(86400));
+    assert_eq!(folded_literal("س = -(٧ / ٢) + ١\n"), Value::Number(-2.5));
+    assert_eq!(
+        folded_literal("س = \"اقرأ\" + \" \" + \"الآن\"\n"),
+        Value::String("اقرأ الآن".into())
+    );
+    assert_eq!(folded_literal("س = ليس (١ < ٢ و ٣ >= ٣)\n"), Value::Bool(false));
+    assert_eq!(
+        folded_literal("س = [١، ٢ * ٣، [٤]]\n"),
+        Value::List(vec![Value::Int(1), Value::Int(6), Value::List(vec![Value::Int(4)])])
+    );
+}
+
+#[test]
+fn test_expressions_with_variables_are_only_partly_folded() {
+    let statements = optimize(parse("س = ٢\nص = س * (٣ + ٤)\nع = [س، ١]\n"));
+    let StmtKind::Assignment { value, .. } = &statements[1].kind else { panic!() };
+    let ExprKind::Binary { left, right, .. } = &value.kind else { panic!("{:?}", value) };
+    assert!(matches!(left.kind, ExprKind::Identifier(_)));
+    assert!(matches!(right.kind, ExprKind::Literal(Value::Int(7))));
+    let StmtKind::Assignment { value, .. } = &statements[2].kind else { panic!() };
+    assert!(matches!(value.kind, ExprKind::List(_)));
+}
+
+#[test]
+fn test_failing_operations_are_left_for_the_runtime() {
+    for code in ["س = ١ / ٠\n", "س = ٥ % ٠\n", "س = ٩٢٢٣٣٧٢٠٣٦٨٥٤٧٧٥٨٠٧ + ١\n", "س = \"أ\" - ١\n"]
+    {
+        let statements = optimize(parse(code));
+        let StmtKind::Assignment { value, .. } = &statements[0].kind else { panic!() };
+        assert!(matches!(value.kind, ExprKind::Binary { .. }), "{}", code);
+    }
+    let code = "اطبع(١)\nس = ١٠ / (٢ - ٢)\n";
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(CapturedOutput::new()));
+    runtime.set_optimize(true);
+    let err = runtime.execute(code).unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.message_en, "Division by zero");
+    assert_eq!(err.line, Some(2));
+}
+
+#[test]
+fn test_folding_the_smallest_integer_divided_by_minus_one_is_an_error() {
+    for code in ["س = (-9223372036854775807 - 1) / -1\n", "س = (-9223372036854775807 - 1) % -1\n"] {
+        let statements = optimize(parse(code));
+        let StmtKind::Assignment { value, .. } = &statements[0].kind else { panic!() };
+        assert!(matches!(value.kind, ExprKind::Binary { .. }), "{}", code);
+        let (_, result) = run(code, true);
+        let err = result.unwrap_err();
+        assert!(err.contains("The result is outside the integer range"), "{}", err);
+    }
+}
+
+#[test]
+fn test_if_with_a_literal_condition_keeps_only_the_live_branch() {
+    let statements = optimize(parse(
+        "اذا خطأ {\n    اطبع(١)\n}\nاذا ١ > ٢ {\n    اطبع(٢)\n} وإلا {\n    اطبع(٣)\n}\nif true { print(4) }\n",
+    ));
+    let blocks: Vec<usize> = statements
+        .iter()
+        .map(|stmt| match &stmt.kind {
+            StmtKind::Block(body) => body.len(),
+            other => panic!("expected a block, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(blocks, [0, 1, 1]);
+    assert_eq!(run("اذا ١ > ٢ {\n    اطبع(٢)\n} وإلا {\n    اطبع(٣)\n}\n", true).0, "3\n");
+
+    let statements = optimize(parse("س = ١\nاذا س > ٢ {\n    اطبع(س)\n}\n"));
+    assert!(matches!(statements[1].kind, StmtKind::If { .. }));
+}
+
+#[test]
+fn test_nested_bodies_are_optimized() {
+    let statements = optimize(parse(
+        "دالة يوم() {\n    ارجع ٦٠ * ٦٠ * ٢٤\n}\nبينما خطأ {\n    اذا صحيح { س = ٢ + ٢ }\n}\n",
+    ));
+    let StmtKind::FunctionDef { body, .. } = &statements[0].kind else { panic!() };
+    let StmtKind::Return(value) = &body[0].kind else { panic!() };
+    assert!(matches!(value.kind, ExprKind::Literal(Value::Int(86400))));
+    let StmtKind::While { body, .. } = &statements[1].kind else { panic!() };
+    assert!(matches!(body[0].kind, StmtKind::Block(_)));
+}
+
+#[test]
+fn test_optimized_programs_behave_the_same() {
+    let mut programs: Vec<String> = [
+        "اذا خطأ {\n    اطبع(\"لا\")\n}\n",
+        "اذا صحيح { ١ } وإلا { ٢ }\n",
+        "س = ٠\nبينما س < ٢ * ٥ {\n    س = س + ١\n}\nس\n",
+        "دالة ضعف(ن = ٢ + ٣) {\n    ارجع ن * ٢\n}\nاطبع(ضعف()، ضعف(١))\n",
+        "جرب {\n    س = ١ / ٠\n} امسك (خ) {\n    اطبع(\"خطأ\")\n}\n",
+        "[١، [٢، ٣]، \"أ\" + \"ب\"][١][٠] + ١\n",
+        "اطبع(-٩٢٢٣٣٧٢٠٣٦٨٥٤٧٧٥٨٠٧ - ٢)\n",
+    ]
+    .iter()
+    .map(|code| code.to_string())
+    .collect();
+    for dir in ["examples", "tests/fixtures"] {
+        let mut paths: Vec<_> =
+            fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+        paths.sort();
+        for path in
+            paths.into_iter().filter(|path| path.extension().is_some_and(|ext| ext == "iqra"))
+        {
+            programs.push(fs::read_to_string(path).unwrap());
+        }
+    }
+    for code in &programs {
+        assert_eq!(run(code, true), run(code, false), "{}", code);
+    }
+}
@@ -0,0 +1,80 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::{Value, display_width};
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn text(code: &str) -> String {
+    match eval(code) {
+        Value::String(s) => s,
+        other => panic!("expected a string, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_display_width_ignores_combining_marks() {
+    assert_eq!(display_width("كتاب"), 4);
+    // Lam followed by alef is drawn as one ligature.
+    assert_eq!(display_width("سلام"), 3);
+    assert_eq!(display_width("مُحَمَّد"), 4);
+    assert_eq!(display_width("abc"), 3);
+}
+
+#[test]
+fn test_repeat() {
+    assert_eq!(text("كرر_نص(\"-\"، ٥)"), "-----");
+    assert_eq!(text("repeat(\"أب\", 0)"), "");
+    assert!(Runtime::new().execute("repeat(\"أ\", -1)").is_err());
+}
+
+#[test]
+fn test_pad_start_end_and_center() {
+    assert_eq!(text("احشِ_يمين(\"٧\"، ٣، \"٠\")"), "٠٠٧");
+    assert_eq!(text("pad_end(\"ab\", 5, \".\")"), "ab...");
+    assert_eq!(text("وسّط(\"أ\"، ٤، \"*\")"), "*أ**");
+    assert_eq!(text("pad_start(\"x\", 3)"), "  x");
+}
+
+#[test]
+fn test_combining_marks_do_not_count() {
+    let padded = text("pad_end(\"مُحَمَّد\", 6, \".\")");
+    assert_eq!(padded, "مُحَمَّد..");
+    assert_eq!(display_width(&padded), 6);
+}
+
+#[test]
+fn test_narrow_width_returns_the_string_unchanged() {
+    assert_eq!(text("pad_start(\"مرحبا\", 2)"), "مرحبا");
+    assert_eq!(text("center(\"مرحبا\", 5, \"-\")"), "مرحبا");
+}
+
+#[test]
+fn test_fill_must_be_one_character() {
+    let err = Runtime::new().execute("pad_end(\"أ\", 5, \"ab\")").unwrap_err();
+    let message = format!("{}", err);
+    assert!(message.contains("حرفاً واحداً"), "{}", message);
+    assert!(message.contains("one visible character"), "{}", message);
+    assert!(Runtime::new().execute("pad_end(\"أ\", 5, \"\")").is_err());
+    // A letter with a haraka is still one grapheme.
+    assert_eq!(text("pad_end(\"\", 2, \"بَ\")"), "بَبَ");
+}
+
+#[test]
+fn test_two_column_listing_aligns() {
+    let code = "صفوف = [[\"قلم\"، \"pen\"]، [\"مُعَلِّم\"، \"teacher\"]، [\"كتاب\"، \"book\"]]\n\
+                ناتج = []\n\
+                ي = ٠\n\
+                بينما ي < طول(صفوف) {\n\
+                    ناتج = أضف(ناتج، احشِ_يسار(صفوف[ي][٠]، ٨، \".\") + \" | \" + احشِ_يمين(صفوف[ي][١]، ٨))\n\
+                    ي = ي + ١\n\
+                }\n\
+                ناتج";
+    let Value::List(lines) = eval(code) else { panic!("expected a list") };
+    let lines: Vec<String> = lines.iter().map(|line| format!("{}", line)).collect();
+    assert_eq!(lines[0], "قلم..... |      pen");
+    assert_eq!(lines[1], "مُعَلِّم.... |  teacher");
+    for line in &lines {
+        assert_eq!(display_width(line), 19, "{}", line);
+    }
+}
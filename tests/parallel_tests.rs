@@ -0,0 +1,66 @@
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::runtime::{IqraError, Runtime};
+use iqra::lang::value::Value;
+
+fn assert_send<T: Send>() {}
+
+fn eval(source: &str) -> Value {
+    Runtime::new().execute(source).unwrap()
+}
+
+#[test]
+fn test_runtime_is_send() {
+    assert_send::<Runtime>();
+}
+
+#[test]
+fn test_parallel_map_matches_sequential_map() {
+    let functions = "دالة مربع(س) { ارجع س * س }\n";
+    let items: Vec<String> = (1..=50).map(|i| i.to_string()).collect();
+    let list = format!("[{}]", items.join("، "));
+    let sequential = eval(&format!(
+        "{}ق = {}\nن = []\nي = ٠\nبينما ي < طول(ق) {{ ن = أضف(ن، مربع(ق[ي]))\nي = ي + ١ }}\nن",
+        functions, list
+    ));
+    let parallel = eval(&format!("{}خريطة_متوازية({}، \"مربع\")", functions, list));
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn test_parallel_map_calls_builtins() {
+    assert_eq!(
+        eval("parallel_map([\"أ\", \"بب\", \"\"], \"len\")"),
+        Value::List(vec![Value::Int(1), Value::Int(2), Value::Int(0)])
+    );
+}
+
+#[test]
+fn test_workers_see_globals_and_print_in_order() {
+    let output = CapturedOutput::new();
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(output.clone()));
+    let value = runtime
+        .execute("عامل = ١٠\nدالة ضرب(س) { اطبع(س)\nارجع س * عامل }\nخريطة_متوازية([١، ٢، ٣، ٤]، \"ضرب\")")
+        .unwrap();
+    assert_eq!(
+        value,
+        Value::List(vec![Value::Int(10), Value::Int(20), Value::Int(30), Value::Int(40)])
+    );
+    assert_eq!(output.contents(), "1\n2\n3\n4\n");
+}
+
+#[test]
+fn test_error_names_the_failing_element() {
+    let err = Runtime::new()
+        .execute("دالة مقلوب(س) { ارجع ١ / س }\nخريطة_متوازية([١، ٢، ٠، ٤]، \"مقلوب\")")
+        .unwrap_err();
+    let err = err.downcast_ref::<IqraError>().expect("IqraError");
+    assert!(err.message_en.starts_with("At element 2:"), "{}", err.message_en);
+}
+
+#[test]
+fn test_empty_list_and_unknown_function() {
+    assert_eq!(eval("parallel_map([], \"len\")"), Value::List(Vec::new()));
+    assert!(Runtime::new().execute("parallel_map([1], \"missing\")").is_err());
+    assert!(Runtime::new().execute("parallel_map(1, \"len\")").is_err());
+}
@@ -0,0 +1,71 @@
+use assert_cmd::Command;
+use iqra::lang::builtins::path::{basename, dirname, is_absolute, join};
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+use predicates::str::contains;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+#[test]
+fn test_join_unix_and_windows_paths() {
+    assert_eq!(join(&["بيانات", "طلاب.csv"]), "بيانات/طلاب.csv");
+    assert_eq!(join(&["/home/علي/", "ملف.txt"]), "/home/علي/ملف.txt");
+    assert_eq!(join(&["C:\\Users\\علي", "مستندات", "ملف.txt"]), "C:\\Users\\علي\\مستندات\\ملف.txt");
+    assert_eq!(join(&["C:\\Users", "a/b"]), "C:\\Users\\a/b");
+    // An absolute part starts over, as with Path::join.
+    assert_eq!(join(&["a", "/etc", "hosts"]), "/etc/hosts");
+    assert_eq!(join(&["a", "D:\\x"]), "D:\\x");
+    assert_eq!(join(&["", "a", ""]), "a");
+}
+
+#[test]
+fn test_basename() {
+    assert_eq!(basename("/home/علي/ملف.txt"), "ملف.txt");
+    assert_eq!(basename("C:\\Users\\علي\\ملف.txt"), "ملف.txt");
+    assert_eq!(basename("بيانات/"), "بيانات");
+    assert_eq!(basename("ملف"), "ملف");
+    assert_eq!(basename("/"), "");
+    assert_eq!(basename("C:\\"), "");
+}
+
+#[test]
+fn test_dirname() {
+    assert_eq!(dirname("/home/علي/ملف.txt"), "/home/علي");
+    assert_eq!(dirname("C:\\Users\\علي\\ملف.txt"), "C:\\Users\\علي");
+    assert_eq!(dirname("/ملف.txt"), "/");
+    assert_eq!(dirname("C:\\ملف.txt"), "C:\\");
+    assert_eq!(dirname("ملف.txt"), "");
+    assert_eq!(dirname("a//b/"), "a");
+}
+
+#[test]
+fn test_is_absolute() {
+    assert!(is_absolute("/a"));
+    assert!(is_absolute("\\\\server\\share"));
+    assert!(is_absolute("c:\\a"));
+    assert!(!is_absolute("a/b"));
+    assert!(!is_absolute("علي:"));
+}
+
+#[test]
+fn test_path_builtins() {
+    assert_eq!(eval("وصل_مسار(\"أ\"، \"ب\"، \"ج.txt\")"), Value::String("أ/ب/ج.txt".to_string()));
+    assert_eq!(eval("اسم_الملف(\"أ/ب.txt\")"), Value::String("ب.txt".to_string()));
+    assert_eq!(eval("dirname(\"أ\\\\ب.txt\")"), Value::String("أ".to_string()));
+    assert!(Runtime::new().execute("join_path()").is_err());
+    assert!(Runtime::new().execute("join_path(\"a\", 1)").is_err());
+}
+
+#[test]
+fn test_run_reports_the_missing_path() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["run", "لا_يوجد.iqra"])
+        .assert()
+        .code(1)
+        .stderr(contains("Could not read 'لا_يوجد.iqra'"))
+        .stderr(contains("تعذرت قراءة الملف"))
+        .stderr(contains("working directory"));
+}
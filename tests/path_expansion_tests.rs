@@ -0,0 +1,153 @@
+use iqra::lang::builtins::path::expand;
+use iqra::lang::runtime::{Runtime, SystemExecutor};
+use iqra::lang::value::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Fake environment; records the path of every file read or written.
+#[derive(Clone, Default)]
+struct FakeEnv {
+    vars: HashMap<String, String>,
+    paths: Arc<Mutex<Vec<String>>>,
+}
+
+impl FakeEnv {
+    fn new(vars: &[(&str, &str)]) -> Self {
+        let vars = vars.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+        FakeEnv { vars, paths: Arc::default() }
+    }
+
+    fn paths(&self) -> Vec<String> {
+        self.paths.lock().unwrap().clone()
+    }
+}
+
+impl SystemExecutor for FakeEnv {
+    fn exec(&self, _cmd: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn exec_with_io(&self, _cmd: &str, _input: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn read_file(&self, path: &str) -> std::io::Result<String> {
+        self.paths.lock().unwrap().push(path.to_string());
+        Ok("محتوى".to_string())
+    }
+
+    fn write_file(&self, path: &str, _content: &str) -> std::io::Result<bool> {
+        self.paths.lock().unwrap().push(path.to_string());
+        Ok(true)
+    }
+
+    fn list_files(&self, path: &str) -> std::io::Result<Vec<String>> {
+        self.paths.lock().unwrap().push(path.to_string());
+        Ok(Vec::new())
+    }
+
+    fn get_env_var(&self, name: &str) -> Option<String> {
+        self.vars.get(name).cloned()
+    }
+
+    fn set_env_var(&self, _name: &str, _value: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn current_dir(&self) -> std::io::Result<String> {
+        Ok("/mock".to_string())
+    }
+
+    fn change_dir(&self, _path: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn process_id(&self) -> u32 {
+        42
+    }
+
+    fn read_line(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn read_password(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn system_info(&self) -> std::io::Result<HashMap<String, String>> {
+        Ok(HashMap::new())
+    }
+}
+
+fn expanded(path: &str) -> (String, Vec<String>) {
+    let env = |name: &str| match name {
+        "DATA" => Some("/srv/بيانات".to_string()),
+        "EMPTY" => Some(String::new()),
+        _ => None,
+    };
+    let mut unset = Vec::new();
+    let path = expand(path, Some("/home/علي"), &env, &mut |name| unset.push(name.to_string()));
+    (path, unset)
+}
+
+#[test]
+fn test_expand_shapes() {
+    assert_eq!(expanded("~").0, "/home/علي");
+    assert_eq!(expanded("~/بيانات.txt").0, "/home/علي/بيانات.txt");
+    assert_eq!(expanded("~\\ملف").0, "/home/علي\\ملف");
+    assert_eq!(expanded("$DATA/طلاب.csv").0, "/srv/بيانات/طلاب.csv");
+    assert_eq!(expanded("${DATA}_قديم/x").0, "/srv/بيانات_قديم/x");
+    assert_eq!(expanded("a/$DATA/$DATA").0, "a//srv/بيانات//srv/بيانات");
+    assert_eq!(expanded("~/$EMPTY/x").0, "/home/علي//x");
+    // Only a leading ~ and $ followed by a name are special.
+    assert_eq!(expanded("~علي/x").0, "~علي/x");
+    assert_eq!(expanded("a/~/b").0, "a/~/b");
+    assert_eq!(expanded("سعر$").0, "سعر$");
+    assert_eq!(expanded("$/x").0, "$/x");
+    assert_eq!(expanded("${DATA").0, "${DATA");
+    assert_eq!(expanded("${}x").0, "${}x");
+    assert_eq!(expanded("plain/path").0, "plain/path");
+}
+
+#[test]
+fn test_unset_variables_expand_to_nothing() {
+    assert_eq!(expanded("$MISSING/x"), ("/x".to_string(), vec!["MISSING".to_string()]));
+    assert_eq!(
+        expanded("${MISSING}${DATA}"),
+        ("/srv/بيانات".to_string(), vec!["MISSING".to_string()])
+    );
+    let mut unset = Vec::new();
+    let path = expand("~/x", None, &|_| None, &mut |name| unset.push(name.to_string()));
+    assert_eq!(path, "~/x");
+    assert!(unset.is_empty());
+}
+
+#[test]
+fn test_file_builtins_expand_their_paths() {
+    let env = FakeEnv::new(&[("HOME", "/home/علي"), ("DATA", "/srv")]);
+    let mut runtime = Runtime::new_with_executor(Box::new(env.clone()));
+    runtime.execute("اقرأ_ملف(\"~/بيانات.txt\")").unwrap();
+    runtime.execute("write_file(\"${DATA}/out.txt\", \"x\")").unwrap();
+    runtime.execute("قائمة_ملفات(\"$DATA\")").unwrap();
+    assert_eq!(env.paths(), ["/home/علي/بيانات.txt", "/srv/out.txt", "/srv"]);
+
+    let value = runtime.execute("وسع_مسار(\"~/$DATA\")").unwrap();
+    assert_eq!(value, Value::String("/home/علي//srv".to_string()));
+}
+
+#[test]
+fn test_expansion_can_be_turned_off_and_is_off_in_sandbox() {
+    let env = FakeEnv::new(&[("HOME", "/home/علي")]);
+    let mut runtime = Runtime::new_with_executor(Box::new(env.clone()));
+    runtime.set_expand_paths(false);
+    runtime.execute("اقرأ_ملف(\"~/أ.txt\")").unwrap();
+    runtime.set_expand_paths(true);
+    runtime.set_sandboxed(true);
+    runtime.execute("اقرأ_ملف(\"~/ب.txt\")").unwrap();
+    assert_eq!(env.paths(), ["~/أ.txt", "~/ب.txt"]);
+    // Asking for it directly still works.
+    assert_eq!(
+        runtime.execute("expand_path(\"~\")").unwrap(),
+        Value::String("/home/علي".to_string())
+    );
+}
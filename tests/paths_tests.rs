@@ -0,0 +1,104 @@
+use assert_cmd::Command;
+use iqra::paths::{global_packages_dir_in, home_dir_in, iqra_home_in, package_search_path_in};
+use mockito::mock;
+use predicates::str::contains;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<OsString> {
+    let vars: HashMap<String, OsString> =
+        vars.iter().map(|(name, value)| (name.to_string(), OsString::from(value))).collect();
+    move |name| vars.get(name).cloned()
+}
+
+#[test]
+fn test_home_prefers_home_then_userprofile() {
+    assert_eq!(
+        home_dir_in(&env(&[("HOME", "/home/amjad"), ("USERPROFILE", "C:\\Users\\amjad")])),
+        Some(PathBuf::from("/home/amjad"))
+    );
+    assert_eq!(
+        home_dir_in(&env(&[("USERPROFILE", "C:\\Users\\amjad")])),
+        Some(PathBuf::from("C:\\Users\\amjad"))
+    );
+    // An empty HOME is treated as unset.
+    assert_eq!(
+        home_dir_in(&env(&[("HOME", ""), ("USERPROFILE", "D:\\u")])),
+        Some(PathBuf::from("D:\\u"))
+    );
+}
+
+#[test]
+fn test_windows_home_from_drive_and_path() {
+    let windows = env(&[("HOMEDRIVE", "C:"), ("HOMEPATH", "\\Users\\amjad")]);
+    assert_eq!(home_dir_in(&windows), Some(PathBuf::from("C:").join("Users\\amjad")));
+    assert_eq!(home_dir_in(&env(&[("HOMEDRIVE", "C:")])), None);
+    assert_eq!(home_dir_in(&env(&[])), None);
+}
+
+#[test]
+fn test_iqra_home_override_and_default() {
+    let home = env(&[("HOME", "/home/amjad")]);
+    assert_eq!(iqra_home_in(&home), Some(PathBuf::from("/home/amjad/.iqra")));
+    assert_eq!(global_packages_dir_in(&home), Some(PathBuf::from("/home/amjad/.iqra/packages")));
+    let custom = env(&[("HOME", "/home/amjad"), ("IQRA_HOME", "/opt/iqra")]);
+    assert_eq!(global_packages_dir_in(&custom), Some(PathBuf::from("/opt/iqra/packages")));
+    assert_eq!(iqra_home_in(&env(&[])), None);
+}
+
+#[test]
+fn test_search_path_is_local_then_global() {
+    let search =
+        package_search_path_in(Path::new("/work/app"), &env(&[("IQRA_HOME", "/opt/iqra")]));
+    assert_eq!(
+        search,
+        [PathBuf::from("/work/app/iqra_packages"), PathBuf::from("/opt/iqra/packages")]
+    );
+    assert_eq!(
+        package_search_path_in(Path::new("."), &env(&[])),
+        [PathBuf::from("./iqra_packages")]
+    );
+}
+
+#[test]
+fn test_global_install_list_and_run_from_anywhere() {
+    let root = std::env::temp_dir().join(format!("iqra_paths_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let (home, project) = (root.join("home"), root.join("project"));
+    fs::create_dir_all(&project).unwrap();
+    let _download = mock("GET", "/packages/salam/latest/download")
+        .with_status(200)
+        .with_body("اطبع(\"سلام عالمي\")\n")
+        .create();
+    let iqra_pkg = |args: &[&str]| {
+        let mut command = Command::cargo_bin("iqra-pkg").unwrap();
+        command
+            .current_dir(&project)
+            .env("IQRA_HOME", &home)
+            .env("MOCKITO_SERVER_URL", mockito::server_url())
+            .args(args);
+        command
+    };
+
+    iqra_pkg(&["install", "-g", "salam"])
+        .assert()
+        .success()
+        .stdout(contains("Installed successfully"));
+    assert!(home.join("packages/salam").is_file());
+    assert!(home.join("iqra-pkg.json").is_file());
+    assert!(!project.join("iqra_packages").exists());
+    iqra_pkg(&["list", "--global"]).assert().success().stdout(contains("- salam (latest)"));
+    iqra_pkg(&["list"]).assert().success().stdout(contains("No packages installed"));
+    iqra_pkg(&["run", "salam"]).assert().success().stdout("سلام عالمي\n");
+
+    // A project-local copy comes first.
+    fs::create_dir_all(project.join("iqra_packages")).unwrap();
+    fs::write(project.join("iqra_packages/salam"), "اطبع(\"سلام محلي\")\n").unwrap();
+    iqra_pkg(&["run", "salam"]).assert().success().stdout("سلام محلي\n");
+
+    iqra_pkg(&["remove", "-g", "salam"]).assert().stdout(contains("Removed locally"));
+    assert!(!home.join("packages/salam").exists());
+    fs::remove_dir_all(root).unwrap();
+}
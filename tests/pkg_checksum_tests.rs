@@ -0,0 +1,152 @@
+use iqra::pkg::registry::gzip;
+use iqra::pkg::{
+    CHECKSUM_HEADER, FsRegistry, HttpRegistry, PackageArchive, PackageManifest, RegistryClient,
+    RegistryPackage,
+};
+use mockito::{Matcher, mock};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+
+fn temp(test: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("iqra_pkg_checksum_{}_{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn sha256(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[test]
+fn test_publish_sends_the_archive_checksum_twice() {
+    let package = temp("publish");
+    fs::write(package.join("main.iqra"), "اطبع(١)\n").unwrap();
+    let archive = PackageArchive::build(&package).unwrap();
+    let checksum = format!("sha256:{}", sha256(&archive.bytes));
+    assert_eq!(archive.checksum(), checksum);
+    let upload = mock("POST", "/packages")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(format!(r#""checksum":"{}""#, checksum)),
+            Matcher::Regex(format!("name=\"checksum\"\r\n\r\n{}\r\n", checksum)),
+        ]))
+        .with_status(201)
+        .create();
+    let manifest = PackageManifest { version: Some("1.0.0".into()), ..PackageManifest::default() };
+    let metadata = manifest.metadata("basma").unwrap();
+    assert_eq!(metadata.checksum, None);
+    HttpRegistry::new(&mockito::server_url()).publish(&metadata, archive).unwrap();
+    upload.assert();
+}
+
+#[test]
+fn test_install_checks_the_digest_in_the_package_metadata() {
+    let dir = temp("metadata");
+    let body = "اطبع(\"وصل\")\n";
+    let advertised = sha256("شيء آخر".as_bytes());
+    let _download = mock("GET", "/packages/basma_bad/latest/download")
+        .with_status(200)
+        .with_body(body)
+        .create();
+    let _info = mock("GET", "/packages/basma_bad")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{"versions": ["1.0.0", "1.1.0"], "checksums": {{"1.1.0": "sha256:{}"}}}}"#,
+            advertised
+        ))
+        .create();
+    let registry = HttpRegistry::new(&mockito::server_url());
+    let err = registry.install("basma_bad", &dir).unwrap_err().to_string();
+    assert!(err.contains(&format!("expected sha256:{}", advertised)), "{}", err);
+    assert!(err.contains(&format!("got sha256:{}", sha256(body.as_bytes()))), "{}", err);
+    assert!(err.contains("المتوقعة"), "{}", err);
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+}
+
+#[test]
+fn test_install_accepts_a_matching_digest_from_either_source() {
+    let dir = temp("matching");
+    let body = "اطبع(\"سليم\")\n";
+    let _header = mock("GET", "/packages/basma_header/latest/download")
+        .with_status(200)
+        .with_header(CHECKSUM_HEADER, &format!("sha256:{}", sha256(body.as_bytes())))
+        .with_body(body)
+        .create();
+    let _download = mock("GET", "/packages/basma_index/latest/download")
+        .with_status(200)
+        .with_body(body)
+        .create();
+    let _info = mock("GET", "/packages/basma_index")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{"versions": ["2.0.0"], "latest": "2.0.0", "checksums": {{"2.0.0": "sha256:{}"}}}}"#,
+            sha256(body.as_bytes()).to_uppercase()
+        ))
+        .create();
+    let registry = HttpRegistry::new(&mockito::server_url());
+    registry.install("basma_header", &dir).unwrap();
+    assert_eq!(registry.install("basma_index", &dir).unwrap(), "latest");
+    assert_eq!(fs::read_to_string(dir.join("basma_header")).unwrap(), body);
+    assert_eq!(fs::read_to_string(dir.join("basma_index")).unwrap(), body);
+}
+
+#[test]
+fn test_header_wins_over_the_package_metadata() {
+    let dir = temp("header_wins");
+    let body = "اطبع(\"رأس\")\n";
+    let _download = mock("GET", "/packages/basma_both/latest/download")
+        .with_status(200)
+        .with_header(CHECKSUM_HEADER, &sha256(body.as_bytes()))
+        .with_body(body)
+        .create();
+    let info = mock("GET", "/packages/basma_both").expect(0).create();
+    HttpRegistry::new(&mockito::server_url()).install("basma_both", &dir).unwrap();
+    info.assert();
+}
+
+#[test]
+fn test_install_refuses_names_that_leave_the_directory() {
+    let root = temp("escape");
+    let dir = root.join("packages");
+    fs::create_dir_all(&dir).unwrap();
+    // Where a download of `../x` would land once the URL is normalized.
+    let download = mock("GET", "/x/latest/download").with_body("اطبع(١)\n").expect(0).create();
+    let registry = HttpRegistry::new(&mockito::server_url());
+    for name in ["../x", "/tmp/x", "a/b", ".."] {
+        let err = registry.install(name, &dir).unwrap_err().to_string();
+        assert!(err.contains(name), "{}", err);
+    }
+    download.assert();
+    assert!(!root.join("x").exists());
+}
+
+#[test]
+fn test_directory_registry_checks_recorded_checksums() {
+    let root = temp("fs");
+    let source = root.join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("main.iqra"), "اطبع(١)\n").unwrap();
+    let archive = gzip(&PackageArchive::build(&source).unwrap().bytes);
+    for (name, checksum) in [("salim", sha256(&archive)), ("talif", sha256(b"other"))] {
+        let dir = root.join("packages").join(name);
+        fs::create_dir_all(dir.join("1.0.0")).unwrap();
+        fs::write(dir.join("1.0.0/archive.tar.gz"), &archive).unwrap();
+        let index = format!(
+            r#"{{"versions": ["1.0.0"], "checksums": {{"1.0.0": "sha256:{}"}}}}"#,
+            checksum
+        );
+        fs::write(dir.join("index.json"), index).unwrap();
+    }
+    let installed = temp("fs_installed");
+    let registry = FsRegistry::new(&root);
+    assert_eq!(registry.install("salim", &installed).unwrap(), "1.0.0");
+    let err = registry.install("talif", &installed).unwrap_err().to_string();
+    assert!(err.contains("SHA-256 mismatch"), "{}", err);
+    assert!(!installed.join("talif").exists());
+    let package: RegistryPackage = registry.package("salim").unwrap();
+    assert_eq!(package.latest_checksum(), Some(format!("sha256:{}", sha256(&archive)).as_str()));
+}
@@ -0,0 +1,121 @@
+use assert_cmd::Command;
+use iqra::pkg::{DownloadOutcome, HttpRegistry, download_packages, render_download_summary};
+use mockito::mock;
+use predicates::str::contains;
+use std::fs;
+use std::path::PathBuf;
+
+fn project(test: &str) -> PathBuf {
+    let project =
+        std::env::temp_dir().join(format!("iqra_pkg_download_{}_{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&project);
+    fs::create_dir_all(&project).unwrap();
+    project
+}
+
+fn download(package: &str, status: usize) -> mockito::Mock {
+    mock("GET", format!("/packages/{}/latest/download", package).as_str())
+        .with_status(status)
+        .with_body(format!("اطبع(\"{}\")\n", package))
+        .expect(1)
+        .create()
+}
+
+#[test]
+fn test_summary_counts_successes_and_failures() {
+    let outcomes = vec![
+        DownloadOutcome { package: "جبر".to_string(), result: Ok("latest".to_string()) },
+        DownloadOutcome { package: "net".to_string(), result: Err("HTTP 500".to_string()) },
+    ];
+    assert_eq!(
+        render_download_summary(&outcomes),
+        "الحزمة | Package  النتيجة | Result\n\
+         جبر               تم | ok\n\
+         net               فشل | failed: HTTP 500\n\
+         نجح 1، فشل 1 | 1 succeeded, 1 failed\n"
+    );
+}
+
+#[test]
+fn test_downloads_keep_input_order_and_write_files() {
+    let dir = project("order");
+    let packages: Vec<String> = (0..6).map(|i| format!("dl_order_{}", i)).collect();
+    let mocks: Vec<_> = packages
+        .iter()
+        .enumerate()
+        .map(|(i, package)| download(package, if i % 3 == 0 { 500 } else { 200 }))
+        .collect();
+    let registry = HttpRegistry::new(&mockito::server_url());
+    let outcomes = download_packages(&registry, &packages, &dir, 3);
+    let names: Vec<&str> = outcomes.iter().map(|o| o.package.as_str()).collect();
+    assert_eq!(names, packages.iter().map(String::as_str).collect::<Vec<_>>());
+    for (i, outcome) in outcomes.iter().enumerate() {
+        if i % 3 == 0 {
+            assert_eq!(outcome.result, Err("HTTP 500".to_string()));
+            assert!(!dir.join(&outcome.package).exists());
+        } else {
+            assert_eq!(outcome.result, Ok("latest".to_string()));
+            let body = fs::read_to_string(dir.join(&outcome.package)).unwrap();
+            assert!(body.contains(&outcome.package), "{}", body);
+        }
+    }
+    mocks.iter().for_each(mockito::Mock::assert);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_install_reports_mixed_results_and_fails() {
+    let project = project("install");
+    let ok = download("dl_install_ok", 200);
+    let broken = download("dl_install_broken", 500);
+    Command::cargo_bin("iqra-pkg")
+        .unwrap()
+        .current_dir(&project)
+        .env("MOCKITO_SERVER_URL", mockito::server_url())
+        .args(["install", "dl_install_ok", "dl_install_broken", "--jobs", "2"])
+        .assert()
+        .failure()
+        .stdout(contains("dl_install_broken  فشل | failed: HTTP 500"))
+        .stdout(contains("نجح 1، فشل 1 | 1 succeeded, 1 failed"));
+    ok.assert();
+    broken.assert();
+    assert!(project.join("iqra_packages/dl_install_ok").exists());
+    let manifest = fs::read_to_string(project.join("iqra-pkg.json")).unwrap();
+    assert!(manifest.contains("dl_install_ok"), "{}", manifest);
+    assert!(!manifest.contains("dl_install_broken"), "{}", manifest);
+    fs::remove_dir_all(project).unwrap();
+}
+
+#[test]
+fn test_update_downloads_every_installed_package() {
+    let project = project("update");
+    fs::create_dir_all(project.join("iqra_packages")).unwrap();
+    fs::write(
+        project.join("iqra-pkg.json"),
+        r#"{"dl_update_a": "latest", "dl_update_b": "latest", "dl_update_c": "latest"}"#,
+    )
+    .unwrap();
+    let mocks =
+        [download("dl_update_a", 200), download("dl_update_b", 200), download("dl_update_c", 200)];
+    Command::cargo_bin("iqra-pkg")
+        .unwrap()
+        .current_dir(&project)
+        .env("MOCKITO_SERVER_URL", mockito::server_url())
+        .args(["update", "-j", "8"])
+        .assert()
+        .success()
+        .stdout(contains("نجح 3، فشل 0 | 3 succeeded, 0 failed"))
+        .stdout(contains("All packages updated successfully"));
+    mocks.iter().for_each(mockito::Mock::assert);
+    fs::remove_dir_all(project).unwrap();
+}
+
+#[test]
+fn test_jobs_must_be_positive() {
+    Command::cargo_bin("iqra-pkg")
+        .unwrap()
+        .args(["install", "dl_jobs", "--jobs", "0"])
+        .assert()
+        .failure()
+        .stdout(contains("--jobs needs a positive number"));
+}
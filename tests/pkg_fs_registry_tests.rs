@@ -0,0 +1,195 @@
+use assert_cmd::Command;
+use iqra::pkg::registry::{gunzip, gzip, unpack_tar};
+use iqra::pkg::{FsRegistry, PackageArchive, RegistryClient, SearchOptions};
+use predicates::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn temp(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("iqra_fs_registry_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Publishes `version` of `name`, made of `files`, into the registry at
+/// `root` by hand, the way a teacher would fill a shared drive.
+fn add_package(root: &Path, name: &str, version: &str, files: &[(&str, &str)]) {
+    let source = root.join("source").join(name).join(version);
+    for (path, contents) in files {
+        fs::create_dir_all(source.join(path).parent().unwrap()).unwrap();
+        fs::write(source.join(path), contents).unwrap();
+    }
+    let archive = PackageArchive::build(&source).unwrap();
+    let dir = root.join("packages").join(name).join(version);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("archive.tar.gz"), gzip(&archive.bytes)).unwrap();
+}
+
+/// A registry with two versions of `hisab` and one of `salam`.
+fn registry(name: &str) -> PathBuf {
+    let root = temp(name);
+    add_package(&root, "hisab", "1.0.0", &[("main.iqra", "اطبع(\"قديم\")\n")]);
+    add_package(
+        &root,
+        "hisab",
+        "1.1.0",
+        &[("main.iqra", "اطبع(٢١ * ٢)\n"), ("lib/math.iqra", "دالة ضعف(س) { ارجع س * ٢ }\n")],
+    );
+    fs::write(
+        root.join("packages/hisab/index.json"),
+        r#"{"description": "حساب سريع", "author": "Amjad", "versions": ["1.0.0", "1.1.0"]}"#,
+    )
+    .unwrap();
+    add_package(&root, "salam", "0.2.0", &[("main.iqra", "اطبع(\"سلام\")\n")]);
+    fs::write(
+        root.join("packages/salam/index.json"),
+        r#"{"description": "تحية", "versions": ["0.2.0"], "latest": "0.2.0"}"#,
+    )
+    .unwrap();
+    root
+}
+
+fn iqra_pkg(project: &Path) -> Command {
+    let mut command = Command::cargo_bin("iqra-pkg").unwrap();
+    command.current_dir(project).env_remove("MOCKITO_SERVER_URL").env_remove("IQRA_REGISTRY_URL");
+    command
+}
+
+#[test]
+fn test_gzip_and_tar_round_trip() {
+    let text = "السلام عليكم ".repeat(100);
+    assert_eq!(gunzip(&gzip(text.as_bytes())).unwrap(), text.as_bytes());
+    let mut corrupt = gzip(text.as_bytes());
+    let last = corrupt.len() - 5;
+    corrupt[last] ^= 1;
+    assert!(gunzip(&corrupt).is_err());
+    assert!(gunzip(b"not gzip at all").is_err());
+
+    let source = temp("tar");
+    fs::create_dir_all(source.join("lib")).unwrap();
+    fs::write(source.join("main.iqra"), "اطبع(١)\n").unwrap();
+    fs::write(source.join("lib/a.iqra"), "").unwrap();
+    let mut archive = PackageArchive::build(&source).unwrap().bytes;
+    let files = unpack_tar(&archive).unwrap();
+    assert_eq!(
+        files,
+        [
+            (PathBuf::from("lib/a.iqra"), vec![]),
+            (PathBuf::from("main.iqra"), b"\xd8\xa7\xd8\xb7\xd8\xa8\xd8\xb9(\xd9\xa1)\n".to_vec())
+        ]
+    );
+    // A path leaving the package is refused.
+    archive[..10].copy_from_slice(b"../a.iqra\0");
+    assert!(
+        unpack_tar(&archive).unwrap_err().to_string().contains("Unsafe path in archive: ../a.iqra")
+    );
+    fs::remove_dir_all(source).unwrap();
+}
+
+#[test]
+fn test_registry_client_reads_the_directory() {
+    let root = registry("client");
+    let registry = FsRegistry::new(&root);
+    let info = registry.package("hisab").unwrap();
+    assert_eq!(info.versions, ["1.0.0", "1.1.0"]);
+    assert_eq!(info.latest.as_deref(), Some("1.1.0"));
+    assert_eq!(registry.package("missing").unwrap(), Default::default());
+    assert_eq!(registry.package("../packages").unwrap(), Default::default());
+
+    let search = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let results = registry.search(&SearchOptions::from_args(&args).unwrap()).unwrap();
+        results.into_iter().map(|r| (r.name, r.version.unwrap_or_default())).collect::<Vec<_>>()
+    };
+    assert_eq!(search(&["حساب"]), [("hisab".to_string(), "1.1.0".to_string())]);
+    assert_eq!(search(&["a"]).len(), 2);
+    assert_eq!(
+        search(&["a", "--limit", "1", "--page", "2"]),
+        [("salam".to_string(), "0.2.0".to_string())]
+    );
+
+    let dir = temp("client_install");
+    assert_eq!(registry.install("hisab", &dir).unwrap(), "1.1.0");
+    assert!(dir.join("hisab/lib/math.iqra").is_file());
+    let err = registry.install("missing", &dir).unwrap_err();
+    assert!(err.to_string().contains("Package not found in the registry: missing"), "{}", err);
+    assert!(
+        registry.yank("hisab", "1.0.0", "token").unwrap_err().to_string().contains("is read-only")
+    );
+    fs::remove_dir_all(dir).unwrap();
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_install_and_run_from_a_registry_dir() {
+    let root = registry("install");
+    let project = temp("install_project");
+    iqra_pkg(&project)
+        .args(["install", "hisab", "salam", "--registry-dir"])
+        .arg(&root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("نجح 2، فشل 0 | 2 succeeded, 0 failed"));
+    assert!(project.join("iqra_packages/hisab/lib/math.iqra").is_file());
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(project.join("iqra-pkg.json")).unwrap()).unwrap();
+    assert_eq!(manifest, serde_json::json!({"hisab": "1.1.0", "salam": "0.2.0"}));
+    iqra_pkg(&project).args(["run", "hisab"]).assert().success().stdout("42\n");
+    iqra_pkg(&project)
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- hisab (1.1.0)"));
+
+    // A file:// registry URL works the same, for update and search too.
+    let url = format!("file://{}", root.display());
+    iqra_pkg(&project).arg("update").env("IQRA_REGISTRY_URL", &url).assert().success();
+    iqra_pkg(&project)
+        .args(["search", "تحية"])
+        .env("IQRA_REGISTRY_URL", &url)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("salam"))
+        .stdout(predicate::str::contains("0.2.0"))
+        .stdout(predicate::str::contains("hisab").not());
+    iqra_pkg(&project)
+        .args(["install", "missing", "--registry-dir"])
+        .arg(&root)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Package not found in the registry: missing"));
+    fs::remove_dir_all(project).unwrap();
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_summary_and_search_follow_lang_flag() {
+    let root = registry("lang");
+    let project = temp("lang_project");
+    let output = iqra_pkg(&project)
+        .args(["--lang", "en", "install", "hisab", "missing", "--registry-dir"])
+        .arg(&root)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Package  Result\n"), "{}", stdout);
+    assert!(stdout.contains("hisab    ok\n"), "{}", stdout);
+    assert!(
+        stdout.contains("missing  failed: Package not found in the registry: missing\n"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("1 succeeded, 1 failed\n"), "{}", stdout);
+    let output = iqra_pkg(&project)
+        .args(["--lang", "ar", "search", "لا_يوجد", "--registry-dir"])
+        .arg(&root)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("لا توجد نتائج\n"), "{}", stdout);
+    assert!(!stdout.contains("No results"), "{}", stdout);
+    fs::remove_dir_all(project).unwrap();
+    fs::remove_dir_all(root).unwrap();
+}
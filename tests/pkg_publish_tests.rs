@@ -0,0 +1,188 @@
+use assert_cmd::Command;
+use iqra::pkg::{
+    PackageArchive, PackageManifest, RegistryPackage, check_publish_version, compare_versions,
+};
+use mockito::{Matcher, mock};
+use predicates::prelude::*;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A project directory with the package `name` at `version` installed.
+fn project_with_package(name: &str, version: &str) -> PathBuf {
+    let project =
+        std::env::temp_dir().join(format!("iqra_pkg_publish_{}_{}", name, std::process::id()));
+    let package = project.join("iqra_packages").join(name);
+    let _ = fs::remove_dir_all(&project);
+    fs::create_dir_all(package.join("lib")).unwrap();
+    let manifest = format!(
+        r#"{{"version": "{}", "author": "Amjad", "description": "حساب سريع", "entry": "main.iqra"}}"#,
+        version
+    );
+    fs::write(package.join("iqra-package.json"), manifest).unwrap();
+    fs::write(package.join("main.iqra"), "اطبع(١)\n").unwrap();
+    fs::write(package.join("lib/math.iqra"), "دالة ضعف(س) { ارجع س * ٢ }\n").unwrap();
+    project
+}
+
+fn publish(project: &Path, args: &[&str]) -> Command {
+    let mut command = Command::cargo_bin("iqra-pkg").unwrap();
+    command
+        .current_dir(project)
+        .env("MOCKITO_SERVER_URL", mockito::server_url())
+        .arg("publish")
+        .args(args);
+    command
+}
+
+#[test]
+fn test_refuses_an_existing_version() {
+    let project = project_with_package("hisab", "1.2.0");
+    let _info = mock("GET", "/packages/hisab")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"versions": ["1.1.0", "1.2.0"], "latest": "1.2.0"}"#)
+        .create();
+    let upload = mock("POST", "/packages")
+        .match_header("content-type", mockito::Matcher::Any)
+        .expect(0)
+        .create();
+    publish(&project, &["hisab"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Version 1.2.0 is already published (latest is 1.2.0)"))
+        .stdout(predicate::str::contains("bump the version in iqra-package.json"));
+    upload.assert();
+    fs::remove_dir_all(project).unwrap();
+}
+
+#[test]
+fn test_new_version_uploads() {
+    let project = project_with_package("jadid", "2.0.0");
+    let _info = mock("GET", "/packages/jadid").with_status(404).create();
+    let upload = mock("POST", "/packages")
+        .match_body(Matcher::Regex("jadid".into()))
+        .with_status(201)
+        .create();
+    publish(&project, &["jadid"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Published successfully"));
+    upload.assert();
+    fs::remove_dir_all(project).unwrap();
+}
+
+#[test]
+fn test_failed_upload_is_an_error() {
+    let project = project_with_package("fashil", "1.0.0");
+    let _info = mock("GET", "/packages/fashil").with_status(404).create();
+    let upload = mock("POST", "/packages")
+        .match_body(Matcher::Regex("fashil".into()))
+        .with_status(500)
+        .create();
+    publish(&project, &["fashil"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Published successfully").not())
+        .stderr(predicate::str::contains("Publish failed: 500"));
+    upload.assert();
+    fs::remove_dir_all(project).unwrap();
+}
+
+#[test]
+fn test_force_replaces_an_existing_version() {
+    let project = project_with_package("quwwa", "1.0.0");
+    let _info = mock("GET", "/packages/quwwa")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"versions": ["1.0.0"]}"#)
+        .create();
+    let upload = mock("POST", "/packages")
+        .match_body(Matcher::Regex("quwwa".into()))
+        .with_status(201)
+        .create();
+    publish(&project, &["quwwa", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("version 1.0.0 will be replaced"));
+    upload.assert();
+    fs::remove_dir_all(project).unwrap();
+}
+
+#[test]
+fn test_dry_run_prints_files_and_metadata_without_uploading() {
+    let project = project_with_package("tajriba", "0.1.0");
+    let info = mock("GET", "/packages/tajriba").expect(0).create();
+    publish(&project, &["--dry-run", "tajriba"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("  iqra-package.json\n  lib/math.iqra\n  main.iqra\n"))
+        .stdout(predicate::str::contains("\"version\": \"0.1.0\""))
+        .stdout(predicate::str::contains("\"description\": \"حساب سريع\""))
+        .stdout(predicate::str::contains("\"checksum\": \"sha256:"))
+        .stdout(predicate::str::contains("Nothing was uploaded"));
+    info.assert();
+    fs::remove_dir_all(project).unwrap();
+}
+
+#[test]
+fn test_version_checks() {
+    let registry = RegistryPackage {
+        versions: vec!["1.0.0".into(), "1.10.0".into(), "1.9.3".into()],
+        latest: None,
+        ..RegistryPackage::default()
+    };
+    assert_eq!(compare_versions("1.10.0", "1.9.3"), Some(Ordering::Greater));
+    assert_eq!(compare_versions("1.0", "1.0.0"), Some(Ordering::Equal));
+    assert_eq!(compare_versions("1.x", "1.0.0"), None);
+    assert!(
+        check_publish_version("1.10.0", &registry, false)
+            .unwrap_err()
+            .to_string()
+            .contains("latest is 1.10.0")
+    );
+    assert!(
+        check_publish_version("1.10.0", &registry, true)
+            .unwrap()
+            .unwrap()
+            .contains("will be replaced")
+    );
+    assert!(
+        check_publish_version("1.5.0", &registry, false)
+            .unwrap()
+            .unwrap()
+            .contains("older than the latest")
+    );
+    assert_eq!(check_publish_version("1.11.0", &registry, false).unwrap(), None);
+    assert_eq!(check_publish_version("0.1.0", &RegistryPackage::default(), false).unwrap(), None);
+}
+
+#[test]
+fn test_metadata_needs_a_version() {
+    let err = PackageManifest::default().metadata("بلا_إصدار").unwrap_err();
+    assert!(err.to_string().contains("Add a \"version\""), "{}", err);
+    let manifest = PackageManifest { version: Some("1.0.0".into()), ..PackageManifest::default() };
+    let metadata = manifest.metadata("حزمة").unwrap();
+    assert_eq!((metadata.name.as_str(), metadata.lang.as_str()), ("حزمة", "ar"));
+}
+
+#[test]
+fn test_archive_is_a_valid_tar() {
+    let project = project_with_package("arshif", "1.0.0");
+    let archive = PackageArchive::build(&project.join("iqra_packages/arshif")).unwrap();
+    assert_eq!(archive.files, ["iqra-package.json", "lib/math.iqra", "main.iqra"]);
+    // Three headers, three one-block files and two closing blocks.
+    assert_eq!(archive.bytes.len(), 8 * 512);
+    let header = &archive.bytes[512 * 2..512 * 3];
+    assert!(header.starts_with(b"lib/math.iqra\0"));
+    assert_eq!(&header[257..263], b"ustar\0");
+    let stored: u32 =
+        u32::from_str_radix(std::str::from_utf8(&header[148..154]).unwrap(), 8).unwrap();
+    let computed: u32 = header
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| if (148..156).contains(&i) { 32 } else { u32::from(b) })
+        .sum();
+    assert_eq!(stored, computed);
+    fs::remove_dir_all(project).unwrap();
+}
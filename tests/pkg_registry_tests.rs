@@ -15,7 +15,7 @@ fn test_registry_search() {
 
     let output = Command::new("cargo")
         .args(["run", "--bin", "iqra-pkg", "search", "قائمة"])
-        .env("MOCKITO_SERVER_URL", &mockito::server_url())
+        .env("MOCKITO_SERVER_URL", mockito::server_url())
         .output()
         .expect("failed to run iqra-pkg");
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -27,6 +27,31 @@ fn test_registry_search() {
     assert!(stdout.contains("دالة لإنشاء قائمة"), "stdout: {}\nstderr: {}", stdout, stderr);
 }
 
+#[test]
+fn test_registry_search_sends_paging_and_renders_a_table() {
+    let _m = mock("GET", "/packages")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("q".into(), "ويب".into()),
+            Matcher::UrlEncoded("limit".into(), "5".into()),
+            Matcher::UrlEncoded("page".into(), "2".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"name":"ويب_سريع","description":"خادم ويب","version":"2.0.1","downloads":42}]"#)
+        .create();
+
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "iqra-pkg", "search", "ويب", "--limit", "5", "--page", "2"])
+        .env("MOCKITO_SERVER_URL", mockito::server_url())
+        .output()
+        .expect("failed to run iqra-pkg");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("الإصدار | Version"), "stdout: {}\nstderr: {}", stdout, stderr);
+    assert!(stdout.contains("2.0.1"), "stdout: {}\nstderr: {}", stdout, stderr);
+    assert!(stdout.contains("42"), "stdout: {}\nstderr: {}", stdout, stderr);
+}
+
 #[test]
 fn test_registry_install() {
     let m1 = mock("GET", "/packages/قائمة/latest/download")
@@ -48,7 +73,7 @@ fn test_registry_install() {
     println!("Expected download URL: {}", download_url);
     let output = Command::new("cargo")
         .args(["run", "--bin", "iqra-pkg", "install", "قائمة"])
-        .env("MOCKITO_SERVER_URL", &mockito::server_url())
+        .env("MOCKITO_SERVER_URL", mockito::server_url())
         .output()
         .expect("failed to run iqra-pkg");
     println!("Raw mock matched: {}", m1.matched());
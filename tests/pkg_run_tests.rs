@@ -0,0 +1,94 @@
+use assert_cmd::Command;
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+use predicates::str::contains;
+use std::fs;
+use std::path::PathBuf;
+
+/// A project directory with the package `تحية` installed, unique per test.
+fn project_with_package(test: &str, manifest: Option<&str>) -> PathBuf {
+    let project =
+        std::env::temp_dir().join(format!("iqra_pkg_run_{}_{}", test, std::process::id()));
+    let package = project.join("iqra_packages").join("تحية");
+    let _ = fs::remove_dir_all(&project);
+    fs::create_dir_all(package.join("scripts")).unwrap();
+    if let Some(manifest) = manifest {
+        fs::write(package.join("iqra-package.json"), manifest).unwrap();
+    }
+    fs::write(package.join("main.iqra"), "اطبع(\"مرحبا \" + وسائط()[٠])\nاطبع(طول(وسائط()))\n")
+        .unwrap();
+    fs::write(package.join("scripts/bye.iqra"), "اطبع(\"مع السلامة\")\n").unwrap();
+    project
+}
+
+fn iqra_pkg(project: &PathBuf) -> Command {
+    let mut command = Command::cargo_bin("iqra-pkg").unwrap();
+    command.current_dir(project);
+    command
+}
+
+#[test]
+fn test_runs_the_default_entry_with_arguments() {
+    let project = project_with_package("default", None);
+    iqra_pkg(&project)
+        .args(["run", "تحية", "سارة", "--verbose"])
+        .assert()
+        .success()
+        .stdout("مرحبا سارة\n2\n");
+    fs::remove_dir_all(project).unwrap();
+}
+
+#[test]
+fn test_runs_a_named_script() {
+    let project =
+        project_with_package("script", Some(r#"{"scripts": {"وداع": "scripts/bye.iqra"}}"#));
+    iqra_pkg(&project)
+        .args(["run", "تحية", "--script", "وداع"])
+        .assert()
+        .success()
+        .stdout("مع السلامة\n");
+    fs::remove_dir_all(project).unwrap();
+}
+
+#[test]
+fn test_missing_entry_lists_the_manifest() {
+    let manifest = r#"{"entry": "bin/start.iqra", "scripts": {"وداع": "scripts/bye.iqra"}}"#;
+    let project = project_with_package("missing", Some(manifest));
+    iqra_pkg(&project)
+        .args(["run", "تحية"])
+        .assert()
+        .failure()
+        .stdout(contains("Entry file not found"))
+        .stdout(contains("entry: bin/start.iqra"))
+        .stdout(contains("scripts.وداع: scripts/bye.iqra"));
+    iqra_pkg(&project)
+        .args(["run", "تحية", "--script", "بناء"])
+        .assert()
+        .failure()
+        .stdout(contains("No script named 'بناء'"));
+    iqra_pkg(&project)
+        .args(["run", "غير_مثبتة"])
+        .assert()
+        .failure()
+        .stdout(contains("Package not installed"));
+    fs::remove_dir_all(project).unwrap();
+}
+
+#[test]
+fn test_script_errors_set_the_exit_code() {
+    let project = project_with_package("error", None);
+    // main.iqra indexes the first argument, so running it without one fails.
+    iqra_pkg(&project).args(["run", "تحية"]).assert().code(1);
+    fs::remove_dir_all(project).unwrap();
+}
+
+#[test]
+fn test_args_is_empty_by_default() {
+    assert_eq!(Runtime::new().execute("وسائط()").unwrap(), Value::List(Vec::new()));
+    let mut runtime = Runtime::new();
+    runtime.set_script_args(vec!["أ".to_string()]);
+    assert_eq!(
+        runtime.execute("args()").unwrap(),
+        Value::List(vec![Value::String("أ".to_string())])
+    );
+}
@@ -0,0 +1,89 @@
+use iqra::pkg::{
+    DEFAULT_SEARCH_LIMIT, SearchOptions, SearchResult, parse_search_results, render_search_results,
+};
+
+const FULL: &str = r#"[
+    {"name": "قائمة", "description": "أدوات القوائم", "version": "1.2.0", "author": "Amjad", "downloads": 1520},
+    {"name": "قائمة_مرتبة", "description": "قائمة تبقى مرتبة", "version": "0.3.1", "author": "Sara", "downloads": 87, "license": "MIT"}
+]"#;
+
+const SPARSE: &str = r#"[{"name": "شبكة"}, {"name": "شبكات", "description": "طلبات HTTP"}]"#;
+
+fn options(args: &[&str]) -> SearchOptions {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    SearchOptions::from_args(&args).unwrap()
+}
+
+#[test]
+fn test_parses_full_and_sparse_responses() {
+    let results = parse_search_results(FULL).unwrap();
+    assert_eq!(results[0].version.as_deref(), Some("1.2.0"));
+    assert_eq!(results[1].downloads, Some(87));
+    let results = parse_search_results(SPARSE).unwrap();
+    assert_eq!(
+        results[0],
+        SearchResult {
+            name: "شبكة".to_string(),
+            description: String::new(),
+            version: None,
+            author: None,
+            downloads: None,
+        }
+    );
+    let err = parse_search_results(r#"{"error": "down"}"#).unwrap_err();
+    assert!(err.to_string().contains("Invalid registry response"), "{}", err);
+}
+
+#[test]
+fn test_flags_and_query_pairs() {
+    let parsed = options(&["قائمة", "--limit", "5", "--page", "2", "--exact"]);
+    assert_eq!((parsed.limit, parsed.page, parsed.exact), (5, 2, true));
+    let pairs = parsed.query_pairs();
+    assert_eq!(pairs[1], ("limit", "5".to_string()));
+    assert_eq!(pairs[2], ("page", "2".to_string()));
+    let defaults = options(&["قائمة"]);
+    assert_eq!((defaults.limit, defaults.page, defaults.exact), (DEFAULT_SEARCH_LIMIT, 1, false));
+    for bad in [&["--limit", "0", "س"][..], &["--page"], &[], &["أ", "ب"], &["أ", "--fast"]] {
+        let args: Vec<String> = bad.iter().map(|arg| arg.to_string()).collect();
+        assert!(SearchOptions::from_args(&args).is_err(), "{:?}", bad);
+    }
+}
+
+#[test]
+fn test_table_aligns_columns_and_fills_gaps() {
+    let output =
+        render_search_results(&parse_search_results(FULL).unwrap(), &options(&["قائمة"]), false);
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines[0].starts_with("الاسم | Name"), "{}", output);
+    assert!(lines[2].starts_with("قائمة        1.2.0"), "{}", output);
+    assert!(lines[2].contains("1520"), "{}", output);
+    assert!(lines[3].ends_with("قائمة تبقى مرتبة"), "{}", output);
+    assert_eq!(lines.len(), 4);
+
+    let output =
+        render_search_results(&parse_search_results(SPARSE).unwrap(), &options(&["شبك"]), false);
+    assert!(output.lines().nth(2).unwrap().starts_with("شبكة         -"), "{}", output);
+}
+
+#[test]
+fn test_highlights_the_query_in_color() {
+    let results = parse_search_results(FULL).unwrap();
+    let output = render_search_results(&results, &options(&["مرتبة"]), true);
+    assert!(output.contains("قائمة_\u{1b}[33;1mمرتبة\u{1b}[0m"), "{:?}", output);
+    assert!(!render_search_results(&results, &options(&["مرتبة"]), false).contains('\u{1b}'));
+}
+
+#[test]
+fn test_no_results_and_did_you_mean() {
+    let output = render_search_results(&[], &options(&["قائمة"]), false);
+    assert_eq!(output, "لا توجد نتائج | No results found\n");
+
+    let results = parse_search_results(SPARSE).unwrap();
+    let output = render_search_results(&results, &options(&["شبكه", "--exact"]), false);
+    assert!(output.starts_with("لا توجد نتائج | No results found\n"), "{}", output);
+    assert!(output.contains("هل تقصد 'شبكة'؟ | Did you mean 'شبكة'?"), "{}", output);
+
+    let output = render_search_results(&results, &options(&["شبكة", "--exact"]), false);
+    assert_eq!(output.lines().count(), 3);
+    assert!(!output.contains("Did you mean"));
+}
@@ -0,0 +1,90 @@
+use iqra::pkg::{CHECKSUM_HEADER, HttpRegistry, RegistryClient};
+use mockito::{Matcher, mock};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+
+fn project(test: &str) -> PathBuf {
+    let project =
+        std::env::temp_dir().join(format!("iqra_pkg_stream_{}_{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&project);
+    fs::create_dir_all(&project).unwrap();
+    project
+}
+
+fn sha256(body: &str) -> String {
+    format!("{:x}", Sha256::digest(body.as_bytes()))
+}
+
+/// Every file left in `dir`, partial downloads included.
+fn files(dir: &PathBuf) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_short_body_fails_and_leaves_no_partial_file() {
+    let dir = project("short");
+    let _m = mock("GET", "/packages/stream_short/latest/download")
+        .with_status(200)
+        .with_header("content-length", "100")
+        .with_body("اطبع(\"نصف\")\n")
+        .create();
+    let registry = HttpRegistry::new(&mockito::server_url());
+    let err = registry.install("stream_short", &dir).unwrap_err();
+    assert!(err.to_string().contains("Incomplete download: got 19 of 100 bytes"), "{}", err);
+    assert!(files(&dir).is_empty(), "{:?}", files(&dir));
+}
+
+#[test]
+fn test_checksum_is_verified_before_installing() {
+    let dir = project("checksum");
+    let body = "اطبع(\"سليم\")\n";
+    let _good = mock("GET", "/packages/stream_good/latest/download")
+        .with_status(200)
+        .with_header(CHECKSUM_HEADER, &sha256(body))
+        .with_body(body)
+        .create();
+    let _bad = mock("GET", "/packages/stream_bad/latest/download")
+        .with_status(200)
+        .with_header(CHECKSUM_HEADER, &sha256("شيء آخر"))
+        .with_body(body)
+        .create();
+    let registry = HttpRegistry::new(&mockito::server_url());
+    registry.install("stream_good", &dir).unwrap();
+    assert_eq!(fs::read_to_string(dir.join("stream_good")).unwrap(), body);
+    let err = registry.install("stream_bad", &dir).unwrap_err();
+    assert!(err.to_string().contains("SHA-256 mismatch"), "{}", err);
+    assert_eq!(files(&dir), ["stream_good"]);
+}
+
+#[test]
+fn test_cut_download_resumes_with_a_range_request() {
+    let dir = project("resume");
+    let body = "اطبع(\"كامل\")\n";
+    let cut = 10;
+    let _first = mock("GET", "/packages/stream_resume/latest/download")
+        .match_header("range", Matcher::Missing)
+        .with_status(200)
+        .with_header("accept-ranges", "bytes")
+        .with_header("content-length", &body.len().to_string())
+        .with_header(CHECKSUM_HEADER, &sha256(body))
+        .with_body(&body.as_bytes()[..cut])
+        .create();
+    let rest = mock("GET", "/packages/stream_resume/latest/download")
+        .match_header("range", format!("bytes={}-", cut).as_str())
+        .with_status(206)
+        .with_body(&body.as_bytes()[cut..])
+        .expect(1)
+        .create();
+    let registry = HttpRegistry::new(&mockito::server_url());
+    registry.install("stream_resume", &dir).unwrap();
+    rest.assert();
+    assert_eq!(fs::read_to_string(dir.join("stream_resume")).unwrap(), body);
+    assert_eq!(files(&dir), ["stream_resume"]);
+}
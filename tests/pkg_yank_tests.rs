@@ -0,0 +1,120 @@
+use assert_cmd::Command;
+use iqra::pkg::{YankOptions, confirmed};
+use mockito::{Matcher, Mock, mock};
+use predicates::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+
+fn project(name: &str) -> PathBuf {
+    let project =
+        std::env::temp_dir().join(format!("iqra_pkg_yank_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&project);
+    fs::create_dir_all(&project).unwrap();
+    project
+}
+
+fn iqra_pkg(project: &PathBuf, args: &[&str]) -> Command {
+    let mut command = Command::cargo_bin("iqra-pkg").unwrap();
+    command
+        .current_dir(project)
+        .env("MOCKITO_SERVER_URL", mockito::server_url())
+        .env_remove("IQRA_REGISTRY_TOKEN")
+        .args(args);
+    command
+}
+
+/// Mocks that fail the test if the registry hears anything at all.
+fn no_requests() -> Vec<Mock> {
+    ["GET", "POST", "PUT", "DELETE"]
+        .into_iter()
+        .map(|method| mock(method, Matcher::Any).expect(0).create())
+        .collect()
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn test_yank_options() {
+    let options = YankOptions::from_args(&args(&["hisab", "--version", "1.2.0", "--yes"])).unwrap();
+    assert_eq!(
+        options,
+        YankOptions { package: "hisab".to_string(), version: "1.2.0".to_string(), yes: true }
+    );
+    let err = YankOptions::from_args(&args(&["hisab"])).unwrap_err();
+    assert!(err.contains("Name the version to yank: yank hisab --version X.Y.Z"), "{}", err);
+    let err = YankOptions::from_args(&args(&["hisab", "--version", "latest"])).unwrap_err();
+    assert!(err.contains("--version needs a version such as 1.2.0"), "{}", err);
+    assert!(YankOptions::from_args(&args(&["--version", "1.0.0"])).is_err());
+    assert!(confirmed("نعم\n") && confirmed(" Y ") && !confirmed("") && !confirmed("no"));
+}
+
+#[test]
+fn test_remove_is_local_only() {
+    let project = project("remove");
+    let package = project.join("iqra_packages/hisab");
+    fs::create_dir_all(&package).unwrap();
+    fs::write(package.join("main.iqra"), "اطبع(١)\n").unwrap();
+    fs::write(project.join("iqra_packages/salam"), "اطبع(٢)\n").unwrap();
+    fs::write(project.join("iqra-pkg.json"), r#"{"hisab": "latest", "salam": "latest"}"#).unwrap();
+    let registry = no_requests();
+
+    iqra_pkg(&project, &["remove", "hisab"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed locally"))
+        .stdout(predicate::str::contains("registry").not());
+    assert!(!package.exists());
+    assert!(project.join("iqra_packages/salam").exists());
+    let manifest = fs::read_to_string(project.join("iqra-pkg.json")).unwrap();
+    assert!(!manifest.contains("hisab") && manifest.contains("salam"), "{}", manifest);
+
+    for mock in &registry {
+        mock.assert();
+    }
+    fs::remove_dir_all(project).unwrap();
+}
+
+#[test]
+fn test_yank_sends_token_and_exact_version() {
+    let project = project("yank");
+    let yank = mock("DELETE", "/packages/hisab/1.2.0")
+        .match_header("authorization", "Bearer secret-123")
+        .with_status(200)
+        .expect(1)
+        .create();
+    iqra_pkg(&project, &["yank", "hisab", "--version", "1.2.0", "--yes"])
+        .env("IQRA_REGISTRY_TOKEN", "secret-123")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Yanked 'hisab' 1.2.0 from the registry"));
+    yank.assert();
+    fs::remove_dir_all(project).unwrap();
+}
+
+#[test]
+fn test_yank_asks_first_and_needs_a_token() {
+    let project = project("yank_refused");
+    let registry = no_requests();
+    iqra_pkg(&project, &["yank", "hisab", "--version", "1.2.0"])
+        .env("IQRA_REGISTRY_TOKEN", "secret-123")
+        .write_stdin("n\n")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Continue? [y/N]"))
+        .stdout(predicate::str::contains("Yank cancelled"));
+    iqra_pkg(&project, &["yank", "hisab", "--version", "1.2.0", "--yes"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Yanking needs a registry token in IQRA_REGISTRY_TOKEN"));
+    iqra_pkg(&project, &["yank", "hisab", "--yes"])
+        .env("IQRA_REGISTRY_TOKEN", "secret-123")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("--version X.Y.Z"));
+    for mock in &registry {
+        mock.assert();
+    }
+    fs::remove_dir_all(project).unwrap();
+}
@@ -0,0 +1,37 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(source: &str) -> Value {
+    Runtime::new().execute(source).unwrap()
+}
+
+#[test]
+fn test_nested_index() {
+    assert_eq!(eval("أ = [[١، ٢]، [٣، ٤]]\nأ[١][٠]"), Value::Int(3));
+    assert_eq!(eval("a = [[1, 2], [3, 4]]\na[0][1]"), Value::Int(2));
+}
+
+#[test]
+fn test_index_on_call_result() {
+    assert_eq!(eval("دالة ق() { ارجع [٥، ٦، ٧] }\nق()[٢]"), Value::Int(7));
+    assert_eq!(eval("list(1, 2, 3)[0]"), Value::Int(1));
+    assert_eq!(eval("جلب_عنصر(قاموس(\"أ\"، [١، ٢])، \"أ\")[١]"), Value::Int(2));
+}
+
+#[test]
+fn test_index_on_parenthesized_and_literal_lists() {
+    assert_eq!(eval("(قائمة(١، ٢))[١]"), Value::Int(2));
+    assert_eq!(eval("[1, 2, 3][1]"), Value::Int(2));
+}
+
+#[test]
+fn test_index_binds_tighter_than_operators() {
+    assert_eq!(eval("أ = [١، ٢، ٣]\nأ[٠] + أ[١] * أ[٢]"), Value::Int(7));
+    assert_eq!(eval("a = [1, 2]\n-a[1]"), Value::Int(-2));
+}
+
+#[test]
+fn test_calling_an_expression_result_is_a_parse_error() {
+    let err = Runtime::new().execute("جلب_عنصر(قاموس()، \"أ\")(١)").unwrap_err();
+    assert!(format!("{}", err).contains("only named functions can be called"));
+}
@@ -0,0 +1,68 @@
+use assert_cmd::Command;
+use iqra::cli::load_into_runtime;
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+use predicates::prelude::*;
+use predicates::str::contains;
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_script(name: &str, source: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("iqra_preload_{}_{}.iqra", name, std::process::id()));
+    fs::write(&path, source).unwrap();
+    path
+}
+
+#[test]
+fn test_loaded_function_is_callable_later() {
+    let path = temp_script("helpers", "دالة ضعف(س) {\n    ارجع س * ٢\n}\nحد = ١٠\n");
+    let mut runtime = Runtime::new();
+    load_into_runtime(&path.to_string_lossy(), &mut runtime).unwrap();
+    assert_eq!(runtime.execute("ضعف(حد)").unwrap(), Value::Int(20));
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_load_errors_are_returned() {
+    let mut runtime = Runtime::new();
+    assert!(load_into_runtime("/no/such/file.iqra", &mut runtime).is_err());
+
+    let path = temp_script("broken", "س = ١\nص = غير_معرف\n");
+    let err = load_into_runtime(&path.to_string_lossy(), &mut runtime).unwrap_err().to_string();
+    assert!(err.contains("Line: 2"), "{}", err);
+    // Statements before the failing one still ran.
+    assert_eq!(runtime.get_variables().get("س"), Some(&Value::Int(1)));
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_repl_preloads_init_file_and_flags_in_order() {
+    let home = std::env::temp_dir().join(format!("iqra_home_{}", std::process::id()));
+    fs::create_dir_all(home.join(".iqra")).unwrap();
+    fs::write(home.join(".iqra").join("init.iqra"), "أساس = ١\n").unwrap();
+    let extra = temp_script("extra", "أساس = أساس + ١\n");
+    let broken = temp_script("bad", "س = (\n");
+
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .env("HOME", &home)
+        .env("IQRA_LANG", "en")
+        .args([
+            "repl",
+            "--preload",
+            &extra.to_string_lossy(),
+            "--preload",
+            &broken.to_string_lossy(),
+        ])
+        .write_stdin("أساس\n")
+        .assert()
+        .success()
+        .stdout(contains("Loaded: ").and(contains("init.iqra")))
+        .stdout(contains("2\n"))
+        .stderr(contains(broken.to_string_lossy().as_ref()));
+
+    fs::remove_dir_all(home).unwrap();
+    fs::remove_file(extra).unwrap();
+    fs::remove_file(broken).unwrap();
+}
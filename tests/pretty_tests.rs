@@ -0,0 +1,94 @@
+use iqra::cli::set_display_limit;
+use iqra::lang::value::{PrettyLimits, Value};
+use std::collections::HashMap;
+
+fn list(items: Vec<Value>) -> Value {
+    Value::List(items)
+}
+
+#[test]
+fn test_scalars_and_short_collections_stay_inline() {
+    assert_eq!(Value::Int(5).pretty(0), "5");
+    assert_eq!(Value::String("نص".to_string()).pretty(0), "نص");
+    assert_eq!(list(vec![]).pretty(0), "[]");
+    assert_eq!(list(vec![Value::Int(1)]).pretty(0), "[1]");
+    assert_eq!(Value::Map(HashMap::new()).pretty(0), "{}");
+}
+
+#[test]
+fn test_multi_element_list_spans_lines() {
+    let value =
+        list(vec![Value::Int(1), list(vec![Value::Int(2), Value::Int(3)]), Value::Bool(true)]);
+    assert_eq!(value.pretty(0), "[\n  1,\n  [\n    2,\n    3\n  ],\n  صحيح\n]");
+}
+
+#[test]
+fn test_map_keys_are_sorted() {
+    let mut map = HashMap::new();
+    map.insert("ب".to_string(), Value::Int(2));
+    map.insert("أ".to_string(), list(vec![Value::Int(1)]));
+    assert_eq!(Value::Map(map).pretty(0), "{\n  أ: [1],\n  ب: 2\n}");
+}
+
+#[test]
+fn test_indent_offsets_nested_output() {
+    let value = list(vec![Value::Int(1), Value::Int(2)]);
+    assert_eq!(value.pretty(1), "[\n    1,\n    2\n  ]");
+}
+
+#[test]
+fn test_long_lists_are_truncated() {
+    let value = list((1..=10).map(Value::Int).collect());
+    let limits = PrettyLimits { max_items: 3, ..PrettyLimits::default() };
+    assert_eq!(value.pretty_with(0, &limits), "[\n  1,\n  2,\n  3,\n  … و ٧ أخرى | … and 7 more\n]");
+}
+
+#[test]
+fn test_deep_nesting_is_cut_off() {
+    let mut value = list(vec![Value::Int(0), Value::Int(0)]);
+    for _ in 0..3 {
+        value = list(vec![value]);
+    }
+    let limits = PrettyLimits { max_depth: 2, ..PrettyLimits::default() };
+    assert_eq!(value.pretty_with(0, &limits), "[[[…]]]");
+    assert_eq!(list(vec![]).pretty_with(0, &PrettyLimits { max_depth: 0, max_items: 1, max_chars: 1 }), "[]");
+}
+
+#[test]
+fn test_default_limit_shows_fifty_items() {
+    let value = list((0..100_000).map(Value::Int).collect());
+    let shown = value.pretty(0);
+    assert_eq!(shown.lines().count(), 53);
+    assert!(shown.starts_with("[\n  0,\n  1,\n"));
+    assert!(shown.ends_with("  49,\n  … و ٩٩٩٥٠ أخرى | … and 99950 more\n]"));
+}
+
+#[test]
+fn test_long_strings_are_truncated_with_their_length() {
+    let limits = PrettyLimits { max_chars: 4, ..PrettyLimits::default() };
+    let text = Value::String("مرحباً بالعالم".to_string());
+    assert_eq!(text.pretty_with(0, &limits), "مرحب… (الطول ١٤ | length 14)");
+    assert_eq!(Value::String("اقرأ".to_string()).pretty_with(0, &limits), "اقرأ");
+    let nested = list(vec![Value::String("abcdefg".to_string()), Value::Int(1)]);
+    assert_eq!(nested.pretty_with(0, &limits), "[\n  abcd… (الطول ٧ | length 7),\n  1\n]");
+}
+
+#[test]
+fn test_unlimited_shows_everything() {
+    let value = list((0..200).map(Value::Int).collect());
+    assert_eq!(value.pretty_with(0, &PrettyLimits::UNLIMITED).lines().count(), 202);
+    let text = "ا".repeat(5000);
+    assert_eq!(Value::String(text.clone()).pretty_with(0, &PrettyLimits::UNLIMITED), text);
+}
+
+#[test]
+fn test_set_display_limit() {
+    let mut limits = PrettyLimits::default();
+    set_display_limit(&mut limits, "max-items 200").unwrap();
+    set_display_limit(&mut limits, "أقصى_حروف ٨٠").unwrap();
+    assert_eq!(limits, PrettyLimits { max_depth: 6, max_items: 200, max_chars: 80 });
+    for bad in ["max-items", "max-items lots", "max-items 1 2", "colour 3"] {
+        assert!(set_display_limit(&mut limits, bad).is_err(), "{}", bad);
+    }
+    assert_eq!(limits.max_items, 200);
+}
@@ -0,0 +1,46 @@
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::runtime::Runtime;
+
+fn printed(code: &str, sink: CapturedOutput) -> String {
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(sink.clone()));
+    runtime.execute(code).unwrap();
+    sink.contents()
+}
+
+#[test]
+fn test_print_goes_through_output_sink() {
+    assert_eq!(printed(r#"اطبع("أ", ١, صحيح)"#, CapturedOutput::new()), "أ 1 صحيح\n");
+    assert_eq!(printed("print()", CapturedOutput::new()), "\n");
+}
+
+#[test]
+fn test_print_color_on_terminal() {
+    assert_eq!(printed(r#"اطبع_ملون("تم", "أخضر")"#, CapturedOutput::tty()), "\x1b[32mتم\x1b[0m\n");
+    assert_eq!(
+        printed(r#"print_color("error", "red", "bold")"#, CapturedOutput::tty()),
+        "\x1b[31;1merror\x1b[0m\n"
+    );
+    assert_eq!(
+        printed(r#"print_color("note", "أزرق", "تحته_خط")"#, CapturedOutput::tty()),
+        "\x1b[34;4mnote\x1b[0m\n"
+    );
+}
+
+#[test]
+fn test_print_color_plain_when_not_a_terminal() {
+    assert_eq!(printed(r#"اطبع_ملون("تم", "أخضر", "عريض")"#, CapturedOutput::new()), "تم\n");
+}
+
+#[test]
+fn test_unknown_color_lists_options() {
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(CapturedOutput::tty()));
+    let err = runtime.execute(r#"print_color("x", "purple")"#).unwrap_err().to_string();
+    assert!(err.contains("Unknown color 'purple'"), "{}", err);
+    assert!(err.contains("أحمر/red"), "{}", err);
+
+    let err = runtime.execute(r#"print_color("x", "red", "blink")"#).unwrap_err().to_string();
+    assert!(err.contains("Unknown style 'blink'"), "{}", err);
+    assert!(err.contains("عريض/bold"), "{}", err);
+}
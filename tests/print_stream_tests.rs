@@ -0,0 +1,60 @@
+use assert_cmd::Command;
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::runtime::{IqraError, Runtime};
+
+/// Runs `code` and returns what it wrote to standard output and to
+/// standard error.
+fn streams(code: &str) -> (String, String) {
+    let sink = CapturedOutput::new();
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(sink.clone()));
+    runtime.execute(code).unwrap();
+    (sink.contents(), sink.errors())
+}
+
+#[test]
+fn test_print_inline_leaves_out_the_newline() {
+    assert_eq!(streams("اطبع_بدون_سطر(\"أ\"، ١)\nاطبع_بدون_سطر(\"ب\")").0, "أ 1ب");
+    assert_eq!(streams("print_inline()").0, "");
+    assert_eq!(streams("print_inline(\"...\")\nprint(\"تم\")").0, "...تم\n");
+}
+
+#[test]
+fn test_print_sep_joins_with_the_separator() {
+    assert_eq!(streams("اطبع_مفصول(\"، \"، ١، \"ب\"، صحيح)").0, "1، ب، صحيح\n");
+    assert_eq!(streams("print_sep(\"\", 1, 2, 3)").0, "123\n");
+    assert_eq!(streams("print_sep(\"-\")").0, "\n");
+
+    let mut runtime = Runtime::new();
+    let err = runtime.execute("print_sep(1, 2)").unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.message_en, "The separator for print_sep must be a string, not number");
+    let err = runtime.execute("اطبع_مفصول()").unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.kind, "عدد وسائط غير صحيح");
+}
+
+#[test]
+fn test_print_error_writes_to_the_error_channel() {
+    let (stdout, stderr) = streams("اطبع(١)\nاطبع_خطأ(\"فشل\"، ٢)\nprint_error()\nاطبع(٣)");
+    assert_eq!(stdout, "1\n3\n");
+    assert_eq!(stderr, "فشل 2\n\n");
+}
+
+#[test]
+fn test_parallel_workers_keep_both_channels() {
+    let (stdout, stderr) = streams(
+        "دالة سجل(ن) {\n    اطبع(ن)\n    اطبع_خطأ(ن * ١٠)\n    ارجع ن\n}\nخريطة_متوازية([١، ٢]، \"سجل\")",
+    );
+    assert_eq!(stdout, "1\n2\n");
+    assert_eq!(stderr, "10\n20\n");
+}
+
+#[test]
+fn test_command_line_streams() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["code", "print_inline(\"a\")\nprint_error(\"b\")\nprint_sep(\"|\", 1, 2)"])
+        .assert()
+        .success()
+        .stdout("a1|2\n")
+        .stderr("b\n");
+}
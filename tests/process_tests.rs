@@ -0,0 +1,158 @@
+use assert_cmd::Command;
+use iqra::lang::runtime::{ExitSignal, Runtime, SystemExecutor};
+use iqra::lang::value::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct HostState {
+    env: HashMap<String, String>,
+    dir: String,
+}
+
+struct MockHost(Arc<Mutex<HostState>>);
+
+impl SystemExecutor for MockHost {
+    fn exec(&self, _cmd: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn exec_with_io(&self, _cmd: &str, _input: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn read_file(&self, _path: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn write_file(&self, _path: &str, _content: &str) -> std::io::Result<bool> {
+        Ok(true)
+    }
+
+    fn list_files(&self, _path: &str) -> std::io::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    fn get_env_var(&self, name: &str) -> Option<String> {
+        self.0.lock().unwrap().env.get(name).cloned()
+    }
+
+    fn set_env_var(&self, name: &str, value: &str) -> std::io::Result<()> {
+        self.0.lock().unwrap().env.insert(name.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn current_dir(&self) -> std::io::Result<String> {
+        Ok(self.0.lock().unwrap().dir.clone())
+    }
+
+    fn change_dir(&self, path: &str) -> std::io::Result<()> {
+        if path == "missing" {
+            return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "not found"));
+        }
+        self.0.lock().unwrap().dir = path.to_string();
+        Ok(())
+    }
+
+    fn process_id(&self) -> u32 {
+        4242
+    }
+
+    fn read_line(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn read_password(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn system_info(&self) -> std::io::Result<HashMap<String, String>> {
+        Ok(HashMap::new())
+    }
+}
+
+fn mock_runtime() -> (Runtime, Arc<Mutex<HostState>>) {
+    let state = Arc::new(Mutex::new(HostState { dir: "/home".to_string(), ..Default::default() }));
+    (Runtime::new_with_executor(Box::new(MockHost(state.clone()))), state)
+}
+
+fn exit_code_of(runtime: &mut Runtime, code: &str) -> Option<i32> {
+    let err = runtime.execute(code).unwrap_err();
+    err.downcast_ref::<ExitSignal>().map(|signal| signal.0)
+}
+
+#[test]
+fn test_env_and_directory_builtins() {
+    let (mut runtime, state) = mock_runtime();
+    runtime.execute(r#"عين_متغير_بيئة("MODE", "test")"#).unwrap();
+    assert_eq!(state.lock().unwrap().env.get("MODE").map(String::as_str), Some("test"));
+    assert_eq!(runtime.execute(r#"env_var("MODE")"#).unwrap(), Value::String("test".to_string()));
+
+    assert_eq!(runtime.execute("مجلد_حالي()").unwrap(), Value::String("/home".to_string()));
+    runtime.execute(r#"change_dir("/tmp")"#).unwrap();
+    assert_eq!(runtime.execute("cwd()").unwrap(), Value::String("/tmp".to_string()));
+    let err = runtime.execute(r#"غير_مجلد("missing")"#).unwrap_err().to_string();
+    assert!(err.contains("Failed to change directory to 'missing'"), "{}", err);
+
+    assert_eq!(runtime.execute("معرف_عملية()").unwrap(), Value::Int(4242));
+}
+
+#[test]
+fn test_exit_unwinds_nested_calls_and_try() {
+    let (mut runtime, _) = mock_runtime();
+    let code = "دالة داخلية() {\n    اخرج(٣)\n}\nدالة خارجية() {\n    try {\n        داخلية()\n    } catch (e) {\n        اطبع(\"لن يصل\")\n    }\n    ارجع ١\n}\nخارجية()\nبعد = ١";
+    assert_eq!(exit_code_of(&mut runtime, code), Some(3));
+    assert_eq!(runtime.get_variables().get("بعد"), None);
+    assert_eq!(runtime.call_depth(), 0);
+}
+
+#[test]
+fn test_runtime_keeps_working_after_exit() {
+    // The REPL reuses its runtime after a line calls exit.
+    let (mut runtime, _) = mock_runtime();
+    runtime.execute("س = ٥").unwrap();
+    assert_eq!(exit_code_of(&mut runtime, "exit()"), Some(0));
+    assert_eq!(runtime.execute("س + ١").unwrap(), Value::Int(6));
+}
+
+#[test]
+fn test_exit_rejects_bad_codes() {
+    let (mut runtime, _) = mock_runtime();
+    let err = runtime.execute(r#"exit("x")"#).unwrap_err().to_string();
+    assert!(err.contains("exit expects a whole number"), "{}", err);
+    let err = runtime.execute("exit(1, 2)").unwrap_err().to_string();
+    assert!(err.contains("expects 0 to 1 argument(s)"), "{}", err);
+}
+
+#[test]
+fn test_sandbox_refuses_host_mutations() {
+    let (mut runtime, state) = mock_runtime();
+    runtime.set_sandboxed(true);
+    for code in [
+        r#"set_env("A", "1")"#,
+        r#"change_dir("/tmp")"#,
+        r#"write_file("a.txt", "x")"#,
+        r#"system("ls")"#,
+    ] {
+        let err = runtime.execute(code).unwrap_err().to_string();
+        assert!(err.contains("is not allowed in sandbox mode"), "{}: {}", code, err);
+    }
+    assert!(state.lock().unwrap().env.is_empty());
+    assert_eq!(runtime.execute("cwd()").unwrap(), Value::String("/home".to_string()));
+}
+
+#[test]
+fn test_run_file_uses_script_exit_code() {
+    let path = std::env::temp_dir().join(format!("iqra_exit_{}.iqra", std::process::id()));
+    fs::write(&path, "اطبع(\"قبل\")\nاخرج(٧)\nاطبع(\"بعد\")\n").unwrap();
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["run", &path.to_string_lossy()])
+        .assert()
+        .code(7)
+        .stdout("قبل\n");
+    fs::remove_file(path).unwrap();
+
+    Command::cargo_bin("iqra").unwrap().args(["code", "-c", "exit(4)"]).assert().code(4);
+}
@@ -0,0 +1,71 @@
+use assert_cmd::Command;
+use iqra::cli::profile::render_profile;
+use iqra::lang::runtime::{ProfileEntry, Runtime};
+use iqra::lang::value::Value;
+use predicates::str::contains;
+use std::collections::HashMap;
+use std::fs;
+use std::time::Duration;
+
+const HOT_LOOP: &str = "دالة مربع(س) {\n    ارجع س * س\n}\nم = ٠\nع = ٠\nبينما ع < ٥٠ {\n    م = م + مربع(ع)\n    ع = ع + ١\n}\n";
+
+fn calls(report: &Value, function: &str) -> Option<Value> {
+    let Value::Map(report) = report else { panic!("expected a map, got {:?}", report) };
+    let Value::Map(stats) = report.get(function)? else { panic!("expected stats map") };
+    stats.get("calls").cloned()
+}
+
+#[test]
+fn test_profile_counts_calls_of_hot_function() {
+    let mut runtime = Runtime::new();
+    runtime.set_profiling(true);
+    runtime.execute(HOT_LOOP).unwrap();
+    assert_eq!(runtime.profile()["مربع"].calls, 50);
+    let report = runtime.execute("ملف_الأداء()").unwrap();
+    assert_eq!(calls(&report, "مربع"), Some(Value::Int(50)));
+}
+
+#[test]
+fn test_builtin_calls_are_counted() {
+    let mut runtime = Runtime::new();
+    runtime.set_profiling(true);
+    runtime.execute("أ = طول(\"abc\")\nب = طول(\"de\")\n").unwrap();
+    assert_eq!(runtime.profile()["طول"].calls, 2);
+}
+
+#[test]
+fn test_nothing_is_recorded_when_profiling_is_off() {
+    let mut runtime = Runtime::new();
+    runtime.execute(HOT_LOOP).unwrap();
+    assert!(runtime.profile().is_empty());
+    assert_eq!(runtime.execute("profile_report()").unwrap(), Value::Map(HashMap::new()));
+}
+
+#[test]
+fn test_render_profile_sorts_by_total_time() {
+    let profile = HashMap::from([
+        ("سريع".to_string(), ProfileEntry { calls: 4, total: Duration::from_millis(2) }),
+        ("بطيء".to_string(), ProfileEntry { calls: 2, total: Duration::from_millis(10) }),
+    ]);
+    let table = render_profile(&profile);
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("function"));
+    assert!(lines[1].starts_with("بطيء"));
+    assert!(lines[1].ends_with("10.000       5.000"));
+    assert!(lines[2].starts_with("سريع"));
+}
+
+#[test]
+fn test_run_with_profile_flag_prints_table_to_stderr() {
+    let path = std::env::temp_dir().join(format!("iqra_profile_{}.iqra", std::process::id()));
+    fs::write(&path, HOT_LOOP).unwrap();
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["run", path.to_str().unwrap(), "--profile"])
+        .assert()
+        .success()
+        .stderr(contains("function"))
+        .stderr(contains("مربع"));
+    fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,167 @@
+use assert_cmd::Command;
+use iqra::pkg::registry::gzip;
+use iqra::pkg::{PackageArchive, PackageManifest};
+use predicates::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FIXTURE: &str = "tests/fixtures/dependent_project";
+
+fn temp(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("iqra_project_deps_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A copy of the fixture project, free to gain an `iqra_packages`.
+fn project(name: &str) -> PathBuf {
+    let dir = temp(name);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    for file in ["iqra.json", "src/main.iqra"] {
+        fs::copy(Path::new(FIXTURE).join(file), dir.join(file)).unwrap();
+    }
+    dir
+}
+
+/// `iqra run`, with an empty `IQRA_HOME` so no global package counts.
+fn iqra(home: &Path) -> Command {
+    let mut command = Command::cargo_bin("iqra").unwrap();
+    command.env("IQRA_HOME", home).env_remove("MOCKITO_SERVER_URL").env_remove("IQRA_REGISTRY_URL");
+    command
+}
+
+#[test]
+fn test_find_project_walks_up_to_the_manifest() {
+    let fixture = fs::canonicalize(FIXTURE).unwrap();
+    assert_eq!(PackageManifest::find_project(&fixture.join("src")), Some(fixture.clone()));
+    assert_eq!(PackageManifest::find_project(&fixture), Some(fixture.clone()));
+    let outside = temp("outside");
+    assert_eq!(PackageManifest::find_project(&outside), None);
+
+    let manifest = PackageManifest::read(&fixture.join("iqra.json")).unwrap();
+    assert_eq!(manifest.dependencies.get("hisab").map(String::as_str), Some("1.1.0"));
+    assert_eq!(manifest.missing_dependencies(&outside), ["hisab", "salam"]);
+    fs::create_dir_all(outside.join("iqra_packages/salam")).unwrap();
+    assert_eq!(manifest.missing_dependencies(&outside), ["hisab"]);
+    fs::remove_dir_all(outside).unwrap();
+}
+
+#[test]
+fn test_missing_dependencies_stop_the_run_with_the_install_command() {
+    let home = temp("home_missing");
+    iqra(&home)
+        .args(["--lang", "en", "run", "tests/fixtures/dependent_project/src/main.iqra"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains("are not installed: hisab, salam"))
+        .stderr(predicate::str::contains("Run: iqra-pkg install hisab salam (in "))
+        .stderr(predicate::str::contains("--auto-install"));
+
+    // Both languages by default, Arabic first.
+    iqra(&home)
+        .args(["run", "tests/fixtures/dependent_project/src/main.iqra"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("غير مثبتة: hisab، salam"))
+        .stderr(predicate::str::contains("نفّذ: iqra-pkg install hisab salam"));
+
+    // Run from inside the project, the relative path still finds iqra.json.
+    iqra(&home)
+        .current_dir("tests/fixtures/dependent_project/src")
+        .args(["--lang", "en", "run", "main.iqra"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("iqra-pkg install hisab salam"));
+    fs::remove_dir_all(home).unwrap();
+}
+
+#[test]
+fn test_installed_dependencies_let_the_script_run() {
+    let home = temp("home_installed");
+    let project = project("installed");
+    fs::create_dir_all(project.join("iqra_packages/hisab")).unwrap();
+    let script = project.join("src/main.iqra");
+    iqra(&home)
+        .args(["--lang", "en", "run"])
+        .arg(&script)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("are not installed: salam\n"))
+        .stderr(predicate::str::contains("iqra-pkg install salam "));
+
+    // A package installed globally counts too.
+    fs::create_dir_all(home.join("packages/salam")).unwrap();
+    iqra(&home).arg("run").arg(&script).assert().success().stdout("جاهز\n");
+    fs::remove_dir_all(project).unwrap();
+    fs::remove_dir_all(home).unwrap();
+}
+
+#[test]
+fn test_invalid_project_manifest_is_reported() {
+    let home = temp("home_invalid");
+    let project = project("invalid");
+    fs::write(project.join("iqra.json"), r#"{"dependencies": ["hisab"]}"#).unwrap();
+    iqra(&home)
+        .args(["--lang", "en", "run"])
+        .arg(project.join("src/main.iqra"))
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Invalid package manifest"));
+    fs::remove_dir_all(project).unwrap();
+    fs::remove_dir_all(home).unwrap();
+}
+
+#[test]
+fn test_auto_install_fetches_missing_dependencies_first() {
+    let home = temp("home_auto");
+    let registry = temp("registry");
+    for (name, version, main) in
+        [("hisab", "1.1.0", "اطبع(٤٢)\n"), ("salam", "0.2.0", "اطبع(\"سلام\")\n")]
+    {
+        let source = registry.join("source").join(name);
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("main.iqra"), main).unwrap();
+        let archive = PackageArchive::build(&source).unwrap();
+        let dir = registry.join("packages").join(name).join(version);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("archive.tar.gz"), gzip(&archive.bytes)).unwrap();
+        let index = format!(r#"{{"versions": ["{}"]}}"#, version);
+        fs::write(registry.join("packages").join(name).join("index.json"), index).unwrap();
+    }
+    let project = project("auto");
+    fs::create_dir_all(project.join("iqra_packages/hisab")).unwrap();
+    iqra(&home)
+        .env("IQRA_REGISTRY_URL", format!("file://{}", registry.display()))
+        .args(["--lang", "en", "run", "--auto-install"])
+        .arg(project.join("src/main.iqra"))
+        .assert()
+        .success()
+        .stdout("جاهز\n")
+        .stderr(predicate::str::contains("Installing salam from registry"))
+        .stderr(predicate::str::contains("1 succeeded, 0 failed"));
+    assert!(project.join("iqra_packages/salam/main.iqra").is_file());
+    let installed = fs::read_to_string(project.join("iqra-pkg.json")).unwrap();
+    assert!(installed.contains(r#""salam": "0.2.0""#), "{}", installed);
+
+    // A package the registry lacks is still listed after the attempt.
+    fs::write(
+        project.join("iqra.json"),
+        r#"{"dependencies": {"salam": "0.2.0", "nawm": "1.0.0"}}"#,
+    )
+    .unwrap();
+    iqra(&home)
+        .env("IQRA_REGISTRY_URL", format!("file://{}", registry.display()))
+        .args(["--lang", "en", "run", "--auto-install"])
+        .arg(project.join("src/main.iqra"))
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains("Package not found in the registry: nawm"))
+        .stderr(predicate::str::contains("are not installed: nawm"));
+    fs::remove_dir_all(project).unwrap();
+    fs::remove_dir_all(registry).unwrap();
+    fs::remove_dir_all(home).unwrap();
+}
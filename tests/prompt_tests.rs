@@ -0,0 +1,137 @@
+use iqra::lang::runtime::{Runtime, SystemExecutor};
+use iqra::lang::value::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Answers prompts from a script and records every prompt shown.
+#[derive(Default)]
+struct Terminal {
+    answers: VecDeque<String>,
+    prompts: Vec<String>,
+    secret_prompts: Vec<String>,
+}
+
+struct ScriptedInput(Arc<Mutex<Terminal>>);
+
+impl SystemExecutor for ScriptedInput {
+    fn exec(&self, _cmd: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn exec_with_io(&self, _cmd: &str, _input: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn read_file(&self, _path: &str) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn write_file(&self, _path: &str, _content: &str) -> std::io::Result<bool> {
+        Ok(true)
+    }
+
+    fn list_files(&self, _path: &str) -> std::io::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    fn get_env_var(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    fn set_env_var(&self, _name: &str, _value: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn current_dir(&self) -> std::io::Result<String> {
+        Ok(String::new())
+    }
+
+    fn change_dir(&self, _path: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn process_id(&self) -> u32 {
+        1
+    }
+
+    fn read_line(&self, prompt: &str) -> std::io::Result<String> {
+        let mut terminal = self.0.lock().unwrap();
+        terminal.prompts.push(prompt.to_string());
+        Ok(terminal.answers.pop_front().unwrap_or_default())
+    }
+
+    fn read_password(&self, prompt: &str) -> std::io::Result<String> {
+        let mut terminal = self.0.lock().unwrap();
+        terminal.secret_prompts.push(prompt.to_string());
+        Ok(terminal.answers.pop_front().unwrap_or_default())
+    }
+
+    fn system_info(&self) -> std::io::Result<HashMap<String, String>> {
+        Ok(HashMap::new())
+    }
+}
+
+fn run(code: &str, answers: &[&str]) -> (Value, Arc<Mutex<Terminal>>) {
+    let terminal = Arc::new(Mutex::new(Terminal {
+        answers: answers.iter().map(|a| a.to_string()).collect(),
+        ..Default::default()
+    }));
+    let mut runtime = Runtime::new_with_executor(Box::new(ScriptedInput(terminal.clone())));
+    (runtime.execute(code).unwrap(), terminal)
+}
+
+#[test]
+fn test_confirm_accepts_arabic_and_english_answers() {
+    assert_eq!(run(r#"أكد؟("متابعة؟")"#, &["نعم"]).0, Value::Bool(true));
+    assert_eq!(run(r#"أكد؟("متابعة؟")"#, &["لا"]).0, Value::Bool(false));
+    assert_eq!(run(r#"confirm("Continue?")"#, &["Y"]).0, Value::Bool(true));
+    assert_eq!(run(r#"confirm("Continue?")"#, &[" no "]).0, Value::Bool(false));
+
+    let (result, terminal) = run(r#"confirm("Continue?")"#, &["ربما", "yes"]);
+    assert_eq!(result, Value::Bool(true));
+    assert_eq!(terminal.lock().unwrap().prompts, vec!["Continue? (نعم/لا | y/n): "; 2]);
+}
+
+#[test]
+fn test_confirm_gives_up_after_three_invalid_answers() {
+    let (result, terminal) = run(r#"confirm("Continue?")"#, &["?", "?", "?", "yes"]);
+    assert_eq!(result, Value::Nil);
+    assert_eq!(terminal.lock().unwrap().prompts.len(), 3);
+}
+
+#[test]
+fn test_choose_shows_menu_and_returns_selection() {
+    let (result, terminal) = run(r#"اختر("اللون؟"، ["أحمر"، "أزرق"، "أخضر"])"#, &["٢"]);
+    assert_eq!(result, Value::String("أزرق".to_string()));
+    assert_eq!(terminal.lock().unwrap().prompts[0], "اللون؟\n1. أحمر\n2. أزرق\n3. أخضر\n> ");
+
+    assert_eq!(
+        run(r#"choose("Color?", ["red", "blue"])"#, &["blue"]).0,
+        Value::String("blue".to_string())
+    );
+    assert_eq!(run(r#"choose("Size?", [10, 20])"#, &["1"]).0, Value::Int(10));
+}
+
+#[test]
+fn test_choose_reprompts_then_returns_nil() {
+    let (result, terminal) = run(r#"choose("Color?", ["red", "blue"])"#, &["5", "green", "0"]);
+    assert_eq!(result, Value::Nil);
+    assert_eq!(terminal.lock().unwrap().prompts.len(), 3);
+
+    let (result, _) = run(r#"choose("Color?", ["red", "blue"])"#, &["9", "2"]);
+    assert_eq!(result, Value::String("blue".to_string()));
+}
+
+#[test]
+fn test_password_reads_without_echo() {
+    let (result, terminal) = run(r#"كلمة_سر("كلمة المرور: ")"#, &["سر١٢٣"]);
+    assert_eq!(result, Value::String("سر١٢٣".to_string()));
+    assert_eq!(terminal.lock().unwrap().secret_prompts, vec!["كلمة المرور: "]);
+    assert!(terminal.lock().unwrap().prompts.is_empty());
+}
+
+#[test]
+fn test_choose_rejects_empty_options() {
+    let err = Runtime::new().execute(r#"choose("x", [])"#).unwrap_err().to_string();
+    assert!(err.contains("non-empty list of options"), "{}", err);
+}
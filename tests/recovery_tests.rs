@@ -0,0 +1,72 @@
+use assert_cmd::Command;
+use iqra::lang::lexer::Lexer;
+use iqra::lang::parser::Parser;
+use iqra::lsp::diagnostics;
+use predicates::str::contains;
+use std::fs;
+
+const FIXTURE: &str = "tests/fixtures/five_errors.iqra";
+
+fn error_lines(source: &str) -> Vec<Option<usize>> {
+    let (_, errors) = Parser::new(Lexer::new(source)).parse_with_recovery();
+    errors.iter().map(|err| err.line).collect()
+}
+
+#[test]
+fn test_all_five_errors_are_reported_with_lines() {
+    let source = fs::read_to_string(FIXTURE).unwrap();
+    let (statements, errors) = Parser::new(Lexer::new(&source)).parse_with_recovery();
+    let lines: Vec<Option<usize>> = errors.iter().map(|err| err.line).collect();
+    assert_eq!(lines, vec![Some(2), Some(4), Some(7), Some(8), Some(12)]);
+    // أ = ١، اطبع(أ) and ز = ٤ still parse.
+    assert_eq!(statements.len(), 3);
+}
+
+#[test]
+fn test_parse_still_stops_at_first_error() {
+    let source = fs::read_to_string(FIXTURE).unwrap();
+    let err = Parser::new(Lexer::new(&source)).parse().unwrap_err();
+    assert!(err.to_string().contains("Newline"));
+}
+
+#[test]
+fn test_max_errors_caps_the_report() {
+    let source = fs::read_to_string(FIXTURE).unwrap();
+    let mut parser = Parser::new(Lexer::new(&source));
+    parser.set_max_errors(2);
+    let (_, errors) = parser.parse_with_recovery();
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn test_lexer_errors_are_collected_instead_of_panicking() {
+    assert_eq!(error_lines("أ = ١ @ ٢\nب = (\n"), vec![Some(1), Some(2)]);
+    assert!(Parser::new(Lexer::new("س = \"بلا نهاية\n")).parse().is_err());
+}
+
+#[test]
+fn test_closing_brace_of_failed_function_is_not_reported() {
+    assert_eq!(error_lines("دالة د(س) {\n    ي = س +\n    ارجع ي\n}\nاطبع(١)\n"), vec![Some(2)]);
+}
+
+#[test]
+fn test_lsp_publishes_every_error() {
+    let source = fs::read_to_string(FIXTURE).unwrap();
+    let lines: Vec<u64> = diagnostics(&source)
+        .iter()
+        .map(|d| d["range"]["start"]["line"].as_u64().unwrap())
+        .collect();
+    assert_eq!(lines, vec![1, 3, 6, 7, 11]);
+}
+
+#[test]
+fn test_check_reports_all_errors() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["--lang", "en", "check", FIXTURE])
+        .env("NO_COLOR", "1")
+        .assert()
+        .code(2)
+        .stderr(contains("five_errors.iqra:2"))
+        .stderr(contains("five_errors.iqra:12"));
+}
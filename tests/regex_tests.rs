@@ -0,0 +1,64 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn strings(items: &[&str]) -> Value {
+    Value::List(items.iter().map(|s| Value::String(s.to_string())).collect())
+}
+
+#[test]
+fn test_regex_match() {
+    assert_eq!(eval(r#"تطابق؟("مرحبا 2024", "\d+")"#), Value::Bool(true));
+    assert_eq!(eval(r#"regex_match("hello", "^\d+$")"#), Value::Bool(false));
+}
+
+#[test]
+fn test_extract_arabic_words() {
+    assert_eq!(
+        eval(r#"ابحث_نمط("Hello مرحبا world بالعالم 42", "\p{Arabic}+")"#),
+        strings(&["مرحبا", "بالعالم"])
+    );
+}
+
+#[test]
+fn test_find_all_with_capture_groups() {
+    let result = eval(r#"regex_find_all("a=1, b=2", "(\w)=(\d)")"#);
+    assert_eq!(result, Value::List(vec![strings(&["a=1", "a", "1"]), strings(&["b=2", "b", "2"])]));
+}
+
+#[test]
+fn test_regex_replace_normalizes_whitespace() {
+    assert_eq!(
+        eval("استبدل_نمط(\"كلمة   أخرى\\t\\tثالثة\", \"\\\\s+\", \" \")"),
+        Value::String("كلمة أخرى ثالثة".to_string())
+    );
+    assert_eq!(
+        eval(r#"regex_replace("2024-10-17", "(\d+)-(\d+)-(\d+)", "$3/$2/$1")"#),
+        Value::String("17/10/2024".to_string())
+    );
+}
+
+#[test]
+fn test_invalid_pattern_is_catchable() {
+    let mut runtime = Runtime::new();
+    let err = runtime.execute(r#"regex_match("x", "(")"#).unwrap_err().to_string();
+    assert!(err.contains("Invalid pattern '('"), "{}", err);
+    assert!(err.contains("unclosed group"), "{}", err);
+
+    let code =
+        "try {\n    regex_match(\"x\", \"[\")\n    نتيجة = 1\n} catch (e) {\n    نتيجة = 2\n}";
+    runtime.execute(code).unwrap();
+    assert_eq!(runtime.get_variables().get("نتيجة"), Some(&Value::Int(2)));
+}
+
+#[test]
+fn test_regex_arguments_must_be_strings() {
+    let mut runtime = Runtime::new();
+    let err = runtime.execute(r#"regex_match(1, "x")"#).unwrap_err().to_string();
+    assert!(err.contains("regex_match expects string arguments"), "{}", err);
+    let err = runtime.execute(r#"regex_replace("x", "x")"#).unwrap_err().to_string();
+    assert!(err.contains("expects 3 argument(s) but received 2"), "{}", err);
+}
@@ -0,0 +1,97 @@
+use assert_cmd::Command;
+use iqra::cli::results::{ResultHistory, result_label};
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+use predicates::prelude::*;
+use predicates::str::contains;
+
+/// Runs each line as the REPL does, returning the numbers results were given.
+fn session(
+    runtime: &mut Runtime,
+    results: &mut ResultHistory,
+    lines: &[&str],
+) -> Vec<Option<usize>> {
+    lines
+        .iter()
+        .map(|line| match runtime.execute(line) {
+            Ok(value) => results.record(runtime, &value).unwrap(),
+            Err(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_results_are_numbered_and_bound() {
+    let mut runtime = Runtime::new();
+    let mut results = ResultHistory::new();
+    let numbers = session(&mut runtime, &mut results, &["٤٠ + ٢", "\"نص\"", "_1 + ١"]);
+    assert_eq!(numbers, [Some(1), Some(2), Some(3)]);
+    assert_eq!(runtime.execute("_2").unwrap(), Value::String("نص".to_string()));
+    assert_eq!(runtime.execute("_").unwrap(), Value::Int(43));
+    assert_eq!(runtime.execute("النتيجة").unwrap(), Value::Int(43));
+}
+
+#[test]
+fn test_errors_and_nil_take_no_number() {
+    let mut runtime = Runtime::new();
+    let mut results = ResultHistory::new();
+    let numbers = session(&mut runtime, &mut results, &["١", "غير_معرف", "اطبع(١)", "٢"]);
+    assert_eq!(numbers, [Some(1), None, None, Some(2)]);
+    assert_eq!(results.count(), 2);
+    assert_eq!(runtime.execute("_2").unwrap(), Value::Int(2));
+}
+
+#[test]
+fn test_reset_clears_the_numbers() {
+    let mut runtime = Runtime::new();
+    let mut results = ResultHistory::new();
+    session(&mut runtime, &mut results, &["١", "٢"]);
+    runtime.execute("ص = ٣").unwrap();
+    results.reset(&mut runtime);
+    assert!(runtime.execute("_1").is_err());
+    assert!(runtime.execute("_").is_err());
+    assert_eq!(runtime.execute("ص").unwrap(), Value::Int(3));
+    assert_eq!(session(&mut runtime, &mut results, &["١٠"]), [Some(1)]);
+}
+
+#[test]
+fn test_a_constant_name_is_not_overwritten() {
+    let mut runtime = Runtime::new();
+    let mut results = ResultHistory::new();
+    runtime.execute("ثابت النتيجة = ٠").unwrap();
+    assert!(results.record(&mut runtime, &Value::Int(1)).is_err());
+    assert_eq!(runtime.execute("النتيجة").unwrap(), Value::Int(0));
+}
+
+#[test]
+fn test_result_labels() {
+    assert_eq!(result_label(3, false), "[٣]");
+    assert_eq!(result_label(12, true), "[12]");
+}
+
+#[test]
+fn test_repl_prints_numbered_results() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .arg("repl")
+        .env("LANG", "C")
+        .write_stdin("٤٠ + ٢\n_ + ١\n:reset\n_\n٧\n")
+        .assert()
+        .success()
+        .stdout(contains("[1] = 42\n").and(contains("[2] = 43\n")).and(contains("[1] = 7\n")))
+        .stderr(contains("_"));
+}
+
+#[test]
+fn test_repl_notes_follow_the_lang_flag() {
+    let numbers: Vec<String> = (1..=60).map(|n| n.to_string()).collect();
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["--lang", "en", "repl"])
+        .env("LANG", "C")
+        .write_stdin(format!("[{}]\nاخرج(٣)\n", numbers.join(", ")))
+        .assert()
+        .success()
+        .stdout(contains("  … and 10 more\n]").and(contains("Program exited with code 3\n")))
+        .stdout(contains("أخرى").not().and(contains("انتهى").not()));
+}
@@ -0,0 +1,41 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+const SUM_ALL: &str = "دالة اجمع_كل(...قيم) {\n    ارجع جمع(قيم)\n}\n";
+
+#[test]
+fn test_rest_parameter_collects_arguments_and_forwards_to_sum() {
+    let mut runtime = Runtime::new();
+    runtime.execute(SUM_ALL).unwrap();
+    runtime.execute("خمسة = اجمع_كل(١, ٢, ٣, ٤, ٥)").unwrap();
+    runtime.execute("لا_شيء = اجمع_كل()").unwrap();
+    assert_eq!(runtime.get_variables().get("خمسة"), Some(&Value::Number(15.0)));
+    assert_eq!(runtime.get_variables().get("لا_شيء"), Some(&Value::Number(0.0)));
+}
+
+#[test]
+fn test_rest_after_fixed_parameters_with_star_syntax() {
+    let mut runtime = Runtime::new();
+    runtime
+        .execute("دالة سجل(مستوى, *رسائل) {\n    ارجع [مستوى, رسائل]\n}\nأ = سجل(\"تنبيه\", \"x\", \"y\")")
+        .unwrap();
+    assert_eq!(
+        runtime.get_variables().get("أ"),
+        Some(&Value::List(vec![
+            Value::String("تنبيه".to_string()),
+            Value::List(vec![Value::String("x".to_string()), Value::String("y".to_string())]),
+        ]))
+    );
+
+    let err = runtime.execute("سجل()").unwrap_err().to_string();
+    assert!(err.contains("expects at least 1 argument(s) but received 0"), "{}", err);
+}
+
+#[test]
+fn test_rest_parameter_must_be_last_and_unique() {
+    for code in ["دالة ف(...أ, ب) {\n    ارجع ب\n}", "دالة ف(...أ, ...ب) {\n    ارجع ب\n}"]
+    {
+        let err = Runtime::new().execute(code).unwrap_err().to_string();
+        assert!(err.contains("The rest parameter (...) must be the last parameter"), "{}", err);
+    }
+}
@@ -0,0 +1,38 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+// `ارجع` used to travel as an error whose text was "__RETURN__:" plus the
+// value's Debug form, parsed back by the caller: anything but a string or
+// number came back as nil, a string stopped at its first `)`, and a return
+// inside an `اذا` only left that block. These pin the fixed behaviour.
+
+fn result(code: &str) -> Value {
+    let mut runtime = Runtime::new();
+    runtime.execute(&format!("{}\nنتيجة = ابدأ()", code)).unwrap();
+    runtime.get_variables().get("نتيجة").cloned().unwrap()
+}
+
+#[test]
+fn test_return_keeps_the_value_type() {
+    assert_eq!(
+        result("دالة ابدأ() {\n    ارجع [صحيح, \"نص (مع قوس)\"]\n}"),
+        Value::List(vec![Value::Bool(true), Value::String("نص (مع قوس)".to_string())])
+    );
+    assert_eq!(result("دالة ابدأ() {\n    ارجع خطأ\n}"), Value::Bool(false));
+}
+
+#[test]
+fn test_return_exits_nested_blocks() {
+    let code = "دالة فحص(قيم) {\n    اذا طول(قيم) > ٠ {\n        ارجع \"وجد\"\n    }\n    ارجع \"لا شيء\"\n}\n\
+                دالة ابدأ() {\n    ارجع [فحص([١]), فحص([])]\n}";
+    assert_eq!(
+        result(code),
+        Value::List(vec![Value::String("وجد".to_string()), Value::String("لا شيء".to_string())])
+    );
+}
+
+#[test]
+fn test_return_outside_a_function_is_an_error() {
+    let err = Runtime::new().execute("ارجع ١").unwrap_err();
+    assert!(err.to_string().contains("return can only be used inside a function"), "{}", err);
+}
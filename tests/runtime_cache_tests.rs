@@ -0,0 +1,165 @@
+use chrono::{NaiveDate, TimeDelta};
+use iqra::cli::report::state_report;
+use iqra::lang::hijri::Clock;
+use iqra::lang::runtime::{CacheStatus, Runtime, SystemExecutor};
+use iqra::lang::value::Value;
+use std::collections::HashMap;
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A clock the test moves forward by hand.
+struct ManualClock(Mutex<NaiveDate>);
+
+impl ManualClock {
+    fn advance_a_day(&self) {
+        let mut day = self.0.lock().unwrap();
+        *day += TimeDelta::days(1);
+    }
+}
+
+impl Clock for ManualClock {
+    fn today(&self) -> NaiveDate {
+        *self.0.lock().unwrap()
+    }
+}
+
+/// Counts the calls to `system_info` and answers with the count.
+struct CountingExecutor(Arc<AtomicUsize>);
+
+impl SystemExecutor for CountingExecutor {
+    fn exec(&self, _cmd: &str) -> io::Result<String> {
+        Ok(String::new())
+    }
+    fn exec_with_io(&self, _cmd: &str, _input: &str) -> io::Result<String> {
+        Ok(String::new())
+    }
+    fn read_file(&self, _path: &str) -> io::Result<String> {
+        Ok(String::new())
+    }
+    fn write_file(&self, _path: &str, _content: &str) -> io::Result<bool> {
+        Ok(true)
+    }
+    fn list_files(&self, _path: &str) -> io::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+    fn get_env_var(&self, _name: &str) -> Option<String> {
+        None
+    }
+    fn set_env_var(&self, _name: &str, _value: &str) -> io::Result<()> {
+        Ok(())
+    }
+    fn current_dir(&self) -> io::Result<String> {
+        Ok("/".to_string())
+    }
+    fn change_dir(&self, _path: &str) -> io::Result<()> {
+        Ok(())
+    }
+    fn process_id(&self) -> u32 {
+        1
+    }
+    fn read_line(&self, _prompt: &str) -> io::Result<String> {
+        Ok(String::new())
+    }
+    fn read_password(&self, _prompt: &str) -> io::Result<String> {
+        Ok(String::new())
+    }
+    fn system_info(&self) -> io::Result<HashMap<String, String>> {
+        let calls = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+        Ok(HashMap::from([("calls".to_string(), calls.to_string())]))
+    }
+}
+
+fn counting_runtime() -> (Runtime, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    (Runtime::new_with_executor(Box::new(CountingExecutor(calls.clone()))), calls)
+}
+
+fn text(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        other => panic!("expected a string, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_hijri_today_follows_the_clock_past_midnight() {
+    let clock = Arc::new(ManualClock(Mutex::new(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap())));
+    let mut runtime = Runtime::new();
+    runtime.set_clock(clock.clone());
+    let first = text(runtime.execute("تاريخ_هجري()").unwrap());
+    assert_eq!(text(runtime.execute("hijri_today()").unwrap()), first);
+    assert_eq!(runtime.cache_status().today, NaiveDate::from_ymd_opt(2025, 3, 1));
+
+    clock.advance_a_day();
+    let next = text(runtime.execute("تاريخ_هجري()").unwrap());
+    assert_ne!(next, first);
+    assert_eq!(next, text(runtime.execute("إلى_هجري(\"2025-03-02\")").unwrap()));
+    assert_eq!(text(runtime.execute("تاريخ_اليوم()").unwrap()), "2025-03-02");
+    assert_eq!(runtime.cache_status().today, NaiveDate::from_ymd_opt(2025, 3, 2));
+}
+
+#[test]
+fn test_system_info_is_cached_until_refreshed() {
+    let (mut runtime, calls) = counting_runtime();
+    assert_eq!(
+        runtime.execute("معلومات_النظام()[\"calls\"]").unwrap(),
+        Value::String("1".to_string())
+    );
+    assert_eq!(
+        runtime.execute("system_info()[\"calls\"]").unwrap(),
+        Value::String("1".to_string())
+    );
+    assert_eq!(
+        runtime.execute("معلومات_النظام(خطأ)[\"calls\"]").unwrap(),
+        Value::String("1".to_string())
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    assert_eq!(
+        runtime.execute("معلومات_النظام(صحيح)[\"calls\"]").unwrap(),
+        Value::String("2".to_string())
+    );
+    assert_eq!(
+        runtime.execute("معلومات_النظام()[\"calls\"]").unwrap(),
+        Value::String("2".to_string())
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_system_info_rejects_a_non_boolean_refresh() {
+    let (mut runtime, calls) = counting_runtime();
+    assert!(runtime.execute("معلومات_النظام(١)").is_err());
+    assert!(runtime.execute("معلومات_النظام(صحيح، صحيح)").is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_clear_caches_forgets_everything() {
+    let (mut runtime, calls) = counting_runtime();
+    assert_eq!(runtime.cache_status(), CacheStatus::default());
+    runtime.execute("معلومات_النظام()\nتاريخ_هجري()\nتطابق؟(\"12\"، \"[0-9]+\")").unwrap();
+    let status = runtime.cache_status();
+    assert!(status.system_info);
+    assert!(status.today.is_some());
+    assert_eq!(status.regex_patterns, 1);
+
+    runtime.clear_caches();
+    assert_eq!(runtime.cache_status(), CacheStatus::default());
+    runtime.execute("معلومات_النظام()").unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_state_report_shows_the_caches() {
+    let (mut runtime, _) = counting_runtime();
+    let report = state_report(&runtime, &Ok(Value::Nil), "");
+    assert_eq!(report["caches"]["system_info"], false);
+    assert!(report["caches"]["today"].is_null());
+
+    let outcome = runtime.execute("معلومات_النظام()");
+    let report = state_report(&runtime, &outcome, "");
+    assert_eq!(report["caches"]["system_info"], true);
+    assert_eq!(report["caches"]["regex_patterns"], 0);
+}
@@ -0,0 +1,57 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+#[test]
+fn test_success_shape() {
+    let result = eval("آمن إلى_رقم(\"٤٢\")");
+    assert_eq!(result.get_path("نجح").unwrap(), &Value::Bool(true));
+    assert_eq!(result.get_path("قيمة").unwrap(), &Value::Number(42.0));
+    assert_eq!(result.get_path("خطأ").unwrap(), &Value::Nil);
+}
+
+#[test]
+fn test_failure_shape() {
+    let result = eval("نتيجة = آمن إلى_رقم(\"abc\")\nنتيجة");
+    assert_eq!(result.get_path("نجح").unwrap(), &Value::Bool(false));
+    assert_eq!(result.get_path("قيمة").unwrap(), &Value::Nil);
+    assert_eq!(
+        result.get_path("خطأ.message").unwrap(),
+        &Value::String("Cannot convert 'abc' to number: unexpected character 'a'".to_string())
+    );
+    assert!(matches!(result.get_path("خطأ.نوع").unwrap(), Value::String(_)));
+    assert!(matches!(result.get_path("خطأ.رسالة").unwrap(), Value::String(_)));
+}
+
+#[test]
+fn test_covers_the_whole_expression_to_its_right() {
+    assert_eq!(eval("(آمن ١ / ٠)[\"نجح\"]"), Value::Bool(false));
+    assert_eq!(eval("try? 2 * 3"), eval("آمن ٢ * ٣"));
+}
+
+#[test]
+fn test_nests_inside_larger_expressions() {
+    let code = "دالة مقلوب(س) { ارجع ١ / س }\n\
+                أ = (آمن مقلوب(٢))[\"قيمة\"]\n\
+                ب = (آمن مقلوب(٠))[\"نجح\"]\n\
+                [أ، ب، (آمن (آمن مقلوب(٠))[\"قيمة\"][\"x\"])[\"نجح\"]]";
+    assert_eq!(
+        eval(code),
+        Value::List(vec![Value::Number(0.5), Value::Bool(false), Value::Bool(false)])
+    );
+}
+
+#[test]
+fn test_branching_on_the_result() {
+    let code = "نتيجة = آمن إلى_رقم(\"x\")\n\
+                اذا نتيجة[\"نجح\"] { نتيجة[\"قيمة\"] } وإلا { -١ }";
+    assert_eq!(eval(code), Value::Int(-1));
+}
+
+#[test]
+fn test_exit_is_not_caught() {
+    assert!(Runtime::new().execute("آمن اخرج(٣)").is_err());
+}
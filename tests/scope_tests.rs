@@ -0,0 +1,96 @@
+use iqra::cli::describe_scopes;
+use iqra::lang::runtime::{DebugAction, Runtime};
+use iqra::lang::value::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+fn map(value: Value) -> HashMap<String, Value> {
+    match value {
+        Value::Map(map) => map,
+        other => panic!("expected a map, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_locals_inside_a_function_holds_its_parameters_only() {
+    let mut runtime = Runtime::new();
+    let source = "عام = ١٠\nدالة جمع(أ، ب = ٢، ...باقي) {\n    مجموع = أ + ب\n    ارجع المتغيرات_المحلية()\n}\nجمع(١)";
+    let locals = map(runtime.execute(source).unwrap());
+    assert_eq!(locals.get("أ"), Some(&Value::Int(1)));
+    assert_eq!(locals.get("ب"), Some(&Value::Int(2)));
+    assert_eq!(locals.get("مجموع"), Some(&Value::Int(3)));
+    assert_eq!(locals.get("باقي"), Some(&Value::List(Vec::new())));
+    assert!(!locals.contains_key("عام"));
+    assert_eq!(locals.len(), 4);
+}
+
+#[test]
+fn test_globals_inside_a_function_ignores_its_locals() {
+    let mut runtime = Runtime::new();
+    let source = "س = ١\nدالة د(س) {\n    ص = ٢\n    ارجع globals()\n}\nد(٥)";
+    let globals = map(runtime.execute(source).unwrap());
+    assert_eq!(globals, HashMap::from([("س".to_string(), Value::Int(1))]));
+}
+
+#[test]
+fn test_locals_at_top_level_are_the_globals() {
+    let mut runtime = Runtime::new();
+    runtime.execute("س = ١\nص = \"أ\"").unwrap();
+    assert_eq!(
+        runtime.execute("locals()").unwrap(),
+        runtime.execute("المتغيرات_العامة()").unwrap()
+    );
+    assert!(runtime.execute("locals(١)").is_err());
+}
+
+#[test]
+fn test_returned_scopes_are_copies() {
+    let mut runtime = Runtime::new();
+    runtime.execute("س = ١\nنسخة = globals()\nس = ٢").unwrap();
+    assert_eq!(runtime.execute("نسخة[\"س\"]").unwrap(), Value::Int(1));
+    assert!(!map(runtime.execute("نسخة").unwrap()).contains_key("نسخة"));
+}
+
+#[test]
+fn test_all_scopes_sees_each_call_as_it_was() {
+    let mut runtime = Runtime::new();
+    let seen = Arc::new(Mutex::new(None));
+    let sink = seen.clone();
+    runtime.hooks_mut().on_statement = Some(Box::new(move |_, runtime| {
+        if runtime.call_depth() == 2 {
+            sink.lock()
+                .unwrap()
+                .get_or_insert_with(|| (runtime.all_scopes(), runtime.global_variables()));
+        }
+        DebugAction::Continue
+    }));
+    let source = "ع = ١\nأ = \"عام\"\nدالة خارجية(أ) {\n    ع = ٢\n    ارجع داخلية(أ + ١)\n}\nدالة داخلية(أ) {\n    ارجع أ\n}\nخارجية(١)";
+    assert_eq!(runtime.execute(source).unwrap(), Value::Int(2));
+    let (scopes, globals) = seen.lock().unwrap().take().unwrap();
+    assert_eq!(scopes.len(), 3);
+    let expected_globals = HashMap::from([
+        ("ع".to_string(), Value::Int(1)),
+        ("أ".to_string(), Value::String("عام".to_string())),
+    ]);
+    assert_eq!(scopes[0], expected_globals);
+    assert_eq!(
+        scopes[1],
+        HashMap::from([("أ".to_string(), Value::Int(1)), ("ع".to_string(), Value::Int(2))])
+    );
+    assert_eq!(scopes[2], HashMap::from([("أ".to_string(), Value::Int(2))]));
+    assert_eq!(globals.into_iter().collect::<HashMap<_, _>>(), expected_globals);
+}
+
+#[test]
+fn test_describe_scopes_lists_the_innermost_call_then_the_globals() {
+    let globals =
+        HashMap::from([("ب".to_string(), Value::Int(2)), ("أ".to_string(), Value::Int(1))]);
+    let locals = HashMap::from([("س".to_string(), Value::Int(3))]);
+    let outside = describe_scopes(std::slice::from_ref(&globals));
+    assert_eq!(outside.len(), 3);
+    assert!(outside[1].contains("أ = 1") && outside[2].contains("ب = 2"), "{:?}", outside);
+    let inside = describe_scopes(&[globals, HashMap::new(), locals]);
+    assert_eq!(inside.len(), 5);
+    assert!(inside[1].contains("س = 3"), "{:?}", inside);
+    assert_eq!(inside[2], outside[0]);
+}
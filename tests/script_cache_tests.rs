@@ -0,0 +1,113 @@
+use assert_cmd::Command;
+use chrono::NaiveDate;
+use iqra::lang::builtins::cache::ScriptCache;
+use iqra::lang::hijri::FixedClock;
+use iqra::lang::runtime::{IqraError, Runtime};
+use iqra::lang::value::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A fresh directory under the system temp dir standing in for `IQRA_HOME`.
+fn temp_home(name: &str) -> PathBuf {
+    let home = std::env::temp_dir().join(format!("iqra_cache_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    home
+}
+
+fn day(day: u32) -> Arc<FixedClock> {
+    Arc::new(FixedClock(NaiveDate::from_ymd_opt(2025, 3, day).unwrap()))
+}
+
+fn runtime_at(home: &Path, clock: Arc<FixedClock>) -> Runtime {
+    let mut runtime = Runtime::new();
+    runtime.set_cache_file(home.join("cache").join("script_cache.json"));
+    runtime.set_clock(clock);
+    runtime
+}
+
+#[test]
+fn test_values_round_trip_through_the_file() {
+    let home = temp_home("round_trip");
+    let mut runtime = runtime_at(&home, day(1));
+    runtime
+        .execute("خزّن(\"بيانات\"، قاموس(\"أ\"، [١، ٢.٥، صحيح، \"نص\"]))\ncache_set(\"عدد\", 7)")
+        .unwrap();
+    assert!(home.join("cache").join("script_cache.json").is_file());
+
+    // A second runtime, as in a later run, sees the same values.
+    let mut later = runtime_at(&home, day(1));
+    let expected = Runtime::new().execute("قاموس(\"أ\"، [١، ٢.٥، صحيح، \"نص\"])").unwrap();
+    assert_eq!(later.execute("تذكر(\"بيانات\")").unwrap(), expected);
+    assert_eq!(later.execute("cache_get(\"عدد\")").unwrap(), Value::Int(7));
+    assert_eq!(later.execute("تذكر(\"مجهول\")").unwrap(), Value::Nil);
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_entries_expire_on_the_clock() {
+    let home = temp_home("expire");
+    let mut runtime = runtime_at(&home, day(1));
+    runtime.execute("خزّن(\"قصير\"، ١، ٦٠)\nخزّن(\"طويل\"، ٢، ١٠٠٠٠٠٠)\nخزّن(\"دائم\"، ٣)").unwrap();
+    assert_eq!(runtime.execute("تذكر(\"قصير\")").unwrap(), Value::Int(1));
+
+    runtime.set_clock(day(2));
+    assert_eq!(runtime.execute("تذكر(\"قصير\")").unwrap(), Value::Nil);
+    assert_eq!(runtime.execute("تذكر(\"طويل\")").unwrap(), Value::Int(2));
+    assert_eq!(runtime.execute("تذكر(\"دائم\")").unwrap(), Value::Int(3));
+
+    // Expired entries are dropped the next time the file is written.
+    runtime.execute("خزّن(\"جديد\"، ٤)").unwrap();
+    let json = fs::read_to_string(home.join("cache").join("script_cache.json")).unwrap();
+    assert!(!json.contains("قصير") && json.contains("طويل"), "{}", json);
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_clear_removes_everything() {
+    let home = temp_home("clear");
+    let mut runtime = runtime_at(&home, day(1));
+    runtime.execute("خزّن(\"أ\"، ١)\nخزّن(\"ب\"، ٢)\nامسح_الذاكرة()").unwrap();
+    assert_eq!(runtime.execute("تذكر(\"أ\")").unwrap(), Value::Nil);
+    assert_eq!(runtime.execute("cache_clear()").unwrap(), Value::Nil);
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_bad_arguments_and_files() {
+    let home = temp_home("bad");
+    let mut runtime = runtime_at(&home, day(1));
+    for code in ["خزّن(١، ٢)", "خزّن(\"أ\"، ١، ٠)", "خزّن(\"أ\"، ١، \"ساعة\")", "تذكر()"]
+    {
+        assert!(runtime.execute(code).is_err(), "{}", code);
+    }
+    // A damaged file reads as an empty cache and is replaced on the next write.
+    fs::create_dir_all(home.join("cache")).unwrap();
+    fs::write(home.join("cache").join("script_cache.json"), "{\"entries\": {").unwrap();
+    assert_eq!(runtime.execute("تذكر(\"أ\")").unwrap(), Value::Nil);
+    runtime.execute("خزّن(\"أ\"، ١)").unwrap();
+    assert_eq!(runtime.execute("تذكر(\"أ\")").unwrap(), Value::Int(1));
+    assert_eq!(ScriptCache::parse("not json"), ScriptCache::default());
+
+    runtime.set_sandboxed(true);
+    let err = runtime.execute("تذكر(\"أ\")").unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.kind, "غير مسموح في وضع العزل | Not allowed in sandbox");
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_the_cache_lives_in_iqra_home() {
+    let home = temp_home("cli");
+    for (code, stdout) in [("خزّن(\"أ\"، ٤٢)", ""), ("اطبع(تذكر(\"أ\"))", "42\n")]
+    {
+        Command::cargo_bin("iqra")
+            .unwrap()
+            .args(["code", code])
+            .env("IQRA_HOME", &home)
+            .assert()
+            .success()
+            .stdout(stdout);
+    }
+    assert!(home.join("cache").join("script_cache.json").is_file());
+    fs::remove_dir_all(&home).unwrap();
+}
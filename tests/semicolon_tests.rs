@@ -0,0 +1,45 @@
+use assert_cmd::Command;
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(source: &str) -> Value {
+    Runtime::new().execute(source).unwrap()
+}
+
+#[test]
+fn test_semicolons_separate_statements() {
+    assert_eq!(eval("س = ١; ص = س + ١; ص"), Value::Int(2));
+    assert_eq!(eval("س = ١؛ ص = س + ١؛ ص"), Value::Int(2));
+}
+
+#[test]
+fn test_mixed_languages_and_separators() {
+    assert_eq!(eval("x = 2؛ ص = x * ٣; print(ص)؛ ص"), Value::Int(6));
+}
+
+#[test]
+fn test_trailing_and_repeated_separators_are_harmless() {
+    assert_eq!(eval("س = ٤;"), Value::Int(4));
+    assert_eq!(eval(";; س = ٤;;؛ س;"), Value::Int(4));
+}
+
+#[test]
+fn test_semicolons_inside_blocks() {
+    assert_eq!(eval("س = ٠; بينما س < ٣ { س = س + ١; }; س"), Value::Int(3));
+    assert_eq!(eval("دالة ضعف(ع) { ن = ع * ٢؛ ارجع ن }؛ ضعف(٥)"), Value::Int(10));
+}
+
+#[test]
+fn test_arabic_semicolon_is_not_part_of_a_name() {
+    assert_eq!(eval("اسم = \"أ\"؛اسم"), Value::String("أ".to_string()));
+}
+
+#[test]
+fn test_code_one_liner() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["code", "-c", "س = ١; اطبع(س)؛ print(س + 1);"])
+        .assert()
+        .success()
+        .stdout("1\n2\n");
+}
@@ -0,0 +1,134 @@
+use iqra::cli::serve::{Server, serve};
+use iqra::lang::runtime::{Limits, Runtime};
+use serde_json::{Value as Json, json};
+use std::io::Cursor;
+use std::time::Duration;
+
+const LIMITS: Limits = Limits {
+    max_statements: Some(10_000),
+    timeout: Some(Duration::from_secs(5)),
+    max_collection_len: None,
+    max_collection_bytes: None,
+};
+
+fn eval_request(id: u64, code: &str, reset: bool) -> String {
+    json!({ "id": id, "method": "eval", "params": { "code": code, "reset": reset } }).to_string()
+}
+
+#[test]
+fn test_scripted_request_sequence() {
+    let requests = [
+        eval_request(1, "١ + ٢", true),
+        eval_request(2, "س = ١٠\nاطبع(\"قيمة\", س)", false),
+        eval_request(3, "س * ٢", false),
+        eval_request(4, "س", true),
+        String::new(),
+        "not json".to_string(),
+        json!({ "id": 5, "method": "compile", "params": {} }).to_string(),
+    ]
+    .join("\n");
+
+    let mut out = Vec::new();
+    serve(Cursor::new(requests), &mut out, LIMITS).unwrap();
+    let responses: Vec<Json> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(responses.len(), 6);
+
+    assert_eq!(
+        responses[0],
+        json!({ "id": 1, "result": { "value": "3", "type": "number", "stdout": "" } })
+    );
+    assert_eq!(responses[1]["result"]["stdout"], "قيمة 10\n");
+    assert_eq!(responses[2]["result"]["value"], "20");
+    // A reset runtime no longer knows س.
+    assert_eq!(responses[3]["id"], 4);
+    assert!(
+        responses[3]["error"]["message_en"].as_str().unwrap().contains("Undefined variable"),
+        "{}",
+        responses[3]
+    );
+    assert_eq!(responses[3]["error"]["line"], 1);
+    assert_eq!(responses[4]["id"], Json::Null);
+    assert_eq!(responses[4]["error"]["kind"], "invalid_request");
+    assert_eq!(responses[5]["error"]["kind"], "unknown_method");
+}
+
+#[test]
+fn test_hostile_programs_are_stopped() {
+    let mut server = Server::new(LIMITS);
+    let response = server.handle_line(&eval_request(
+        1,
+        "while true {\n    try {\n        س = ١\n    } catch (e) {\n    }\n}",
+        false,
+    ));
+    assert!(
+        response["error"]["message_en"].as_str().unwrap().contains("limit of 10000 statements"),
+        "{}",
+        response
+    );
+
+    let mut server =
+        Server::new(Limits { timeout: Some(Duration::from_millis(50)), ..Limits::default() });
+    let response = server.handle_line(&eval_request(2, "while true { }", false));
+    assert!(
+        response["error"]["message_en"].as_str().unwrap().contains("time limit of 50 ms"),
+        "{}",
+        response
+    );
+
+    let response = server.handle_line(&eval_request(3, "system(\"ls\")", false));
+    assert!(
+        response["error"]["message_en"].as_str().unwrap().contains("not allowed in sandbox"),
+        "{}",
+        response
+    );
+
+    // Limits apply per request, so the session keeps working.
+    let response = server.handle_line(&eval_request(4, "١", false));
+    assert_eq!(response["result"]["value"], "1");
+}
+
+#[test]
+fn test_long_operator_chains_are_a_clean_error() {
+    let mut server = Server::new(LIMITS);
+    let chain = format!("س = {}", vec!["١"; 100_000].join(" + "));
+    let indexes = format!("س = [٠]{}", "[٠]".repeat(100_000));
+    for (id, code) in [(1, chain), (2, indexes)] {
+        let response = server.handle_line(&eval_request(id, &code, false));
+        assert_eq!(response["error"]["kind"], "تجاوز عمق التداخل | Nesting too deep");
+    }
+    let response = server.handle_line(&eval_request(3, "١ + ٢", false));
+    assert_eq!(response["result"]["value"], "3");
+}
+
+#[test]
+fn test_exit_is_reported_as_result() {
+    let mut server = Server::new(LIMITS);
+    let response = server.handle_line(&eval_request(1, "اطبع(\"وداعاً\")\nexit(3)", false));
+    assert_eq!(response["result"]["exit_code"], 3);
+    assert_eq!(response["result"]["stdout"], "وداعاً\n");
+}
+
+#[test]
+fn test_stderr_is_reported_next_to_stdout() {
+    let mut server = Server::new(LIMITS);
+    let response = server.handle_line(&eval_request(1, "اطبع(١)\nاطبع_خطأ(\"تحذير\")", false));
+    assert_eq!(response["result"]["stdout"], "1\n");
+    assert_eq!(response["result"]["stderr"], "تحذير\n");
+    let response = server.handle_line(&eval_request(2, "print_error(\"x\")\n١ / ٠", false));
+    assert_eq!(response["error"]["stderr"], "x\n");
+}
+
+#[test]
+fn test_runtime_limits_count_statements() {
+    let mut runtime = Runtime::new();
+    runtime.set_limits(Limits { max_statements: Some(5), ..Limits::default() });
+    runtime.execute("أ = ١\nب = ٢").unwrap();
+    assert_eq!(runtime.statements_executed(), 2);
+    let err = runtime.execute("ع = ٠\nwhile ع < ١٠ {\n    ع = ع + ١\n}").unwrap_err().to_string();
+    assert!(err.contains("exceeded the limit of 5 statements"), "{}", err);
+    assert!(err.contains("Line: 3"), "{}", err);
+}
@@ -0,0 +1,120 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+use std::fs;
+
+const SESSION: &str = "\
+س = ١٠
+ن = ٢٫٥
+أسماء = [\"أحمد\"، \"منى\"]
+بيانات = قاموس(\"عمر\"، ٣٠)
+م = مجموعة(\"أ\"، \"ب\")
+ثابت حد = ١٠٠
+/// يضاعف العدد
+دالة ضعف(ع، مرات = ٢، ...بقية) {
+    اذا ع > حد {
+        ارجع حد
+    }
+    ارجع ع * مرات
+}
+";
+
+fn restored(json: &str) -> Runtime {
+    let mut runtime = Runtime::new();
+    runtime.load_state(json).unwrap();
+    runtime
+}
+
+#[test]
+fn test_round_trip_into_fresh_runtime() {
+    let mut original = Runtime::new();
+    original.execute(SESSION).unwrap();
+    let mut runtime = restored(&original.save_state().unwrap());
+
+    assert_eq!(runtime.execute("س").unwrap(), Value::Int(10));
+    assert_eq!(runtime.execute("ن").unwrap(), Value::Number(2.5));
+    assert_eq!(runtime.execute("عنصر(أسماء، ١)").unwrap(), Value::String("منى".to_string()));
+    assert_eq!(runtime.execute("جلب_عنصر(بيانات، \"عمر\")").unwrap(), Value::Int(30));
+    assert_eq!(runtime.execute("في_مجموعة؟(م، \"ب\")").unwrap(), Value::Bool(true));
+    assert_eq!(runtime.get_variables(), original.get_variables());
+}
+
+#[test]
+fn test_functions_and_constants_are_restored() {
+    let mut original = Runtime::new();
+    original.execute(SESSION).unwrap();
+    let mut runtime = restored(&original.save_state().unwrap());
+
+    assert_eq!(runtime.execute("ضعف(٤)").unwrap(), Value::Int(8));
+    assert_eq!(runtime.execute("ضعف(٤، ٣)").unwrap(), Value::Int(12));
+    assert_eq!(runtime.execute("ضعف(٥٠٠)").unwrap(), Value::Int(100));
+    assert_eq!(
+        runtime.execute("مساعدة(\"ضعف\")").unwrap(),
+        Value::String("يضاعف العدد".to_string())
+    );
+    assert!(runtime.execute("حد = ١").is_err());
+}
+
+#[test]
+fn test_int_and_number_stay_distinct() {
+    let mut original = Runtime::new();
+    original.execute("أ = ٢\nب = ٢٫٠").unwrap();
+    let mut runtime = restored(&original.save_state().unwrap());
+    assert!(matches!(runtime.execute("أ").unwrap(), Value::Int(2)));
+    assert!(matches!(runtime.execute("ب").unwrap(), Value::Number(_)));
+}
+
+#[test]
+fn test_unsaveable_values_are_skipped_and_listed() {
+    let mut original = Runtime::new();
+    original.execute("لانهاية = إلى_رقم(\"inf\")\nعادي = ١").unwrap();
+    let json = original.save_state().unwrap();
+    let mut runtime = Runtime::new();
+    assert_eq!(runtime.load_state(&json).unwrap(), vec!["لانهاية".to_string()]);
+    assert_eq!(runtime.execute("عادي").unwrap(), Value::Int(1));
+    assert!(runtime.execute("لانهاية").is_err());
+}
+
+#[test]
+fn test_loading_keeps_unrelated_variables() {
+    let mut original = Runtime::new();
+    original.execute("أ = ١").unwrap();
+    let mut runtime = Runtime::new();
+    runtime.execute("أ = ٩\nب = ٢").unwrap();
+    runtime.load_state(&original.save_state().unwrap()).unwrap();
+    assert_eq!(runtime.execute("أ + ب").unwrap(), Value::Int(3));
+}
+
+#[test]
+fn test_invalid_session_is_an_error() {
+    let err = Runtime::new().load_state("{\"version\": 1").unwrap_err();
+    assert!(format!("{}", err).contains("Invalid session file"));
+    let err = Runtime::new()
+        .load_state("{\"version\": 99, \"variables\": {}, \"constants\": [], \"functions\": {}}")
+        .unwrap_err();
+    assert!(format!("{}", err).contains("Unsupported session version 99"));
+}
+
+#[test]
+fn test_save_and_restore_builtins() {
+    let path = std::env::temp_dir().join(format!("iqra_session_{}.json", std::process::id()));
+    let path = path.to_str().unwrap().replace('\\', "/");
+    let mut original = Runtime::new();
+    original.execute(SESSION).unwrap();
+    assert_eq!(
+        original.execute(&format!("احفظ_الحالة(\"{}\")", path)).unwrap(),
+        Value::List(vec![])
+    );
+
+    let mut runtime = Runtime::new();
+    runtime.execute(&format!("restore_state(\"{}\")", path)).unwrap();
+    assert_eq!(runtime.execute("ضعف(س)").unwrap(), Value::Int(20));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_save_state_is_refused_in_sandbox() {
+    let mut runtime = Runtime::new();
+    runtime.set_sandboxed(true);
+    let err = runtime.execute("save_state(\"x.json\")").unwrap_err();
+    assert!(format!("{}", err).contains("not allowed in sandbox mode"));
+}
@@ -0,0 +1,68 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::{Value, ValueSet};
+
+fn eval(source: &str) -> Value {
+    Runtime::new().execute(source).unwrap()
+}
+
+fn set(values: &[Value]) -> Value {
+    Value::Set(ValueSet::from_values(values).unwrap())
+}
+
+#[test]
+fn test_set_deduplicates_a_repeated_list() {
+    let value = eval("مجموعة([١، ٢، ٣، ١، ٢، ٣، ٣])");
+    assert_eq!(value, set(&[Value::Int(1), Value::Int(2), Value::Int(3)]));
+    assert_eq!(format!("{}", value), "مجموعة{1, 2, 3}");
+    assert_eq!(eval("طول(مجموعة([١، ٢، ٣، ١، ٢، ٣]))"), Value::Int(3));
+}
+
+#[test]
+fn test_arabic_string_members_keep_insertion_order() {
+    let value = eval("مجموعة(\"قلم\"، \"كتاب\"، \"قلم\"، \"دفتر\")");
+    assert_eq!(format!("{}", value), "مجموعة{قلم, كتاب, دفتر}");
+    assert_eq!(eval("في_مجموعة؟(مجموعة(\"قلم\"، \"كتاب\")، \"كتاب\")"), Value::Bool(true));
+    assert_eq!(eval("set_contains(set(\"قلم\"), \"ممحاة\")"), Value::Bool(false));
+}
+
+#[test]
+fn test_int_and_equal_number_are_the_same_member() {
+    assert_eq!(eval("طول(مجموعة(١، ١٫٠، ٢٫٥))"), Value::Int(2));
+}
+
+#[test]
+fn test_set_add_returns_a_new_set() {
+    assert_eq!(eval("م = مجموعة(١)\nن = أضف_لمجموعة(م، ٢)\nطول(م) * ١٠ + طول(ن)"), Value::Int(12));
+    assert_eq!(eval("طول(set_add(set(1), 1))"), Value::Int(1));
+}
+
+#[test]
+fn test_set_operations() {
+    let setup = "أ = مجموعة(١، ٢، ٣)\nب = مجموعة(٢، ٣، ٤)\n";
+    assert_eq!(eval(&format!("{}إلى_قائمة(اتحاد(أ، ب))", setup)), eval("[١، ٢، ٣، ٤]"));
+    assert_eq!(eval(&format!("{}إلى_قائمة(تقاطع(أ، ب))", setup)), eval("[٢، ٣]"));
+    assert_eq!(eval(&format!("{}to_list(difference(أ، ب))", setup)), eval("[١]"));
+}
+
+#[test]
+fn test_set_equality_ignores_order_and_truthiness_follows_size() {
+    assert_eq!(eval("مجموعة(١، ٢) == مجموعة(٢، ١)"), Value::Bool(true));
+    assert!(!eval("مجموعة()").is_truthy());
+    assert!(eval("مجموعة(٠)").is_truthy());
+}
+
+#[test]
+fn test_type_names() {
+    let value = eval("مجموعة()");
+    assert_eq!(value.type_name(), "set");
+    assert_eq!(value.type_name_ar(), "مجموعة");
+    assert_eq!(eval("نوع(مجموعة())"), Value::String("set".to_string()));
+}
+
+#[test]
+fn test_lists_and_maps_cannot_be_members() {
+    let err = Runtime::new().execute("مجموعة(١، [٢])").unwrap_err();
+    assert!(format!("{}", err).contains("cannot be a set member"));
+    assert!(Runtime::new().execute("أضف_لمجموعة(مجموعة()، قاموس())").is_err());
+    assert!(Runtime::new().execute("اتحاد([١]، مجموعة())").is_err());
+}
@@ -0,0 +1,66 @@
+use assert_cmd::Command;
+use iqra::lang::runtime::{ErrorLanguage, IqraError, Runtime};
+use predicates::str::contains;
+
+fn named_error(source: &str, name: &str) -> IqraError {
+    let err = Runtime::new().execute_named(source, name).unwrap_err();
+    err.downcast::<IqraError>().unwrap()
+}
+
+#[test]
+fn test_execute_named_sets_source_name() {
+    let err = named_error("س = ١\nاطبع(ص)", "main.iqra");
+    assert_eq!(err.source_name.as_deref(), Some("main.iqra"));
+    assert_eq!(err.line, Some(2));
+    let shown = format!("{}", err.localized(ErrorLanguage::Both));
+    assert!(shown.ends_with("\nالملف: main.iqra | File: main.iqra"), "{}", shown);
+}
+
+#[test]
+fn test_parse_errors_carry_source_name() {
+    let err = named_error("س = (", "broken.iqra");
+    assert_eq!(err.source_name.as_deref(), Some("broken.iqra"));
+}
+
+#[test]
+fn test_execute_leaves_source_name_unset() {
+    let err = Runtime::new().execute("اطبع(ص)").unwrap_err().downcast::<IqraError>().unwrap();
+    assert_eq!(err.source_name, None);
+    let shown = format!("{}", err.localized(ErrorLanguage::English));
+    assert!(!shown.contains("File:"), "{}", shown);
+}
+
+#[test]
+fn test_run_reports_the_fixture_path() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .env("IQRA_LANG", "en")
+        .args(["run", "tests/fixtures/undefined_name.iqra"])
+        .assert()
+        .code(1)
+        .stderr(contains("tests/fixtures/undefined_name.iqra:5"));
+}
+
+#[test]
+fn test_repl_errors_name_repl() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .env("HOME", std::env::temp_dir())
+        .env("IQRA_LANG", "en")
+        .arg("repl")
+        .write_stdin("اطبع(غير_معرف)\n")
+        .assert()
+        .success()
+        .stderr(contains("File: <repl>"));
+}
+
+#[test]
+fn test_code_errors_name_cmdline() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .env("IQRA_LANG", "en")
+        .args(["code", "--code", "اطبع(غير_معرف)"])
+        .assert()
+        .failure()
+        .stderr(contains("--> <cmdline>:1"));
+}
@@ -0,0 +1,66 @@
+use iqra::lang::lexer::{Lexer, SourceMap, Span};
+use iqra::lang::parser::{Expr, ExprKind, Parser, Stmt, StmtKind};
+
+fn parse(source: &str) -> Vec<Stmt> {
+    Parser::new(Lexer::new(source)).parse().unwrap()
+}
+
+fn span(start_line: usize, start_col: usize, end_line: usize, end_col: usize) -> Span {
+    Span { start_line, start_col, end_line, end_col }
+}
+
+fn expression(stmt: &Stmt) -> &Expr {
+    match &stmt.kind {
+        StmtKind::Expression(expr) => expr,
+        other => panic!("expected an expression statement, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_binary_expression_spans_its_operands() {
+    let source = "١ + س * ٢";
+    let statements = parse(source);
+    let expr = expression(&statements[0]);
+    assert_eq!(expr.span, span(1, 1, 1, 10));
+    let ExprKind::Binary { left, right, .. } = &expr.kind else { panic!("expected binary") };
+    assert_eq!(left.span, span(1, 1, 1, 2));
+    assert_eq!(right.span, span(1, 5, 1, 10));
+    let map = SourceMap::new(source);
+    assert_eq!(map.text(right.span), Some("س * ٢"));
+}
+
+#[test]
+fn test_call_index_and_parentheses() {
+    let source = "اطبع(قائمة[٠]، (١ + ٢))";
+    let statements = parse(source);
+    let map = SourceMap::new(source);
+    let expr = expression(&statements[0]);
+    assert_eq!(map.text(expr.span), Some(source));
+    let ExprKind::Call { args, .. } = &expr.kind else { panic!("expected call") };
+    let texts: Vec<_> = args.iter().map(|arg| map.text(arg.span)).collect();
+    assert_eq!(texts, vec![Some("قائمة[٠]"), Some("(١ + ٢)")]);
+}
+
+#[test]
+fn test_statement_and_expression_spans_on_later_lines() {
+    let source = "س = ١\nاذا س > ٠ {\n    اطبع(\"نعم\")\n}\n";
+    let statements = parse(source);
+    let map = SourceMap::new(source);
+    assert_eq!(statements[1].span, span(2, 1, 4, 2));
+    assert_eq!(map.text(statements[1].span), Some("اذا س > ٠ {\n    اطبع(\"نعم\")\n}"));
+    let StmtKind::If { condition, .. } = &statements[1].kind else { panic!("expected if") };
+    assert_eq!(map.text(condition.span), Some("س > ٠"));
+    let StmtKind::Assignment { value, .. } = &statements[0].kind else {
+        panic!("expected assignment")
+    };
+    assert_eq!(value.span, span(1, 5, 1, 6));
+}
+
+#[test]
+fn test_source_map_lines_and_out_of_range_spans() {
+    let map = SourceMap::new("أ\nبب\n");
+    assert_eq!(map.line(2), Some("بب"));
+    assert_eq!(map.line(0), None);
+    assert_eq!(map.text(span(2, 1, 2, 3)), Some("بب"));
+    assert_eq!(map.text(span(9, 1, 9, 2)), None);
+}
@@ -0,0 +1,77 @@
+use assert_cmd::Command;
+use iqra::cli::report::{STATE_REPORT_VERSION, state_report, value_json};
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+use serde_json::{Value as Json, json};
+use std::fs;
+use std::path::PathBuf;
+
+fn report_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("iqra_state_{}_{}.json", name, std::process::id()))
+}
+
+/// Runs `iqra run --emit-state` on `script`, returning the report it wrote.
+fn emit_state(name: &str, script: &str, code: i32) -> Json {
+    let path = report_path(name);
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["run", "--no-warnings", script, "--emit-state"])
+        .arg(&path)
+        .assert()
+        .code(code);
+    let report = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    fs::remove_file(&path).unwrap();
+    report
+}
+
+#[test]
+fn test_successful_run_is_reported() {
+    let report = emit_state("fixture", "tests/fixtures/state.iqra", 0);
+    assert_eq!(report["version"], json!(STATE_REPORT_VERSION));
+    assert_eq!(report["success"], json!(true));
+    assert_eq!(report["exit_code"], json!(0));
+    assert_eq!(report["functions"], json!(["رحب", "ضعف"]));
+    assert_eq!(report["last_value"], json!({"Int": 43}));
+    assert_eq!(report["stdout"], json!("مرحبا علي\n"));
+    assert_eq!(report["error"], Json::Null);
+    let variables = report["variables"].as_object().unwrap();
+    assert_eq!(variables.len(), 3);
+    assert_eq!(variables["س"], json!({"Int": 42}));
+    assert_eq!(variables["أسماء"], json!({"List": [{"String": "علي"}, {"String": "منى"}]}));
+    assert_eq!(variables["لانهاية"], json!({"type": "number", "repr": "inf"}));
+}
+
+#[test]
+fn test_failed_run_reports_the_error() {
+    let script = report_path("failing_script").with_extension("iqra");
+    fs::write(&script, "س = ١\nاطبع(\"قبل\")\nص = غير_معرف + ١\n").unwrap();
+    let report = emit_state("failing", script.to_str().unwrap(), 1);
+    assert_eq!(report["success"], json!(false));
+    assert_eq!(report["exit_code"], json!(1));
+    assert_eq!(report["variables"], json!({"س": {"Int": 1}}));
+    assert_eq!(report["last_value"], Json::Null);
+    assert_eq!(report["stdout"], json!("قبل\n"));
+    let error = &report["error"];
+    assert_eq!(error["line"], json!(3));
+    assert!(error["message_en"].as_str().unwrap().contains("غير_معرف"), "{}", error);
+
+    // A script that doesn't parse still gets a report, with nothing run.
+    fs::write(&script, "س = (١\n").unwrap();
+    let report = emit_state("unparsed", script.to_str().unwrap(), 2);
+    assert_eq!(report["exit_code"], json!(2));
+    assert_eq!(report["variables"], json!({}));
+    assert!(report["error"]["kind"].is_string());
+    fs::remove_file(&script).unwrap();
+}
+
+#[test]
+fn test_exit_code_is_not_an_error() {
+    let mut runtime = Runtime::new();
+    let outcome = runtime.execute("ع = ٥\nاخرج(٣)");
+    let report = state_report(&runtime, &outcome, "");
+    assert_eq!(report["exit_code"], json!(3));
+    assert_eq!(report["success"], json!(false));
+    assert_eq!(report["error"], Json::Null);
+    assert_eq!(report["variables"]["ع"], json!({"Int": 5}));
+    assert_eq!(value_json(&Value::Number(f64::NAN)), json!({"type": "number", "repr": "NaN"}));
+}
@@ -0,0 +1,63 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn number(code: &str) -> f64 {
+    match eval(code) {
+        Value::Number(n) => n,
+        other => panic!("expected a number, got {:?}", other),
+    }
+}
+
+fn error(code: &str) -> String {
+    format!("{}", Runtime::new().execute(code).unwrap_err())
+}
+
+#[test]
+fn test_median_of_odd_and_even_lengths() {
+    assert_eq!(number("وسيط([٧، ١، ٣])"), 3.0);
+    assert_eq!(number("وسيط([٤، ١، ٣، ٢])"), 2.5);
+    assert_eq!(number("median([1.5])"), 1.5);
+    assert!(error("وسيط([])").contains("non-empty list"));
+}
+
+#[test]
+fn test_mode_returns_a_list_on_ties() {
+    assert_eq!(eval("منوال([١، ٢، ٢، ٣])"), Value::Int(2));
+    assert_eq!(eval("منوال([٣، ١، ٣، ١، ٢])"), Value::List(vec![Value::Int(3), Value::Int(1)]));
+    assert_eq!(eval("mode([])"), Value::List(Vec::new()));
+}
+
+#[test]
+fn test_population_and_sample_variance() {
+    // Mean 5, squared deviations sum to 32.
+    let data = "[٢، ٤، ٤، ٤، ٥، ٥، ٧، ٩]";
+    assert_eq!(number(&format!("تباين({})", data)), 4.0);
+    assert_eq!(number(&format!("انحراف_معياري({})", data)), 2.0);
+    assert!((number(&format!("تباين({}، صحيح)", data)) - 32.0 / 7.0).abs() < 1e-12);
+    assert!((number(&format!("stddev({}, صحيح)", data)) - (32.0f64 / 7.0).sqrt()).abs() < 1e-12);
+    assert_eq!(number("variance([5])"), 0.0);
+    assert!(error("تباين([٥]، صحيح)").contains("needs at least 2 values"));
+    assert!(error("تباين([])").contains("non-empty list"));
+}
+
+#[test]
+fn test_percentile_interpolates_linearly() {
+    let data = "[١٥، ٢٠، ٣٥، ٤٠، ٥٠]";
+    assert_eq!(number(&format!("مئين({}، ٠)", data)), 15.0);
+    assert_eq!(number(&format!("مئين({}، ١٠٠)", data)), 50.0);
+    assert_eq!(number(&format!("مئين({}، ٥٠)", data)), 35.0);
+    // Rank 0.4 * 4 = 1.6: 20 + 0.6 * (35 - 20).
+    assert!((number(&format!("مئين({}، ٤٠)", data)) - 29.0).abs() < 1e-12);
+    assert!(error("percentile([1], 101)").contains("from 0 to 100"));
+}
+
+#[test]
+fn test_elements_must_be_numbers() {
+    assert!(error("وسيط([١، \"أ\"])").contains("expects a list of numbers"));
+    assert!(error("منوال(\"أ\")").contains("expects a list"));
+    assert!(error("تباين([١]، ١)").contains("is a boolean"));
+}
@@ -0,0 +1,108 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::stepper::{Stepper, VariableChange};
+use iqra::lang::value::Value;
+
+fn run_all(stepper: &mut Stepper, runtime: &mut Runtime) -> Vec<(usize, Value)> {
+    let mut steps = Vec::new();
+    while let Some(step) = stepper.next_step(runtime) {
+        let step = step.unwrap();
+        steps.push((step.span.start_line, step.result));
+    }
+    steps
+}
+
+#[test]
+fn test_step_descends_into_if_block() {
+    let mut runtime = Runtime::new();
+    let mut stepper = Stepper::parse(
+        "عدد = ٥\nاذا عدد > ٣ {\n    نتيجة = \"كبير\"\n} وإلا {\n    نتيجة = \"صغير\"\n}\nنتيجة\n",
+    )
+    .unwrap();
+
+    let steps = run_all(&mut stepper, &mut runtime);
+    let lines: Vec<usize> = steps.iter().map(|(line, _)| *line).collect();
+    assert_eq!(lines, vec![1, 2, 3, 7]);
+    assert_eq!(steps[1].1, Value::Bool(true));
+    assert_eq!(steps[3].1, Value::String("كبير".to_string()));
+    assert!(stepper.is_finished());
+}
+
+#[test]
+fn test_step_while_loop_checks_condition_each_iteration() {
+    let mut runtime = Runtime::new();
+    let mut stepper =
+        Stepper::parse("number = 1\nwhile number <= 2 {\n    number = number + 1\n}\n").unwrap();
+
+    let steps = run_all(&mut stepper, &mut runtime);
+    let lines: Vec<usize> = steps.iter().map(|(line, _)| *line).collect();
+    assert_eq!(lines, vec![1, 2, 3, 2, 3, 2]);
+    assert_eq!(steps.last().unwrap().1, Value::Bool(false));
+    assert_eq!(runtime.get_variables().get("number"), Some(&Value::Number(3.0)));
+}
+
+#[test]
+fn test_step_function_definition_and_call() {
+    let mut runtime = Runtime::new();
+    let mut stepper = Stepper::parse("دالة ضعف(س) {\n    ارجع س * ٢\n}\nناتج = ضعف(٤)\n").unwrap();
+
+    let first = stepper.next_step(&mut runtime).unwrap().unwrap();
+    assert_eq!((first.span.start_line, first.span.end_line), (1, 3));
+    let second = stepper.next_step(&mut runtime).unwrap().unwrap();
+    assert_eq!(second.result, Value::Number(8.0));
+    assert!(stepper.next_step(&mut runtime).is_none());
+}
+
+#[test]
+fn test_step_reports_variable_changes() {
+    let mut runtime = Runtime::new();
+    let mut stepper = Stepper::parse("س = ١\nس = ٢\nص = س\n").unwrap();
+
+    let first = stepper.next_step(&mut runtime).unwrap().unwrap();
+    assert_eq!(
+        first.changes,
+        vec![VariableChange { name: "س".to_string(), old: None, new: Some(Value::Number(1.0)) }]
+    );
+    let second = stepper.next_step(&mut runtime).unwrap().unwrap();
+    assert_eq!(second.changes[0].old, Some(Value::Number(1.0)));
+    assert_eq!(second.changes[0].new, Some(Value::Number(2.0)));
+    let third = stepper.next_step(&mut runtime).unwrap().unwrap();
+    assert_eq!(third.changes.len(), 1);
+    assert_eq!(third.changes[0].name, "ص");
+}
+
+#[test]
+fn test_step_error_skips_statement() {
+    let mut runtime = Runtime::new();
+    let mut stepper = Stepper::parse("س = غير_معرف\nص = ٢\n").unwrap();
+
+    assert!(stepper.next_step(&mut runtime).unwrap().is_err());
+    let step = stepper.next_step(&mut runtime).unwrap().unwrap();
+    assert_eq!(step.result, Value::Number(2.0));
+}
+
+#[test]
+fn test_step_restart_reuses_program() {
+    let mut runtime = Runtime::new();
+    let mut stepper = Stepper::parse("س = ١\nاذا س == ١ {\n    ص = ٢\n}\n").unwrap();
+    let first_run = run_all(&mut stepper, &mut runtime);
+
+    let mut runtime = Runtime::new();
+    stepper.restart();
+    let second_run = run_all(&mut stepper, &mut runtime);
+    assert_eq!(first_run.len(), second_run.len());
+    assert_eq!(runtime.get_variables().get("ص"), Some(&Value::Number(2.0)));
+}
+
+#[test]
+fn test_step_descends_into_matching_arm() {
+    let mut runtime = Runtime::new();
+    let mut stepper = Stepper::parse(
+        "س = ٢\nطابق س {\n    حالة ١ { ص = ١ }\n    حالة ٢ {\n        ص = ٢\n    }\n}\n",
+    )
+    .unwrap();
+
+    let lines: Vec<usize> =
+        run_all(&mut stepper, &mut runtime).iter().map(|(line, _)| *line).collect();
+    assert_eq!(lines, vec![1, 2, 5]);
+    assert_eq!(runtime.get_variables().get("ص"), Some(&Value::Number(2.0)));
+}
@@ -0,0 +1,88 @@
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn eval(code: &str) -> Value {
+    Runtime::new().execute(code).unwrap()
+}
+
+fn text(value: &str) -> Value {
+    Value::String(value.to_string())
+}
+
+const WORDS: &str =
+    "كلمات = [\"قلم\"، \"كتاب\"، \"قلم\"، \"باب\"، \"كتاب\"، \"قلم\"، \"شمس\"، \"باب\"]\n";
+
+#[test]
+fn test_count_by() {
+    let Value::Map(counts) = eval(&format!("{}عد_التكرار(كلمات)", WORDS)) else {
+        panic!("expected a map")
+    };
+    assert_eq!(counts.len(), 4);
+    assert_eq!(counts.get("قلم"), Some(&Value::Int(3)));
+    assert_eq!(counts.get("كتاب"), Some(&Value::Int(2)));
+    assert_eq!(counts.get("شمس"), Some(&Value::Int(1)));
+    // Non-string elements are keyed by how they display.
+    let Value::Map(numbers) = eval("count_by([1, 2, 1])") else { panic!("expected a map") };
+    assert_eq!(numbers.get("1"), Some(&Value::Int(2)));
+}
+
+#[test]
+fn test_tally_top_is_sorted_with_ties_in_first_appearance_order() {
+    let top = eval(&format!("{}افرز_قيم(كلمات، ٣)", WORDS));
+    let expected = Value::List(vec![
+        Value::List(vec![text("قلم"), Value::Int(3)]),
+        Value::List(vec![text("كتاب"), Value::Int(2)]),
+        Value::List(vec![text("باب"), Value::Int(2)]),
+    ]);
+    assert_eq!(top, expected);
+    assert_eq!(eval(&format!("{}طول(افرز_قيم(كلمات، ١٠))", WORDS)), Value::Int(4));
+    assert_eq!(eval("tally_top([1, 1], 0)"), Value::List(Vec::new()));
+}
+
+#[test]
+fn test_group_by_field() {
+    let code = "طلاب = [قاموس(\"اسم\"، \"علي\"، \"صف\"، \"أول\")، قاموس(\"اسم\"، \"سارة\"، \"صف\"، \"ثاني\")، \
+                قاموس(\"اسم\"، \"عمر\"، \"صف\"، \"أول\")]\nمجموعات = جمّع_حسب(طلاب، \"صف\")\n\
+                [طول(مجموعات[\"أول\"])، مجموعات[\"أول\"][١][\"اسم\"]، مجموعات[\"ثاني\"][٠][\"اسم\"]]";
+    assert_eq!(eval(code), Value::List(vec![Value::Int(2), text("عمر"), text("سارة")]));
+}
+
+/// What `code` prints.
+fn printed(code: &str) -> String {
+    let output = CapturedOutput::new();
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(output.clone()));
+    runtime.execute(code).unwrap();
+    output.take()
+}
+
+#[test]
+fn test_counts_and_groups_print_in_key_order() {
+    // A map's own order changes from run to run; what it prints must not.
+    for _ in 0..10 {
+        assert_eq!(
+            printed(&format!("{}اطبع(عد_التكرار(كلمات))", WORDS)),
+            "{باب: 2, شمس: 1, قلم: 3, كتاب: 2}\n"
+        );
+        assert_eq!(
+            printed(
+                "طلاب = [قاموس(\"اسم\"، \"علي\"، \"صف\"، \"ثاني\")، قاموس(\"اسم\"، \"سارة\"، \"صف\"، \"أول\")]\n\
+                 اطبع(جمّع_حسب(طلاب، \"صف\"))"
+            ),
+            "{أول: [{اسم: سارة, صف: أول}], ثاني: [{اسم: علي, صف: ثاني}]}\n"
+        );
+    }
+}
+
+#[test]
+fn test_errors() {
+    let error = |code: &str| format!("{}", Runtime::new().execute(code).unwrap_err());
+    assert!(error("عد_التكرار(\"قلم\")").contains("expects a list, got string"));
+    assert!(error("افرز_قيم([١]، -١)").contains("non-negative whole count"));
+    let message = error("جمّع_حسب([قاموس(\"صف\"، ١)، قاموس()]، \"صف\")");
+    assert!(message.contains("Element 1 is not a map with the field 'صف'"), "{}", message);
+    assert!(message.contains("العنصر 1"), "{}", message);
+    assert!(error("group_by([], 1)").contains("field name"));
+    assert!(Runtime::new().execute("count_by()").is_err());
+}
@@ -0,0 +1,84 @@
+use iqra::cli::render_error_with;
+use iqra::cli::terminal::{Prompts, choose_prompts, has_rtl, isolate, shapes_arabic};
+use iqra::lang::runtime::{ErrorLanguage, IqraError};
+use std::collections::HashMap;
+
+/// An environment lookup over `pairs`.
+fn env(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> =
+        pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+    move |name| vars.get(name).cloned()
+}
+
+const ARABIC_TERMINAL: &[(&str, &str)] = &[("LANG", "ar_SA.UTF-8"), ("TERM", "xterm-256color")];
+
+#[test]
+fn test_isolate_wraps_only_right_to_left_text() {
+    assert_eq!(isolate("س = ١"), "\u{2066}س = ١\u{2069}");
+    assert_eq!(isolate("x = 1"), "x = 1");
+    assert_eq!(isolate(""), "");
+    assert_eq!(isolate("print(\"שלום\")"), "\u{2066}print(\"שלום\")\u{2069}");
+    assert!(has_rtl("ﻻ"));
+    assert!(has_rtl("١٢"));
+    assert!(!has_rtl("12 + 3"));
+}
+
+#[test]
+fn test_the_locale_decides_shaping() {
+    assert!(shapes_arabic(env(ARABIC_TERMINAL)));
+    assert!(shapes_arabic(env(&[("LC_ALL", "en_US.utf8"), ("LANG", "C")])));
+    // LC_ALL wins over LANG.
+    assert!(!shapes_arabic(env(&[("LC_ALL", "C"), ("LANG", "ar_EG.UTF-8")])));
+    assert!(!shapes_arabic(env(&[("LANG", "POSIX")])));
+    assert!(!shapes_arabic(env(&[])));
+    assert!(!shapes_arabic(env(&[("LANG", "ar_SA.UTF-8"), ("TERM", "linux")])));
+    assert!(!shapes_arabic(env(&[("LANG", "ar_SA.UTF-8"), ("TERM", "dumb")])));
+}
+
+#[test]
+fn test_default_prompts_follow_the_terminal() {
+    assert_eq!(choose_prompts(None, env(ARABIC_TERMINAL)), Prompts::arabic());
+    let ascii = choose_prompts(None, env(&[("LANG", "C")]));
+    assert_eq!(ascii, Prompts::ascii());
+    assert_eq!(ascii.main, "iqra> ");
+    assert_eq!(ascii.step, "iqra (step)> ");
+    assert!([&ascii.main, &ascii.step, &ascii.debug, &ascii.program].iter().all(|p| p.is_ascii()));
+}
+
+#[test]
+fn test_flag_overrides_environment() {
+    let terminal = env(&[("LANG", "C"), ("IQRA_PROMPT", "$ ")]);
+    assert_eq!(choose_prompts(None, &terminal).main, "$ ");
+    assert_eq!(choose_prompts(Some(">> "), &terminal).main, ">> ");
+    assert_eq!(choose_prompts(Some("arabic"), &terminal), Prompts::arabic());
+    assert_eq!(choose_prompts(None, env(&[("IQRA_PROMPT", "ascii")])), Prompts::ascii());
+    // An empty variable counts as unset.
+    assert_eq!(choose_prompts(None, env(&[("IQRA_PROMPT", "")])), Prompts::ascii());
+}
+
+#[test]
+fn test_custom_prompts_name_the_mode() {
+    let prompts = choose_prompts(Some("> "), env(ARABIC_TERMINAL));
+    assert_eq!(prompts.step, "(خطوة) > ");
+    assert_eq!(prompts.debug, "(تصحيح) > ");
+    assert_eq!(prompts.program, Prompts::arabic().program);
+    let prompts = Prompts::custom("> ", true);
+    assert_eq!(prompts.step, "(step) > ");
+    assert_eq!(prompts.program, "program> ");
+}
+
+#[test]
+fn test_error_source_lines_are_isolated() {
+    let err = IqraError {
+        kind: "Undefined variable".to_string(),
+        message_ar: "غير معرف".to_string(),
+        message_en: "undefined".to_string(),
+        suggestion: None,
+        line: Some(1),
+        column: None,
+        source_name: None,
+    };
+    let rendered = |source| render_error_with(source, "x", &err, ErrorLanguage::English, false);
+    assert!(rendered("  اطبع(x)\n").contains("1 |   \u{2066}اطبع(x)\u{2069}\n  |   ^^^^^^^\n"));
+    assert!(rendered("print(x)\n").contains("1 | print(x)\n"));
+}
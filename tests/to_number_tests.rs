@@ -0,0 +1,59 @@
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::Value;
+
+fn to_number(input: &str, strict: bool) -> Result<Value, String> {
+    let mut runtime = Runtime::new();
+    runtime
+        .call_builtin("إلى_رقم", &[Value::String(input.to_string()), Value::Bool(strict)])
+        .map_err(|e| format!("{}", e))
+}
+
+#[test]
+fn test_lenient_inputs() {
+    let cases = [
+        ("١٢٣", Value::Int(123)),
+        (" ١٢٣ ", Value::Int(123)),
+        ("\u{a0}42\t", Value::Int(42)),
+        ("+7", Value::Int(7)),
+        ("-٥", Value::Int(-5)),
+        ("−٣", Value::Int(-3)),
+        ("٣٫٥", Value::Number(3.5)),
+        ("1,234,567", Value::Int(1234567)),
+        ("١٬٢٣٤٫٥", Value::Number(1234.5)),
+        ("50%", Value::Number(0.5)),
+        ("٪٥٠", Value::Number(0.5)),
+        ("٢٠٠٪", Value::Int(2)),
+        ("-12.5 %", Value::Number(-0.125)),
+        ("\u{200f}٩٩", Value::Int(99)),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(to_number(input, false), Ok(expected), "input {:?}", input);
+    }
+}
+
+#[test]
+fn test_strict_keeps_the_old_rules() {
+    assert_eq!(to_number("١٢٣", true), Ok(Value::Int(123)));
+    assert_eq!(to_number("-٤٫٥", true), Ok(Value::Number(-4.5)));
+    for input in [" ١٢٣ ", "1,234", "50%", "−٣"] {
+        assert!(to_number(input, true).is_err(), "input {:?}", input);
+    }
+}
+
+#[test]
+fn test_error_names_the_bad_character() {
+    let err = to_number("١٢س٣", false).unwrap_err();
+    assert!(err.contains("الحرف 'س' غير متوقع"), "{}", err);
+    assert!(err.contains("Cannot convert '١٢س٣' to number: unexpected character 'س'"), "{}", err);
+    for input in ["", "--1", "1.2.3", "%"] {
+        assert!(to_number(input, false).is_err(), "input {:?}", input);
+    }
+}
+
+#[test]
+fn test_script_call_forms() {
+    let mut runtime = Runtime::new();
+    assert_eq!(runtime.execute("إلى_رقم(\" ١٠ \")").unwrap(), Value::Int(10));
+    assert!(runtime.execute("إلى_رقم(\" ١٠ \"، صحيح)").is_err());
+    assert!(runtime.execute("to_number(\"1\", 2)").is_err());
+}
@@ -0,0 +1,84 @@
+use assert_cmd::Command;
+use iqra::cli::trace::{Tracer, format_changes, format_statement};
+use iqra::lang::lexer::Span;
+use iqra::lang::output::CapturedOutput;
+use iqra::lang::runtime::Runtime;
+use iqra::lang::stepper::VariableChange;
+use iqra::lang::value::Value;
+use predicates::str::contains;
+use std::fs;
+
+const FIXTURE: &str = "tests/fixtures/trace.iqra";
+
+fn trace(source: &str, limit: usize) -> String {
+    let output = CapturedOutput::new();
+    let mut runtime = Runtime::new();
+    runtime.set_output(Box::new(output.clone()));
+    let tracer = Tracer::new(source, limit, Box::new(output.clone()));
+    tracer.attach(&mut runtime);
+    runtime.execute(source).unwrap();
+    tracer.finish(&runtime);
+    output.contents()
+}
+
+#[test]
+fn test_format_statement_shows_line_and_first_source_line() {
+    let source = "س = ١\nبينما س < ٣ {\n    س = س + ١\n}\n";
+    let span = Span { start_line: 2, start_col: 1, end_line: 4, end_col: 2 };
+    assert_eq!(format_statement(source, span, 0), "[2] بينما س < ٣ {\n");
+    assert_eq!(format_statement(source, span, 2), "    [2] بينما س < ٣ {\n");
+}
+
+#[test]
+fn test_format_changes_lists_each_variable() {
+    let changes = vec![
+        VariableChange { name: "أ".to_string(), old: None, new: Some(Value::Int(1)) },
+        VariableChange {
+            name: "ب".to_string(),
+            old: Some(Value::Int(1)),
+            new: Some(Value::Int(2)),
+        },
+    ];
+    assert_eq!(format_changes(&changes, 0), "    + أ = 1\n    ب: 1 -> 2\n");
+}
+
+#[test]
+fn test_trace_of_fixture_program() {
+    let source = fs::read_to_string(FIXTURE).unwrap();
+    let expected = "\
+[1] س = ٠
+    + س = 0
+[2] دالة ضعف(ع) {
+[5] بينما س < ٢ {
+[6] س = س + ١
+    س: 0 -> 1
+[6] س = س + ١
+    س: 1 -> 2
+[8] ص = ضعف(س)
+  [3] ارجع ع * ٢
+    + ص = 4
+[9] اطبع(ص)
+4
+";
+    assert_eq!(trace(&source, 1000), expected);
+}
+
+#[test]
+fn test_trace_limit_stops_tracing_but_not_the_program() {
+    let source = fs::read_to_string(FIXTURE).unwrap();
+    let output = trace(&source, 2);
+    assert!(output.starts_with("[1] س = ٠\n    + س = 0\n[2] دالة ضعف(ع) {\n"));
+    assert!(output.contains("trace limit of 2 statements reached"));
+    assert!(!output.contains("[6]"));
+    assert!(output.ends_with("4\n"));
+}
+
+#[test]
+fn test_run_with_trace_flag() {
+    Command::cargo_bin("iqra")
+        .unwrap()
+        .args(["run", FIXTURE, "--trace"])
+        .assert()
+        .success()
+        .stdout(contains("[6] س = س + ١\n    س: 1 -> 2\n"));
+}
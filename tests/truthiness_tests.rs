@@ -0,0 +1,103 @@
+use iqra::lang::analyzer::{WarningKind, analyze, analyze_with_comments};
+use iqra::lang::lexer::Lexer;
+use iqra::lang::parser::Parser;
+use iqra::lang::runtime::Runtime;
+use iqra::lang::value::{Value, ValueSet};
+use std::collections::HashMap;
+
+fn to_bool(value: Value) -> Value {
+    Runtime::new().call_builtin("إلى_منطقي", &[value]).unwrap()
+}
+
+fn truthy_warnings(source: &str) -> Vec<(String, usize)> {
+    let mut parser = Parser::new(Lexer::new(source));
+    let program = parser.parse().unwrap();
+    analyze_with_comments(&program, parser.comments())
+        .into_iter()
+        .filter(|warning| warning.kind == WarningKind::TruthyCondition)
+        .map(|warning| (warning.name, warning.line))
+        .collect()
+}
+
+#[test]
+fn test_to_bool_of_every_value_variant() {
+    let map = HashMap::from([("أ".to_string(), Value::Int(1))]);
+    let set = ValueSet::from_values(&[Value::Int(1)]).unwrap();
+    let cases = [
+        (Value::Nil, false),
+        (Value::Bool(false), false),
+        (Value::Bool(true), true),
+        (Value::Int(0), false),
+        (Value::Int(-3), true),
+        (Value::Number(0.0), false),
+        (Value::Number(0.5), true),
+        (Value::String(String::new()), false),
+        (Value::String("٠".to_string()), true),
+        (Value::List(Vec::new()), false),
+        (Value::List(vec![Value::Int(0)]), true),
+        (Value::Map(HashMap::new()), false),
+        (Value::Map(map), true),
+        (Value::Set(ValueSet::new()), false),
+        (Value::Set(set), true),
+        (Value::Bytes(Vec::new()), false),
+        (Value::Bytes(vec![0]), true),
+    ];
+    for (value, expected) in cases {
+        assert_eq!(to_bool(value.clone()), Value::Bool(expected), "{:?}", value);
+    }
+}
+
+#[test]
+fn test_to_bool_and_is_bool_from_iqra() {
+    let mut runtime = Runtime::new();
+    assert_eq!(
+        runtime.execute("[إلى_منطقي(\"\")، to_bool([٠])]").unwrap(),
+        runtime.execute("[خطأ، صحيح]").unwrap()
+    );
+    assert_eq!(runtime.execute("منطقي؟(صحيح)").unwrap(), Value::Bool(true));
+    assert_eq!(runtime.execute("is_bool(١)").unwrap(), Value::Bool(false));
+    assert_eq!(runtime.execute("منطقي؟(إلى_منطقي(٥))").unwrap(), Value::Bool(true));
+    assert!(runtime.execute("إلى_منطقي()").is_err());
+}
+
+#[test]
+fn test_string_or_list_condition_is_warned() {
+    let source = "اسم = \"\"\nاذا اسم {\n    اطبع(اسم)\n}\nق = []\nبينما ليس ق {\n    ق = [١]\n}\n";
+    assert_eq!(truthy_warnings(source), [("اسم".to_string(), 2), ("ق".to_string(), 6)]);
+    let source = "اذا \"نعم\" و صحيح {\n    اطبع(١)\n}\n";
+    assert_eq!(truthy_warnings(source), [("\"نعم\"".to_string(), 1)]);
+    let warning =
+        &analyze(&Parser::new(Lexer::new("س = [١]\nاذا س {\n    اطبع(س)\n}\n")).parse().unwrap())
+            [0];
+    assert_eq!(warning.kind, WarningKind::TruthyCondition);
+    assert!(warning.suggestion.as_deref().unwrap().contains("to_bool(س)"));
+}
+
+#[test]
+fn test_explicit_conditions_are_not_warned() {
+    for source in [
+        "اسم = \"\"\nاذا اسم != \"\" {\n    اطبع(اسم)\n}\n",
+        "ق = []\nاذا طول(ق) > ٠ {\n    اطبع(ق)\n}\n",
+        "ق = []\nاذا إلى_منطقي(ق) {\n    اطبع(ق)\n}\n",
+        "س = ٠\nاذا س {\n    اطبع(س)\n}\n",
+        // The string was replaced by a number before the condition.
+        "س = \"أ\"\nس = ١\nاذا س {\n    اطبع(س)\n}\n",
+        // A parameter is not the global of the same name.
+        "س = \"أ\"\nدالة د(س) {\n    اذا س {\n        ارجع ١\n    }\n    ارجع ٠\n}\nاطبع(د(١))\n",
+    ] {
+        assert_eq!(truthy_warnings(source), [], "{}", source);
+    }
+}
+
+#[test]
+fn test_truthy_condition_warning_can_be_silenced() {
+    let source = "اسم = \"\"\n// تجاهل: truthy-condition\nاذا اسم {\n    اطبع(اسم)\n}\n";
+    assert_eq!(truthy_warnings(source), []);
+    let source = "ق = []\nبينما ق { // allow: unused-variable, truthy-condition\n    ق = []\n}\n";
+    assert_eq!(truthy_warnings(source), []);
+    // Other codes, or a comment further away, leave it.
+    let source = "اسم = \"\"\n// تجاهل: unused-variable\nاذا اسم {\n    اطبع(اسم)\n}\n";
+    assert_eq!(truthy_warnings(source), [("اسم".to_string(), 3)]);
+    let source = "اسم = \"\"\n// تجاهل: truthy-condition\n\nاذا اسم {\n    اطبع(اسم)\n}\n";
+    assert_eq!(truthy_warnings(source), [("اسم".to_string(), 4)]);
+}
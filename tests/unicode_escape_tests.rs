@@ -0,0 +1,55 @@
+use iqra::lang::runtime::{IqraError, Runtime};
+use iqra::lang::value::Value;
+
+fn eval(source: &str) -> Value {
+    Runtime::new().execute(source).unwrap()
+}
+
+fn error(source: &str) -> IqraError {
+    let err = Runtime::new().execute(source).unwrap_err();
+    err.downcast_ref::<IqraError>().cloned().unwrap_or_else(|| panic!("not an IqraError: {}", err))
+}
+
+#[test]
+fn test_unicode_escape_with_arabic_codepoints() {
+    assert_eq!(eval("\"\\u{0623}\\u{0642}\\u{0631}\\u{0623}\""), Value::String("أقرأ".to_string()));
+    assert_eq!(eval("\"\\u{1F600}\""), Value::String("😀".to_string()));
+}
+
+#[test]
+fn test_null_escape() {
+    assert_eq!(eval("طول(\"أ\\0ب\")"), Value::Int(3));
+    assert_eq!(eval("رمز_حرف(\"\\0\")"), Value::Int(0));
+}
+
+#[test]
+fn test_malformed_unicode_escapes_are_string_errors_with_line() {
+    for source in ["س = ١\nص = \"\\u0623\"", "س = ١\nص = \"\\u{0623\"", "س = ١\nص = \"\\u{xyz}\""]
+    {
+        let err = error(source);
+        assert_eq!(err.kind, "خطأ في السلسلة | String Error", "{}", source);
+        assert_eq!(err.line, Some(2), "{}", source);
+    }
+}
+
+#[test]
+fn test_surrogate_escape_is_rejected() {
+    let err = error("\"\\u{D800}\"");
+    assert!(err.message_en.contains("U+D800"));
+}
+
+#[test]
+fn test_char_code_and_char_from_code() {
+    assert_eq!(eval("رمز_حرف(\"أ\")"), Value::Int(0x0623));
+    assert_eq!(eval("char_code(\"abc\")"), Value::Int(97));
+    assert_eq!(eval("حرف_من_رمز(١٥٧١)"), Value::String("أ".to_string()));
+    assert_eq!(eval("char_from_code(char_code(\"ق\"))"), Value::String("ق".to_string()));
+}
+
+#[test]
+fn test_char_from_code_rejects_invalid_codepoints() {
+    assert!(error("حرف_من_رمز(55296)").message_en.contains("not a valid Unicode code point"));
+    assert!(Runtime::new().execute("char_from_code(1114112)").is_err());
+    assert!(Runtime::new().execute("char_from_code(-1)").is_err());
+    assert!(Runtime::new().execute("char_code(\"\")").is_err());
+}
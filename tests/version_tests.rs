@@ -0,0 +1,30 @@
+use clap_complete::Shell;
+use iqra::cli::args::{version_info, write_completions};
+
+fn completions(shell: Shell) -> String {
+    let mut out = Vec::new();
+    write_completions(shell, &mut out);
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_version_info_lists_build_details() {
+    let info = version_info();
+    assert!(info.starts_with(&format!("iqra {}\n", env!("CARGO_PKG_VERSION"))), "{}", info);
+    for marker in ["commit: ", "target: ", "features: "] {
+        assert!(info.contains(marker), "missing {:?} in {}", marker, info);
+    }
+}
+
+#[test]
+fn test_completions_cover_subcommands_and_flags() {
+    let bash = completions(Shell::Bash);
+    assert!(bash.contains("_iqra()"), "{}", bash);
+    for word in ["run", "check", "bench", "completions", "--watch", "--lang"] {
+        assert!(bash.contains(word), "bash completions miss {}", word);
+    }
+
+    assert!(completions(Shell::Zsh).contains("#compdef iqra"));
+    assert!(completions(Shell::Fish).contains("complete -c iqra"));
+    assert!(completions(Shell::PowerShell).contains("Register-ArgumentCompleter"));
+}
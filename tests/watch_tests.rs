@@ -0,0 +1,63 @@
+use iqra::cli::{WatchEvent, watch_events};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+const DEBOUNCE: Duration = Duration::from_millis(50);
+
+#[test]
+fn test_burst_of_changes_reruns_once() {
+    let (sender, events) = mpsc::channel();
+    for _ in 0..5 {
+        sender.send(WatchEvent::Changed).unwrap();
+    }
+    drop(sender);
+    // The channel closes before the debounce expires, so nothing runs.
+    assert_eq!(watch_events(&events, DEBOUNCE, || {}), 0);
+
+    let (sender, events) = mpsc::channel();
+    let feeder = thread::spawn(move || {
+        for _ in 0..5 {
+            sender.send(WatchEvent::Changed).unwrap();
+        }
+        thread::sleep(DEBOUNCE * 4);
+        sender.send(WatchEvent::Stop).unwrap();
+    });
+    let mut runs = 0;
+    assert_eq!(watch_events(&events, DEBOUNCE, || runs += 1), 1);
+    assert_eq!(runs, 1);
+    feeder.join().unwrap();
+}
+
+#[test]
+fn test_separate_changes_rerun_each_time() {
+    let (sender, events) = mpsc::channel();
+    let feeder = thread::spawn(move || {
+        for _ in 0..3 {
+            sender.send(WatchEvent::Changed).unwrap();
+            thread::sleep(DEBOUNCE * 4);
+        }
+        sender.send(WatchEvent::Stop).unwrap();
+    });
+    assert_eq!(watch_events(&events, DEBOUNCE, || {}), 3);
+    feeder.join().unwrap();
+}
+
+#[test]
+fn test_stop_during_burst_exits_without_running() {
+    let (sender, events) = mpsc::channel();
+    sender.send(WatchEvent::Changed).unwrap();
+    sender.send(WatchEvent::Stop).unwrap();
+    let mut ran = false;
+    assert_eq!(watch_events(&events, DEBOUNCE, || ran = true), 0);
+    assert!(!ran);
+    drop(sender);
+}
+
+#[test]
+fn test_stop_while_idle_exits() {
+    let (sender, events) = mpsc::channel();
+    sender.send(WatchEvent::Stop).unwrap();
+    assert_eq!(watch_events(&events, DEBOUNCE, || {}), 0);
+    drop(sender);
+}